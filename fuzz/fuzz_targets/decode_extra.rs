@@ -0,0 +1,15 @@
+//! Transaction `extra` parser on raw bytes.
+//!
+//! Parsing must never panic, and anything it accepts must re-encode to the
+//! same bytes.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nyx_core::extra::Extra;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(extra) = Extra::parse(data) {
+        assert_eq!(extra.to_bytes(), data);
+    }
+});
@@ -0,0 +1,14 @@
+//! Network message decoder on raw frame payloads.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nyx_core::DecodeLimits;
+use nyx_network::Message;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(message) = Message::from_bytes(data) {
+        assert!(message.check_limits(&DecodeLimits::default()).is_ok());
+        let _ = message.to_bytes().expect("decoded message re-encodes");
+    }
+});
@@ -0,0 +1,15 @@
+//! Canonical transaction decoder on raw bytes.
+//!
+//! Decoding must never panic, and anything it accepts must re-encode to the
+//! same bytes.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nyx_core::encoding::{decode_transaction, encode_transaction};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(tx) = decode_transaction(data) {
+        assert_eq!(encode_transaction(&tx), data);
+    }
+});
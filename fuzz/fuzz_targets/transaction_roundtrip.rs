@@ -0,0 +1,12 @@
+//! Structured round trip of generated transactions.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nyx_core::encoding::{decode_transaction, encode_transaction};
+use nyx_core::Transaction;
+
+fuzz_target!(|tx: Transaction| {
+    let decoded = decode_transaction(&encode_transaction(&tx)).expect("generated transaction decodes");
+    assert_eq!(decoded, tx);
+});
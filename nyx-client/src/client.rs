@@ -0,0 +1,683 @@
+// src/client.rs
+
+//! Async client for the node's HTTP RPC.
+//!
+//! Connections are pooled and reused across requests. Reads are retried
+//! with exponential backoff when the node is unreachable, times out or
+//! fails with a server error; requests that change state are sent once.
+
+use crate::errors::{ClientError, Result};
+use crate::types::*;
+use futures_util::stream::{self, Stream};
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::{header, Method, Request, StatusCode, Uri};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use nyx_core::{Hash, Timestamp, Transaction};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+/// Default time allowed for one attempt of a request
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default number of retries of a failed read
+pub const DEFAULT_RETRIES: u32 = 3;
+
+/// Delay before the first retry, doubled for each further one
+const RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Idle connections kept open per node
+const MAX_IDLE_CONNECTIONS: usize = 8;
+
+/// Typed client for a node's RPC endpoint
+///
+/// Cloning is cheap; clones share the connection pool.
+///
+/// # Example
+/// ```no_run
+/// # async fn run() -> nyx_client::Result<()> {
+/// use nyx_client::NyxClient;
+///
+/// let client = NyxClient::new("http://127.0.0.1:8080")?.with_retries(5);
+/// let status = client.status().await?;
+/// println!("{} peers, {} pending", status.peers, status.mempool_size);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct NyxClient {
+    /// Endpoint without a trailing slash, e.g. `http://127.0.0.1:8080` or
+    /// `http://gateway.example/nyx`
+    base: String,
+
+    /// Pooled HTTP client
+    http: Client<HttpConnector, Full<Bytes>>,
+
+    /// Time allowed for one attempt
+    timeout: Duration,
+
+    /// Retries of a failed read
+    retries: u32,
+
+    /// Bearer token for the admin endpoints
+    admin_token: Option<String>,
+}
+
+impl NyxClient {
+    /// Creates a client for an endpoint such as `http://127.0.0.1:8080`,
+    /// or `http://gateway.example/nyx` for a node served under a base path
+    ///
+    /// # Errors
+    /// - `InvalidInput` if the endpoint is not an `http://` URL without a
+    ///   query
+    pub fn new(endpoint: &str) -> Result<Self> {
+        let base = endpoint.trim_end_matches('/');
+        let uri: Uri = base.parse()
+            .map_err(|_| ClientError::InvalidInput(format!("Invalid endpoint: {}", endpoint)))?;
+        if uri.scheme_str() != Some("http") || uri.authority().is_none() || uri.query().is_some() {
+            return Err(ClientError::InvalidInput(format!("Unsupported endpoint: {}", endpoint)));
+        }
+
+        let http = Client::builder(TokioExecutor::new())
+            .pool_max_idle_per_host(MAX_IDLE_CONNECTIONS)
+            .build_http();
+
+        Ok(Self {
+            base: base.to_string(),
+            http,
+            timeout: DEFAULT_TIMEOUT,
+            retries: DEFAULT_RETRIES,
+            admin_token: None,
+        })
+    }
+
+    /// Sets the time allowed for one attempt of a request
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how many times a failed read is retried
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets the bearer token sent to the admin endpoints
+    pub fn with_admin_token(mut self, token: impl Into<String>) -> Self {
+        self.admin_token = Some(token.into());
+        self
+    }
+
+    // Node
+
+    /// Node version, peer count, mempool size and sync state
+    pub async fn status(&self) -> Result<NodeStatus> {
+        self.get("/status").await
+    }
+
+    /// Liveness report; `Err` with status 503 if storage is unhealthy
+    pub async fn health(&self) -> Result<HealthReport> {
+        self.get("/health").await
+    }
+
+    /// Readiness report, including failed checks when not ready
+    pub async fn ready(&self) -> Result<HealthReport> {
+        let (status, body) = self.send(Method::GET, "/ready", None, false).await?;
+        if status == StatusCode::SERVICE_UNAVAILABLE {
+            return Ok(serde_json::from_slice(&body)?);
+        }
+        decode(status, body)
+    }
+
+    /// Connected peers
+    pub async fn peers(&self) -> Result<Vec<PeerInfo>> {
+        self.get("/peers").await
+    }
+
+    /// Metrics in the Prometheus text format
+    pub async fn metrics(&self) -> Result<String> {
+        self.get_text("/metrics").await
+    }
+
+    // DAG
+
+    /// DAG statistics
+    pub async fn dag_stats(&self) -> Result<DagStats> {
+        self.get("/dag/stats").await
+    }
+
+    /// Renders the DAG around `center`, or around the tips if `None`
+    pub async fn dag_graph(&self, center: Option<&Hash>, depth: usize, format: GraphFormat) -> Result<String> {
+        let format = match format {
+            GraphFormat::Dot => "dot",
+            GraphFormat::GraphMl => "graphml",
+        };
+        let mut path = format!("/dag/graph?depth={}&format={}", depth, format);
+        if let Some(center) = center {
+            path.push_str(&format!("&center={}", hex::encode(center)));
+        }
+        self.get_text(&path).await
+    }
+
+    /// Expected wait for a transaction submitted now to be confirmed
+    pub async fn confirmation_estimate(&self) -> Result<ConfirmationEstimate> {
+        self.get("/estimate/confirmation").await
+    }
+
+    // Transactions
+
+    /// Submits a signed transaction
+    pub async fn submit(&self, tx: &Transaction) -> Result<SubmitResult> {
+        let request = SubmitRequest {
+            transaction: hex::encode(nyx_core::encoding::encode_transaction(tx)),
+        };
+        self.post("/submit", &request, false).await
+    }
+
+    /// Transactions created at or after `from`, oldest first, for wallet
+    /// scanning
+    pub async fn wallet_transactions(&self, from: Timestamp, limit: usize) -> Result<Vec<Transaction>> {
+        self.get(&format!("/wallet/transactions?from={}&limit={}", from, limit)).await
+    }
+
+    /// Streams alerts about double spends involving a transaction
+    ///
+    /// Conflicts the node already knows are sent first. The transaction
+    /// does not need to have arrived yet. The stream ends when the node
+    /// closes the connection.
+    pub async fn subscribe_double_spends(
+        &self,
+        tx_hash: &Hash,
+    ) -> Result<impl Stream<Item = Result<DoubleSpendAlert>> + Send + 'static> {
+        let path = format!("/alerts/double-spend/{}", hex::encode(tx_hash));
+        let response = tokio::time::timeout(self.timeout, self.http.request(self.request(Method::GET, &path, None)?))
+            .await
+            .map_err(|_| ClientError::Timeout)?
+            .map_err(|e| ClientError::Connection(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = read_body(response.into_body()).await?;
+            return Err(status_error(status, &body));
+        }
+
+        Ok(event_stream(response.into_body()))
+    }
+
+    // Mempool
+
+    /// Pending transactions, oldest first, up to `limit` listed
+    pub async fn mempool(&self, limit: usize) -> Result<MempoolInfo> {
+        self.get(&format!("/mempool?limit={}", limit)).await
+    }
+
+    /// A pending transaction, if the mempool holds it
+    pub async fn mempool_transaction(&self, tx_hash: &Hash) -> Result<Option<Transaction>> {
+        self.get_optional(&format!("/mempool/{}", hex::encode(tx_hash))).await
+    }
+
+    /// Drops a pending transaction from the mempool (admin)
+    ///
+    /// # Errors
+    /// - `Status` 404 if the transaction is not pending
+    pub async fn evict(&self, tx_hash: &Hash) -> Result<()> {
+        self.admin_action(Method::DELETE, &format!("/admin/mempool/{}", hex::encode(tx_hash)), None).await
+    }
+
+    // Node wallet
+
+    /// Balance of the node's wallet
+    pub async fn balance(&self) -> Result<u64> {
+        self.get::<BalanceResponse>("/balance").await.map(|r| r.balance)
+    }
+
+    /// Sends `amount` from the node's wallet to `to`
+    pub async fn send_to(&self, to: &str, amount: u64) -> Result<SendResult> {
+        self.post("/send", &SendRequest { to, amount }, false).await
+    }
+
+    /// Mints `count` outputs of `amount` to the node's wallet (regtest only)
+    pub async fn generate(&self, count: u32, amount: u64) -> Result<GenerateResult> {
+        self.post("/generate", &GenerateRequest { count, amount }, false).await
+    }
+
+    // Explorer
+
+    /// Indexed transaction by hash
+    pub async fn transaction(&self, tx_hash: &Hash) -> Result<Option<TransactionView>> {
+        self.get_optional(&format!("/explorer/tx/{}", hex::encode(tx_hash))).await
+    }
+
+    /// Transaction spending a key image
+    pub async fn transaction_by_key_image(&self, key_image: &[u8; 32]) -> Result<Option<TransactionView>> {
+        self.get_optional(&format!("/explorer/key_image/{}", hex::encode(key_image))).await
+    }
+
+    /// Whether a key image has been spent, and by which transaction
+    pub async fn key_image_status(&self, key_image: &[u8; 32]) -> Result<KeyImageStatus> {
+        self.get(&format!("/explorer/key_image/{}/spent", hex::encode(key_image))).await
+    }
+
+    /// Outputs whose stealth address starts with `prefix`
+    pub async fn outputs_by_address_prefix(&self, prefix: &[u8], limit: usize) -> Result<Vec<OutputView>> {
+        self.get(&format!("/explorer/outputs?prefix={}&limit={}", hex::encode(prefix), limit)).await
+    }
+
+    /// Transactions paying a stealth address
+    pub async fn transactions_by_address(&self, stealth_address: &[u8]) -> Result<Vec<TransactionView>> {
+        self.get(&format!("/explorer/address/{}", hex::encode(stealth_address))).await
+    }
+
+    /// Transactions created between `from` and `to`, inclusive
+    pub async fn transactions_by_time(&self, from: Timestamp, to: Timestamp, limit: usize) -> Result<Vec<TransactionView>> {
+        self.get(&format!("/explorer/transactions?from={}&to={}&limit={}", from, to, limit)).await
+    }
+
+    /// Most recently indexed transactions, newest first
+    pub async fn recent(&self, limit: usize) -> Result<Vec<TransactionView>> {
+        self.get(&format!("/explorer/recent?limit={}", limit)).await
+    }
+
+    /// Depth statistics of the indexed DAG
+    pub async fn explorer_stats(&self) -> Result<DagDepthView> {
+        self.get("/explorer/stats").await
+    }
+
+    // Light client
+
+    /// Header of the latest applied snapshot, if any
+    pub async fn latest_snapshot(&self) -> Result<Option<SnapshotHeader>> {
+        self.get_optional("/snapshots/latest").await
+    }
+
+    /// Header of snapshot `number`, if applied
+    pub async fn snapshot(&self, number: u64) -> Result<Option<SnapshotHeader>> {
+        self.get_optional(&format!("/snapshots/{}", number)).await
+    }
+
+    /// Consecutive snapshot headers starting at `from`
+    pub async fn light_headers(&self, from: u64, limit: usize) -> Result<Vec<SnapshotHeader>> {
+        self.get(&format!("/light/headers?from={}&limit={}", from, limit)).await
+    }
+
+    /// Proof that a transaction was finalized, if it was
+    pub async fn inclusion_proof(&self, tx_hash: &Hash) -> Result<Option<InclusionProof>> {
+        self.get_optional(&format!("/light/proof/{}", hex::encode(tx_hash))).await
+    }
+
+    // Admin
+
+    /// Runtime settings (admin)
+    pub async fn settings(&self) -> Result<RuntimeSettings> {
+        let (status, body) = self.send(Method::GET, "/admin/settings", None, true).await?;
+        decode(status, body)
+    }
+
+    /// Changes runtime settings without a restart (admin)
+    pub async fn update_settings(&self, update: &SettingsUpdate) -> Result<RuntimeSettings> {
+        self.post("/admin/settings", update, true).await
+    }
+
+    /// Every peer the node manages, with its traffic, and the bans (admin)
+    pub async fn peer_table(&self) -> Result<PeerTable> {
+        let (status, body) = self.send(Method::GET, "/admin/peers", None, true).await?;
+        decode(status, body)
+    }
+
+    /// Connects the node to a peer and remembers it (admin)
+    pub async fn add_peer(&self, address: SocketAddr) -> Result<()> {
+        let body = serde_json::to_vec(&PeerAddressRequest { address })?;
+        self.admin_action(Method::POST, "/admin/peers/add", Some(body.into())).await
+    }
+
+    /// Disconnects a peer (admin)
+    ///
+    /// # Errors
+    /// - `Status` 404 if the peer is not connected
+    pub async fn remove_peer(&self, address: SocketAddr) -> Result<()> {
+        let body = serde_json::to_vec(&PeerAddressRequest { address })?;
+        self.admin_action(Method::POST, "/admin/peers/remove", Some(body.into())).await
+    }
+
+    /// Bans an IP address for `duration`, disconnecting its peers (admin)
+    ///
+    /// # Returns
+    /// Number of peers disconnected
+    pub async fn ban_peer(&self, ip: IpAddr, duration: Duration) -> Result<usize> {
+        let request = BanRequest { ip, duration_secs: duration.as_secs() };
+        self.post::<_, BanResult>("/admin/peers/ban", &request, true).await.map(|r| r.disconnected)
+    }
+
+    /// Lifts the ban of an IP address (admin)
+    ///
+    /// # Errors
+    /// - `Status` 404 if the address is not banned
+    pub async fn unban_peer(&self, ip: IpAddr) -> Result<()> {
+        let body = serde_json::to_vec(&UnbanRequest { ip })?;
+        self.admin_action(Method::POST, "/admin/peers/unban", Some(body.into())).await
+    }
+
+    /// Re-verifies the node's stored transactions and DAG indexes,
+    /// rebuilding broken indexes with `repair` (admin)
+    pub async fn verify_db(&self, repair: bool) -> Result<DbVerification> {
+        self.post("/admin/verify-db", &VerifyDbRequest { repair }, true).await
+    }
+
+    /// Disk space used by each component of the node's data directory (admin)
+    pub async fn disk_usage(&self) -> Result<DiskUsage> {
+        let (status, body) = self.send(Method::GET, "/admin/du", None, true).await?;
+        decode(status, body)
+    }
+
+    // Transport
+
+    /// Sends an admin request answered without a body
+    async fn admin_action(&self, method: Method, path: &str, body: Option<Bytes>) -> Result<()> {
+        let (status, body) = self.send(method, path, body, true).await?;
+        if !status.is_success() {
+            return Err(status_error(status, &body));
+        }
+        Ok(())
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let (status, body) = self.send(Method::GET, path, None, false).await?;
+        decode(status, body)
+    }
+
+    async fn get_optional<T: DeserializeOwned>(&self, path: &str) -> Result<Option<T>> {
+        match self.get(path).await {
+            Ok(value) => Ok(Some(value)),
+            Err(ClientError::Status { status: 404, .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn get_text(&self, path: &str) -> Result<String> {
+        let (status, body) = self.send(Method::GET, path, None, false).await?;
+        if !status.is_success() {
+            return Err(status_error(status, &body));
+        }
+        String::from_utf8(body.to_vec()).map_err(|e| ClientError::Decode(e.to_string()))
+    }
+
+    async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B, admin: bool) -> Result<T> {
+        let body = Bytes::from(serde_json::to_vec(body)?);
+        let (status, body) = self.send(Method::POST, path, Some(body), admin).await?;
+        decode(status, body)
+    }
+
+    /// Sends a request, retrying reads that fail transiently
+    async fn send(&self, method: Method, path: &str, body: Option<Bytes>, admin: bool) -> Result<(StatusCode, Bytes)> {
+        let attempts = if method == Method::GET { self.retries + 1 } else { 1 };
+        let mut backoff = RETRY_BACKOFF;
+        let mut attempt = 1;
+        loop {
+            let mut request = self.request(method.clone(), path, body.clone())?;
+            if admin {
+                let token = self.admin_token.as_deref()
+                    .ok_or_else(|| ClientError::InvalidInput("No admin token set".to_string()))?;
+                let value = format!("Bearer {}", token).parse()
+                    .map_err(|_| ClientError::InvalidInput("Invalid admin token".to_string()))?;
+                request.headers_mut().insert(header::AUTHORIZATION, value);
+            }
+
+            let result = self.attempt(request).await;
+            let retry = match &result {
+                Ok((status, _)) => status.is_server_error() && *status != StatusCode::SERVICE_UNAVAILABLE
+                    || *status == StatusCode::SERVICE_UNAVAILABLE && path != "/ready",
+                Err(e) => e.is_transient(),
+            };
+            if !retry || attempt >= attempts {
+                return result;
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+
+    async fn attempt(&self, request: Request<Full<Bytes>>) -> Result<(StatusCode, Bytes)> {
+        tokio::time::timeout(self.timeout, async {
+            let response = self.http.request(request).await
+                .map_err(|e| ClientError::Connection(e.to_string()))?;
+            let status = response.status();
+            Ok((status, read_body(response.into_body()).await?))
+        })
+        .await
+        .map_err(|_| ClientError::Timeout)?
+    }
+
+    fn request(&self, method: Method, path: &str, body: Option<Bytes>) -> Result<Request<Full<Bytes>>> {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(format!("{}{}", self.base, path));
+        if body.is_some() {
+            builder = builder.header(header::CONTENT_TYPE, "application/json");
+        }
+        builder.body(Full::new(body.unwrap_or_default()))
+            .map_err(|e| ClientError::InvalidInput(e.to_string()))
+    }
+}
+
+impl std::fmt::Debug for NyxClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NyxClient")
+            .field("base", &self.base)
+            .field("timeout", &self.timeout)
+            .field("retries", &self.retries)
+            .field("admin_token", &self.admin_token.as_ref().map(|_| "[REDACTED]"))
+            .finish()
+    }
+}
+
+async fn read_body(body: Incoming) -> Result<Bytes> {
+    body.collect().await
+        .map(|collected| collected.to_bytes())
+        .map_err(|e| ClientError::Connection(e.to_string()))
+}
+
+fn decode<T: DeserializeOwned>(status: StatusCode, body: Bytes) -> Result<T> {
+    if !status.is_success() {
+        return Err(status_error(status, &body));
+    }
+    Ok(serde_json::from_slice(&body)?)
+}
+
+fn status_error(status: StatusCode, body: &[u8]) -> ClientError {
+    ClientError::Status {
+        status: status.as_u16(),
+        message: String::from_utf8_lossy(body).into_owned(),
+    }
+}
+
+/// Decodes the `data` of each server-sent event in a response body
+fn event_stream<T: DeserializeOwned>(body: Incoming) -> impl Stream<Item = Result<T>> + Send + 'static {
+    stream::unfold(Some((body, String::new())), |state| async move {
+        let (mut body, mut buffer) = state?;
+        loop {
+            if let Some(end) = buffer.find("\n\n") {
+                let block: String = buffer.drain(..end + 2).collect();
+                if let Some(data) = event_data(&block) {
+                    let item = serde_json::from_str(&data).map_err(ClientError::from);
+                    return Some((item, Some((body, buffer))));
+                }
+                continue;
+            }
+
+            match body.frame().await {
+                Some(Ok(frame)) => {
+                    if let Ok(data) = frame.into_data() {
+                        buffer.push_str(&String::from_utf8_lossy(&data).replace("\r\n", "\n"));
+                    }
+                }
+                Some(Err(e)) => return Some((Err(ClientError::Connection(e.to_string())), None)),
+                None => return None,
+            }
+        }
+    })
+}
+
+/// Joined `data` lines of an event block, if it has any
+fn event_data(block: &str) -> Option<String> {
+    let lines: Vec<&str> = block.lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| data.strip_prefix(' ').unwrap_or(data))
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+    use nyx_node::node::NyxNode;
+    use nyx_node::rpc::RpcServer;
+    use nyx_node::NodeConfig;
+    use std::sync::Arc;
+    use tokio::sync::RwLock;
+
+    /// Starts a regtest node serving RPC on a free port
+    async fn start_node(dir: &tempfile::TempDir, admin_token: Option<&str>) -> String {
+        let mut config = NodeConfig::regtest();
+        config.network.listen_addr = "127.0.0.1:0".parse().unwrap();
+        config.data_dir = dir.path().to_path_buf();
+        let node = Arc::new(RwLock::new(NyxNode::new(config).await.unwrap()));
+
+        let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let server = RpcServer::new(addr, node).with_admin_token(admin_token.map(str::to_string));
+        tokio::spawn(server.start());
+
+        let endpoint = format!("http://{}", addr);
+        for _ in 0..100 {
+            if tokio::net::TcpStream::connect(addr).await.is_ok() {
+                return endpoint;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("RPC server did not start");
+    }
+
+    #[test]
+    fn test_endpoint_validation() {
+        assert!(NyxClient::new("http://127.0.0.1:8080/").is_ok());
+        assert!(NyxClient::new("https://node.example").is_err());
+        assert_eq!(NyxClient::new("http://node.example/rpc/").unwrap().base, "http://node.example/rpc");
+        assert!(NyxClient::new("http://node.example/rpc?x=1").is_err());
+        assert!(NyxClient::new("127.0.0.1:8080").is_err());
+    }
+
+    #[test]
+    fn test_event_data() {
+        assert_eq!(event_data("event: double_spend\ndata: {\"a\":1}\n\n"), Some("{\"a\":1}".to_string()));
+        assert_eq!(event_data(":\n\n"), None);
+    }
+
+    #[tokio::test]
+    async fn test_typed_calls_against_node() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = NyxClient::new(&start_node(&dir, Some("secret")).await).unwrap()
+            .with_admin_token("secret");
+
+        let status = client.status().await.unwrap();
+        assert_eq!(status.version, nyx_node::NODE_VERSION);
+        assert!(client.health().await.unwrap().storage_ok);
+        client.ready().await.unwrap();
+        client.dag_stats().await.unwrap();
+        assert!(client.metrics().await.unwrap().contains("nyx_"));
+
+        let before = client.balance().await.unwrap();
+        let generated = client.generate(1, 500).await.unwrap();
+        assert_eq!(generated.balance, before + 500);
+        assert_eq!(client.balance().await.unwrap(), before + 500);
+        let tx_hash: Hash = hex::decode(&generated.tx_hash).unwrap().try_into().unwrap();
+
+        let graph = client.dag_graph(Some(&tx_hash), 2, GraphFormat::Dot).await.unwrap();
+        assert!(graph.contains(&generated.tx_hash));
+        assert_eq!(client.transaction(&[0xEEu8; 32]).await.unwrap(), None);
+        assert!(matches!(
+            client.dag_graph(Some(&[0xEEu8; 32]), 2, GraphFormat::Dot).await,
+            Err(ClientError::Status { status: 404, .. })
+        ));
+
+        let pending = client.mempool(10).await.unwrap();
+        assert_eq!(pending.count, pending.transactions.len());
+        assert_eq!(client.mempool_transaction(&[0xEEu8; 32]).await.unwrap(), None);
+        assert!(matches!(client.evict(&[0xEEu8; 32]).await, Err(ClientError::Status { status: 404, .. })));
+
+        let banned: IpAddr = "10.9.9.9".parse().unwrap();
+        assert_eq!(client.ban_peer(banned, Duration::from_secs(60)).await.unwrap(), 0);
+        assert_eq!(client.peer_table().await.unwrap().banned[0].ip, "10.9.9.9");
+        assert!(matches!(
+            client.add_peer(SocketAddr::new(banned, 8000)).await,
+            Err(ClientError::Status { status: 403, .. })
+        ));
+        client.unban_peer(banned).await.unwrap();
+        assert!(client.verify_db(false).await.unwrap().issues.is_empty());
+        assert!(client.disk_usage().await.unwrap().keystore > 0);
+        assert!(matches!(
+            client.remove_peer(SocketAddr::new(banned, 8000)).await,
+            Err(ClientError::Status { status: 404, .. })
+        ));
+
+        let update = SettingsUpdate { max_peers: Some(9), ..Default::default() };
+        assert_eq!(client.update_settings(&update).await.unwrap().max_peers, 9);
+        assert_eq!(client.settings().await.unwrap().max_peers, 9);
+        assert!(matches!(
+            client.clone().with_admin_token("wrong").settings().await,
+            Err(ClientError::Status { status: 401, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_explorer_calls_against_node() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = NyxClient::new(&start_node(&dir, None).await).unwrap();
+        let generated = client.generate(1, 100).await.unwrap();
+        let tx_hash: Hash = hex::decode(&generated.tx_hash).unwrap().try_into().unwrap();
+
+        // The indexer catches up with the DAG in the background
+        let mut view = None;
+        for _ in 0..100 {
+            view = client.transaction(&tx_hash).await.unwrap();
+            if view.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let view = view.expect("transaction indexed");
+        assert_eq!(view.hash, generated.tx_hash);
+
+        assert!(client.recent(5).await.unwrap().iter().any(|v| v.hash == generated.tx_hash));
+        assert!(client.explorer_stats().await.unwrap().transactions >= 1);
+        let scanned = client.wallet_transactions(0, 10).await.unwrap();
+        assert!(scanned.iter().any(|tx| tx.id() == tx_hash));
+        assert!(client.key_image_status(&[0xEEu8; 32]).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_double_spend_subscription_opens() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = NyxClient::new(&start_node(&dir, None).await).unwrap();
+
+        let mut alerts = Box::pin(client.subscribe_double_spends(&[7u8; 32]).await.unwrap());
+        let next = tokio::time::timeout(Duration::from_millis(200), alerts.next()).await;
+        assert!(next.is_err(), "no alert without a conflict");
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_node_is_retried_then_reported() {
+        let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let client = NyxClient::new(&format!("http://{}", addr)).unwrap().with_retries(2);
+
+        let started = std::time::Instant::now();
+        assert!(matches!(client.status().await, Err(ClientError::Connection(_))));
+        assert!(started.elapsed() >= RETRY_BACKOFF * 3);
+    }
+}
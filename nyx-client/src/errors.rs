@@ -0,0 +1,76 @@
+// src/errors.rs
+
+//! Error types for RPC client operations.
+
+use std::fmt;
+
+/// Main error type for RPC client operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientError {
+    /// Endpoint or argument is malformed
+    InvalidInput(String),
+
+    /// Node could not be reached
+    Connection(String),
+
+    /// Node did not answer in time
+    Timeout,
+
+    /// Node answered with an error status
+    Status {
+        /// HTTP status code
+        status: u16,
+        /// Error message from the node
+        message: String,
+    },
+
+    /// Response could not be decoded
+    Decode(String),
+}
+
+impl ClientError {
+    /// Whether repeating the request may succeed
+    pub fn is_transient(&self) -> bool {
+        match self {
+            ClientError::Connection(_) | ClientError::Timeout => true,
+            ClientError::Status { status, .. } => *status >= 500,
+            ClientError::InvalidInput(_) | ClientError::Decode(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
+            ClientError::Connection(msg) => write!(f, "Connection error: {}", msg),
+            ClientError::Timeout => write!(f, "Request timed out"),
+            ClientError::Status { status, message } => write!(f, "Node returned {}: {}", status, message),
+            ClientError::Decode(msg) => write!(f, "Decode error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(err: serde_json::Error) -> Self {
+        ClientError::Decode(format!("JSON error: {}", err))
+    }
+}
+
+/// Result type alias for RPC client operations
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transient_errors() {
+        assert!(ClientError::Timeout.is_transient());
+        assert!(ClientError::Status { status: 503, message: String::new() }.is_transient());
+        assert!(!ClientError::Status { status: 404, message: String::new() }.is_transient());
+        assert!(!ClientError::Decode("bad".to_string()).is_transient());
+    }
+}
@@ -0,0 +1,45 @@
+// src/lib.rs
+
+//! # Nyx RPC Client
+//!
+//! Async typed client for a node's HTTP RPC, so Rust integrators do not
+//! hand-roll requests against the node's JSON API.
+//!
+//! [`NyxClient`] covers node status, transaction submission, the mempool,
+//! double-spend alert subscriptions, the node wallet, the explorer,
+//! light-client headers and the admin endpoints, peer management
+//! included. Connections are pooled, and reads are retried with
+//! exponential backoff when the node is briefly unavailable.
+//!
+//! ## Example Usage
+//!
+//! ```no_run
+//! use nyx_client::NyxClient;
+//! use std::time::Duration;
+//!
+//! # async fn run() -> nyx_client::Result<()> {
+//! let client = NyxClient::new("http://127.0.0.1:8080")?
+//!     .with_timeout(Duration::from_secs(5))
+//!     .with_retries(3);
+//!
+//! let stats = client.dag_stats().await?;
+//! for tx in client.recent(10).await? {
+//!     println!("{} finalized={}", tx.hash, tx.finalized);
+//! }
+//! # let _ = stats;
+//! # Ok(())
+//! # }
+//! ```
+
+#![warn(missing_docs)]
+#![warn(clippy::all)]
+#![deny(unsafe_code)]
+
+pub mod errors;
+pub mod types;
+pub mod client;
+
+// Re-export commonly used types
+pub use crate::errors::{ClientError, Result};
+pub use crate::client::NyxClient;
+pub use crate::types::*;
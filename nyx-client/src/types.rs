@@ -0,0 +1,361 @@
+// src/types.rs
+
+//! Request and response types of the node RPC.
+//!
+//! Types the node shares with other crates (DAG statistics, snapshot
+//! headers, explorer views) are re-exported from there; the ones private
+//! to the node's RPC server are defined here with the same JSON shape.
+
+use serde::{Deserialize, Serialize};
+
+pub use nyx_core::dag::DagStats;
+pub use nyx_core::finality::{InclusionProof, NodeMode, SnapshotHeader};
+pub use nyx_core::graph::GraphFormat;
+pub use nyx_core::metrics::ConfirmationEstimate;
+pub use nyx_indexer::{DagDepthView, KeyImageStatus, OutputView, TransactionView};
+
+/// Node status, served by `/status`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeStatus {
+    /// Node software version
+    pub version: String,
+
+    /// Number of connected peers
+    pub peers: usize,
+
+    /// Number of pending transactions in the mempool
+    pub mempool_size: usize,
+
+    /// Whether the node is syncing
+    pub syncing: bool,
+
+    /// Whether the node serves full history
+    #[serde(default)]
+    pub mode: NodeMode,
+
+    /// Last snapshot whose transactions the node pruned (0 if none)
+    #[serde(default)]
+    pub pruned_through: u64,
+}
+
+/// Result of a health check, served by `/health` and `/ready`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// Whether storage answered a query
+    pub storage_ok: bool,
+
+    /// Number of connected peers
+    pub peers: usize,
+
+    /// Whether a sync session is running
+    pub syncing: bool,
+
+    /// Distance between the sync target and the current height
+    pub sync_lag: u64,
+
+    /// Seconds since the latest snapshot was applied, if any was
+    pub last_snapshot_age_secs: Option<u64>,
+
+    /// Readiness checks that failed
+    pub failures: Vec<String>,
+}
+
+impl HealthReport {
+    /// Whether every readiness check passed
+    pub fn is_ready(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// A connected peer, served by `/peers`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerInfo {
+    /// Peer ID (hex)
+    pub peer_id: String,
+
+    /// Socket address
+    pub address: String,
+
+    /// Latest measured round trip, if any
+    pub latency_ms: Option<u64>,
+
+    /// Heartbeats missed in a row
+    pub missed_heartbeats: u32,
+
+    /// Whether the peer serves full history
+    #[serde(default)]
+    pub mode: NodeMode,
+}
+
+/// Pending transactions, served by `/mempool`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MempoolInfo {
+    /// Number of pending transactions, including unlisted ones
+    pub count: usize,
+
+    /// Maximum number of pending transactions
+    pub max_size: usize,
+
+    /// Listed transactions, oldest first
+    pub transactions: Vec<MempoolEntry>,
+}
+
+/// A pending transaction in the mempool listing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MempoolEntry {
+    /// Transaction hash (hex)
+    pub tx_hash: String,
+
+    /// Encoded size in bytes
+    pub size: usize,
+
+    /// Seconds since the node admitted it
+    pub age_secs: u64,
+}
+
+/// Peer table, served by `/admin/peers`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerTable {
+    /// Every peer the node manages, connecting ones included
+    pub peers: Vec<PeerEntry>,
+
+    /// Banned IP addresses
+    pub banned: Vec<PeerBan>,
+}
+
+/// A managed peer with its connection state and traffic
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerEntry {
+    /// Peer ID (hex)
+    pub peer_id: String,
+
+    /// Socket address
+    pub address: String,
+
+    /// `Connecting`, `Connected`, `Disconnected` or `Banned`
+    pub state: String,
+
+    /// Latest measured round trip, if any
+    pub latency_ms: Option<u64>,
+
+    /// Heartbeats missed in a row
+    pub missed_heartbeats: u32,
+
+    /// Bytes sent to the peer
+    pub bytes_sent: u64,
+
+    /// Bytes received from the peer
+    pub bytes_received: u64,
+
+    /// Transactions from the peer refused by the node
+    #[serde(default)]
+    pub transactions_rejected: u64,
+
+    /// Transactions from the peer refused for exceeding its quota
+    #[serde(default)]
+    pub quota_exceeded: u64,
+
+    /// Whether the peer serves full history
+    #[serde(default)]
+    pub mode: NodeMode,
+}
+
+/// A banned IP address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerBan {
+    /// Banned IP address
+    pub ip: String,
+
+    /// Seconds until the ban expires
+    pub remaining_secs: u64,
+}
+
+/// Outcome of `/send` from the node wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SendResult {
+    /// Whether the transaction was sent
+    pub success: bool,
+
+    /// Hash of the sent transaction (hex)
+    pub tx_hash: Option<String>,
+
+    /// Why sending failed
+    pub error: Option<String>,
+}
+
+/// Outcome of `/submit`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubmitResult {
+    /// Whether the node accepted the transaction
+    pub success: bool,
+
+    /// Why it was rejected
+    pub error: Option<String>,
+}
+
+/// Outcome of `/generate` (regtest only)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenerateResult {
+    /// Hash of the minting transaction (hex)
+    pub tx_hash: String,
+
+    /// Node wallet balance afterwards
+    pub balance: u64,
+}
+
+/// Two or more transactions spending the same key image
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoubleSpendAlert {
+    /// The doubly spent key image (hex)
+    pub key_image: String,
+
+    /// Every known transaction spending it, leading branch first
+    pub branches: Vec<ConflictBranch>,
+}
+
+/// Standing of one side of a double spend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConflictBranch {
+    /// Transaction hash (hex)
+    pub tx_hash: String,
+
+    /// Whether the DAG holds the transaction
+    pub in_dag: bool,
+
+    /// Confirmation score (0 outside the DAG)
+    pub score: f64,
+
+    /// Number of transactions approving it, itself included
+    pub cumulative_weight: u64,
+}
+
+/// Runtime settings, served by `/admin/settings`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeSettings {
+    /// Log filter directives
+    pub log_filter: String,
+
+    /// Number of peers each gossiped message is sent to (`None` for all)
+    pub gossip_fanout: Option<usize>,
+
+    /// Maximum number of connected peers
+    pub max_peers: usize,
+
+    /// Maximum number of pending transactions in the mempool
+    pub mempool_max_size: usize,
+}
+
+/// Partial change to the runtime settings; absent fields are kept
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SettingsUpdate {
+    /// New log filter directives
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_filter: Option<String>,
+
+    /// New gossip fanout; `Some(None)` sends to all peers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gossip_fanout: Option<Option<usize>>,
+
+    /// New maximum number of connected peers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_peers: Option<usize>,
+
+    /// New maximum mempool size
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mempool_max_size: Option<usize>,
+}
+
+/// Outcome of `/admin/verify-db`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbVerification {
+    /// Number of stored transactions checked
+    pub checked: usize,
+
+    /// Every problem found, repaired ones included
+    pub issues: Vec<String>,
+
+    /// Number of issues repaired
+    pub repaired: usize,
+}
+
+/// Disk space used by each component of the data directory, in bytes,
+/// from `/admin/du`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskUsage {
+    /// Databases
+    pub db: u64,
+
+    /// Key files
+    pub keystore: u64,
+
+    /// Address book
+    pub peers: u64,
+
+    /// Snapshot downloads
+    pub snapshots: u64,
+
+    /// Anything else in the data directory
+    pub other: u64,
+
+    /// All components
+    pub total: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SendRequest<'a> {
+    pub to: &'a str,
+    pub amount: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SubmitRequest {
+    pub transaction: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct GenerateRequest {
+    pub count: u32,
+    pub amount: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PeerAddressRequest {
+    pub address: std::net::SocketAddr,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct BanRequest {
+    pub ip: std::net::IpAddr,
+    pub duration_secs: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct UnbanRequest {
+    pub ip: std::net::IpAddr,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct VerifyDbRequest {
+    pub repair: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct BanResult {
+    pub disconnected: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct BalanceResponse {
+    pub balance: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_update_omits_absent_fields() {
+        let update = SettingsUpdate { max_peers: Some(8), gossip_fanout: Some(None), ..Default::default() };
+        assert_eq!(serde_json::to_string(&update).unwrap(), r#"{"gossip_fanout":null,"max_peers":8}"#);
+    }
+}
@@ -1,9 +1,54 @@
 // benches/dag_benchmark.rs
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use nyx_core::dag::DagProcessor;
+use nyx_core::storage::MemoryStorage;
+use nyx_core::testing::{funding_tx, test_tx};
+use nyx_core::transaction::Transaction;
+use nyx_core::verification::VerifyMode;
+use nyx_core::Hash;
 
-fn dummy_bench(_c: &mut Criterion) {
-    // We'll implement real DAG benchmarks later
+/// Unsigned transaction `n` approving `parent1` and `parent2`, spending an
+/// output and key image no other `n` does
+fn chain_tx(parent1: Hash, parent2: Hash, n: u64) -> Transaction {
+    let mut unique = [0u8; 32];
+    unique[..8].copy_from_slice(&n.to_le_bytes());
+
+    let mut tx = test_tx(parent1, parent2, 0);
+    tx.inputs[0].prev_tx = unique;
+    tx.inputs[0].key_image = unique;
+    tx
+}
+
+/// Inserts on top of an unfinalized chain of the given depth, where every
+/// transaction approves the two before it
+fn bench_insert_on_deep_dag(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dag_insert");
+
+    for depth in [100u64, 1_000, 5_000] {
+        group.bench_with_input(BenchmarkId::from_parameter(depth), &depth, |b, &depth| {
+            let storage = MemoryStorage::new();
+            let dag = DagProcessor::new(storage.clone());
+            let mut parents = [
+                storage.store_transaction(funding_tx()).unwrap(),
+                storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 1)).unwrap(),
+            ];
+            let mut n = 0;
+            let mut extend = || {
+                n += 1;
+                let tx = chain_tx(parents[0], parents[1], n);
+                let hash = dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly).unwrap();
+                parents = [hash, parents[0]];
+            };
+
+            for _ in 0..depth {
+                extend();
+            }
+            b.iter(&mut extend);
+        });
+    }
+
+    group.finish();
 }
 
-criterion_group!(benches, dummy_bench);
+criterion_group!(benches, bench_insert_on_deep_dag);
 criterion_main!(benches);
@@ -0,0 +1,252 @@
+// src/amount.rs
+
+//! Typed amounts and denomination handling.
+//!
+//! All balances are tracked in atomic units (the smallest indivisible unit).
+//! One NYX is `10^DECIMALS` atomic units. [`Amount`] wraps the raw `u64` so
+//! that arithmetic is checked and display/parsing go through one place.
+
+use crate::errors::{NyxError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Number of decimal places in one NYX
+pub const DECIMALS: u32 = 12;
+
+/// Atomic units per whole NYX
+pub const ATOMIC_UNITS_PER_NYX: u64 = 10u64.pow(DECIMALS);
+
+/// Ticker symbol used in display and parsing
+pub const TICKER: &str = "NYX";
+
+/// Amount of NYX in atomic units
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Amount(u64);
+
+impl Amount {
+    /// Zero amount
+    pub const ZERO: Amount = Amount(0);
+
+    /// Largest representable amount
+    pub const MAX: Amount = Amount(u64::MAX);
+
+    /// One whole NYX
+    pub const ONE_NYX: Amount = Amount(ATOMIC_UNITS_PER_NYX);
+
+    /// Creates an amount from atomic units
+    pub const fn from_atomic(units: u64) -> Self {
+        Amount(units)
+    }
+
+    /// Creates an amount from whole NYX, returning `None` on overflow
+    pub fn from_nyx(nyx: u64) -> Option<Self> {
+        nyx.checked_mul(ATOMIC_UNITS_PER_NYX).map(Amount)
+    }
+
+    /// Returns the amount in atomic units
+    pub const fn as_atomic(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns true if the amount is zero
+    pub const fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Checked addition
+    pub fn checked_add(self, other: Amount) -> Option<Amount> {
+        self.0.checked_add(other.0).map(Amount)
+    }
+
+    /// Checked subtraction
+    pub fn checked_sub(self, other: Amount) -> Option<Amount> {
+        self.0.checked_sub(other.0).map(Amount)
+    }
+
+    /// Checked multiplication by a scalar
+    pub fn checked_mul(self, factor: u64) -> Option<Amount> {
+        self.0.checked_mul(factor).map(Amount)
+    }
+
+    /// Saturating addition
+    pub fn saturating_add(self, other: Amount) -> Amount {
+        Amount(self.0.saturating_add(other.0))
+    }
+
+    /// Saturating subtraction
+    pub fn saturating_sub(self, other: Amount) -> Amount {
+        Amount(self.0.saturating_sub(other.0))
+    }
+
+    /// Sums amounts, returning `None` if the total overflows
+    ///
+    /// # Example
+    /// ```
+    /// use nyx_core::Amount;
+    ///
+    /// let total = Amount::checked_sum([Amount::from_atomic(1), Amount::from_atomic(2)]);
+    /// assert_eq!(total, Some(Amount::from_atomic(3)));
+    ///
+    /// assert_eq!(Amount::checked_sum([Amount::MAX, Amount::from_atomic(1)]), None);
+    /// ```
+    pub fn checked_sum<I: IntoIterator<Item = Amount>>(amounts: I) -> Option<Amount> {
+        amounts.into_iter().try_fold(Amount::ZERO, Amount::checked_add)
+    }
+
+    /// Sums raw atomic-unit values, returning `None` if the total overflows
+    pub fn checked_sum_atomic<I: IntoIterator<Item = u64>>(units: I) -> Option<Amount> {
+        Self::checked_sum(units.into_iter().map(Amount))
+    }
+
+    /// Parses a decimal NYX string ("1.5", "1.5 NYX") into atomic units
+    pub fn parse_nyx(s: &str) -> Result<Amount> {
+        let s = s.trim();
+        let s = s.strip_suffix(TICKER).unwrap_or(s).trim_end();
+
+        if s.is_empty() {
+            return Err(NyxError::InvalidAmount("Empty amount".to_string()));
+        }
+
+        let (whole, frac) = match s.split_once('.') {
+            Some((w, f)) => (w, f),
+            None => (s, ""),
+        };
+
+        if whole.is_empty() && frac.is_empty() {
+            return Err(NyxError::InvalidAmount(format!("Invalid amount: {}", s)));
+        }
+
+        if !whole.chars().all(|c| c.is_ascii_digit()) || !frac.chars().all(|c| c.is_ascii_digit()) {
+            return Err(NyxError::InvalidAmount(format!("Invalid amount: {}", s)));
+        }
+
+        if frac.len() > DECIMALS as usize {
+            return Err(NyxError::InvalidAmount(format!(
+                "Too many decimal places: max {}, got {}",
+                DECIMALS,
+                frac.len()
+            )));
+        }
+
+        let whole_units = if whole.is_empty() {
+            0
+        } else {
+            whole.parse::<u64>()
+                .map_err(|_| NyxError::InvalidAmount(format!("Amount out of range: {}", s)))?
+        };
+
+        let frac_units = if frac.is_empty() {
+            0
+        } else {
+            let padded = format!("{:0<width$}", frac, width = DECIMALS as usize);
+            padded.parse::<u64>()
+                .map_err(|_| NyxError::InvalidAmount(format!("Invalid fraction: {}", s)))?
+        };
+
+        Amount::from_nyx(whole_units)
+            .and_then(|a| a.checked_add(Amount(frac_units)))
+            .ok_or_else(|| NyxError::InvalidAmount(format!("Amount out of range: {}", s)))
+    }
+}
+
+impl fmt::Display for Amount {
+    /// Formats as a decimal NYX value with trailing zeros trimmed, e.g. `1.5 NYX`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let whole = self.0 / ATOMIC_UNITS_PER_NYX;
+        let frac = self.0 % ATOMIC_UNITS_PER_NYX;
+
+        if frac == 0 {
+            write!(f, "{} {}", whole, TICKER)
+        } else {
+            let frac_str = format!("{:0width$}", frac, width = DECIMALS as usize);
+            write!(f, "{}.{} {}", whole, frac_str.trim_end_matches('0'), TICKER)
+        }
+    }
+}
+
+impl FromStr for Amount {
+    type Err = NyxError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Amount::parse_nyx(s)
+    }
+}
+
+impl From<u64> for Amount {
+    fn from(units: u64) -> Self {
+        Amount(units)
+    }
+}
+
+impl From<Amount> for u64 {
+    fn from(amount: Amount) -> Self {
+        amount.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(ATOMIC_UNITS_PER_NYX, 1_000_000_000_000);
+        assert_eq!(Amount::ONE_NYX.as_atomic(), ATOMIC_UNITS_PER_NYX);
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let a = Amount::from_atomic(10);
+        let b = Amount::from_atomic(3);
+
+        assert_eq!(a.checked_add(b), Some(Amount::from_atomic(13)));
+        assert_eq!(a.checked_sub(b), Some(Amount::from_atomic(7)));
+        assert_eq!(b.checked_sub(a), None);
+        assert_eq!(Amount::MAX.checked_add(b), None);
+        assert_eq!(Amount::MAX.checked_mul(2), None);
+    }
+
+    #[test]
+    fn test_checked_sum_atomic() {
+        assert_eq!(Amount::checked_sum_atomic([1, 2, 3]), Some(Amount::from_atomic(6)));
+        assert_eq!(Amount::checked_sum_atomic([u64::MAX, 1]), None);
+        assert_eq!(Amount::checked_sum_atomic([]), Some(Amount::ZERO));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Amount::ONE_NYX.to_string(), "1 NYX");
+        assert_eq!(Amount::from_atomic(1_500_000_000_000).to_string(), "1.5 NYX");
+        assert_eq!(Amount::from_atomic(1).to_string(), "0.000000000001 NYX");
+        assert_eq!(Amount::ZERO.to_string(), "0 NYX");
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("1.5 NYX".parse::<Amount>().unwrap(), Amount::from_atomic(1_500_000_000_000));
+        assert_eq!("1.5".parse::<Amount>().unwrap(), Amount::from_atomic(1_500_000_000_000));
+        assert_eq!("2".parse::<Amount>().unwrap(), Amount::from_nyx(2).unwrap());
+        assert_eq!(".25".parse::<Amount>().unwrap(), Amount::from_atomic(250_000_000_000));
+        assert_eq!("0.000000000001".parse::<Amount>().unwrap(), Amount::from_atomic(1));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("".parse::<Amount>().is_err());
+        assert!(".".parse::<Amount>().is_err());
+        assert!("abc".parse::<Amount>().is_err());
+        assert!("-1".parse::<Amount>().is_err());
+        assert!("1.0000000000001".parse::<Amount>().is_err());
+        assert!("99999999999999999999".parse::<Amount>().is_err());
+    }
+
+    #[test]
+    fn test_display_parse_roundtrip() {
+        for units in [0, 1, 42, 1_234_567_890_123, u64::MAX] {
+            let amount = Amount::from_atomic(units);
+            assert_eq!(amount.to_string().parse::<Amount>().unwrap(), amount);
+        }
+    }
+}
@@ -0,0 +1,180 @@
+// src/balance.rs
+
+//! Input/output balance verification.
+//!
+//! Amounts are hidden in Pedersen commitments, so consensus cannot add them
+//! up directly. Instead it relies on the homomorphic property:
+//!
+//! Σ C(inputs) == Σ C(outputs) + fee·G
+//!
+//! which holds only if the hidden values balance and the sender chose output
+//! blinding factors that cancel the input ones.
+
+use crate::errors::{NyxError, Result};
+use crate::storage::MemoryStorage;
+use crate::transaction::{Transaction, TxOutput};
+use nyx_crypto::pedersen::{self, Commitment};
+
+/// Fee charged on Layer 1 transactions (Layer 1 is fee-free)
+pub const LAYER1_FEE: u64 = 0;
+
+/// Parses a list of raw commitments
+fn parse_commitments<'a, I>(raw: I, what: &str) -> Result<Vec<Commitment>>
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    raw.into_iter()
+        .enumerate()
+        .map(|(i, bytes)| {
+            Commitment::from_bytes(bytes).map_err(|e| {
+                NyxError::InvalidTransaction(format!("Invalid {} commitment {}: {}", what, i, e))
+            })
+        })
+        .collect()
+}
+
+/// Looks up the commitments of the outputs spent by a transaction
+///
+/// # Errors
+/// Returns `InvalidTransaction` if a referenced transaction or output index
+/// does not exist.
+pub fn resolve_input_commitments(storage: &MemoryStorage, tx: &Transaction) -> Result<Vec<Vec<u8>>> {
+    Ok(resolve_spent_outputs(storage, tx)?
+        .into_iter()
+        .map(|output| output.amount_commitment)
+        .collect())
+}
+
+/// Looks up the outputs spent by a transaction
+///
+/// Does not check whether they were spent before; the DAG records every
+/// spent output when it accepts a transaction.
+///
+/// # Errors
+/// Returns `InvalidTransaction` if a referenced transaction or output index
+/// does not exist.
+pub fn resolve_spent_outputs(storage: &MemoryStorage, tx: &Transaction) -> Result<Vec<TxOutput>> {
+    tx.inputs.iter()
+        .map(|input| {
+            let outputs = storage.get_outputs(&input.prev_tx).map_err(|_| {
+                NyxError::InvalidTransaction(format!(
+                    "Spent transaction not found: {}",
+                    hex::encode(input.prev_tx)
+                ))
+            })?;
+
+            outputs.get(input.index as usize)
+                .cloned()
+                .ok_or_else(|| NyxError::InvalidTransaction(format!(
+                    "Output index {} out of range for {}",
+                    input.index,
+                    hex::encode(input.prev_tx)
+                )))
+        })
+        .collect()
+}
+
+/// Verifies that a transaction's commitments balance
+///
+/// # Arguments
+/// * `tx` - Transaction whose outputs are checked
+/// * `input_commitments` - Commitments of the outputs being spent
+/// * `fee` - Public fee paid by the transaction
+pub fn verify_balance(tx: &Transaction, input_commitments: &[Vec<u8>], fee: u64) -> Result<()> {
+    if input_commitments.len() != tx.inputs.len() {
+        return Err(NyxError::InvalidTransaction(format!(
+            "Expected {} input commitments, got {}",
+            tx.inputs.len(),
+            input_commitments.len()
+        )));
+    }
+
+    let inputs = parse_commitments(input_commitments.iter().map(|c| c.as_slice()), "input")?;
+    let outputs = parse_commitments(
+        tx.outputs.iter().map(|o| o.amount_commitment.as_slice()),
+        "output",
+    )?;
+
+    if !pedersen::verify_balance(&inputs, &outputs, fee) {
+        return Err(NyxError::InvalidTransaction(
+            "Input and output commitments do not balance".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::{TxInput, TxOutput};
+    use nyx_crypto::pedersen::{commit, random_blinding, sub_blindings};
+    use nyx_crypto::RingSignature;
+
+    fn tx_with_outputs(commitments: Vec<Vec<u8>>) -> Transaction {
+        Transaction::try_new(
+            vec![TxInput {
+                prev_tx: [1u8; 32],
+                index: 0,
+                key_image: [2u8; 32],
+                ring_indices: vec![],
+            }],
+            commitments.into_iter().map(|c| TxOutput {
+                stealth_address: vec![],
+                amount_commitment: c,
+                range_proof: vec![],
+                ephemeral_pubkey: vec![],
+                unlock_time: None,
+                htlc: None,
+            }).collect(),
+            vec![RingSignature {
+                ring_members: vec![],
+                signature: vec![],
+                key_image: [2u8; 32],
+            }],
+            vec![],
+            [0u8; 32],
+            [1u8; 32],
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_balanced_transaction() {
+        let r_in = random_blinding();
+        let r_out1 = random_blinding();
+        let r_out2 = sub_blindings(&r_in, &r_out1);
+
+        let tx = tx_with_outputs(vec![
+            commit(70, &r_out1).to_bytes().to_vec(),
+            commit(30, &r_out2).to_bytes().to_vec(),
+        ]);
+        let inputs = vec![commit(100, &r_in).to_bytes().to_vec()];
+
+        assert!(verify_balance(&tx, &inputs, 0).is_ok());
+    }
+
+    #[test]
+    fn test_inflation_rejected() {
+        let r = random_blinding();
+        let tx = tx_with_outputs(vec![commit(101, &r).to_bytes().to_vec()]);
+        let inputs = vec![commit(100, &r).to_bytes().to_vec()];
+
+        assert!(verify_balance(&tx, &inputs, 0).is_err());
+    }
+
+    #[test]
+    fn test_malformed_commitment_rejected() {
+        let r = random_blinding();
+        let tx = tx_with_outputs(vec![vec![0xFF; 32]]);
+        let inputs = vec![commit(100, &r).to_bytes().to_vec()];
+
+        assert!(verify_balance(&tx, &inputs, 0).is_err());
+    }
+
+    #[test]
+    fn test_resolve_missing_input() {
+        let storage = MemoryStorage::new();
+        let tx = tx_with_outputs(vec![vec![0u8; 32]]);
+        assert!(resolve_input_commitments(&storage, &tx).is_err());
+    }
+}
@@ -0,0 +1,515 @@
+// src/bridge.rs
+
+//! Ethereum event proofs for a cross-chain bridge.
+//!
+//! A bridge contract on Ethereum emits events when tokens are locked or
+//! burned there. An [`EventProof`] shows Nyx that one of those events
+//! happened: it carries the block header, a Merkle-Patricia proof of the
+//! transaction's receipt under the header's receipts root, and the index
+//! of the log within the receipt. Verifying it needs only the block hash,
+//! which the caller must already trust (from a light client, checkpoint or
+//! validator attestation); nothing here follows Ethereum consensus.
+//!
+//! Proofs are checked with Keccak-256, as Ethereum hashes headers and trie
+//! nodes with it. Only receipts of successful transactions are accepted,
+//! as a reverted transaction's logs never took effect.
+
+use crate::errors::{NyxError, Result};
+use crate::types::Hash;
+use nyx_crypto::hash::keccak_hash;
+use serde::{Deserialize, Serialize};
+
+/// Most trie nodes an event proof may hold
+///
+/// Receipt keys are at most 9 bytes, so no path has more than 19 nodes.
+pub const MAX_PROOF_NODES: usize = 32;
+
+/// Deepest RLP nesting decoded
+const MAX_RLP_DEPTH: usize = 8;
+
+/// Position of the receipts root in an Ethereum header
+const HEADER_RECEIPTS_ROOT: usize = 5;
+
+/// Position of the block number in an Ethereum header
+const HEADER_NUMBER: usize = 8;
+
+/// Decoded RLP item, borrowing from its encoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rlp<'a> {
+    /// Byte string
+    Bytes(&'a [u8]),
+
+    /// List of items, with the encoding of the whole list
+    List(Vec<Rlp<'a>>, &'a [u8]),
+}
+
+impl<'a> Rlp<'a> {
+    /// Decodes a single RLP item spanning all of `data`
+    pub fn decode(data: &'a [u8]) -> Result<Self> {
+        let (item, used) = decode_item(data, 0)?;
+        if used != data.len() {
+            return Err(invalid("trailing bytes after RLP item"));
+        }
+        Ok(item)
+    }
+
+    /// Bytes of a byte string item
+    pub fn bytes(&self) -> Result<&'a [u8]> {
+        match self {
+            Rlp::Bytes(bytes) => Ok(bytes),
+            Rlp::List(..) => Err(invalid("expected an RLP string, found a list")),
+        }
+    }
+
+    /// Items of a list item
+    pub fn items(&self) -> Result<&[Rlp<'a>]> {
+        match self {
+            Rlp::List(items, _) => Ok(items),
+            Rlp::Bytes(_) => Err(invalid("expected an RLP list, found a string")),
+        }
+    }
+
+    /// Big-endian integer of at most eight bytes
+    pub fn as_u64(&self) -> Result<u64> {
+        let bytes = self.bytes()?;
+        if bytes.len() > 8 {
+            return Err(invalid("RLP integer wider than 64 bits"));
+        }
+        Ok(bytes.iter().fold(0u64, |n, &b| (n << 8) | u64::from(b)))
+    }
+
+    /// Byte string of exactly `N` bytes
+    fn fixed<const N: usize>(&self) -> Result<[u8; N]> {
+        self.bytes()?.try_into().map_err(|_| invalid(&format!("expected {} bytes", N)))
+    }
+}
+
+fn invalid(reason: &str) -> NyxError {
+    NyxError::InvalidBridgeProof(reason.to_string())
+}
+
+/// Decodes the item at the start of `data`, returning it and its length
+fn decode_item(data: &[u8], depth: usize) -> Result<(Rlp<'_>, usize)> {
+    if depth > MAX_RLP_DEPTH {
+        return Err(invalid("RLP nested too deeply"));
+    }
+    let prefix = *data.first().ok_or_else(|| invalid("truncated RLP"))?;
+    let (header, length, is_list) = match prefix {
+        0x00..=0x7f => return Ok((Rlp::Bytes(&data[..1]), 1)),
+        0x80..=0xb7 => (1, usize::from(prefix - 0x80), false),
+        0xb8..=0xbf => (1 + usize::from(prefix - 0xb7), long_length(data, prefix - 0xb7)?, false),
+        0xc0..=0xf7 => (1, usize::from(prefix - 0xc0), true),
+        0xf8..=0xff => (1 + usize::from(prefix - 0xf7), long_length(data, prefix - 0xf7)?, true),
+    };
+    let end = header.checked_add(length)
+        .filter(|&end| end <= data.len())
+        .ok_or_else(|| invalid("truncated RLP"))?;
+    let payload = &data[header..end];
+
+    if !is_list {
+        return Ok((Rlp::Bytes(payload), end));
+    }
+    let mut items = Vec::new();
+    let mut offset = 0;
+    while offset < payload.len() {
+        let (item, used) = decode_item(&payload[offset..], depth + 1)?;
+        items.push(item);
+        offset += used;
+    }
+    Ok((Rlp::List(items, &data[..end]), end))
+}
+
+/// Reads the `width`-byte length following a long-form prefix
+fn long_length(data: &[u8], width: u8) -> Result<usize> {
+    let bytes = data.get(1..1 + usize::from(width)).ok_or_else(|| invalid("truncated RLP"))?;
+    if bytes.len() > std::mem::size_of::<usize>() {
+        return Err(invalid("RLP length too large"));
+    }
+    Ok(bytes.iter().fold(0usize, |n, &b| (n << 8) | usize::from(b)))
+}
+
+/// RLP encoding of a receipt's transaction index, its key in the receipts
+/// trie
+fn receipt_key(tx_index: u64) -> Vec<u8> {
+    match tx_index {
+        0 => vec![0x80],
+        1..=0x7f => vec![tx_index as u8],
+        _ => {
+            let bytes = tx_index.to_be_bytes();
+            let skip = bytes.iter().take_while(|&&b| b == 0).count();
+            let mut key = vec![0x80 + (8 - skip) as u8];
+            key.extend_from_slice(&bytes[skip..]);
+            key
+        }
+    }
+}
+
+/// Reference from a trie node to a child
+enum NodeRef<'a> {
+    /// Child stored separately, by its Keccak-256 hash
+    Hash([u8; 32]),
+
+    /// Child shorter than a hash, inlined in its parent
+    Inline(&'a [u8]),
+}
+
+fn child_ref<'a>(item: &Rlp<'a>) -> Result<NodeRef<'a>> {
+    match item {
+        Rlp::Bytes([]) => Err(invalid("key is not in the trie")),
+        Rlp::Bytes(bytes) => Ok(NodeRef::Hash(
+            (*bytes).try_into().map_err(|_| invalid("trie child is not a hash"))?,
+        )),
+        Rlp::List(_, raw) => Ok(NodeRef::Inline(raw)),
+    }
+}
+
+/// Splits a hex-prefix encoded path into whether it ends at a leaf, and
+/// its nibbles
+fn decode_path(encoded: &[u8]) -> Result<(bool, Vec<u8>)> {
+    let first = *encoded.first().ok_or_else(|| invalid("empty trie path"))?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(invalid("bad trie path prefix"));
+    }
+    let mut nibbles = Vec::with_capacity(encoded.len() * 2);
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    for byte in &encoded[1..] {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+    Ok((flag >= 2, nibbles))
+}
+
+/// Verifies a Merkle-Patricia proof that `key` maps to a value under
+/// `root`, returning the value
+///
+/// `proof` holds the hashed nodes from the root down, each RLP encoded, as
+/// `eth_getProof` and receipt proof tools return them.
+pub fn verify_trie_proof<'a>(root: &Hash, key: &[u8], proof: &'a [Vec<u8>]) -> Result<&'a [u8]> {
+    if proof.len() > MAX_PROOF_NODES {
+        return Err(invalid("too many proof nodes"));
+    }
+    let nibbles: Vec<u8> = key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect();
+    let mut path = &nibbles[..];
+    let mut nodes = proof.iter();
+    let mut next = NodeRef::Hash(*root);
+
+    loop {
+        let node: &[u8] = match next {
+            NodeRef::Hash(hash) => {
+                let node = nodes.next().ok_or_else(|| invalid("proof ends before the value"))?;
+                if keccak_hash(node) != hash {
+                    return Err(invalid("proof node does not match its hash"));
+                }
+                node
+            }
+            NodeRef::Inline(raw) => raw,
+        };
+
+        let decoded = Rlp::decode(node)?;
+        let items = decoded.items()?;
+        let value = match items.len() {
+            17 => match path.split_first() {
+                Some((&nibble, rest)) => {
+                    next = child_ref(&items[usize::from(nibble)])?;
+                    path = rest;
+                    continue;
+                }
+                None => items[16].bytes()?,
+            },
+            2 => {
+                let (leaf, node_path) = decode_path(items[0].bytes()?)?;
+                if !path.starts_with(&node_path) || (leaf && path.len() != node_path.len()) {
+                    return Err(invalid("key is not in the trie"));
+                }
+                path = &path[node_path.len()..];
+                if !leaf {
+                    next = child_ref(&items[1])?;
+                    continue;
+                }
+                items[1].bytes()?
+            }
+            _ => return Err(invalid("trie node is neither a branch, extension nor leaf")),
+        };
+
+        if value.is_empty() {
+            return Err(invalid("key is not in the trie"));
+        }
+        if nodes.next().is_some() {
+            return Err(invalid("proof has unused nodes"));
+        }
+        return Ok(value);
+    }
+}
+
+/// Fields of an Ethereum block header an event proof relies on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EthHeader {
+    /// Keccak-256 hash of the RLP encoded header
+    pub hash: Hash,
+
+    /// Block number
+    pub number: u64,
+
+    /// Root of the block's receipts trie
+    pub receipts_root: Hash,
+}
+
+impl EthHeader {
+    /// Decodes an RLP encoded header
+    pub fn decode(rlp: &[u8]) -> Result<Self> {
+        let decoded = Rlp::decode(rlp)?;
+        let fields = decoded.items()?;
+        if fields.len() <= HEADER_NUMBER {
+            return Err(invalid("header has too few fields"));
+        }
+        Ok(Self {
+            hash: keccak_hash(rlp),
+            number: fields[HEADER_NUMBER].as_u64()?,
+            receipts_root: fields[HEADER_RECEIPTS_ROOT].fixed()?,
+        })
+    }
+}
+
+/// Log emitted by an Ethereum contract
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EthLog {
+    /// Contract that emitted the log
+    pub address: [u8; 20],
+
+    /// Indexed topics; the first is the event signature's
+    pub topics: Vec<[u8; 32]>,
+
+    /// ABI encoded non-indexed arguments
+    pub data: Vec<u8>,
+}
+
+impl EthLog {
+    /// Checks whether the log is event `signature` emitted by `contract`
+    pub fn is_event(&self, contract: &[u8; 20], signature: &str) -> bool {
+        &self.address == contract && self.topics.first() == Some(&event_topic(signature))
+    }
+
+    fn decode(item: &Rlp<'_>) -> Result<Self> {
+        let [address, topics, data] = item.items()? else {
+            return Err(invalid("log does not have three fields"));
+        };
+        Ok(Self {
+            address: address.fixed()?,
+            topics: topics.items()?.iter().map(Rlp::fixed).collect::<Result<_>>()?,
+            data: data.bytes()?.to_vec(),
+        })
+    }
+}
+
+/// First topic of logs of an event, e.g. `Transfer(address,address,uint256)`
+pub fn event_topic(signature: &str) -> [u8; 32] {
+    keccak_hash(signature.as_bytes())
+}
+
+/// Decodes the logs of a successful transaction's receipt
+///
+/// Typed receipts (EIP-2718) carry a type byte before their RLP list.
+fn decode_receipt_logs(receipt: &[u8]) -> Result<Vec<EthLog>> {
+    let body = match receipt.first() {
+        Some(&kind) if kind < 0x80 => &receipt[1..],
+        _ => receipt,
+    };
+    let decoded = Rlp::decode(body)?;
+    let [status, _gas_used, _bloom, logs] = decoded.items()? else {
+        return Err(invalid("receipt does not have four fields"));
+    };
+    if status.bytes()? != [1] {
+        return Err(invalid("receipt is not of a successful transaction"));
+    }
+    logs.items()?.iter().map(EthLog::decode).collect()
+}
+
+/// Proof that an Ethereum block holds a log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventProof {
+    /// RLP encoded block header
+    pub header: Vec<u8>,
+
+    /// Index of the transaction within the block
+    pub tx_index: u64,
+
+    /// Receipts trie nodes from the root to the transaction's receipt
+    pub receipt_proof: Vec<Vec<u8>>,
+
+    /// Index of the log within the receipt
+    pub log_index: u32,
+}
+
+impl EventProof {
+    /// Verifies the proof against the hash of a trusted block
+    ///
+    /// # Returns
+    /// The proven log and the header it was proven under
+    ///
+    /// # Errors
+    /// - `InvalidBridgeProof` if the header is not the trusted block's, the
+    ///   receipt is not under its receipts root, the transaction failed or
+    ///   the receipt has no such log
+    pub fn verify(&self, block_hash: &Hash) -> Result<(EthHeader, EthLog)> {
+        let header = EthHeader::decode(&self.header)?;
+        if &header.hash != block_hash {
+            return Err(invalid("header is not the trusted block's"));
+        }
+
+        let key = receipt_key(self.tx_index);
+        let receipt = verify_trie_proof(&header.receipts_root, &key, &self.receipt_proof)?;
+        let log = decode_receipt_logs(receipt)?
+            .into_iter()
+            .nth(self.log_index as usize)
+            .ok_or_else(|| invalid("receipt has no such log"))?;
+        Ok((header, log))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    enum Item {
+        Bytes(Vec<u8>),
+        List(Vec<Item>),
+    }
+
+    fn encode(item: &Item) -> Vec<u8> {
+        let (payload, short, long) = match item {
+            Item::Bytes(bytes) if bytes.len() == 1 && bytes[0] < 0x80 => return bytes.clone(),
+            Item::Bytes(bytes) => (bytes.clone(), 0x80, 0xb7),
+            Item::List(items) => (items.iter().flat_map(encode).collect(), 0xc0, 0xf7),
+        };
+        let mut out = if payload.len() <= 55 {
+            vec![short + payload.len() as u8]
+        } else {
+            let len = (payload.len() as u64).to_be_bytes();
+            let skip = len.iter().take_while(|&&b| b == 0).count();
+            let mut out = vec![long + (8 - skip) as u8];
+            out.extend_from_slice(&len[skip..]);
+            out
+        };
+        out.extend(payload);
+        out
+    }
+
+    fn bytes(data: &[u8]) -> Item {
+        Item::Bytes(data.to_vec())
+    }
+
+    const BRIDGE: [u8; 20] = [0xB1; 20];
+    const MINT: &str = "Mint(address,uint256)";
+
+    fn receipt(status: u8) -> Vec<u8> {
+        let log = Item::List(vec![
+            bytes(&BRIDGE),
+            Item::List(vec![bytes(&event_topic(MINT)), bytes(&[7u8; 32])]),
+            bytes(&[0u8; 64]),
+        ]);
+        let mut receipt = vec![0x02]; // EIP-1559
+        receipt.extend(encode(&Item::List(vec![
+            bytes(&[status]),
+            bytes(&[0x52, 0x08]),
+            bytes(&[0u8; 256]),
+            Item::List(vec![log]),
+        ])));
+        receipt
+    }
+
+    fn header(receipts_root: &Hash) -> Vec<u8> {
+        let mut fields: Vec<Item> = (0..16).map(|_| bytes(&[0u8; 32])).collect();
+        fields[HEADER_RECEIPTS_ROOT] = bytes(receipts_root);
+        fields[HEADER_NUMBER] = bytes(&[0x01, 0x2c]);
+        encode(&Item::List(fields))
+    }
+
+    /// Receipts trie of two transactions: a branch on the first nibble of
+    /// the keys `0x80` and `0x01`, over one leaf each
+    fn two_receipt_trie(first: Vec<u8>, second: Vec<u8>) -> (Hash, Vec<u8>, Vec<u8>, Vec<u8>) {
+        let leaf0 = encode(&Item::List(vec![bytes(&[0x30]), Item::Bytes(first)]));
+        let leaf1 = encode(&Item::List(vec![bytes(&[0x31]), Item::Bytes(second)]));
+        let mut children: Vec<Item> = (0..17).map(|_| bytes(&[])).collect();
+        children[8] = bytes(&keccak_hash(&leaf0));
+        children[0] = bytes(&keccak_hash(&leaf1));
+        let branch = encode(&Item::List(children));
+        (keccak_hash(&branch), branch, leaf0, leaf1)
+    }
+
+    #[test]
+    fn test_rlp_decoding() {
+        let list = [0xc8, 0x83, b'c', b'a', b't', 0x83, b'd', b'o', b'g'];
+        let decoded = Rlp::decode(&list).unwrap();
+        assert_eq!(decoded.items().unwrap()[1].bytes().unwrap(), b"dog");
+        assert_eq!(Rlp::decode(&[0x82, 0x04, 0x00]).unwrap().as_u64().unwrap(), 1024);
+        assert_eq!(Rlp::decode(&[0x0f]).unwrap(), Rlp::Bytes(&[0x0f]));
+
+        let long = encode(&bytes(&[0xAA; 60]));
+        assert_eq!(Rlp::decode(&long).unwrap().bytes().unwrap(), &[0xAA; 60]);
+
+        assert!(Rlp::decode(&list[..5]).is_err());
+        assert!(Rlp::decode(&[0x80, 0x80]).is_err());
+        assert!(Rlp::decode(&[0xc1; 16]).is_err());
+    }
+
+    #[test]
+    fn test_receipt_keys() {
+        assert_eq!(receipt_key(0), vec![0x80]);
+        assert_eq!(receipt_key(1), vec![0x01]);
+        assert_eq!(receipt_key(0x80), vec![0x81, 0x80]);
+        assert_eq!(receipt_key(0x0400), vec![0x82, 0x04, 0x00]);
+    }
+
+    #[test]
+    fn test_event_proof_verifies() {
+        let (root, branch, leaf0, leaf1) = two_receipt_trie(receipt(1), receipt(0));
+        let header = header(&root);
+        let block_hash = keccak_hash(&header);
+        let proof = EventProof {
+            header: header.clone(),
+            tx_index: 0,
+            receipt_proof: vec![branch.clone(), leaf0.clone()],
+            log_index: 0,
+        };
+
+        let (eth_header, log) = proof.verify(&block_hash).unwrap();
+        assert_eq!(eth_header.number, 300);
+        assert_eq!(eth_header.receipts_root, root);
+        assert!(log.is_event(&BRIDGE, MINT));
+        assert!(!log.is_event(&[0u8; 20], MINT));
+        assert_eq!(log.topics[1], [7u8; 32]);
+
+        // Untrusted block, missing log
+        assert!(proof.verify(&[0u8; 32]).is_err());
+        assert!(EventProof { log_index: 1, ..proof.clone() }.verify(&block_hash).is_err());
+
+        // A reverted transaction's logs do not count
+        let reverted = EventProof {
+            tx_index: 1,
+            receipt_proof: vec![branch.clone(), leaf1],
+            ..proof.clone()
+        };
+        assert!(reverted.verify(&block_hash).is_err());
+    }
+
+    #[test]
+    fn test_trie_proof_rejects_tampering() {
+        let (root, branch, leaf0, leaf1) = two_receipt_trie(receipt(1), receipt(1));
+        assert!(verify_trie_proof(&root, &receipt_key(0), &[branch.clone(), leaf0.clone()]).is_ok());
+        assert!(verify_trie_proof(&root, &receipt_key(1), &[branch.clone(), leaf1.clone()]).is_ok());
+
+        // Leaf of another key, a changed leaf, a missing leaf or extra nodes
+        assert!(verify_trie_proof(&root, &receipt_key(0), &[branch.clone(), leaf1.clone()]).is_err());
+        let mut forged = leaf0.clone();
+        *forged.last_mut().unwrap() ^= 1;
+        assert!(verify_trie_proof(&root, &receipt_key(0), &[branch.clone(), forged]).is_err());
+        assert!(verify_trie_proof(&root, &receipt_key(0), std::slice::from_ref(&branch)).is_err());
+        assert!(verify_trie_proof(&root, &receipt_key(0), &[branch.clone(), leaf0, leaf1]).is_err());
+
+        // Key absent from the trie
+        assert!(verify_trie_proof(&root, &receipt_key(2), &[branch]).is_err());
+    }
+}
@@ -0,0 +1,312 @@
+// src/checkpoint.rs
+
+//! Finalized checkpoints for snap sync.
+//!
+//! A [`Checkpoint`] bundles everything a fresh node needs to start from the
+//! latest finalized state instead of replaying history:
+//! - The validator-signed PoS snapshot that finalized it
+//! - The snapshot header chain from the first snapshot
+//! - The finalized part of the DAG
+//!
+//! [`Checkpoint::verify`] checks the validator quorum, the header chain, and
+//! that the state hashes to the state root in the latest header. Headers are
+//! not signed themselves, so callers should additionally require several
+//! peers to agree on the checkpoint header before trusting it.
+
+use crate::consensus::{SignedSnapshot, ValidatorSet};
+use crate::dag::TxState;
+use crate::errors::{NyxError, Result};
+use crate::finality::{FinalizedState, SnapshotHeader};
+use crate::snapshot::DagSnapshot;
+use crate::types::Hash;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Latest finalized state with its proof of finality
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Validator-signed snapshot the checkpoint is taken at
+    pub signed: SignedSnapshot,
+
+    /// Snapshot headers from 1 up to the signed snapshot
+    pub headers: Vec<SnapshotHeader>,
+
+    /// Finalized transactions, in topological order
+    #[serde(with = "state_bytes")]
+    pub state: DagSnapshot,
+}
+
+impl Checkpoint {
+    /// Snapshot number of the checkpoint
+    pub fn number(&self) -> u64 {
+        self.signed.snapshot.number
+    }
+
+    /// Header of the checkpoint snapshot
+    pub fn header(&self) -> Option<&SnapshotHeader> {
+        self.headers.last()
+    }
+
+    /// Encodes the checkpoint as a snapshot file
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        bincode::serialize(self)
+            .map_err(|e| NyxError::SerializationError(format!("Failed to encode checkpoint: {}", e)))
+    }
+
+    /// Decodes a snapshot file
+    ///
+    /// The checkpoint still has to be verified before use.
+    ///
+    /// # Errors
+    /// Returns `SerializationError` if the file is truncated or malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        bincode::deserialize(bytes)
+            .map_err(|e| NyxError::SerializationError(format!("Invalid checkpoint: {}", e)))
+    }
+
+    /// Verifies the checkpoint against the validator set
+    ///
+    /// # Errors
+    /// - `CryptoError` / `ConsensusError` if the snapshot lacks a valid
+    ///   two-thirds stake quorum
+    /// - `ConsensusError` if the header chain is malformed or does not end at
+    ///   the signed snapshot
+    /// - `InvalidTransaction` if the state contains unfinalized transactions,
+    ///   misses an anchor, or does not match the header's state root
+    pub fn verify(&self, validators: &ValidatorSet) -> Result<()> {
+        validators.verify(&self.signed)?;
+        self.verify_headers()?;
+        self.verify_state()
+    }
+
+    fn verify_headers(&self) -> Result<()> {
+        let mut prev_hash = [0u8; 32];
+        for (i, header) in self.headers.iter().enumerate() {
+            if header.number != i as u64 + 1 || header.prev_hash != prev_hash {
+                return Err(NyxError::ConsensusError(format!(
+                    "Checkpoint header chain broken at snapshot {}",
+                    header.number
+                )));
+            }
+            prev_hash = header.hash();
+        }
+
+        if self.headers.len() as u64 != self.number() {
+            return Err(NyxError::ConsensusError(format!(
+                "Checkpoint has {} headers for snapshot {}",
+                self.headers.len(),
+                self.number()
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn verify_state(&self) -> Result<()> {
+        let header = self.header()
+            .ok_or_else(|| NyxError::ConsensusError("Checkpoint has no headers".to_string()))?;
+
+        let mut finalized = FinalizedState::default();
+        let mut hashes = HashSet::with_capacity(self.state.entries.len());
+        for entry in &self.state.entries {
+            let hash = entry.tx.id();
+            if entry.state != TxState::Finalized {
+                return Err(NyxError::InvalidTransaction(format!(
+                    "Checkpoint contains unfinalized transaction {}",
+                    hex::encode(hash)
+                )));
+            }
+            finalized.record(hash, &entry.tx);
+            hashes.insert(hash);
+        }
+
+        if let Some(anchor) = self.signed.snapshot.anchors.iter().find(|a| !hashes.contains(*a)) {
+            return Err(NyxError::InvalidTransaction(format!(
+                "Checkpoint state is missing anchor {}",
+                hex::encode(anchor)
+            )));
+        }
+
+        let state_root: Hash = finalized.state_root();
+        if state_root != header.state_root {
+            return Err(NyxError::InvalidTransaction(format!(
+                "Checkpoint state root {} does not match header {}",
+                hex::encode(state_root),
+                hex::encode(header.state_root)
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Serializes the DAG state in its binary snapshot format
+mod state_bytes {
+    use crate::snapshot::DagSnapshot;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(state: &DagSnapshot, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        state.to_bytes().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<DagSnapshot, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        DagSnapshot::from_bytes(&bytes).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::Validator;
+    use crate::dag::DagProcessor;
+    use crate::finality::{FinalityTracker, PosSnapshot};
+    use crate::storage::MemoryStorage;
+    use crate::testing::{funding_key, funding_tx, test_tx, TestTx};
+    use crate::verification::VerifyMode;
+    use nyx_crypto::keys::ed25519_public_key;
+
+    fn validators() -> (ValidatorSet, Vec<[u8; 32]>) {
+        let keys: Vec<[u8; 32]> = (0..3u8).map(|i| [i; 32]).collect();
+        let set = ValidatorSet::new(
+            keys.iter().map(|k| Validator { public_key: ed25519_public_key(k).to_vec(), stake: 10 }).collect(),
+        )
+        .unwrap();
+        (set, keys)
+    }
+
+    fn sign(snapshot: PosSnapshot, keys: &[[u8; 32]]) -> SignedSnapshot {
+        let mut signed = SignedSnapshot::new(snapshot);
+        for (i, key) in keys.iter().enumerate() {
+            signed.sign(i as u32, key).unwrap();
+        }
+        signed
+    }
+
+    /// Finalizes `a` then `b` on top of two genesis transactions; `c` stays pending
+    fn finalized_tracker(set: &ValidatorSet, keys: &[[u8; 32]]) -> (FinalityTracker, DagProcessor, Hash) {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let g1 = storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 1)).unwrap();
+        let g2 = storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 2)).unwrap();
+        let a = dag.add_transaction_with_mode(test_tx(g1, g2, 3), VerifyMode::HeadersOnly).unwrap();
+        let b = dag.add_transaction_with_mode(test_tx(a, g2, 4), VerifyMode::HeadersOnly).unwrap();
+        let c = dag.add_transaction_with_mode(test_tx(b, a, 5), VerifyMode::HeadersOnly).unwrap();
+
+        let tracker = FinalityTracker::new(dag.clone());
+        tracker.apply_signed_snapshot(&sign(PosSnapshot { number: 1, anchors: vec![a] }, keys), set).unwrap();
+        tracker.apply_signed_snapshot(&sign(PosSnapshot { number: 2, anchors: vec![b] }, keys), set).unwrap();
+
+        (tracker, dag, c)
+    }
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let (set, keys) = validators();
+        let (tracker, _, pending) = finalized_tracker(&set, &keys);
+
+        let checkpoint = tracker.checkpoint().unwrap().unwrap();
+        assert_eq!(checkpoint.number(), 2);
+        assert_eq!(checkpoint.state.entries.len(), 4);
+        assert!(checkpoint.state.entries.iter().all(|e| e.tx.id() != pending));
+        checkpoint.verify(&set).unwrap();
+
+        let bytes = checkpoint.to_bytes().unwrap();
+        let decoded = Checkpoint::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, checkpoint);
+        assert!(Checkpoint::from_bytes(&bytes[..bytes.len() / 2]).is_err());
+
+        let fresh = FinalityTracker::new(DagProcessor::new(MemoryStorage::new()));
+        assert_eq!(fresh.restore_checkpoint(decoded, &set).unwrap(), 4);
+        assert_eq!(fresh.latest_snapshot(), 2);
+        assert_eq!(fresh.latest_header(), tracker.latest_header());
+        assert!(fresh.check_header(&tracker.header(1).unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_restored_node_continues_chain() {
+        let (set, keys) = validators();
+        let (tracker, dag, pending) = finalized_tracker(&set, &keys);
+
+        let fresh_dag = DagProcessor::new(MemoryStorage::new());
+        let fresh = FinalityTracker::new(fresh_dag.clone());
+        fresh.restore_checkpoint(tracker.checkpoint().unwrap().unwrap(), &set).unwrap();
+
+        // The pending transaction arrives through live gossip afterwards
+        let tx = dag.storage().get_transaction(&pending).unwrap();
+        fresh_dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly).unwrap();
+
+        let next = sign(PosSnapshot { number: 3, anchors: vec![pending] }, &keys);
+        tracker.apply_signed_snapshot(&next, &set).unwrap();
+        fresh.apply_signed_snapshot(&next, &set).unwrap();
+        assert_eq!(fresh.latest_header(), tracker.latest_header());
+    }
+
+    #[test]
+    fn test_restored_node_resolves_ring_indices_like_source() {
+        let (set, keys) = validators();
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+        let funding = storage.store_transaction(funding_tx()).unwrap();
+        let other = storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 1)).unwrap();
+        let a = dag.add_transaction_with_mode(test_tx(funding, other, 2), VerifyMode::HeadersOnly).unwrap();
+
+        // Finalizing walks back from the anchor, so `a` is indexed before
+        // the funding transaction it descends from
+        let tracker = FinalityTracker::new(dag.clone());
+        tracker.apply_signed_snapshot(&sign(PosSnapshot { number: 1, anchors: vec![a] }, &keys), &set).unwrap();
+        let first = storage.first_output_index(&funding).unwrap().unwrap();
+        assert_ne!(first, 0);
+
+        // Spend funding output 5 with a ring built against the source's indices
+        let (signer, decoy) = (funding_key(5), funding_key(6));
+        let mut spend = TestTx::new(5).parents(a, other).funded().build();
+        spend.inputs[0].ring_indices = vec![(first + 5) as u32, (first + 6) as u32];
+        let ring = vec![signer.public_key.clone(), decoy.public_key];
+        let message = spend.signing_message();
+        spend.sign(0, &message, signer.private_key(), &signer.public_key, &ring).unwrap();
+
+        let fresh_dag = DagProcessor::new(MemoryStorage::new());
+        let fresh = FinalityTracker::new(fresh_dag.clone());
+        fresh.restore_checkpoint(tracker.checkpoint().unwrap().unwrap(), &set).unwrap();
+        assert_eq!(fresh_dag.storage().first_output_index(&funding).unwrap(), Some(first));
+
+        fresh_dag.add_transaction(spend.clone()).unwrap();
+        dag.add_transaction(spend).unwrap();
+    }
+
+    #[test]
+    fn test_tampered_checkpoint_rejected() {
+        let (set, keys) = validators();
+        let (tracker, _, _) = finalized_tracker(&set, &keys);
+        let checkpoint = tracker.checkpoint().unwrap().unwrap();
+
+        let mut missing_tx = checkpoint.clone();
+        missing_tx.state.entries.remove(0);
+        assert!(matches!(missing_tx.verify(&set), Err(NyxError::InvalidTransaction(_))));
+
+        let mut wrong_root = checkpoint.clone();
+        wrong_root.headers[1].state_root = [9u8; 32];
+        assert!(matches!(wrong_root.verify(&set), Err(NyxError::InvalidTransaction(_))));
+
+        let mut broken_chain = checkpoint.clone();
+        broken_chain.headers[0].tx_count += 1;
+        assert!(matches!(broken_chain.verify(&set), Err(NyxError::ConsensusError(_))));
+
+        let mut unsigned = checkpoint.clone();
+        unsigned.signed.signature.signatures.truncate(1);
+        assert!(matches!(unsigned.verify(&set), Err(NyxError::ConsensusError(_))));
+
+        let fresh = FinalityTracker::new(DagProcessor::new(MemoryStorage::new()));
+        assert!(fresh.restore_checkpoint(wrong_root, &set).is_err());
+        assert_eq!(fresh.latest_snapshot(), 0);
+    }
+
+    #[test]
+    fn test_no_checkpoint_without_signatures() {
+        let dag = DagProcessor::new(MemoryStorage::new());
+        assert!(FinalityTracker::new(dag).checkpoint().unwrap().is_none());
+    }
+}
@@ -0,0 +1,95 @@
+// src/clock.rs
+
+//! Injectable time source.
+//!
+//! Components that compare against the current time take a [`Clock`]
+//! instead of reading the system clock, so tests and simulations can run on
+//! virtual time with a [`ManualClock`].
+
+use crate::types::Timestamp;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Source of the current Unix time
+pub trait Clock: Debug + Send + Sync {
+    /// Current Unix timestamp in seconds
+    fn now(&self) -> Timestamp;
+}
+
+/// Shared handle to a clock
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The system wall clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("System time should be after Unix epoch")
+            .as_secs()
+    }
+}
+
+/// Shared handle to the system clock
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// A clock that only moves when told to
+///
+/// Clones share the same time.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    now: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    /// Creates a clock stopped at `now`
+    pub fn new(now: Timestamp) -> Self {
+        Self {
+            now: Arc::new(AtomicU64::new(now)),
+        }
+    }
+
+    /// Sets the current time
+    pub fn set(&self, now: Timestamp) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+
+    /// Moves the clock forward
+    pub fn advance(&self, secs: u64) {
+        self.now.fetch_add(secs, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Timestamp {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_shared_between_clones() {
+        let clock = ManualClock::new(100);
+        let shared = clock.clone();
+
+        clock.advance(5);
+        assert_eq!(shared.now(), 105);
+
+        shared.set(7);
+        assert_eq!(clock.now(), 7);
+    }
+
+    #[test]
+    fn test_system_clock_is_recent() {
+        // 2023-01-01
+        assert!(SystemClock.now() > 1_672_531_200);
+    }
+}
@@ -0,0 +1,201 @@
+// src/consensus.rs
+
+//! Validator signing of PoS snapshots.
+//!
+//! A snapshot is only accepted once validators holding at least two thirds
+//! of the total stake have signed it. Signatures are collected into an
+//! [`AggregateSignature`] indexed by position in the [`ValidatorSet`], and
+//! every contribution is verified before its stake is counted.
+
+use crate::errors::{NyxError, Result};
+use crate::finality::PosSnapshot;
+use nyx_crypto::multisig::{self, AggregateSignature};
+use serde::{Deserialize, Serialize};
+
+/// Numerator of the stake fraction required to accept a snapshot
+pub const QUORUM_NUMERATOR: u64 = 2;
+
+/// Denominator of the stake fraction required to accept a snapshot
+pub const QUORUM_DENOMINATOR: u64 = 3;
+
+/// A staked validator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validator {
+    /// Ed25519 public key used to sign snapshots
+    pub public_key: Vec<u8>,
+
+    /// Bonded stake
+    pub stake: u64,
+}
+
+/// Validators eligible to sign snapshots, in signer-index order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorSet {
+    validators: Vec<Validator>,
+    public_keys: Vec<Vec<u8>>,
+    total_stake: u64,
+}
+
+impl ValidatorSet {
+    /// Creates a validator set
+    ///
+    /// # Errors
+    /// - `ConsensusError` if the set is empty, has no stake, or the total
+    ///   stake overflows
+    pub fn new(validators: Vec<Validator>) -> Result<Self> {
+        let total_stake = validators.iter()
+            .try_fold(0u64, |total, v| total.checked_add(v.stake))
+            .ok_or_else(|| NyxError::ConsensusError("Total stake overflows".to_string()))?;
+
+        if total_stake == 0 {
+            return Err(NyxError::ConsensusError("Validator set has no stake".to_string()));
+        }
+
+        let public_keys = validators.iter().map(|v| v.public_key.clone()).collect();
+        Ok(Self { validators, public_keys, total_stake })
+    }
+
+    /// Gets the validators in signer-index order
+    pub fn validators(&self) -> &[Validator] {
+        &self.validators
+    }
+
+    /// Returns the total bonded stake
+    pub fn total_stake(&self) -> u64 {
+        self.total_stake
+    }
+
+    /// Checks whether `stake` meets the two-thirds quorum
+    pub fn has_quorum(&self, stake: u64) -> bool {
+        stake as u128 * QUORUM_DENOMINATOR as u128
+            >= self.total_stake as u128 * QUORUM_NUMERATOR as u128
+    }
+
+    /// Verifies a signed snapshot
+    ///
+    /// # Errors
+    /// - `CryptoError` if any signature is invalid or from an unknown signer
+    /// - `ConsensusError` if the signers hold less than two thirds of the stake
+    ///
+    /// # Returns
+    /// Stake that signed the snapshot
+    pub fn verify(&self, signed: &SignedSnapshot) -> Result<u64> {
+        let signers = multisig::verify_aggregate(
+            &signed.snapshot.signing_hash(),
+            &signed.signature,
+            &self.public_keys,
+        )
+        .map_err(|e| NyxError::CryptoError(format!("{}", e)))?;
+
+        let stake: u64 = signers.iter()
+            .map(|&i| self.validators[i as usize].stake)
+            .sum();
+
+        if !self.has_quorum(stake) {
+            return Err(NyxError::ConsensusError(format!(
+                "Snapshot {} signed by {} of {} stake, need at least {}/{}",
+                signed.snapshot.number, stake, self.total_stake,
+                QUORUM_NUMERATOR, QUORUM_DENOMINATOR
+            )));
+        }
+
+        Ok(stake)
+    }
+}
+
+/// A snapshot with its validator signatures
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedSnapshot {
+    /// The snapshot
+    pub snapshot: PosSnapshot,
+
+    /// Validator signatures over [`PosSnapshot::signing_hash`]
+    pub signature: AggregateSignature,
+}
+
+impl SignedSnapshot {
+    /// Wraps a snapshot with no signatures yet
+    pub fn new(snapshot: PosSnapshot) -> Self {
+        Self { snapshot, signature: AggregateSignature::new() }
+    }
+
+    /// Adds the signature of the validator at `index`, made with its
+    /// Ed25519 key seed
+    ///
+    /// # Errors
+    /// - `CryptoError` if the validator already signed
+    pub fn sign(&mut self, index: u32, seed: &[u8; 32]) -> Result<()> {
+        self.signature.sign(&self.snapshot.signing_hash(), index, seed)
+            .map_err(|e| NyxError::CryptoError(format!("{}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_crypto::keys::ed25519_public_key;
+
+    fn validators(stakes: &[u64]) -> (ValidatorSet, Vec<[u8; 32]>) {
+        let keys: Vec<[u8; 32]> = (0..stakes.len()).map(|i| [i as u8; 32]).collect();
+        let set = ValidatorSet::new(
+            keys.iter().zip(stakes)
+                .map(|(seed, &stake)| Validator { public_key: ed25519_public_key(seed).to_vec(), stake })
+                .collect(),
+        )
+        .unwrap();
+        (set, keys)
+    }
+
+    fn signed_by(keys: &[[u8; 32]], signers: &[u32]) -> SignedSnapshot {
+        let mut signed = SignedSnapshot::new(PosSnapshot { number: 1, anchors: vec![[1u8; 32]] });
+        for &i in signers {
+            signed.sign(i, &keys[i as usize]).unwrap();
+        }
+        signed
+    }
+
+    #[test]
+    fn test_quorum_by_stake() {
+        let (set, keys) = validators(&[50, 20, 20, 10]);
+
+        assert_eq!(set.verify(&signed_by(&keys, &[0, 1])).unwrap(), 70);
+        assert!(matches!(set.verify(&signed_by(&keys, &[1, 2, 3])), Err(NyxError::ConsensusError(_))));
+        assert!(matches!(set.verify(&signed_by(&keys, &[])), Err(NyxError::ConsensusError(_))));
+
+        // Exactly two thirds is enough
+        let (set, keys) = validators(&[1, 1, 1]);
+        assert_eq!(set.verify(&signed_by(&keys, &[0, 2])).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_tampered_snapshot_rejected() {
+        let (set, keys) = validators(&[1, 1, 1]);
+        let mut signed = signed_by(&keys, &[0, 1, 2]);
+        signed.snapshot.anchors.push([2u8; 32]);
+
+        assert!(matches!(set.verify(&signed), Err(NyxError::CryptoError(_))));
+    }
+
+    #[test]
+    fn test_signatures_without_validator_keys_rejected() {
+        let (set, _) = validators(&[1, 1, 1]);
+        let outsider = [9u8; 32];
+
+        // Every validator "signed" by a key that is not theirs
+        let mut signed = SignedSnapshot::new(PosSnapshot { number: 1, anchors: vec![[1u8; 32]] });
+        for i in 0..3 {
+            signed.sign(i, &outsider).unwrap();
+        }
+        assert!(matches!(set.verify(&signed), Err(NyxError::CryptoError(_))));
+    }
+
+    #[test]
+    fn test_invalid_validator_set() {
+        assert!(ValidatorSet::new(vec![]).is_err());
+        assert!(ValidatorSet::new(vec![
+            Validator { public_key: vec![], stake: u64::MAX },
+            Validator { public_key: vec![], stake: 1 },
+        ])
+        .is_err());
+    }
+}
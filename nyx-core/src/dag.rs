@@ -6,14 +6,40 @@
 //! - Transaction confirmation scoring
 //! - Conflict resolution
 //! - Finality determination
+//!
+//! All DAG state lives in sharded concurrent maps, so a `DagProcessor` is a
+//! cheap handle that can be cloned across tasks and used without an outer
+//! lock. Inserts touching different transactions only contend on the shard
+//! they hash to.
 
 use crate::errors::{NyxError, Result};
 use crate::types::Hash;
-use crate::transaction::Transaction;
-use crate::storage::MemoryStorage;
-use crate::{CONFIRMATION_THRESHOLD, SCORE_DECAY_FACTOR};
-use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, RwLock};
+use crate::network::NetworkId;
+use crate::params::{Genesis, ProtocolParams};
+use crate::clock::{self, SharedClock};
+use crate::transaction::{Transaction, TxInput, TxOutput};
+use crate::balance;
+use crate::graph::{DagGraph, GraphNode, GraphRange, MAX_GRAPH_NODES};
+use crate::metrics::{self, ConfirmationEstimate, DagMetrics, MetricsSnapshot, ScorePercentiles, TipSample};
+use crate::snapshot::{DagSnapshot, SnapshotEntry};
+use crate::storage::{MemoryStorage, WriteBatch};
+use crate::verification::{self, VerifyMode};
+use crate::versioning::{ValidationContext, VersionRegistry};
+use crate::weight_index::WeightIndex;
+use dashmap::{DashMap, DashSet};
+use nyx_crypto::Entropy;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+/// Number of generations of ancestors a new transaction adds its weight to
+///
+/// Bounds the work of every insert. Ancestors further back are buried deep
+/// enough that their cumulative weights no longer matter, and stop growing
+/// like finalized ones.
+pub const WEIGHT_DEPTH: usize = 64;
 
 /// Represents the state of a transaction in the DAG
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -28,6 +54,43 @@ pub enum TxState {
     Conflicted,
 }
 
+/// Notification emitted when the DAG changes
+#[derive(Debug, Clone, PartialEq)]
+pub enum DagEvent {
+    /// A transaction was accepted into the DAG
+    TransactionAdded {
+        /// Transaction hash
+        hash: Hash,
+        /// The accepted transaction (shared between subscribers)
+        tx: Arc<Transaction>,
+    },
+
+    /// A transaction reached the confirmation threshold
+    ///
+    /// Sent after the transaction's `TransactionAdded`. Transactions that
+    /// are finalized without being confirmed first skip this event.
+    TransactionConfirmed {
+        /// Transaction hash
+        hash: Hash,
+    },
+
+    /// A transaction was finalized by a PoS snapshot
+    TransactionFinalized {
+        /// Transaction hash
+        hash: Hash,
+    },
+}
+
+/// Result of adding a batch of transactions
+#[derive(Debug, Default)]
+pub struct BatchOutcome {
+    /// Hashes of the added transactions, parents before children
+    pub added: Vec<Hash>,
+
+    /// Hashes of the rejected transactions with the reason
+    pub rejected: Vec<(Hash, NyxError)>,
+}
+
 /// DAG processor managing the transaction graph
 #[derive(Clone)]
 pub struct DagProcessor {
@@ -35,31 +98,150 @@ pub struct DagProcessor {
     storage: MemoryStorage,
 
     /// Transaction confirmation scores
-    scores: Arc<RwLock<HashMap<Hash, f64>>>,
+    scores: Arc<DashMap<Hash, f64>>,
 
     /// Transaction states
-    states: Arc<RwLock<HashMap<Hash, TxState>>>,
+    states: Arc<DashMap<Hash, TxState>>,
 
     /// Children map: tx_hash -> set of children hashes
-    children: Arc<RwLock<HashMap<Hash, HashSet<Hash>>>>,
+    children: Arc<DashMap<Hash, HashSet<Hash>>>,
+
+    /// Cumulative weights, kept up to date as transactions are added
+    weights: Arc<DashMap<Hash, u64>>,
+
+    /// Current tips (unconfirmed transactions with no children), indexed
+    /// by selection weight exp(score × tip_selection_alpha)
+    tips: Arc<Mutex<WeightIndex>>,
+
+    /// Key images of every accepted input
+    key_images: Arc<DashSet<[u8; 32]>>,
+
+    /// Outputs spent by every accepted input, as (transaction, index)
+    spent_outputs: Arc<DashSet<(Hash, u32)>>,
+
+    /// Per-version validation rules
+    versions: Arc<VersionRegistry>,
+
+    /// Network whose transactions are accepted
+    network: NetworkId,
 
-    /// Current tips (unconfirmed transactions with no children)
-    tips: Arc<RwLock<HashSet<Hash>>>,
+    /// Consensus parameters of the network
+    params: Arc<ProtocolParams>,
+
+    /// Randomness for tip sampling
+    entropy: Entropy,
+
+    /// Time source for timestamp checks and minted transactions
+    clock: SharedClock,
+
+    /// Event subscribers
+    subscribers: Arc<Mutex<Vec<Sender<DagEvent>>>>,
+
+    /// Throughput, latency, tip and score metrics
+    metrics: Arc<DagMetrics>,
 }
 
 impl DagProcessor {
     /// Creates a new DAG processor with the given storage
     pub fn new(storage: MemoryStorage) -> Self {
+        Self::with_version_registry(storage, VersionRegistry::new())
+    }
+
+    /// Creates a new DAG processor with custom version rules
+    ///
+    /// Used to schedule protocol upgrades by registering future transaction
+    /// versions with their activation points.
+    pub fn with_version_registry(storage: MemoryStorage, versions: VersionRegistry) -> Self {
         Self {
             storage,
-            scores: Arc::new(RwLock::new(HashMap::new())),
-            states: Arc::new(RwLock::new(HashMap::new())),
-            children: Arc::new(RwLock::new(HashMap::new())),
-            tips: Arc::new(RwLock::new(HashSet::new())),
+            scores: Arc::new(DashMap::new()),
+            states: Arc::new(DashMap::new()),
+            children: Arc::new(DashMap::new()),
+            weights: Arc::new(DashMap::new()),
+            tips: Arc::new(Mutex::new(WeightIndex::new())),
+            key_images: Arc::new(DashSet::new()),
+            spent_outputs: Arc::new(DashSet::new()),
+            versions: Arc::new(versions),
+            network: NetworkId::Mainnet,
+            params: Arc::new(ProtocolParams::for_network(NetworkId::Mainnet)),
+            entropy: Entropy::os(),
+            clock: clock::system_clock(),
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+            metrics: Arc::new(DagMetrics::new()),
         }
     }
 
-    /// Adds a transaction to the DAG
+    /// Sets the network whose transactions are accepted, with its built-in
+    /// protocol parameters
+    ///
+    /// Transactions for any other network are rejected.
+    pub fn with_network(mut self, network: NetworkId) -> Self {
+        self.network = network;
+        self.params = Arc::new(ProtocolParams::for_network(network));
+        self
+    }
+
+    /// Sets the network and protocol parameters from a genesis
+    pub fn with_genesis(mut self, genesis: &Genesis) -> Self {
+        self.network = genesis.network;
+        self.params = Arc::new(genesis.params.clone());
+        self
+    }
+
+    /// Sets the protocol parameters
+    ///
+    /// Must be called before any transaction is added: scores and tip
+    /// weights already computed are not recalculated.
+    pub fn with_params(mut self, params: ProtocolParams) -> Self {
+        self.params = Arc::new(params);
+        self
+    }
+
+    /// Gets the network whose transactions are accepted
+    pub fn network(&self) -> NetworkId {
+        self.network
+    }
+
+    /// Gets the protocol parameters
+    pub fn params(&self) -> &ProtocolParams {
+        &self.params
+    }
+
+    /// Sets the randomness used for tip sampling
+    ///
+    /// A seeded [`Entropy`] makes tip selection reproducible.
+    pub fn with_entropy(mut self, entropy: Entropy) -> Self {
+        self.entropy = entropy;
+        self
+    }
+
+    /// Gets the randomness used for tip sampling
+    pub fn entropy(&self) -> &Entropy {
+        &self.entropy
+    }
+
+    /// Sets the time source
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Gets the time source
+    pub fn clock(&self) -> &SharedClock {
+        &self.clock
+    }
+
+    /// Gets the version rules used by this processor
+    pub fn version_registry(&self) -> &VersionRegistry {
+        &self.versions
+    }
+
+    /// Gets the storage backend
+    pub fn storage(&self) -> &MemoryStorage {
+        &self.storage
+    }
+
+    /// Adds a transaction to the DAG after full verification
     ///
     /// # Arguments
     /// * `tx` - The transaction to add
@@ -67,68 +249,395 @@ impl DagProcessor {
     /// # Returns
     /// Transaction hash if successful
     pub fn add_transaction(&self, tx: Transaction) -> Result<Hash> {
-        // Validate transaction structure
-        if !tx.validate_structure() {
-            return Err(NyxError::InvalidTransaction(
-                "Invalid transaction structure".to_string()
-            ));
+        self.add_transaction_with_mode(tx, VerifyMode::Full)
+    }
+
+    /// Adds a transaction to the DAG using the given verification mode
+    ///
+    /// `VerifyMode::HeadersOnly` skips signature, range proof and balance
+    /// checks and is only meant for replaying transactions that were already
+    /// fully verified (e.g. during sync from a trusted checkpoint).
+    ///
+    /// On networks that allow minting, transactions without inputs are
+    /// accepted as minted transactions relayed by peers.
+    pub fn add_transaction_with_mode(&self, tx: Transaction, mode: VerifyMode) -> Result<Hash> {
+        let tx_hash = self.insert(&tx, mode, None)?;
+
+        // Update confirmation scores for ancestors
+        let mut confirmed = Vec::new();
+        if !is_minted(&tx) {
+            self.update_scores(&tx.references, &mut confirmed);
         }
 
-        // Check that parent transactions exist
-        for parent_hash in &tx.references {
-            if !self.storage.has_transaction(parent_hash)? {
-                return Err(NyxError::InvalidParent(
-                    format!("Parent transaction not found: {:?}", parent_hash)
-                ));
+        self.emit_added(tx_hash, tx);
+        self.emit_confirmed(confirmed);
+
+        Ok(tx_hash)
+    }
+
+    /// Adds a batch of transactions after full verification
+    ///
+    /// See [`add_transactions_batch_with_mode`](Self::add_transactions_batch_with_mode).
+    pub fn add_transactions_batch(&self, txs: Vec<Transaction>) -> Result<BatchOutcome> {
+        self.add_transactions_batch_with_mode(txs, VerifyMode::Full)
+    }
+
+    /// Adds a batch of transactions, such as a sync response, in one pass
+    ///
+    /// The batch may arrive in any order: it is sorted so parents come
+    /// before their children, and a transaction may reference parents
+    /// earlier in the same batch. Each transaction is verified and linked
+    /// as by [`add_transaction_with_mode`](Self::add_transaction_with_mode),
+    /// but ancestor scores are recomputed once at the end, children before
+    /// parents, instead of after every insert.
+    ///
+    /// A rejected transaction does not abort the batch; its descendants in
+    /// the batch are rejected for the missing parent. The added
+    /// transactions are written to storage as one [`WriteBatch`].
+    ///
+    /// # Errors
+    /// - `StorageError` if the write batch cannot be flushed; the
+    ///   transactions stay added but may not survive a crash
+    pub fn add_transactions_batch_with_mode(&self, txs: Vec<Transaction>, mode: VerifyMode) -> Result<BatchOutcome> {
+        let mut outcome = BatchOutcome::default();
+        let (ordered, duplicates) = topological_order(txs);
+        for (hash, _) in duplicates {
+            outcome.rejected.push((hash, NyxError::DagError("Duplicate transaction in batch".to_string())));
+        }
+
+        let mut batch = self.storage.begin_batch();
+        let mut inserted = Vec::with_capacity(ordered.len());
+        for (hash, tx) in ordered {
+            match self.insert(&tx, mode, Some(&mut batch)) {
+                Ok(_) => inserted.push((hash, tx)),
+                Err(e) => outcome.rejected.push((hash, e)),
             }
         }
 
-        // Store transaction
-        let tx_hash = self.storage.store_transaction(tx.clone())?;
+        // Parents of later (deeper) transactions first, so each score is
+        // computed from its children's final scores
+        let mut updated = HashSet::new();
+        let mut confirmed = Vec::new();
+        for (_, tx) in inserted.iter().rev() {
+            if is_minted(tx) {
+                continue;
+            }
+            for parent_hash in &tx.references {
+                if updated.insert(*parent_hash) {
+                    self.update_score_recursive(parent_hash, &mut confirmed);
+                }
+            }
+        }
 
-        // Initialize score and state
-        {
-            let mut scores = self.scores.write()
-                .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
-            scores.insert(tx_hash, 1.0);
+        let committed = batch.commit();
+
+        for (hash, tx) in inserted {
+            self.emit_added(hash, tx);
+            outcome.added.push(hash);
+        }
+        self.emit_confirmed(confirmed);
+
+        committed.map(|_| outcome)
+    }
+
+    /// Checks a non-minted transaction against the consensus rules that do
+    /// not depend on other pending spends
+    ///
+    /// Covers the version rules, parents and timestamps and, in full mode,
+    /// ring signatures and members, range proofs, unlock times, HTLC spends
+    /// and commitment balance. Height-based rules are checked against the
+    /// current DAG height. Key image and output reuse is checked when the
+    /// inputs are reserved.
+    pub(crate) fn validate(&self, tx: &Transaction, mode: VerifyMode) -> Result<()> {
+        // Validate against the rules of the transaction's version
+        let ctx = ValidationContext {
+            height: (self.storage.transaction_count()? + self.storage.pruned_count()) as u64,
+            timestamp: tx.timestamp,
+        };
+        self.versions.validate(tx, &ctx)?;
+
+        // Check that parent transactions exist, possibly pruned
+        let mut parent_timestamps = Vec::with_capacity(tx.references.len());
+        for parent_hash in &tx.references {
+            let timestamp = self.storage.get_timestamp(parent_hash).map_err(|_| {
+                NyxError::InvalidParent(format!("Parent transaction not found: {:?}", parent_hash))
+            })?;
+            parent_timestamps.push(timestamp);
+        }
+
+        verification::verify_timestamp(tx, &parent_timestamps, self.clock.now())?;
+
+        if mode.is_full() {
+            verification::verify_ring_signature(tx)?;
+            verification::verify_ring_members(
+                tx,
+                |index| self.storage.get_output_by_index(index).ok().map(|output| output.stealth_address),
+                |prev_tx, index| {
+                    let first = self.storage.first_output_index(prev_tx).ok().flatten()?;
+                    let spent = first + u64::from(index);
+                    let location = self.storage.output_location(spent).ok().flatten()?;
+                    (location == (*prev_tx, index)).then_some(spent)
+                },
+            )?;
+            verification::verify_range_proofs(tx)?;
+
+            let spent = balance::resolve_spent_outputs(&self.storage, tx)?;
+            verification::verify_unlock_times(&spent, &ctx)?;
+            verification::verify_htlc_spends(tx, &spent, &ctx)?;
+
+            // Check that input and output commitments balance
+            let input_commitments: Vec<Vec<u8>> = spent.into_iter()
+                .map(|output| output.amount_commitment)
+                .collect();
+            balance::verify_balance(tx, &input_commitments, balance::LAYER1_FEE)?;
+        }
+
+        Ok(())
+    }
+
+    /// Verifies, stores and links a transaction without updating ancestor
+    /// scores or notifying subscribers
+    ///
+    /// Writes go to `batch` if given, or straight to storage.
+    fn insert(&self, tx: &Transaction, mode: VerifyMode, batch: Option<&mut WriteBatch>) -> Result<Hash> {
+        if tx.network != self.network {
+            return Err(NyxError::InvalidTransaction(format!(
+                "Transaction is for {}, this node is on {}",
+                tx.network, self.network
+            )));
         }
+        self.params.limits.check_transaction(tx)?;
 
-        {
-            let mut states = self.states.write()
-                .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
-            states.insert(tx_hash, TxState::Pending);
+        if is_minted(tx) && self.network.allows_minting() {
+            return self.insert_minted(tx, batch);
         }
 
+        self.validate(tx, mode)?;
+
+        // Reserve key images and spent outputs before storing so a
+        // concurrent double-spend cannot slip in between the check and the
+        // insert
+        self.reserve_inputs(tx)?;
+
+        // Store transaction
+        let tx_hash = match self.store(tx, batch) {
+            Ok(hash) => hash,
+            Err(e) => {
+                self.release_inputs(&tx.inputs);
+                return Err(e);
+            }
+        };
+
+        // Initialize score and state
+        self.set_score(tx_hash, 1.0);
+        self.init_state(tx_hash);
+
         // Update parent-child relationships
-        self.update_children(&tx_hash, &tx.references)?;
+        self.update_children(&tx_hash, &tx.references);
+        self.add_weight(tx_hash, &tx.references);
 
         // Update tips
         self.update_tips(&tx_hash, &tx.references)?;
 
-        // Update confirmation scores for ancestors
-        self.update_scores(&tx_hash)?;
+        Ok(tx_hash)
+    }
+
+    /// Mints a transaction paying the given outputs out of thin air
+    ///
+    /// Minted transactions have no inputs and reference genesis placeholders,
+    /// so they skip parent and balance checks. Only networks that allow
+    /// minting (regtest) accept them.
+    ///
+    /// # Errors
+    /// - `InvalidTransaction` if the network does not allow minting or there
+    ///   are no outputs
+    pub fn mint(&self, outputs: Vec<TxOutput>) -> Result<Hash> {
+        if !self.network.allows_minting() {
+            return Err(NyxError::InvalidTransaction(format!(
+                "Minting is not allowed on {}",
+                self.network
+            )));
+        }
+
+        let mut tx = Transaction::new(
+            Vec::new(),
+            outputs,
+            Vec::new(),
+            Vec::new(),
+            [0u8; 32],
+            [0u8; 32],
+        )
+        .with_network(self.network);
+        tx.timestamp = self.clock.now();
+
+        self.add_minted(tx)
+    }
+
+    /// Stores a minted transaction as a new tip and notifies subscribers
+    fn add_minted(&self, tx: Transaction) -> Result<Hash> {
+        let tx_hash = self.insert_minted(&tx, None)?;
+        self.emit_added(tx_hash, tx);
+
+        Ok(tx_hash)
+    }
+
+    /// Stores a minted transaction as a new tip
+    fn insert_minted(&self, tx: &Transaction, batch: Option<&mut WriteBatch>) -> Result<Hash> {
+        if tx.outputs.is_empty() {
+            return Err(NyxError::InvalidTransaction("Nothing to mint".to_string()));
+        }
+
+        let tx_hash = self.store(tx, batch)?;
+        self.set_score(tx_hash, 1.0);
+        self.init_state(tx_hash);
+        self.add_weight(tx_hash, &tx.references);
+        let mut tips = self.tips.lock()
+            .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
+        tips.insert(tx_hash, self.tip_weight(1.0));
+        self.metrics.record_tips(tips.len(), self.clock.now());
 
         Ok(tx_hash)
     }
 
+    /// Stores a transaction in `batch` if given, or straight to storage
+    fn store(&self, tx: &Transaction, batch: Option<&mut WriteBatch>) -> Result<Hash> {
+        match batch {
+            Some(batch) => batch.store_transaction(tx.clone()),
+            None => self.storage.store_transaction(tx.clone()),
+        }
+    }
+
+    /// Sets a transaction's score, keeping the score metrics in step
+    fn set_score(&self, tx_hash: Hash, score: f64) {
+        let old = self.scores.insert(tx_hash, score);
+        self.metrics.record_score(old, score);
+    }
+
+    /// Sets the state of a newly added transaction and records it in the
+    /// metrics
+    fn init_state(&self, tx_hash: Hash) {
+        let state = self.initial_state();
+        self.states.insert(tx_hash, state);
+        self.metrics.record_added(tx_hash, self.clock.now(), state != TxState::Pending);
+    }
+
+    /// State of a newly added transaction with the base score of 1
+    fn initial_state(&self) -> TxState {
+        if 1.0 >= self.params.confirmation_threshold {
+            TxState::Confirmed
+        } else {
+            TxState::Pending
+        }
+    }
+
+    /// Adds a transaction without blocking the async runtime
+    ///
+    /// Verification is CPU-bound, so it runs on tokio's blocking pool.
+    #[cfg(feature = "async")]
+    pub async fn add_transaction_async(&self, tx: Transaction, mode: VerifyMode) -> Result<Hash> {
+        self.query(move |dag| dag.add_transaction_with_mode(tx, mode)).await
+    }
+
+    /// Adds a batch of transactions without blocking the async runtime
+    #[cfg(feature = "async")]
+    pub async fn add_transactions_batch_async(&self, txs: Vec<Transaction>, mode: VerifyMode) -> Result<BatchOutcome> {
+        self.query(move |dag| dag.add_transactions_batch_with_mode(txs, mode)).await
+    }
+
+    /// Subscribes to DAG events
+    ///
+    /// Events are buffered until received; dropping the receiver
+    /// unsubscribes.
+    pub fn subscribe(&self) -> Receiver<DagEvent> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(tx);
+        }
+        rx
+    }
+
+    /// Notifies subscribers of an added transaction, and of its
+    /// confirmation if it was confirmed on arrival
+    fn emit_added(&self, hash: Hash, tx: Transaction) {
+        self.emit(DagEvent::TransactionAdded { hash, tx: Arc::new(tx) });
+        if self.initial_state() == TxState::Confirmed {
+            self.emit(DagEvent::TransactionConfirmed { hash });
+        }
+    }
+
+    /// Notifies subscribers of newly confirmed transactions
+    fn emit_confirmed(&self, hashes: Vec<Hash>) {
+        for hash in hashes {
+            self.emit(DagEvent::TransactionConfirmed { hash });
+        }
+    }
+
+    /// Sends an event to all subscribers, dropping disconnected ones
+    fn emit(&self, event: DagEvent) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        }
+    }
+
+    /// Checks whether a key image has already been spent in the DAG
+    pub fn is_key_image_spent(&self, key_image: &[u8; 32]) -> Result<bool> {
+        Ok(self.key_images.contains(key_image))
+    }
+
+    /// Checks whether output `index` of `tx_hash` has already been spent
+    /// in the DAG
+    pub fn is_output_spent(&self, tx_hash: &Hash, index: u32) -> Result<bool> {
+        Ok(self.spent_outputs.contains(&(*tx_hash, index)))
+    }
+
+    /// Atomically claims a transaction's key images and spent outputs
+    fn reserve_inputs(&self, tx: &Transaction) -> Result<()> {
+        verification::verify_key_images_unique(tx, |ki| self.key_images.contains(ki))?;
+        verification::verify_outputs_unspent(tx, |prev_tx, index| {
+            self.spent_outputs.contains(&(*prev_tx, index))
+        })?;
+
+        for (i, input) in tx.inputs.iter().enumerate() {
+            let conflict = if !self.key_images.insert(input.key_image) {
+                Some(format!("Key image {}", hex::encode(input.key_image)))
+            } else if !self.spent_outputs.insert((input.prev_tx, input.index)) {
+                self.key_images.remove(&input.key_image);
+                Some(format!("Output {}:{}", hex::encode(input.prev_tx), input.index))
+            } else {
+                None
+            };
+
+            if let Some(conflict) = conflict {
+                // Lost a race with a concurrent insert; undo our claims
+                self.release_inputs(&tx.inputs[..i]);
+                return Err(NyxError::DoubleSpend(format!("{} already spent", conflict)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Releases the claims of inputs reserved for a transaction that
+    /// failed to store
+    fn release_inputs(&self, inputs: &[TxInput]) {
+        for input in inputs {
+            self.key_images.remove(&input.key_image);
+            self.spent_outputs.remove(&(input.prev_tx, input.index));
+        }
+    }
+
     /// Gets the confirmation score of a transaction
     ///
     /// Score calculation from whitepaper:
     /// Score(T) = 1 + Σ(Score(Di) × decay_factor)
     /// where Di are direct descendants
     pub fn get_score(&self, tx_hash: &Hash) -> Result<f64> {
-        let scores = self.scores.read()
-            .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
-
-        Ok(*scores.get(tx_hash).unwrap_or(&0.0))
+        Ok(self.scores.get(tx_hash).map_or(0.0, |s| *s))
     }
 
     /// Gets the state of a transaction
     pub fn get_state(&self, tx_hash: &Hash) -> Result<TxState> {
-        let states = self.states.read()
-            .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
-
-        Ok(*states.get(tx_hash).unwrap_or(&TxState::Pending))
+        Ok(self.states.get(tx_hash).map_or(TxState::Pending, |s| *s))
     }
 
     /// Checks if a transaction has reached the confirmation threshold
@@ -136,34 +645,87 @@ impl DagProcessor {
         let score = self.get_score(tx_hash)?;
         let state = self.get_state(tx_hash)?;
 
-        Ok(score >= CONFIRMATION_THRESHOLD && state != TxState::Conflicted)
+        Ok(score >= self.params.confirmation_threshold && state != TxState::Conflicted)
     }
 
     /// Gets the current tips (unconfirmed transactions with no children)
     pub fn get_tips(&self) -> Result<Vec<Hash>> {
-        let tips = self.tips.read()
+        let tips = self.tips.lock()
             .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
 
-        Ok(tips.iter().copied().collect())
+        Ok(tips.keys().copied().collect())
     }
 
-    /// Updates parent-child relationships
-    fn update_children(&self, tx_hash: &Hash, parent_hashes: &[Hash; 2]) -> Result<()> {
-        let mut children = self.children.write()
+    /// Adds or removes a tip, weighted by its current score
+    ///
+    /// Used by [`crate::integrity`] to repair the tip set.
+    pub(crate) fn set_tip(&self, tx_hash: &Hash, tip: bool) -> Result<()> {
+        let weight = self.tip_weight(self.get_score(tx_hash)?);
+        let mut tips = self.tips.lock()
+            .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
+        if tip {
+            tips.insert(*tx_hash, weight);
+        } else {
+            tips.remove(tx_hash);
+        }
+        self.metrics.record_tips(tips.len(), self.clock.now());
+
+        Ok(())
+    }
+
+    /// Samples a tip with probability exp(score × tip_alpha) / Σ in O(log n)
+    pub fn sample_tip(&self) -> Result<Option<Hash>> {
+        let tips = self.tips.lock()
             .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
 
+        Ok(tips.sample(&mut self.entropy.clone()))
+    }
+
+    /// Gets the alpha used to weight tips in the sampling index
+    pub fn tip_alpha(&self) -> f64 {
+        self.params.tip_selection_alpha
+    }
+
+    /// Updates parent-child relationships
+    fn update_children(&self, tx_hash: &Hash, parent_hashes: &[Hash; 2]) {
         for parent_hash in parent_hashes {
-            children.entry(*parent_hash)
-                .or_insert_with(HashSet::new)
+            self.children.entry(*parent_hash)
+                .or_default()
                 .insert(*tx_hash);
         }
+    }
 
-        Ok(())
+    /// Gives a new transaction its own weight and adds it to the cumulative
+    /// weight of every unfinalized ancestor up to [`WEIGHT_DEPTH`]
+    /// generations back
+    ///
+    /// The walk also stops at finalized and pruned transactions, so each
+    /// insert visits at most the ancestors within that depth.
+    fn add_weight(&self, tx_hash: Hash, parent_hashes: &[Hash; 2]) {
+        self.weights.insert(tx_hash, 1);
+
+        // Breadth-first, so each ancestor is reached at its nearest depth
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<(Hash, usize)> = parent_hashes.iter().map(|hash| (*hash, 1)).collect();
+        while let Some((hash, depth)) = queue.pop_front() {
+            if !visited.insert(hash)
+                || !self.storage.has_transaction(&hash).unwrap_or(false)
+                || self.states.get(&hash).is_some_and(|state| *state == TxState::Finalized)
+            {
+                continue;
+            }
+
+            *self.weights.entry(hash).or_insert(1) += 1;
+            if depth < WEIGHT_DEPTH {
+                queue.extend(self.get_parents(&hash).unwrap_or_default().into_iter().map(|parent| (parent, depth + 1)));
+            }
+        }
     }
 
     /// Updates the tips set when a new transaction is added
     fn update_tips(&self, tx_hash: &Hash, parent_hashes: &[Hash; 2]) -> Result<()> {
-        let mut tips = self.tips.write()
+        let weight = self.tip_weight(self.get_score(tx_hash)?);
+        let mut tips = self.tips.lock()
             .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
 
         // Remove parents from tips (they now have children)
@@ -172,177 +734,1514 @@ impl DagProcessor {
         }
 
         // Add this transaction as a new tip
-        tips.insert(*tx_hash);
+        tips.insert(*tx_hash, weight);
+        self.metrics.record_tips(tips.len(), self.clock.now());
 
         Ok(())
     }
 
-    /// Recursively updates confirmation scores for ancestors
-    fn update_scores(&self, tx_hash: &Hash) -> Result<()> {
-        let tx = self.storage.get_transaction(tx_hash)?;
+    /// Selection weight of a tip with the given score
+    fn tip_weight(&self, score: f64) -> f64 {
+        (score * self.params.tip_selection_alpha).exp()
+    }
 
-        // Update scores for both parents
-        for parent_hash in &tx.references {
-            self.update_score_recursive(parent_hash)?;
+    /// Updates confirmation scores for the parents of a new transaction,
+    /// collecting newly confirmed parents in `confirmed`
+    fn update_scores(&self, parent_hashes: &[Hash; 2], confirmed: &mut Vec<Hash>) {
+        for parent_hash in parent_hashes {
+            self.update_score_recursive(parent_hash, confirmed);
         }
-
-        Ok(())
     }
 
-    /// Recursively calculates and updates the score for a transaction
-    fn update_score_recursive(&self, tx_hash: &Hash) -> Result<f64> {
+    /// Recursively calculates and updates the score for a transaction,
+    /// adding it to `confirmed` if it just reached the threshold
+    fn update_score_recursive(&self, tx_hash: &Hash, confirmed: &mut Vec<Hash>) -> f64 {
+        // Pruned transactions are final and no longer scored
+        if self.storage.is_pruned(tx_hash) {
+            return 0.0;
+        }
+
         // Base score is 1
         let mut score = 1.0;
 
-        // Get children
-        let children_set = {
-            let children = self.children.read()
-                .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
-            children.get(tx_hash).cloned().unwrap_or_default()
-        };
+        // Get children (cloned so no shard lock is held while reading scores)
+        let children_set = self.children.get(tx_hash)
+            .map(|c| c.clone())
+            .unwrap_or_default();
 
         // Add weighted scores from all children
         for child_hash in children_set {
-            let child_score = self.get_score(&child_hash)?;
-            score += child_score * SCORE_DECAY_FACTOR;
+            let child_score = self.scores.get(&child_hash).map_or(0.0, |s| *s);
+            score += child_score * self.params.score_decay_factor;
         }
 
         // Update stored score
-        {
-            let mut scores = self.scores.write()
-                .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
-            scores.insert(*tx_hash, score);
+        self.set_score(*tx_hash, score);
+        if let Ok(mut tips) = self.tips.lock() {
+            if tips.contains(tx_hash) {
+                tips.insert(*tx_hash, self.tip_weight(score));
+            }
         }
 
         // Update state if threshold reached
-        if score >= CONFIRMATION_THRESHOLD {
-            let mut states = self.states.write()
-                .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
-            if states.get(tx_hash) == Some(&TxState::Pending) {
-                states.insert(*tx_hash, TxState::Confirmed);
+        if score >= self.params.confirmation_threshold {
+            if let Some(mut state) = self.states.get_mut(tx_hash) {
+                if *state == TxState::Pending {
+                    *state = TxState::Confirmed;
+                    self.metrics.record_confirmed(tx_hash, self.clock.now());
+                    confirmed.push(*tx_hash);
+                }
             }
         }
 
-        Ok(score)
+        score
     }
 
-    /// Marks a transaction as finalized (by PoS snapshot)
-    pub fn finalize_transaction(&self, tx_hash: &Hash) -> Result<()> {
-        let mut states = self.states.write()
-            .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
+    /// Gets the parents of a transaction that exist in the DAG
+    ///
+    /// Genesis transactions reference placeholder hashes that are not
+    /// stored, so they have no parents.
+    pub fn get_parents(&self, tx_hash: &Hash) -> Result<Vec<Hash>> {
+        let tx = self.storage.get_transaction_shared(tx_hash)?;
 
-        states.insert(*tx_hash, TxState::Finalized);
-        self.storage.mark_confirmed(tx_hash)?;
+        let mut parents = Vec::with_capacity(tx.references.len());
+        for parent in tx.references {
+            if !parents.contains(&parent) && self.storage.has_transaction(&parent)? {
+                parents.push(parent);
+            }
+        }
 
-        Ok(())
+        Ok(parents)
     }
 
-    /// Returns statistics about the DAG
-    pub fn get_stats(&self) -> Result<DagStats> {
-        let scores = self.scores.read()
-            .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
-        let states = self.states.read()
-            .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
-        let tips = self.tips.read()
-            .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
+    /// Transactions with at least one child link
+    pub(crate) fn linked_parents(&self) -> Vec<Hash> {
+        self.children.iter().map(|entry| *entry.key()).collect()
+    }
 
-        let total = scores.len();
-        let pending = states.values().filter(|s| **s == TxState::Pending).count();
-        let confirmed = states.values().filter(|s| **s == TxState::Confirmed).count();
-        let finalized = states.values().filter(|s| **s == TxState::Finalized).count();
+    /// Links a child to its parent (integrity repair)
+    pub(crate) fn link_child(&self, parent: &Hash, child: &Hash) {
+        self.children.entry(*parent).or_default().insert(*child);
+    }
 
-        Ok(DagStats {
-            total_transactions: total,
-            pending_transactions: pending,
-            confirmed_transactions: confirmed,
-            finalized_transactions: finalized,
-            current_tips: tips.len(),
-        })
+    /// Removes a child link, dropping the parent's entry once empty
+    /// (integrity repair)
+    pub(crate) fn unlink_child(&self, parent: &Hash, child: &Hash) {
+        self.children.remove_if_mut(parent, |_, children| {
+            children.remove(child);
+            children.is_empty()
+        });
     }
-}
 
-/// Statistics about the DAG state
-#[derive(Debug, Clone)]
-pub struct DagStats {
-    /// Total number of transactions
-    pub total_transactions: usize,
-    /// Pending transactions
-    pub pending_transactions: usize,
-    /// Confirmed transactions
-    pub confirmed_transactions: usize,
-    /// Finalized transactions
-    pub finalized_transactions: usize,
-    /// Current number of tips
-    pub current_tips: usize,
-}
+    /// Gets the direct children of a transaction
+    pub fn get_children(&self, tx_hash: &Hash) -> Result<Vec<Hash>> {
+        let mut children: Vec<Hash> = self.children.get(tx_hash)
+            .map(|c| c.iter().copied().collect())
+            .unwrap_or_default();
+        children.sort_unstable();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::transaction::{TxInput, TxOutput};
-    use nyx_crypto::RingSignature;
-
-
-    fn create_test_tx(parent1: Hash, parent2: Hash, nonce: u8) -> Transaction {
-        Transaction::new(
-            vec![TxInput {
-                prev_tx: [nonce; 32],
-                index: 0,
-                key_image: [nonce; 32],
-                ring_indices: vec![],
-            }],
-            vec![TxOutput {
-                stealth_address: vec![nonce],
-                amount_commitment: vec![],
-                range_proof: vec![],
-                ephemeral_pubkey: vec![],
-            }],
-            RingSignature {
-                ring_members: vec![vec![1], vec![2]],
-                signature: vec![nonce],
-                key_image: [0u8; 32],
-            },
-            vec![nonce],
-            parent1,
-            parent2,
-        )
+        Ok(children)
     }
 
-    #[test]
-    fn test_genesis_transaction() {
-        let storage = MemoryStorage::new();
-        let dag = DagProcessor::new(storage.clone());
-
-        // Create two DIFFERENT genesis transactions by using different nonce values
-        let genesis1 = create_test_tx([0u8; 32], [0u8; 32], 1);
-        let genesis2 = create_test_tx([0u8; 32], [0u8; 32], 2);
+    /// Gets the ancestors of a transaction up to `depth` levels back
+    ///
+    /// Results are in breadth-first order (parents first) and exclude `tx_hash`
+    /// itself. Use `usize::MAX` for an unbounded walk.
+    pub fn get_ancestors(&self, tx_hash: &Hash, depth: usize) -> Result<Vec<Hash>> {
+        self.walk(tx_hash, depth, |hash| self.get_parents(hash))
+    }
 
-        let gen1_hash = storage.store_transaction(genesis1.clone()).unwrap();
-        let gen2_hash = storage.store_transaction(genesis2.clone()).unwrap();
+    /// Gets the descendants of a transaction up to `depth` levels forward
+    ///
+    /// Results are in breadth-first order (children first) and exclude
+    /// `tx_hash` itself. Use `usize::MAX` for an unbounded walk.
+    pub fn get_descendants(&self, tx_hash: &Hash, depth: usize) -> Result<Vec<Hash>> {
+        self.walk(tx_hash, depth, |hash| self.get_children(hash))
+    }
 
-        // Verify they have different hashes
-        assert_ne!(gen1_hash, gen2_hash);
+    /// Checks whether `ancestor` is reachable from `descendant` through parent links
+    pub fn is_ancestor(&self, ancestor: &Hash, descendant: &Hash) -> Result<bool> {
+        if ancestor == descendant {
+            return Ok(false);
+        }
 
-        // Now create a transaction referencing both
-        let tx = create_test_tx(gen1_hash, gen2_hash, 3);
-        let tx_hash = dag.add_transaction(tx).unwrap();
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from([*descendant]);
 
-        let score = dag.get_score(&tx_hash).unwrap();
-        assert_eq!(score, 1.0);
+        while let Some(current) = queue.pop_front() {
+            for parent in self.get_parents(&current)? {
+                if parent == *ancestor {
+                    return Ok(true);
+                }
+                if visited.insert(parent) {
+                    queue.push_back(parent);
+                }
+            }
+        }
 
-        let state = dag.get_state(&tx_hash).unwrap();
-        assert_eq!(state, TxState::Pending);
+        Ok(false)
     }
 
-    #[test]
+    /// Returns an iterator over all transactions in topological order
+    ///
+    /// Every transaction is yielded after all of its parents. Ties are broken
+    /// by hash so the order is deterministic for a given DAG.
+    pub fn topological_order(&self) -> Result<TopologicalIter> {
+        let hashes = self.storage.transaction_hashes()?;
+
+        let mut pending_parents = HashMap::with_capacity(hashes.len());
+        let mut roots = Vec::new();
+        for hash in hashes {
+            let parent_count = self.get_parents(&hash)?.len();
+            if parent_count == 0 {
+                roots.push(hash);
+            } else {
+                pending_parents.insert(hash, parent_count);
+            }
+        }
+        roots.sort_unstable();
+
+        Ok(TopologicalIter {
+            dag: self.clone(),
+            queue: roots.into(),
+            pending_parents,
+        })
+    }
+
+    /// Gets the cumulative weight of a transaction
+    ///
+    /// Cumulative weight is the transaction's own weight (1) plus the number
+    /// of transactions that directly or indirectly approve it within
+    /// [`WEIGHT_DEPTH`] generations. Weights are kept up to date on insert
+    /// and stop growing once a transaction is finalized.
+    pub fn cumulative_weight(&self, tx_hash: &Hash) -> Result<u64> {
+        Ok(self.weights.get(tx_hash).map_or(1, |weight| *weight))
+    }
+
+    /// Gets the transactions with no parents in the DAG (genesis)
+    pub fn get_roots(&self) -> Result<Vec<Hash>> {
+        let mut roots = Vec::new();
+        for hash in self.storage.transaction_hashes()? {
+            if self.get_parents(&hash)?.is_empty() {
+                roots.push(hash);
+            }
+        }
+        roots.sort_unstable();
+
+        Ok(roots)
+    }
+
+    /// Gets the most recent finalized transaction (latest snapshot), if any
+    pub fn latest_finalized(&self) -> Result<Option<Hash>> {
+        let mut latest: Option<(u64, Hash)> = None;
+
+        for entry in self.states.iter() {
+            if *entry.value() != TxState::Finalized {
+                continue;
+            }
+            let hash = *entry.key();
+            let timestamp = self.storage.get_transaction_shared(&hash)?.timestamp;
+            if latest.is_none_or(|best| (timestamp, hash) > best) {
+                latest = Some((timestamp, hash));
+            }
+        }
+
+        Ok(latest.map(|(_, hash)| hash))
+    }
+
+    /// Collects a neighborhood of the DAG for visualization
+    ///
+    /// At most [`MAX_GRAPH_NODES`] transactions are included, nearest to
+    /// the center or the tips first. Nodes are ordered parents first.
+    pub fn graph(&self, range: &GraphRange) -> Result<DagGraph> {
+        let mut selected = Vec::new();
+        match *range {
+            GraphRange::Around { center, depth } => {
+                if !self.storage.has_transaction(&center)? {
+                    return Err(NyxError::TransactionNotFound(hex::encode(center)));
+                }
+                selected.push(center);
+                selected.extend(self.get_ancestors(&center, depth)?);
+                selected.extend(self.get_descendants(&center, depth)?);
+            }
+            GraphRange::Tips { depth } => {
+                let mut tips = self.get_tips()?;
+                tips.sort_unstable();
+                selected.extend(&tips);
+                for tip in &tips {
+                    selected.extend(self.get_ancestors(tip, depth)?);
+                }
+            }
+        }
+
+        let mut included = HashSet::new();
+        selected.retain(|hash| included.insert(*hash));
+        selected.truncate(MAX_GRAPH_NODES);
+        let included: HashSet<Hash> = selected.iter().copied().collect();
+
+        // Order parents first within the selection
+        let mut parents = HashMap::with_capacity(selected.len());
+        for hash in &selected {
+            let mut inside = self.get_parents(hash)?;
+            inside.retain(|parent| included.contains(parent));
+            parents.insert(*hash, inside);
+        }
+        let tips: HashSet<Hash> = self.get_tips()?.into_iter().collect();
+        let mut placed = HashSet::with_capacity(selected.len());
+        let mut nodes = Vec::with_capacity(selected.len());
+        selected.sort_unstable();
+        while nodes.len() < selected.len() {
+            for hash in &selected {
+                if placed.contains(hash) || !parents[hash].iter().all(|parent| placed.contains(parent)) {
+                    continue;
+                }
+                placed.insert(*hash);
+                nodes.push(GraphNode {
+                    hash: *hash,
+                    state: self.get_state(hash)?,
+                    score: self.get_score(hash)?,
+                    tip: tips.contains(hash),
+                    parents: parents[hash].clone(),
+                });
+            }
+        }
+
+        Ok(DagGraph {
+            nodes,
+            confirmation_threshold: self.params.confirmation_threshold,
+        })
+    }
+
+    /// Exports a neighborhood of the DAG as Graphviz DOT
+    ///
+    /// Nodes are colored by state and outlined by score; see
+    /// [`crate::graph`].
+    pub fn export_graphviz(&self, range: &GraphRange) -> Result<String> {
+        Ok(self.graph(range)?.to_dot())
+    }
+
+    /// Exports a neighborhood of the DAG as GraphML
+    pub fn export_graphml(&self, range: &GraphRange) -> Result<String> {
+        Ok(self.graph(range)?.to_graphml())
+    }
+
+    /// Breadth-first walk following `next` up to `depth` levels
+    fn walk<F>(&self, start: &Hash, depth: usize, next: F) -> Result<Vec<Hash>>
+    where
+        F: Fn(&Hash) -> Result<Vec<Hash>>,
+    {
+        let mut visited = HashSet::from([*start]);
+        let mut result = Vec::new();
+        let mut frontier = vec![*start];
+
+        for _ in 0..depth {
+            let mut next_frontier = Vec::new();
+            for hash in &frontier {
+                for neighbour in next(hash)? {
+                    if visited.insert(neighbour) {
+                        result.push(neighbour);
+                        next_frontier.push(neighbour);
+                    }
+                }
+            }
+
+            if next_frontier.is_empty() {
+                break;
+            }
+            frontier = next_frontier;
+        }
+
+        Ok(result)
+    }
+
+    /// Captures the DAG in snapshot form
+    pub fn snapshot(&self) -> Result<DagSnapshot> {
+        let mut entries = Vec::new();
+        for hash in self.topological_order()? {
+            entries.push(SnapshotEntry {
+                tx: self.storage.get_transaction(&hash)?,
+                score: self.get_score(&hash)?,
+                state: self.get_state(&hash)?,
+                output_index: self.storage.first_output_index(&hash)?,
+            });
+        }
+
+        let mut tips = self.get_tips()?;
+        tips.sort_unstable();
+
+        Ok(DagSnapshot { entries, tips })
+    }
+
+    /// Writes a binary snapshot of the DAG
+    ///
+    /// Should be taken while no transactions are being inserted, otherwise
+    /// the snapshot may miss concurrent additions.
+    pub fn export_snapshot<W: std::io::Write>(&self, writer: W) -> Result<()> {
+        self.snapshot()?.write_to(writer)
+    }
+
+    /// Restores the DAG from a binary snapshot
+    ///
+    /// The processor must be empty. Snapshot contents are trusted, so only the
+    /// format and DAG shape are checked, not signatures or balances.
+    ///
+    /// # Returns
+    /// Number of transactions imported
+    pub fn import_snapshot<R: std::io::Read>(&self, reader: R) -> Result<usize> {
+        self.restore_snapshot(DagSnapshot::read_from(reader)?)
+    }
+
+    /// Restores the DAG from a decoded snapshot
+    ///
+    /// Transactions are written to storage as one [`WriteBatch`].
+    /// Confirmed transactions are confirmed in the order of their output
+    /// indices, so their outputs keep the global indices they have on the
+    /// source. A rejected snapshot leaves the processor empty, so the import
+    /// can be retried.
+    ///
+    /// # Errors
+    /// - `DagError` if the DAG is not empty, the snapshot is not in
+    ///   topological order, a tip is missing from it, or its output indices
+    ///   cannot be reproduced
+    /// - `DoubleSpend` if two entries spend the same output or key image
+    pub fn restore_snapshot(&self, snapshot: DagSnapshot) -> Result<usize> {
+        if self.storage.transaction_count()? != 0 {
+            return Err(NyxError::DagError(
+                "Cannot import a snapshot into a non-empty DAG".to_string()
+            ));
+        }
+
+        // Check the DAG shape before changing anything, so a rejected
+        // snapshot leaves the processor empty
+        let hashes: Vec<Hash> = snapshot.entries.iter().map(|e| e.tx.id()).collect();
+        let all_hashes: HashSet<Hash> = hashes.iter().copied().collect();
+        let mut seen = HashSet::with_capacity(all_hashes.len());
+        for (entry, tx_hash) in snapshot.entries.iter().zip(&hashes) {
+            // Parents in the snapshot must come first; references to hashes
+            // outside it are genesis placeholders
+            for parent in &entry.tx.references {
+                if all_hashes.contains(parent) && !seen.contains(parent) {
+                    return Err(NyxError::DagError(format!(
+                        "Snapshot is not in topological order at {}",
+                        hex::encode(tx_hash)
+                    )));
+                }
+            }
+            seen.insert(*tx_hash);
+        }
+        if let Some(tip) = snapshot.tips.iter().find(|tip| !all_hashes.contains(*tip)) {
+            return Err(NyxError::DagError(format!(
+                "Snapshot tip {} is not in the snapshot",
+                hex::encode(tip)
+            )));
+        }
+
+        // Claim every input, releasing the claims if any conflict
+        for (i, entry) in snapshot.entries.iter().enumerate() {
+            if let Err(e) = self.reserve_inputs(&entry.tx) {
+                for claimed in &snapshot.entries[..i] {
+                    self.release_inputs(&claimed.tx.inputs);
+                }
+                return Err(e);
+            }
+        }
+        let release_all = |inputs: &[Vec<TxInput>]| {
+            for tx_inputs in inputs {
+                self.release_inputs(tx_inputs);
+            }
+        };
+        let inputs: Vec<Vec<TxInput>> = snapshot.entries.iter().map(|e| e.tx.inputs.clone()).collect();
+
+        // Storage writes are rolled back with the batch if it is dropped
+        let mut batch = self.storage.begin_batch();
+        let mut restored = Vec::with_capacity(hashes.len());
+        let mut confirmed = Vec::new();
+        for (entry, tx_hash) in snapshot.entries.into_iter().zip(hashes) {
+            if entry.state == TxState::Finalized || entry.output_index.is_some() {
+                confirmed.push((entry.output_index, tx_hash));
+            }
+            restored.push((tx_hash, entry.tx.references, entry.score, entry.state));
+            if let Err(e) = batch.store_transaction(entry.tx) {
+                release_all(&inputs);
+                return Err(e);
+            }
+        }
+
+        // Confirm in the source's order, so every output gets the global
+        // index ring indices on the rest of the network refer to
+        confirmed.sort_unstable_by_key(|(output_index, _)| *output_index);
+        for (output_index, tx_hash) in confirmed {
+            let checked = batch.mark_confirmed(&tx_hash).and_then(|_| {
+                let restored = self.storage.first_output_index(&tx_hash)?;
+                if output_index.is_some() && restored != output_index {
+                    return Err(NyxError::DagError(format!(
+                        "Snapshot indexes the outputs of {} from {:?}, restored from {:?}",
+                        hex::encode(tx_hash),
+                        output_index,
+                        restored
+                    )));
+                }
+                Ok(())
+            });
+            if let Err(e) = checked {
+                release_all(&inputs);
+                return Err(e);
+            }
+        }
+        let committed = batch.commit();
+
+        let imported = restored.len();
+        for (tx_hash, references, score, state) in restored {
+            for parent in &references {
+                if all_hashes.contains(parent) {
+                    self.children.entry(*parent).or_default().insert(tx_hash);
+                }
+            }
+            self.set_score(tx_hash, score);
+            self.states.insert(tx_hash, state);
+            self.add_weight(tx_hash, &references);
+        }
+
+        let mut tips = self.tips.lock()
+            .map_err(|e| NyxError::DagError(format!("Lock poisoned: {}", e)))?;
+        for tip in snapshot.tips {
+            tips.insert(tip, self.tip_weight(self.get_score(&tip)?));
+        }
+
+        committed.map(|_| imported)
+    }
+
+    /// Marks a transaction as finalized (by PoS snapshot)
+    pub fn finalize_transaction(&self, tx_hash: &Hash) -> Result<()> {
+        self.states.insert(*tx_hash, TxState::Finalized);
+        self.metrics.record_confirmed(tx_hash, self.clock.now());
+        self.storage.mark_confirmed(tx_hash)?;
+
+        self.emit(DagEvent::TransactionFinalized { hash: *tx_hash });
+
+        Ok(())
+    }
+
+    /// Marks transactions as finalized, writing them to storage as one
+    /// [`WriteBatch`]
+    ///
+    /// Subscribers are notified once the batch is committed.
+    pub fn finalize_transactions(&self, tx_hashes: &[Hash]) -> Result<()> {
+        let mut batch = self.storage.begin_batch();
+        for tx_hash in tx_hashes {
+            batch.mark_confirmed(tx_hash)?;
+        }
+        batch.commit()?;
+
+        let now = self.clock.now();
+        for tx_hash in tx_hashes {
+            self.states.insert(*tx_hash, TxState::Finalized);
+            self.metrics.record_confirmed(tx_hash, now);
+            self.emit(DagEvent::TransactionFinalized { hash: *tx_hash });
+        }
+
+        Ok(())
+    }
+
+    /// Drops finalized transactions from the DAG
+    ///
+    /// Their bodies are removed from storage, keeping only the timestamps
+    /// and outputs later transactions are checked against, and they no
+    /// longer take part in scoring, parent/child links or graph walks.
+    /// Their key images stay recorded so they cannot be spent again.
+    ///
+    /// # Errors
+    /// - `DagError` if a transaction is not finalized
+    ///
+    /// # Returns
+    /// Number of transactions pruned
+    pub fn prune(&self, tx_hashes: &[Hash]) -> Result<usize> {
+        if let Some(hash) = tx_hashes.iter().find(|hash| self.get_state(hash).ok() != Some(TxState::Finalized)) {
+            return Err(NyxError::DagError(format!(
+                "Cannot prune {}, it is not finalized",
+                hex::encode(hash)
+            )));
+        }
+
+        let mut pruned = 0;
+        for tx_hash in tx_hashes {
+            if !self.storage.prune_transaction(tx_hash)? {
+                continue;
+            }
+            // Anything finalized at or before a transaction is pruned with
+            // it, so only links to later children are left to drop
+            self.children.remove(tx_hash);
+            self.weights.remove(tx_hash);
+            if let Some((_, score)) = self.scores.remove(tx_hash) {
+                self.metrics.forget_score(score);
+            }
+            if let Ok(mut tips) = self.tips.lock() {
+                tips.remove(tx_hash);
+            }
+            pruned += 1;
+        }
+
+        Ok(pruned)
+    }
+
+    /// Returns statistics about the DAG
+    pub fn get_stats(&self) -> Result<DagStats> {
+        let mut pending = 0;
+        let mut confirmed = 0;
+        let mut finalized = 0;
+
+        for state in self.states.iter() {
+            match *state {
+                TxState::Pending => pending += 1,
+                TxState::Confirmed => confirmed += 1,
+                TxState::Finalized => finalized += 1,
+                TxState::Conflicted => {}
+            }
+        }
+
+        let metrics = self.metrics();
+
+        Ok(DagStats {
+            total_transactions: self.scores.len(),
+            pending_transactions: pending,
+            confirmed_transactions: confirmed,
+            finalized_transactions: finalized,
+            current_tips: self.get_tips()?.len(),
+            tps: metrics.tps,
+            avg_confirmation_latency_secs: metrics.avg_confirmation_latency_secs,
+            tip_history: metrics.tip_history,
+            score_percentiles: metrics.score_percentiles,
+        })
+    }
+
+    /// Returns the rolling throughput, latency, tip and score metrics
+    ///
+    /// Unlike [`get_stats`](Self::get_stats) this does not count states.
+    pub fn metrics(&self) -> MetricsSnapshot {
+        self.metrics.snapshot(self.clock.now())
+    }
+
+    /// Estimates how long a transaction added now takes to confirm
+    ///
+    /// See [`metrics::estimate_confirmation`].
+    pub fn estimate_confirmation(&self) -> ConfirmationEstimate {
+        metrics::estimate_confirmation(&self.metrics(), &self.params)
+    }
+}
+
+/// Non-blocking queries
+///
+/// Storage reads and graph walks block the calling thread, which stalls
+/// every other task of an async runtime worker once storage is on disk.
+/// These run the blocking call on tokio's blocking pool instead; async
+/// handlers should prefer them over the synchronous methods.
+#[cfg(feature = "async")]
+impl DagProcessor {
+    /// Runs `query` against the DAG on tokio's blocking pool
+    ///
+    /// For anything without a dedicated async method.
+    ///
+    /// # Errors
+    /// - `DagError` if the task panicked, otherwise whatever `query` returns
+    pub async fn query<T, F>(&self, query: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&DagProcessor) -> Result<T> + Send + 'static,
+    {
+        let dag = self.clone();
+        tokio::task::spawn_blocking(move || query(&dag))
+            .await
+            .map_err(|e| NyxError::DagError(format!("DAG task failed: {}", e)))?
+    }
+
+    /// Async [`MemoryStorage::get_transaction`]
+    pub async fn get_transaction_async(&self, tx_hash: Hash) -> Result<Transaction> {
+        self.query(move |dag| dag.storage.get_transaction(&tx_hash)).await
+    }
+
+    /// Async [`MemoryStorage::has_transaction`]
+    pub async fn has_transaction_async(&self, tx_hash: Hash) -> Result<bool> {
+        self.query(move |dag| dag.storage.has_transaction(&tx_hash)).await
+    }
+
+    /// Async [`get_tips`](Self::get_tips)
+    pub async fn get_tips_async(&self) -> Result<Vec<Hash>> {
+        self.query(|dag| dag.get_tips()).await
+    }
+
+    /// Async [`get_parents`](Self::get_parents)
+    pub async fn get_parents_async(&self, tx_hash: Hash) -> Result<Vec<Hash>> {
+        self.query(move |dag| dag.get_parents(&tx_hash)).await
+    }
+
+    /// Async [`get_children`](Self::get_children)
+    pub async fn get_children_async(&self, tx_hash: Hash) -> Result<Vec<Hash>> {
+        self.query(move |dag| dag.get_children(&tx_hash)).await
+    }
+
+    /// Async [`is_key_image_spent`](Self::is_key_image_spent)
+    pub async fn is_key_image_spent_async(&self, key_image: [u8; 32]) -> Result<bool> {
+        self.query(move |dag| dag.is_key_image_spent(&key_image)).await
+    }
+
+    /// Async [`is_output_spent`](Self::is_output_spent)
+    pub async fn is_output_spent_async(&self, tx_hash: Hash, index: u32) -> Result<bool> {
+        self.query(move |dag| dag.is_output_spent(&tx_hash, index)).await
+    }
+
+    /// Async [`get_stats`](Self::get_stats)
+    pub async fn get_stats_async(&self) -> Result<DagStats> {
+        self.query(|dag| dag.get_stats()).await
+    }
+
+    /// Async [`graph`](Self::graph)
+    pub async fn graph_async(&self, range: GraphRange) -> Result<DagGraph> {
+        self.query(move |dag| dag.graph(&range)).await
+    }
+}
+
+/// Iterator over DAG transactions in topological order (Kahn's algorithm)
+pub struct TopologicalIter {
+    dag: DagProcessor,
+    queue: VecDeque<Hash>,
+    pending_parents: HashMap<Hash, usize>,
+}
+
+impl Iterator for TopologicalIter {
+    type Item = Hash;
+
+    fn next(&mut self) -> Option<Hash> {
+        let current = self.queue.pop_front()?;
+
+        for child in self.dag.get_children(&current).unwrap_or_default() {
+            if let Some(remaining) = self.pending_parents.get_mut(&child) {
+                *remaining -= 1;
+                if *remaining == 0 {
+                    self.pending_parents.remove(&child);
+                    self.queue.push_back(child);
+                }
+            }
+        }
+
+        Some(current)
+    }
+}
+
+/// Statistics about the DAG state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DagStats {
+    /// Total number of transactions
+    pub total_transactions: usize,
+    /// Pending transactions
+    pub pending_transactions: usize,
+    /// Confirmed transactions
+    pub confirmed_transactions: usize,
+    /// Finalized transactions
+    pub finalized_transactions: usize,
+    /// Current number of tips
+    pub current_tips: usize,
+    /// Transactions added per second over the metrics window
+    pub tps: f64,
+    /// Mean seconds from added to confirmed over the metrics window
+    pub avg_confirmation_latency_secs: Option<f64>,
+    /// Tip count per second over the metrics window
+    pub tip_history: Vec<TipSample>,
+    /// Approximate distribution of confirmation scores
+    pub score_percentiles: ScorePercentiles,
+}
+
+/// Whether a transaction creates funds instead of spending inputs
+pub(crate) fn is_minted(tx: &Transaction) -> bool {
+    tx.inputs.is_empty()
+}
+
+/// Transaction paired with its hash
+type HashedTx = (Hash, Transaction);
+
+/// Orders a batch so every transaction follows its parents in the batch
+///
+/// Ties keep the input order. Returns the ordered transactions with their
+/// hashes, and any repeated transactions separately.
+fn topological_order(txs: Vec<Transaction>) -> (Vec<HashedTx>, Vec<HashedTx>) {
+    let mut index: HashMap<Hash, usize> = HashMap::with_capacity(txs.len());
+    let mut batch = Vec::with_capacity(txs.len());
+    let mut duplicates = Vec::new();
+    for tx in txs {
+        let hash = tx.id();
+        match index.entry(hash) {
+            Entry::Occupied(_) => duplicates.push((hash, tx)),
+            Entry::Vacant(slot) => {
+                slot.insert(batch.len());
+                batch.push((hash, tx));
+            }
+        }
+    }
+
+    let mut pending = vec![0usize; batch.len()];
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); batch.len()];
+    for (i, (_, tx)) in batch.iter().enumerate() {
+        let mut parents: Vec<usize> = tx.references.iter().filter_map(|p| index.get(p).copied()).collect();
+        parents.dedup();
+        pending[i] = parents.len();
+        for parent in parents {
+            dependents[parent].push(i);
+        }
+    }
+
+    let mut ready: VecDeque<usize> = (0..batch.len()).filter(|&i| pending[i] == 0).collect();
+    let mut order = Vec::with_capacity(batch.len());
+    while let Some(i) = ready.pop_front() {
+        order.push(i);
+        for &child in &dependents[i] {
+            pending[child] -= 1;
+            if pending[child] == 0 {
+                ready.push_back(child);
+            }
+        }
+    }
+
+    let mut slots: Vec<Option<HashedTx>> = batch.into_iter().map(Some).collect();
+    let ordered = order.into_iter().filter_map(|i| slots[i].take()).collect();
+
+    (ordered, duplicates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{
+        funded_key_image, funded_tx, funding_key, funding_tx, test_blinding, test_tx, TestTx, TEST_AMOUNT,
+    };
+    use crate::transaction::{TxOutput, UnlockTime};
+    use crate::{PROTOCOL_VERSION, RING_INDEX_VERSION};
+    use nyx_crypto::{pedersen, range};
+
+    /// Creates an unsigned transaction, which only passes
+    /// [`VerifyMode::HeadersOnly`]
+    fn create_unsigned_tx(parent1: Hash, parent2: Hash, nonce: u8) -> Transaction {
+        test_tx(parent1, parent2, nonce)
+    }
+
+    /// Stores a genesis transaction directly, confirmed so its outputs get
+    /// global indices
+    fn store_genesis(storage: &MemoryStorage, tx: Transaction) -> Hash {
+        let hash = storage.store_transaction(tx).unwrap();
+        storage.mark_confirmed(&hash).unwrap();
+        hash
+    }
+
+    /// Stores [`funding_tx`], then a second genesis transaction
+    fn store_genesis_pair(storage: &MemoryStorage) -> (Hash, Hash) {
+        let gen1_hash = store_genesis(storage, funding_tx());
+        (gen1_hash, store_genesis(storage, test_tx([0u8; 32], [0u8; 32], 2)))
+    }
+
+    #[test]
+    fn test_genesis_transaction() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        // Verify they have different hashes
+        assert_ne!(gen1_hash, gen2_hash);
+
+        // Now create a transaction referencing both
+        let tx = funded_tx(gen1_hash, gen2_hash, 3);
+        let tx_hash = dag.add_transaction(tx).unwrap();
+
+        let score = dag.get_score(&tx_hash).unwrap();
+        assert_eq!(score, 1.0);
+
+        let state = dag.get_state(&tx_hash).unwrap();
+        assert_eq!(state, TxState::Pending);
+    }
+
+    #[test]
+    fn test_unbalanced_transaction_rejected() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        let blinding = test_blinding();
+        let tx = TestTx::new(3)
+            .parents(gen1_hash, gen2_hash)
+            .funded()
+            .amount(pedersen::commit(101, &blinding).to_bytes().to_vec(), range::prove(101, &blinding))
+            .build();
+
+        let result = dag.add_transaction(tx);
+        assert!(matches!(result, Err(NyxError::InvalidTransaction(_))));
+        assert_eq!(storage.transaction_count().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_unknown_version_rejected() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        let mut tx = funded_tx(gen1_hash, gen2_hash, 3);
+        tx.version = 99;
+
+        let result = dag.add_transaction(tx);
+        assert!(matches!(result, Err(NyxError::UnsupportedVersion(_))));
+    }
+
+    #[test]
+    fn test_other_network_rejected() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone()).with_network(NetworkId::Testnet);
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        let tx = funded_tx(gen1_hash, gen2_hash, 3);
+        let result = dag.add_transaction_with_mode(tx.clone(), VerifyMode::HeadersOnly);
+        assert!(matches!(result, Err(NyxError::InvalidTransaction(_))));
+
+        let tx = tx.with_network(NetworkId::Testnet);
+        assert!(dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly).is_ok());
+    }
+
+    #[test]
+    fn test_regtest_mints_and_confirms_instantly() {
+        let storage = MemoryStorage::new();
+        let output = TxOutput {
+            stealth_address: vec![1u8; 32],
+            amount_commitment: vec![],
+            range_proof: vec![],
+            ephemeral_pubkey: vec![],
+            unlock_time: None,
+            htlc: None,
+        };
+
+        let mainnet = DagProcessor::new(storage.clone());
+        assert!(matches!(mainnet.mint(vec![output.clone()]), Err(NyxError::InvalidTransaction(_))));
+
+        let regtest = DagProcessor::new(storage.clone()).with_network(NetworkId::Regtest);
+        assert!(regtest.mint(Vec::new()).is_err());
+
+        let minted = regtest.mint(vec![output]).unwrap();
+        let tx = storage.get_transaction(&minted).unwrap();
+        assert_eq!(tx.network, NetworkId::Regtest);
+        assert!(regtest.is_confirmed(&minted).unwrap());
+        assert!(regtest.get_tips().unwrap().contains(&minted));
+
+        // Peers replicate minted transactions
+        let peer = DagProcessor::new(MemoryStorage::new()).with_network(NetworkId::Regtest);
+        assert_eq!(peer.add_transaction(tx).unwrap(), minted);
+    }
+
+    #[test]
+    fn test_invalid_signature_rejected() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        let tx = create_unsigned_tx(gen1_hash, gen2_hash, 3);
+        let result = dag.add_transaction(tx);
+        assert!(matches!(result, Err(NyxError::InvalidTransaction(_))));
+    }
+
+    #[test]
+    fn test_headers_only_skips_signature() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        let tx = create_unsigned_tx(gen1_hash, gen2_hash, 3);
+        assert!(dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly).is_ok());
+    }
+
+    #[test]
+    fn test_double_spend_rejected() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        dag.add_transaction(funded_tx(gen1_hash, gen2_hash, 3)).unwrap();
+        assert!(dag.is_key_image_spent(&funded_key_image(3)).unwrap());
+
+        // Same key image, different content
+        let result = dag.add_transaction(funded_tx(gen2_hash, gen1_hash, 3));
+        assert!(matches!(result, Err(NyxError::DoubleSpend(_))));
+    }
+
+    #[test]
+    fn test_output_spent_twice_rejected() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+        let a = dag.add_transaction(funded_tx(gen1_hash, gen2_hash, 3)).unwrap();
+        assert!(dag.is_output_spent(&gen1_hash, 3).unwrap());
+
+        // Key 2 signs for output 3, which is in its ring, under a fresh key
+        // image
+        let respend = TestTx::new(2).parents(a, gen2_hash).funded().spends(gen1_hash, 3).build();
+        let result = dag.add_transaction(respend);
+        assert!(matches!(result, Err(NyxError::DoubleSpend(_))));
+        assert!(!dag.is_key_image_spent(&funded_key_image(2)).unwrap());
+        assert_eq!(storage.transaction_count().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_tampered_transaction_rejected() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        // Content changed after signing no longer matches the signature
+        let mut tx = funded_tx(gen1_hash, gen2_hash, 3);
+        tx.extra.push(0);
+        let result = dag.add_transaction(tx);
+        assert!(matches!(result, Err(NyxError::InvalidTransaction(_))));
+        assert!(!dag.is_key_image_spent(&funded_key_image(3)).unwrap());
+    }
+
+    #[test]
+    fn test_ring_members_are_indexed_outputs() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let (signer, decoy) = (funding_key(3), funding_key(4));
+        let gen1_hash = storage.store_transaction(funding_tx()).unwrap();
+        let gen2_hash = storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 2)).unwrap();
+        dag.finalize_transactions(&[gen1_hash, gen2_hash]).unwrap();
+
+        let spend = |version: u8, ring_indices: Vec<u32>| {
+            let mut tx = funded_tx(gen1_hash, gen2_hash, 3);
+            tx.version = version;
+            tx.inputs[0].ring_indices = ring_indices;
+            let ring = vec![signer.public_key.clone(), decoy.public_key.clone()];
+            let message = tx.signing_message();
+            tx.sign(0, &message, signer.private_key(), &signer.public_key, &ring).unwrap();
+            tx
+        };
+
+        // Versions before 3 do not require ring indices structurally, but
+        // their members are checked all the same
+        for version in [PROTOCOL_VERSION, RING_INDEX_VERSION] {
+            for ring_indices in [vec![4, 3], vec![3, 5], vec![]] {
+                let result = dag.add_transaction(spend(version, ring_indices));
+                assert!(matches!(result, Err(NyxError::InvalidTransaction(_))));
+            }
+        }
+        dag.add_transaction(spend(RING_INDEX_VERSION, vec![3, 4])).unwrap();
+    }
+
+    #[test]
+    fn test_ring_must_include_spent_output() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        // Key 4 signs over genuine outputs 4 and 5 while spending output 3
+        let tx = TestTx::new(4).parents(gen1_hash, gen2_hash).funded().spends(gen1_hash, 3).build();
+        let result = dag.add_transaction(tx);
+        assert!(matches!(result, Err(NyxError::InvalidTransaction(_))));
+        assert!(!dag.is_output_spent(&gen1_hash, 3).unwrap());
+    }
+
+    #[test]
+    fn test_locked_output_spendable_once_unlocked() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let mut locked = funding_tx();
+        locked.outputs[3].unlock_time = Some(UnlockTime::Height(3));
+        let gen1_hash = store_genesis(&storage, locked);
+        let gen2_hash = store_genesis(&storage, test_tx([0u8; 32], [0u8; 32], 2));
+        let spend = || TestTx::new(3).parents(gen1_hash, gen2_hash).funded().spends(gen1_hash, 3).build();
+
+        let result = dag.add_transaction(spend());
+        assert!(matches!(result, Err(NyxError::InvalidTransaction(_))));
+        assert!(!dag.is_key_image_spent(&funded_key_image(3)).unwrap());
+
+        store_genesis(&storage, test_tx([0u8; 32], [0u8; 32], 4));
+        dag.add_transaction(spend()).unwrap();
+    }
+
+    #[test]
+    fn test_stale_timestamp_rejected() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        let mut tx = create_unsigned_tx(gen1_hash, gen2_hash, 3);
+        tx.timestamp = 0; // far older than its parents
+        let result = dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly);
+        assert!(matches!(result, Err(NyxError::InvalidTransaction(_))));
+    }
+
+    #[test]
+    fn test_concurrent_double_spend() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        // Distinct transactions sharing key image [3; 32]
+        let first = create_unsigned_tx(gen1_hash, gen2_hash, 3);
+        let second = create_unsigned_tx(gen2_hash, gen1_hash, 3);
+
+        let handles: Vec<_> = [first, second].into_iter()
+            .map(|tx| {
+                let dag = dag.clone();
+                std::thread::spawn(move || dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly))
+            })
+            .collect();
+
+        let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        assert_eq!(storage.transaction_count().unwrap(), 3);
+    }
+
+    /// Builds genesis pair g1, g2, then a <- (g1, g2), b <- (a, g2), c <- (a, g1)
+    fn build_small_dag() -> (DagProcessor, [Hash; 5]) {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let (g1, g2) = store_genesis_pair(&storage);
+        let a = dag.add_transaction(funded_tx(g1, g2, 3)).unwrap();
+        let b = dag.add_transaction(funded_tx(a, g2, 4)).unwrap();
+        let c = dag.add_transaction(funded_tx(a, g1, 5)).unwrap();
+
+        (dag, [g1, g2, a, b, c])
+    }
+
+    #[test]
+    fn test_ancestors_and_descendants() {
+        let (dag, [g1, g2, a, b, c]) = build_small_dag();
+
+        let mut ancestors = dag.get_ancestors(&b, usize::MAX).unwrap();
+        ancestors.sort_unstable();
+        let mut expected = vec![a, g1, g2];
+        expected.sort_unstable();
+        assert_eq!(ancestors, expected);
+
+        assert_eq!(dag.get_ancestors(&c, 1).unwrap().len(), 2);
+        assert!(dag.get_ancestors(&g1, usize::MAX).unwrap().is_empty());
+
+        let mut descendants = dag.get_descendants(&a, usize::MAX).unwrap();
+        descendants.sort_unstable();
+        let mut expected = vec![b, c];
+        expected.sort_unstable();
+        assert_eq!(descendants, expected);
+
+        assert_eq!(dag.get_descendants(&g2, 1).unwrap().len(), 2);
+        assert_eq!(dag.get_descendants(&g2, 2).unwrap().len(), 3);
+        assert!(dag.get_descendants(&g2, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cumulative_weight_tracks_descendants() {
+        let (dag, hashes) = build_small_dag();
+        for hash in hashes {
+            let descendants = dag.get_descendants(&hash, usize::MAX).unwrap().len() as u64;
+            assert_eq!(dag.cumulative_weight(&hash).unwrap(), 1 + descendants);
+        }
+
+        // Finalized transactions keep the weight they had
+        let [_, g2, a, b, c] = hashes;
+        dag.finalize_transaction(&a).unwrap();
+        let d = funded_tx(c, b, 6);
+        dag.add_transaction(d).unwrap();
+        assert_eq!(dag.cumulative_weight(&a).unwrap(), 3);
+        assert_eq!(dag.cumulative_weight(&b).unwrap(), 2);
+        assert_eq!(dag.cumulative_weight(&g2).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_cumulative_weight_is_bounded_by_depth() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        let mut chain = Vec::new();
+        let mut parent = dag.add_transaction_with_mode(test_tx(gen1_hash, gen2_hash, 3), VerifyMode::HeadersOnly).unwrap();
+        chain.push(parent);
+        for nonce in 4..WEIGHT_DEPTH as u8 + 5 {
+            parent = dag.add_transaction_with_mode(test_tx(parent, gen2_hash, nonce), VerifyMode::HeadersOnly).unwrap();
+            chain.push(parent);
+        }
+
+        // Every chain transaction approves the second genesis directly, but
+        // the first is more than WEIGHT_DEPTH generations behind the last
+        assert_eq!(dag.cumulative_weight(&gen2_hash).unwrap(), 1 + chain.len() as u64);
+        assert_eq!(dag.get_descendants(&chain[0], usize::MAX).unwrap().len(), WEIGHT_DEPTH + 1);
+        assert_eq!(dag.cumulative_weight(&chain[0]).unwrap(), 1 + WEIGHT_DEPTH as u64);
+    }
+
+    #[test]
+    fn test_graph_export() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+        let tx3 = dag.add_transaction(funded_tx(gen1_hash, gen2_hash, 3)).unwrap();
+        let tx4 = dag.add_transaction(funded_tx(tx3, gen2_hash, 4)).unwrap();
+
+        let graph = dag.graph(&GraphRange::Around { center: tx3, depth: 1 }).unwrap();
+        let position = |hash: &Hash| graph.nodes.iter().position(|node| node.hash == *hash).unwrap();
+        assert_eq!(graph.nodes.len(), 4);
+        assert!(position(&gen1_hash) < position(&tx3) && position(&tx3) < position(&tx4));
+        assert_eq!(graph.nodes[position(&tx4)].parents, vec![tx3, gen2_hash]);
+        assert!(graph.nodes[position(&tx4)].tip);
+
+        let dot = dag.export_graphviz(&GraphRange::Tips { depth: 0 }).unwrap();
+        assert_eq!(dot.matches("label=").count(), 1);
+        assert!(dot.contains(&hex::encode(tx4)));
+        assert!(dag.export_graphml(&GraphRange::Around { center: [9u8; 32], depth: 1 }).is_err());
+    }
+
+    #[test]
+    fn test_is_ancestor() {
+        let (dag, [g1, g2, a, b, c]) = build_small_dag();
+
+        assert!(dag.is_ancestor(&g1, &b).unwrap());
+        assert!(dag.is_ancestor(&a, &c).unwrap());
+        assert!(!dag.is_ancestor(&b, &c).unwrap());
+        assert!(!dag.is_ancestor(&c, &g2).unwrap());
+        assert!(!dag.is_ancestor(&a, &a).unwrap());
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let (dag, hashes) = build_small_dag();
+
+        let order: Vec<Hash> = dag.topological_order().unwrap().collect();
+        assert_eq!(order.len(), hashes.len());
+
+        let position = |h: &Hash| order.iter().position(|x| x == h).unwrap();
+        for hash in &hashes {
+            for parent in dag.get_parents(hash).unwrap() {
+                assert!(position(&parent) < position(hash));
+            }
+        }
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let (dag, hashes) = build_small_dag();
+        dag.finalize_transaction(&hashes[2]).unwrap();
+
+        let mut bytes = Vec::new();
+        dag.export_snapshot(&mut bytes).unwrap();
+
+        let restored = DagProcessor::new(MemoryStorage::new());
+        assert_eq!(restored.import_snapshot(bytes.as_slice()).unwrap(), hashes.len());
+
+        for hash in &hashes {
+            assert_eq!(restored.get_score(hash).unwrap(), dag.get_score(hash).unwrap());
+            assert_eq!(restored.get_state(hash).unwrap(), dag.get_state(hash).unwrap());
+            assert_eq!(restored.get_children(hash).unwrap(), dag.get_children(hash).unwrap());
+        }
+
+        let mut tips = restored.get_tips().unwrap();
+        tips.sort_unstable();
+        assert_eq!(tips, dag.snapshot().unwrap().tips);
+        assert!(restored.is_key_image_spent(&funded_key_image(3)).unwrap());
+        assert_eq!(restored.latest_finalized().unwrap(), Some(hashes[2]));
+    }
+
+    #[test]
+    fn test_snapshot_import_requires_empty_dag() {
+        let (dag, _) = build_small_dag();
+
+        let mut bytes = Vec::new();
+        dag.export_snapshot(&mut bytes).unwrap();
+
+        assert!(matches!(dag.import_snapshot(bytes.as_slice()), Err(NyxError::DagError(_))));
+    }
+
+    #[test]
+    fn test_snapshot_rejects_unordered_entries() {
+        let (dag, _) = build_small_dag();
+        let mut snapshot = dag.snapshot().unwrap();
+        snapshot.entries.reverse();
+
+        let restored = DagProcessor::new(MemoryStorage::new());
+        assert!(matches!(restored.restore_snapshot(snapshot), Err(NyxError::DagError(_))));
+    }
+
+    #[test]
+    fn test_rejected_snapshot_import_can_be_retried() {
+        let (dag, [_, _, a, b, _]) = build_small_dag();
+        dag.finalize_transaction(&a).unwrap();
+        let snapshot = dag.snapshot().unwrap();
+        let restored = DagProcessor::new(MemoryStorage::new());
+
+        let mut missing_tip = snapshot.clone();
+        missing_tip.tips.push([9u8; 32]);
+        assert!(matches!(restored.restore_snapshot(missing_tip), Err(NyxError::DagError(_))));
+
+        let mut double_spend = snapshot.clone();
+        let last = double_spend.entries.last().unwrap().clone();
+        double_spend.entries.push(last);
+        assert!(matches!(restored.restore_snapshot(double_spend), Err(NyxError::DoubleSpend(_))));
+
+        let mut wrong_index = snapshot.clone();
+        let entry = wrong_index.entries.iter_mut().find(|e| e.tx.id() == a).unwrap();
+        entry.output_index = entry.output_index.map(|index| index + 100);
+        assert!(matches!(restored.restore_snapshot(wrong_index), Err(NyxError::DagError(_))));
+
+        assert!(!restored.is_key_image_spent(&funded_key_image(3)).unwrap());
+        assert!(restored.get_tips().unwrap().is_empty());
+        assert!(restored.get_children(&a).unwrap().is_empty());
+
+        assert_eq!(restored.restore_snapshot(snapshot.clone()).unwrap(), snapshot.entries.len());
+        assert_eq!(restored.get_children(&a).unwrap(), dag.get_children(&a).unwrap());
+        assert_eq!(restored.get_state(&b).unwrap(), dag.get_state(&b).unwrap());
+        assert!(restored.is_key_image_spent(&funded_key_image(3)).unwrap());
+    }
+
+    #[test]
+    fn test_event_subscription() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+        let events = dag.subscribe();
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+        let tx = funded_tx(gen1_hash, gen2_hash, 3);
+        let tx_hash = dag.add_transaction(tx.clone()).unwrap();
+        dag.finalize_transaction(&tx_hash).unwrap();
+
+        assert_eq!(events.try_recv().unwrap(), DagEvent::TransactionAdded { hash: tx_hash, tx: Arc::new(tx) });
+        assert_eq!(events.try_recv().unwrap(), DagEvent::TransactionFinalized { hash: tx_hash });
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_confirmation_events_follow_additions() {
+        let storage = MemoryStorage::new();
+        let mut params = ProtocolParams::for_network(NetworkId::Mainnet);
+        params.confirmation_threshold = 1.5;
+        let dag = DagProcessor::new(storage.clone()).with_params(params);
+        let events = dag.subscribe();
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+        let a = create_unsigned_tx(gen1_hash, gen2_hash, 3);
+        let b = create_unsigned_tx(a.id(), gen2_hash, 4);
+        let c = create_unsigned_tx(b.id(), gen1_hash, 5);
+
+        // Within a batch, additions come before the confirmations they cause
+        dag.add_transactions_batch_with_mode(vec![a.clone(), b.clone()], VerifyMode::HeadersOnly).unwrap();
+        assert!(matches!(events.try_recv().unwrap(), DagEvent::TransactionAdded { hash, .. } if hash == a.id()));
+        assert!(matches!(events.try_recv().unwrap(), DagEvent::TransactionAdded { hash, .. } if hash == b.id()));
+        assert_eq!(events.try_recv().unwrap(), DagEvent::TransactionConfirmed { hash: a.id() });
+        assert!(events.try_recv().is_err());
+
+        dag.add_transaction_with_mode(c.clone(), VerifyMode::HeadersOnly).unwrap();
+        assert!(matches!(events.try_recv().unwrap(), DagEvent::TransactionAdded { hash, .. } if hash == c.id()));
+        assert_eq!(events.try_recv().unwrap(), DagEvent::TransactionConfirmed { hash: b.id() });
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_finalize_transactions_in_one_batch() {
+        let (dag, [_, _, a, b, c]) = build_small_dag();
+        let events = dag.subscribe();
+        let flushes = dag.storage().flush_count();
+
+        dag.finalize_transactions(&[a, b, c]).unwrap();
+
+        assert_eq!(dag.storage().flush_count(), flushes + 1);
+        for hash in [a, b, c] {
+            assert_eq!(dag.get_state(&hash).unwrap(), TxState::Finalized);
+            assert!(dag.storage().is_confirmed(&hash).unwrap());
+            assert_eq!(events.try_recv().unwrap(), DagEvent::TransactionFinalized { hash });
+        }
+    }
+
+    #[test]
     fn test_tip_tracking() {
         let storage = MemoryStorage::new();
         let dag = DagProcessor::new(storage.clone());
 
-        let genesis = create_test_tx([0u8; 32], [0u8; 32], 1);
+        let genesis = funding_tx();
         storage.store_transaction(genesis.clone()).unwrap();
 
         let tips = dag.get_tips().unwrap();
         assert_eq!(tips.len(), 0); // No tips yet (genesis not added through dag)
     }
+
+    #[test]
+    fn test_clock_drives_future_timestamp_check() {
+        let storage = MemoryStorage::new();
+        let clock = Arc::new(crate::clock::ManualClock::new(1_000_000));
+        let dag = DagProcessor::new(storage.clone()).with_clock(clock.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        // Stamped with wall-clock time, which is far ahead of the manual clock
+        let tx = create_unsigned_tx(gen1_hash, gen2_hash, 3);
+        let result = dag.add_transaction_with_mode(tx.clone(), VerifyMode::HeadersOnly);
+        assert!(matches!(result, Err(NyxError::InvalidTransaction(_))));
+
+        clock.set(tx.timestamp);
+        assert!(dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly).is_ok());
+    }
+
+    #[test]
+    fn test_stats_track_throughput_and_latency() {
+        let storage = MemoryStorage::new();
+        let clock = Arc::new(crate::clock::ManualClock::new(crate::transaction::current_timestamp()));
+        let mut params = ProtocolParams::for_network(NetworkId::Mainnet);
+        params.confirmation_threshold = 1.5;
+        let dag = DagProcessor::new(storage.clone()).with_params(params).with_clock(clock.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+        let a = dag.add_transaction_with_mode(create_unsigned_tx(gen1_hash, gen2_hash, 3), VerifyMode::HeadersOnly).unwrap();
+        assert_eq!(dag.get_stats().unwrap().avg_confirmation_latency_secs, None);
+
+        clock.advance(4);
+        dag.add_transaction_with_mode(create_unsigned_tx(a, gen2_hash, 4), VerifyMode::HeadersOnly).unwrap();
+
+        let stats = dag.get_stats().unwrap();
+        assert!(dag.is_confirmed(&a).unwrap());
+        assert_eq!(stats.avg_confirmation_latency_secs, Some(4.0));
+        assert_eq!(stats.tps, 2.0 / 5.0);
+        assert_eq!(stats.tip_history.last().unwrap().tips, 1);
+        assert!(stats.score_percentiles.max >= 1.9);
+    }
+
+    #[test]
+    fn test_params_drive_confirmation_and_limits() {
+        let storage = MemoryStorage::new();
+        let mut params = ProtocolParams::for_network(NetworkId::Mainnet);
+        params.confirmation_threshold = 1.0;
+        let dag = DagProcessor::new(storage.clone()).with_params(params.clone());
+
+        let (gen1_hash, gen2_hash) = store_genesis_pair(&storage);
+
+        let tx_hash = dag.add_transaction_with_mode(create_unsigned_tx(gen1_hash, gen2_hash, 3), VerifyMode::HeadersOnly).unwrap();
+        assert!(dag.is_confirmed(&tx_hash).unwrap());
+
+        params.limits.max_ring_size = 1;
+        let strict = DagProcessor::new(storage).with_params(params);
+        let result = strict.add_transaction_with_mode(create_unsigned_tx(gen1_hash, gen2_hash, 4), VerifyMode::HeadersOnly);
+        assert!(matches!(result, Err(NyxError::LimitExceeded(_))));
+    }
+
+    #[test]
+    fn test_batch_accepts_any_order() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+        let (g1, g2) = store_genesis_pair(&storage);
+
+        let a = funded_tx(g1, g2, 3);
+        let b = funded_tx(a.id(), g2, 4);
+        let c = funded_tx(a.id(), g1, 5);
+        let d = funded_tx(b.id(), c.id(), 6);
+        let batch = vec![d.clone(), c.clone(), b.clone(), a.clone(), a.clone()];
+
+        let outcome = dag.add_transactions_batch(batch).unwrap();
+        assert_eq!(outcome.added, vec![a.id(), c.id(), b.id(), d.id()]);
+        assert_eq!(outcome.rejected.len(), 1);
+        assert_eq!(dag.get_tips().unwrap(), vec![d.id()]);
+        assert_eq!(storage.flush_count(), 1);
+        assert!(dag.get_score(&a.id()).unwrap() > dag.get_score(&d.id()).unwrap());
+    }
+
+    #[test]
+    fn test_batch_rejects_descendants_of_invalid() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+        let (g1, g2) = store_genesis_pair(&storage);
+
+        let bad = create_unsigned_tx(g1, g2, 3);
+        let child = funded_tx(bad.id(), g2, 4);
+        let other = funded_tx(g2, g1, 5);
+
+        let outcome = dag.add_transactions_batch(vec![child.clone(), bad.clone(), other.clone()]).unwrap();
+        assert_eq!(outcome.added, vec![other.id()]);
+        let rejected: Vec<Hash> = outcome.rejected.iter().map(|(hash, _)| *hash).collect();
+        assert_eq!(rejected, vec![bad.id(), child.id()]);
+        assert!(storage.get_transaction(&child.id()).is_err());
+    }
+
+    #[test]
+    fn test_batch_scores_match_sequential() {
+        let g1 = funding_tx();
+        let g2 = test_tx([0u8; 32], [0u8; 32], 2);
+        let a = funded_tx(g1.id(), g2.id(), 3);
+        let b = funded_tx(a.id(), g2.id(), 4);
+        let c = funded_tx(b.id(), a.id(), 5);
+        let txs = vec![a, b, c];
+        let dag = || {
+            let storage = MemoryStorage::new();
+            store_genesis(&storage, g1.clone());
+            store_genesis(&storage, g2.clone());
+            DagProcessor::new(storage)
+        };
+
+        let sequential = dag();
+        for tx in txs.clone() {
+            sequential.add_transaction(tx).unwrap();
+        }
+
+        let batched = dag();
+        assert_eq!(batched.add_transactions_batch(txs.clone()).unwrap().added.len(), 3);
+
+        for hash in [g1.id(), g2.id()].into_iter().chain(txs.iter().map(|tx| tx.id())) {
+            assert!(batched.get_score(&hash).unwrap() >= sequential.get_score(&hash).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_seeded_tip_sampling_is_reproducible() {
+        let sample = || {
+            let dag = DagProcessor::new(MemoryStorage::new())
+                .with_network(NetworkId::Regtest)
+                .with_clock(Arc::new(crate::clock::ManualClock::new(1_700_000_000)))
+                .with_entropy(Entropy::seeded(5));
+            for nonce in 0..8u8 {
+                dag.mint(vec![TxOutput {
+                    stealth_address: vec![nonce],
+                    amount_commitment: pedersen::commit(TEST_AMOUNT, &test_blinding()).to_bytes().to_vec(),
+                    range_proof: Vec::new(),
+                    ephemeral_pubkey: Vec::new(),
+                    unlock_time: None,
+                    htlc: None,
+                }])
+                .unwrap();
+            }
+            (0..16).map(|_| dag.sample_tip().unwrap().unwrap()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(sample(), sample());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_queries_match_blocking_ones() {
+        let dag = DagProcessor::new(MemoryStorage::new()).with_network(NetworkId::Regtest);
+        let output = TxOutput {
+            stealth_address: vec![1u8; 32],
+            amount_commitment: vec![],
+            range_proof: vec![],
+            ephemeral_pubkey: vec![],
+            unlock_time: None,
+            htlc: None,
+        };
+        let minted = dag.mint(vec![output]).unwrap();
+
+        assert_eq!(dag.get_transaction_async(minted).await.unwrap(), dag.storage().get_transaction(&minted).unwrap());
+        assert!(dag.has_transaction_async(minted).await.unwrap());
+        assert!(!dag.has_transaction_async([9u8; 32]).await.unwrap());
+        assert_eq!(dag.get_tips_async().await.unwrap(), dag.get_tips().unwrap());
+        assert_eq!(dag.get_stats_async().await.unwrap().total_transactions, 1);
+        assert!(matches!(
+            dag.get_transaction_async([9u8; 32]).await,
+            Err(NyxError::TransactionNotFound(_))
+        ));
+    }
 }
@@ -0,0 +1,726 @@
+// src/encoding.rs
+
+//! Canonical binary encoding for consensus objects.
+//!
+//! Transaction IDs and signing messages must be byte-for-byte identical on
+//! every node regardless of serializer version, so consensus objects are
+//! encoded with an explicitly specified layout instead of bincode's default:
+//!
+//! - Integers are little-endian and fixed width (`u8`, `u32`, `u64`)
+//! - Hashes and key images are written as raw 32-byte arrays
+//! - Variable-length byte strings are prefixed with a `u32` length
+//! - Sequences are prefixed with a `u32` element count
+//! - Fields are written in declaration order, with no padding or tags
+//! - From [`UNLOCK_TIME_VERSION`] on, a transaction's outputs are followed
+//!   by one tagged unlock time per output (0 = none, 1 = timestamp,
+//!   2 = height, the latter two followed by a `u64`)
+//! - From [`HTLC_VERSION`] on, those are followed by one tagged hash/time-lock
+//!   per output (0 = none, 1 = hashlock, tagged timeout, claim key and
+//!   refund key)
+//!
+//! A transaction's ring signatures follow its outputs as a sequence, one
+//! per input. Its ID hashes its encoding with the ring signatures left out,
+//! and its wtxid the full encoding.
+//!
+//! [`decode_header`] reads only the leading fields of an encoded
+//! transaction, so batches can be screened before anything is allocated.
+//!
+//! Decoding is strict: trailing bytes, truncated input and oversized
+//! length prefixes are all rejected, and element counts are checked against
+//! the decoder's [`DecodeLimits`] before anything is allocated.
+
+use crate::errors::{NyxError, Result};
+use crate::limits::{DecodeLimits, LimitExceeded};
+use crate::network::NetworkId;
+use crate::transaction::{HtlcLock, Transaction, TxInput, TxOutput, UnlockTime};
+use crate::types::Hash;
+use crate::{HTLC_VERSION, UNLOCK_TIME_VERSION};
+use nyx_crypto::ring::RingSignature;
+use nyx_crypto::KEY_IMAGE_SIZE;
+
+/// Append-only writer producing canonical encodings
+#[derive(Debug, Default)]
+pub struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    /// Creates an empty encoder
+    pub fn new() -> Self {
+        Self { buf: Vec::new() }
+    }
+
+    /// Writes a single byte
+    pub fn put_u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+
+    /// Writes a little-endian `u32`
+    pub fn put_u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Writes a little-endian `u64`
+    pub fn put_u64(&mut self, value: u64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Writes a fixed 32-byte array without a length prefix
+    pub fn put_hash(&mut self, hash: &Hash) {
+        self.buf.extend_from_slice(hash);
+    }
+
+    /// Writes a `u32` length prefix followed by the bytes
+    pub fn put_bytes(&mut self, bytes: &[u8]) {
+        self.put_len(bytes.len());
+        self.buf.extend_from_slice(bytes);
+    }
+
+    /// Writes a `u32` element count
+    pub fn put_len(&mut self, len: usize) {
+        let len = u32::try_from(len).expect("canonical length exceeds u32::MAX");
+        self.put_u32(len);
+    }
+
+    /// Consumes the encoder and returns the encoded bytes
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+/// Cursor reading canonical encodings
+#[derive(Debug)]
+pub struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+    limits: DecodeLimits,
+}
+
+impl<'a> Decoder<'a> {
+    /// Creates a decoder over the given bytes with the default limits
+    pub fn new(data: &'a [u8]) -> Self {
+        Self::with_limits(data, DecodeLimits::default())
+    }
+
+    /// Creates a decoder over the given bytes with custom limits
+    pub fn with_limits(data: &'a [u8], limits: DecodeLimits) -> Self {
+        Self { data, pos: 0, limits }
+    }
+
+    /// Gets the limits applied by this decoder
+    pub fn limits(&self) -> &DecodeLimits {
+        &self.limits
+    }
+
+    /// Returns the number of unread bytes
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.remaining() < n {
+            return Err(NyxError::SerializationError(format!(
+                "Unexpected end of input: need {} bytes at offset {}, have {}",
+                n,
+                self.pos,
+                self.remaining()
+            )));
+        }
+        let slice = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+
+    /// Reads a single byte
+    pub fn get_u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Reads a little-endian `u32`
+    pub fn get_u32(&mut self) -> Result<u32> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    /// Reads a little-endian `u64`
+    pub fn get_u64(&mut self) -> Result<u64> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Reads a fixed 32-byte array
+    pub fn get_hash(&mut self) -> Result<Hash> {
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(self.take(32)?);
+        Ok(hash)
+    }
+
+    /// Reads a length-prefixed byte string
+    pub fn get_bytes(&mut self) -> Result<Vec<u8>> {
+        let len = self.get_len(1)?;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    /// Reads a `u32` element count
+    ///
+    /// `min_element_size` is the smallest number of bytes a single element
+    /// can occupy; counts that could not possibly fit in the remaining input
+    /// are rejected before any allocation happens.
+    pub fn get_len(&mut self, min_element_size: usize) -> Result<usize> {
+        let len = self.get_u32()? as usize;
+        self.ensure_fits(len, min_element_size)?;
+        Ok(len)
+    }
+
+    /// Reads a `u32` element count that may not exceed `max`
+    ///
+    /// Fails with [`NyxError::LimitExceeded`] naming `field` if it does.
+    pub fn get_limited_len(&mut self, min_element_size: usize, field: &'static str, max: usize) -> Result<usize> {
+        let len = self.get_u32()? as usize;
+        LimitExceeded::check(field, max, len)?;
+        self.ensure_fits(len, min_element_size)?;
+        Ok(len)
+    }
+
+    /// Reads a length-prefixed byte string of at most `max` bytes
+    pub fn get_limited_bytes(&mut self, field: &'static str, max: usize) -> Result<Vec<u8>> {
+        let len = self.get_limited_len(1, field, max)?;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    /// Fails if `len` elements of `min_element_size` bytes cannot fit in
+    /// the remaining input
+    fn ensure_fits(&self, len: usize, min_element_size: usize) -> Result<()> {
+        if len.saturating_mul(min_element_size.max(1)) > self.remaining() {
+            return Err(NyxError::SerializationError(format!(
+                "Length prefix {} exceeds remaining input ({} bytes)",
+                len,
+                self.remaining()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Fails if any input remains unread
+    pub fn finish(self) -> Result<()> {
+        if self.remaining() != 0 {
+            return Err(NyxError::SerializationError(format!(
+                "{} trailing bytes after canonical object",
+                self.remaining()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Types with a canonical binary encoding
+pub trait CanonicalEncode: Sized {
+    /// Writes the canonical encoding of `self` into the encoder
+    fn encode_into(&self, enc: &mut Encoder);
+
+    /// Reads a value from the decoder
+    fn decode_from(dec: &mut Decoder<'_>) -> Result<Self>;
+
+    /// Returns the canonical encoding of `self`
+    fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut enc = Encoder::new();
+        self.encode_into(&mut enc);
+        enc.finish()
+    }
+
+    /// Decodes a value, rejecting trailing bytes
+    fn from_canonical_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut dec = Decoder::new(bytes);
+        let value = Self::decode_from(&mut dec)?;
+        dec.finish()?;
+        Ok(value)
+    }
+}
+
+impl CanonicalEncode for TxInput {
+    fn encode_into(&self, enc: &mut Encoder) {
+        enc.put_hash(&self.prev_tx);
+        enc.put_u32(self.index);
+        enc.put_hash(&self.key_image);
+        enc.put_len(self.ring_indices.len());
+        for index in &self.ring_indices {
+            enc.put_u32(*index);
+        }
+    }
+
+    fn decode_from(dec: &mut Decoder<'_>) -> Result<Self> {
+        let prev_tx = dec.get_hash()?;
+        let index = dec.get_u32()?;
+        let key_image = dec.get_hash()?;
+        let count = dec.get_limited_len(4, "ring indices", dec.limits().max_ring_size)?;
+        let mut ring_indices = Vec::with_capacity(count);
+        for _ in 0..count {
+            ring_indices.push(dec.get_u32()?);
+        }
+        Ok(Self {
+            prev_tx,
+            index,
+            key_image,
+            ring_indices,
+        })
+    }
+}
+
+impl CanonicalEncode for TxOutput {
+    fn encode_into(&self, enc: &mut Encoder) {
+        enc.put_bytes(&self.stealth_address);
+        enc.put_bytes(&self.amount_commitment);
+        enc.put_bytes(&self.range_proof);
+        enc.put_bytes(&self.ephemeral_pubkey);
+    }
+
+    fn decode_from(dec: &mut Decoder<'_>) -> Result<Self> {
+        Ok(Self {
+            stealth_address: dec.get_bytes()?,
+            amount_commitment: dec.get_bytes()?,
+            range_proof: dec.get_bytes()?,
+            ephemeral_pubkey: dec.get_bytes()?,
+            unlock_time: None,
+            htlc: None,
+        })
+    }
+}
+
+/// Tags of an encoded unlock time
+const UNLOCK_NONE: u8 = 0;
+const UNLOCK_TIMESTAMP: u8 = 1;
+const UNLOCK_HEIGHT: u8 = 2;
+
+pub(crate) fn encode_unlock_time(enc: &mut Encoder, unlock_time: Option<UnlockTime>) {
+    match unlock_time {
+        None => enc.put_u8(UNLOCK_NONE),
+        Some(UnlockTime::Timestamp(timestamp)) => {
+            enc.put_u8(UNLOCK_TIMESTAMP);
+            enc.put_u64(timestamp);
+        }
+        Some(UnlockTime::Height(height)) => {
+            enc.put_u8(UNLOCK_HEIGHT);
+            enc.put_u64(height);
+        }
+    }
+}
+
+fn decode_unlock_time(dec: &mut Decoder<'_>) -> Result<Option<UnlockTime>> {
+    match dec.get_u8()? {
+        UNLOCK_NONE => Ok(None),
+        UNLOCK_TIMESTAMP => Ok(Some(UnlockTime::Timestamp(dec.get_u64()?))),
+        UNLOCK_HEIGHT => Ok(Some(UnlockTime::Height(dec.get_u64()?))),
+        tag => Err(NyxError::SerializationError(format!("Unknown unlock time tag {}", tag))),
+    }
+}
+
+/// Tags of an encoded hash/time-lock
+const HTLC_NONE: u8 = 0;
+const HTLC_LOCKED: u8 = 1;
+
+pub(crate) fn encode_htlc(enc: &mut Encoder, htlc: Option<&HtlcLock>) {
+    match htlc {
+        None => enc.put_u8(HTLC_NONE),
+        Some(htlc) => {
+            enc.put_u8(HTLC_LOCKED);
+            enc.put_hash(&htlc.hashlock);
+            encode_unlock_time(enc, Some(htlc.timeout));
+            enc.put_hash(&htlc.claim_key);
+            enc.put_hash(&htlc.refund_key);
+        }
+    }
+}
+
+fn decode_htlc(dec: &mut Decoder<'_>) -> Result<Option<HtlcLock>> {
+    match dec.get_u8()? {
+        HTLC_NONE => Ok(None),
+        HTLC_LOCKED => {
+            let hashlock = dec.get_hash()?;
+            let timeout = decode_unlock_time(dec)?
+                .ok_or_else(|| NyxError::SerializationError("HTLC without a timeout".to_string()))?;
+            Ok(Some(HtlcLock { hashlock, timeout, claim_key: dec.get_hash()?, refund_key: dec.get_hash()? }))
+        }
+        tag => Err(NyxError::SerializationError(format!("Unknown HTLC tag {}", tag))),
+    }
+}
+
+impl CanonicalEncode for RingSignature {
+    fn encode_into(&self, enc: &mut Encoder) {
+        enc.put_len(self.ring_members.len());
+        for member in &self.ring_members {
+            enc.put_bytes(member);
+        }
+        enc.put_bytes(&self.signature);
+        enc.put_hash(&self.key_image);
+    }
+
+    fn decode_from(dec: &mut Decoder<'_>) -> Result<Self> {
+        let count = dec.get_limited_len(4, "ring members", dec.limits().max_ring_size)?;
+        let mut ring_members = Vec::with_capacity(count);
+        for _ in 0..count {
+            ring_members.push(dec.get_bytes()?);
+        }
+        let signature = dec.get_bytes()?;
+        let key_image: [u8; KEY_IMAGE_SIZE] = dec.get_hash()?;
+        Ok(Self {
+            ring_members,
+            signature,
+            key_image,
+        })
+    }
+}
+
+impl CanonicalEncode for Transaction {
+    fn encode_into(&self, enc: &mut Encoder) {
+        encode_transaction_fields(enc, self, true);
+    }
+
+    fn decode_from(dec: &mut Decoder<'_>) -> Result<Self> {
+        let version = dec.get_u8()?;
+        let network = NetworkId::from_u8(dec.get_u8()?)?;
+        let limits = *dec.limits();
+        let inputs = decode_seq(dec, 72, "inputs", limits.max_inputs)?;
+        let mut outputs: Vec<TxOutput> = decode_seq(dec, 16, "outputs", limits.max_outputs)?;
+        if version >= UNLOCK_TIME_VERSION {
+            for output in &mut outputs {
+                output.unlock_time = decode_unlock_time(dec)?;
+            }
+        }
+        if version >= HTLC_VERSION {
+            for output in &mut outputs {
+                output.htlc = decode_htlc(dec)?;
+            }
+        }
+        let ring_signatures = decode_seq(dec, 40, "ring signatures", limits.max_inputs)?;
+        let tx_key = dec.get_bytes()?;
+        let parent1 = dec.get_hash()?;
+        let parent2 = dec.get_hash()?;
+        let timestamp = dec.get_u64()?;
+        let extra = dec.get_limited_bytes("extra", limits.max_extra_size)?;
+        Ok(Self {
+            version,
+            network,
+            inputs,
+            outputs,
+            ring_signatures,
+            tx_key,
+            references: [parent1, parent2],
+            timestamp,
+            extra,
+        })
+    }
+}
+
+/// Writes the fields of `tx` in order, the ring signatures only if `witness`
+fn encode_transaction_fields(enc: &mut Encoder, tx: &Transaction, witness: bool) {
+    enc.put_u8(tx.version);
+    enc.put_u8(tx.network.as_u8());
+    encode_seq(enc, &tx.inputs);
+    encode_seq(enc, &tx.outputs);
+    if tx.version >= UNLOCK_TIME_VERSION {
+        for output in &tx.outputs {
+            encode_unlock_time(enc, output.unlock_time);
+        }
+    }
+    if tx.version >= HTLC_VERSION {
+        for output in &tx.outputs {
+            encode_htlc(enc, output.htlc.as_ref());
+        }
+    }
+    if witness {
+        encode_seq(enc, &tx.ring_signatures);
+    }
+    enc.put_bytes(&tx.tx_key);
+    enc.put_hash(&tx.references[0]);
+    enc.put_hash(&tx.references[1]);
+    enc.put_u64(tx.timestamp);
+    enc.put_bytes(&tx.extra);
+}
+
+fn encode_seq<T: CanonicalEncode>(enc: &mut Encoder, items: &[T]) {
+    enc.put_len(items.len());
+    for item in items {
+        item.encode_into(enc);
+    }
+}
+
+fn decode_seq<T: CanonicalEncode>(
+    dec: &mut Decoder<'_>,
+    min_element_size: usize,
+    field: &'static str,
+    max: usize,
+) -> Result<Vec<T>> {
+    let count = dec.get_limited_len(min_element_size, field, max)?;
+    let mut items = Vec::with_capacity(count);
+    for _ in 0..count {
+        items.push(T::decode_from(dec)?);
+    }
+    Ok(items)
+}
+
+/// Encodes a transaction in canonical form
+pub fn encode_transaction(tx: &Transaction) -> Vec<u8> {
+    tx.to_canonical_bytes()
+}
+
+/// Encodes a transaction in canonical form without its ring signatures
+///
+/// This is the preimage of the transaction ID; it cannot be decoded.
+pub fn encode_transaction_without_witness(tx: &Transaction) -> Vec<u8> {
+    let mut enc = Encoder::new();
+    encode_transaction_fields(&mut enc, tx, false);
+    enc.finish()
+}
+
+/// Leading fields of an encoded transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxHeader {
+    /// Format version
+    pub version: u8,
+
+    /// Network the transaction is bound to
+    pub network: NetworkId,
+
+    /// Number of inputs
+    pub inputs: usize,
+}
+
+/// Reads the header of a canonically encoded transaction without decoding
+/// the rest
+///
+/// # Errors
+/// - `LimitExceeded` if the encoding or its input count is over `limits`
+/// - `SerializationError` if the header is truncated or names an unknown
+///   network
+pub fn decode_header(bytes: &[u8], limits: DecodeLimits) -> Result<TxHeader> {
+    LimitExceeded::check("transaction bytes", limits.max_tx_size, bytes.len())?;
+    let mut dec = Decoder::with_limits(bytes, limits);
+    let version = dec.get_u8()?;
+    let network = NetworkId::from_u8(dec.get_u8()?)?;
+    let inputs = dec.get_limited_len(72, "inputs", limits.max_inputs)?;
+    Ok(TxHeader { version, network, inputs })
+}
+
+/// Decodes a canonically encoded transaction with the default limits
+pub fn decode_transaction(bytes: &[u8]) -> Result<Transaction> {
+    decode_transaction_with_limits(bytes, DecodeLimits::default())
+}
+
+/// Decodes a canonically encoded transaction with custom limits
+pub fn decode_transaction_with_limits(bytes: &[u8], limits: DecodeLimits) -> Result<Transaction> {
+    LimitExceeded::check("transaction bytes", limits.max_tx_size, bytes.len())?;
+    let mut dec = Decoder::with_limits(bytes, limits);
+    let tx = Transaction::decode_from(&mut dec)?;
+    dec.finish()?;
+    Ok(tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_tx() -> Transaction {
+        Transaction {
+            version: 1,
+            network: NetworkId::Testnet,
+            inputs: vec![TxInput {
+                prev_tx: [3u8; 32],
+                index: 7,
+                key_image: [4u8; 32],
+                ring_indices: vec![1, 2, 3],
+            }],
+            outputs: vec![TxOutput {
+                stealth_address: vec![5u8; 32],
+                amount_commitment: vec![6u8; 40],
+                range_proof: vec![7u8; 10],
+                ephemeral_pubkey: vec![8u8; 32],
+                unlock_time: None,
+                htlc: None,
+            }],
+            ring_signatures: vec![RingSignature {
+                ring_members: vec![vec![9u8; 32], vec![10u8; 32]],
+                signature: vec![11u8; 96],
+                key_image: [12u8; 32],
+            }],
+            tx_key: vec![13u8; 32],
+            references: [[14u8; 32], [15u8; 32]],
+            timestamp: 1_700_000_000,
+            extra: vec![16, 17],
+        }
+    }
+
+    #[test]
+    fn test_ring_signature_is_not_part_of_txid() {
+        let tx = sample_tx();
+        let mut malleated = tx.clone();
+        malleated.ring_signatures[0].signature[0] ^= 1;
+
+        assert_eq!(tx.id(), malleated.id());
+        assert_ne!(tx.wtxid(), malleated.wtxid());
+
+        // Everything else is still covered
+        let mut changed = tx.clone();
+        changed.extra.push(0);
+        assert_ne!(tx.id(), changed.id());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let tx = sample_tx();
+        let bytes = encode_transaction(&tx);
+        let decoded = decode_transaction(&bytes).unwrap();
+        assert_eq!(tx, decoded);
+    }
+
+    #[test]
+    fn test_one_ring_signature_per_input_roundtrips() {
+        let mut tx = sample_tx();
+        let mut second = tx.inputs[0].clone();
+        second.key_image = [20u8; 32];
+        tx.inputs.push(second);
+        let mut signature = tx.ring_signatures[0].clone();
+        signature.key_image = [21u8; 32];
+        tx.ring_signatures.push(signature);
+
+        assert_eq!(decode_transaction(&encode_transaction(&tx)).unwrap(), tx);
+    }
+
+    #[test]
+    fn test_header_is_read_without_body() {
+        let tx = sample_tx();
+        let bytes = encode_transaction(&tx);
+        let header = decode_header(&bytes, DecodeLimits::default()).unwrap();
+        assert_eq!(header, TxHeader { version: 1, network: NetworkId::Testnet, inputs: 1 });
+
+        // The body is only measured, not decoded
+        assert!(decode_header(&bytes[..6 + 72], DecodeLimits::default()).is_ok());
+        assert!(decode_header(&bytes[..6], DecodeLimits::default()).is_err());
+        assert!(decode_header(&bytes[..3], DecodeLimits::default()).is_err());
+
+        let limits = DecodeLimits { max_inputs: 0, ..DecodeLimits::default() };
+        assert!(matches!(decode_header(&bytes, limits), Err(NyxError::LimitExceeded(_))));
+    }
+
+    #[test]
+    fn test_unlock_times_roundtrip() {
+        let mut tx = sample_tx();
+        tx.outputs[0].unlock_time = Some(UnlockTime::Height(42));
+        tx.version = Transaction::version_for(&tx.outputs);
+        assert_ne!(encode_transaction(&tx), encode_transaction(&sample_tx()));
+        assert_eq!(decode_transaction(&encode_transaction(&tx)).unwrap(), tx);
+
+        tx.outputs[0].unlock_time = Some(UnlockTime::Timestamp(1_800_000_000));
+        assert_eq!(decode_transaction(&encode_transaction(&tx)).unwrap(), tx);
+    }
+
+    #[test]
+    fn test_htlcs_roundtrip() {
+        let mut tx = sample_tx();
+        tx.outputs[0].htlc = Some(HtlcLock {
+            hashlock: [0x5A; 32],
+            timeout: UnlockTime::Height(500),
+            claim_key: [2u8; 32],
+            refund_key: [3u8; 32],
+        });
+        tx.version = Transaction::version_for(&tx.outputs);
+        assert_eq!(tx.version, HTLC_VERSION);
+        let bytes = encode_transaction(&tx);
+        assert_eq!(decode_transaction(&bytes).unwrap(), tx);
+
+        // The lock is signed, so a relay cannot strip it
+        let mut stripped = tx.clone();
+        stripped.outputs[0].htlc = None;
+        assert_ne!(stripped.signing_message(), tx.signing_message());
+
+        // A lock needs a timeout
+        let timeout_tag = bytes.windows(32).position(|window| window == [0x5A; 32]).unwrap() + 32;
+        assert_eq!(bytes[timeout_tag], UNLOCK_HEIGHT);
+        let mut untimed = bytes.clone();
+        untimed[timeout_tag] = 0;
+        assert!(decode_transaction(&untimed).is_err());
+    }
+
+    #[test]
+    fn test_layout_is_stable() {
+        let input = TxInput {
+            prev_tx: [0xAA; 32],
+            index: 1,
+            key_image: [0xBB; 32],
+            ring_indices: vec![2],
+        };
+        let bytes = input.to_canonical_bytes();
+
+        let mut expected = vec![0xAA; 32];
+        expected.extend_from_slice(&[1, 0, 0, 0]);
+        expected.extend_from_slice(&[0xBB; 32]);
+        expected.extend_from_slice(&[1, 0, 0, 0]);
+        expected.extend_from_slice(&[2, 0, 0, 0]);
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn test_rejects_unknown_network() {
+        let mut bytes = encode_transaction(&sample_tx());
+        bytes[1] = 0xFF;
+        assert!(decode_transaction(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_rejects_trailing_bytes() {
+        let mut bytes = encode_transaction(&sample_tx());
+        bytes.push(0);
+        assert!(decode_transaction(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_rejects_truncated_input() {
+        let bytes = encode_transaction(&sample_tx());
+        for len in [0, 1, 10, bytes.len() - 1] {
+            assert!(decode_transaction(&bytes[..len]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rejects_oversized_length_prefix() {
+        let mut enc = Encoder::new();
+        enc.put_u32(u32::MAX);
+        let bytes = enc.finish();
+
+        let mut dec = Decoder::new(&bytes);
+        assert!(dec.get_bytes().is_err());
+    }
+
+    #[test]
+    fn test_rejects_counts_over_limit() {
+        let mut tx = sample_tx();
+        tx.ring_signatures[0].ring_members = vec![vec![1u8]; crate::limits::MAX_RING_SIZE + 1];
+        let err = decode_transaction(&encode_transaction(&tx)).unwrap_err();
+        assert!(matches!(err, NyxError::LimitExceeded(LimitExceeded { field: "ring members", .. })));
+
+        let bytes = encode_transaction(&sample_tx());
+        let strict = DecodeLimits { max_extra_size: 1, ..DecodeLimits::default() };
+        let err = decode_transaction_with_limits(&bytes, strict).unwrap_err();
+        assert_eq!(err, NyxError::LimitExceeded(LimitExceeded { field: "extra", max: 1, actual: 2 }));
+    }
+
+    #[test]
+    fn test_rejects_oversized_transaction() {
+        let bytes = encode_transaction(&sample_tx());
+        let strict = DecodeLimits { max_tx_size: bytes.len() - 1, ..DecodeLimits::default() };
+        let err = decode_transaction_with_limits(&bytes, strict).unwrap_err();
+        assert!(matches!(err, NyxError::LimitExceeded(LimitExceeded { field: "transaction bytes", .. })));
+    }
+
+    #[test]
+    fn test_limit_checked_before_input_length() {
+        // A huge input count is reported as a limit hit, not as truncation
+        let mut enc = Encoder::new();
+        enc.put_u8(1);
+        enc.put_u8(NetworkId::Testnet.as_u8());
+        enc.put_u32(u32::MAX);
+        let err = decode_transaction(&enc.finish()).unwrap_err();
+        assert!(matches!(err, NyxError::LimitExceeded(LimitExceeded { field: "inputs", .. })));
+    }
+}
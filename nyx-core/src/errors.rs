@@ -5,6 +5,7 @@
 //! Provides comprehensive error handling for all Nyx operations
 //! including DAG processing, consensus, and cryptographic validation.
 
+use crate::limits::LimitExceeded;
 use std::fmt;
 
 /// Main error type for Nyx operations
@@ -39,6 +40,21 @@ pub enum NyxError {
 
     /// Serialization/deserialization error
     SerializationError(String),
+
+    /// Transaction version is unknown or not yet active
+    UnsupportedVersion(String),
+
+    /// Amount is malformed or out of range
+    InvalidAmount(String),
+
+    /// Misbehavior evidence is malformed, unverifiable or already applied
+    InvalidEvidence(String),
+
+    /// Cross-chain event proof is malformed or does not verify
+    InvalidBridgeProof(String),
+
+    /// Decoded input exceeds a structural limit
+    LimitExceeded(LimitExceeded),
 }
 
 impl fmt::Display for NyxError {
@@ -54,6 +70,11 @@ impl fmt::Display for NyxError {
             NyxError::TipSelectionError(msg) => write!(f, "Tip selection error: {}", msg),
             NyxError::ConsensusError(msg) => write!(f, "Consensus error: {}", msg),
             NyxError::SerializationError(msg) => write!(f, "Serialization error: {}", msg),
+            NyxError::UnsupportedVersion(msg) => write!(f, "Unsupported version: {}", msg),
+            NyxError::InvalidAmount(msg) => write!(f, "Invalid amount: {}", msg),
+            NyxError::InvalidEvidence(msg) => write!(f, "Invalid evidence: {}", msg),
+            NyxError::InvalidBridgeProof(msg) => write!(f, "Invalid bridge proof: {}", msg),
+            NyxError::LimitExceeded(err) => write!(f, "Limit exceeded: {}", err),
         }
     }
 }
@@ -0,0 +1,259 @@
+// src/evidence.rs
+
+//! Evidence of validator misbehavior.
+//!
+//! A validator equivocates when it signs two different snapshots with the
+//! same sequence number. Anyone holding both signatures can package them as
+//! [`Evidence`], gossip it, and have the offender slashed through the
+//! [`StakingRegistry`](crate::staking::StakingRegistry). Both votes must be
+//! Ed25519 signatures by the validator's own key, so evidence cannot be
+//! fabricated against a validator that did not equivocate.
+
+use crate::consensus::{SignedSnapshot, ValidatorSet};
+use crate::encoding::Encoder;
+use crate::errors::{NyxError, Result};
+use crate::finality::PosSnapshot;
+use crate::types::Hash;
+use nyx_crypto::keys;
+use serde::{Deserialize, Serialize};
+
+/// A single validator's signature on a snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotVote {
+    /// Snapshot that was signed
+    pub snapshot: PosSnapshot,
+
+    /// Validator Ed25519 signature over [`PosSnapshot::signing_hash`]
+    pub signature: Vec<u8>,
+}
+
+/// Proof that a validator signed two conflicting snapshots
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EquivocationEvidence {
+    /// Index of the offending validator in the validator set
+    pub validator: u32,
+
+    /// First signed snapshot
+    pub first: SnapshotVote,
+
+    /// Conflicting signed snapshot for the same number
+    pub second: SnapshotVote,
+}
+
+impl EquivocationEvidence {
+    /// Extracts equivocation evidence for `validator` from two signed snapshots
+    ///
+    /// Returns `None` if the snapshots do not conflict or the validator did
+    /// not sign both.
+    pub fn from_signed_snapshots(a: &SignedSnapshot, b: &SignedSnapshot, validator: u32) -> Option<Self> {
+        if a.snapshot.number != b.snapshot.number || a.snapshot == b.snapshot {
+            return None;
+        }
+
+        let vote = |signed: &SignedSnapshot| {
+            signed.signature.signatures.iter()
+                .find(|s| s.signer == validator)
+                .map(|s| SnapshotVote {
+                    snapshot: signed.snapshot.clone(),
+                    signature: s.signature.clone(),
+                })
+        };
+
+        Some(Self {
+            validator,
+            first: vote(a)?,
+            second: vote(b)?,
+        })
+    }
+
+    /// Verifies the evidence against the validator set
+    ///
+    /// # Errors
+    /// - `InvalidEvidence` if the snapshots do not conflict, the validator is
+    ///   unknown, or either signature is invalid
+    pub fn verify(&self, validators: &ValidatorSet) -> Result<()> {
+        if self.first.snapshot.number != self.second.snapshot.number {
+            return Err(NyxError::InvalidEvidence(format!(
+                "Votes are for different snapshots ({} and {})",
+                self.first.snapshot.number, self.second.snapshot.number
+            )));
+        }
+
+        let (first_hash, second_hash) = (self.first.snapshot.signing_hash(), self.second.snapshot.signing_hash());
+        if first_hash == second_hash {
+            return Err(NyxError::InvalidEvidence("Votes are for the same snapshot".to_string()));
+        }
+
+        let validator = validators.validators().get(self.validator as usize)
+            .ok_or_else(|| NyxError::InvalidEvidence(format!("Unknown validator {}", self.validator)))?;
+
+        for (hash, vote) in [(first_hash, &self.first), (second_hash, &self.second)] {
+            if !keys::verify_ed25519(&hash, &vote.signature, &validator.public_key) {
+                return Err(NyxError::InvalidEvidence(format!(
+                    "Invalid signature from validator {} on snapshot {}",
+                    self.validator, vote.snapshot.number
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Evidence of validator misbehavior
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Evidence {
+    /// Two conflicting snapshot signatures for the same number
+    Equivocation(EquivocationEvidence),
+}
+
+impl Evidence {
+    /// Index of the accused validator
+    pub fn validator(&self) -> u32 {
+        match self {
+            Evidence::Equivocation(e) => e.validator,
+        }
+    }
+
+    /// Identifier of the offense
+    ///
+    /// Keyed on the validator and the snapshot number only, so every pair
+    /// of conflicting votes for one number (in either order) has the same
+    /// id and the offense is only punished once.
+    pub fn id(&self) -> Hash {
+        let mut enc = Encoder::new();
+        match self {
+            Evidence::Equivocation(e) => {
+                enc.put_u8(0);
+                enc.put_u32(e.validator);
+                enc.put_u64(e.first.snapshot.number);
+            }
+        }
+        nyx_crypto::hash::blake3_hash(&enc.finish())
+    }
+
+    /// Verifies the evidence against the validator set
+    ///
+    /// # Errors
+    /// - `InvalidEvidence` if the evidence does not prove misbehavior
+    pub fn verify(&self, validators: &ValidatorSet) -> Result<()> {
+        match self {
+            Evidence::Equivocation(e) => e.verify(validators),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::consensus::Validator;
+
+    pub(crate) fn setup(n: u8) -> (ValidatorSet, Vec<[u8; 32]>) {
+        let keys: Vec<[u8; 32]> = (0..n).map(|i| [i; 32]).collect();
+        let set = ValidatorSet::new(
+            keys.iter().map(|seed| Validator { public_key: keys::ed25519_public_key(seed).to_vec(), stake: 100 }).collect(),
+        )
+        .unwrap();
+        (set, keys)
+    }
+
+    pub(crate) fn equivocation(keys: &[[u8; 32]], validator: u32) -> Evidence {
+        let mut a = SignedSnapshot::new(PosSnapshot { number: 5, anchors: vec![[1u8; 32]] });
+        let mut b = SignedSnapshot::new(PosSnapshot { number: 5, anchors: vec![[2u8; 32]] });
+        a.sign(validator, &keys[validator as usize]).unwrap();
+        b.sign(validator, &keys[validator as usize]).unwrap();
+
+        Evidence::Equivocation(EquivocationEvidence::from_signed_snapshots(&a, &b, validator).unwrap())
+    }
+
+    #[test]
+    fn test_valid_equivocation() {
+        let (set, keys) = setup(3);
+        let evidence = equivocation(&keys, 1);
+
+        evidence.verify(&set).unwrap();
+        assert_eq!(evidence.validator(), 1);
+
+        // Swapping the votes describes the same offense
+        let Evidence::Equivocation(mut swapped) = evidence.clone();
+        std::mem::swap(&mut swapped.first, &mut swapped.second);
+        assert_eq!(Evidence::Equivocation(swapped).id(), evidence.id());
+    }
+
+    #[test]
+    fn test_equivocation_id_per_snapshot_number() {
+        let (_, keys) = setup(2);
+        let signed = |anchor: u8| {
+            let mut snapshot = SignedSnapshot::new(PosSnapshot { number: 5, anchors: vec![[anchor; 32]] });
+            snapshot.sign(0, &keys[0]).unwrap();
+            snapshot
+        };
+        let (a, b, c) = (signed(1), signed(2), signed(3));
+        let evidence = |x, y| Evidence::Equivocation(EquivocationEvidence::from_signed_snapshots(x, y, 0).unwrap());
+
+        // Three conflicting votes at one number are a single offense
+        assert_eq!(evidence(&a, &b).id(), evidence(&a, &c).id());
+        assert_eq!(evidence(&a, &b).id(), evidence(&b, &c).id());
+
+        let mut later = SignedSnapshot::new(PosSnapshot { number: 6, anchors: vec![[1u8; 32]] });
+        let mut later_conflict = SignedSnapshot::new(PosSnapshot { number: 6, anchors: vec![[2u8; 32]] });
+        later.sign(0, &keys[0]).unwrap();
+        later_conflict.sign(0, &keys[0]).unwrap();
+        assert_ne!(evidence(&later, &later_conflict).id(), evidence(&a, &b).id());
+    }
+
+    #[test]
+    fn test_invalid_evidence() {
+        let (set, keys) = setup(3);
+        let Evidence::Equivocation(valid) = equivocation(&keys, 1);
+
+        let mut same = valid.clone();
+        same.second = same.first.clone();
+        assert!(matches!(same.verify(&set), Err(NyxError::InvalidEvidence(_))));
+
+        let mut other_number = valid.clone();
+        other_number.second.snapshot.number = 6;
+        assert!(matches!(other_number.verify(&set), Err(NyxError::InvalidEvidence(_))));
+
+        let mut forged = valid.clone();
+        forged.second.signature[40] ^= 0xFF;
+        assert!(matches!(forged.verify(&set), Err(NyxError::InvalidEvidence(_))));
+
+        let mut unknown = valid;
+        unknown.validator = 9;
+        assert!(matches!(unknown.verify(&set), Err(NyxError::InvalidEvidence(_))));
+    }
+
+    #[test]
+    fn test_evidence_without_validator_key_rejected() {
+        let (set, keys) = setup(3);
+
+        // Votes signed by another validator's key
+        let Evidence::Equivocation(mut framed) = equivocation(&keys, 0);
+        framed.validator = 1;
+        assert!(matches!(framed.verify(&set), Err(NyxError::InvalidEvidence(_))));
+
+        // Keyless votes committing to the snapshot hash only
+        let keyless = |snapshot: PosSnapshot| {
+            let mut signature = vec![0u8; 64];
+            signature[32..].copy_from_slice(&nyx_crypto::hash::blake3_hash(&snapshot.signing_hash()));
+            SnapshotVote { snapshot, signature }
+        };
+        let fabricated = EquivocationEvidence {
+            validator: 2,
+            first: keyless(PosSnapshot { number: 5, anchors: vec![[1u8; 32]] }),
+            second: keyless(PosSnapshot { number: 5, anchors: vec![[2u8; 32]] }),
+        };
+        assert!(matches!(fabricated.verify(&set), Err(NyxError::InvalidEvidence(_))));
+    }
+
+    #[test]
+    fn test_no_evidence_without_conflict() {
+        let (_, keys) = setup(2);
+        let mut a = SignedSnapshot::new(PosSnapshot { number: 5, anchors: vec![[1u8; 32]] });
+        a.sign(0, &keys[0]).unwrap();
+
+        assert!(EquivocationEvidence::from_signed_snapshots(&a, &a, 0).is_none());
+        assert!(EquivocationEvidence::from_signed_snapshots(&a, &a.clone(), 1).is_none());
+    }
+}
@@ -0,0 +1,426 @@
+// src/extra.rs
+
+//! Typed contents of a transaction's `extra` field.
+//!
+//! `extra` holds a sequence of tag-length-value fields, each a one-byte
+//! tag followed by a `u32`-length-prefixed value, in the canonical
+//! encoding of [`crate::encoding`]. Fields appear in strictly ascending
+//! tag order, so each tag occurs at most once and every [`Extra`] has a
+//! single encoding.
+//!
+//! | Tag    | Field                  | Value                                  |
+//! |--------|------------------------|----------------------------------------|
+//! | `0x01` | Ephemeral public keys  | Sequence of length-prefixed keys       |
+//! | `0x02` | Encrypted payment ID   | [`PAYMENT_ID_SIZE`] bytes              |
+//! | `0x03` | Memo                   | At most [`MAX_MEMO_SIZE`] bytes        |
+//! | `0x04` | Proof-of-work nonce    | `u64`                                  |
+//! | `0x05` | HTLC witnesses         | Sequence of [`HtlcWitness`]            |
+//! | `0xA1` | Amount hints           | 8-byte encrypted amount per output     |
+//!
+//! Unknown tags are kept as they are, so fields added later pass through
+//! older nodes and wallets.
+
+use crate::encoding::{Decoder, Encoder};
+use crate::errors::{NyxError, Result};
+use crate::limits::{LimitExceeded, MAX_EXTRA_SIZE, MAX_TX_INPUTS, MAX_TX_OUTPUTS};
+
+/// Tag of the ephemeral public keys field
+pub const TAG_EPHEMERAL_PUBKEYS: u8 = 0x01;
+
+/// Tag of the encrypted payment ID field
+pub const TAG_PAYMENT_ID: u8 = 0x02;
+
+/// Tag of the memo field
+pub const TAG_MEMO: u8 = 0x03;
+
+/// Tag of the proof-of-work nonce field
+pub const TAG_POW_NONCE: u8 = 0x04;
+
+/// Tag of the HTLC witnesses field
+pub const TAG_HTLC_WITNESSES: u8 = 0x05;
+
+/// Tag of the amount hints field
+pub const TAG_AMOUNT_HINTS: u8 = 0xA1;
+
+/// Size of an encrypted payment ID in bytes
+pub const PAYMENT_ID_SIZE: usize = 8;
+
+/// Maximum length of a memo in bytes
+pub const MAX_MEMO_SIZE: usize = 256;
+
+/// Size of an HTLC witness signature in bytes
+pub const HTLC_SIGNATURE_SIZE: usize = 64;
+
+/// What unlocks one input's spend of an HTLC output (see
+/// [`crate::transaction::HtlcLock`])
+///
+/// Encoded as the `u32` input index, a `u8` path (0 = refund, 1 = claim
+/// followed by the 32-byte preimage) and the length-prefixed signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HtlcWitness {
+    /// Index of the spending input
+    pub input: u32,
+
+    /// Swap secret when claiming; `None` when refunding
+    pub preimage: Option<[u8; 32]>,
+
+    /// Ed25519 signature of the claim or refund key over the
+    /// transaction's [HTLC witness message](crate::Transaction::htlc_witness_message)
+    pub signature: [u8; HTLC_SIGNATURE_SIZE],
+}
+
+/// Paths of an encoded HTLC witness
+const HTLC_REFUND: u8 = 0;
+const HTLC_CLAIM: u8 = 1;
+
+/// A single field of `extra`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtraField {
+    /// Additional ephemeral public keys, e.g. one per output
+    EphemeralPubkeys(Vec<Vec<u8>>),
+
+    /// Payment ID encrypted for the recipient
+    EncryptedPaymentId([u8; PAYMENT_ID_SIZE]),
+
+    /// Free-form memo
+    Memo(Vec<u8>),
+
+    /// Proof-of-work nonce
+    PowNonce(u64),
+
+    /// Witnesses of the inputs spending HTLC outputs
+    HtlcWitnesses(Vec<HtlcWitness>),
+
+    /// Encrypted output amounts, one per output in output order (see
+    /// [`nyx_crypto::amount`])
+    AmountHints(Vec<u8>),
+
+    /// Field with a tag this version does not know
+    Unknown {
+        /// Field tag
+        tag: u8,
+
+        /// Raw value
+        value: Vec<u8>,
+    },
+}
+
+impl ExtraField {
+    /// Gets the field's tag
+    pub fn tag(&self) -> u8 {
+        match self {
+            ExtraField::EphemeralPubkeys(_) => TAG_EPHEMERAL_PUBKEYS,
+            ExtraField::EncryptedPaymentId(_) => TAG_PAYMENT_ID,
+            ExtraField::Memo(_) => TAG_MEMO,
+            ExtraField::PowNonce(_) => TAG_POW_NONCE,
+            ExtraField::HtlcWitnesses(_) => TAG_HTLC_WITNESSES,
+            ExtraField::AmountHints(_) => TAG_AMOUNT_HINTS,
+            ExtraField::Unknown { tag, .. } => *tag,
+        }
+    }
+
+    /// Encodes the field's value
+    fn encode_value(&self) -> Vec<u8> {
+        match self {
+            ExtraField::EphemeralPubkeys(keys) => {
+                let mut enc = Encoder::new();
+                enc.put_len(keys.len());
+                for key in keys {
+                    enc.put_bytes(key);
+                }
+                enc.finish()
+            }
+            ExtraField::EncryptedPaymentId(payment_id) => payment_id.to_vec(),
+            ExtraField::PowNonce(nonce) => nonce.to_le_bytes().to_vec(),
+            ExtraField::HtlcWitnesses(witnesses) => {
+                let mut enc = Encoder::new();
+                enc.put_len(witnesses.len());
+                for witness in witnesses {
+                    enc.put_u32(witness.input);
+                    match &witness.preimage {
+                        None => enc.put_u8(HTLC_REFUND),
+                        Some(preimage) => {
+                            enc.put_u8(HTLC_CLAIM);
+                            enc.put_hash(preimage);
+                        }
+                    }
+                    enc.put_bytes(&witness.signature);
+                }
+                enc.finish()
+            }
+            ExtraField::Memo(value)
+            | ExtraField::AmountHints(value)
+            | ExtraField::Unknown { value, .. } => value.clone(),
+        }
+    }
+
+    /// Decodes the value of a field with the given tag
+    fn decode_value(tag: u8, value: Vec<u8>) -> Result<Self> {
+        let field = match tag {
+            TAG_EPHEMERAL_PUBKEYS => {
+                let mut dec = Decoder::new(&value);
+                let count = dec.get_limited_len(4, "extra ephemeral keys", MAX_TX_OUTPUTS)?;
+                let keys = (0..count).map(|_| dec.get_bytes()).collect::<Result<Vec<_>>>()?;
+                dec.finish()?;
+                ExtraField::EphemeralPubkeys(keys)
+            }
+            TAG_PAYMENT_ID => {
+                let payment_id = value.try_into().map_err(|value: Vec<u8>| NyxError::SerializationError(
+                    format!("Payment ID is {} bytes, expected {}", value.len(), PAYMENT_ID_SIZE)
+                ))?;
+                ExtraField::EncryptedPaymentId(payment_id)
+            }
+            TAG_MEMO => {
+                LimitExceeded::check("memo", MAX_MEMO_SIZE, value.len())?;
+                ExtraField::Memo(value)
+            }
+            TAG_POW_NONCE => {
+                let mut dec = Decoder::new(&value);
+                let nonce = dec.get_u64()?;
+                dec.finish()?;
+                ExtraField::PowNonce(nonce)
+            }
+            TAG_HTLC_WITNESSES => {
+                let mut dec = Decoder::new(&value);
+                let count = dec.get_limited_len(9, "HTLC witnesses", MAX_TX_INPUTS)?;
+                let mut witnesses = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let input = dec.get_u32()?;
+                    let preimage = match dec.get_u8()? {
+                        HTLC_REFUND => None,
+                        HTLC_CLAIM => Some(dec.get_hash()?),
+                        path => return Err(NyxError::SerializationError(format!("Unknown HTLC path {}", path))),
+                    };
+                    let signature = dec.get_bytes()?.try_into().map_err(|signature: Vec<u8>| {
+                        NyxError::SerializationError(format!(
+                            "HTLC signature is {} bytes, expected {}",
+                            signature.len(),
+                            HTLC_SIGNATURE_SIZE
+                        ))
+                    })?;
+                    witnesses.push(HtlcWitness { input, preimage, signature });
+                }
+                dec.finish()?;
+                ExtraField::HtlcWitnesses(witnesses)
+            }
+            TAG_AMOUNT_HINTS => ExtraField::AmountHints(value),
+            tag => ExtraField::Unknown { tag, value },
+        };
+        Ok(field)
+    }
+}
+
+/// Parsed `extra` field
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Extra {
+    /// Fields in ascending tag order
+    fields: Vec<ExtraField>,
+}
+
+impl Extra {
+    /// Creates an empty `extra`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a field, replacing any with the same tag
+    pub fn with_field(mut self, field: ExtraField) -> Self {
+        self.set(field);
+        self
+    }
+
+    /// Sets a field, replacing any with the same tag
+    pub fn set(&mut self, field: ExtraField) {
+        match self.fields.binary_search_by_key(&field.tag(), ExtraField::tag) {
+            Ok(i) => self.fields[i] = field,
+            Err(i) => self.fields.insert(i, field),
+        }
+    }
+
+    /// Removes the field with a tag
+    pub fn remove(&mut self, tag: u8) -> Option<ExtraField> {
+        let i = self.fields.binary_search_by_key(&tag, ExtraField::tag).ok()?;
+        Some(self.fields.remove(i))
+    }
+
+    /// Gets the field with a tag
+    pub fn get(&self, tag: u8) -> Option<&ExtraField> {
+        let i = self.fields.binary_search_by_key(&tag, ExtraField::tag).ok()?;
+        Some(&self.fields[i])
+    }
+
+    /// Gets all fields in tag order
+    pub fn fields(&self) -> &[ExtraField] {
+        &self.fields
+    }
+
+    /// Checks if there are no fields
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Gets the ephemeral public keys, if present
+    pub fn ephemeral_pubkeys(&self) -> Option<&[Vec<u8>]> {
+        match self.get(TAG_EPHEMERAL_PUBKEYS)? {
+            ExtraField::EphemeralPubkeys(keys) => Some(keys),
+            _ => None,
+        }
+    }
+
+    /// Gets the encrypted payment ID, if present
+    pub fn payment_id(&self) -> Option<[u8; PAYMENT_ID_SIZE]> {
+        match self.get(TAG_PAYMENT_ID)? {
+            ExtraField::EncryptedPaymentId(payment_id) => Some(*payment_id),
+            _ => None,
+        }
+    }
+
+    /// Gets the memo, if present
+    pub fn memo(&self) -> Option<&[u8]> {
+        match self.get(TAG_MEMO)? {
+            ExtraField::Memo(memo) => Some(memo),
+            _ => None,
+        }
+    }
+
+    /// Gets the proof-of-work nonce, if present
+    pub fn pow_nonce(&self) -> Option<u64> {
+        match self.get(TAG_POW_NONCE)? {
+            ExtraField::PowNonce(nonce) => Some(*nonce),
+            _ => None,
+        }
+    }
+
+    /// Gets the witness of an input spending an HTLC output, if present
+    pub fn htlc_witness(&self, input: u32) -> Option<&HtlcWitness> {
+        match self.get(TAG_HTLC_WITNESSES)? {
+            ExtraField::HtlcWitnesses(witnesses) => witnesses.iter().find(|witness| witness.input == input),
+            _ => None,
+        }
+    }
+
+    /// Gets the amount hints, if present
+    pub fn amount_hints(&self) -> Option<&[u8]> {
+        match self.get(TAG_AMOUNT_HINTS)? {
+            ExtraField::AmountHints(hints) => Some(hints),
+            _ => None,
+        }
+    }
+
+    /// Parses an `extra` field
+    ///
+    /// # Errors
+    /// - `SerializationError` if a field is truncated or malformed, or
+    ///   fields are not in strictly ascending tag order
+    /// - `LimitExceeded` if `extra` or a field is over its size limit
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        LimitExceeded::check("extra", MAX_EXTRA_SIZE, bytes.len())?;
+
+        let mut dec = Decoder::new(bytes);
+        let mut fields: Vec<ExtraField> = Vec::new();
+        while dec.remaining() > 0 {
+            let tag = dec.get_u8()?;
+            if fields.last().is_some_and(|last| last.tag() >= tag) {
+                return Err(NyxError::SerializationError(format!(
+                    "Extra field {:#04x} is out of order or repeated",
+                    tag
+                )));
+            }
+            let value = dec.get_bytes()?;
+            fields.push(ExtraField::decode_value(tag, value)?);
+        }
+
+        Ok(Self { fields })
+    }
+
+    /// Encodes the fields for a transaction's `extra`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut enc = Encoder::new();
+        for field in &self.fields {
+            enc.put_u8(field.tag());
+            enc.put_bytes(&field.encode_value());
+        }
+        enc.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Extra {
+        Extra::new()
+            .with_field(ExtraField::PowNonce(42))
+            .with_field(ExtraField::Memo(b"rent".to_vec()))
+            .with_field(ExtraField::EncryptedPaymentId([7u8; PAYMENT_ID_SIZE]))
+            .with_field(ExtraField::EphemeralPubkeys(vec![vec![1u8; 32], vec![2u8; 32]]))
+            .with_field(ExtraField::Unknown { tag: 0x7F, value: vec![9] })
+    }
+
+    #[test]
+    fn test_htlc_witnesses_roundtrip() {
+        let claim = HtlcWitness { input: 1, preimage: Some([3u8; 32]), signature: [4u8; HTLC_SIGNATURE_SIZE] };
+        let refund = HtlcWitness { input: 0, preimage: None, signature: [5u8; HTLC_SIGNATURE_SIZE] };
+        let extra = Extra::new().with_field(ExtraField::HtlcWitnesses(vec![claim, refund]));
+        let parsed = Extra::parse(&extra.to_bytes()).unwrap();
+        assert_eq!(parsed.htlc_witness(1), Some(&claim));
+        assert_eq!(parsed.htlc_witness(0), Some(&refund));
+        assert_eq!(parsed.htlc_witness(2), None);
+
+        // Signatures have a fixed size
+        let mut value = Encoder::new();
+        value.put_len(1);
+        value.put_u32(0);
+        value.put_u8(HTLC_REFUND);
+        value.put_bytes(&[5u8; HTLC_SIGNATURE_SIZE - 1]);
+        let short = Extra::new().with_field(ExtraField::Unknown { tag: TAG_HTLC_WITNESSES, value: value.finish() });
+        assert!(Extra::parse(&short.to_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let extra = sample();
+        let parsed = Extra::parse(&extra.to_bytes()).unwrap();
+        assert_eq!(parsed, extra);
+        assert_eq!(parsed.pow_nonce(), Some(42));
+        assert_eq!(parsed.memo(), Some(&b"rent"[..]));
+        assert_eq!(parsed.payment_id(), Some([7u8; PAYMENT_ID_SIZE]));
+        assert_eq!(parsed.ephemeral_pubkeys().map(<[_]>::len), Some(2));
+        assert_eq!(parsed.amount_hints(), None);
+
+        // Fields are kept in tag order whatever order they were added in
+        let tags: Vec<u8> = parsed.fields().iter().map(ExtraField::tag).collect();
+        assert_eq!(tags, vec![TAG_EPHEMERAL_PUBKEYS, TAG_PAYMENT_ID, TAG_MEMO, TAG_POW_NONCE, 0x7F]);
+        assert_eq!(Extra::parse(&[]).unwrap(), Extra::new());
+    }
+
+    #[test]
+    fn test_setting_a_tag_replaces_it() {
+        let mut extra = sample();
+        extra.set(ExtraField::PowNonce(7));
+        assert_eq!(extra.pow_nonce(), Some(7));
+        assert_eq!(extra.fields().len(), 5);
+
+        assert!(extra.remove(TAG_POW_NONCE).is_some());
+        assert_eq!(extra.pow_nonce(), None);
+    }
+
+    #[test]
+    fn test_malformed_extra_rejected() {
+        // Repeated and out-of-order tags
+        let memo = Extra::new().with_field(ExtraField::Memo(vec![1])).to_bytes();
+        assert!(Extra::parse(&[memo.clone(), memo.clone()].concat()).is_err());
+        let nonce = Extra::new().with_field(ExtraField::PowNonce(1)).to_bytes();
+        assert!(Extra::parse(&[nonce, memo].concat()).is_err());
+
+        // Truncated field and wrong-size values
+        let bytes = sample().to_bytes();
+        assert!(Extra::parse(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Extra::parse(&[TAG_PAYMENT_ID, 1, 0, 0, 0, 0]).is_err());
+        assert!(Extra::parse(&[TAG_POW_NONCE, 1, 0, 0, 0, 0]).is_err());
+
+        // Oversized memo
+        let long = Extra::new().with_field(ExtraField::Memo(vec![0u8; MAX_MEMO_SIZE + 1])).to_bytes();
+        assert!(matches!(Extra::parse(&long), Err(NyxError::LimitExceeded(_))));
+    }
+}
@@ -0,0 +1,1039 @@
+// src/finality.rs
+
+//! Finality tracking for integrators.
+//!
+//! Layer 2 PoS validators periodically publish snapshots that anchor a set of
+//! DAG transactions. Everything an anchor references, directly or
+//! indirectly, becomes irreversible at that snapshot. The
+//! [`FinalityTracker`] applies snapshots to the DAG and lets callers:
+//! - Query whether a transaction is final and how many snapshots deep it is
+//! - Await finality (optionally at a minimum depth) without polling
+//!
+//! Snapshots must be applied in sequence and are never rolled back, so once a
+//! transaction is reported final it stays final.
+//!
+//! Each applied snapshot produces a [`SnapshotHeader`] committing to the
+//! transactions it finalized with a Merkle root and chaining to the previous
+//! header. Light clients holding only headers can check an
+//! [`InclusionProof`] served by a full node.
+//!
+//! Headers also carry a state root over everything finalized so far (the
+//! finalized transaction set and the key images they spend). Nodes that
+//! agree on finality produce identical state roots, so comparing headers
+//! with [`FinalityTracker::check_header`] detects divergence quickly.
+//!
+//! A tracker in [`NodeMode::Pruned`] drops transactions finalized before its
+//! last few snapshots from the DAG. Headers are kept, but inclusion proofs
+//! and checkpoints are only served by archive nodes.
+//!
+//! The returned futures do not depend on a specific async runtime.
+
+use crate::checkpoint::Checkpoint;
+use crate::consensus::{SignedSnapshot, ValidatorSet};
+use crate::dag::{DagEvent, DagProcessor, TxState};
+use crate::encoding::Encoder;
+use crate::errors::{NyxError, Result};
+use crate::transaction::Transaction;
+use crate::types::Hash;
+use dashmap::DashMap;
+use nyx_crypto::merkle::{self, MerkleProof};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::JoinHandle;
+
+/// How much finalized history a node keeps
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeMode {
+    /// Keeps the full DAG and serves all of history
+    #[default]
+    Archive,
+
+    /// Keeps only transactions finalized by the most recent snapshots
+    Pruned,
+}
+
+impl fmt::Display for NodeMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeMode::Archive => write!(f, "archive"),
+            NodeMode::Pruned => write!(f, "pruned"),
+        }
+    }
+}
+
+/// A PoS snapshot finalizing part of the DAG
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PosSnapshot {
+    /// Sequence number (the first snapshot is 1)
+    pub number: u64,
+
+    /// Transactions anchored by this snapshot
+    pub anchors: Vec<Hash>,
+}
+
+/// Domain separator for snapshot signatures
+const SNAPSHOT_SIGNING_DOMAIN: &[u8] = b"nyx-pos-snapshot";
+
+impl PosSnapshot {
+    /// Hash validators sign to approve this snapshot
+    pub fn signing_hash(&self) -> Hash {
+        let mut enc = Encoder::new();
+        enc.put_bytes(SNAPSHOT_SIGNING_DOMAIN);
+        enc.put_u64(self.number);
+        enc.put_len(self.anchors.len());
+        for anchor in &self.anchors {
+            enc.put_hash(anchor);
+        }
+        nyx_crypto::hash::blake3_hash(&enc.finish())
+    }
+}
+
+/// Header committing to the transactions finalized by a snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    /// Snapshot sequence number
+    pub number: u64,
+
+    /// Hash of the previous header (all zeros for the first snapshot)
+    pub prev_hash: Hash,
+
+    /// Merkle root of the hashes finalized by this snapshot, sorted
+    pub tx_root: Hash,
+
+    /// Number of transactions finalized by this snapshot
+    pub tx_count: u32,
+
+    /// Commitment to all state finalized up to and including this snapshot
+    pub state_root: Hash,
+}
+
+impl SnapshotHeader {
+    /// Hash identifying this header
+    pub fn hash(&self) -> Hash {
+        let mut enc = Encoder::new();
+        enc.put_u64(self.number);
+        enc.put_hash(&self.prev_hash);
+        enc.put_hash(&self.tx_root);
+        enc.put_u32(self.tx_count);
+        enc.put_hash(&self.state_root);
+        nyx_crypto::hash::blake3_hash(&enc.finish())
+    }
+}
+
+/// Proof that a transaction was finalized by a given snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    /// Transaction being proven
+    pub tx_hash: Hash,
+
+    /// Snapshot that finalized it
+    pub snapshot: u64,
+
+    /// Merkle path to the snapshot's `tx_root`
+    pub proof: MerkleProof,
+}
+
+impl InclusionProof {
+    /// Verifies the proof against the header of its snapshot
+    pub fn verify(&self, header: &SnapshotHeader) -> bool {
+        header.number == self.snapshot && self.proof.verify(&self.tx_hash, &header.tx_root)
+    }
+}
+
+/// Computes a state root from its component Merkle roots
+///
+/// The state root is `merkle_node(key_image_root, finalized_tx_root)`, where
+/// each component is the Merkle root of the sorted set.
+pub fn compute_state_root(key_image_root: &Hash, finalized_tx_root: &Hash) -> Hash {
+    merkle::hash_node(key_image_root, finalized_tx_root)
+}
+
+/// Everything finalized so far, kept sorted for state root computation
+#[derive(Default)]
+pub(crate) struct FinalizedState {
+    tx_hashes: BTreeSet<Hash>,
+    key_images: BTreeSet<[u8; 32]>,
+}
+
+impl FinalizedState {
+    pub(crate) fn record(&mut self, hash: Hash, tx: &Transaction) {
+        self.key_images.extend(tx.inputs.iter().map(|input| input.key_image));
+        self.tx_hashes.insert(hash);
+    }
+
+    pub(crate) fn state_root(&self) -> Hash {
+        let key_images: Vec<[u8; 32]> = self.key_images.iter().copied().collect();
+        let tx_hashes: Vec<Hash> = self.tx_hashes.iter().copied().collect();
+
+        compute_state_root(&merkle::merkle_root(&key_images), &merkle::merkle_root(&tx_hashes))
+    }
+}
+
+/// Shared state behind a tracker and its futures
+struct FinalityState {
+    /// Number of the latest applied snapshot (0 if none)
+    latest: Mutex<u64>,
+
+    /// DAG clock time at which the latest snapshot was applied
+    applied_at: Mutex<Option<u64>>,
+
+    /// Headers of applied snapshots; `headers[n - 1]` is snapshot `n`
+    headers: Mutex<Vec<SnapshotHeader>>,
+
+    /// Sorted hashes finalized by each snapshot
+    snapshot_txs: DashMap<u64, Vec<Hash>>,
+
+    /// Cumulative finalized state committed to by `state_root`
+    finalized: Mutex<FinalizedState>,
+
+    /// Snapshot number at which each transaction became final
+    finalized_at: DashMap<Hash, u64>,
+
+    /// Latest snapshot applied with validator signatures, served to snap sync
+    latest_signed: Mutex<Option<SignedSnapshot>>,
+
+    /// Snapshots up to this number had their transactions pruned (0 if none)
+    pruned_through: Mutex<u64>,
+
+    /// Waker of each future waiting for the next snapshot, by future id
+    wakers: Mutex<HashMap<u64, Waker>>,
+
+    /// Id of the next finality future
+    next_waiter: AtomicU64,
+
+    /// Receivers of applied snapshot headers
+    subscribers: Mutex<Vec<Sender<SnapshotHeader>>>,
+}
+
+/// Tracks PoS finality of DAG transactions
+#[derive(Clone)]
+pub struct FinalityTracker {
+    dag: DagProcessor,
+    state: Arc<FinalityState>,
+
+    /// Snapshots whose transactions are kept in pruned mode
+    retained_snapshots: Option<u64>,
+}
+
+impl FinalityTracker {
+    /// Creates a tracker for the given DAG
+    pub fn new(dag: DagProcessor) -> Self {
+        Self {
+            dag,
+            state: Arc::new(FinalityState {
+                latest: Mutex::new(0),
+                applied_at: Mutex::new(None),
+                headers: Mutex::new(Vec::new()),
+                snapshot_txs: DashMap::new(),
+                finalized: Mutex::new(FinalizedState::default()),
+                finalized_at: DashMap::new(),
+                latest_signed: Mutex::new(None),
+                pruned_through: Mutex::new(0),
+                wakers: Mutex::new(HashMap::new()),
+                next_waiter: AtomicU64::new(0),
+                subscribers: Mutex::new(Vec::new()),
+            }),
+            retained_snapshots: None,
+        }
+    }
+
+    /// Switches to pruned mode, keeping the transactions finalized by the
+    /// last `snapshots` snapshots (at least one)
+    ///
+    /// Older transactions are pruned from the DAG as snapshots are applied.
+    pub fn with_retained_snapshots(mut self, snapshots: u64) -> Self {
+        self.retained_snapshots = Some(snapshots.max(1));
+        self
+    }
+
+    /// Gets whether history is pruned
+    pub fn mode(&self) -> NodeMode {
+        match self.retained_snapshots {
+            Some(_) => NodeMode::Pruned,
+            None => NodeMode::Archive,
+        }
+    }
+
+    /// Gets the number of snapshots whose transactions are kept in pruned
+    /// mode
+    pub fn retained_snapshots(&self) -> Option<u64> {
+        self.retained_snapshots
+    }
+
+    /// Gets the last snapshot whose transactions were pruned (0 if none)
+    pub fn pruned_through(&self) -> u64 {
+        self.state.pruned_through.lock().map_or(0, |pruned| *pruned)
+    }
+
+    /// Checks whether the transactions of a snapshot were pruned
+    pub fn is_pruned(&self, number: u64) -> bool {
+        number <= self.pruned_through()
+    }
+
+    /// Prunes transactions finalized before the last `keep` snapshots from
+    /// the DAG, along with their inclusion proofs
+    ///
+    /// # Errors
+    /// - `DagError` if the DAG fails to prune
+    ///
+    /// # Returns
+    /// Number of transactions pruned
+    pub fn prune(&self, keep: u64) -> Result<usize> {
+        let horizon = self.latest_snapshot().saturating_sub(keep.max(1));
+
+        let mut pruned_through = self.state.pruned_through.lock()
+            .map_err(|e| NyxError::ConsensusError(format!("Lock poisoned: {}", e)))?;
+        if horizon <= *pruned_through {
+            return Ok(0);
+        }
+
+        let hashes: Vec<Hash> = self.state.finalized_at.iter()
+            .filter(|entry| *entry.value() > *pruned_through && *entry.value() <= horizon)
+            .map(|entry| *entry.key())
+            .collect();
+        let pruned = self.dag.prune(&hashes)?;
+
+        self.state.snapshot_txs.retain(|number, _| *number > horizon);
+        *pruned_through = horizon;
+
+        Ok(pruned)
+    }
+
+    /// Applies a PoS snapshot, finalizing its anchors and their ancestors
+    ///
+    /// In pruned mode, transactions that fall out of the retained snapshots
+    /// are pruned afterwards.
+    ///
+    /// # Errors
+    /// - `ConsensusError` if the snapshot is out of sequence
+    /// - `TransactionNotFound` if an anchor is not in the DAG
+    /// - `DagError` if pruning fails
+    ///
+    /// # Returns
+    /// Number of transactions newly finalized
+    pub fn apply_snapshot(&self, snapshot: &PosSnapshot) -> Result<usize> {
+        let mut latest = self.state.latest.lock()
+            .map_err(|e| NyxError::ConsensusError(format!("Lock poisoned: {}", e)))?;
+
+        if snapshot.number != *latest + 1 {
+            return Err(NyxError::ConsensusError(format!(
+                "Snapshot {} out of sequence (latest applied: {})",
+                snapshot.number, *latest
+            )));
+        }
+
+        for anchor in &snapshot.anchors {
+            if !self.dag.storage().has_transaction(anchor)? {
+                return Err(NyxError::TransactionNotFound(format!(
+                    "Snapshot anchor not found: {}",
+                    hex::encode(anchor)
+                )));
+            }
+        }
+
+        // Walk back from the anchors, stopping at already-final history
+        let mut newly_final = Vec::new();
+        let mut queue: VecDeque<Hash> = snapshot.anchors.iter().copied().collect();
+        while let Some(hash) = queue.pop_front() {
+            if self.state.finalized_at.contains_key(&hash) {
+                continue;
+            }
+
+            self.state.finalized_at.insert(hash, snapshot.number);
+            newly_final.push(hash);
+
+            queue.extend(self.dag.get_parents(&hash)?);
+        }
+        self.dag.finalize_transactions(&newly_final)?;
+        newly_final.sort_unstable();
+
+        let state_root = {
+            let mut finalized = self.state.finalized.lock()
+                .map_err(|e| NyxError::ConsensusError(format!("Lock poisoned: {}", e)))?;
+            for hash in &newly_final {
+                finalized.record(*hash, &*self.dag.storage().get_transaction_shared(hash)?);
+            }
+            finalized.state_root()
+        };
+
+        let mut headers = self.state.headers.lock()
+            .map_err(|e| NyxError::ConsensusError(format!("Lock poisoned: {}", e)))?;
+        let header = SnapshotHeader {
+            number: snapshot.number,
+            prev_hash: headers.last().map_or([0u8; 32], |h| h.hash()),
+            tx_root: merkle::merkle_root(&newly_final),
+            tx_count: newly_final.len() as u32,
+            state_root,
+        };
+        headers.push(header.clone());
+        drop(headers);
+
+        let newly_final_count = newly_final.len();
+        self.state.snapshot_txs.insert(snapshot.number, newly_final);
+
+        *latest = snapshot.number;
+        self.record_applied();
+        drop(latest);
+
+        self.wake_all();
+        self.notify(header);
+
+        if let Some(keep) = self.retained_snapshots {
+            self.prune(keep)?;
+        }
+
+        Ok(newly_final_count)
+    }
+
+    /// Applies the next snapshot in sequence with the given anchors
+    ///
+    /// # Returns
+    /// Number of transactions newly finalized
+    pub fn apply_next_snapshot(&self, anchors: Vec<Hash>) -> Result<usize> {
+        self.apply_snapshot(&PosSnapshot {
+            number: self.latest_snapshot() + 1,
+            anchors,
+        })
+    }
+
+    /// Finalizes every transaction as soon as it is added to the DAG
+    ///
+    /// Development networks have no validators, so each added transaction is
+    /// anchored by its own snapshot on a background thread. The thread exits
+    /// when the DAG is dropped, or with the first error.
+    ///
+    /// # Errors
+    /// - `ConsensusError` if the DAG's network does not have instant finality
+    pub fn spawn_instant_finality(&self) -> Result<JoinHandle<Result<()>>> {
+        if !self.dag.network().instant_finality() {
+            return Err(NyxError::ConsensusError(format!(
+                "{} does not have instant finality",
+                self.dag.network()
+            )));
+        }
+
+        let events = self.dag.subscribe();
+        let tracker = self.clone();
+        std::thread::Builder::new()
+            .name("nyx-instant-finality".to_string())
+            .spawn(move || {
+                for event in events {
+                    if let DagEvent::TransactionAdded { hash, .. } = event {
+                        tracker.apply_next_snapshot(vec![hash])?;
+                    }
+                }
+                Ok(())
+            })
+            .map_err(|e| NyxError::ConsensusError(format!("Failed to spawn finality thread: {}", e)))
+    }
+
+    /// Verifies validator signatures, then applies the snapshot
+    ///
+    /// # Errors
+    /// - `CryptoError` if any signature is invalid
+    /// - `ConsensusError` if signers hold less than two thirds of the stake,
+    ///   or the snapshot is out of sequence
+    /// - `TransactionNotFound` if an anchor is not in the DAG
+    ///
+    /// # Returns
+    /// Number of transactions newly finalized
+    pub fn apply_signed_snapshot(&self, signed: &SignedSnapshot, validators: &ValidatorSet) -> Result<usize> {
+        validators.verify(signed)?;
+        let finalized = self.apply_snapshot(&signed.snapshot)?;
+
+        if let Ok(mut latest_signed) = self.state.latest_signed.lock() {
+            *latest_signed = Some(signed.clone());
+        }
+        Ok(finalized)
+    }
+
+    /// Builds a checkpoint of the finalized state for snap sync
+    ///
+    /// Returns `None` unless the latest snapshot was applied through
+    /// [`apply_signed_snapshot`](Self::apply_signed_snapshot), or once
+    /// history was pruned.
+    pub fn checkpoint(&self) -> Result<Option<Checkpoint>> {
+        if self.pruned_through() > 0 {
+            return Ok(None);
+        }
+
+        // Hold the sequence lock so state and headers stay consistent
+        let latest = self.state.latest.lock()
+            .map_err(|e| NyxError::ConsensusError(format!("Lock poisoned: {}", e)))?;
+
+        let signed = match self.state.latest_signed.lock() {
+            Ok(signed) => match signed.as_ref() {
+                Some(signed) if signed.snapshot.number == *latest => signed.clone(),
+                _ => return Ok(None),
+            },
+            Err(_) => return Ok(None),
+        };
+
+        let mut state = self.dag.snapshot()?;
+        state.entries.retain(|e| e.state == TxState::Finalized);
+
+        // Finalized transactions no other finalized transaction references
+        let referenced: HashSet<Hash> = state.entries.iter()
+            .flat_map(|e| e.tx.references)
+            .collect();
+        state.tips = state.entries.iter()
+            .map(|e| e.tx.id())
+            .filter(|hash| !referenced.contains(hash))
+            .collect();
+        state.tips.sort_unstable();
+
+        let headers = self.headers(1, usize::MAX);
+        drop(latest);
+
+        Ok(Some(Checkpoint { signed, headers, state }))
+    }
+
+    /// Starts from a verified checkpoint instead of replaying history
+    ///
+    /// The tracker and its DAG must be empty. Transactions restored from the
+    /// checkpoint are all recorded as finalized by the checkpoint snapshot,
+    /// and inclusion proofs are only available for later snapshots.
+    ///
+    /// # Errors
+    /// - Any error from [`Checkpoint::verify`]
+    /// - `ConsensusError` if snapshots were already applied
+    /// - `DagError` if the DAG is not empty
+    ///
+    /// # Returns
+    /// Number of transactions restored
+    pub fn restore_checkpoint(&self, checkpoint: Checkpoint, validators: &ValidatorSet) -> Result<usize> {
+        checkpoint.verify(validators)?;
+
+        let mut latest = self.state.latest.lock()
+            .map_err(|e| NyxError::ConsensusError(format!("Lock poisoned: {}", e)))?;
+        if *latest != 0 {
+            return Err(NyxError::ConsensusError(
+                "Cannot restore a checkpoint after applying snapshots".to_string()
+            ));
+        }
+
+        let Checkpoint { signed, headers, state } = checkpoint;
+        let number = signed.snapshot.number;
+
+        let mut finalized = FinalizedState::default();
+        let mut hashes = Vec::with_capacity(state.entries.len());
+        for entry in &state.entries {
+            let hash = entry.tx.id();
+            finalized.record(hash, &entry.tx);
+            hashes.push(hash);
+        }
+
+        let restored = self.dag.restore_snapshot(state)?;
+
+        for hash in hashes {
+            self.state.finalized_at.insert(hash, number);
+        }
+        *self.state.finalized.lock()
+            .map_err(|e| NyxError::ConsensusError(format!("Lock poisoned: {}", e)))? = finalized;
+
+        *self.state.headers.lock()
+            .map_err(|e| NyxError::ConsensusError(format!("Lock poisoned: {}", e)))? = headers;
+        if let Ok(mut latest_signed) = self.state.latest_signed.lock() {
+            *latest_signed = Some(signed);
+        }
+
+        *latest = number;
+        self.record_applied();
+        drop(latest);
+
+        self.wake_all();
+
+        Ok(restored)
+    }
+
+    /// Gets the header of an applied snapshot
+    pub fn header(&self, number: u64) -> Option<SnapshotHeader> {
+        let index = usize::try_from(number.checked_sub(1)?).ok()?;
+        self.state.headers.lock().ok()?.get(index).cloned()
+    }
+
+    /// Gets up to `limit` consecutive headers starting at `from`
+    pub fn headers(&self, from: u64, limit: usize) -> Vec<SnapshotHeader> {
+        let Ok(headers) = self.state.headers.lock() else {
+            return Vec::new();
+        };
+        let start = usize::try_from(from.saturating_sub(1)).unwrap_or(usize::MAX);
+
+        headers.iter().skip(start).take(limit).cloned().collect()
+    }
+
+    /// Gets the header of the latest applied snapshot
+    pub fn latest_header(&self) -> Option<SnapshotHeader> {
+        self.state.headers.lock().ok()?.last().cloned()
+    }
+
+    /// Compares a header received from another node with the local one
+    ///
+    /// Returns `Ok(false)` if the snapshot has not been applied locally yet.
+    ///
+    /// # Errors
+    /// - `ConsensusError` if the local header for the same snapshot differs
+    ///   (divergent finalized state)
+    pub fn check_header(&self, remote: &SnapshotHeader) -> Result<bool> {
+        let Some(local) = self.header(remote.number) else {
+            return Ok(false);
+        };
+
+        if local.state_root != remote.state_root {
+            return Err(NyxError::ConsensusError(format!(
+                "State root mismatch at snapshot {}: local {}, remote {}",
+                remote.number,
+                hex::encode(local.state_root),
+                hex::encode(remote.state_root)
+            )));
+        }
+        if local != *remote {
+            return Err(NyxError::ConsensusError(format!(
+                "Header mismatch at snapshot {}",
+                remote.number
+            )));
+        }
+
+        Ok(true)
+    }
+
+    /// Builds a proof that a transaction is final
+    ///
+    /// Returns `None` if the transaction has not been finalized.
+    pub fn inclusion_proof(&self, tx_hash: &Hash) -> Option<InclusionProof> {
+        let snapshot = self.finalized_at(tx_hash)?;
+        let txs = self.state.snapshot_txs.get(&snapshot)?;
+        let index = txs.binary_search(tx_hash).ok()?;
+
+        Some(InclusionProof {
+            tx_hash: *tx_hash,
+            snapshot,
+            proof: merkle::prove(&txs, index)?,
+        })
+    }
+
+    /// Returns the number of the latest applied snapshot (0 if none)
+    pub fn latest_snapshot(&self) -> u64 {
+        self.state.latest.lock().map_or(0, |latest| *latest)
+    }
+
+    /// Seconds since the latest snapshot was applied, by the DAG's clock
+    ///
+    /// Returns `None` if no snapshot has been applied yet.
+    pub fn latest_snapshot_age(&self) -> Option<u64> {
+        let applied_at = (*self.state.applied_at.lock().ok()?)?;
+        Some(self.dag.clock().now().saturating_sub(applied_at))
+    }
+
+    fn record_applied(&self) {
+        if let Ok(mut applied_at) = self.state.applied_at.lock() {
+            *applied_at = Some(self.dag.clock().now());
+        }
+    }
+
+    /// Checks whether a transaction is final
+    pub fn is_final(&self, tx_hash: &Hash) -> bool {
+        self.state.finalized_at.contains_key(tx_hash)
+    }
+
+    /// Gets the snapshot number at which a transaction became final
+    pub fn finalized_at(&self, tx_hash: &Hash) -> Option<u64> {
+        self.state.finalized_at.get(tx_hash).map(|n| *n)
+    }
+
+    /// Gets the finality depth of a transaction
+    ///
+    /// Depth is the number of snapshots applied since (and including) the one
+    /// that finalized the transaction, so a transaction finalized by the
+    /// latest snapshot has depth 1.
+    pub fn finality_depth(&self, tx_hash: &Hash) -> Option<u64> {
+        let finalized_at = self.finalized_at(tx_hash)?;
+        Some(self.latest_snapshot() - finalized_at + 1)
+    }
+
+    /// Waits until a transaction is final
+    ///
+    /// Resolves to the snapshot number that finalized it.
+    pub fn wait_for_finality(&self, tx_hash: Hash) -> FinalityFuture {
+        self.wait_for_finality_depth(tx_hash, 1)
+    }
+
+    /// Waits until a transaction is final at least `depth` snapshots deep
+    ///
+    /// Resolves to the snapshot number that finalized it.
+    pub fn wait_for_finality_depth(&self, tx_hash: Hash, depth: u64) -> FinalityFuture {
+        FinalityFuture {
+            tracker: self.clone(),
+            id: self.state.next_waiter.fetch_add(1, Ordering::Relaxed),
+            tx_hash,
+            depth: depth.max(1),
+        }
+    }
+
+    /// Subscribes to the headers of snapshots as they are applied
+    ///
+    /// The channel closes when the tracker and all its clones are dropped.
+    pub fn subscribe(&self) -> Receiver<SnapshotHeader> {
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subscribers) = self.state.subscribers.lock() {
+            subscribers.push(tx);
+        }
+        rx
+    }
+
+    /// Sends a header to all subscribers, dropping disconnected ones
+    fn notify(&self, header: SnapshotHeader) {
+        if let Ok(mut subscribers) = self.state.subscribers.lock() {
+            subscribers.retain(|subscriber| subscriber.send(header.clone()).is_ok());
+        }
+    }
+
+    fn wake_all(&self) {
+        let wakers = match self.state.wakers.lock() {
+            Ok(mut wakers) => std::mem::take(&mut *wakers),
+            Err(_) => return,
+        };
+
+        for waker in wakers.into_values() {
+            waker.wake();
+        }
+    }
+
+    fn check(&self, tx_hash: &Hash, depth: u64) -> Option<u64> {
+        let finalized_at = self.finalized_at(tx_hash)?;
+        (self.latest_snapshot() - finalized_at + 1 >= depth).then_some(finalized_at)
+    }
+}
+
+/// Future returned by [`FinalityTracker::wait_for_finality`]
+///
+/// A pending future holds one waker slot, replaced on each poll and freed
+/// when the future is dropped.
+pub struct FinalityFuture {
+    tracker: FinalityTracker,
+    id: u64,
+    tx_hash: Hash,
+    depth: u64,
+}
+
+impl Future for FinalityFuture {
+    type Output = u64;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<u64> {
+        if let Some(number) = self.tracker.check(&self.tx_hash, self.depth) {
+            return Poll::Ready(number);
+        }
+
+        if let Ok(mut wakers) = self.tracker.state.wakers.lock() {
+            match wakers.get_mut(&self.id) {
+                Some(waker) if waker.will_wake(cx.waker()) => {}
+                Some(waker) => waker.clone_from(cx.waker()),
+                None => {
+                    wakers.insert(self.id, cx.waker().clone());
+                }
+            }
+        }
+
+        // Re-check in case a snapshot landed before the waker was registered
+        match self.tracker.check(&self.tx_hash, self.depth) {
+            Some(number) => Poll::Ready(number),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for FinalityFuture {
+    fn drop(&mut self) {
+        if let Ok(mut wakers) = self.tracker.state.wakers.lock() {
+            wakers.remove(&self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use crate::testing::test_tx;
+    use crate::transaction::TxOutput;
+    use crate::verification::VerifyMode;
+
+    /// Builds g1, g2 <- a <- b
+    fn build_dag() -> (DagProcessor, [Hash; 4]) {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let g1 = storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 1)).unwrap();
+        let g2 = storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 2)).unwrap();
+        let a = dag.add_transaction_with_mode(test_tx(g1, g2, 3), VerifyMode::HeadersOnly).unwrap();
+        let b = dag.add_transaction_with_mode(test_tx(a, g2, 4), VerifyMode::HeadersOnly).unwrap();
+
+        (dag, [g1, g2, a, b])
+    }
+
+    #[test]
+    fn test_latest_snapshot_age_follows_clock() {
+        let clock = crate::clock::ManualClock::new(1_000);
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone()).with_clock(Arc::new(clock.clone()));
+        let g1 = storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 1)).unwrap();
+        let tracker = FinalityTracker::new(dag);
+        assert_eq!(tracker.latest_snapshot_age(), None);
+
+        clock.advance(30);
+        tracker.apply_next_snapshot(vec![g1]).unwrap();
+        assert_eq!(tracker.latest_snapshot_age(), Some(0));
+
+        clock.advance(45);
+        assert_eq!(tracker.latest_snapshot_age(), Some(45));
+    }
+
+    #[test]
+    fn test_subscribers_receive_applied_headers() {
+        let (dag, [_, _, a, b]) = build_dag();
+        let tracker = FinalityTracker::new(dag);
+        let headers = tracker.subscribe();
+
+        tracker.apply_snapshot(&PosSnapshot { number: 1, anchors: vec![a] }).unwrap();
+        tracker.apply_snapshot(&PosSnapshot { number: 2, anchors: vec![b] }).unwrap();
+
+        let first = headers.try_recv().unwrap();
+        let second = headers.try_recv().unwrap();
+        assert_eq!((first.number, first.tx_count), (1, 3));
+        assert_eq!(second.prev_hash, first.hash());
+        assert_eq!(Some(second), tracker.latest_header());
+        assert!(headers.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_snapshot_finalizes_ancestors() {
+        let (dag, [g1, g2, a, b]) = build_dag();
+        let tracker = FinalityTracker::new(dag.clone());
+
+        let finalized = tracker.apply_snapshot(&PosSnapshot { number: 1, anchors: vec![a] }).unwrap();
+        assert_eq!(finalized, 3);
+        assert!(tracker.is_final(&g1) && tracker.is_final(&g2) && tracker.is_final(&a));
+        assert!(!tracker.is_final(&b));
+        assert_eq!(dag.get_state(&a).unwrap(), crate::dag::TxState::Finalized);
+
+        let finalized = tracker.apply_snapshot(&PosSnapshot { number: 2, anchors: vec![b] }).unwrap();
+        assert_eq!(finalized, 1);
+        assert_eq!(tracker.finality_depth(&a), Some(2));
+        assert_eq!(tracker.finality_depth(&b), Some(1));
+    }
+
+    #[test]
+    fn test_pruned_mode_drops_old_history() {
+        let (dag, [g1, g2, a, b]) = build_dag();
+        let tracker = FinalityTracker::new(dag.clone()).with_retained_snapshots(1);
+        assert_eq!(tracker.mode(), NodeMode::Pruned);
+
+        tracker.apply_next_snapshot(vec![a]).unwrap();
+        assert_eq!(tracker.pruned_through(), 0);
+
+        tracker.apply_next_snapshot(vec![b]).unwrap();
+        assert_eq!(tracker.pruned_through(), 1);
+        assert!(tracker.is_pruned(1) && !tracker.is_pruned(2));
+        for hash in [g1, g2, a] {
+            assert!(dag.storage().is_pruned(&hash));
+            assert!(!dag.storage().has_transaction(&hash).unwrap());
+            assert!(tracker.is_final(&hash));
+        }
+        assert!(dag.storage().has_transaction(&b).unwrap());
+
+        // Headers stay, proofs and checkpoints of pruned history do not
+        assert!(tracker.header(1).is_some());
+        assert!(tracker.inclusion_proof(&a).is_none());
+        assert!(tracker.inclusion_proof(&b).is_some());
+        assert!(tracker.checkpoint().unwrap().is_none());
+
+        // New transactions may still reference pruned parents
+        let c = dag.add_transaction_with_mode(test_tx(b, a, 5), VerifyMode::HeadersOnly).unwrap();
+        assert_eq!(dag.get_parents(&c).unwrap(), vec![b]);
+        crate::invariants::check_invariants(&dag).unwrap();
+    }
+
+    #[test]
+    fn test_archive_mode_keeps_history() {
+        let (dag, [_, _, a, b]) = build_dag();
+        let tracker = FinalityTracker::new(dag.clone());
+        assert_eq!(tracker.mode(), NodeMode::Archive);
+
+        tracker.apply_next_snapshot(vec![a]).unwrap();
+        tracker.apply_next_snapshot(vec![b]).unwrap();
+        assert_eq!(tracker.pruned_through(), 0);
+        assert!(dag.storage().has_transaction(&a).unwrap());
+        assert!(tracker.inclusion_proof(&a).is_some());
+    }
+
+    #[test]
+    fn test_signed_snapshot_requires_quorum() {
+        use crate::consensus::Validator;
+        use nyx_crypto::keys::ed25519_public_key;
+
+        let (dag, [_, _, a, _]) = build_dag();
+        let tracker = FinalityTracker::new(dag);
+
+        let keys: Vec<[u8; 32]> = (0..3u8).map(|i| [i; 32]).collect();
+        let validators = ValidatorSet::new(
+            keys.iter().map(|k| Validator { public_key: ed25519_public_key(k).to_vec(), stake: 10 }).collect(),
+        )
+        .unwrap();
+
+        let mut signed = SignedSnapshot::new(PosSnapshot { number: 1, anchors: vec![a] });
+        signed.sign(0, &keys[0]).unwrap();
+
+        let result = tracker.apply_signed_snapshot(&signed, &validators);
+        assert!(matches!(result, Err(NyxError::ConsensusError(_))));
+        assert!(!tracker.is_final(&a));
+
+        signed.sign(2, &keys[2]).unwrap();
+        assert_eq!(tracker.apply_signed_snapshot(&signed, &validators).unwrap(), 3);
+        assert!(tracker.is_final(&a));
+    }
+
+    #[test]
+    fn test_snapshot_sequence_enforced() {
+        let (dag, [_, _, a, _]) = build_dag();
+        let tracker = FinalityTracker::new(dag);
+
+        let result = tracker.apply_snapshot(&PosSnapshot { number: 2, anchors: vec![a] });
+        assert!(matches!(result, Err(NyxError::ConsensusError(_))));
+
+        let result = tracker.apply_snapshot(&PosSnapshot { number: 1, anchors: vec![[9u8; 32]] });
+        assert!(matches!(result, Err(NyxError::TransactionNotFound(_))));
+        assert_eq!(tracker.latest_snapshot(), 0);
+    }
+
+    #[test]
+    fn test_headers_and_inclusion_proofs() {
+        let (dag, [g1, _, a, b]) = build_dag();
+        let tracker = FinalityTracker::new(dag);
+
+        tracker.apply_snapshot(&PosSnapshot { number: 1, anchors: vec![a] }).unwrap();
+        tracker.apply_snapshot(&PosSnapshot { number: 2, anchors: vec![b] }).unwrap();
+
+        let h1 = tracker.header(1).unwrap();
+        let h2 = tracker.header(2).unwrap();
+        assert_eq!(h1.prev_hash, [0u8; 32]);
+        assert_eq!(h2.prev_hash, h1.hash());
+        assert_eq!((h1.tx_count, h2.tx_count), (3, 1));
+        assert_eq!(tracker.headers(1, 10), vec![h1.clone(), h2.clone()]);
+        assert!(tracker.header(3).is_none());
+
+        let proof = tracker.inclusion_proof(&g1).unwrap();
+        assert_eq!(proof.snapshot, 1);
+        assert!(proof.verify(&h1));
+        assert!(!proof.verify(&h2));
+
+        let mut forged = proof.clone();
+        forged.tx_hash = b;
+        assert!(!forged.verify(&h1));
+    }
+
+    #[test]
+    fn test_state_root_detects_divergence() {
+        let (dag, [g1, g2, a, b]) = build_dag();
+        let tracker = FinalityTracker::new(dag);
+        tracker.apply_snapshot(&PosSnapshot { number: 1, anchors: vec![a] }).unwrap();
+
+        let (other_dag, _) = build_dag();
+        let same = FinalityTracker::new(other_dag);
+        same.apply_snapshot(&PosSnapshot { number: 1, anchors: vec![a] }).unwrap();
+
+        let (other_dag, _) = build_dag();
+        let diverged = FinalityTracker::new(other_dag);
+        diverged.apply_snapshot(&PosSnapshot { number: 1, anchors: vec![b] }).unwrap();
+
+        let header = tracker.latest_header().unwrap();
+        let mut key_images = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+        key_images.sort_unstable();
+        let mut txs = vec![g1, g2, a];
+        txs.sort_unstable();
+        assert_eq!(
+            header.state_root,
+            compute_state_root(&merkle::merkle_root(&key_images), &merkle::merkle_root(&txs))
+        );
+
+        assert!(same.check_header(&header).unwrap());
+        assert!(matches!(diverged.check_header(&header), Err(NyxError::ConsensusError(_))));
+
+        let mut future = header.clone();
+        future.number = 2;
+        assert!(!tracker.check_header(&future).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_finality() {
+        let (dag, [_, _, a, b]) = build_dag();
+        let tracker = FinalityTracker::new(dag);
+
+        let waiter = tokio::spawn(tracker.wait_for_finality_depth(a, 2));
+
+        tracker.apply_snapshot(&PosSnapshot { number: 1, anchors: vec![a] }).unwrap();
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+
+        tracker.apply_snapshot(&PosSnapshot { number: 2, anchors: vec![b] }).unwrap();
+        assert_eq!(waiter.await.unwrap(), 1);
+
+        // Already final resolves immediately
+        assert_eq!(tracker.wait_for_finality(b).await, 2);
+    }
+
+    #[test]
+    fn test_repeated_polls_keep_one_waker() {
+        let (dag, [_, _, a, b]) = build_dag();
+        let tracker = FinalityTracker::new(dag);
+        let mut cx = Context::from_waker(Waker::noop());
+
+        let mut first = Box::pin(tracker.wait_for_finality(a));
+        let mut second = Box::pin(tracker.wait_for_finality(b));
+        for _ in 0..100 {
+            assert!(first.as_mut().poll(&mut cx).is_pending());
+            assert!(second.as_mut().poll(&mut cx).is_pending());
+        }
+        assert_eq!(tracker.state.wakers.lock().unwrap().len(), 2);
+
+        drop(second);
+        assert_eq!(tracker.state.wakers.lock().unwrap().len(), 1);
+        tracker.apply_snapshot(&PosSnapshot { number: 1, anchors: vec![a] }).unwrap();
+        assert!(tracker.state.wakers.lock().unwrap().is_empty());
+        assert_eq!(first.as_mut().poll(&mut cx), Poll::Ready(1));
+    }
+
+    #[tokio::test]
+    async fn test_instant_finality_on_regtest() {
+        use crate::network::NetworkId;
+
+        let (dag, _) = build_dag();
+        assert!(FinalityTracker::new(dag).spawn_instant_finality().is_err());
+
+        let dag = DagProcessor::new(MemoryStorage::new()).with_network(NetworkId::Regtest);
+        let tracker = FinalityTracker::new(dag.clone());
+        tracker.spawn_instant_finality().unwrap();
+
+        let output = |nonce: u8| TxOutput {
+            stealth_address: vec![nonce; 32],
+            amount_commitment: vec![],
+            range_proof: vec![],
+            ephemeral_pubkey: vec![],
+            unlock_time: None,
+            htlc: None,
+        };
+        let first = dag.mint(vec![output(1)]).unwrap();
+        let second = dag.mint(vec![output(2)]).unwrap();
+
+        assert_eq!(tracker.wait_for_finality(first).await, 1);
+        assert_eq!(tracker.wait_for_finality(second).await, 2);
+        assert_eq!(dag.get_state(&second).unwrap(), TxState::Finalized);
+    }
+}
@@ -0,0 +1,92 @@
+// src/fuzzing.rs
+
+//! [`Arbitrary`] implementations for fuzz targets.
+//!
+//! Enabled by the `arbitrary` feature. Generated values stay within the
+//! default [`DecodeLimits`](crate::limits::DecodeLimits), so structured fuzz
+//! targets exercise encode/decode round trips instead of tripping over the
+//! limits on every input.
+
+use crate::limits::{MAX_EXTRA_SIZE, MAX_RING_SIZE, MAX_TX_INPUTS, MAX_TX_OUTPUTS};
+use crate::network::NetworkId;
+use crate::transaction::{Transaction, TxInput, TxOutput};
+use arbitrary::{Arbitrary, Result, Unstructured};
+use nyx_crypto::ring::RingSignature;
+
+/// Generates a vector of at most `max` elements
+pub fn bounded_vec<'a, T: Arbitrary<'a>>(u: &mut Unstructured<'a>, max: usize) -> Result<Vec<T>> {
+    let len = u.arbitrary_len::<T>()?.min(max);
+    (0..len).map(|_| T::arbitrary(u)).collect()
+}
+
+/// Builds a ring signature, which is foreign to this crate, from fuzz input
+fn arbitrary_ring_signature(u: &mut Unstructured<'_>) -> Result<RingSignature> {
+    Ok(RingSignature {
+        ring_members: bounded_vec(u, MAX_RING_SIZE)?,
+        signature: u.arbitrary()?,
+        key_image: u.arbitrary()?,
+    })
+}
+
+impl<'a> Arbitrary<'a> for NetworkId {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(*u.choose(&NetworkId::ALL)?)
+    }
+}
+
+impl<'a> Arbitrary<'a> for TxInput {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            prev_tx: u.arbitrary()?,
+            index: u.arbitrary()?,
+            key_image: u.arbitrary()?,
+            ring_indices: bounded_vec(u, MAX_RING_SIZE)?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for TxOutput {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            stealth_address: u.arbitrary()?,
+            amount_commitment: u.arbitrary()?,
+            range_proof: u.arbitrary()?,
+            ephemeral_pubkey: u.arbitrary()?,
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Transaction {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            version: u.arbitrary()?,
+            network: u.arbitrary()?,
+            inputs: bounded_vec(u, MAX_TX_INPUTS)?,
+            outputs: bounded_vec(u, MAX_TX_OUTPUTS)?,
+            ring_signatures: {
+                let len = u.int_in_range(0..=MAX_TX_INPUTS)?;
+                (0..len).map(|_| arbitrary_ring_signature(u)).collect::<Result<_>>()?
+            },
+            tx_key: u.arbitrary()?,
+            references: u.arbitrary()?,
+            timestamp: u.arbitrary()?,
+            extra: bounded_vec(u, MAX_EXTRA_SIZE)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::{decode_transaction, encode_transaction};
+
+    #[test]
+    fn test_arbitrary_transactions_roundtrip() {
+        let data: Vec<u8> = (0..4096u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8).collect();
+        let mut u = Unstructured::new(&data);
+        while !u.is_empty() {
+            let tx = Transaction::arbitrary(&mut u).unwrap();
+            assert_eq!(decode_transaction(&encode_transaction(&tx)).unwrap(), tx);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+// src/graph.rs
+
+//! DAG visualization export.
+//!
+//! Renders a neighborhood of the DAG as Graphviz DOT or GraphML so tip
+//! selection and conflict behavior can be inspected visually. Nodes are
+//! filled by state (pending, confirmed, finalized, conflicted), outlined
+//! more heavily as their confirmation score grows, and tips are dashed.
+//! Edges run from a transaction to the parents it approves.
+
+use crate::dag::TxState;
+use crate::types::Hash;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// Maximum number of transactions in one exported graph
+pub const MAX_GRAPH_NODES: usize = 1000;
+
+/// Hex digits of a hash shown in node labels
+const LABEL_HASH_DIGITS: usize = 8;
+
+/// Part of the DAG to export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphRange {
+    /// A transaction with its ancestors and descendants up to `depth`
+    /// levels away
+    Around {
+        /// Transaction at the center
+        center: Hash,
+        /// Levels to include in each direction
+        depth: usize,
+    },
+
+    /// The current tips and their ancestors up to `depth` levels back
+    Tips {
+        /// Levels to include behind the tips
+        depth: usize,
+    },
+}
+
+/// Output format of an exported graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphFormat {
+    /// Graphviz DOT
+    #[default]
+    Dot,
+
+    /// GraphML (XML)
+    GraphMl,
+}
+
+impl GraphFormat {
+    /// MIME type of the format
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "text/vnd.graphviz",
+            GraphFormat::GraphMl => "application/graphml+xml",
+        }
+    }
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(GraphFormat::Dot),
+            "graphml" => Ok(GraphFormat::GraphMl),
+            other => Err(format!("Unknown graph format '{}'", other)),
+        }
+    }
+}
+
+/// A transaction in an exported graph
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphNode {
+    /// Transaction hash
+    pub hash: Hash,
+
+    /// Current state
+    pub state: TxState,
+
+    /// Confirmation score
+    pub score: f64,
+
+    /// Whether the transaction is a tip
+    pub tip: bool,
+
+    /// Parents within the exported graph
+    pub parents: Vec<Hash>,
+}
+
+/// An exported neighborhood of the DAG
+#[derive(Debug, Clone, PartialEq)]
+pub struct DagGraph {
+    /// Transactions, parents before children
+    pub nodes: Vec<GraphNode>,
+
+    /// Score at which transactions confirm, for scaling outlines
+    pub confirmation_threshold: f64,
+}
+
+impl DagGraph {
+    /// Renders the graph in the given format
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::GraphMl => self.to_graphml(),
+        }
+    }
+
+    /// Renders the graph as a Graphviz digraph
+    pub fn to_dot(&self) -> String {
+        render_dot(&self.nodes, self.confirmation_threshold)
+    }
+
+    /// Renders the graph as a GraphML document
+    pub fn to_graphml(&self) -> String {
+        render_graphml(&self.nodes)
+    }
+}
+
+fn render_dot(nodes: &[GraphNode], confirmation_threshold: f64) -> String {
+    let mut out = String::from("digraph nyx_dag {\n");
+    out.push_str("    rankdir=RL;\n");
+    out.push_str("    node [shape=box, style=filled, fontname=monospace];\n");
+
+    for node in nodes {
+        let hash = hex::encode(node.hash);
+        let style = if node.tip { "filled,dashed" } else { "filled" };
+        let _ = writeln!(
+            out,
+            "    \"{}\" [label=\"{}\\n{:?} {:.1}\", fillcolor=\"{}\", penwidth={:.1}, style=\"{}\"];",
+            hash,
+            &hash[..LABEL_HASH_DIGITS],
+            node.state,
+            node.score,
+            state_color(node.state),
+            pen_width(node.score, confirmation_threshold),
+            style,
+        );
+    }
+    for node in nodes {
+        for parent in &node.parents {
+            let _ = writeln!(out, "    \"{}\" -> \"{}\";", hex::encode(node.hash), hex::encode(parent));
+        }
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+fn render_graphml(nodes: &[GraphNode]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    out.push_str("  <key id=\"state\" for=\"node\" attr.name=\"state\" attr.type=\"string\"/>\n");
+    out.push_str("  <key id=\"score\" for=\"node\" attr.name=\"score\" attr.type=\"double\"/>\n");
+    out.push_str("  <key id=\"tip\" for=\"node\" attr.name=\"tip\" attr.type=\"boolean\"/>\n");
+    out.push_str("  <key id=\"color\" for=\"node\" attr.name=\"color\" attr.type=\"string\"/>\n");
+    out.push_str("  <graph id=\"nyx_dag\" edgedefault=\"directed\">\n");
+
+    for node in nodes {
+        let _ = writeln!(out, "    <node id=\"{}\">", hex::encode(node.hash));
+        let _ = writeln!(out, "      <data key=\"state\">{:?}</data>", node.state);
+        let _ = writeln!(out, "      <data key=\"score\">{}</data>", node.score);
+        let _ = writeln!(out, "      <data key=\"tip\">{}</data>", node.tip);
+        let _ = writeln!(out, "      <data key=\"color\">{}</data>", state_color(node.state));
+        out.push_str("    </node>\n");
+    }
+    for node in nodes {
+        for parent in &node.parents {
+            let _ = writeln!(
+                out,
+                "    <edge source=\"{}\" target=\"{}\"/>",
+                hex::encode(node.hash),
+                hex::encode(parent)
+            );
+        }
+    }
+
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+/// Fill color of a node in the given state
+fn state_color(state: TxState) -> &'static str {
+    match state {
+        TxState::Pending => "#ffffff",
+        TxState::Confirmed => "#b7e4c7",
+        TxState::Finalized => "#40916c",
+        TxState::Conflicted => "#e5383b",
+    }
+}
+
+/// Outline width growing with the score, up to 4 at the confirmation
+/// threshold
+fn pen_width(score: f64, confirmation_threshold: f64) -> f64 {
+    if confirmation_threshold <= 0.0 {
+        return 4.0;
+    }
+    1.0 + 3.0 * (score / confirmation_threshold).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> DagGraph {
+        DagGraph {
+            nodes: vec![
+                GraphNode { hash: [1u8; 32], state: TxState::Finalized, score: 150.0, tip: false, parents: vec![] },
+                GraphNode { hash: [2u8; 32], state: TxState::Conflicted, score: 0.0, tip: true, parents: vec![[1u8; 32]] },
+            ],
+            confirmation_threshold: 100.0,
+        }
+    }
+
+    #[test]
+    fn test_render_dot() {
+        let dot = sample().render(GraphFormat::Dot);
+        assert!(dot.starts_with("digraph nyx_dag {"));
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\";", hex::encode([2u8; 32]), hex::encode([1u8; 32]))));
+        assert!(dot.contains("fillcolor=\"#e5383b\", penwidth=1.0, style=\"filled,dashed\""));
+        assert!(dot.contains("fillcolor=\"#40916c\", penwidth=4.0, style=\"filled\""));
+    }
+
+    #[test]
+    fn test_render_graphml() {
+        let graphml = sample().render(GraphFormat::GraphMl);
+        assert_eq!(graphml.matches("<node ").count(), 2);
+        assert!(graphml.contains(&format!("<edge source=\"{}\" target=\"{}\"/>", hex::encode([2u8; 32]), hex::encode([1u8; 32]))));
+        assert!(graphml.contains("<data key=\"state\">Conflicted</data>"));
+        assert!(graphml.ends_with("</graphml>\n"));
+    }
+
+    #[test]
+    fn test_format_parsing() {
+        assert_eq!("DOT".parse::<GraphFormat>(), Ok(GraphFormat::Dot));
+        assert_eq!("graphml".parse::<GraphFormat>(), Ok(GraphFormat::GraphMl));
+        assert!("svg".parse::<GraphFormat>().is_err());
+    }
+}
@@ -0,0 +1,387 @@
+// src/integrity.rs
+
+//! Storage integrity verification.
+//!
+//! [`verify_dag`] re-walks every stored transaction and checks what the
+//! node accepted earlier still holds:
+//!
+//! - Each transaction is stored under its own hash
+//! - Parents exist, possibly pruned
+//! - Transactions pass the same full-mode checks as on insertion: version
+//!   rules, timestamps, ring signatures and members, range proofs, unlock
+//!   times, HTLC spends and commitment balance
+//! - Child links and the tip set agree with the stored parent references
+//!
+//! Unlike [`crate::invariants`], every problem is collected into an
+//! [`IntegrityReport`] rather than stopping at the first one. With `repair`,
+//! child links and tips are rebuilt from the stored references; corrupt
+//! transactions cannot be repaired locally and are only reported.
+
+use crate::dag::{is_minted, DagProcessor};
+use crate::errors::Result;
+use crate::types::Hash;
+use crate::verification::VerifyMode;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Problem found by [`verify_dag`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    /// Transaction stored under a key other than its hash
+    HashMismatch {
+        /// Storage key
+        key: Hash,
+        /// Hash of the stored transaction
+        actual: Hash,
+    },
+
+    /// Referenced parent is neither stored nor pruned
+    MissingParent {
+        /// Transaction referencing the parent
+        hash: Hash,
+        /// Missing parent
+        parent: Hash,
+    },
+
+    /// Transaction fails the checks it had to pass on insertion
+    InvalidTransaction {
+        /// Transaction hash
+        hash: Hash,
+        /// Failed check
+        reason: String,
+    },
+
+    /// Parent does not list a transaction referencing it as a child
+    MissingChildLink {
+        /// Referenced parent
+        parent: Hash,
+        /// Referencing transaction
+        child: Hash,
+    },
+
+    /// Parent lists a child that is not stored or does not reference it
+    StaleChildLink {
+        /// Parent listing the child
+        parent: Hash,
+        /// Listed child
+        child: Hash,
+    },
+
+    /// Transaction without children missing from the tips
+    MissingTip(Hash),
+
+    /// Tip that has children or is not in the DAG
+    StaleTip(Hash),
+}
+
+impl IntegrityIssue {
+    /// Checks whether [`verify_dag`] can repair the issue from storage
+    pub fn is_repairable(&self) -> bool {
+        matches!(
+            self,
+            IntegrityIssue::MissingChildLink { .. }
+                | IntegrityIssue::StaleChildLink { .. }
+                | IntegrityIssue::MissingTip(_)
+                | IntegrityIssue::StaleTip(_)
+        )
+    }
+}
+
+impl fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityIssue::HashMismatch { key, actual } => {
+                write!(f, "{} is stored under {}", hex::encode(actual), hex::encode(key))
+            }
+            IntegrityIssue::MissingParent { hash, parent } => {
+                write!(f, "{} references missing parent {}", hex::encode(hash), hex::encode(parent))
+            }
+            IntegrityIssue::InvalidTransaction { hash, reason } => {
+                write!(f, "{} is invalid: {}", hex::encode(hash), reason)
+            }
+            IntegrityIssue::MissingChildLink { parent, child } => {
+                write!(f, "{} does not list child {}", hex::encode(parent), hex::encode(child))
+            }
+            IntegrityIssue::StaleChildLink { parent, child } => {
+                write!(f, "{} lists stale child {}", hex::encode(parent), hex::encode(child))
+            }
+            IntegrityIssue::MissingTip(hash) => write!(f, "{} is missing from the tips", hex::encode(hash)),
+            IntegrityIssue::StaleTip(hash) => write!(f, "{} is a stale tip", hex::encode(hash)),
+        }
+    }
+}
+
+/// Outcome of [`verify_dag`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Number of stored transactions checked
+    pub checked: usize,
+
+    /// Every problem found, repaired ones included
+    pub issues: Vec<IntegrityIssue>,
+
+    /// Number of issues repaired
+    pub repaired: usize,
+}
+
+impl IntegrityReport {
+    /// Checks whether no problem was found
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Issues left after repairs
+    pub fn unrepaired(&self) -> usize {
+        self.issues.len() - self.repaired
+    }
+}
+
+/// Re-verifies stored transactions and the DAG indexes built from them
+///
+/// Transactions written to storage directly rather than through the DAG
+/// (they have no score) are treated as external history and only checked
+/// for their hash. Minted transactions skip parent and cryptographic checks,
+/// as on insertion. Others are re-validated with the checks insertion runs
+/// in full mode, unless a parent is missing, which is reported on its own.
+/// Height-based rules are checked against the current height.
+///
+/// # Errors
+/// - `StorageError` or `DagError` if storage or the DAG cannot be read
+pub fn verify_dag(dag: &DagProcessor, repair: bool) -> Result<IntegrityReport> {
+    let storage = dag.storage();
+    let stored = storage.transaction_hashes()?;
+    let mut report = IntegrityReport { checked: stored.len(), ..IntegrityReport::default() };
+
+    let mut managed = HashSet::new();
+    for hash in &stored {
+        let tx = storage.get_transaction_shared(hash)?;
+        let actual = tx.id();
+        if actual != *hash {
+            report.issues.push(IntegrityIssue::HashMismatch { key: *hash, actual });
+            continue;
+        }
+        if dag.get_score(hash)? == 0.0 {
+            continue;
+        }
+        managed.insert(*hash);
+        if is_minted(&tx) && dag.network().allows_minting() {
+            continue;
+        }
+
+        let missing: Vec<Hash> = tx.references.iter()
+            .filter(|parent| storage.get_timestamp(parent).is_err())
+            .copied()
+            .collect();
+        if !missing.is_empty() {
+            for parent in missing {
+                report.issues.push(IntegrityIssue::MissingParent { hash: *hash, parent });
+            }
+            continue;
+        }
+
+        if let Err(e) = dag.validate(&tx, VerifyMode::Full) {
+            report.issues.push(IntegrityIssue::InvalidTransaction { hash: *hash, reason: e.to_string() });
+        }
+    }
+
+    check_links(dag, &managed, repair, &mut report)?;
+    check_tips(dag, &managed, repair, &mut report)?;
+
+    Ok(report)
+}
+
+/// Child links must match the stored parent references
+fn check_links(
+    dag: &DagProcessor,
+    managed: &HashSet<Hash>,
+    repair: bool,
+    report: &mut IntegrityReport,
+) -> Result<()> {
+    let storage = dag.storage();
+
+    for parent in dag.linked_parents() {
+        for child in dag.get_children(&parent)? {
+            let linked = storage.has_transaction(&child)?
+                && storage.get_transaction_shared(&child)?.references.contains(&parent);
+            if !linked {
+                report.issues.push(IntegrityIssue::StaleChildLink { parent, child });
+                if repair {
+                    dag.unlink_child(&parent, &child);
+                    report.repaired += 1;
+                }
+            }
+        }
+    }
+
+    for child in managed {
+        for parent in storage.get_transaction_shared(child)?.references {
+            if managed.contains(&parent) && !dag.get_children(&parent)?.contains(child) {
+                report.issues.push(IntegrityIssue::MissingChildLink { parent, child: *child });
+                if repair {
+                    dag.link_child(&parent, child);
+                    report.repaired += 1;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Tips must be exactly the scored transactions no other one references
+///
+/// Children are taken from the stored references, not the child links, so
+/// the outcome does not depend on whether broken links were repaired.
+fn check_tips(
+    dag: &DagProcessor,
+    managed: &HashSet<Hash>,
+    repair: bool,
+    report: &mut IntegrityReport,
+) -> Result<()> {
+    let tips: HashSet<Hash> = dag.get_tips()?.into_iter().collect();
+    let mut referenced = HashSet::new();
+    for hash in managed {
+        referenced.extend(dag.storage().get_transaction_shared(hash)?.references);
+    }
+
+    for tip in &tips {
+        if !managed.contains(tip) || referenced.contains(tip) {
+            report.issues.push(IntegrityIssue::StaleTip(*tip));
+            if repair {
+                dag.set_tip(tip, false)?;
+                report.repaired += 1;
+            }
+        }
+    }
+
+    for hash in managed {
+        if !tips.contains(hash) && !referenced.contains(hash) {
+            report.issues.push(IntegrityIssue::MissingTip(*hash));
+            if repair {
+                dag.set_tip(hash, true)?;
+                report.repaired += 1;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::network::NetworkId;
+    use crate::storage::MemoryStorage;
+    use crate::testing::{funding_key, funding_tx, test_tx, TestTx};
+    use crate::transaction::TxOutput;
+    use crate::verification::VerifyMode;
+    use std::sync::Arc;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn output(nonce: u8) -> TxOutput {
+        TxOutput {
+            stealth_address: vec![nonce],
+            amount_commitment: Vec::new(),
+            range_proof: Vec::new(),
+            ephemeral_pubkey: Vec::new(),
+            unlock_time: None,
+            htlc: None,
+        }
+    }
+
+    fn regtest_dag() -> DagProcessor {
+        DagProcessor::new(MemoryStorage::new())
+            .with_network(NetworkId::Regtest)
+            .with_clock(Arc::new(ManualClock::new(NOW)))
+    }
+
+    #[test]
+    fn test_clean_dag_passes() {
+        let dag = regtest_dag();
+        dag.mint(vec![output(1)]).unwrap();
+        dag.mint(vec![output(2)]).unwrap();
+
+        let report = verify_dag(&dag, false).unwrap();
+        assert_eq!(report.checked, 2);
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn test_unverified_transaction_is_reported() {
+        let dag = regtest_dag();
+        let a = dag.mint(vec![output(1)]).unwrap();
+        let b = dag.mint(vec![output(2)]).unwrap();
+
+        // Accepted without cryptographic checks, as from a trusted replay
+        let mut tx = TestTx::new(3).parents(a, b).spends(a, 0).build().with_network(NetworkId::Regtest);
+        tx.timestamp = NOW;
+        let spend = dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly).unwrap();
+
+        let report = verify_dag(&dag, true).unwrap();
+        assert!(matches!(
+            report.issues.as_slice(),
+            [IntegrityIssue::InvalidTransaction { hash, .. }] if *hash == spend
+        ));
+        assert!(!report.issues[0].is_repairable());
+        assert_eq!(report.unrepaired(), 1);
+    }
+
+    #[test]
+    fn test_ring_members_are_reverified() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+        let funding = storage.store_transaction(funding_tx()).unwrap();
+        storage.mark_confirmed(&funding).unwrap();
+        let other = storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 1)).unwrap();
+
+        // Signed and balanced, but its ring misses the output it spends
+        let (signer, decoy) = (funding_key(5), funding_key(6));
+        let mut tx = TestTx::new(5).parents(funding, other).funded().build();
+        tx.inputs[0].ring_indices = vec![7, 8];
+        let ring = vec![signer.public_key.clone(), decoy.public_key];
+        let message = tx.signing_message();
+        tx.sign(0, &message, signer.private_key(), &signer.public_key, &ring).unwrap();
+        assert!(dag.add_transaction(tx.clone()).is_err());
+        let spend = dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly).unwrap();
+
+        // The genesis placeholders' own parents are reported as missing
+        let report = verify_dag(&dag, false).unwrap();
+        let invalid: Vec<_> = report.issues.iter()
+            .filter(|issue| matches!(issue, IntegrityIssue::InvalidTransaction { .. }))
+            .collect();
+        assert!(matches!(
+            invalid.as_slice(),
+            [IntegrityIssue::InvalidTransaction { hash, reason }] if *hash == spend && reason.contains("ring")
+        ));
+    }
+
+    #[test]
+    fn test_broken_indexes_are_repaired() {
+        let dag = regtest_dag();
+        let a = dag.mint(vec![output(1)]).unwrap();
+        let b = dag.mint(vec![output(2)]).unwrap();
+        let c = dag.mint(vec![output(3)]).unwrap();
+        let mut tx = TestTx::new(4).parents(a, b).spends(a, 0).build().with_network(NetworkId::Regtest);
+        tx.timestamp = NOW;
+        let d = dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly).unwrap();
+
+        dag.unlink_child(&a, &d);
+        dag.link_child(&c, &[9u8; 32]);
+        dag.set_tip(&a, true).unwrap();
+        dag.set_tip(&d, false).unwrap();
+
+        // Besides the index damage, `d` was never verified
+        let report = verify_dag(&dag, false).unwrap();
+        let repairable = report.issues.iter().filter(|issue| issue.is_repairable()).count();
+        assert_eq!((report.issues.len(), repairable), (5, 4));
+        assert_eq!(report.repaired, 0);
+        assert!(crate::invariants::check_invariants(&dag).is_err());
+
+        let report = verify_dag(&dag, true).unwrap();
+        assert_eq!(report.repaired, 4);
+        assert_eq!(verify_dag(&dag, false).unwrap().issues.len(), 1);
+        crate::invariants::check_invariants(&dag).unwrap();
+    }
+}
@@ -0,0 +1,445 @@
+// src/invariants.rs
+
+//! DAG invariant checking.
+//!
+//! [`check_invariants`] verifies properties every [`DagProcessor`] must
+//! maintain no matter which operations were applied:
+//!
+//! - The graph is acyclic: a topological order covers every transaction
+//! - Parent and child links agree with each other
+//! - Tips are exactly the scored transactions without children
+//! - Every score is at least the base score of 1
+//! - Conflicted transactions are never finalized or descended from by a
+//!   finalized transaction, and no key image or output is spent twice
+//!
+//! [`InvariantChecker`] additionally remembers the previous check, so it can
+//! assert that scores never decrease as descendants are added and that
+//! finality is never revoked.
+//!
+//! Checks walk the whole DAG (ancestor checks are quadratic) and are meant
+//! for tests and debugging, not for the transaction path.
+
+use crate::dag::{DagProcessor, TxState};
+use crate::errors::{NyxError, Result};
+use crate::types::Hash;
+use std::collections::{HashMap, HashSet};
+
+/// Tolerance for comparing scores across checks
+const SCORE_EPSILON: f64 = 1e-9;
+
+/// Checks the structural invariants of a DAG
+///
+/// Transactions written to storage directly rather than through the DAG
+/// (they have no score) are treated as external history and only checked
+/// for acyclicity.
+///
+/// # Errors
+/// - `DagError` describing the first violated invariant
+pub fn check_invariants(dag: &DagProcessor) -> Result<()> {
+    let storage = dag.storage();
+    let stored = storage.transaction_hashes()?;
+
+    // Kahn's algorithm leaves every transaction on a cycle unvisited
+    let ordered = dag.topological_order()?.count();
+    if ordered != stored.len() {
+        return Err(violation(format!(
+            "topological order covers {} of {} transactions",
+            ordered,
+            stored.len()
+        )));
+    }
+
+    let mut managed = HashSet::new();
+    for hash in &stored {
+        let score = dag.get_score(hash)?;
+        if score == 0.0 {
+            continue;
+        }
+        if score < 1.0 {
+            return Err(violation(format!("{} has score {} below 1", short(hash), score)));
+        }
+        managed.insert(*hash);
+    }
+
+    let tracked = dag.get_stats()?.total_transactions;
+    if tracked != managed.len() {
+        return Err(violation(format!(
+            "{} transactions are scored but only {} of them are stored",
+            tracked,
+            managed.len()
+        )));
+    }
+
+    check_links(dag, &managed)?;
+    check_tips(dag, &managed)?;
+    check_conflicts(dag, &managed)?;
+
+    Ok(())
+}
+
+/// Parent and child maps must describe the same edges
+fn check_links(dag: &DagProcessor, managed: &HashSet<Hash>) -> Result<()> {
+    for hash in managed {
+        for child in dag.get_children(hash)? {
+            if !dag.storage().has_transaction(&child)? {
+                return Err(violation(format!("{} has unknown child {}", short(hash), short(&child))));
+            }
+            if !dag.storage().get_transaction_shared(&child)?.references.contains(hash) {
+                return Err(violation(format!(
+                    "{} lists {} as a child, which does not reference it",
+                    short(hash),
+                    short(&child)
+                )));
+            }
+        }
+
+        for parent in dag.get_parents(hash)? {
+            if managed.contains(&parent) && !dag.get_children(&parent)?.contains(hash) {
+                return Err(violation(format!(
+                    "{} references {}, which does not list it as a child",
+                    short(hash),
+                    short(&parent)
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Tips must be exactly the scored transactions without children
+fn check_tips(dag: &DagProcessor, managed: &HashSet<Hash>) -> Result<()> {
+    let tips: HashSet<Hash> = dag.get_tips()?.into_iter().collect();
+
+    for tip in &tips {
+        if !managed.contains(tip) {
+            return Err(violation(format!("tip {} is not in the DAG", short(tip))));
+        }
+        if !dag.get_children(tip)?.is_empty() {
+            return Err(violation(format!("tip {} has children", short(tip))));
+        }
+    }
+
+    for hash in managed {
+        if !tips.contains(hash) && dag.get_children(hash)?.is_empty() {
+            return Err(violation(format!("{} has no children but is not a tip", short(hash))));
+        }
+    }
+
+    Ok(())
+}
+
+/// Conflicted transactions must stay out of the finalized history
+fn check_conflicts(dag: &DagProcessor, managed: &HashSet<Hash>) -> Result<()> {
+    let mut conflicted = HashSet::new();
+    let mut finalized = Vec::new();
+    for hash in managed {
+        match dag.get_state(hash)? {
+            TxState::Conflicted => {
+                conflicted.insert(*hash);
+            }
+            TxState::Finalized => finalized.push(*hash),
+            TxState::Pending | TxState::Confirmed => {}
+        }
+    }
+
+    for hash in &conflicted {
+        if dag.storage().is_confirmed(hash)? {
+            return Err(violation(format!("conflicted {} is confirmed in storage", short(hash))));
+        }
+    }
+
+    for hash in &finalized {
+        if !dag.storage().is_confirmed(hash)? {
+            return Err(violation(format!("finalized {} is not confirmed in storage", short(hash))));
+        }
+        if !conflicted.is_empty() {
+            for ancestor in dag.get_ancestors(hash, usize::MAX)? {
+                if conflicted.contains(&ancestor) {
+                    return Err(violation(format!(
+                        "finalized {} descends from conflicted {}",
+                        short(hash),
+                        short(&ancestor)
+                    )));
+                }
+            }
+        }
+    }
+
+    let mut spent = HashMap::new();
+    let mut spent_outputs = HashMap::new();
+    for hash in managed {
+        for input in &dag.storage().get_transaction_shared(hash)?.inputs {
+            if !dag.is_key_image_spent(&input.key_image)? {
+                return Err(violation(format!("key image of {} is not recorded", short(hash))));
+            }
+            if !dag.is_output_spent(&input.prev_tx, input.index)? {
+                return Err(violation(format!("output spent by {} is not recorded", short(hash))));
+            }
+            if let Some(other) = spent.insert(input.key_image, *hash) {
+                return Err(violation(format!(
+                    "{} and {} spend the same key image",
+                    short(&other),
+                    short(hash)
+                )));
+            }
+            if let Some(other) = spent_outputs.insert((input.prev_tx, input.index), *hash) {
+                return Err(violation(format!(
+                    "{} and {} spend the same output",
+                    short(&other),
+                    short(hash)
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Invariant checker that also compares against its previous check
+#[derive(Debug, Default)]
+pub struct InvariantChecker {
+    /// Scores seen by the last check
+    scores: HashMap<Hash, f64>,
+
+    /// Transactions finalized as of the last check
+    finalized: HashSet<Hash>,
+}
+
+impl InvariantChecker {
+    /// Creates a checker with no history
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks the structural invariants and the changes since the last check
+    ///
+    /// # Errors
+    /// - `DagError` describing the first violated invariant
+    pub fn check(&mut self, dag: &DagProcessor) -> Result<()> {
+        check_invariants(dag)?;
+
+        for (hash, previous) in &self.scores {
+            let score = dag.get_score(hash)?;
+            if score + SCORE_EPSILON < *previous {
+                return Err(violation(format!(
+                    "score of {} dropped from {} to {}",
+                    short(hash),
+                    previous,
+                    score
+                )));
+            }
+        }
+
+        for hash in &self.finalized {
+            if dag.get_state(hash)? != TxState::Finalized {
+                return Err(violation(format!("{} is no longer finalized", short(hash))));
+            }
+        }
+
+        self.scores.clear();
+        self.finalized.clear();
+        for hash in dag.storage().transaction_hashes()? {
+            let score = dag.get_score(&hash)?;
+            if score > 0.0 {
+                self.scores.insert(hash, score);
+            }
+            if dag.get_state(&hash)? == TxState::Finalized {
+                self.finalized.insert(hash);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn violation(detail: String) -> NyxError {
+    NyxError::DagError(format!("Invariant violated: {}", detail))
+}
+
+fn short(hash: &Hash) -> String {
+    hex::encode(&hash[..4])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::network::NetworkId;
+    use crate::storage::MemoryStorage;
+    use crate::transaction::{Transaction, TxInput, TxOutput};
+    use crate::verification::VerifyMode;
+    use nyx_crypto::RingSignature;
+    use proptest::prelude::*;
+    use std::sync::Arc;
+
+    const NOW: u64 = 1_700_000_000;
+
+    /// Operations applied to a random DAG
+    #[derive(Debug, Clone)]
+    enum Op {
+        /// Mint a new root
+        Mint,
+        /// Spend with two existing transactions as parents
+        Spend(usize, usize),
+        /// Finalize an existing transaction
+        Finalize(usize),
+        /// Reuse the key image of an earlier spend, which must be rejected
+        DoubleSpend(usize),
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            1 => Just(Op::Mint),
+            4 => (any::<usize>(), any::<usize>()).prop_map(|(a, b)| Op::Spend(a, b)),
+            1 => any::<usize>().prop_map(Op::Finalize),
+            1 => any::<usize>().prop_map(Op::DoubleSpend),
+        ]
+    }
+
+    fn output(nonce: u64) -> TxOutput {
+        TxOutput {
+            stealth_address: nonce.to_le_bytes().to_vec(),
+            amount_commitment: Vec::new(),
+            range_proof: Vec::new(),
+            ephemeral_pubkey: Vec::new(),
+            unlock_time: None,
+            htlc: None,
+        }
+    }
+
+    fn spend(parent1: Hash, parent2: Hash, key_image: [u8; 32], nonce: u64) -> Transaction {
+        let mut tx = Transaction::try_new(
+            vec![TxInput { prev_tx: parent1, index: nonce as u32, key_image, ring_indices: vec![] }],
+            vec![output(nonce)],
+            vec![RingSignature { ring_members: vec![vec![1], vec![2]], signature: vec![], key_image }],
+            Vec::new(),
+            parent1,
+            parent2,
+        ).unwrap()
+        .with_network(NetworkId::Regtest);
+        tx.timestamp = NOW;
+        tx
+    }
+
+    fn regtest_dag() -> DagProcessor {
+        DagProcessor::new(MemoryStorage::new())
+            .with_network(NetworkId::Regtest)
+            .with_clock(Arc::new(ManualClock::new(NOW)))
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn prop_invariants_hold_after_every_operation(ops in prop::collection::vec(op(), 1..60)) {
+            let dag = regtest_dag();
+            let mut checker = InvariantChecker::new();
+            let mut accepted: Vec<Hash> = Vec::new();
+            let mut spent_images: Vec<[u8; 32]> = Vec::new();
+            let mut nonce = 0u64;
+
+            for op in ops {
+                nonce += 1;
+                match op {
+                    Op::Mint => {
+                        accepted.push(dag.mint(vec![output(nonce)]).unwrap());
+                    }
+                    Op::Spend(a, b) if accepted.len() >= 2 => {
+                        let parent1 = accepted[a % accepted.len()];
+                        let parent2 = accepted[b % accepted.len()];
+                        if parent1 == parent2 {
+                            continue;
+                        }
+                        let mut key_image = [0u8; 32];
+                        key_image[..8].copy_from_slice(&nonce.to_le_bytes());
+                        let tx = spend(parent1, parent2, key_image, nonce);
+                        accepted.push(dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly).unwrap());
+                        spent_images.push(key_image);
+                    }
+                    Op::Finalize(i) if !accepted.is_empty() => {
+                        dag.finalize_transaction(&accepted[i % accepted.len()]).unwrap();
+                    }
+                    Op::DoubleSpend(i) if !spent_images.is_empty() && accepted.len() >= 2 => {
+                        let key_image = spent_images[i % spent_images.len()];
+                        let tx = spend(accepted[0], accepted[1], key_image, nonce);
+                        let before = dag.storage().transaction_count().unwrap();
+                        let result = dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly);
+                        prop_assert!(matches!(result, Err(NyxError::DoubleSpend(_))));
+                        prop_assert_eq!(dag.storage().transaction_count().unwrap(), before);
+                    }
+                    _ => continue,
+                }
+
+                if let Err(e) = checker.check(&dag) {
+                    prop_assert!(false, "{}", e);
+                }
+            }
+        }
+
+        #[test]
+        fn prop_snapshot_restore_preserves_invariants(spends in prop::collection::vec((any::<usize>(), any::<usize>()), 0..30)) {
+            let dag = regtest_dag();
+            let mut accepted = vec![
+                dag.mint(vec![output(0)]).unwrap(),
+                dag.mint(vec![output(1)]).unwrap(),
+            ];
+            for (nonce, (a, b)) in spends.into_iter().enumerate() {
+                let parent1 = accepted[a % accepted.len()];
+                let parent2 = accepted[b % accepted.len()];
+                if parent1 == parent2 {
+                    continue;
+                }
+                let nonce = nonce as u64 + 2;
+                let mut key_image = [0u8; 32];
+                key_image[..8].copy_from_slice(&nonce.to_le_bytes());
+                let tx = spend(parent1, parent2, key_image, nonce);
+                accepted.push(dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly).unwrap());
+            }
+
+            let restored = regtest_dag();
+            restored.restore_snapshot(dag.snapshot().unwrap()).unwrap();
+            if let Err(e) = check_invariants(&restored) {
+                prop_assert!(false, "{}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_detects_inconsistent_tips() {
+        let dag = regtest_dag();
+        let a = dag.mint(vec![output(0)]).unwrap();
+        let b = dag.mint(vec![output(1)]).unwrap();
+        dag.add_transaction_with_mode(spend(a, b, [1u8; 32], 2), VerifyMode::HeadersOnly).unwrap();
+        check_invariants(&dag).unwrap();
+
+        // A snapshot claiming a parent is still a tip restores inconsistently
+        let mut snapshot = dag.snapshot().unwrap();
+        snapshot.tips.push(a);
+        let restored = regtest_dag();
+        restored.restore_snapshot(snapshot).unwrap();
+
+        let err = check_invariants(&restored).unwrap_err();
+        assert!(err.to_string().contains("has children"), "{}", err);
+    }
+
+    #[test]
+    fn test_detects_revoked_finality() {
+        let dag = regtest_dag();
+        let a = dag.mint(vec![output(0)]).unwrap();
+        dag.finalize_transaction(&a).unwrap();
+
+        let mut checker = InvariantChecker::new();
+        checker.check(&dag).unwrap();
+
+        // Restoring a snapshot entry in another state stands in for a bug
+        // that un-finalizes a transaction
+        let mut snapshot = dag.snapshot().unwrap();
+        snapshot.entries[0].state = TxState::Confirmed;
+        let restored = regtest_dag();
+        restored.restore_snapshot(snapshot).unwrap();
+
+        let err = checker.check(&restored).unwrap_err();
+        assert!(err.to_string().contains("no longer finalized"), "{}", err);
+    }
+}
@@ -22,47 +22,101 @@
 //! ## Example Usage
 //!
 //! ```rust
-//! use nyx_core::{Transaction, TxInput, TxOutput, RingSignature};
+//! use nyx_core::{RingSignature, Transaction, TxInput, TxOutput};
 //!
-//! // Create a new transaction
-//! let tx = Transaction::new(
-//!     vec![/* inputs */],
-//!     vec![/* outputs */],
-//!     RingSignature { ring_members: vec![], signature: vec![], key_image: [0u8; 32] },
-//!     vec![/* tx_key */],
+//! # fn main() -> nyx_core::Result<()> {
+//! let key_image = [7u8; 32];
+//!
+//! // Create a new transaction spending output 0 of a confirmed transaction
+//! let tx = Transaction::try_new(
+//!     vec![TxInput {
+//!         prev_tx: [2u8; 32],
+//!         index: 0,
+//!         key_image,
+//!         ring_indices: vec![0, 1], // global indices of the ring members
+//!     }],
+//!     vec![TxOutput {
+//!         stealth_address: vec![3u8; 32],
+//!         amount_commitment: vec![],
+//!         range_proof: vec![],
+//!         ephemeral_pubkey: vec![],
+//!         unlock_time: None,
+//!         htlc: None,
+//!     }],
+//!     vec![RingSignature {
+//!         ring_members: vec![vec![4u8; 32], vec![5u8; 32]],
+//!         signature: vec![], // filled in by `Transaction::sign`
+//!         key_image, // the key image its input spends
+//!     }],
+//!     vec![], // tx_key, deprecated
 //!     [0u8; 32], // parent 1
 //!     [1u8; 32], // parent 2
-//! );
+//! )?;
 //!
 //! // Get transaction ID
 //! let tx_id = tx.id();
+//! # Ok(())
+//! # }
 //! ```
 
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 
 pub mod types;
-///! Transaction structure and validation logic.
+pub mod clock;
+pub mod network;
+pub mod amount;
+/// Transaction structure and validation logic.
 pub mod transaction;
 pub mod transaction_builder;
+pub mod encoding;
+pub mod extra;
+pub mod limits;
+pub mod params;
+pub mod versioning;
+pub mod balance;
+pub mod verification;
 pub mod dag;
 pub mod tip_selection;
+pub mod graph;
+pub mod invariants;
+pub mod integrity;
+pub mod metrics;
+pub mod weight_index;
 pub mod storage;
+pub mod tx_cache;
+pub mod snapshot;
+pub mod finality;
+pub mod consensus;
+pub mod evidence;
+pub mod staking;
+pub mod checkpoint;
+pub mod bridge;
 pub mod errors;
+#[cfg(feature = "arbitrary")]
+pub mod fuzzing;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 // Re-export crypto for convenience
 pub use nyx_crypto;
 
 // Re-export commonly used types
-pub use crate::transaction::{Transaction, TxInput, TxOutput};
+pub use crate::transaction::{HtlcLock, RingSignature, Transaction, TxInput, TxOutput, UnlockTime};
 pub use crate::transaction_builder::TransactionBuilder;
+pub use crate::amount::Amount;
+pub use crate::network::NetworkId;
 pub use crate::types::{Hash, Timestamp, hash_bytes_to_hash};
 pub use crate::errors::{NyxError, Result};
+pub use crate::extra::{Extra, ExtraField};
+pub use crate::limits::{DecodeLimits, LimitExceeded};
+pub use crate::params::{Genesis, ProtocolParams};
+pub use crate::finality::NodeMode;
+pub use crate::tx_cache::TxCache;
 
 // Re-export crypto types that are commonly used
 pub use nyx_crypto::{
     keys::KeyPair,
-    ring::RingSignature,
     hash,
     encryption,
 };
@@ -71,12 +125,27 @@ pub use nyx_crypto::{
 /// Protocol version
 pub const PROTOCOL_VERSION: u8 = 1;
 
+/// First transaction version whose outputs can carry an unlock time
+pub const UNLOCK_TIME_VERSION: u8 = 2;
+
+/// First transaction version whose inputs must have one ring index per ring
+/// member, even when only headers are verified
+pub const RING_INDEX_VERSION: u8 = 3;
+
+/// First transaction version whose outputs can carry a hash/time-lock
+pub const HTLC_VERSION: u8 = 4;
+
 /// Default ring size for privacy (16 total: 1 true + 15 decoys)
 pub const DEFAULT_RING_SIZE: u8 = 16;
 
+// Built-in values of `ProtocolParams`; code reads the params it was given
+
 /// DAG confirmation threshold (from whitepaper: Score > 100)
 pub const CONFIRMATION_THRESHOLD: f64 = 100.0;
 
+/// Confirmation threshold on regtest, where a transaction confirms on arrival
+pub const REGTEST_CONFIRMATION_THRESHOLD: f64 = 1.0;
+
 /// PoS snapshot interval in seconds (from whitepaper: every 10 seconds)
 pub const SNAPSHOT_INTERVAL_SECS: u64 = 10;
 
@@ -0,0 +1,177 @@
+// src/limits.rs
+
+//! Structural limits enforced while decoding untrusted input.
+//!
+//! A length prefix on the wire is only bounded by the bytes that follow it,
+//! so without explicit caps a peer can make a node allocate and iterate over
+//! millions of empty inputs or ring members in a single message. Decoders
+//! check every count against [`DecodeLimits`] as soon as it is read, and
+//! report violations as [`LimitExceeded`] so callers and fuzz targets can
+//! tell a limit hit from malformed bytes.
+
+use crate::errors::NyxError;
+use crate::encoding;
+use crate::transaction::Transaction;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Maximum canonical encoding size of a transaction in bytes
+pub const MAX_TX_SIZE: usize = 1024 * 1024;
+
+/// Maximum number of inputs in a transaction
+pub const MAX_TX_INPUTS: usize = 256;
+
+/// Maximum number of outputs in a transaction
+pub const MAX_TX_OUTPUTS: usize = 256;
+
+/// Maximum number of ring members (and ring indices per input)
+pub const MAX_RING_SIZE: usize = 128;
+
+/// Maximum length of a transaction's `extra` field in bytes
+pub const MAX_EXTRA_SIZE: usize = 1024;
+
+/// A decoded count or length that exceeded its limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitExceeded {
+    /// Name of the limited field, e.g. `"inputs"`
+    pub field: &'static str,
+
+    /// Largest accepted value
+    pub max: usize,
+
+    /// Value found in the input
+    pub actual: usize,
+}
+
+impl LimitExceeded {
+    /// Returns an error if `actual` exceeds `max`
+    pub fn check(field: &'static str, max: usize, actual: usize) -> std::result::Result<(), Self> {
+        if actual > max {
+            return Err(Self { field, max, actual });
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} has {} entries, limit is {}", self.field, self.actual, self.max)
+    }
+}
+
+impl std::error::Error for LimitExceeded {}
+
+/// Caps applied when decoding transactions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodeLimits {
+    /// Maximum canonical encoding size in bytes
+    pub max_tx_size: usize,
+
+    /// Maximum number of inputs
+    pub max_inputs: usize,
+
+    /// Maximum number of outputs
+    pub max_outputs: usize,
+
+    /// Maximum ring size, also applied to each input's ring indices
+    pub max_ring_size: usize,
+
+    /// Maximum length of the `extra` field in bytes
+    pub max_extra_size: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_tx_size: MAX_TX_SIZE,
+            max_inputs: MAX_TX_INPUTS,
+            max_outputs: MAX_TX_OUTPUTS,
+            max_ring_size: MAX_RING_SIZE,
+            max_extra_size: MAX_EXTRA_SIZE,
+        }
+    }
+}
+
+impl DecodeLimits {
+    /// Checks an already decoded transaction against the limits
+    ///
+    /// Used for transactions that arrive through serde (e.g. bincode network
+    /// messages) rather than the canonical decoder, which checks each count
+    /// before allocating.
+    pub fn check_transaction(&self, tx: &Transaction) -> std::result::Result<(), LimitExceeded> {
+        LimitExceeded::check("inputs", self.max_inputs, tx.inputs.len())?;
+        LimitExceeded::check("outputs", self.max_outputs, tx.outputs.len())?;
+        LimitExceeded::check("ring signatures", self.max_inputs, tx.ring_signatures.len())?;
+        for ring_signature in &tx.ring_signatures {
+            LimitExceeded::check("ring members", self.max_ring_size, ring_signature.ring_members.len())?;
+        }
+        for input in &tx.inputs {
+            LimitExceeded::check("ring indices", self.max_ring_size, input.ring_indices.len())?;
+        }
+        LimitExceeded::check("extra", self.max_extra_size, tx.extra.len())?;
+        LimitExceeded::check("transaction bytes", self.max_tx_size, encoding::encode_transaction(tx).len())?;
+        Ok(())
+    }
+}
+
+impl From<LimitExceeded> for NyxError {
+    fn from(err: LimitExceeded) -> Self {
+        NyxError::LimitExceeded(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::TxOutput;
+
+    fn tx_with_outputs(count: usize) -> Transaction {
+        let output = TxOutput {
+            stealth_address: vec![1],
+            amount_commitment: vec![],
+            range_proof: vec![],
+            ephemeral_pubkey: vec![],
+            unlock_time: None,
+            htlc: None,
+        };
+        Transaction::new(
+            vec![],
+            vec![output; count],
+            vec![],
+            vec![],
+            [0u8; 32],
+            [1u8; 32],
+        )
+    }
+
+    #[test]
+    fn test_check_transaction() {
+        let limits = DecodeLimits::default();
+        assert!(limits.check_transaction(&tx_with_outputs(MAX_TX_OUTPUTS)).is_ok());
+
+        let err = limits.check_transaction(&tx_with_outputs(MAX_TX_OUTPUTS + 1)).unwrap_err();
+        assert_eq!(err, LimitExceeded { field: "outputs", max: MAX_TX_OUTPUTS, actual: MAX_TX_OUTPUTS + 1 });
+        assert_eq!(NyxError::from(err).to_string(), "Limit exceeded: outputs has 257 entries, limit is 256");
+    }
+
+    #[test]
+    fn test_extra_limit() {
+        let mut tx = tx_with_outputs(1);
+        tx.extra = vec![0u8; MAX_EXTRA_SIZE + 1];
+        let limits = DecodeLimits { max_extra_size: MAX_EXTRA_SIZE + 1, ..DecodeLimits::default() };
+        assert!(limits.check_transaction(&tx).is_ok());
+        assert!(DecodeLimits::default().check_transaction(&tx).is_err());
+    }
+
+    #[test]
+    fn test_size_limit() {
+        let tx = tx_with_outputs(4);
+        let size = crate::encoding::encode_transaction(&tx).len();
+
+        let exact = DecodeLimits { max_tx_size: size, ..DecodeLimits::default() };
+        assert!(exact.check_transaction(&tx).is_ok());
+
+        let small = DecodeLimits { max_tx_size: size - 1, ..DecodeLimits::default() };
+        assert_eq!(small.check_transaction(&tx).unwrap_err().field, "transaction bytes");
+    }
+}
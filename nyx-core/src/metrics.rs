@@ -0,0 +1,407 @@
+// src/metrics.rs
+
+//! Time-series metrics of the DAG.
+//!
+//! [`DagMetrics`] is updated by the DAG processor as transactions are
+//! added, confirmed and rescored, so reading a [`MetricsSnapshot`] never
+//! walks the graph. Time comes from the processor's clock in whole seconds:
+//! throughput and confirmation latency are averaged over the last
+//! [`METRICS_WINDOW_SECS`] seconds, and one tip count is kept per second.
+//!
+//! Score percentiles come from a histogram with four buckets per power of
+//! two, so they are accurate to within about 19%.
+//!
+//! [`estimate_confirmation`] turns the metrics into the expected wait for
+//! a new transaction to confirm, for wallets to show to users.
+
+use crate::params::ProtocolParams;
+use crate::types::{Hash, Timestamp};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Seconds of history behind the rolling averages and tip history
+pub const METRICS_WINDOW_SECS: u64 = 60;
+
+/// Histogram buckets per power of two of the score
+const BUCKETS_PER_OCTAVE: f64 = 4.0;
+
+/// Number of histogram buckets; the last one holds every score above 2^32
+const SCORE_BUCKETS: usize = 129;
+
+/// Activity within one second
+#[derive(Debug, Clone, Copy, Default)]
+struct Second {
+    at: Timestamp,
+    added: u64,
+    confirmed: u64,
+    latency_total: u64,
+}
+
+/// Tip count observed at a point in time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TipSample {
+    /// Unix time of the sample
+    pub timestamp: Timestamp,
+
+    /// Number of tips at the end of that second
+    pub tips: usize,
+}
+
+/// Approximate distribution of confirmation scores
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScorePercentiles {
+    /// Median score
+    pub p50: f64,
+
+    /// 90th percentile score
+    pub p90: f64,
+
+    /// 99th percentile score
+    pub p99: f64,
+
+    /// Highest score
+    pub max: f64,
+}
+
+/// Point-in-time view of the DAG metrics
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    /// Transactions added per second over the window
+    pub tps: f64,
+
+    /// Mean seconds from being added to being confirmed, over
+    /// confirmations in the window
+    pub avg_confirmation_latency_secs: Option<f64>,
+
+    /// Tip count per second over the window, oldest first
+    pub tip_history: Vec<TipSample>,
+
+    /// Distribution of the scores of all transactions
+    pub score_percentiles: ScorePercentiles,
+}
+
+#[derive(Debug)]
+struct MetricsState {
+    /// Per-second activity within the window, oldest first
+    seconds: VecDeque<Second>,
+
+    /// When each still-pending transaction was added
+    pending_since: HashMap<Hash, Timestamp>,
+
+    /// Last tip count of each second within the window, oldest first
+    tips: VecDeque<TipSample>,
+
+    /// Score histogram
+    scores: Vec<u64>,
+
+    /// Highest score seen
+    max_score: f64,
+}
+
+/// Incrementally maintained DAG metrics
+#[derive(Debug)]
+pub struct DagMetrics {
+    state: Mutex<MetricsState>,
+}
+
+impl DagMetrics {
+    /// Creates empty metrics
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(MetricsState {
+                seconds: VecDeque::new(),
+                pending_since: HashMap::new(),
+                tips: VecDeque::new(),
+                scores: vec![0; SCORE_BUCKETS],
+                max_score: 0.0,
+            }),
+        }
+    }
+
+    /// Records a transaction added at `now`
+    ///
+    /// Transactions added already confirmed count with zero latency.
+    pub fn record_added(&self, tx_hash: Hash, now: Timestamp, confirmed: bool) {
+        let Ok(mut state) = self.state.lock() else { return };
+        let second = state.second(now);
+        second.added += 1;
+        if confirmed {
+            second.confirmed += 1;
+        } else {
+            state.pending_since.insert(tx_hash, now);
+        }
+    }
+
+    /// Records a pending transaction becoming confirmed or finalized
+    ///
+    /// Transactions not added through [`record_added`](Self::record_added),
+    /// such as those restored from a snapshot, are ignored.
+    pub fn record_confirmed(&self, tx_hash: &Hash, now: Timestamp) {
+        let Ok(mut state) = self.state.lock() else { return };
+        let Some(added_at) = state.pending_since.remove(tx_hash) else { return };
+        let second = state.second(now);
+        second.confirmed += 1;
+        second.latency_total += now.saturating_sub(added_at);
+    }
+
+    /// Records the tip count at `now`
+    pub fn record_tips(&self, tips: usize, now: Timestamp) {
+        let Ok(mut state) = self.state.lock() else { return };
+        match state.tips.back_mut() {
+            Some(last) if last.timestamp == now => last.tips = tips,
+            _ => state.tips.push_back(TipSample { timestamp: now, tips }),
+        }
+        while state.tips.front().is_some_and(|s| s.timestamp + METRICS_WINDOW_SECS <= now) {
+            state.tips.pop_front();
+        }
+    }
+
+    /// Records a score change from `old` (`None` for a new transaction)
+    pub fn record_score(&self, old: Option<f64>, new: f64) {
+        let Ok(mut state) = self.state.lock() else { return };
+        if let Some(old) = old {
+            let bucket = &mut state.scores[score_bucket(old)];
+            *bucket = bucket.saturating_sub(1);
+        }
+        state.scores[score_bucket(new)] += 1;
+        state.max_score = state.max_score.max(new);
+    }
+
+    /// Removes a score that is no longer tracked
+    pub fn forget_score(&self, score: f64) {
+        let Ok(mut state) = self.state.lock() else { return };
+        let bucket = &mut state.scores[score_bucket(score)];
+        *bucket = bucket.saturating_sub(1);
+    }
+
+    /// Returns the metrics as of `now`
+    pub fn snapshot(&self, now: Timestamp) -> MetricsSnapshot {
+        let Ok(state) = self.state.lock() else { return MetricsSnapshot::default() };
+
+        let window: Vec<&Second> = state.seconds.iter()
+            .filter(|s| s.at + METRICS_WINDOW_SECS > now)
+            .collect();
+        let added: u64 = window.iter().map(|s| s.added).sum();
+        let confirmed: u64 = window.iter().map(|s| s.confirmed).sum();
+        let latency_total: u64 = window.iter().map(|s| s.latency_total).sum();
+
+        // Before a full window has passed, average over the time observed
+        let elapsed = window.first().map_or(1, |s| (now + 1).saturating_sub(s.at).clamp(1, METRICS_WINDOW_SECS));
+
+        MetricsSnapshot {
+            tps: added as f64 / elapsed as f64,
+            avg_confirmation_latency_secs: (confirmed > 0).then(|| latency_total as f64 / confirmed as f64),
+            tip_history: state.tips.iter()
+                .filter(|s| s.timestamp + METRICS_WINDOW_SECS > now)
+                .copied()
+                .collect(),
+            score_percentiles: state.percentiles(),
+        }
+    }
+}
+
+impl Default for DagMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsState {
+    /// Activity of the second `now`, dropping seconds outside the window
+    fn second(&mut self, now: Timestamp) -> &mut Second {
+        while self.seconds.front().is_some_and(|s| s.at + METRICS_WINDOW_SECS <= now) {
+            self.seconds.pop_front();
+        }
+        if self.seconds.back().is_none_or(|s| s.at != now) {
+            self.seconds.push_back(Second { at: now, ..Second::default() });
+        }
+        self.seconds.back_mut().expect("second was just pushed")
+    }
+
+    fn percentiles(&self) -> ScorePercentiles {
+        let total: u64 = self.scores.iter().sum();
+        if total == 0 {
+            return ScorePercentiles::default();
+        }
+
+        let percentile = |p: f64| {
+            let rank = ((total as f64 * p).ceil() as u64).max(1);
+            let mut seen = 0;
+            for (bucket, count) in self.scores.iter().enumerate() {
+                seen += count;
+                if seen >= rank {
+                    return bucket_upper_bound(bucket).min(self.max_score);
+                }
+            }
+            self.max_score
+        };
+
+        ScorePercentiles {
+            p50: percentile(0.50),
+            p90: percentile(0.90),
+            p99: percentile(0.99),
+            max: self.max_score,
+        }
+    }
+}
+
+/// Histogram bucket of a score; bucket 0 holds scores up to 1
+fn score_bucket(score: f64) -> usize {
+    if score.is_nan() || score <= 1.0 {
+        return 0;
+    }
+    let bucket = (score.log2() * BUCKETS_PER_OCTAVE).ceil() as usize;
+    bucket.min(SCORE_BUCKETS - 1)
+}
+
+/// Highest score in a histogram bucket
+fn bucket_upper_bound(bucket: usize) -> f64 {
+    if bucket == SCORE_BUCKETS - 1 {
+        return f64::INFINITY;
+    }
+    (bucket as f64 / BUCKETS_PER_OCTAVE).exp2()
+}
+
+/// What a confirmation estimate is based on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EstimateBasis {
+    /// New transactions are confirmed as soon as they are added
+    Instant,
+
+    /// Mean latency of recent confirmations
+    Measured,
+
+    /// Current throughput and tip count; nothing confirmed recently
+    Modelled,
+
+    /// No recent activity to base an estimate on
+    Unknown,
+}
+
+/// Expected wait for a new transaction to be confirmed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConfirmationEstimate {
+    /// Expected seconds to confirmation, if known
+    pub seconds: Option<f64>,
+
+    /// What the estimate is based on
+    pub basis: EstimateBasis,
+
+    /// Transactions added per second the estimate assumes
+    pub tps: f64,
+
+    /// Number of tips the estimate assumes
+    pub tips: usize,
+}
+
+/// Estimates how long a transaction added now takes to confirm
+///
+/// Recent confirmations are the best guide, so their mean latency is used
+/// when there are any. Otherwise the wait is modelled from throughput
+/// `R`, tip count `K`, threshold `T` and decay `d`: the transaction waits
+/// about `K / 2R` seconds to be referenced by one of the two parents of a
+/// new transaction, then needs about `(T - 1) / d` descendants, which
+/// arrive at rate `R`.
+pub fn estimate_confirmation(metrics: &MetricsSnapshot, params: &ProtocolParams) -> ConfirmationEstimate {
+    let tps = metrics.tps;
+    let tips = metrics.tip_history.last().map_or(0, |s| s.tips);
+    let estimate = |seconds, basis| ConfirmationEstimate { seconds, basis, tps, tips };
+
+    if params.confirmation_threshold <= 1.0 {
+        return estimate(Some(0.0), EstimateBasis::Instant);
+    }
+    if let Some(latency) = metrics.avg_confirmation_latency_secs {
+        return estimate(Some(latency), EstimateBasis::Measured);
+    }
+    if tps <= 0.0 {
+        return estimate(None, EstimateBasis::Unknown);
+    }
+
+    let first_approval = tips.max(1) as f64 / (2.0 * tps);
+    let descendants = (params.confirmation_threshold - 1.0) / params.score_decay_factor;
+    estimate(Some(first_approval + descendants / tps), EstimateBasis::Modelled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rolling_tps_and_latency() {
+        let metrics = DagMetrics::new();
+        for i in 0..30u8 {
+            metrics.record_added([i; 32], 1000 + (i as u64) / 10, false);
+        }
+        metrics.record_confirmed(&[0; 32], 1004);
+        metrics.record_confirmed(&[1; 32], 1008);
+        metrics.record_confirmed(&[1; 32], 1009);
+
+        let snapshot = metrics.snapshot(1009);
+        assert_eq!(snapshot.tps, 3.0);
+        assert_eq!(snapshot.avg_confirmation_latency_secs, Some(6.0));
+
+        // Everything ages out of the window
+        let snapshot = metrics.snapshot(1000 + METRICS_WINDOW_SECS + 10);
+        assert_eq!(snapshot.tps, 0.0);
+        assert_eq!(snapshot.avg_confirmation_latency_secs, None);
+    }
+
+    #[test]
+    fn test_tip_history_keeps_last_sample_per_second() {
+        let metrics = DagMetrics::new();
+        metrics.record_tips(1, 100);
+        metrics.record_tips(3, 100);
+        metrics.record_tips(2, 101);
+
+        assert_eq!(metrics.snapshot(101).tip_history, vec![
+            TipSample { timestamp: 100, tips: 3 },
+            TipSample { timestamp: 101, tips: 2 },
+        ]);
+
+        metrics.record_tips(5, 100 + METRICS_WINDOW_SECS);
+        assert_eq!(metrics.snapshot(100 + METRICS_WINDOW_SECS).tip_history.len(), 2);
+    }
+
+    #[test]
+    fn test_score_percentiles() {
+        let metrics = DagMetrics::new();
+        for _ in 0..90 {
+            metrics.record_score(None, 1.0);
+        }
+        for _ in 0..10 {
+            metrics.record_score(None, 1.0);
+            metrics.record_score(Some(1.0), 40.0);
+        }
+
+        let percentiles = metrics.snapshot(0).score_percentiles;
+        assert_eq!(percentiles.p50, 1.0);
+        assert_eq!(percentiles.p90, 1.0);
+        assert!(percentiles.p99 >= 40.0 / 1.19 && percentiles.p99 <= 40.0);
+        assert_eq!(percentiles.max, 40.0);
+    }
+
+    #[test]
+    fn test_estimate_confirmation() {
+        let params = ProtocolParams::for_network(crate::NetworkId::Mainnet);
+        let mut metrics = MetricsSnapshot::default();
+        assert_eq!(estimate_confirmation(&metrics, &params).basis, EstimateBasis::Unknown);
+        assert_eq!(estimate_confirmation(&metrics, &params).seconds, None);
+
+        // 10 tips at 1,000 TPS: 5 ms to the first approval, then
+        // (100 - 1) / 0.9 = 110 descendants at 1,000 TPS
+        metrics.tps = 1000.0;
+        metrics.tip_history = vec![TipSample { timestamp: 0, tips: 10 }];
+        let modelled = estimate_confirmation(&metrics, &params);
+        assert_eq!(modelled.basis, EstimateBasis::Modelled);
+        assert!((modelled.seconds.unwrap() - 0.115).abs() < 1e-9);
+
+        metrics.avg_confirmation_latency_secs = Some(8.0);
+        let measured = estimate_confirmation(&metrics, &params);
+        assert_eq!((measured.seconds, measured.basis), (Some(8.0), EstimateBasis::Measured));
+
+        let regtest = ProtocolParams::for_network(crate::NetworkId::Regtest);
+        assert_eq!(estimate_confirmation(&metrics, &regtest).basis, EstimateBasis::Instant);
+    }
+}
@@ -0,0 +1,138 @@
+// src/network.rs
+
+//! Network (chain) identifiers.
+//!
+//! Every network has its own [`NetworkId`]. It is part of the transaction
+//! signing message, the peer handshake and the address encoding, so a
+//! transaction, peer or address from one network is rejected by all others.
+
+use crate::errors::{NyxError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Identifies the network a node, transaction or address belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum NetworkId {
+    /// Production network
+    #[default]
+    Mainnet,
+
+    /// Public test network
+    Testnet,
+
+    /// Shared development network
+    Devnet,
+
+    /// Local regression-test network with instant finality and minting
+    Regtest,
+}
+
+impl NetworkId {
+    /// All known networks
+    pub const ALL: [NetworkId; 4] = [
+        NetworkId::Mainnet,
+        NetworkId::Testnet,
+        NetworkId::Devnet,
+        NetworkId::Regtest,
+    ];
+
+    /// Wire identifier used in canonical encodings
+    pub fn as_u8(self) -> u8 {
+        match self {
+            NetworkId::Mainnet => 0,
+            NetworkId::Testnet => 1,
+            NetworkId::Devnet => 2,
+            NetworkId::Regtest => 3,
+        }
+    }
+
+    /// Parses a wire identifier
+    ///
+    /// # Errors
+    /// - `SerializationError` if the identifier is unknown
+    pub fn from_u8(id: u8) -> Result<Self> {
+        Self::ALL.into_iter()
+            .find(|n| n.as_u8() == id)
+            .ok_or_else(|| NyxError::SerializationError(format!("Unknown network id {}", id)))
+    }
+
+    /// Lowercase network name
+    pub fn name(self) -> &'static str {
+        match self {
+            NetworkId::Mainnet => "mainnet",
+            NetworkId::Testnet => "testnet",
+            NetworkId::Devnet => "devnet",
+            NetworkId::Regtest => "regtest",
+        }
+    }
+
+    /// Human-readable prefix of addresses on this network
+    pub fn address_prefix(self) -> &'static str {
+        match self {
+            NetworkId::Mainnet => "nyx",
+            NetworkId::Testnet => "tnyx",
+            NetworkId::Devnet => "dnyx",
+            NetworkId::Regtest => "rnyx",
+        }
+    }
+
+    /// Whether every transaction is finalized as soon as it is added
+    pub fn instant_finality(self) -> bool {
+        self == NetworkId::Regtest
+    }
+
+    /// Whether funded outputs can be created out of thin air
+    pub fn allows_minting(self) -> bool {
+        self == NetworkId::Regtest
+    }
+
+    /// Looks up the network for an address prefix
+    pub fn from_address_prefix(prefix: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|n| n.address_prefix() == prefix)
+    }
+}
+
+impl fmt::Display for NetworkId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for NetworkId {
+    type Err = NyxError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL.into_iter()
+            .find(|n| n.name() == s.to_ascii_lowercase())
+            .ok_or_else(|| NyxError::SerializationError(format!("Unknown network '{}'", s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifiers_roundtrip() {
+        for network in NetworkId::ALL {
+            assert_eq!(NetworkId::from_u8(network.as_u8()).unwrap(), network);
+            assert_eq!(network.name().parse::<NetworkId>().unwrap(), network);
+            assert_eq!(NetworkId::from_address_prefix(network.address_prefix()), Some(network));
+        }
+
+        assert!(NetworkId::from_u8(200).is_err());
+        assert!("moonnet".parse::<NetworkId>().is_err());
+    }
+
+    #[test]
+    fn test_only_regtest_is_instant() {
+        assert!(NetworkId::Regtest.instant_finality());
+        assert!(NetworkId::Regtest.allows_minting());
+
+        for network in [NetworkId::Mainnet, NetworkId::Testnet, NetworkId::Devnet] {
+            assert!(!network.instant_finality());
+            assert!(!network.allows_minting());
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// src/params.rs
+
+//! Consensus parameters and genesis.
+//!
+//! Every value nodes must agree on to validate and confirm transactions
+//! lives in [`ProtocolParams`]. A network's parameters come from its
+//! [`Genesis`], either the built-in one for a [`NetworkId`] or one loaded
+//! from a JSON file for a custom network, and are handed to the DAG, the
+//! mempool and the network layer instead of being read from constants.
+
+use crate::errors::{NyxError, Result};
+use crate::limits::DecodeLimits;
+use crate::network::NetworkId;
+use crate::{
+    CONFIRMATION_THRESHOLD, REGTEST_CONFIRMATION_THRESHOLD, SCORE_DECAY_FACTOR,
+    SNAPSHOT_INTERVAL_SECS, TIP_SELECTION_ALPHA,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Consensus parameters of a network
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProtocolParams {
+    /// Size and count limits of a transaction
+    #[serde(flatten)]
+    pub limits: DecodeLimits,
+
+    /// DAG score at which transactions count as confirmed
+    pub confirmation_threshold: f64,
+
+    /// Weight of a child's score in its parent's score
+    pub score_decay_factor: f64,
+
+    /// Tip selection bias towards high scores (0.0 = uniform)
+    pub tip_selection_alpha: f64,
+
+    /// Seconds between PoS snapshots
+    pub snapshot_interval_secs: u64,
+}
+
+impl ProtocolParams {
+    /// Built-in parameters of a network
+    pub fn for_network(network: NetworkId) -> Self {
+        let confirmation_threshold = match network {
+            NetworkId::Regtest => REGTEST_CONFIRMATION_THRESHOLD,
+            _ => CONFIRMATION_THRESHOLD,
+        };
+
+        Self {
+            limits: DecodeLimits::default(),
+            confirmation_threshold,
+            score_decay_factor: SCORE_DECAY_FACTOR,
+            tip_selection_alpha: TIP_SELECTION_ALPHA,
+            snapshot_interval_secs: SNAPSHOT_INTERVAL_SECS,
+        }
+    }
+
+    /// Checks that the parameters describe a usable network
+    ///
+    /// # Errors
+    /// - `ConsensusError` naming the first out-of-range parameter
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: &str| Err(NyxError::ConsensusError(format!("Invalid protocol params: {}", msg)));
+
+        if !(self.confirmation_threshold >= 1.0 && self.confirmation_threshold.is_finite()) {
+            return invalid("confirmation_threshold must be at least 1");
+        }
+        if !(self.score_decay_factor > 0.0 && self.score_decay_factor <= 1.0) {
+            return invalid("score_decay_factor must be in (0, 1]");
+        }
+        if !(self.tip_selection_alpha >= 0.0 && self.tip_selection_alpha.is_finite()) {
+            return invalid("tip_selection_alpha must be non-negative");
+        }
+        if self.snapshot_interval_secs == 0 {
+            return invalid("snapshot_interval_secs must be positive");
+        }
+        if self.limits.max_tx_size == 0 || self.limits.max_outputs == 0 || self.limits.max_ring_size == 0 {
+            return invalid("transaction limits must be positive");
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for ProtocolParams {
+    fn default() -> Self {
+        Self::for_network(NetworkId::default())
+    }
+}
+
+/// Genesis description of a network
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Genesis {
+    /// Network the genesis belongs to
+    pub network: NetworkId,
+
+    /// Consensus parameters
+    pub params: ProtocolParams,
+}
+
+impl Genesis {
+    /// Built-in genesis of a network
+    pub fn for_network(network: NetworkId) -> Self {
+        Self {
+            network,
+            params: ProtocolParams::for_network(network),
+        }
+    }
+
+    /// Parses and validates a JSON genesis
+    ///
+    /// # Errors
+    /// - `SerializationError` if the JSON is malformed
+    /// - `ConsensusError` if a parameter is out of range
+    pub fn from_json(json: &str) -> Result<Self> {
+        let genesis: Self = serde_json::from_str(json)
+            .map_err(|e| NyxError::SerializationError(format!("Invalid genesis: {}", e)))?;
+        genesis.params.validate()?;
+        Ok(genesis)
+    }
+
+    /// Loads and validates a JSON genesis file
+    ///
+    /// # Errors
+    /// - `StorageError` if the file cannot be read
+    /// - Any error from [`from_json`](Self::from_json)
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| NyxError::StorageError(format!("Failed to read genesis {}: {}", path.display(), e)))?;
+        Self::from_json(&json)
+    }
+
+    /// Serializes the genesis as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("genesis serializes to JSON")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_params_are_valid() {
+        for network in NetworkId::ALL {
+            let params = ProtocolParams::for_network(network);
+            params.validate().unwrap();
+            assert_eq!(params.limits, DecodeLimits::default());
+        }
+
+        assert!(ProtocolParams::for_network(NetworkId::Regtest).confirmation_threshold
+            < ProtocolParams::for_network(NetworkId::Mainnet).confirmation_threshold);
+    }
+
+    #[test]
+    fn test_genesis_json_roundtrip() {
+        let mut genesis = Genesis::for_network(NetworkId::Devnet);
+        genesis.params.limits.max_ring_size = 32;
+        genesis.params.confirmation_threshold = 20.0;
+
+        let json = genesis.to_json();
+        assert!(json.contains("\"max_ring_size\": 32"));
+        assert_eq!(Genesis::from_json(&json).unwrap(), genesis);
+    }
+
+    #[test]
+    fn test_invalid_genesis_rejected() {
+        let mut genesis = Genesis::for_network(NetworkId::Testnet);
+        genesis.params.score_decay_factor = 1.5;
+        assert!(matches!(Genesis::from_json(&genesis.to_json()), Err(NyxError::ConsensusError(_))));
+
+        assert!(matches!(Genesis::from_json("{}"), Err(NyxError::SerializationError(_))));
+    }
+
+    #[test]
+    fn test_load_genesis_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("genesis.json");
+        std::fs::write(&path, Genesis::for_network(NetworkId::Regtest).to_json()).unwrap();
+
+        assert_eq!(Genesis::load(&path).unwrap().network, NetworkId::Regtest);
+        assert!(Genesis::load(&dir.path().join("missing.json")).is_err());
+    }
+}
@@ -0,0 +1,267 @@
+// src/snapshot.rs
+
+//! Binary snapshot format for fast-syncing the DAG.
+//!
+//! A snapshot captures everything needed to rebuild a `DagProcessor`
+//! without replaying gossip. Layout (all integers little-endian):
+//!
+//! ```text
+//! magic        "NYXSNAP\0"            8 bytes
+//! version      u8
+//! tx_count     u32
+//! entries      tx_count × { canonical tx, score: u64 (f64 bits), state: u8,
+//!                           indexed: u8, output_index: u64 if indexed }
+//! tip_count    u32
+//! tips         tip_count × hash
+//! checksum     blake3(all preceding bytes)  32 bytes
+//! ```
+//!
+//! Entries are written in topological order so parents always precede
+//! their children. Confirmed entries carry the global index of their first
+//! output, since outputs are indexed in confirmation order and ring indices
+//! must resolve to the same outputs on every node. Snapshots are trusted input: signatures and balances are
+//! not re-verified on import, only the format, checksum and DAG shape.
+
+use crate::dag::TxState;
+use crate::encoding::{CanonicalEncode, Decoder, Encoder};
+use crate::errors::{NyxError, Result};
+use crate::transaction::Transaction;
+use crate::types::Hash;
+use std::io::{Read, Write};
+
+/// Magic bytes identifying a snapshot file
+pub const SNAPSHOT_MAGIC: &[u8; 8] = b"NYXSNAP\0";
+
+/// Current snapshot format version
+pub const SNAPSHOT_VERSION: u8 = 2;
+
+/// Size of the trailing checksum
+const CHECKSUM_SIZE: usize = 32;
+
+/// Smallest possible encoded entry, used to bound allocation on decode
+const MIN_ENTRY_SIZE: usize = 64;
+
+/// A transaction together with its DAG metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotEntry {
+    /// The transaction
+    pub tx: Transaction,
+
+    /// Confirmation score
+    pub score: f64,
+
+    /// Confirmation state
+    pub state: TxState,
+
+    /// Global index of the first output, if the transaction is confirmed
+    /// and has outputs
+    pub output_index: Option<u64>,
+}
+
+/// In-memory form of a DAG snapshot
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DagSnapshot {
+    /// Transactions in topological order
+    pub entries: Vec<SnapshotEntry>,
+
+    /// Tips at the time of the snapshot
+    pub tips: Vec<Hash>,
+}
+
+impl DagSnapshot {
+    /// Encodes the snapshot, including magic, version and checksum
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut enc = Encoder::new();
+        for byte in SNAPSHOT_MAGIC {
+            enc.put_u8(*byte);
+        }
+        enc.put_u8(SNAPSHOT_VERSION);
+
+        enc.put_len(self.entries.len());
+        for entry in &self.entries {
+            entry.tx.encode_into(&mut enc);
+            enc.put_u64(entry.score.to_bits());
+            enc.put_u8(state_to_u8(entry.state));
+            match entry.output_index {
+                Some(index) => {
+                    enc.put_u8(1);
+                    enc.put_u64(index);
+                }
+                None => enc.put_u8(0),
+            }
+        }
+
+        enc.put_len(self.tips.len());
+        for tip in &self.tips {
+            enc.put_hash(tip);
+        }
+
+        let mut bytes = enc.finish();
+        let checksum = blake3::hash(&bytes);
+        bytes.extend_from_slice(checksum.as_bytes());
+        bytes
+    }
+
+    /// Decodes and checksums a snapshot
+    ///
+    /// # Errors
+    /// Returns `SerializationError` on bad magic, unsupported version,
+    /// checksum mismatch or malformed contents.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < SNAPSHOT_MAGIC.len() + 1 + CHECKSUM_SIZE {
+            return Err(NyxError::SerializationError("Snapshot truncated".to_string()));
+        }
+
+        let (body, checksum) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+        if !nyx_crypto::ct::ct_eq(blake3::hash(body).as_bytes(), checksum) {
+            return Err(NyxError::SerializationError("Snapshot checksum mismatch".to_string()));
+        }
+
+        let mut dec = Decoder::new(body);
+        for expected in SNAPSHOT_MAGIC {
+            if dec.get_u8()? != *expected {
+                return Err(NyxError::SerializationError("Not a Nyx snapshot".to_string()));
+            }
+        }
+
+        let version = dec.get_u8()?;
+        if version != SNAPSHOT_VERSION {
+            return Err(NyxError::SerializationError(format!(
+                "Unsupported snapshot version {} (expected {})",
+                version, SNAPSHOT_VERSION
+            )));
+        }
+
+        let entry_count = dec.get_len(MIN_ENTRY_SIZE)?;
+        let mut entries = Vec::with_capacity(entry_count);
+        for _ in 0..entry_count {
+            let tx = Transaction::decode_from(&mut dec)?;
+            let score = f64::from_bits(dec.get_u64()?);
+            let state = state_from_u8(dec.get_u8()?)?;
+            let output_index = match dec.get_u8()? {
+                0 => None,
+                1 => Some(dec.get_u64()?),
+                other => return Err(NyxError::SerializationError(format!("Invalid output index flag {}", other))),
+            };
+            entries.push(SnapshotEntry { tx, score, state, output_index });
+        }
+
+        let tip_count = dec.get_len(32)?;
+        let mut tips = Vec::with_capacity(tip_count);
+        for _ in 0..tip_count {
+            tips.push(dec.get_hash()?);
+        }
+
+        dec.finish()?;
+
+        Ok(Self { entries, tips })
+    }
+
+    /// Writes the encoded snapshot to a writer
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<()> {
+        writer.write_all(&self.to_bytes())
+            .and_then(|_| writer.flush())
+            .map_err(|e| NyxError::StorageError(format!("Failed to write snapshot: {}", e)))
+    }
+
+    /// Reads and decodes a snapshot from a reader
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)
+            .map_err(|e| NyxError::StorageError(format!("Failed to read snapshot: {}", e)))?;
+
+        Self::from_bytes(&bytes)
+    }
+}
+
+fn state_to_u8(state: TxState) -> u8 {
+    match state {
+        TxState::Pending => 0,
+        TxState::Confirmed => 1,
+        TxState::Finalized => 2,
+        TxState::Conflicted => 3,
+    }
+}
+
+fn state_from_u8(value: u8) -> Result<TxState> {
+    match value {
+        0 => Ok(TxState::Pending),
+        1 => Ok(TxState::Confirmed),
+        2 => Ok(TxState::Finalized),
+        3 => Ok(TxState::Conflicted),
+        other => Err(NyxError::SerializationError(format!("Invalid transaction state {}", other))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::{TxInput, TxOutput};
+    use nyx_crypto::RingSignature;
+
+    fn sample_snapshot() -> DagSnapshot {
+        let tx = Transaction::try_new(
+            vec![TxInput {
+                prev_tx: [1u8; 32],
+                index: 0,
+                key_image: [2u8; 32],
+                ring_indices: vec![3],
+            }],
+            vec![TxOutput {
+                stealth_address: vec![4],
+                amount_commitment: vec![5],
+                range_proof: vec![6],
+                ephemeral_pubkey: vec![7],
+                unlock_time: None,
+                htlc: None,
+            }],
+            vec![RingSignature {
+                ring_members: vec![vec![1], vec![2]],
+                signature: vec![8],
+                key_image: [9u8; 32],
+            }],
+            vec![10],
+            [0u8; 32],
+            [1u8; 32],
+        ).unwrap();
+        let tip = tx.id();
+
+        DagSnapshot {
+            entries: vec![SnapshotEntry { tx, score: 1.5, state: TxState::Confirmed, output_index: Some(3) }],
+            tips: vec![tip],
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let snapshot = sample_snapshot();
+        let bytes = snapshot.to_bytes();
+        assert_eq!(&bytes[..8], SNAPSHOT_MAGIC);
+        assert_eq!(DagSnapshot::from_bytes(&bytes).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_corruption_detected() {
+        let mut bytes = sample_snapshot().to_bytes();
+        let mid = bytes.len() / 2;
+        bytes[mid] ^= 0xFF;
+        assert!(matches!(DagSnapshot::from_bytes(&bytes), Err(NyxError::SerializationError(_))));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let mut bytes = sample_snapshot().to_bytes();
+        bytes[8] = SNAPSHOT_VERSION + 1;
+        let body_len = bytes.len() - CHECKSUM_SIZE;
+        let checksum = blake3::hash(&bytes[..body_len]);
+        bytes[body_len..].copy_from_slice(checksum.as_bytes());
+
+        let err = DagSnapshot::from_bytes(&bytes).unwrap_err();
+        assert!(err.to_string().contains("Unsupported snapshot version"));
+    }
+
+    #[test]
+    fn test_truncated() {
+        assert!(DagSnapshot::from_bytes(&[0u8; 10]).is_err());
+    }
+}
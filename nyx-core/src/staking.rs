@@ -0,0 +1,129 @@
+// src/staking.rs
+
+//! Validator stake bookkeeping and slashing.
+//!
+//! The [`StakingRegistry`] holds each validator's bonded stake in signer-index
+//! order and produces the [`ValidatorSet`] used to check snapshot quorums.
+//! Verified [`Evidence`] burns part of the offender's stake; each offense is
+//! punished at most once.
+
+use crate::consensus::{Validator, ValidatorSet};
+use crate::errors::{NyxError, Result};
+use crate::evidence::Evidence;
+use crate::types::Hash;
+use std::collections::HashSet;
+
+/// Percentage of stake burned for equivocation
+pub const EQUIVOCATION_SLASH_PERCENT: u64 = 50;
+
+/// Bonded stake of every validator
+#[derive(Debug, Clone)]
+pub struct StakingRegistry {
+    validators: Vec<Validator>,
+    applied_evidence: HashSet<Hash>,
+    total_slashed: u64,
+}
+
+impl StakingRegistry {
+    /// Creates a registry from validators in signer-index order
+    pub fn new(validators: Vec<Validator>) -> Self {
+        Self {
+            validators,
+            applied_evidence: HashSet::new(),
+            total_slashed: 0,
+        }
+    }
+
+    /// Gets a validator's current stake
+    pub fn stake(&self, index: u32) -> Option<u64> {
+        self.validators.get(index as usize).map(|v| v.stake)
+    }
+
+    /// Returns the total stake burned by slashing
+    pub fn total_slashed(&self) -> u64 {
+        self.total_slashed
+    }
+
+    /// Builds the validator set for quorum checks from current stakes
+    ///
+    /// # Errors
+    /// - `ConsensusError` if no stake remains
+    pub fn validator_set(&self) -> Result<ValidatorSet> {
+        ValidatorSet::new(self.validators.clone())
+    }
+
+    /// Verifies evidence and slashes the offending validator
+    ///
+    /// # Errors
+    /// - `InvalidEvidence` if the evidence does not verify against the
+    ///   current validator set, or was already applied
+    ///
+    /// # Returns
+    /// Amount of stake burned
+    pub fn apply_evidence(&mut self, evidence: &Evidence) -> Result<u64> {
+        let id = evidence.id();
+        if self.applied_evidence.contains(&id) {
+            return Err(NyxError::InvalidEvidence(format!(
+                "Evidence already applied: {}",
+                hex::encode(id)
+            )));
+        }
+
+        evidence.verify(&self.validator_set()?)?;
+
+        let percent = match evidence {
+            Evidence::Equivocation(_) => EQUIVOCATION_SLASH_PERCENT,
+        };
+        let slashed = self.slash(evidence.validator(), percent);
+        self.applied_evidence.insert(id);
+
+        Ok(slashed)
+    }
+
+    /// Burns `percent` of a validator's stake, returning the amount burned
+    fn slash(&mut self, index: u32, percent: u64) -> u64 {
+        let Some(validator) = self.validators.get_mut(index as usize) else {
+            return 0;
+        };
+
+        let amount = (validator.stake as u128 * percent.min(100) as u128 / 100) as u64;
+        validator.stake -= amount;
+        self.total_slashed += amount;
+        amount
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evidence::tests::{equivocation, setup};
+
+    #[test]
+    fn test_equivocation_slashes_once() {
+        let (set, keys) = setup(3);
+        let mut registry = StakingRegistry::new(set.validators().to_vec());
+        let evidence = equivocation(&keys, 2);
+
+        assert_eq!(registry.apply_evidence(&evidence).unwrap(), 50);
+        assert_eq!(registry.stake(2), Some(50));
+        assert_eq!(registry.stake(0), Some(100));
+        assert_eq!(registry.total_slashed(), 50);
+        assert_eq!(registry.validator_set().unwrap().total_stake(), 250);
+
+        let replay = registry.apply_evidence(&evidence);
+        assert!(matches!(replay, Err(NyxError::InvalidEvidence(_))));
+        assert_eq!(registry.stake(2), Some(50));
+    }
+
+    #[test]
+    fn test_invalid_evidence_not_slashed() {
+        let (set, keys) = setup(2);
+        let mut registry = StakingRegistry::new(set.validators().to_vec());
+
+        let Evidence::Equivocation(mut forged) = equivocation(&keys, 1);
+        forged.second = forged.first.clone();
+
+        assert!(registry.apply_evidence(&Evidence::Equivocation(forged)).is_err());
+        assert_eq!(registry.total_slashed(), 0);
+    }
+}
@@ -4,12 +4,42 @@
 //!
 //! This module will eventually use a production database like RocksDB
 //! for efficient key-value storage of transactions, DAG structure, and state.
+//!
+//! Writes can be grouped in a [`WriteBatch`] that is made durable with a
+//! single flush on commit, as a database would with one write-ahead log
+//! sync per batch. The [`FsyncPolicy`] decides when flushes happen.
+//!
+//! Transactions are kept behind an [`Arc`] and read through a [`TxCache`],
+//! so [`MemoryStorage::get_transaction_shared`] hands out the copy other
+//! components already share instead of cloning it.
+//!
+//! Outputs get a global index when their transaction is confirmed: the
+//! outputs of each newly confirmed transaction take the next indices, in
+//! output order. Ring members refer to outputs by these indices.
 
 use crate::errors::{NyxError, Result};
-use crate::types::Hash;
-use crate::transaction::Transaction;
+use crate::types::{Hash, Timestamp};
+use crate::transaction::{Transaction, TxOutput};
+use crate::tx_cache::TxCache;
+use dashmap::mapref::entry::Entry;
+use dashmap::{DashMap, DashSet};
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// When writes are flushed to durable storage
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// Flush after every single write and every batch
+    Always,
+
+    /// Flush once per committed batch; single writes are not flushed
+    #[default]
+    Batch,
+
+    /// Never flush implicitly; only [`MemoryStorage::flush`] does
+    Never,
+}
 
 /// In-memory storage for development and testing
 ///
@@ -18,42 +48,119 @@ use std::sync::{Arc, RwLock};
 #[derive(Clone)]
 pub struct MemoryStorage {
     /// Transactions indexed by their hash
-    transactions: Arc<RwLock<HashMap<Hash, Transaction>>>,
+    transactions: Arc<DashMap<Hash, Arc<Transaction>>>,
+
+    /// Recently read transactions
+    cache: TxCache,
+
+    /// Hashes of transactions that have been confirmed
+    confirmed: Arc<DashSet<Hash>>,
+
+    /// What is kept of pruned transactions
+    pruned: Arc<DashMap<Hash, PrunedTransaction>>,
 
-    /// Track which transactions have been confirmed
-    confirmed: Arc<RwLock<HashMap<Hash, bool>>>,
+    /// Global indices of confirmed outputs
+    output_index: Arc<Mutex<OutputIndex>>,
+
+    /// When writes are flushed
+    fsync: FsyncPolicy,
+
+    /// Number of flushes so far, shared by clones
+    flushes: Arc<AtomicU64>,
 }
 
 impl MemoryStorage {
     /// Creates a new empty in-memory storage
     pub fn new() -> Self {
         Self {
-            transactions: Arc::new(RwLock::new(HashMap::new())),
-            confirmed: Arc::new(RwLock::new(HashMap::new())),
+            transactions: Arc::new(DashMap::new()),
+            cache: TxCache::default(),
+            confirmed: Arc::new(DashSet::new()),
+            pruned: Arc::new(DashMap::new()),
+            output_index: Arc::new(Mutex::new(OutputIndex::default())),
+            fsync: FsyncPolicy::default(),
+            flushes: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Sets when writes are flushed
+    pub fn with_fsync_policy(mut self, fsync: FsyncPolicy) -> Self {
+        self.fsync = fsync;
+        self
+    }
+
+    /// Reads transactions through `cache`, normally one shared with the
+    /// mempool
+    pub fn with_tx_cache(mut self, cache: TxCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Gets the transaction cache
+    pub fn tx_cache(&self) -> &TxCache {
+        &self.cache
+    }
+
+    /// Gets the flush policy
+    pub fn fsync_policy(&self) -> FsyncPolicy {
+        self.fsync
+    }
+
+    /// Flushes all writes so far to durable storage
+    ///
+    /// A no-op for memory storage apart from counting the flush.
+    pub fn flush(&self) -> Result<()> {
+        self.flushes.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Returns the number of flushes so far
+    pub fn flush_count(&self) -> u64 {
+        self.flushes.load(Ordering::Relaxed)
+    }
+
+    /// Starts a batch of writes that are flushed together on commit
+    pub fn begin_batch(&self) -> WriteBatch {
+        WriteBatch {
+            storage: self.clone(),
+            stored: Vec::new(),
+            confirmed: Vec::new(),
+            committed: false,
         }
     }
 
     /// Stores a transaction
     ///
+    /// Transactions are keyed by ID, so a copy differing only in its ring
+    /// signature is refused as already stored.
+    ///
     /// # Arguments
     /// * `tx` - The transaction to store
     ///
     /// # Returns
     /// The transaction hash if successful
     pub fn store_transaction(&self, tx: Transaction) -> Result<Hash> {
-        let tx_id = tx.id();
+        let tx_id = self.insert_transaction(tx)?;
+        if self.fsync == FsyncPolicy::Always {
+            self.flush()?;
+        }
+        Ok(tx_id)
+    }
 
-        let mut txs = self.transactions.write()
-            .map_err(|e| NyxError::StorageError(format!("Lock poisoned: {}", e)))?;
+    /// Inserts a transaction without flushing
+    fn insert_transaction(&self, tx: Transaction) -> Result<Hash> {
+        let tx_id = tx.id();
 
-        if txs.contains_key(&tx_id) {
-            return Err(NyxError::StorageError(
+        match self.transactions.entry(tx_id) {
+            Entry::Occupied(_) => Err(NyxError::StorageError(
                 "Transaction already exists".to_string()
-            ));
+            )),
+            Entry::Vacant(entry) => {
+                // Replaces any copy cached before a rolled back batch
+                entry.insert(self.cache.insert(Arc::new(tx)));
+                Ok(tx_id)
+            }
         }
-
-        txs.insert(tx_id, tx);
-        Ok(tx_id)
     }
 
     /// Retrieves a transaction by hash
@@ -64,91 +171,289 @@ impl MemoryStorage {
     /// # Returns
     /// The transaction if found, error otherwise
     pub fn get_transaction(&self, tx_hash: &Hash) -> Result<Transaction> {
-        let txs = self.transactions.read()
-            .map_err(|e| NyxError::StorageError(format!("Lock poisoned: {}", e)))?;
+        self.get_transaction_shared(tx_hash).map(Arc::unwrap_or_clone)
+    }
 
-        txs.get(tx_hash)
-            .cloned()
+    /// Retrieves a transaction by hash without copying it
+    ///
+    /// Prefer this over [`get_transaction`](Self::get_transaction) on hot
+    /// paths; the transaction stays cached for the next reader.
+    pub fn get_transaction_shared(&self, tx_hash: &Hash) -> Result<Arc<Transaction>> {
+        if let Some(tx) = self.cache.get(tx_hash) {
+            return Ok(tx);
+        }
+        self.transactions.get(tx_hash)
+            .map(|tx| self.cache.insert(tx.clone()))
             .ok_or_else(|| NyxError::TransactionNotFound(
                 format!("Transaction not found: {:?}", tx_hash)
             ))
     }
 
-    /// Marks a transaction as confirmed
+    /// Marks a transaction as confirmed, assigning global indices to its
+    /// outputs the first time
     pub fn mark_confirmed(&self, tx_hash: &Hash) -> Result<()> {
-        let mut confirmed = self.confirmed.write()
-            .map_err(|e| NyxError::StorageError(format!("Lock poisoned: {}", e)))?;
-
-        confirmed.insert(*tx_hash, true);
+        if self.confirmed.insert(*tx_hash) {
+            self.assign_output_indices(tx_hash)?;
+        }
+        if self.fsync == FsyncPolicy::Always {
+            self.flush()?;
+        }
         Ok(())
     }
 
     /// Checks if a transaction is confirmed
     pub fn is_confirmed(&self, tx_hash: &Hash) -> Result<bool> {
-        let confirmed = self.confirmed.read()
-            .map_err(|e| NyxError::StorageError(format!("Lock poisoned: {}", e)))?;
+        Ok(self.confirmed.contains(tx_hash))
+    }
+
+    /// Gives the outputs of a newly confirmed transaction the next global
+    /// indices
+    fn assign_output_indices(&self, tx_hash: &Hash) -> Result<()> {
+        // Confirmations of unknown transactions have no outputs to index
+        let count = self.get_outputs(tx_hash).map_or(0, |outputs| outputs.len());
+        let mut index = self.lock_output_index()?;
+        if count > 0 && !index.first.contains_key(tx_hash) {
+            let first = index.outputs.len() as u64;
+            index.first.insert(*tx_hash, first);
+            index.outputs.extend((0..count as u32).map(|output| (*tx_hash, output)));
+        }
+        Ok(())
+    }
+
+    /// Removes the indices of a transaction if it holds the last ones
+    fn unassign_output_indices(&self, tx_hash: &Hash) -> Result<()> {
+        let mut index = self.lock_output_index()?;
+        if let Some(&first) = index.first.get(tx_hash) {
+            if index.outputs.last().is_some_and(|(last, _)| last == tx_hash) {
+                index.outputs.truncate(first as usize);
+                index.first.remove(tx_hash);
+            }
+        }
+        Ok(())
+    }
+
+    /// Locks the global output index
+    fn lock_output_index(&self) -> Result<std::sync::MutexGuard<'_, OutputIndex>> {
+        self.output_index.lock()
+            .map_err(|e| NyxError::StorageError(format!("Lock poisoned: {}", e)))
+    }
+
+    /// Returns the number of outputs with a global index
+    pub fn output_count(&self) -> Result<u64> {
+        Ok(self.lock_output_index()?.outputs.len() as u64)
+    }
+
+    /// Gets the global index of a transaction's first output, if it is
+    /// confirmed
+    pub fn first_output_index(&self, tx_hash: &Hash) -> Result<Option<u64>> {
+        Ok(self.lock_output_index()?.first.get(tx_hash).copied())
+    }
 
-        Ok(*confirmed.get(tx_hash).unwrap_or(&false))
+    /// Gets the transaction and output index at a global index
+    pub fn output_location(&self, global_index: u64) -> Result<Option<(Hash, u32)>> {
+        let index = self.lock_output_index()?;
+        Ok(usize::try_from(global_index).ok()
+            .and_then(|global_index| index.outputs.get(global_index))
+            .copied())
+    }
+
+    /// Gets the output at a global index, even if its transaction was pruned
+    ///
+    /// # Errors
+    /// - `StorageError` if no output has the index
+    pub fn get_output_by_index(&self, global_index: u64) -> Result<TxOutput> {
+        let not_found = || NyxError::StorageError(format!("No output with global index {}", global_index));
+        let (tx_hash, output) = self.output_location(global_index)?.ok_or_else(not_found)?;
+        self.get_outputs(&tx_hash)?
+            .into_iter()
+            .nth(output as usize)
+            .ok_or_else(not_found)
     }
 
     /// Returns the total number of stored transactions
     pub fn transaction_count(&self) -> Result<usize> {
-        let txs = self.transactions.read()
-            .map_err(|e| NyxError::StorageError(format!("Lock poisoned: {}", e)))?;
-
-        Ok(txs.len())
+        Ok(self.transactions.len())
     }
 
     /// Checks if a transaction exists
     pub fn has_transaction(&self, tx_hash: &Hash) -> Result<bool> {
-        let txs = self.transactions.read()
-            .map_err(|e| NyxError::StorageError(format!("Lock poisoned: {}", e)))?;
+        Ok(self.transactions.contains_key(tx_hash))
+    }
 
-        Ok(txs.contains_key(tx_hash))
+    /// Returns the hashes of all stored transactions (unordered)
+    pub fn transaction_hashes(&self) -> Result<Vec<Hash>> {
+        Ok(self.transactions.iter().map(|entry| *entry.key()).collect())
+    }
+
+    /// Drops a transaction's body, keeping only what later transactions
+    /// are validated against: its timestamp and outputs
+    ///
+    /// # Returns
+    /// `false` if the transaction is not stored
+    pub fn prune_transaction(&self, tx_hash: &Hash) -> Result<bool> {
+        let Some((_, tx)) = self.transactions.remove(tx_hash) else {
+            return Ok(false);
+        };
+        self.cache.remove(tx_hash);
+        self.pruned.insert(*tx_hash, PrunedTransaction {
+            timestamp: tx.timestamp,
+            outputs: tx.outputs.clone(),
+        });
+        if self.fsync == FsyncPolicy::Always {
+            self.flush()?;
+        }
+        Ok(true)
+    }
+
+    /// Checks if a transaction was pruned
+    pub fn is_pruned(&self, tx_hash: &Hash) -> bool {
+        self.pruned.contains_key(tx_hash)
+    }
+
+    /// Returns the number of pruned transactions
+    pub fn pruned_count(&self) -> usize {
+        self.pruned.len()
+    }
+
+    /// Gets the timestamp of a stored or pruned transaction
+    pub fn get_timestamp(&self, tx_hash: &Hash) -> Result<Timestamp> {
+        if let Some(tx) = self.transactions.get(tx_hash) {
+            return Ok(tx.timestamp);
+        }
+        self.pruned.get(tx_hash)
+            .map(|pruned| pruned.timestamp)
+            .ok_or_else(|| NyxError::TransactionNotFound(
+                format!("Transaction not found: {:?}", tx_hash)
+            ))
+    }
+
+    /// Gets the outputs of a stored or pruned transaction
+    pub fn get_outputs(&self, tx_hash: &Hash) -> Result<Vec<TxOutput>> {
+        if let Some(tx) = self.transactions.get(tx_hash) {
+            return Ok(tx.outputs.clone());
+        }
+        self.pruned.get(tx_hash)
+            .map(|pruned| pruned.outputs.clone())
+            .ok_or_else(|| NyxError::TransactionNotFound(
+                format!("Transaction not found: {:?}", tx_hash)
+            ))
     }
 }
 
+/// Global output indices
+#[derive(Default)]
+struct OutputIndex {
+    /// Transaction and output index of each global index
+    outputs: Vec<(Hash, u32)>,
+
+    /// Global index of each confirmed transaction's first output
+    first: HashMap<Hash, u64>,
+}
+
+/// Part of a pruned transaction that is kept
+struct PrunedTransaction {
+    /// Checked against the timestamps of children
+    timestamp: Timestamp,
+
+    /// Spendable by later transactions
+    outputs: Vec<TxOutput>,
+}
+
 impl Default for MemoryStorage {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Group of writes flushed together
+///
+/// Writes are visible as soon as they are made, so later writes in the
+/// batch can build on earlier ones. [`commit`](Self::commit) flushes them
+/// once according to the storage's [`FsyncPolicy`]; a batch dropped
+/// without committing undoes its writes.
+pub struct WriteBatch {
+    /// Storage written to
+    storage: MemoryStorage,
+
+    /// Transactions stored by this batch
+    stored: Vec<Hash>,
+
+    /// Transactions newly marked confirmed by this batch
+    confirmed: Vec<Hash>,
+
+    /// Whether the batch was committed
+    committed: bool,
+}
+
+impl WriteBatch {
+    /// Stores a transaction as part of the batch
+    ///
+    /// # Errors
+    /// - `StorageError` if the transaction already exists
+    pub fn store_transaction(&mut self, tx: Transaction) -> Result<Hash> {
+        let tx_id = self.storage.insert_transaction(tx)?;
+        self.stored.push(tx_id);
+        Ok(tx_id)
+    }
+
+    /// Marks a transaction as confirmed as part of the batch
+    pub fn mark_confirmed(&mut self, tx_hash: &Hash) -> Result<()> {
+        if self.storage.confirmed.insert(*tx_hash) {
+            self.confirmed.push(*tx_hash);
+            self.storage.assign_output_indices(tx_hash)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the number of writes in the batch
+    pub fn len(&self) -> usize {
+        self.stored.len() + self.confirmed.len()
+    }
+
+    /// Checks if the batch has no writes
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Makes the batch's writes durable
+    ///
+    /// Flushes unless the policy is [`FsyncPolicy::Never`] or the batch is
+    /// empty. If the flush fails the writes stay applied but may not
+    /// survive a crash.
+    pub fn commit(mut self) -> Result<()> {
+        self.committed = true;
+        if self.is_empty() || self.storage.fsync == FsyncPolicy::Never {
+            return Ok(());
+        }
+        self.storage.flush()
+    }
+}
+
+impl Drop for WriteBatch {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        for tx_id in &self.stored {
+            self.storage.transactions.remove(tx_id);
+            self.storage.cache.remove(tx_id);
+        }
+        for tx_id in self.confirmed.iter().rev() {
+            self.storage.confirmed.remove(tx_id);
+            let _ = self.storage.unassign_output_indices(tx_id);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::transaction::{TxInput, TxOutput};
-    use nyx_crypto::RingSignature;
-
-    fn create_test_tx() -> Transaction {
-        Transaction::new(
-            vec![TxInput {
-                prev_tx: [1u8; 32],
-                index: 0,
-                key_image: [0u8; 32],
-                ring_indices: vec![],
-            }],
-            vec![TxOutput {
-                stealth_address: vec![],
-                amount_commitment: vec![],
-                range_proof: vec![],
-                ephemeral_pubkey: vec![],
-            }],
-            RingSignature {
-                ring_members: vec![],
-                signature: vec![],
-                key_image: [0u8; 32],
-            },
-            vec![],
-            [0u8; 32],
-            [1u8; 32],
-        )
-    }
+    use crate::testing::TestTx;
 
     #[test]
     fn test_store_and_retrieve() {
         let storage = MemoryStorage::new();
-        let tx = create_test_tx();
+        let tx = TestTx::new(0).build();
         let expected_id = tx.id();
 
         let stored_id = storage.store_transaction(tx.clone()).unwrap();
@@ -161,7 +466,7 @@ mod tests {
     #[test]
     fn test_confirmed_tracking() {
         let storage = MemoryStorage::new();
-        let tx = create_test_tx();
+        let tx = TestTx::new(0).build();
         let tx_id = storage.store_transaction(tx).unwrap();
 
         assert!(!storage.is_confirmed(&tx_id).unwrap());
@@ -176,7 +481,113 @@ mod tests {
         let storage = MemoryStorage::new();
         assert_eq!(storage.transaction_count().unwrap(), 0);
 
-        storage.store_transaction(create_test_tx()).unwrap();
+        storage.store_transaction(TestTx::new(0).build()).unwrap();
         assert_eq!(storage.transaction_count().unwrap(), 1);
     }
+
+    #[test]
+    fn test_batch_flushes_once() {
+        let storage = MemoryStorage::new();
+        let mut batch = storage.begin_batch();
+        for nonce in 0..3u8 {
+            let mut tx = TestTx::new(0).build();
+            tx.extra = vec![nonce];
+            let tx_id = batch.store_transaction(tx).unwrap();
+            batch.mark_confirmed(&tx_id).unwrap();
+        }
+        assert_eq!(batch.len(), 6);
+        assert_eq!(storage.transaction_count().unwrap(), 3);
+
+        batch.commit().unwrap();
+        assert_eq!(storage.flush_count(), 1);
+
+        // Single writes are only flushed under the `Always` policy
+        storage.store_transaction(TestTx::new(0).build()).unwrap();
+        assert_eq!(storage.flush_count(), 1);
+
+        let always = MemoryStorage::new().with_fsync_policy(FsyncPolicy::Always);
+        always.store_transaction(TestTx::new(0).build()).unwrap();
+        assert_eq!(always.flush_count(), 1);
+
+        let never = MemoryStorage::new().with_fsync_policy(FsyncPolicy::Never);
+        let mut batch = never.begin_batch();
+        batch.store_transaction(TestTx::new(0).build()).unwrap();
+        batch.commit().unwrap();
+        assert_eq!(never.flush_count(), 0);
+    }
+
+    #[test]
+    fn test_malleated_copy_is_a_duplicate() {
+        let storage = MemoryStorage::new();
+        let tx = TestTx::new(0).build();
+        let stored = storage.store_transaction(tx.clone()).unwrap();
+
+        let mut malleated = tx;
+        malleated.ring_signatures[0].signature.push(0);
+        assert_eq!(malleated.id(), stored);
+        assert!(storage.store_transaction(malleated).is_err());
+    }
+
+    #[test]
+    fn test_dropped_batch_rolls_back() {
+        let storage = MemoryStorage::new();
+        let base = TestTx::new(0).build();
+        let existing = storage.store_transaction(base.clone()).unwrap();
+
+        let mut tx = base.clone();
+        tx.extra = vec![1];
+        let mut batch = storage.begin_batch();
+        let added = batch.store_transaction(tx).unwrap();
+        batch.mark_confirmed(&existing).unwrap();
+        assert!(batch.store_transaction(base).is_err());
+        drop(batch);
+
+        assert!(!storage.has_transaction(&added).unwrap());
+        assert!(storage.has_transaction(&existing).unwrap());
+        assert!(!storage.is_confirmed(&existing).unwrap());
+        assert_eq!(storage.output_count().unwrap(), 0);
+        assert_eq!(storage.flush_count(), 0);
+    }
+
+    #[test]
+    fn test_confirmed_outputs_get_global_indices() {
+        let storage = MemoryStorage::new();
+        let first = storage.store_transaction(TestTx::new(0).build()).unwrap();
+        let mut tx = TestTx::new(0).build();
+        tx.outputs.push(tx.outputs[0].clone());
+        tx.outputs[1].stealth_address = vec![7];
+        let second = storage.store_transaction(tx).unwrap();
+
+        // Indices follow confirmation order, not storage order
+        storage.mark_confirmed(&second).unwrap();
+        storage.mark_confirmed(&first).unwrap();
+        storage.mark_confirmed(&second).unwrap();
+        assert_eq!(storage.output_count().unwrap(), 3);
+        assert_eq!(storage.first_output_index(&second).unwrap(), Some(0));
+        assert_eq!(storage.first_output_index(&first).unwrap(), Some(2));
+        assert_eq!(storage.output_location(1).unwrap(), Some((second, 1)));
+        assert_eq!(storage.output_location(3).unwrap(), None);
+
+        // Pruned outputs still resolve
+        storage.prune_transaction(&second).unwrap();
+        assert_eq!(storage.get_output_by_index(1).unwrap().stealth_address, vec![7]);
+        assert!(storage.get_output_by_index(3).is_err());
+    }
+
+    #[test]
+    fn test_shared_reads_go_through_cache() {
+        let cache = TxCache::new(8);
+        let storage = MemoryStorage::new().with_tx_cache(cache.clone());
+        let tx_id = storage.store_transaction(TestTx::new(0).build()).unwrap();
+
+        let first = storage.get_transaction_shared(&tx_id).unwrap();
+        let second = storage.get_transaction_shared(&tx_id).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.stats().hits, 2);
+
+        // Pruned bodies are not served from the cache
+        storage.prune_transaction(&tx_id).unwrap();
+        assert!(cache.is_empty());
+        assert!(storage.get_transaction_shared(&tx_id).is_err());
+    }
 }
@@ -0,0 +1,243 @@
+// src/testing.rs
+
+//! Transaction fixtures for tests.
+//!
+//! Compiled for this crate's tests, and for other crates' tests with the
+//! `testing` feature, so every crate builds its test transactions the same
+//! way.
+//!
+//! Transactions that must pass
+//! [`VerifyMode::Full`](crate::verification::VerifyMode::Full) spend outputs of
+//! [`funding_tx`], which tests store and confirm before anything else.
+
+use crate::transaction::{Transaction, TxInput, TxOutput};
+use crate::types::{Hash, Timestamp};
+use nyx_crypto::keys::{generate_keypair_from_seed, KeyPair};
+use nyx_crypto::pedersen::{self, BlindingFactor};
+use nyx_crypto::range;
+use nyx_crypto::ring::{self, RingSignature};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::sync::OnceLock;
+
+/// Number of outputs of [`funding_tx`], one per nonce a funded
+/// transaction can have
+pub const FUNDING_OUTPUTS: u8 = 32;
+
+/// Amount of every funding output, and of the output of every funded
+/// transaction
+pub const TEST_AMOUNT: u64 = 100;
+
+/// Timestamp of [`funding_tx`]
+const FUNDING_TIMESTAMP: Timestamp = 1_700_000_000;
+
+/// Blinding factor of every funding and funded output, so that funded
+/// transactions balance
+pub fn test_blinding() -> BlindingFactor {
+    pedersen::blinding_from_bytes(b"nyx-test")
+}
+
+/// Key owning output `index` of [`funding_tx`]
+pub fn funding_key(index: u8) -> KeyPair {
+    generate_keypair_from_seed(&[index; 32])
+}
+
+/// Key image of the funding output [funded](TestTx::funded) transaction
+/// `nonce` spends
+pub fn funded_key_image(nonce: u8) -> [u8; 32] {
+    ring::generate_key_image(funding_key(nonce).private_key())
+}
+
+/// Genesis transaction paying [`TEST_AMOUNT`] to each of
+/// [`FUNDING_OUTPUTS`] outputs, output `n` owned by [`funding_key`]`(n)`
+///
+/// Every call returns the same transaction. Stored and confirmed before
+/// any other transaction, its output `n` gets global index `n`.
+pub fn funding_tx() -> Transaction {
+    static FUNDING: OnceLock<Transaction> = OnceLock::new();
+    FUNDING.get_or_init(|| {
+        let blinding = test_blinding();
+        let mut rng = StdRng::seed_from_u64(0);
+        let outputs = (0..FUNDING_OUTPUTS)
+            .map(|n| TxOutput {
+                stealth_address: funding_key(n).public_key,
+                amount_commitment: pedersen::commit(TEST_AMOUNT, &blinding).to_bytes().to_vec(),
+                range_proof: range::prove_with_rng(TEST_AMOUNT, &blinding, &mut rng),
+                ephemeral_pubkey: vec![],
+                unlock_time: None,
+                htlc: None,
+            })
+            .collect();
+        let mut tx = Transaction::new(vec![], outputs, vec![], vec![], [0u8; 32], [0u8; 32]);
+        tx.timestamp = FUNDING_TIMESTAMP;
+        tx
+    })
+    .clone()
+}
+
+/// Builder for a test transaction with one input and one output
+///
+/// Unless told otherwise, transaction `nonce` approves the placeholder
+/// parents `[0; 32]` and `[1; 32]`, spends output 0 of placeholder
+/// transaction `[nonce; 32]` under key image `[nonce; 32]`, and pays an
+/// unblinded output to stealth address `[nonce]`. Transactions with
+/// different nonces therefore never conflict.
+///
+/// The ring signature is left unsigned, so the DAG only accepts the
+/// transaction with
+/// [`VerifyMode::HeadersOnly`](crate::verification::VerifyMode::HeadersOnly)
+/// unless it is [funded](Self::funded).
+#[derive(Debug, Clone)]
+pub struct TestTx {
+    nonce: u8,
+    parents: (Hash, Hash),
+    spends: (Hash, u32),
+    output: TxOutput,
+    funded: bool,
+}
+
+impl TestTx {
+    /// Starts transaction `nonce`
+    pub fn new(nonce: u8) -> Self {
+        Self {
+            nonce,
+            parents: ([0u8; 32], [1u8; 32]),
+            spends: ([nonce; 32], 0),
+            output: TxOutput {
+                stealth_address: vec![nonce],
+                amount_commitment: vec![],
+                range_proof: vec![],
+                ephemeral_pubkey: vec![],
+                unlock_time: None,
+                htlc: None,
+            },
+            funded: false,
+        }
+    }
+
+    /// Approves `parent1` and `parent2`
+    pub fn parents(mut self, parent1: Hash, parent2: Hash) -> Self {
+        self.parents = (parent1, parent2);
+        self
+    }
+
+    /// Spends output `index` of `prev_tx`
+    pub fn spends(mut self, prev_tx: Hash, index: u32) -> Self {
+        self.spends = (prev_tx, index);
+        self
+    }
+
+    /// Pays to `stealth_address`
+    pub fn stealth_address(mut self, stealth_address: Vec<u8>) -> Self {
+        self.output.stealth_address = stealth_address;
+        self
+    }
+
+    /// Commits the output's amount with `commitment`, proven by
+    /// `range_proof`
+    pub fn amount(mut self, commitment: Vec<u8>, range_proof: Vec<u8>) -> Self {
+        self.output.amount_commitment = commitment;
+        self.output.range_proof = range_proof;
+        self
+    }
+
+    /// Spends [`funding_tx`] output `nonce` and pays [`TEST_AMOUNT`], signed
+    /// with the output's key
+    ///
+    /// The ring is the spent output and the next funding output, so the
+    /// transaction passes full verification once the funding transaction is
+    /// stored and confirmed first.
+    ///
+    /// # Panics
+    /// On [`build`](Self::build), if `nonce` is not below
+    /// [`FUNDING_OUTPUTS`].
+    pub fn funded(mut self) -> Self {
+        let blinding = test_blinding();
+        self.spends = (funding_tx().id(), u32::from(self.nonce));
+        self.output.amount_commitment = pedersen::commit(TEST_AMOUNT, &blinding).to_bytes().to_vec();
+        self.output.range_proof = range::prove(TEST_AMOUNT, &blinding);
+        self.funded = true;
+        self
+    }
+
+    /// Builds the transaction
+    ///
+    /// # Panics
+    /// If the parents are the same non-placeholder transaction.
+    pub fn build(self) -> Transaction {
+        let (prev_tx, index) = self.spends;
+        let mut tx = Transaction::try_new(
+            vec![TxInput {
+                prev_tx,
+                index,
+                key_image: [self.nonce; 32],
+                ring_indices: vec![],
+            }],
+            vec![self.output],
+            vec![RingSignature {
+                ring_members: vec![vec![1], vec![2]],
+                signature: vec![],
+                key_image: [self.nonce; 32],
+            }],
+            vec![self.nonce],
+            self.parents.0,
+            self.parents.1,
+        )
+        .expect("test transaction should be well formed");
+
+        if self.funded {
+            assert!(self.nonce < FUNDING_OUTPUTS, "no funding output {}", self.nonce);
+            let signer = funding_key(self.nonce);
+            let decoy = (self.nonce + 1) % FUNDING_OUTPUTS;
+            let mut members = vec![(self.nonce, signer.public_key.clone()), (decoy, funding_key(decoy).public_key)];
+            members.sort_by_key(|(index, _)| *index);
+
+            tx.inputs[0].key_image = funded_key_image(self.nonce);
+            tx.inputs[0].ring_indices = members.iter().map(|(index, _)| u32::from(*index)).collect();
+            let ring: Vec<Vec<u8>> = members.into_iter().map(|(_, member)| member).collect();
+            let message = tx.signing_message();
+            tx.sign(0, &message, signer.private_key(), &signer.public_key, &ring)
+                .expect("signer should be in its ring");
+        }
+        tx
+    }
+}
+
+/// Builds test transaction `nonce` approving `parent1` and `parent2`
+///
+/// Shorthand for the [`TestTx`] defaults with other parents.
+pub fn test_tx(parent1: Hash, parent2: Hash, nonce: u8) -> Transaction {
+    TestTx::new(nonce).parents(parent1, parent2).build()
+}
+
+/// Builds funded test transaction `nonce` approving `parent1` and `parent2`
+///
+/// Shorthand for a [funded](TestTx::funded) [`TestTx`] with other parents.
+pub fn funded_tx(parent1: Hash, parent2: Hash, nonce: u8) -> Transaction {
+    TestTx::new(nonce).parents(parent1, parent2).funded().build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distinct_nonces_do_not_conflict() {
+        let (a, b) = (test_tx([0u8; 32], [1u8; 32], 1), test_tx([0u8; 32], [1u8; 32], 2));
+        assert_ne!(a.id(), b.id());
+        assert_ne!(a.inputs[0].key_image, b.inputs[0].key_image);
+        assert_ne!(a.inputs[0].prev_tx, b.inputs[0].prev_tx);
+        assert_eq!(a.inputs[0].key_image, a.ring_signatures[0].key_image);
+    }
+
+    #[test]
+    fn test_funded_transactions_are_signed() {
+        assert_eq!(funding_tx().id(), funding_tx().id());
+
+        let tx = funded_tx([0u8; 32], [1u8; 32], FUNDING_OUTPUTS - 1);
+        assert!(tx.verify_signature().unwrap());
+        assert_eq!(tx.inputs[0].key_image, funded_key_image(FUNDING_OUTPUTS - 1));
+        assert_eq!(tx.inputs[0].prev_tx, funding_tx().id());
+        assert_eq!(tx.inputs[0].ring_indices, vec![0, u32::from(FUNDING_OUTPUTS - 1)]);
+    }
+}
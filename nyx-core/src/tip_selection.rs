@@ -3,16 +3,26 @@
 //! Tip selection algorithm for choosing parent transactions.
 //!
 //! Implements the weighted random walk algorithm from the Nyx whitepaper:
-//! - Starts from genesis or latest snapshot
-//! - Uses exponential weighting based on confirmation scores
-//! - Prevents double-spending attacks by favoring high-weight paths
+//! - Starts from the latest snapshot (finalized transaction) or genesis
+//! - Steps from a transaction to one of its children with probability
+//!   P(C) = exp(α × H(C)) / Σ exp(α × H(Ci)), where H is cumulative weight
+//! - Stops at a transaction with no children, which is a tip
+//!
+//! Favouring heavy branches makes it hard for an attacker to get their
+//! (lightweight) conflicting branch approved. If no walk start exists or a
+//! walk exceeds the maximum depth, selection falls back to sampling
+//! directly among the current tips ("lazy tips").
 
 use crate::errors::{NyxError, Result};
 use crate::types::Hash;
 use crate::dag::DagProcessor;
-use crate::TIP_SELECTION_ALPHA;
+use nyx_crypto::Entropy;
+use rand::seq::SliceRandom;
 use rand::Rng;
 
+/// Default maximum number of steps a random walk may take
+pub const DEFAULT_MAX_WALK_DEPTH: usize = 10_000;
+
 /// Tip selector implementing the weighted random walk algorithm
 pub struct TipSelector {
     /// Reference to the DAG processor
@@ -20,6 +30,12 @@ pub struct TipSelector {
 
     /// Alpha parameter controlling randomness (default: 0.5)
     alpha: f64,
+
+    /// Maximum number of steps before falling back to lazy tips
+    max_walk_depth: usize,
+
+    /// Randomness for walks, shared with the DAG unless overridden
+    entropy: Entropy,
 }
 
 impl TipSelector {
@@ -28,10 +44,8 @@ impl TipSelector {
     /// # Arguments
     /// * `dag` - The DAG processor to select tips from
     pub fn new(dag: DagProcessor) -> Self {
-        Self {
-            dag,
-            alpha: TIP_SELECTION_ALPHA,
-        }
+        let alpha = dag.tip_alpha();
+        Self::with_alpha(dag, alpha)
     }
 
     /// Creates a new tip selector with custom alpha parameter
@@ -40,52 +54,133 @@ impl TipSelector {
     /// * `dag` - The DAG processor
     /// * `alpha` - Controls randomness (0.0 = fully random, 1.0 = always pick highest score)
     pub fn with_alpha(dag: DagProcessor, alpha: f64) -> Self {
-        Self { dag, alpha }
+        let entropy = dag.entropy().clone();
+        Self {
+            dag,
+            alpha,
+            max_walk_depth: DEFAULT_MAX_WALK_DEPTH,
+            entropy,
+        }
+    }
+
+    /// Sets the randomness used for walks
+    pub fn with_entropy(mut self, entropy: Entropy) -> Self {
+        self.entropy = entropy;
+        self
     }
 
     /// Selects two tips for a new transaction
     ///
-    /// Uses weighted random walk as specified in whitepaper:
-    /// P(C) = exp(Score(C) × α) / Σ exp(Score(Ci) × α)
+    /// Runs two independent weighted random walks as specified in the
+    /// whitepaper.
     ///
     /// # Returns
     /// Two distinct transaction hashes to use as parents
+    ///
+    /// # Errors
+    /// - `TipSelectionError` if fewer than two tips exist, as a transaction
+    ///   may not approve the same parent twice
     pub fn select_tips(&self) -> Result<[Hash; 2]> {
-        let tips = self.dag.get_tips()?;
+        let tip1 = self.select_tip()?;
+
+        // Select second tip (must be different from first)
+        let mut tip2 = self.select_tip()?;
+        let mut attempts = 0;
+        while tip2 == tip1 && attempts < 10 {
+            tip2 = self.select_tip()?;
+            attempts += 1;
+        }
+
+        // If the walks keep converging, take any other tip
+        if tip2 == tip1 {
+            tip2 = self.dag.get_tips()?.into_iter().find(|t| *t != tip1).ok_or_else(|| {
+                NyxError::TipSelectionError("Only one tip available for selection".to_string())
+            })?;
+        }
+
+        Ok([tip1, tip2])
+    }
+
+    /// Selects a single tip by random walk, falling back to lazy tips
+    pub fn select_tip(&self) -> Result<Hash> {
+        if let Some(start) = self.walk_start()? {
+            if let Some(tip) = self.random_walk(start)? {
+                return Ok(tip);
+            }
+        }
+
+        self.select_lazy_tip()
+    }
 
+    /// Samples directly among the current tips
+    ///
+    /// Uses the DAG's O(log n) weight index when this selector's alpha
+    /// matches the one the index was built with, otherwise weights every
+    /// tip on the fly.
+    fn select_lazy_tip(&self) -> Result<Hash> {
+        if self.alpha == self.dag.tip_alpha() {
+            return self.dag.sample_tip()?.ok_or_else(|| NyxError::TipSelectionError(
+                "No tips available for selection".to_string()
+            ));
+        }
+
+        let tips = self.dag.get_tips()?;
         if tips.is_empty() {
             return Err(NyxError::TipSelectionError(
                 "No tips available for selection".to_string()
             ));
         }
 
-        if tips.len() == 1 {
-            // Special case: only one tip available, use it twice
-            return Ok([tips[0], tips[0]]);
+        self.select_single_tip(&tips)
+    }
+
+    /// Picks the walk start: latest snapshot, otherwise a random genesis
+    fn walk_start(&self) -> Result<Option<Hash>> {
+        if let Some(snapshot) = self.dag.latest_finalized()? {
+            return Ok(Some(snapshot));
         }
 
-        // Select first tip
-        let tip1 = self.select_single_tip(&tips)?;
+        let roots = self.dag.get_roots()?;
+        Ok(roots.choose(&mut self.entropy.clone()).copied())
+    }
 
-        // Select second tip (must be different from first)
-        let mut tip2 = self.select_single_tip(&tips)?;
-        let mut attempts = 0;
-        while tip2 == tip1 && attempts < 10 {
-            tip2 = self.select_single_tip(&tips)?;
-            attempts += 1;
+    /// Walks from `start` towards the tips
+    ///
+    /// # Returns
+    /// The tip reached, or `None` if the walk exceeded the maximum depth
+    pub fn random_walk(&self, start: Hash) -> Result<Option<Hash>> {
+        let mut current = start;
+
+        for _ in 0..self.max_walk_depth {
+            let children = self.dag.get_children(&current)?;
+            if children.is_empty() {
+                return Ok(Some(current));
+            }
+
+            current = self.select_child(&children)?;
         }
 
-        // If we couldn't find a different tip after 10 attempts, just pick another one
-        if tip2 == tip1 && tips.len() > 1 {
-            tip2 = tips.iter()
-                .find(|&t| *t != tip1)
-                .copied()
-                .ok_or_else(|| NyxError::TipSelectionError(
-                    "Could not select distinct tips".to_string()
-                ))?;
+        Ok(None)
+    }
+
+    /// Chooses the next step of the walk weighted by cumulative weight
+    fn select_child(&self, children: &[Hash]) -> Result<Hash> {
+        if children.len() == 1 {
+            return Ok(children[0]);
         }
 
-        Ok([tip1, tip2])
+        let cumulative: Vec<f64> = children.iter()
+            .map(|c| self.dag.cumulative_weight(c).map(|w| w as f64))
+            .collect::<Result<_>>()?;
+
+        // Subtract the max before exponentiating to avoid overflow
+        let max = cumulative.iter().copied().fold(f64::MIN, f64::max);
+        let weights: Vec<f64> = cumulative.iter()
+            .map(|w| ((w - max) * self.alpha).exp())
+            .collect();
+        let total_weight = weights.iter().sum();
+
+        self.select_from_weights(children, &weights, total_weight)
     }
 
     /// Selects a single tip using weighted random selection
@@ -117,7 +212,7 @@ impl TipSelector {
             .collect();
 
         // Select a tip based on weighted probability
-        let mut rng = rand::thread_rng();
+        let mut rng = self.entropy.clone();
         let random_value: f64 = rng.gen();
 
         let mut cumulative = 0.0;
@@ -138,6 +233,10 @@ impl TipSelector {
     /// - Prefer recent tips
     /// - Prefer tips with lower scores (help weak transactions)
     /// - Geographic preferences, etc.
+    ///
+    /// # Errors
+    /// - `TipSelectionError` if fewer than two tips exist, as a transaction
+    ///   may not approve the same parent twice
     pub fn select_tips_with_preference<F>(&self, prefer: F) -> Result<[Hash; 2]>
     where
         F: Fn(&Hash) -> f64,
@@ -151,7 +250,9 @@ impl TipSelector {
         }
 
         if tips.len() == 1 {
-            return Ok([tips[0], tips[0]]);
+            return Err(NyxError::TipSelectionError(
+                "Only one tip available for selection".to_string()
+            ));
         }
 
         // Calculate weights with preference function
@@ -177,6 +278,13 @@ impl TipSelector {
             attempts += 1;
         }
 
+        // If the draws keep colliding, take any other tip
+        if tip2 == tip1 {
+            tip2 = tips.into_iter().find(|t| *t != tip1).ok_or_else(|| {
+                NyxError::TipSelectionError("Only one tip available for selection".to_string())
+            })?;
+        }
+
         Ok([tip1, tip2])
     }
 
@@ -187,7 +295,7 @@ impl TipSelector {
         weights: &[f64],
         total_weight: f64,
     ) -> Result<Hash> {
-        let mut rng = rand::thread_rng();
+        let mut rng = self.entropy.clone();
         let random_value: f64 = rng.gen_range(0.0..total_weight);
 
         let mut cumulative = 0.0;
@@ -213,6 +321,16 @@ impl TipSelector {
     pub fn set_alpha(&mut self, alpha: f64) {
         self.alpha = alpha.clamp(0.0, 1.0);
     }
+
+    /// Returns the maximum random walk depth
+    pub fn max_walk_depth(&self) -> usize {
+        self.max_walk_depth
+    }
+
+    /// Sets the maximum random walk depth
+    pub fn set_max_walk_depth(&mut self, depth: usize) {
+        self.max_walk_depth = depth;
+    }
 }
 
 
@@ -220,6 +338,99 @@ impl TipSelector {
 mod tests {
     use super::*;
     use crate::storage::MemoryStorage;
+    use crate::testing::test_tx;
+    use crate::verification::VerifyMode;
+
+    /// Builds two genesis transactions and a chain of `len` transactions
+    fn build_chain(len: u8) -> (DagProcessor, Vec<Hash>) {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let g1 = storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 1)).unwrap();
+        let g2 = storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 2)).unwrap();
+
+        let mut hashes = vec![g1, g2];
+        for nonce in 3..3 + len {
+            let n = hashes.len();
+            let tx = test_tx(hashes[n - 1], hashes[n - 2], nonce);
+            hashes.push(dag.add_transaction_with_mode(tx, VerifyMode::HeadersOnly).unwrap());
+        }
+
+        (dag, hashes)
+    }
+
+    #[test]
+    fn test_walk_reaches_tip() {
+        let (dag, hashes) = build_chain(5);
+        let selector = TipSelector::new(dag.clone());
+        let tips = dag.get_tips().unwrap();
+
+        assert_eq!(selector.random_walk(hashes[0]).unwrap(), Some(*hashes.last().unwrap()));
+        assert!(tips.contains(&selector.select_tip().unwrap()));
+    }
+
+    #[test]
+    fn test_walk_starts_from_snapshot() {
+        let (dag, hashes) = build_chain(4);
+        dag.finalize_transaction(&hashes[3]).unwrap();
+
+        assert_eq!(dag.latest_finalized().unwrap(), Some(hashes[3]));
+
+        let selector = TipSelector::new(dag);
+        assert_eq!(selector.select_tip().unwrap(), *hashes.last().unwrap());
+    }
+
+    #[test]
+    fn test_walk_depth_fallback() {
+        let (dag, hashes) = build_chain(5);
+        let mut selector = TipSelector::new(dag);
+        selector.set_max_walk_depth(2);
+
+        assert_eq!(selector.random_walk(hashes[0]).unwrap(), None);
+        // Lazy tips fallback still returns the only tip
+        assert_eq!(selector.select_tip().unwrap(), *hashes.last().unwrap());
+    }
+
+    #[test]
+    fn test_lazy_tips_with_custom_alpha() {
+        let (dag, hashes) = build_chain(3);
+        let mut selector = TipSelector::with_alpha(dag.clone(), 0.9);
+        selector.set_max_walk_depth(0);
+
+        // Custom alpha bypasses the DAG's weight index but finds the same tip
+        assert_eq!(selector.select_tip().unwrap(), *hashes.last().unwrap());
+        assert_eq!(dag.sample_tip().unwrap(), Some(*hashes.last().unwrap()));
+    }
+
+    #[test]
+    fn test_empty_dag_has_no_tips() {
+        let dag = DagProcessor::new(MemoryStorage::new());
+        let selector = TipSelector::new(dag);
+
+        assert!(selector.select_tips().is_err());
+    }
+
+    #[test]
+    fn test_single_tip_cannot_be_approved_twice() {
+        let (dag, hashes) = build_chain(3);
+        let selector = TipSelector::new(dag);
+
+        assert_eq!(selector.select_tip().unwrap(), *hashes.last().unwrap());
+        assert!(matches!(selector.select_tips(), Err(NyxError::TipSelectionError(_))));
+        assert!(matches!(selector.select_tips_with_preference(|_| 1.0), Err(NyxError::TipSelectionError(_))));
+    }
+
+    #[test]
+    fn test_preference_never_picks_one_tip_twice() {
+        let (dag, hashes) = build_chain(3);
+        let other = dag.add_transaction_with_mode(test_tx(hashes[3], hashes[2], 9), VerifyMode::HeadersOnly).unwrap();
+        let selector = TipSelector::new(dag);
+
+        // Every draw lands on `other`, so the second tip is the remaining one
+        let [tip1, tip2] = selector.select_tips_with_preference(|tip| if *tip == other { 1.0 } else { 0.0 }).unwrap();
+        assert_eq!(tip1, other);
+        assert_eq!(tip2, *hashes.last().unwrap());
+    }
 
     #[test]
     fn test_tip_selector_creation() {
@@ -227,7 +438,7 @@ mod tests {
         let dag = DagProcessor::new(storage);
         let selector = TipSelector::new(dag);
 
-        assert_eq!(selector.alpha(), TIP_SELECTION_ALPHA);
+        assert_eq!(selector.alpha(), crate::TIP_SELECTION_ALPHA);
     }
 
     #[test]
@@ -1,9 +1,18 @@
 // src/transaction.rs
 
+use crate::encoding::{CanonicalEncode, Encoder};
+use crate::network::NetworkId;
 use crate::types::{Hash, Timestamp};
+use crate::versioning::ValidationContext;
 use nyx_crypto::ring;  // Import crypto types
 use serde::{Deserialize, Serialize};
 
+/// Ring signature carried by every transaction
+///
+/// Defined in `nyx-crypto` and re-exported here so the whole transaction
+/// schema is reachable from this module.
+pub use nyx_crypto::ring::RingSignature;
+
 /// Transaction input referencing a previous output
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct TxInput {
@@ -29,11 +38,122 @@ pub struct TxOutput {
     /// Encrypted amount (Pedersen commitment)
     pub amount_commitment: Vec<u8>,
 
-    /// Range proof (Bulletproofs+)
+    /// Range proof that the amount commitment holds a 64-bit amount
     pub range_proof: Vec<u8>,
 
     /// Ephemeral public key for ECDH
+    ///
+    /// Every output has its own, so each recipient of a multi-recipient
+    /// transaction derives a distinct shared secret.
     pub ephemeral_pubkey: Vec<u8>,
+
+    /// Earliest point the output can be spent, if time-locked
+    ///
+    /// Only encoded from [`UNLOCK_TIME_VERSION`](crate::UNLOCK_TIME_VERSION)
+    /// on; [`Transaction::new`] picks that version when an output is locked.
+    #[serde(default)]
+    pub unlock_time: Option<UnlockTime>,
+
+    /// Hash/time-lock the output is spent under, if any
+    ///
+    /// Only encoded from [`HTLC_VERSION`](crate::HTLC_VERSION) on;
+    /// [`Transaction::new`] picks that version when an output carries one.
+    #[serde(default)]
+    pub htlc: Option<HtlcLock>,
+}
+
+impl TxOutput {
+    /// Locks the output until `unlock_time`
+    pub fn with_unlock_time(mut self, unlock_time: UnlockTime) -> Self {
+        self.unlock_time = Some(unlock_time);
+        self
+    }
+
+    /// Puts the output under a hash/time-lock
+    pub fn with_htlc(mut self, htlc: HtlcLock) -> Self {
+        self.htlc = Some(htlc);
+        self
+    }
+}
+
+/// Hash/time-lock of an atomic swap output
+///
+/// Until `timeout` the output can only be claimed, by revealing the
+/// preimage of `hashlock` with a signature of `claim_key`. From `timeout`
+/// on it can also be refunded with a signature of `refund_key`. Hashlocks
+/// are SHA-256, so the same secret unlocks the counterparty's HTLC on a
+/// Bitcoin-like chain.
+///
+/// The preimage and signatures are carried in the spending transaction's
+/// `extra` (see [`crate::extra::HtlcWitness`]). Every spend of an HTLC
+/// output uses the output's [`key_image`](Self::key_image), so a claim
+/// and a refund of the same output are a double spend.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HtlcLock {
+    /// SHA-256 hash of the swap secret
+    pub hashlock: [u8; 32],
+
+    /// Point from which the refund path opens
+    pub timeout: UnlockTime,
+
+    /// Ed25519 public key of the party claiming with the secret
+    pub claim_key: [u8; 32],
+
+    /// Ed25519 public key of the party refunded after the timeout
+    pub refund_key: [u8; 32],
+}
+
+impl HtlcLock {
+    /// Checks whether `preimage` opens the hashlock
+    pub fn opens(&self, preimage: &[u8]) -> bool {
+        nyx_crypto::ct::ct_eq(&nyx_crypto::hash::sha256(preimage), &self.hashlock)
+    }
+
+    /// Key that signs the ring signature of a spend of the HTLC output
+    /// `tx_hash:index`
+    ///
+    /// Anyone can derive it: the HTLC witness authorizes the spend, and the
+    /// ring signature only proves the output's key image.
+    pub fn spend_key(tx_hash: &Hash, index: u32) -> [u8; 32] {
+        let mut data = b"nyx-htlc".to_vec();
+        data.extend_from_slice(tx_hash);
+        data.extend_from_slice(&index.to_le_bytes());
+        nyx_crypto::hash::blake3_hash(&data)
+    }
+
+    /// Key image every spend of the HTLC output `tx_hash:index` must use
+    pub fn key_image(tx_hash: &Hash, index: u32) -> [u8; 32] {
+        ring::generate_key_image(&Self::spend_key(tx_hash, index))
+    }
+}
+
+/// Earliest point a time-locked output can be spent
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum UnlockTime {
+    /// Spendable by transactions timestamped at or after this time
+    Timestamp(Timestamp),
+
+    /// Spendable once the DAG holds this many transactions
+    Height(u64),
+}
+
+impl UnlockTime {
+    /// Checks whether the output can be spent in the given context
+    pub fn is_unlocked(&self, ctx: &ValidationContext) -> bool {
+        match *self {
+            UnlockTime::Timestamp(timestamp) => ctx.timestamp >= timestamp,
+            UnlockTime::Height(height) => ctx.height >= height,
+        }
+    }
+}
+
+impl std::fmt::Display for UnlockTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnlockTime::Timestamp(timestamp) => write!(f, "timestamp {}", timestamp),
+            UnlockTime::Height(height) => write!(f, "height {}", height),
+        }
+    }
 }
 
 /// Complete Nyx transaction structure
@@ -42,16 +162,27 @@ pub struct Transaction {
     /// Protocol version
     pub version: u8,
 
+    /// Network the transaction is valid on
+    pub network: NetworkId,
+
     /// Inputs being spent
     pub inputs: Vec<TxInput>,
 
     /// New outputs being created
     pub outputs: Vec<TxOutput>,
 
-    /// Ring signature (from nyx-crypto)
-    pub ring_signature: ring::RingSignature,
-
-    /// Transaction public key for ECDH
+    /// Ring signatures (from nyx-crypto), one per input in input order
+    ///
+    /// Each input is a separate spend with its own ring and key image, so
+    /// each carries its own signature over the
+    /// [signing message](Self::signing_message).
+    pub ring_signatures: Vec<ring::RingSignature>,
+
+    /// Transaction-wide public key (deprecated, left empty)
+    ///
+    /// One key cannot serve several recipients, so ECDH uses each output's
+    /// [`ephemeral_pubkey`](TxOutput::ephemeral_pubkey) instead. The field
+    /// is kept so existing transactions keep their encoding and ID.
     pub tx_key: Vec<u8>,
 
     /// Two parent transaction hashes (DAG references)
@@ -65,29 +196,94 @@ pub struct Transaction {
 }
 
 impl Transaction {
-    /// Computes transaction ID using nyx-crypto's hash
+    /// Computes the transaction ID (txid)
+    ///
+    /// The ID is the BLAKE3 hash of the canonical encoding (see
+    /// [`crate::encoding`]) without the ring signatures, so a relay that
+    /// re-encodes a signature cannot change it. DAG references, storage
+    /// and deduplication all use this ID.
     pub fn id(&self) -> Hash {
-        let serialized = bincode::serialize(self)
-            .expect("Transaction serialization should never fail");
+        nyx_crypto::hash::blake3_hash(&crate::encoding::encode_transaction_without_witness(self))
+    }
+
+    /// Computes the witness transaction ID (wtxid)
+    ///
+    /// The BLAKE3 hash of the full canonical encoding, ring signatures
+    /// included. Two encodings of the same transaction share an ID but
+    /// not a wtxid.
+    pub fn wtxid(&self) -> Hash {
+        nyx_crypto::hash::blake3_hash(&self.to_canonical_bytes())
+    }
+
+    /// Creates a new transaction, checking its structure
+    ///
+    /// Like [`new`](Self::new), but refuses transactions no node would
+    /// accept whatever their signatures: without inputs or outputs, with
+    /// a ring signature count differing from the input count, approving
+    /// the same parent twice, referencing themselves, or spending the same
+    /// key image twice.
+    ///
+    /// Only the null parents of genesis transactions may be identical.
+    ///
+    /// # Errors
+    /// - `InvalidTransaction` naming the violated invariant
+    pub fn try_new(
+        inputs: Vec<TxInput>,
+        outputs: Vec<TxOutput>,
+        ring_signatures: Vec<ring::RingSignature>,
+        tx_key: Vec<u8>,
+        parent1: Hash,
+        parent2: Hash,
+    ) -> crate::Result<Self> {
+        let invalid = |reason: &str| Err(crate::NyxError::InvalidTransaction(reason.to_string()));
+        if inputs.is_empty() {
+            return invalid("no inputs");
+        }
+        if outputs.is_empty() {
+            return invalid("no outputs");
+        }
+        if ring_signatures.len() != inputs.len() {
+            return invalid("ring signature count differs from input count");
+        }
+        if parent1 == parent2 && parent1 != [0u8; 32] {
+            return invalid("approves the same parent twice");
+        }
+        let mut key_images = std::collections::HashSet::new();
+        if !inputs.iter().all(|input| key_images.insert(input.key_image)) {
+            return invalid("key image spent twice");
+        }
 
-        // Use nyx-crypto's BLAKE3 hash
-        nyx_crypto::hash::blake3_hash(&serialized)
+        let tx = Self::new(inputs, outputs, ring_signatures, tx_key, parent1, parent2);
+        if tx.references.contains(&tx.id()) {
+            return invalid("references itself");
+        }
+        Ok(tx)
     }
 
-    /// Creates a new transaction
+    /// Creates a new transaction without checking its structure
+    ///
+    /// Meant for minted transactions, which have no inputs, and for tests
+    /// that need malformed transactions; use [`try_new`](Self::try_new)
+    /// otherwise.
+    ///
+    /// The version is [`HTLC_VERSION`](crate::HTLC_VERSION) if any output
+    /// is hash/time-locked, [`UNLOCK_TIME_VERSION`](crate::UNLOCK_TIME_VERSION)
+    /// if any is time-locked, and 1 otherwise. `tx_key` is deprecated; pass
+    /// an empty key.
     pub fn new(
         inputs: Vec<TxInput>,
         outputs: Vec<TxOutput>,
-        ring_signature: ring::RingSignature,
+        ring_signatures: Vec<ring::RingSignature>,
         tx_key: Vec<u8>,
         parent1: Hash,
         parent2: Hash,
     ) -> Self {
         Self {
-            version: 1,
+            version: Self::version_for(&outputs),
+            network: NetworkId::Mainnet,
             inputs,
             outputs,
-            ring_signature,
+            ring_signatures,
             tx_key,
             references: [parent1, parent2],
             timestamp: current_timestamp(),
@@ -95,52 +291,164 @@ impl Transaction {
         }
     }
 
-    /// Signs transaction with ring signature
+    /// Gets the lowest transaction version able to carry `outputs`
+    pub fn version_for(outputs: &[TxOutput]) -> u8 {
+        if outputs.iter().any(|output| output.htlc.is_some()) {
+            crate::HTLC_VERSION
+        } else if outputs.iter().any(|output| output.unlock_time.is_some()) {
+            crate::UNLOCK_TIME_VERSION
+        } else {
+            crate::PROTOCOL_VERSION
+        }
+    }
+
+    /// Sets the network the transaction is valid on
+    ///
+    /// Must be called before signing, as the network is part of the
+    /// signing message.
+    pub fn with_network(mut self, network: NetworkId) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Signs one input with a ring signature
+    ///
+    /// `input` indexes both the input and its entry in
+    /// [`ring_signatures`](Self::ring_signatures), which must already hold
+    /// an entry for it.
     pub fn sign(
         &mut self,
+        input: usize,
         message: &[u8],
         private_key: &[u8],
         public_key: &[u8],
         ring_members: &[Vec<u8>],
     ) -> Result<(), nyx_crypto::CryptoError> {
+        self.sign_with_rng(
+            input,
+            message,
+            private_key,
+            public_key,
+            ring_members,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    /// Signs one input with a ring signature using the given RNG
+    ///
+    /// A seeded RNG produces the same signature for the same inputs.
+    pub fn sign_with_rng<R: rand::Rng + ?Sized>(
+        &mut self,
+        input: usize,
+        message: &[u8],
+        private_key: &[u8],
+        public_key: &[u8],
+        ring_members: &[Vec<u8>],
+        rng: &mut R,
+    ) -> Result<(), nyx_crypto::CryptoError> {
+        let slot = self.ring_signatures.get_mut(input).ok_or_else(|| {
+            nyx_crypto::CryptoError::RingSignatureError(format!("No ring signature slot for input {}", input))
+        })?;
+
         // Generate ring signature using nyx-crypto
-        let ring_sig = nyx_crypto::ring::generate_ring_signature(
+        *slot = nyx_crypto::ring::generate_ring_signature_with_rng(
             message,
             private_key,
             public_key,
             ring_members,
+            rng,
         )?;
-
-        self.ring_signature = ring_sig;
         Ok(())
     }
 
-    /// Verifies the ring signature
+    /// Verifies the ring signatures
+    ///
+    /// Valid only if there is exactly one signature per input, each proves
+    /// the key image its input spends, and every one of them verifies.
     pub fn verify_signature(&self) -> Result<bool, nyx_crypto::CryptoError> {
+        if self.ring_signatures.len() != self.inputs.len() {
+            return Ok(false);
+        }
+
         let message = self.signing_message();
-        nyx_crypto::ring::verify_ring_signature(&message, &self.ring_signature)
+        for (input, ring_signature) in self.inputs.iter().zip(&self.ring_signatures) {
+            // Otherwise one key could sign for any number of key images
+            if ring_signature.key_image != input.key_image {
+                return Ok(false);
+            }
+            if !nyx_crypto::ring::verify_ring_signature(&message, ring_signature)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
     }
 
     /// Creates message to be signed
+    ///
+    /// Covers every field except the ring signatures themselves, so each
+    /// signature binds the whole transaction: changing any input, output,
+    /// reference, the timestamp or `extra` invalidates it. Fields are
+    /// written with the canonical encoder so that variable-length values
+    /// are length-prefixed and cannot be shifted between fields. The
+    /// network id is included so a signature is only valid on one network.
     pub fn signing_message(&self) -> Vec<u8> {
         // Serialize everything except the signature
-        let mut data = Vec::new();
-        data.extend_from_slice(&[self.version]);
+        let mut enc = Encoder::new();
+        enc.put_u8(self.version);
+        enc.put_u8(self.network.as_u8());
 
+        enc.put_len(self.inputs.len());
         for input in &self.inputs {
-            data.extend_from_slice(&input.prev_tx);
-            data.extend_from_slice(&input.index.to_le_bytes());
+            enc.put_hash(&input.prev_tx);
+            enc.put_u32(input.index);
+            enc.put_hash(&input.key_image);
+            enc.put_len(input.ring_indices.len());
+            for ring_index in &input.ring_indices {
+                enc.put_u32(*ring_index);
+            }
         }
 
+        enc.put_len(self.outputs.len());
         for output in &self.outputs {
-            data.extend_from_slice(&output.stealth_address);
-            data.extend_from_slice(&output.amount_commitment);
+            enc.put_bytes(&output.stealth_address);
+            enc.put_bytes(&output.amount_commitment);
+            enc.put_bytes(&output.range_proof);
+            enc.put_bytes(&output.ephemeral_pubkey);
+        }
+
+        // Unlock times are signed so a relay cannot strip a lock
+        if self.version >= crate::UNLOCK_TIME_VERSION {
+            for output in &self.outputs {
+                crate::encoding::encode_unlock_time(&mut enc, output.unlock_time);
+            }
+        }
+        if self.version >= crate::HTLC_VERSION {
+            for output in &self.outputs {
+                crate::encoding::encode_htlc(&mut enc, output.htlc.as_ref());
+            }
         }
 
-        data.extend_from_slice(&self.references[0]);
-        data.extend_from_slice(&self.references[1]);
+        enc.put_bytes(&self.tx_key);
+        enc.put_hash(&self.references[0]);
+        enc.put_hash(&self.references[1]);
+        enc.put_u64(self.timestamp);
+        enc.put_bytes(&self.extra);
+
+        nyx_crypto::hash::blake3_hash(&enc.finish()).to_vec()
+    }
 
-        nyx_crypto::hash::blake3_hash(&data).to_vec()
+    /// Creates the message HTLC witnesses sign
+    ///
+    /// The [signing message](Self::signing_message) of the transaction
+    /// with its HTLC witnesses left out of `extra`, so the witnesses can be
+    /// added after signing and the ring signatures still cover them.
+    pub fn htlc_witness_message(&self) -> Vec<u8> {
+        let mut unwitnessed = self.clone();
+        if let Ok(mut extra) = crate::extra::Extra::parse(&self.extra) {
+            extra.remove(crate::extra::TAG_HTLC_WITNESSES);
+            unwitnessed.extra = extra.to_bytes();
+        }
+        unwitnessed.signing_message()
     }
 
     /// Validates transaction structure
@@ -154,13 +462,14 @@ impl Transaction {
         }
 
         let now = current_timestamp();
-        let two_hours = 2 * 60 * 60;
-        if self.timestamp > now + two_hours {
+        if self.timestamp > now + crate::verification::MAX_FUTURE_DRIFT_SECS {
             return false;
         }
 
-        // Validate ring signature structure
-        if self.ring_signature.ring_size() < 2 {
+        // Validate ring signature structure, one signature per input
+        if self.ring_signatures.len() != self.inputs.len()
+            || self.ring_signatures.iter().any(|sig| sig.ring_size() < 2)
+        {
             return false;
         }
 
@@ -175,9 +484,95 @@ impl Transaction {
     }
 }
 
-fn current_timestamp() -> Timestamp {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .expect("System time should be after Unix epoch")
-        .as_secs()
+pub(crate) fn current_timestamp() -> Timestamp {
+    crate::clock::Clock::now(&crate::clock::SystemClock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NyxError;
+
+    fn input(key_image: u8) -> TxInput {
+        TxInput { prev_tx: [9u8; 32], index: 0, key_image: [key_image; 32], ring_indices: vec![] }
+    }
+
+    fn output() -> TxOutput {
+        TxOutput {
+            stealth_address: vec![1u8; 32],
+            amount_commitment: vec![],
+            range_proof: vec![],
+            ephemeral_pubkey: vec![],
+            unlock_time: None,
+            htlc: None,
+        }
+    }
+
+    fn ring() -> ring::RingSignature {
+        ring::RingSignature { ring_members: vec![vec![1], vec![2]], signature: vec![], key_image: [0u8; 32] }
+    }
+
+    fn rejection(result: crate::Result<Transaction>) -> String {
+        match result {
+            Err(NyxError::InvalidTransaction(reason)) => reason,
+            other => panic!("expected InvalidTransaction, got {:?}", other.map(|tx| tx.id())),
+        }
+    }
+
+    #[test]
+    fn test_try_new_accepts_well_formed() {
+        let tx = Transaction::try_new(vec![input(1), input(2)], vec![output()], vec![ring(), ring()], vec![], [0u8; 32], [1u8; 32])
+            .unwrap();
+        assert_eq!(tx, Transaction::new(vec![input(1), input(2)], vec![output()], vec![ring(), ring()], vec![], [0u8; 32], [1u8; 32]));
+    }
+
+    #[test]
+    fn test_try_new_rejects_malformed() {
+        let parents = ([0u8; 32], [1u8; 32]);
+        let build = |inputs: Vec<TxInput>, outputs, parent1, parent2| {
+            let rings = vec![ring(); inputs.len()];
+            Transaction::try_new(inputs, outputs, rings, vec![], parent1, parent2)
+        };
+
+        assert_eq!(rejection(build(vec![], vec![output()], parents.0, parents.1)), "no inputs");
+        assert_eq!(rejection(build(vec![input(1)], vec![], parents.0, parents.1)), "no outputs");
+        assert_eq!(
+            rejection(build(vec![input(1), input(1)], vec![output()], parents.0, parents.1)),
+            "key image spent twice"
+        );
+        assert_eq!(
+            rejection(Transaction::try_new(vec![input(1), input(2)], vec![output()], vec![ring()], vec![], parents.0, parents.1)),
+            "ring signature count differs from input count"
+        );
+
+        // `new` still builds them
+        assert!(Transaction::new(vec![], vec![output()], vec![], vec![], parents.0, parents.1).inputs.is_empty());
+    }
+
+    #[test]
+    fn test_signature_must_prove_the_input_key_image() {
+        let signer = nyx_crypto::keys::generate_keypair_from_seed(&[1u8; 32]);
+        let other = nyx_crypto::keys::generate_keypair_from_seed(&[2u8; 32]);
+        let members = vec![signer.public_key.clone(), other.public_key.clone()];
+        let mut spend = input(0);
+        spend.key_image = ring::generate_key_image(signer.private_key());
+        let unsigned = Transaction::try_new(vec![spend], vec![output()], vec![ring()], vec![], [0u8; 32], [1u8; 32]).unwrap();
+        let message = unsigned.signing_message();
+
+        let mut tx = unsigned.clone();
+        tx.sign(0, &message, signer.private_key(), &signer.public_key, &members).unwrap();
+        assert!(tx.verify_signature().unwrap());
+
+        // A valid signature by another ring member proves another key image
+        let mut tx = unsigned;
+        tx.sign(0, &message, other.private_key(), &other.public_key, &members).unwrap();
+        assert!(!tx.verify_signature().unwrap());
+    }
+
+    #[test]
+    fn test_try_new_rejects_identical_parents() {
+        let tip = [7u8; 32];
+        let result = Transaction::try_new(vec![input(1)], vec![output()], vec![ring()], vec![], tip, tip);
+        assert_eq!(rejection(result), "approves the same parent twice");
+    }
 }
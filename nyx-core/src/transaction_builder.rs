@@ -1,17 +1,31 @@
 // src/transaction_builder.rs
 
 //! Transaction builder with integrated cryptography.
+//!
+//! Output commitments are blinded with masks derived from each output's
+//! stealth shared secret, and the amounts are encrypted under the same
+//! secret in the amount hints field of `extra`, so recipients can decrypt
+//! and check them while scanning (see [`nyx_crypto::amount`]).
 
+use crate::clock::{self, SharedClock};
+use crate::extra::{Extra, ExtraField};
+use crate::network::NetworkId;
 use crate::transaction::{Transaction, TxInput, TxOutput};
 use crate::types::Hash;
-use nyx_crypto::{ring, stealth, encryption, keys};
+use nyx_crypto::amount::{self, ENCRYPTED_AMOUNT_SIZE};
+use nyx_crypto::{pedersen, range, ring, stealth, keys, Entropy};
 
 /// Builder for creating privacy-preserving transactions
 pub struct TransactionBuilder {
     inputs: Vec<TxInput>,
+    input_keys: Vec<Vec<u8>>,
     outputs: Vec<TxOutput>,
+    amount_hints: Vec<[u8; ENCRYPTED_AMOUNT_SIZE]>,
     ring_members: Vec<Vec<u8>>,
     signer_keypair: Option<keys::KeyPair>,
+    network: NetworkId,
+    entropy: Entropy,
+    clock: SharedClock,
 }
 
 impl TransactionBuilder {
@@ -19,19 +33,44 @@ impl TransactionBuilder {
     pub fn new() -> Self {
         Self {
             inputs: Vec::new(),
+            input_keys: Vec::new(),
             outputs: Vec::new(),
+            amount_hints: Vec::new(),
             ring_members: Vec::new(),
             signer_keypair: None,
+            network: NetworkId::Mainnet,
+            entropy: Entropy::os(),
+            clock: clock::system_clock(),
         }
     }
 
+    /// Sets the network the transaction is valid on
+    pub fn with_network(mut self, network: NetworkId) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Sets the randomness used for ephemeral keys and signing
+    pub fn with_entropy(mut self, entropy: Entropy) -> Self {
+        self.entropy = entropy;
+        self
+    }
+
+    /// Sets the time source for the transaction timestamp
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Sets the signer's keypair
     pub fn with_signer(mut self, keypair: keys::KeyPair) -> Self {
         self.signer_keypair = Some(keypair);
         self
     }
 
-    /// Adds an input to spend
+    /// Adds an input to spend, signed with `private_key`
+    ///
+    /// The signer's public key stands for the input in its ring.
     pub fn add_input(
         mut self,
         prev_tx: Hash,
@@ -49,6 +88,7 @@ impl TransactionBuilder {
         };
 
         self.inputs.push(input);
+        self.input_keys.push(private_key.to_vec());
         Ok(self)
     }
 
@@ -60,7 +100,7 @@ impl TransactionBuilder {
         amount: u64,
     ) -> Result<Self, nyx_crypto::CryptoError> {
         // Generate random for stealth address
-        let random = stealth::generate_random_ephemeral();
+        let random = stealth::generate_random_ephemeral_with_rng(&mut self.entropy);
 
         // Create stealth address
         let (stealth_address, ephemeral_pubkey) = stealth::generate_stealth_address(
@@ -69,19 +109,21 @@ impl TransactionBuilder {
             &random,
         )?;
 
-        // Encrypt amount (simplified - in production use Pedersen commitments)
-        let encryption_key = encryption::generate_key();
-        let amount_bytes = amount.to_le_bytes();
-        let amount_commitment = encryption::encrypt(&amount_bytes, &encryption_key)?;
-
-        // TODO: Generate actual range proof (Bulletproofs+)
-        let range_proof = vec![0u8; 100]; // Placeholder
+        // Blind the commitment and encrypt the amount for the recipient
+        let shared_secret = stealth::derive_sender_shared_secret(view_public, &random)?;
+        let index = self.outputs.len() as u32;
+        let mask = amount::amount_mask(&shared_secret, index);
+        let amount_commitment = pedersen::commit(amount, &mask).to_bytes().to_vec();
+        self.amount_hints.push(amount::encrypt_amount(&shared_secret, index, amount));
+        let range_proof = range::prove_with_rng(amount, &mask, &mut self.entropy);
 
         let output = TxOutput {
             stealth_address,
             amount_commitment,
             range_proof,
             ephemeral_pubkey,
+            unlock_time: None,
+            htlc: None,
         };
 
         self.outputs.push(output);
@@ -109,33 +151,41 @@ impl TransactionBuilder {
             ));
         }
 
-        // Create unsigned transaction
-        let mut tx = Transaction {
-            version: 1,
-            inputs: self.inputs,
-            outputs: self.outputs,
-            ring_signature: ring::RingSignature {
-                ring_members: self.ring_members.clone(),
-                signature: Vec::new(),
-                key_image: [0u8; 32],
-            },
-            tx_key: keypair.public_key.clone(),
-            references: [parent1, parent2],
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
-            extra: Vec::new(),
+        // Create unsigned transaction, with an empty signature per input
+        let unsigned = ring::RingSignature {
+            ring_members: self.ring_members.clone(),
+            signature: Vec::new(),
+            key_image: [0u8; 32],
         };
+        let input_count = self.inputs.len();
+        let mut tx = Transaction::try_new(
+            self.inputs,
+            self.outputs,
+            vec![unsigned; input_count],
+            Vec::new(),
+            parent1,
+            parent2,
+        )
+        .map_err(|e| nyx_crypto::CryptoError::InvalidInput(e.to_string()))?
+        .with_network(self.network);
+        tx.timestamp = self.clock.now();
+        tx.extra = Extra::new()
+            .with_field(ExtraField::AmountHints(self.amount_hints.concat()))
+            .to_bytes();
 
-        // Sign transaction
+        // Sign every input over the final content
         let message = tx.signing_message();
-        tx.sign(
-            &message,
-            keypair.private_key(),
-            &keypair.public_key,
-            &self.ring_members,
-        )?;
+        let mut entropy = self.entropy;
+        for (input, private_key) in self.input_keys.iter().enumerate() {
+            tx.sign_with_rng(
+                input,
+                &message,
+                private_key,
+                &keypair.public_key,
+                &self.ring_members,
+                &mut entropy,
+            )?;
+        }
 
         Ok(tx)
     }
@@ -176,5 +226,170 @@ mod tests {
         let tx = result.unwrap();
         assert_eq!(tx.inputs.len(), 1);
         assert_eq!(tx.outputs.len(), 1);
+        assert!(tx.tx_key.is_empty());
+    }
+
+    #[test]
+    fn test_recipient_decrypts_amount() {
+        let signer_keypair = keys::generate_keypair();
+        let (view_private_key, view_public_key) = keys::generate_keypair_ed25519();
+        let (_, spend_public_key) = keys::generate_keypair_ed25519();
+        let ring = vec![signer_keypair.public_key.clone(), keys::generate_keypair().public_key];
+
+        let tx = TransactionBuilder::new()
+            .with_signer(signer_keypair)
+            .add_input([1u8; 32], 0, &view_private_key)
+            .unwrap()
+            .add_output(&view_public_key, &spend_public_key, 1000)
+            .unwrap()
+            .add_output(&view_public_key, &spend_public_key, 250)
+            .unwrap()
+            .with_ring_members(ring)
+            .build([0u8; 32], [1u8; 32])
+            .unwrap();
+
+        // The recipient derives each output's secret with its view key and
+        // rebuilds the commitment from the decrypted amount
+        let extra = Extra::parse(&tx.extra).unwrap();
+        let hints = extra.amount_hints().unwrap();
+        for (index, expected) in [(0usize, 1000u64), (1, 250)] {
+            let output = &tx.outputs[index];
+            let secret = stealth::derive_shared_secret(&view_private_key, &output.ephemeral_pubkey).unwrap();
+            let hint: [u8; ENCRYPTED_AMOUNT_SIZE] = hints[index * ENCRYPTED_AMOUNT_SIZE..][..ENCRYPTED_AMOUNT_SIZE]
+                .try_into()
+                .unwrap();
+            let opened = amount::open_amount(&secret, index as u32, &hint, &output.amount_commitment);
+            assert_eq!(opened, Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_signature_bound_to_network() {
+        let signer_keypair = keys::generate_keypair();
+        let (view_private_key, view_public_key) = keys::generate_keypair_ed25519();
+        let (_, spend_public_key) = keys::generate_keypair_ed25519();
+        let ring = vec![signer_keypair.public_key.clone(), keys::generate_keypair().public_key];
+
+        let tx = TransactionBuilder::new()
+            .with_network(NetworkId::Testnet)
+            .with_signer(signer_keypair)
+            .add_input([1u8; 32], 0, &view_private_key)
+            .unwrap()
+            .add_output(&view_public_key, &spend_public_key, 1000)
+            .unwrap()
+            .with_ring_members(ring)
+            .build([0u8; 32], [1u8; 32])
+            .unwrap();
+
+        assert_eq!(tx.network, NetworkId::Testnet);
+        assert!(tx.verify_signature().unwrap());
+
+        // Replaying the testnet transaction on mainnet breaks the signature
+        let replayed = tx.with_network(NetworkId::Mainnet);
+        assert!(!replayed.verify_signature().unwrap());
+    }
+
+    #[test]
+    fn test_signature_bound_to_content() {
+        use crate::verification;
+
+        let signer_keypair = keys::generate_keypair();
+        let (view_private_key, view_public_key) = keys::generate_keypair_ed25519();
+        let (_, spend_public_key) = keys::generate_keypair_ed25519();
+        let ring = vec![signer_keypair.public_key.clone(), keys::generate_keypair().public_key];
+
+        let tx = TransactionBuilder::new()
+            .with_signer(signer_keypair)
+            .add_input([1u8; 32], 0, &view_private_key)
+            .unwrap()
+            .add_output(&view_public_key, &spend_public_key, 1000)
+            .unwrap()
+            .with_ring_members(ring)
+            .build([0u8; 32], [1u8; 32])
+            .unwrap();
+        assert!(tx.verify_signature().unwrap());
+        assert!(verification::verify_ring_signature(&tx).is_ok());
+
+        // Any change to the signed content breaks the signature
+        let tampered: [fn(&mut Transaction); 6] = [
+            |tx| tx.inputs[0].key_image[0] ^= 1,
+            |tx| tx.outputs[0].ephemeral_pubkey[0] ^= 1,
+            |tx| tx.outputs[0].range_proof.push(0),
+            |tx| tx.references[1] = [2u8; 32],
+            |tx| tx.timestamp += 1,
+            |tx| tx.extra.push(0),
+        ];
+        for tamper in tampered {
+            let mut changed = tx.clone();
+            tamper(&mut changed);
+            assert!(verification::verify_ring_signature(&changed).is_err());
+        }
+    }
+
+    #[test]
+    fn test_every_input_is_signed() {
+        let signer_keypair = keys::generate_keypair();
+        let (view_private_key, view_public_key) = keys::generate_keypair_ed25519();
+        let (other_view_private_key, _) = keys::generate_keypair_ed25519();
+        let (_, spend_public_key) = keys::generate_keypair_ed25519();
+        let ring = vec![signer_keypair.public_key.clone(), keys::generate_keypair().public_key];
+
+        let tx = TransactionBuilder::new()
+            .with_signer(signer_keypair)
+            .add_input([1u8; 32], 0, &view_private_key)
+            .unwrap()
+            .add_input([2u8; 32], 1, &other_view_private_key)
+            .unwrap()
+            .add_output(&view_public_key, &spend_public_key, 1000)
+            .unwrap()
+            .with_ring_members(ring)
+            .build([0u8; 32], [1u8; 32])
+            .unwrap();
+        assert_eq!(tx.ring_signatures.len(), 2);
+        assert!(tx.verify_signature().unwrap());
+        assert!(tx.validate_structure());
+
+        // A missing or broken signature for either input fails
+        let mut unsigned = tx.clone();
+        unsigned.ring_signatures.pop();
+        assert!(!unsigned.verify_signature().unwrap());
+        assert!(!unsigned.validate_structure());
+
+        let mut broken = tx.clone();
+        broken.ring_signatures[1].signature[0] ^= 1;
+        assert!(!broken.verify_signature().unwrap());
+    }
+
+    #[test]
+    fn test_seeded_build_is_reproducible() {
+        let build = || {
+            let mut entropy = Entropy::seeded(11);
+            let signer_keypair = keys::generate_keypair_with_rng(&mut entropy);
+            let (view_private_key, view_public_key) = keys::generate_keypair_ed25519_with_rng(&mut entropy);
+            let (_, spend_public_key) = keys::generate_keypair_ed25519_with_rng(&mut entropy);
+            let ring = vec![
+                signer_keypair.public_key.clone(),
+                keys::generate_keypair_with_rng(&mut entropy).public_key,
+            ];
+
+            TransactionBuilder::new()
+                .with_entropy(entropy)
+                .with_clock(std::sync::Arc::new(clock::ManualClock::new(1_700_000_000)))
+                .with_signer(signer_keypair)
+                .add_input([1u8; 32], 0, &view_private_key)
+                .unwrap()
+                .add_output(&view_public_key, &spend_public_key, 1000)
+                .unwrap()
+                .with_ring_members(ring)
+                .build([0u8; 32], [1u8; 32])
+                .unwrap()
+        };
+
+        let a = build();
+        let b = build();
+        assert_eq!(a.timestamp, 1_700_000_000);
+        assert_eq!(a.id(), b.id());
+        assert_eq!(a.ring_signatures, b.ring_signatures);
+        assert!(a.verify_signature().unwrap());
     }
 }
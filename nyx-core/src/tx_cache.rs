@@ -0,0 +1,254 @@
+// src/tx_cache.rs
+
+//! Shared transaction cache.
+//!
+//! Reading a transaction out of storage used to clone its whole body, ring
+//! signature and range proofs included, once per reader. [`TxCache`] keeps
+//! recently used transactions behind an [`Arc`] keyed by ID, so the DAG,
+//! the mempool and the RPC server hand out the same copy and a transaction
+//! is decoded once however often it is read. Clones of a cache share it.
+//!
+//! Storage is the only writer: it caches what it stores and reads, so a
+//! cached transaction is always the stored copy. Other components only
+//! [`share`](TxCache::share) it. Eviction is least recently used; reads and
+//! inserts both count as use.
+
+use crate::transaction::Transaction;
+use crate::types::Hash;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Transactions cached by default
+pub const DEFAULT_TX_CACHE_CAPACITY: usize = 10_000;
+
+/// Hit and eviction counters of a [`TxCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxCacheStats {
+    /// Lookups answered from the cache
+    pub hits: u64,
+
+    /// Lookups that missed
+    pub misses: u64,
+
+    /// Transactions dropped to make room
+    pub evictions: u64,
+}
+
+/// Least recently used cache of shared transactions
+#[derive(Clone)]
+pub struct TxCache {
+    /// Maximum number of cached transactions; 0 caches nothing
+    capacity: usize,
+
+    /// Cached transactions and their recency order
+    entries: Arc<Mutex<Lru>>,
+
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
+    evictions: Arc<AtomicU64>,
+}
+
+#[derive(Default)]
+struct Lru {
+    /// Transactions with the tick of their last use
+    by_id: HashMap<Hash, (Arc<Transaction>, u64)>,
+
+    /// Transaction IDs by tick of last use, least recent first
+    by_use: BTreeMap<u64, Hash>,
+
+    /// Source of use ticks
+    tick: u64,
+}
+
+impl Lru {
+    /// Marks `tx_id` as used now
+    fn touch(&mut self, tx_id: &Hash) -> Option<Arc<Transaction>> {
+        self.tick += 1;
+        let tick = self.tick;
+        let (tx, used) = self.by_id.get_mut(tx_id)?;
+        self.by_use.remove(used);
+        self.by_use.insert(tick, *tx_id);
+        *used = tick;
+        Some(tx.clone())
+    }
+}
+
+impl TxCache {
+    /// Creates a cache holding up to `capacity` transactions
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Arc::new(Mutex::new(Lru::default())),
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
+            evictions: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Maximum number of cached transactions
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of cached transactions
+    pub fn len(&self) -> usize {
+        self.lock().by_id.len()
+    }
+
+    /// Checks if nothing is cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Looks up a transaction by ID
+    pub fn get(&self, tx_id: &Hash) -> Option<Arc<Transaction>> {
+        let tx = self.lock().touch(tx_id);
+        let counter = if tx.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        tx
+    }
+
+    /// Caches a transaction under its ID
+    ///
+    /// A cached copy with the same ID but another witness is replaced, so
+    /// readers never get a ring signature other than the latest inserted.
+    /// Meant for storage; see [`share`](Self::share) otherwise.
+    ///
+    /// # Returns
+    /// The copy to share: the one already cached if it is identical, `tx`
+    /// otherwise
+    pub fn insert(&self, tx: Arc<Transaction>) -> Arc<Transaction> {
+        if self.capacity == 0 {
+            return tx;
+        }
+
+        let tx_id = tx.id();
+        let mut lru = self.lock();
+        if let Some(cached) = lru.touch(&tx_id) {
+            if Arc::ptr_eq(&cached, &tx) || cached.wtxid() == tx.wtxid() {
+                return cached;
+            }
+            if let Some((_, used)) = lru.by_id.remove(&tx_id) {
+                lru.by_use.remove(&used);
+            }
+        }
+
+        while lru.by_id.len() >= self.capacity {
+            let Some((_, oldest)) = lru.by_use.pop_first() else { break };
+            lru.by_id.remove(&oldest);
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+        lru.tick += 1;
+        let tick = lru.tick;
+        lru.by_use.insert(tick, tx_id);
+        lru.by_id.insert(tx_id, (tx.clone(), tick));
+        tx
+    }
+
+    /// Wraps `tx` for sharing, reusing the cached copy if it is identical
+    ///
+    /// Unlike [`insert`](Self::insert) this never caches `tx`: a relayed
+    /// transaction may carry another witness than the stored one.
+    pub fn share(&self, tx: Transaction) -> Arc<Transaction> {
+        match self.get(&tx.id()) {
+            Some(cached) if cached.wtxid() == tx.wtxid() => cached,
+            _ => Arc::new(tx),
+        }
+    }
+
+    /// Drops a transaction from the cache
+    pub fn remove(&self, tx_id: &Hash) {
+        let mut lru = self.lock();
+        if let Some((_, used)) = lru.by_id.remove(tx_id) {
+            lru.by_use.remove(&used);
+        }
+    }
+
+    /// Drops every cached transaction
+    pub fn clear(&self) {
+        let mut lru = self.lock();
+        lru.by_id.clear();
+        lru.by_use.clear();
+    }
+
+    /// Hit and eviction counters so far
+    pub fn stats(&self) -> TxCacheStats {
+        TxCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for TxCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TX_CACHE_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestTx;
+
+    fn tx(nonce: u8) -> Arc<Transaction> {
+        Arc::new(TestTx::new(nonce).build())
+    }
+
+    #[test]
+    fn test_least_recently_used_is_evicted() {
+        let cache = TxCache::new(2);
+        let (a, b, c) = (tx(1), tx(2), tx(3));
+        cache.insert(a.clone());
+        cache.insert(b.clone());
+
+        // Reading `a` makes `b` the least recently used
+        assert!(cache.get(&a.id()).is_some());
+        cache.insert(c.clone());
+
+        assert!(cache.get(&b.id()).is_none());
+        assert!(cache.get(&a.id()).is_some());
+        assert!(cache.get(&c.id()).is_some());
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.stats(), TxCacheStats { hits: 3, misses: 1, evictions: 1 });
+    }
+
+    #[test]
+    fn test_insert_shares_cached_copy() {
+        let cache = TxCache::new(4);
+        let first = cache.insert(tx(1));
+        let second = cache.insert(tx(1));
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // Another witness under the same ID replaces the cached copy
+        let mut malleated = tx(1).as_ref().clone();
+        malleated.ring_signatures[0].signature = vec![7];
+        let replaced = cache.insert(Arc::new(malleated));
+        assert!(!Arc::ptr_eq(&first, &replaced));
+        assert_eq!(cache.get(&first.id()).unwrap().ring_signatures[0].signature, vec![7]);
+        assert_eq!(cache.len(), 1);
+
+        // Sharing reuses identical copies only and never caches
+        let shared = cache.share(tx(1).as_ref().clone());
+        assert!(!Arc::ptr_eq(&shared, &replaced));
+        let mut same = tx(1).as_ref().clone();
+        same.ring_signatures[0].signature = vec![7];
+        assert!(Arc::ptr_eq(&cache.share(same), &replaced));
+        cache.share(tx(2).as_ref().clone());
+        assert_eq!(cache.len(), 1);
+
+        cache.remove(&first.id());
+        assert!(cache.is_empty());
+
+        // A zero capacity cache hands transactions back without keeping them
+        let uncached = TxCache::new(0);
+        uncached.insert(tx(1));
+        assert!(uncached.get(&first.id()).is_none());
+    }
+}
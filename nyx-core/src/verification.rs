@@ -0,0 +1,452 @@
+// src/verification.rs
+
+//! Transaction verification stages run before DAG insertion.
+//!
+//! Verification is split into cheap header checks and expensive
+//! cryptographic checks so that trusted replays (e.g. restoring from a
+//! checkpoint) can skip the latter:
+//!
+//! | Stage                  | `HeadersOnly` | `Full` |
+//! |------------------------|:-------------:|:------:|
+//! | Version / structure    | ✓             | ✓      |
+//! | Parents exist          | ✓             | ✓      |
+//! | Timestamp bounds       | ✓             | ✓      |
+//! | Key image uniqueness   | ✓             | ✓      |
+//! | Outputs unspent        | ✓             | ✓      |
+//! | Ring signature         |               | ✓      |
+//! | Ring members           |               | ✓      |
+//! | Range proofs           |               | ✓      |
+//! | Commitment balance     |               | ✓      |
+//! | Unlock times           |               | ✓      |
+//! | HTLC witnesses         |               | ✓      |
+
+use crate::errors::{NyxError, Result};
+use crate::extra::Extra;
+use crate::transaction::{HtlcLock, Transaction, TxOutput};
+use crate::types::{Hash, Timestamp};
+use crate::versioning::ValidationContext;
+use nyx_crypto::pedersen::Commitment;
+use nyx_crypto::range;
+use std::collections::HashSet;
+
+/// Maximum number of seconds a transaction timestamp may be ahead of local time
+pub const MAX_FUTURE_DRIFT_SECS: u64 = 2 * 60 * 60;
+
+/// Maximum number of seconds a transaction may predate its newest parent
+pub const MAX_PARENT_SKEW_SECS: u64 = 2 * 60 * 60;
+
+/// How thoroughly a transaction is verified before insertion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerifyMode {
+    /// Run every check, including signatures, range proofs and balance
+    #[default]
+    Full,
+
+    /// Skip cryptographic checks; for replaying already-verified data
+    HeadersOnly,
+}
+
+impl VerifyMode {
+    /// Returns true if cryptographic checks should run
+    pub fn is_full(&self) -> bool {
+        matches!(self, VerifyMode::Full)
+    }
+}
+
+/// Verifies the transaction's ring signature over its signing message
+pub fn verify_ring_signature(tx: &Transaction) -> Result<()> {
+    let valid = tx.verify_signature()
+        .map_err(|e| NyxError::CryptoError(format!("Ring signature verification failed: {}", e)))?;
+
+    if !valid {
+        return Err(NyxError::InvalidTransaction(
+            "Invalid ring signature".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Verifies that every ring member is the output at its ring index, and
+/// that each ring includes the output its input spends
+///
+/// `output_key` gives the stealth address of the output at a global index,
+/// or `None` if no confirmed output has it. `spent_index` gives the global
+/// index of output `index` of a transaction, or `None` if it is not
+/// confirmed.
+pub fn verify_ring_members<F, G>(tx: &Transaction, output_key: F, spent_index: G) -> Result<()>
+where
+    F: Fn(u64) -> Option<Vec<u8>>,
+    G: Fn(&Hash, u32) -> Option<u64>,
+{
+    for (i, (input, ring_signature)) in tx.inputs.iter().zip(&tx.ring_signatures).enumerate() {
+        if input.ring_indices.len() != ring_signature.ring_members.len() {
+            return Err(NyxError::InvalidTransaction(format!(
+                "Input {} has {} ring indices for {} ring members",
+                i,
+                input.ring_indices.len(),
+                ring_signature.ring_members.len()
+            )));
+        }
+
+        // Otherwise the signer could spend an output its key does not own
+        match spent_index(&input.prev_tx, input.index) {
+            Some(spent) if input.ring_indices.iter().any(|&index| u64::from(index) == spent) => {}
+            Some(spent) => return Err(NyxError::InvalidTransaction(format!(
+                "Input {} ring does not include the spent output {}",
+                i, spent
+            ))),
+            None => return Err(NyxError::InvalidTransaction(format!(
+                "Input {} spends an output that is not confirmed",
+                i
+            ))),
+        }
+
+        for (member, &index) in ring_signature.ring_members.iter().zip(&input.ring_indices) {
+            match output_key(u64::from(index)) {
+                Some(key) if key == *member => {}
+                Some(_) => return Err(NyxError::InvalidTransaction(format!(
+                    "Input {} ring member is not output {}",
+                    i, index
+                ))),
+                None => return Err(NyxError::InvalidTransaction(format!(
+                    "Input {} ring index {} is not a confirmed output",
+                    i, index
+                ))),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Verifies the range proof attached to each output
+///
+/// Each proof shows its output's commitment holds an amount below 2^64, so
+/// no output can commit to a "negative" amount that the balance check
+/// would accept.
+pub fn verify_range_proofs(tx: &Transaction) -> Result<()> {
+    for (i, output) in tx.outputs.iter().enumerate() {
+        if output.range_proof.len() != range::RANGE_PROOF_SIZE {
+            return Err(NyxError::InvalidTransaction(format!(
+                "Output {} range proof is {} bytes, expected {}",
+                i,
+                output.range_proof.len(),
+                range::RANGE_PROOF_SIZE
+            )));
+        }
+
+        let commitment = Commitment::from_bytes(&output.amount_commitment)
+            .map_err(|e| NyxError::InvalidTransaction(format!("Output {} commitment: {}", i, e)))?;
+        if !range::verify(&commitment, &output.range_proof) {
+            return Err(NyxError::InvalidTransaction(
+                format!("Output {} has an invalid range proof", i)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Verifies the transaction timestamp against local time and its parents
+///
+/// # Arguments
+/// * `tx` - Transaction to check
+/// * `parent_timestamps` - Timestamps of the referenced parents
+/// * `now` - Current local time
+pub fn verify_timestamp(tx: &Transaction, parent_timestamps: &[Timestamp], now: Timestamp) -> Result<()> {
+    if tx.timestamp > now.saturating_add(MAX_FUTURE_DRIFT_SECS) {
+        return Err(NyxError::InvalidTransaction(format!(
+            "Timestamp {} is too far in the future (now {})",
+            tx.timestamp, now
+        )));
+    }
+
+    if let Some(&newest_parent) = parent_timestamps.iter().max() {
+        if tx.timestamp.saturating_add(MAX_PARENT_SKEW_SECS) < newest_parent {
+            return Err(NyxError::InvalidTransaction(format!(
+                "Timestamp {} predates parent timestamp {}",
+                tx.timestamp, newest_parent
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Verifies that none of the transaction's key images have been spent
+///
+/// `is_spent` reports whether a key image is already recorded. Also rejects
+/// transactions that reuse a key image across their own inputs.
+pub fn verify_key_images_unique<F>(tx: &Transaction, is_spent: F) -> Result<()>
+where
+    F: Fn(&[u8; 32]) -> bool,
+{
+    let mut seen = HashSet::with_capacity(tx.inputs.len());
+
+    for input in &tx.inputs {
+        if !seen.insert(input.key_image) {
+            return Err(NyxError::DoubleSpend(format!(
+                "Key image {} used twice in one transaction",
+                hex::encode(input.key_image)
+            )));
+        }
+
+        if is_spent(&input.key_image) {
+            return Err(NyxError::DoubleSpend(format!(
+                "Key image {} already spent",
+                hex::encode(input.key_image)
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Verifies that none of the outputs a transaction spends have been spent
+///
+/// `is_spent` reports whether output `index` of a transaction is already
+/// recorded as spent. Without this, inputs with fresh key images could
+/// spend the same output any number of times. Also rejects transactions
+/// that spend one output in several inputs.
+pub fn verify_outputs_unspent<F>(tx: &Transaction, is_spent: F) -> Result<()>
+where
+    F: Fn(&Hash, u32) -> bool,
+{
+    let mut seen = HashSet::with_capacity(tx.inputs.len());
+
+    for input in &tx.inputs {
+        if !seen.insert((input.prev_tx, input.index)) {
+            return Err(NyxError::DoubleSpend(format!(
+                "Output {}:{} spent twice in one transaction",
+                hex::encode(input.prev_tx),
+                input.index
+            )));
+        }
+
+        if is_spent(&input.prev_tx, input.index) {
+            return Err(NyxError::DoubleSpend(format!(
+                "Output {}:{} already spent",
+                hex::encode(input.prev_tx),
+                input.index
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Verifies that every output a transaction spends is unlocked
+///
+/// # Arguments
+/// * `spent` - Outputs spent by the transaction, in input order
+/// * `ctx` - DAG height and the spending transaction's timestamp
+pub fn verify_unlock_times(spent: &[TxOutput], ctx: &ValidationContext) -> Result<()> {
+    for (i, output) in spent.iter().enumerate() {
+        if let Some(unlock_time) = output.unlock_time {
+            if !unlock_time.is_unlocked(ctx) {
+                return Err(NyxError::InvalidTransaction(format!(
+                    "Input {} spends an output locked until {}",
+                    i, unlock_time
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Verifies that every spend of a hash/time-locked output is unlocked
+///
+/// Each such input must use the output's [key image](HtlcLock::key_image)
+/// and have a witness in `extra`: the hashlock's preimage signed by the
+/// claim key, or, once the timeout has passed, a signature of the refund
+/// key. Both sign the transaction's
+/// [HTLC witness message](Transaction::htlc_witness_message).
+///
+/// # Arguments
+/// * `spent` - Outputs spent by the transaction, in input order
+/// * `ctx` - DAG height and the spending transaction's timestamp
+pub fn verify_htlc_spends(tx: &Transaction, spent: &[TxOutput], ctx: &ValidationContext) -> Result<()> {
+    if spent.iter().all(|output| output.htlc.is_none()) {
+        return Ok(());
+    }
+
+    let extra = Extra::parse(&tx.extra)?;
+    let message = tx.htlc_witness_message();
+    for (i, (input, output)) in tx.inputs.iter().zip(spent).enumerate() {
+        let Some(htlc) = &output.htlc else {
+            continue;
+        };
+        let invalid = |reason: String| Err(NyxError::InvalidTransaction(format!("Input {} {}", i, reason)));
+
+        if input.key_image != HtlcLock::key_image(&input.prev_tx, input.index) {
+            return invalid("spends an HTLC output without its key image".to_string());
+        }
+        let Some(witness) = extra.htlc_witness(i as u32) else {
+            return invalid("spends an HTLC output without a witness".to_string());
+        };
+        let key = match &witness.preimage {
+            Some(preimage) if !htlc.opens(preimage) => return invalid("reveals a wrong HTLC preimage".to_string()),
+            Some(_) => &htlc.claim_key,
+            None if !htlc.timeout.is_unlocked(ctx) => {
+                return invalid(format!("refunds an HTLC output locked until {}", htlc.timeout));
+            }
+            None => &htlc.refund_key,
+        };
+        if !nyx_crypto::keys::verify_ed25519(&message, &witness.signature, key) {
+            return invalid("has an invalid HTLC signature".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extra::ExtraField;
+    use crate::testing::{funding_key, TestTx};
+    use crate::transaction::UnlockTime;
+    use nyx_crypto::pedersen;
+
+    #[test]
+    fn test_bad_ring_signature_rejected() {
+        let tx = TestTx::new(2).build();
+        assert!(verify_ring_signature(&tx).is_err());
+    }
+
+    #[test]
+    fn test_range_proof_checks() {
+        let mut tx = TestTx::new(2).build();
+        let blinding = pedersen::random_blinding();
+        tx.outputs[0].amount_commitment = pedersen::commit(1_000, &blinding).to_bytes().to_vec();
+        tx.outputs[0].range_proof = range::prove(1_000, &blinding);
+        assert!(verify_range_proofs(&tx).is_ok());
+
+        // A proof of another commitment, a placeholder and no proof at all
+        let mut other = tx.clone();
+        other.outputs[0].amount_commitment = pedersen::commit(1_001, &blinding).to_bytes().to_vec();
+        assert!(verify_range_proofs(&other).is_err());
+
+        tx.outputs[0].range_proof = vec![0u8; range::RANGE_PROOF_SIZE];
+        assert!(verify_range_proofs(&tx).is_err());
+
+        tx.outputs[0].range_proof.clear();
+        assert!(verify_range_proofs(&tx).is_err());
+    }
+
+    #[test]
+    fn test_timestamp_bounds() {
+        let mut tx = TestTx::new(2).build();
+        let now = 1_000_000;
+
+        tx.timestamp = now;
+        assert!(verify_timestamp(&tx, &[now - 10], now).is_ok());
+
+        tx.timestamp = now + MAX_FUTURE_DRIFT_SECS + 1;
+        assert!(verify_timestamp(&tx, &[], now).is_err());
+
+        tx.timestamp = now - MAX_PARENT_SKEW_SECS - 1;
+        assert!(verify_timestamp(&tx, &[now], now).is_err());
+    }
+
+    #[test]
+    fn test_unlock_times() {
+        let output = TestTx::new(2).build().outputs[0].clone();
+        let ctx = ValidationContext { height: 50, timestamp: 1_000 };
+
+        assert!(verify_unlock_times(std::slice::from_ref(&output), &ctx).is_ok());
+        for unlocked in [UnlockTime::Height(50), UnlockTime::Timestamp(1_000)] {
+            assert!(verify_unlock_times(&[output.clone().with_unlock_time(unlocked)], &ctx).is_ok());
+        }
+        for locked in [UnlockTime::Height(51), UnlockTime::Timestamp(1_001)] {
+            assert!(verify_unlock_times(&[output.clone().with_unlock_time(locked)], &ctx).is_err());
+        }
+    }
+
+    #[test]
+    fn test_htlc_spends() {
+        let (claim_seed, refund_seed, preimage) = ([1u8; 32], [2u8; 32], [3u8; 32]);
+        let htlc = HtlcLock {
+            hashlock: nyx_crypto::hash::sha256(&preimage),
+            timeout: UnlockTime::Height(10),
+            claim_key: nyx_crypto::keys::ed25519_public_key(&claim_seed),
+            refund_key: nyx_crypto::keys::ed25519_public_key(&refund_seed),
+        };
+        let spent = vec![TestTx::new(2).build().outputs[0].clone().with_htlc(htlc)];
+        let ctx = ValidationContext { height: 5, timestamp: 1_000 };
+
+        let mut tx = TestTx::new(2).build();
+        assert!(verify_htlc_spends(&tx, &spent, &ctx).is_err());
+        tx.inputs[0].key_image = HtlcLock::key_image(&tx.inputs[0].prev_tx, 0);
+        assert!(verify_htlc_spends(&tx, &spent, &ctx).is_err());
+
+        let witnessed = |preimage: Option<[u8; 32]>, seed: &[u8; 32]| {
+            let mut tx = tx.clone();
+            let signature = nyx_crypto::keys::sign_ed25519(&tx.htlc_witness_message(), seed);
+            let witness = crate::extra::HtlcWitness { input: 0, preimage, signature };
+            tx.extra = Extra::new().with_field(ExtraField::HtlcWitnesses(vec![witness])).to_bytes();
+            tx
+        };
+        assert!(verify_htlc_spends(&witnessed(Some(preimage), &claim_seed), &spent, &ctx).is_ok());
+        assert!(verify_htlc_spends(&witnessed(Some([4u8; 32]), &claim_seed), &spent, &ctx).is_err());
+        assert!(verify_htlc_spends(&witnessed(Some(preimage), &refund_seed), &spent, &ctx).is_err());
+
+        // Refunds open at the timeout
+        let refund = witnessed(None, &refund_seed);
+        assert!(verify_htlc_spends(&refund, &spent, &ctx).is_err());
+        assert!(verify_htlc_spends(&refund, &spent, &ValidationContext { height: 10, ..ctx }).is_ok());
+    }
+
+    #[test]
+    fn test_key_image_uniqueness() {
+        let mut tx = TestTx::new(2).build();
+        let mut spent = HashSet::new();
+        assert!(verify_key_images_unique(&tx, |ki| spent.contains(ki)).is_ok());
+
+        spent.insert([2u8; 32]);
+        assert!(matches!(
+            verify_key_images_unique(&tx, |ki| spent.contains(ki)),
+            Err(NyxError::DoubleSpend(_))
+        ));
+
+        let dup = tx.inputs[0].clone();
+        tx.inputs.push(dup);
+        assert!(matches!(verify_key_images_unique(&tx, |_| false), Err(NyxError::DoubleSpend(_))));
+    }
+
+    #[test]
+    fn test_outputs_unspent() {
+        let mut tx = TestTx::new(2).build();
+        let mut spent = HashSet::new();
+        assert!(verify_outputs_unspent(&tx, |prev_tx, index| spent.contains(&(*prev_tx, index))).is_ok());
+
+        // A fresh key image does not make a spent output spendable again
+        spent.insert(([2u8; 32], 0));
+        tx.inputs[0].key_image = [9u8; 32];
+        assert!(matches!(
+            verify_outputs_unspent(&tx, |prev_tx, index| spent.contains(&(*prev_tx, index))),
+            Err(NyxError::DoubleSpend(_))
+        ));
+
+        let mut dup = tx.inputs[0].clone();
+        dup.key_image = [10u8; 32];
+        tx.inputs.push(dup);
+        assert!(matches!(verify_outputs_unspent(&tx, |_, _| false), Err(NyxError::DoubleSpend(_))));
+    }
+
+    #[test]
+    fn test_ring_must_include_spent_output() {
+        let tx = TestTx::new(3).funded().build();
+        let output_key = |index: u64| Some(funding_key(index as u8).public_key);
+        assert!(verify_ring_members(&tx, output_key, |_, index| Some(u64::from(index))).is_ok());
+
+        // Every member checks out, but the spent output is not among them
+        let result = verify_ring_members(&tx, output_key, |_, _| Some(5));
+        assert!(matches!(result, Err(NyxError::InvalidTransaction(_))));
+
+        let result = verify_ring_members(&tx, output_key, |_, _| None);
+        assert!(matches!(result, Err(NyxError::InvalidTransaction(_))));
+    }
+}
@@ -0,0 +1,376 @@
+// src/versioning.rs
+
+//! Transaction versioning and protocol upgrade rules.
+//!
+//! Every transaction version has a rule set registered in a
+//! [`VersionRegistry`]. A rule set carries the validation function for that
+//! version and the point at which it becomes valid on the network:
+//! - Activation height (DAG transaction count)
+//! - Activation timestamp (transaction timestamp)
+//!
+//! Transactions with versions that are unknown, or known but not yet active,
+//! are rejected. New versions can therefore be shipped ahead of time and
+//! switched on at a coordinated point without forking older nodes.
+
+use crate::errors::{NyxError, Result};
+use crate::transaction::Transaction;
+use crate::types::Timestamp;
+use crate::{HTLC_VERSION, PROTOCOL_VERSION, RING_INDEX_VERSION, UNLOCK_TIME_VERSION};
+use std::collections::BTreeMap;
+
+/// Validation function for a specific transaction version
+pub type ValidationFn = fn(&Transaction) -> Result<()>;
+
+/// Point at which a transaction version becomes valid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Activation {
+    /// Minimum DAG height (transaction count) before the version is accepted
+    pub height: Option<u64>,
+
+    /// Minimum transaction timestamp before the version is accepted
+    pub timestamp: Option<Timestamp>,
+}
+
+impl Activation {
+    /// Activation that is effective immediately
+    pub const fn always() -> Self {
+        Self {
+            height: None,
+            timestamp: None,
+        }
+    }
+
+    /// Activation at a given DAG height
+    pub const fn at_height(height: u64) -> Self {
+        Self {
+            height: Some(height),
+            timestamp: None,
+        }
+    }
+
+    /// Activation at a given timestamp
+    pub const fn at_timestamp(timestamp: Timestamp) -> Self {
+        Self {
+            height: None,
+            timestamp: Some(timestamp),
+        }
+    }
+
+    /// Checks whether the activation conditions are met
+    pub fn is_active(&self, ctx: &ValidationContext) -> bool {
+        let height_ok = self.height.is_none_or(|h| ctx.height >= h);
+        let time_ok = self.timestamp.is_none_or(|t| ctx.timestamp >= t);
+        height_ok && time_ok
+    }
+}
+
+/// Chain position a transaction is being validated against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ValidationContext {
+    /// Current DAG height (transaction count)
+    pub height: u64,
+
+    /// Timestamp used for activation checks (normally the tx timestamp)
+    pub timestamp: Timestamp,
+}
+
+/// Rules for a single transaction version
+#[derive(Clone)]
+pub struct VersionRules {
+    /// Transaction version these rules apply to
+    pub version: u8,
+
+    /// When this version becomes valid
+    pub activation: Activation,
+
+    /// Validation function
+    pub validate: ValidationFn,
+}
+
+impl std::fmt::Debug for VersionRules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VersionRules")
+            .field("version", &self.version)
+            .field("activation", &self.activation)
+            .finish()
+    }
+}
+
+/// Registry dispatching validation by transaction version
+#[derive(Clone, Debug)]
+pub struct VersionRegistry {
+    rules: BTreeMap<u8, VersionRules>,
+}
+
+impl VersionRegistry {
+    /// Creates an empty registry that rejects every version
+    pub fn empty() -> Self {
+        Self {
+            rules: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a registry with the rules for all built-in versions
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        registry.register(VersionRules {
+            version: PROTOCOL_VERSION,
+            activation: Activation::always(),
+            validate: validate_v1,
+        });
+        registry.register(VersionRules {
+            version: UNLOCK_TIME_VERSION,
+            activation: Activation::always(),
+            validate: validate_v2,
+        });
+        registry.register(VersionRules {
+            version: RING_INDEX_VERSION,
+            activation: Activation::always(),
+            validate: validate_v3,
+        });
+        registry.register(VersionRules {
+            version: HTLC_VERSION,
+            activation: Activation::always(),
+            validate: validate_v4,
+        });
+        registry
+    }
+
+    /// Registers (or replaces) the rules for a version
+    pub fn register(&mut self, rules: VersionRules) {
+        self.rules.insert(rules.version, rules);
+    }
+
+    /// Gets the rules for a version
+    pub fn get(&self, version: u8) -> Option<&VersionRules> {
+        self.rules.get(&version)
+    }
+
+    /// Returns the highest registered version
+    pub fn latest_version(&self) -> Option<u8> {
+        self.rules.keys().next_back().copied()
+    }
+
+    /// Returns the versions active in the given context
+    pub fn active_versions(&self, ctx: &ValidationContext) -> Vec<u8> {
+        self.rules.values()
+            .filter(|r| r.activation.is_active(ctx))
+            .map(|r| r.version)
+            .collect()
+    }
+
+    /// Validates a transaction against the rules of its version
+    ///
+    /// # Errors
+    /// - `UnsupportedVersion` if the version is unknown or not yet active
+    /// - Whatever the version's validation function returns
+    pub fn validate(&self, tx: &Transaction, ctx: &ValidationContext) -> Result<()> {
+        let rules = self.rules.get(&tx.version).ok_or_else(|| {
+            NyxError::UnsupportedVersion(format!(
+                "Unknown transaction version {} (latest supported: {})",
+                tx.version,
+                self.latest_version().map_or("none".to_string(), |v| v.to_string())
+            ))
+        })?;
+
+        if !rules.activation.is_active(ctx) {
+            return Err(NyxError::UnsupportedVersion(format!(
+                "Transaction version {} is not active yet",
+                tx.version
+            )));
+        }
+
+        (rules.validate)(tx)
+    }
+}
+
+impl Default for VersionRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Validation rules for version 1 transactions
+fn validate_v1(tx: &Transaction) -> Result<()> {
+    // Version 1 does not encode unlock times, so its ID and signature
+    // would not commit to them
+    if tx.outputs.iter().any(|output| output.unlock_time.is_some()) {
+        return Err(NyxError::InvalidTransaction(format!(
+            "Unlock times require transaction version {}",
+            UNLOCK_TIME_VERSION
+        )));
+    }
+
+    validate_v2(tx)
+}
+
+/// Validation rules for version 2 transactions, which add output unlock
+/// times
+fn validate_v2(tx: &Transaction) -> Result<()> {
+    // Versions before 4 do not encode hash/time-locks
+    if tx.outputs.iter().any(|output| output.htlc.is_some()) {
+        return Err(NyxError::InvalidTransaction(format!(
+            "Hash/time-locks require transaction version {}",
+            HTLC_VERSION
+        )));
+    }
+
+    validate_structure(tx)
+}
+
+/// Checks the structure every version shares
+fn validate_structure(tx: &Transaction) -> Result<()> {
+    if !tx.validate_structure() {
+        return Err(NyxError::InvalidTransaction(
+            "Invalid transaction structure".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validation rules for version 3 transactions, which have a ring index per
+/// ring member
+///
+/// That each member is the output at its global index is checked against
+/// storage during full verification, for every version.
+fn validate_v3(tx: &Transaction) -> Result<()> {
+    validate_v2(tx)?;
+    validate_ring_indices(tx)
+}
+
+/// Validation rules for version 4 transactions, whose outputs can carry a
+/// hash/time-lock
+///
+/// Spends of HTLC outputs are checked against the spent outputs during full
+/// verification.
+fn validate_v4(tx: &Transaction) -> Result<()> {
+    validate_structure(tx)?;
+    validate_ring_indices(tx)
+}
+
+/// Checks that every input has one ring index per ring member
+fn validate_ring_indices(tx: &Transaction) -> Result<()> {
+    for (i, (input, ring_signature)) in tx.inputs.iter().zip(&tx.ring_signatures).enumerate() {
+        if input.ring_indices.len() != ring_signature.ring_members.len() {
+            return Err(NyxError::InvalidTransaction(format!(
+                "Input {} has {} ring indices for {} ring members",
+                i,
+                input.ring_indices.len(),
+                ring_signature.ring_members.len()
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestTx;
+
+    fn create_test_tx(version: u8) -> Transaction {
+        let mut tx = TestTx::new(1).build();
+        tx.version = version;
+        tx
+    }
+
+    #[test]
+    fn test_unlock_times_require_v2() {
+        let registry = VersionRegistry::new();
+        let ctx = ValidationContext::default();
+
+        let mut tx = create_test_tx(1);
+        tx.outputs[0].unlock_time = Some(crate::UnlockTime::Height(10));
+        assert!(matches!(registry.validate(&tx, &ctx), Err(NyxError::InvalidTransaction(_))));
+
+        tx.version = UNLOCK_TIME_VERSION;
+        assert!(registry.validate(&tx, &ctx).is_ok());
+    }
+
+    #[test]
+    fn test_htlcs_require_v4() {
+        let registry = VersionRegistry::new();
+        let ctx = ValidationContext::default();
+
+        let mut tx = create_test_tx(RING_INDEX_VERSION);
+        tx.inputs[0].ring_indices = vec![4, 9];
+        tx.outputs[0].htlc = Some(crate::HtlcLock {
+            hashlock: [1u8; 32],
+            timeout: crate::UnlockTime::Height(10),
+            claim_key: [2u8; 32],
+            refund_key: [3u8; 32],
+        });
+        assert!(matches!(registry.validate(&tx, &ctx), Err(NyxError::InvalidTransaction(_))));
+
+        tx.version = HTLC_VERSION;
+        assert!(registry.validate(&tx, &ctx).is_ok());
+    }
+
+    fn accept_all(_tx: &Transaction) -> Result<()> {
+        Ok(())
+    }
+
+    #[test]
+    fn test_v1_accepted() {
+        let registry = VersionRegistry::new();
+        let ctx = ValidationContext::default();
+        assert!(registry.validate(&create_test_tx(1), &ctx).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_version_rejected() {
+        let registry = VersionRegistry::new();
+        let ctx = ValidationContext::default();
+
+        let result = registry.validate(&create_test_tx(HTLC_VERSION + 1), &ctx);
+        assert!(matches!(result, Err(NyxError::UnsupportedVersion(_))));
+    }
+
+    #[test]
+    fn test_v3_requires_an_index_per_ring_member() {
+        let registry = VersionRegistry::new();
+        let ctx = ValidationContext::default();
+
+        let mut tx = create_test_tx(RING_INDEX_VERSION);
+        assert!(matches!(registry.validate(&tx, &ctx), Err(NyxError::InvalidTransaction(_))));
+
+        tx.inputs[0].ring_indices = vec![4, 9];
+        assert!(registry.validate(&tx, &ctx).is_ok());
+    }
+
+    #[test]
+    fn test_activation_height() {
+        let mut registry = VersionRegistry::new();
+        registry.register(VersionRules {
+            version: 2,
+            activation: Activation::at_height(100),
+            validate: accept_all,
+        });
+
+        let tx = create_test_tx(2);
+        let before = ValidationContext { height: 99, timestamp: 0 };
+        let after = ValidationContext { height: 100, timestamp: 0 };
+
+        assert!(registry.validate(&tx, &before).is_err());
+        assert!(registry.validate(&tx, &after).is_ok());
+        assert_eq!(registry.latest_version(), Some(HTLC_VERSION));
+    }
+
+    #[test]
+    fn test_activation_timestamp() {
+        let mut registry = VersionRegistry::new();
+        registry.register(VersionRules {
+            version: 2,
+            activation: Activation::at_timestamp(1_000),
+            validate: accept_all,
+        });
+
+        let ctx = ValidationContext { height: 0, timestamp: 999 };
+        assert_eq!(registry.active_versions(&ctx), vec![1, 3, 4]);
+
+        let ctx = ValidationContext { height: 0, timestamp: 1_000 };
+        assert_eq!(registry.active_versions(&ctx), vec![1, 2, 3, 4]);
+    }
+}
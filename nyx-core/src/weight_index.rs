@@ -0,0 +1,298 @@
+// src/weight_index.rs
+
+//! Weighted sampling index backed by a Fenwick (binary indexed) tree.
+//!
+//! Keys are assigned slots in a Fenwick tree holding their weights, which
+//! makes every operation logarithmic in the number of slots:
+//! - Insert / update / remove: O(log n)
+//! - Weighted random sample: O(log n)
+//!
+//! Slots freed by removals are reused, so the tree only grows when the
+//! number of live keys exceeds its capacity.
+
+use crate::types::Hash;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Initial number of slots allocated
+const INITIAL_CAPACITY: usize = 64;
+
+/// Weighted index supporting O(log n) updates and sampling
+#[derive(Debug, Clone)]
+pub struct WeightIndex {
+    /// Fenwick tree over slot weights (1-based)
+    tree: Vec<f64>,
+
+    /// Raw weight per slot
+    weights: Vec<f64>,
+
+    /// Key occupying each slot
+    keys: Vec<Option<Hash>>,
+
+    /// Slot assigned to each key
+    slots: HashMap<Hash, usize>,
+
+    /// Unoccupied slots available for reuse
+    free: Vec<usize>,
+}
+
+impl WeightIndex {
+    /// Creates an empty index
+    pub fn new() -> Self {
+        Self::with_capacity(INITIAL_CAPACITY)
+    }
+
+    /// Creates an empty index with room for `capacity` keys before growing
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            tree: vec![0.0; capacity + 1],
+            weights: vec![0.0; capacity],
+            keys: vec![None; capacity],
+            slots: HashMap::with_capacity(capacity),
+            free: (0..capacity).rev().collect(),
+        }
+    }
+
+    /// Number of keys in the index
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns true if the index holds no keys
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Checks whether a key is present
+    pub fn contains(&self, key: &Hash) -> bool {
+        self.slots.contains_key(key)
+    }
+
+    /// Gets the weight of a key
+    pub fn weight(&self, key: &Hash) -> Option<f64> {
+        self.slots.get(key).map(|&slot| self.weights[slot])
+    }
+
+    /// Returns the sum of all weights
+    pub fn total(&self) -> f64 {
+        self.prefix_sum(self.weights.len())
+    }
+
+    /// Iterates over the keys in the index (unordered)
+    pub fn keys(&self) -> impl Iterator<Item = &Hash> {
+        self.slots.keys()
+    }
+
+    /// Inserts a key or updates its weight
+    ///
+    /// Negative and non-finite weights are treated as zero.
+    pub fn insert(&mut self, key: Hash, weight: f64) {
+        let weight = if weight.is_finite() { weight.max(0.0) } else { 0.0 };
+
+        let slot = match self.slots.get(&key) {
+            Some(&slot) => slot,
+            None => {
+                let slot = self.allocate_slot();
+                self.keys[slot] = Some(key);
+                self.slots.insert(key, slot);
+                slot
+            }
+        };
+
+        let delta = weight - self.weights[slot];
+        self.weights[slot] = weight;
+        self.add(slot, delta);
+    }
+
+    /// Removes a key, returning true if it was present
+    pub fn remove(&mut self, key: &Hash) -> bool {
+        let Some(slot) = self.slots.remove(key) else {
+            return false;
+        };
+
+        let delta = -self.weights[slot];
+        self.weights[slot] = 0.0;
+        self.add(slot, delta);
+        self.keys[slot] = None;
+        self.free.push(slot);
+
+        true
+    }
+
+    /// Samples a key with probability proportional to its weight
+    ///
+    /// Falls back to a uniform choice if every weight is zero.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Hash> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let total = self.total();
+        if total > 0.0 {
+            let target = rng.gen::<f64>() * total;
+            if let Some(key) = self.keys.get(self.find(target)).copied().flatten() {
+                return Some(key);
+            }
+        }
+
+        // All weights zero (or float drift landed on an empty slot)
+        let n = rng.gen_range(0..self.len());
+        self.slots.keys().nth(n).copied()
+    }
+
+    /// Finds the slot whose cumulative weight range contains `target`
+    fn find(&self, mut target: f64) -> usize {
+        let capacity = self.weights.len();
+        let mut pos = 0;
+        let mut step = capacity.next_power_of_two();
+
+        while step > 0 {
+            let next = pos + step;
+            if next <= capacity && self.tree[next] <= target {
+                pos = next;
+                target -= self.tree[next];
+            }
+            step >>= 1;
+        }
+
+        pos.min(capacity - 1)
+    }
+
+    /// Sum of weights in slots `0..end`
+    fn prefix_sum(&self, end: usize) -> f64 {
+        let mut i = end;
+        let mut sum = 0.0;
+        while i > 0 {
+            sum += self.tree[i];
+            i &= i - 1;
+        }
+        sum
+    }
+
+    /// Adds `delta` to a slot's weight in the tree
+    fn add(&mut self, slot: usize, delta: f64) {
+        let mut i = slot + 1;
+        while i < self.tree.len() {
+            self.tree[i] += delta;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Takes a free slot, doubling capacity if none are left
+    fn allocate_slot(&mut self) -> usize {
+        if let Some(slot) = self.free.pop() {
+            return slot;
+        }
+
+        let old_capacity = self.weights.len();
+        let new_capacity = old_capacity * 2;
+        self.weights.resize(new_capacity, 0.0);
+        self.keys.resize(new_capacity, None);
+        self.free.extend((old_capacity + 1..new_capacity).rev());
+        self.rebuild();
+
+        old_capacity
+    }
+
+    /// Rebuilds the Fenwick tree from raw weights in O(n)
+    fn rebuild(&mut self) {
+        let capacity = self.weights.len();
+        self.tree = vec![0.0; capacity + 1];
+
+        for slot in 0..capacity {
+            let i = slot + 1;
+            self.tree[i] += self.weights[slot];
+            let parent = i + (i & i.wrapping_neg());
+            if parent <= capacity {
+                self.tree[parent] += self.tree[i];
+            }
+        }
+    }
+}
+
+impl Default for WeightIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: u8) -> Hash {
+        [n; 32]
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut index = WeightIndex::new();
+        index.insert(key(1), 2.0);
+        index.insert(key(2), 3.0);
+
+        assert_eq!(index.len(), 2);
+        assert!((index.total() - 5.0).abs() < 1e-9);
+
+        index.insert(key(1), 1.0);
+        assert_eq!(index.weight(&key(1)), Some(1.0));
+        assert!((index.total() - 4.0).abs() < 1e-9);
+
+        assert!(index.remove(&key(2)));
+        assert!(!index.remove(&key(2)));
+        assert_eq!(index.len(), 1);
+        assert!((index.total() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_growth_preserves_weights() {
+        let mut index = WeightIndex::with_capacity(2);
+        for n in 0..100 {
+            index.insert(key(n), 1.0);
+        }
+
+        assert_eq!(index.len(), 100);
+        assert!((index.total() - 100.0).abs() < 1e-9);
+
+        for n in 0..50 {
+            index.remove(&key(n));
+        }
+        assert!((index.total() - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sample_respects_weights() {
+        let mut index = WeightIndex::new();
+        index.insert(key(1), 0.0);
+        index.insert(key(2), 1.0);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            assert_eq!(index.sample(&mut rng), Some(key(2)));
+        }
+    }
+
+    #[test]
+    fn test_sample_distribution() {
+        let mut index = WeightIndex::new();
+        index.insert(key(1), 1.0);
+        index.insert(key(2), 9.0);
+
+        let mut rng = rand::thread_rng();
+        let heavy = (0..10_000)
+            .filter(|_| index.sample(&mut rng) == Some(key(2)))
+            .count();
+
+        assert!(heavy > 8_500 && heavy < 9_500, "heavy picked {} times", heavy);
+    }
+
+    #[test]
+    fn test_sample_empty_and_zero_weights() {
+        let mut index = WeightIndex::new();
+        let mut rng = rand::thread_rng();
+        assert_eq!(index.sample(&mut rng), None);
+
+        index.insert(key(7), 0.0);
+        assert_eq!(index.sample(&mut rng), Some(key(7)));
+    }
+}
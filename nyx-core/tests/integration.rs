@@ -1,4 +1,4 @@
-// src/tests/integration.rs
+// tests/integration.rs
 
 //! Integration tests for the Nyx DAG implementation
 //!
@@ -11,30 +11,19 @@ mod tests {
     use nyx_core::storage::MemoryStorage;
     use nyx_core::dag::{DagProcessor, TxState};
     use nyx_core::tip_selection::TipSelector;
-    use nyx_core::transaction::{Transaction, TxInput, TxOutput, RingSignature};
-
-    /// Helper to create a test transaction
-    fn create_test_tx(parent1: Hash, parent2: Hash, nonce: u8) -> Transaction {
-        Transaction::new(
-            vec![TxInput {
-                prev_tx: [nonce; 32],
-                index: 0,
-                key_image: [0u8; 32],
-                ring_indices: vec![0, 1, 2, 3],
-            }],
-            vec![TxOutput {
-                stealth_address: vec![nonce, nonce, nonce],
-                amount_commitment: vec![nonce + 1],
-                range_proof: vec![nonce + 2],
-            }],
-            RingSignature {
-                signature_data: vec![nonce + 3],
-                ring_size: 16,
-            },
-            vec![nonce + 4],
-            parent1,
-            parent2,
-        )
+    use nyx_core::testing::{funded_tx, funding_tx, test_tx};
+
+    /// Stores [`funding_tx`] and a second genesis transaction directly,
+    /// returning their hashes
+    ///
+    /// Both are confirmed, so the funded transactions from
+    /// [`funded_tx`] pass full verification.
+    fn store_genesis(storage: &MemoryStorage) -> (Hash, Hash) {
+        let gen1_hash = storage.store_transaction(funding_tx()).unwrap();
+        let gen2_hash = storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 2)).unwrap();
+        storage.mark_confirmed(&gen1_hash).unwrap();
+        storage.mark_confirmed(&gen2_hash).unwrap();
+        (gen1_hash, gen2_hash)
     }
 
     #[test]
@@ -44,14 +33,10 @@ mod tests {
         let dag = DagProcessor::new(storage.clone());
 
         // Create genesis transactions (stored directly in storage)
-        let genesis1 = create_test_tx([0u8; 32], [0u8; 32], 1);
-        let genesis2 = create_test_tx([0u8; 32], [0u8; 32], 2);
-
-        let gen1_hash = storage.store_transaction(genesis1.clone()).unwrap();
-        let gen2_hash = storage.store_transaction(genesis2.clone()).unwrap();
+        let (gen1_hash, gen2_hash) = store_genesis(&storage);
 
         // Create a transaction referencing genesis
-        let tx1 = create_test_tx(gen1_hash, gen2_hash, 3);
+        let tx1 = funded_tx(gen1_hash, gen2_hash, 3);
         let tx1_hash = dag.add_transaction(tx1).unwrap();
 
         // Verify initial score
@@ -72,18 +57,17 @@ mod tests {
         let dag = DagProcessor::new(storage.clone());
 
         // Genesis
-        let genesis = create_test_tx([0u8; 32], [0u8; 32], 1);
-        let gen_hash = storage.store_transaction(genesis).unwrap();
+        let (gen_hash, gen2_hash) = store_genesis(&storage);
 
         // Build a chain of transactions
-        let mut prev_hash = gen_hash;
+        let mut parents = [gen_hash, gen2_hash];
         let mut hashes = vec![gen_hash];
 
-        for i in 2..6 {
-            let tx = create_test_tx(prev_hash, prev_hash, i);
+        for i in 3..7 {
+            let tx = funded_tx(parents[0], parents[1], i);
             let tx_hash = dag.add_transaction(tx).unwrap();
             hashes.push(tx_hash);
-            prev_hash = tx_hash;
+            parents = [tx_hash, parents[0]];
         }
 
         // Check that later transactions have higher scores due to descendants
@@ -103,18 +87,17 @@ mod tests {
         let dag = DagProcessor::new(storage.clone());
 
         // Genesis
-        let genesis = create_test_tx([0u8; 32], [0u8; 32], 1);
-        let gen_hash = storage.store_transaction(genesis).unwrap();
+        let (gen_hash, gen2_hash) = store_genesis(&storage);
 
         // Add several transactions
         for i in 2..10 {
-            let tx = create_test_tx(gen_hash, gen_hash, i);
+            let tx = funded_tx(gen_hash, gen2_hash, i);
             dag.add_transaction(tx).unwrap();
         }
 
         let stats = dag.get_stats().unwrap();
 
-        assert_eq!(stats.total_transactions, 8); // 8 transactions added through DAG
+        assert_eq!(stats.total_transactions, 10); // 2 genesis + 8 added through DAG
         assert_eq!(stats.pending_transactions, 8);
         assert_eq!(stats.confirmed_transactions, 0);
         assert_eq!(stats.finalized_transactions, 0);
@@ -125,10 +108,9 @@ mod tests {
         let storage = MemoryStorage::new();
         let dag = DagProcessor::new(storage.clone());
 
-        let genesis = create_test_tx([0u8; 32], [0u8; 32], 1);
-        let gen_hash = storage.store_transaction(genesis).unwrap();
+        let (gen_hash, gen2_hash) = store_genesis(&storage);
 
-        let tx = create_test_tx(gen_hash, gen_hash, 2);
+        let tx = funded_tx(gen_hash, gen2_hash, 2);
         let tx_hash = dag.add_transaction(tx).unwrap();
 
         // Initially pending
@@ -147,11 +129,10 @@ mod tests {
         let storage = MemoryStorage::new();
         let dag = DagProcessor::new(storage.clone());
 
-        let genesis = create_test_tx([0u8; 32], [0u8; 32], 1);
-        let gen_hash = storage.store_transaction(genesis).unwrap();
+        let (gen_hash, gen2_hash) = store_genesis(&storage);
 
         // Add first transaction
-        let tx1 = create_test_tx(gen_hash, gen_hash, 2);
+        let tx1 = funded_tx(gen_hash, gen2_hash, 2);
         let tx1_hash = dag.add_transaction(tx1).unwrap();
 
         let tips = dag.get_tips().unwrap();
@@ -159,7 +140,7 @@ mod tests {
         assert!(tips.contains(&tx1_hash));
 
         // Add second transaction (also referencing genesis)
-        let tx2 = create_test_tx(gen_hash, gen_hash, 3);
+        let tx2 = funded_tx(gen_hash, gen2_hash, 3);
         let tx2_hash = dag.add_transaction(tx2).unwrap();
 
         let tips = dag.get_tips().unwrap();
@@ -168,7 +149,7 @@ mod tests {
         assert!(tips.contains(&tx2_hash));
 
         // Add third transaction referencing both previous ones
-        let tx3 = create_test_tx(tx1_hash, tx2_hash, 4);
+        let tx3 = funded_tx(tx1_hash, tx2_hash, 4);
         let tx3_hash = dag.add_transaction(tx3).unwrap();
 
         let tips = dag.get_tips().unwrap();
@@ -182,12 +163,11 @@ mod tests {
         let storage = MemoryStorage::new();
         let dag = DagProcessor::new(storage.clone());
 
-        let genesis = create_test_tx([0u8; 32], [0u8; 32], 1);
-        let gen_hash = storage.store_transaction(genesis).unwrap();
+        let (gen_hash, gen2_hash) = store_genesis(&storage);
 
         // Add multiple tips
         for i in 2..6 {
-            let tx = create_test_tx(gen_hash, gen_hash, i);
+            let tx = funded_tx(gen_hash, gen2_hash, i);
             dag.add_transaction(tx).unwrap();
         }
 
@@ -207,7 +187,7 @@ mod tests {
         let dag = DagProcessor::new(storage);
 
         // Try to add transaction with non-existent parents
-        let tx = create_test_tx([99u8; 32], [98u8; 32], 1);
+        let tx = funded_tx([99u8; 32], [98u8; 32], 1);
         let result = dag.add_transaction(tx);
 
         // Should fail with InvalidParent error
@@ -223,8 +203,10 @@ mod tests {
         let storage = MemoryStorage::new();
         let dag = DagProcessor::new(storage);
 
-        // Create transaction with invalid structure (same parent twice)
-        let mut tx = create_test_tx([1u8; 32], [1u8; 32], 1);
+        // Create transaction with invalid structure (same parent twice),
+        // which `try_new` refuses to build
+        let mut tx = funded_tx([1u8; 32], [2u8; 32], 1);
+        tx.references = [[1u8; 32]; 2];
 
         let result = dag.add_transaction(tx);
 
@@ -238,16 +220,12 @@ mod tests {
         let dag = DagProcessor::new(storage.clone());
 
         // Genesis
-        let genesis = create_test_tx([0u8; 32], [0u8; 32], 1);
-        let gen_hash = storage.store_transaction(genesis).unwrap();
+        let (gen_hash, gen2_hash) = store_genesis(&storage);
 
         // Build DAG with 20 transactions
-        let mut all_hashes = vec![gen_hash];
-
-        for i in 2..22 {
-            // Use tip selector to choose parents
-            let selector = TipSelector::new(dag.clone());
+        let mut all_hashes = vec![gen_hash, gen2_hash];
 
+        for i in 3..23 {
             let parents = if all_hashes.len() >= 2 {
                 // Use last two transactions as parents
                 [
@@ -255,10 +233,10 @@ mod tests {
                     all_hashes[all_hashes.len() - 2],
                 ]
             } else {
-                [gen_hash, gen_hash]
+                [gen_hash, gen2_hash]
             };
 
-            let tx = create_test_tx(parents[0], parents[1], i);
+            let tx = funded_tx(parents[0], parents[1], i);
             let tx_hash = dag.add_transaction(tx).unwrap();
             all_hashes.push(tx_hash);
         }
@@ -0,0 +1,117 @@
+// src/amount.rs
+
+//! Output amounts blinded with ECDH-derived masks.
+//!
+//! As in Monero, everything needed to open an output's commitment is
+//! derived from the stealth shared secret the sender and recipient both
+//! know. For output `i` with shared secret `s`:
+//!
+//! - the commitment's blinding factor is H("nyx-amount-mask" || s || i)
+//! - the amount is encrypted by XOR with H("nyx-amount" || s || i)
+//!
+//! so the recipient, scanning with its view key, decrypts the amount and
+//! rebuilds the commitment, and accepts the amount only if the two match.
+
+use crate::pedersen::{self, BlindingFactor, Commitment};
+use curve25519_dalek::scalar::Scalar;
+
+/// Size of an encrypted amount in bytes
+pub const ENCRYPTED_AMOUNT_SIZE: usize = 8;
+
+/// Domain tag for amount masks
+const MASK_TAG: &[u8] = b"nyx-amount-mask";
+
+/// Domain tag for the amount keystream
+const KEYSTREAM_TAG: &[u8] = b"nyx-amount";
+
+/// Derives the blinding factor of output `index`'s commitment
+pub fn amount_mask(shared_secret: &[u8], index: u32) -> BlindingFactor {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(MASK_TAG);
+    hasher.update(shared_secret);
+    hasher.update(&index.to_le_bytes());
+    let mut wide = [0u8; 64];
+    hasher.finalize_xof().fill(&mut wide);
+    Scalar::from_bytes_mod_order_wide(&wide).to_bytes()
+}
+
+/// Commits to the amount of output `index` under its derived mask
+pub fn commit_amount(shared_secret: &[u8], index: u32, amount: u64) -> Commitment {
+    pedersen::commit(amount, &amount_mask(shared_secret, index))
+}
+
+/// Encrypts the amount of output `index` for its recipient
+pub fn encrypt_amount(shared_secret: &[u8], index: u32, amount: u64) -> [u8; ENCRYPTED_AMOUNT_SIZE] {
+    let mut encrypted = amount.to_le_bytes();
+    for (byte, key_byte) in encrypted.iter_mut().zip(keystream(shared_secret, index)) {
+        *byte ^= key_byte;
+    }
+    encrypted
+}
+
+/// Decrypts the amount of output `index`
+///
+/// Any shared secret decrypts to some amount; check it with
+/// [`open_amount`] before trusting it.
+pub fn decrypt_amount(shared_secret: &[u8], index: u32, encrypted: &[u8; ENCRYPTED_AMOUNT_SIZE]) -> u64 {
+    // The cipher is a XOR with the keystream, so encrypting decrypts
+    u64::from_le_bytes(encrypt_amount(shared_secret, index, u64::from_le_bytes(*encrypted)))
+}
+
+/// Decrypts the amount of output `index` and checks it against the
+/// output's commitment
+///
+/// # Returns
+/// `None` if the commitment is not to the decrypted amount under the
+/// output's mask
+pub fn open_amount(
+    shared_secret: &[u8],
+    index: u32,
+    encrypted: &[u8; ENCRYPTED_AMOUNT_SIZE],
+    commitment: &[u8],
+) -> Option<u64> {
+    let amount = decrypt_amount(shared_secret, index, encrypted);
+    (commit_amount(shared_secret, index, amount).to_bytes().as_slice() == commitment).then_some(amount)
+}
+
+/// Keystream for the amount of output `index`
+fn keystream(shared_secret: &[u8], index: u32) -> [u8; ENCRYPTED_AMOUNT_SIZE] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(KEYSTREAM_TAG);
+    hasher.update(shared_secret);
+    hasher.update(&index.to_le_bytes());
+
+    let mut key = [0u8; ENCRYPTED_AMOUNT_SIZE];
+    key.copy_from_slice(&hasher.finalize().as_bytes()[..ENCRYPTED_AMOUNT_SIZE]);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipient_opens_amount() {
+        let secret = [5u8; 32];
+        let commitment = commit_amount(&secret, 1, 2_500).to_bytes();
+        let encrypted = encrypt_amount(&secret, 1, 2_500);
+
+        assert_eq!(open_amount(&secret, 1, &encrypted, &commitment), Some(2_500));
+
+        // Another secret or index decrypts to garbage that fails to open
+        assert_eq!(open_amount(&[6u8; 32], 1, &encrypted, &commitment), None);
+        assert_eq!(open_amount(&secret, 0, &encrypted, &commitment), None);
+    }
+
+    #[test]
+    fn test_masks_hide_equal_amounts() {
+        let first = commit_amount(&[1u8; 32], 0, 100);
+        let second = commit_amount(&[2u8; 32], 0, 100);
+        assert_ne!(first, second);
+
+        // Commitments still balance: masked minus masked is a pure mask
+        let difference = first.sub(&second);
+        let mask = pedersen::sub_blindings(&amount_mask(&[1u8; 32], 0), &amount_mask(&[2u8; 32], 0));
+        assert_eq!(difference, pedersen::commit(0, &mask));
+    }
+}
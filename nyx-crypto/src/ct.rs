@@ -0,0 +1,121 @@
+// src/ct.rs
+
+//! Constant-time comparison and zeroization helpers.
+//!
+//! Comparisons on secret-dependent data (key images, shared secrets,
+//! MACs, derived addresses) must not leak timing information about
+//! where two buffers first differ. All helpers here are built on
+//! `subtle` and run in time dependent only on the input lengths.
+
+use crate::KEY_IMAGE_SIZE;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroize;
+
+/// Compares two byte slices in constant time
+///
+/// Slices of different length compare unequal. The length check itself
+/// is not constant-time, which is fine because lengths are public.
+///
+/// # Arguments
+/// * `a` - First buffer
+/// * `b` - Second buffer
+///
+/// # Returns
+/// true if both buffers have identical contents
+///
+/// # Example
+/// ```
+/// use nyx_crypto::ct::ct_eq;
+///
+/// assert!(ct_eq(b"secret", b"secret"));
+/// assert!(!ct_eq(b"secret", b"secreT"));
+/// ```
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.ct_eq(b).into()
+}
+
+/// Compares two 32-byte values in constant time
+///
+/// Convenience wrapper for hashes, shared secrets and key material.
+pub fn ct_eq_32(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    a.ct_eq(b).into()
+}
+
+/// Compares two key images in constant time
+pub fn key_image_eq(a: &[u8; KEY_IMAGE_SIZE], b: &[u8; KEY_IMAGE_SIZE]) -> bool {
+    a.ct_eq(b).into()
+}
+
+/// Checks whether a buffer is entirely zero in constant time
+///
+/// Useful both for rejecting degenerate values (e.g. all-zero key images)
+/// and for auditing that secret buffers were wiped.
+pub fn ct_is_zero(data: &[u8]) -> bool {
+    let acc = data.iter().fold(0u8, |acc, &b| acc | b);
+    acc.ct_eq(&0u8).into()
+}
+
+/// Zeroizes a buffer in place and reports whether it was fully wiped
+///
+/// Intended for audit checks in tests and debug assertions where code
+/// wants to confirm secret material no longer lingers in memory.
+pub fn wipe(data: &mut [u8]) -> bool {
+    data.zeroize();
+    ct_is_zero(data)
+}
+
+/// Returns true if the buffer has been zeroized
+///
+/// Alias of [`ct_is_zero`] with intent-revealing naming for audit code.
+pub fn is_zeroized(data: &[u8]) -> bool {
+    ct_is_zero(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ct_eq() {
+        assert!(ct_eq(&[1, 2, 3], &[1, 2, 3]));
+        assert!(!ct_eq(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!ct_eq(&[1, 2, 3], &[1, 2]));
+        assert!(ct_eq(&[], &[]));
+    }
+
+    #[test]
+    fn test_ct_eq_32() {
+        let a = [7u8; 32];
+        let mut b = a;
+        assert!(ct_eq_32(&a, &b));
+
+        b[31] ^= 1;
+        assert!(!ct_eq_32(&a, &b));
+    }
+
+    #[test]
+    fn test_key_image_eq() {
+        let ki1 = [1u8; KEY_IMAGE_SIZE];
+        let ki2 = [2u8; KEY_IMAGE_SIZE];
+        assert!(key_image_eq(&ki1, &ki1));
+        assert!(!key_image_eq(&ki1, &ki2));
+    }
+
+    #[test]
+    fn test_ct_is_zero() {
+        assert!(ct_is_zero(&[0u8; 32]));
+        assert!(!ct_is_zero(&[0, 0, 1, 0]));
+        assert!(ct_is_zero(&[]));
+    }
+
+    #[test]
+    fn test_wipe() {
+        let mut secret = vec![0xAAu8; 64];
+        assert!(!is_zeroized(&secret));
+        assert!(wipe(&mut secret));
+        assert!(is_zeroized(&secret));
+    }
+}
@@ -36,6 +36,14 @@ use rand::Rng;
 /// assert!(ciphertext.len() > plaintext.len());
 /// ```
 pub fn encrypt(plaintext: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    encrypt_with_rng(plaintext, key, &mut rand::thread_rng())
+}
+
+/// Encrypts data using AES-256-GCM, drawing the nonce from `rng`
+///
+/// `rng` must not repeat values under the same key; a seeded
+/// [`Entropy`](crate::entropy::Entropy) is only safe in tests.
+pub fn encrypt_with_rng<R: Rng + ?Sized>(plaintext: &[u8], key: &[u8], rng: &mut R) -> Result<Vec<u8>> {
     if key.len() != AES_KEY_SIZE {
         return Err(CryptoError::EncryptionError(
             format!("Invalid key size: expected {}, got {}", AES_KEY_SIZE, key.len())
@@ -47,7 +55,6 @@ pub fn encrypt(plaintext: &[u8], key: &[u8]) -> Result<Vec<u8>> {
         .map_err(|e| CryptoError::EncryptionError(format!("Failed to create cipher: {}", e)))?;
 
     // Generate random nonce
-    let mut rng = rand::thread_rng();
     let nonce_bytes: [u8; AES_NONCE_SIZE] = rng.gen();
     let nonce = Nonce::from_slice(&nonce_bytes);
 
@@ -124,7 +131,11 @@ pub fn decrypt(ciphertext: &[u8], key: &[u8]) -> Result<Vec<u8>> {
 /// assert_eq!(key.len(), 32);
 /// ```
 pub fn generate_key() -> Vec<u8> {
-    let mut rng = rand::thread_rng();
+    generate_key_with_rng(&mut rand::thread_rng())
+}
+
+/// Generates an encryption key drawing randomness from `rng`
+pub fn generate_key_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Vec<u8> {
     let key: [u8; AES_KEY_SIZE] = rng.gen();
     key.to_vec()
 }
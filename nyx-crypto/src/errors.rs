@@ -5,7 +5,8 @@
 //! Provides comprehensive error handling for all cryptographic primitives
 //! in the Nyx protocol.
 
-use std::fmt;
+use alloc::string::String;
+use core::fmt;
 
 /// Main error type for cryptographic operations
 #[derive(Debug, Clone, PartialEq)]
@@ -62,10 +63,11 @@ impl fmt::Display for CryptoError {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for CryptoError {}
 
 /// Result type alias for cryptographic operations
-pub type Result<T> = std::result::Result<T, CryptoError>;
+pub type Result<T> = core::result::Result<T, CryptoError>;
 
 #[cfg(test)]
 mod tests {
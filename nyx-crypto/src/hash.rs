@@ -4,10 +4,14 @@
 //!
 //! Provides BLAKE3 (primary) and Keccak-256 (secondary) hashing.
 //! BLAKE3 is used for general-purpose hashing due to its speed and
-//! quantum resistance. Keccak is provided for Ethereum compatibility.
+//! quantum resistance. Keccak is provided for Ethereum compatibility, and
+//! SHA-256 for hashlocks shared with Bitcoin-like chains.
 
 use crate::errors::{CryptoError, Result};
 use crate::HASH_SIZE;
+use alloc::format;
+use alloc::string::String;
+use sha2::Sha256;
 use sha3::{Digest, Keccak256};
 
 /// Computes BLAKE3 hash of input data
@@ -68,6 +72,24 @@ pub fn keccak_hash(data: &[u8]) -> [u8; HASH_SIZE] {
     out
 }
 
+/// Computes SHA-256 hash of input data
+///
+/// Only for interoperability: atomic swap hashlocks must use the hash the
+/// counterparty chain's scripts can check.
+///
+/// # Example
+/// ```
+/// use nyx_crypto::hash::sha256;
+///
+/// assert_eq!(
+///     hex::encode(sha256(b"abc")),
+///     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+/// );
+/// ```
+pub fn sha256(data: &[u8]) -> [u8; HASH_SIZE] {
+    Sha256::digest(data).into()
+}
+
 /// Converts a hash to hexadecimal string
 ///
 /// # Arguments
@@ -6,9 +6,13 @@
 //! lattice-based signatures. In production, this would be replaced with
 //! actual Dilithium or Falcon implementations.
 
+use crate::ct;
 use crate::errors::{CryptoError, Result};
 use crate::hash::blake3_hash;
 use crate::{PQ_PUBLIC_KEY_SIZE, PQ_PRIVATE_KEY_SIZE, PQ_SIGNATURE_SIZE};
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use alloc::{format, vec};
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
@@ -40,8 +44,8 @@ struct PrivateKey {
     data: Vec<u8>,
 }
 
-impl std::fmt::Debug for PrivateKey {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "PrivateKey([REDACTED])")
     }
 }
@@ -65,6 +69,7 @@ impl KeyPair {
 /// let keypair = generate_keypair();
 /// assert!(keypair.public_key.len() > 0);
 /// ```
+#[cfg(feature = "std")]
 pub fn generate_keypair() -> KeyPair {
     let mut rng = rand::thread_rng();
     generate_keypair_with_rng(&mut rng)
@@ -94,8 +99,10 @@ pub fn generate_keypair_from_seed(seed: &[u8; 32]) -> KeyPair {
     generate_keypair_with_rng(&mut rng)
 }
 
-/// Internal keypair generation with custom RNG
-fn generate_keypair_with_rng<R: Rng>(rng: &mut R) -> KeyPair {
+/// Generates a keypair drawing randomness from `rng`
+///
+/// Pass a seeded [`Entropy`](crate::entropy::Entropy) for reproducible keys.
+pub fn generate_keypair_with_rng<R: Rng + ?Sized>(rng: &mut R) -> KeyPair {
     // Generate mock public key
     let mut public_key = vec![0u8; PQ_PUBLIC_KEY_SIZE];
     rng.fill(&mut public_key[..]);
@@ -118,8 +125,13 @@ fn generate_keypair_with_rng<R: Rng>(rng: &mut R) -> KeyPair {
 ///
 /// # Returns
 /// A tuple containing the private key and public key as byte vectors.
+#[cfg(feature = "std")]
 pub fn generate_keypair_ed25519() -> (Vec<u8>, Vec<u8>) {
-    let mut rng = rand::thread_rng();
+    generate_keypair_ed25519_with_rng(&mut rand::thread_rng())
+}
+
+/// Generates an Ed25519 keypair drawing randomness from `rng`
+pub fn generate_keypair_ed25519_with_rng<R: Rng + ?Sized>(rng: &mut R) -> (Vec<u8>, Vec<u8>) {
     let private: [u8; 32] = rng.gen();
     let scalar = Scalar::from_bytes_mod_order(blake3_hash(&private));
     let public = (&scalar * ED25519_BASEPOINT_TABLE).compress().to_bytes().to_vec();
@@ -212,7 +224,7 @@ pub fn verify(data: &[u8], signature: &[u8], public_key: &[u8]) -> Result<bool>
 
     // Verify data hash matches what's in signature
     let data_hash = blake3_hash(data);
-    if &signature[32..64] != &data_hash[..] {
+    if !ct::ct_eq(&signature[32..64], &data_hash) {
         return Ok(false);
     }
 
@@ -249,6 +261,7 @@ pub fn derive_public_key(private_key: &[u8]) -> Result<Vec<u8>> {
 }
 
 /// Generates an Ed25519 keypair and returns it as a KeyPair struct
+#[cfg(feature = "std")]
 pub fn generate_ed25519_keypair_as_struct() -> KeyPair {
     let (private, public) = generate_keypair_ed25519();
     KeyPair {
@@ -257,6 +270,48 @@ pub fn generate_ed25519_keypair_as_struct() -> KeyPair {
     }
 }
 
+/// Generates an Ed25519 keypair from a deterministic seed, as a KeyPair
+/// struct
+///
+/// Used to restore wallet keys from a recovery phrase.
+pub fn generate_ed25519_keypair_from_seed(seed: &[u8; 32]) -> KeyPair {
+    let (private, public) = generate_keypair_ed25519_with_rng(&mut StdRng::from_seed(*seed));
+    KeyPair {
+        public_key: public,
+        private_key_inner: PrivateKey { data: private },
+    }
+}
+
+/// Derives the Ed25519 public key of a 32-byte signing key seed
+pub fn ed25519_public_key(seed: &[u8; 32]) -> [u8; 32] {
+    ed25519_dalek::SigningKey::from_bytes(seed).verifying_key().to_bytes()
+}
+
+/// Signs data with an Ed25519 signing key seed
+///
+/// Unlike [`sign`], this is a real signature: [`verify_ed25519`] checks it
+/// against the public key.
+pub fn sign_ed25519(data: &[u8], seed: &[u8; 32]) -> [u8; crate::ED25519_SIGNATURE_SIZE] {
+    use ed25519_dalek::Signer;
+    ed25519_dalek::SigningKey::from_bytes(seed).sign(data).to_bytes()
+}
+
+/// Verifies an Ed25519 signature
+///
+/// Malformed keys and signatures fail verification.
+pub fn verify_ed25519(data: &[u8], signature: &[u8], public_key: &[u8]) -> bool {
+    let Ok(public_key) = <[u8; 32]>::try_from(public_key) else {
+        return false;
+    };
+    let Ok(key) = ed25519_dalek::VerifyingKey::from_bytes(&public_key) else {
+        return false;
+    };
+    let Ok(signature) = ed25519_dalek::Signature::from_slice(signature) else {
+        return false;
+    };
+    key.verify_strict(data, &signature).is_ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -278,6 +333,15 @@ mod tests {
         assert_eq!(kp1.private_key(), kp2.private_key());
     }
 
+    #[test]
+    fn test_deterministic_ed25519_keypair() {
+        let kp1 = generate_ed25519_keypair_from_seed(&[7u8; 32]);
+        let kp2 = generate_ed25519_keypair_from_seed(&[7u8; 32]);
+        assert_eq!(kp1.public_key, kp2.public_key);
+        assert_eq!(kp1.private_key(), kp2.private_key());
+        assert_ne!(kp1.public_key, generate_ed25519_keypair_from_seed(&[8u8; 32]).public_key);
+    }
+
     #[test]
     fn test_different_seeds_different_keys() {
         let kp1 = generate_keypair_from_seed(&[1u8; 32]);
@@ -367,4 +431,16 @@ mod tests {
         // but the type system ensures it via ZeroizeOnDrop)
         assert_eq!(private_copy.len(), PQ_PRIVATE_KEY_SIZE);
     }
+
+    #[test]
+    fn test_ed25519_sign_verify() {
+        let seed = [3u8; 32];
+        let public_key = ed25519_public_key(&seed);
+        let signature = sign_ed25519(b"hello", &seed);
+
+        assert!(verify_ed25519(b"hello", &signature, &public_key));
+        assert!(!verify_ed25519(b"hellO", &signature, &public_key));
+        assert!(!verify_ed25519(b"hello", &signature, &ed25519_public_key(&[4u8; 32])));
+        assert!(!verify_ed25519(b"hello", &signature[..63], &public_key));
+    }
 }
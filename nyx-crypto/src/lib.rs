@@ -12,6 +12,14 @@
 //! - **Ring Signatures**: Lattice-based linkable ring signatures for privacy
 //! - **Stealth Addresses**: Monero-style ECDH for unlinkability
 //! - **Encryption**: AES-256-GCM for confidential data
+//! - **Commitments**: Pedersen commitments for confidential amounts
+//! - **Range Proofs**: Borromean proofs that committed amounts fit in 64 bits
+//! - **Amount Blinding**: Commitment masks and amount encryption derived from ECDH
+//! - **Merkle Trees**: Inclusion proofs for light clients
+//! - **Multi-Signatures**: Aggregated validator signatures with per-signer verification
+//! - **Secret Sharing**: Shamir splitting of keys into threshold shares
+//! - **Constant-Time Helpers**: Timing-safe comparisons and zeroization checks
+//! - **Entropy**: Injectable randomness, seedable for reproducible tests
 //!
 //! ## Security Properties
 //!
@@ -20,6 +28,23 @@
 //! - **Unlinkability**: Stealth addresses prevent transaction graph analysis
 //! - **Confidentiality**: Symmetric encryption protects sensitive data
 //!
+//! ## `no_std` Support
+//!
+//! With the default `std` feature disabled the crate builds as `no_std` with
+//! `alloc`, for embedded validators and browser light clients that verify
+//! transactions without the full node stack. The stateless core stays
+//! available: [`hash`], [`ct`], [`keys`] (signing and verification),
+//! [`ring`] (key images and ring signature verification), [`pedersen`]
+//! (commitments and balance checks), [`range`] (proof verification),
+//! [`amount`], [`merkle`] and [`multisig`]. Helpers
+//! that draw from the thread-local RNG, and the `stealth`, `encryption`,
+//! `shamir` and `entropy` modules, require `std`; their `*_with_rng`
+//! variants work anywhere a caller can supply an RNG.
+//!
+//! ```toml
+//! nyx-crypto = { version = "0.1", default-features = false }
+//! ```
+//!
 //! ## Example Usage
 //!
 //! ```rust
@@ -46,21 +71,38 @@
 //! ).unwrap();
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 #![deny(unsafe_code)]
 
+extern crate alloc;
+
 pub mod errors;
+pub mod ct;
 pub mod hash;
 pub mod keys;
 pub mod ring;
+#[cfg(feature = "std")]
 pub mod stealth;
+#[cfg(feature = "std")]
 pub mod encryption;
+pub mod pedersen;
+pub mod range;
+pub mod amount;
+pub mod merkle;
+pub mod multisig;
+#[cfg(feature = "std")]
+pub mod shamir;
+#[cfg(feature = "std")]
+pub mod entropy;
 
 // Re-export commonly used types
 pub use crate::errors::{CryptoError, Result};
 pub use crate::keys::KeyPair;
 pub use crate::ring::RingSignature;
+#[cfg(feature = "std")]
+pub use crate::entropy::Entropy;
 
 /// Standard hash output size (32 bytes / 256 bits)
 pub const HASH_SIZE: usize = 32;
@@ -90,6 +132,9 @@ pub const AES_NONCE_SIZE: usize = 12;
 /// Stealth address size (Ed25519 point: 32 bytes)
 pub const STEALTH_ADDRESS_SIZE: usize = 32;
 
+/// Ed25519 signature size
+pub const ED25519_SIGNATURE_SIZE: usize = 64;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,186 @@
+// src/merkle.rs
+
+//! Binary Merkle trees over 32-byte leaves.
+//!
+//! Used to commit to sets of transactions (and later state) in snapshot
+//! headers so light clients can verify membership without the full DAG.
+//!
+//! Construction:
+//! - Leaves are hashed as `BLAKE3(0x00 || leaf)`, inner nodes as
+//!   `BLAKE3(0x01 || left || right)`, so a leaf can never be passed off as
+//!   an inner node
+//! - A node without a sibling is promoted to the next level unchanged
+//!   (rather than duplicated), so distinct leaf lists never share a root
+//! - The root of an empty tree is all zeros
+
+use crate::hash::hash_chunks;
+use crate::HASH_SIZE;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Merkle node / root hash
+pub type MerkleHash = [u8; HASH_SIZE];
+
+/// Root of a tree with no leaves
+pub const EMPTY_ROOT: MerkleHash = [0u8; HASH_SIZE];
+
+const LEAF_PREFIX: &[u8] = &[0x00];
+const NODE_PREFIX: &[u8] = &[0x01];
+
+/// One step from a node towards the root
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofStep {
+    /// Hash of the sibling node
+    pub sibling: MerkleHash,
+
+    /// True if the sibling is the left-hand child
+    pub sibling_is_left: bool,
+}
+
+/// Proof that a leaf is included under a Merkle root
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct MerkleProof {
+    /// Steps from the leaf up to the root
+    pub steps: Vec<ProofStep>,
+}
+
+impl MerkleProof {
+    /// Computes the root implied by this proof for `leaf`
+    pub fn compute_root(&self, leaf: &MerkleHash) -> MerkleHash {
+        self.steps.iter().fold(hash_leaf(leaf), |node, step| {
+            if step.sibling_is_left {
+                hash_node(&step.sibling, &node)
+            } else {
+                hash_node(&node, &step.sibling)
+            }
+        })
+    }
+
+    /// Verifies that `leaf` is included under `root`
+    pub fn verify(&self, leaf: &MerkleHash, root: &MerkleHash) -> bool {
+        crate::ct::ct_eq_32(&self.compute_root(leaf), root)
+    }
+}
+
+/// Hashes a leaf value
+pub fn hash_leaf(leaf: &MerkleHash) -> MerkleHash {
+    hash_chunks(&[LEAF_PREFIX, leaf])
+}
+
+/// Hashes two child nodes into their parent
+pub fn hash_node(left: &MerkleHash, right: &MerkleHash) -> MerkleHash {
+    hash_chunks(&[NODE_PREFIX, left, right])
+}
+
+/// Computes the next level up from `level`
+fn next_level(level: &[MerkleHash]) -> Vec<MerkleHash> {
+    level.chunks(2)
+        .map(|pair| match pair {
+            [left, right] => hash_node(left, right),
+            [single] => *single,
+            _ => unreachable!("chunks(2) yields one or two items"),
+        })
+        .collect()
+}
+
+/// Computes the Merkle root of a list of leaves
+///
+/// # Example
+/// ```
+/// use nyx_crypto::merkle::{merkle_root, prove, EMPTY_ROOT};
+///
+/// let leaves = [[1u8; 32], [2u8; 32], [3u8; 32]];
+/// let root = merkle_root(&leaves);
+///
+/// let proof = prove(&leaves, 2).unwrap();
+/// assert!(proof.verify(&leaves[2], &root));
+/// assert_eq!(merkle_root(&[]), EMPTY_ROOT);
+/// ```
+pub fn merkle_root(leaves: &[MerkleHash]) -> MerkleHash {
+    if leaves.is_empty() {
+        return EMPTY_ROOT;
+    }
+
+    let mut level: Vec<MerkleHash> = leaves.iter().map(hash_leaf).collect();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Builds an inclusion proof for the leaf at `index`
+///
+/// Returns `None` if `index` is out of range.
+pub fn prove(leaves: &[MerkleHash], index: usize) -> Option<MerkleProof> {
+    if index >= leaves.len() {
+        return None;
+    }
+
+    let mut steps = Vec::new();
+    let mut level: Vec<MerkleHash> = leaves.iter().map(hash_leaf).collect();
+    let mut position = index;
+
+    while level.len() > 1 {
+        let sibling = position ^ 1;
+        if sibling < level.len() {
+            steps.push(ProofStep {
+                sibling: level[sibling],
+                sibling_is_left: sibling < position,
+            });
+        }
+
+        level = next_level(&level);
+        position /= 2;
+    }
+
+    Some(MerkleProof { steps })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(n: u8) -> Vec<MerkleHash> {
+        (0..n).map(|i| [i; 32]).collect()
+    }
+
+    #[test]
+    fn test_every_leaf_proves() {
+        for n in 1..=9 {
+            let leaves = leaves(n);
+            let root = merkle_root(&leaves);
+
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = prove(&leaves, i).unwrap();
+                assert!(proof.verify(leaf, &root), "leaf {} of {}", i, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wrong_leaf_or_root_rejected() {
+        let leaves = leaves(5);
+        let root = merkle_root(&leaves);
+        let proof = prove(&leaves, 1).unwrap();
+
+        assert!(!proof.verify(&leaves[2], &root));
+        assert!(!proof.verify(&leaves[1], &merkle_root(&leaves[..4])));
+    }
+
+    #[test]
+    fn test_single_leaf_and_empty() {
+        let leaf = [7u8; 32];
+        assert_eq!(merkle_root(&[leaf]), hash_leaf(&leaf));
+        assert!(prove(&[leaf], 0).unwrap().steps.is_empty());
+        assert!(prove(&[], 0).is_none());
+        assert_eq!(merkle_root(&[]), EMPTY_ROOT);
+    }
+
+    #[test]
+    fn test_no_duplicate_last_leaf_collision() {
+        let a = leaves(3);
+        let mut b = a.clone();
+        b.push(a[2]);
+        assert_ne!(merkle_root(&a), merkle_root(&b));
+    }
+}
@@ -0,0 +1,205 @@
+// src/multisig.rs
+
+//! Aggregated multi-signatures over a single message.
+//!
+//! Aggregation is a plain list of individual Ed25519 signatures, each
+//! tagged with the signer's index in an externally agreed key list, so
+//! every contribution is bound to its signer's key. A compact aggregate
+//! can replace the list later without changing callers.
+
+use crate::errors::{CryptoError, Result};
+use crate::keys;
+use alloc::format;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// A single signer's contribution to an aggregate
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerSignature {
+    /// Index of the signer in the public key list
+    pub signer: u32,
+
+    /// Signature over the message
+    pub signature: Vec<u8>,
+}
+
+/// Signatures from several signers over the same message
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct AggregateSignature {
+    /// Contributions, sorted by signer index
+    pub signatures: Vec<SignerSignature>,
+}
+
+impl AggregateSignature {
+    /// Creates an empty aggregate
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signs `message` with an Ed25519 key seed and adds the signature for
+    /// `signer`
+    ///
+    /// # Errors
+    /// - `InvalidInput` if `signer` already contributed
+    pub fn sign(&mut self, message: &[u8], signer: u32, seed: &[u8; 32]) -> Result<()> {
+        let signature = keys::sign_ed25519(message, seed).to_vec();
+        self.add(SignerSignature { signer, signature })
+    }
+
+    /// Adds an existing signature
+    ///
+    /// # Errors
+    /// - `InvalidInput` if the signer already contributed
+    pub fn add(&mut self, contribution: SignerSignature) -> Result<()> {
+        match self.signatures.binary_search_by_key(&contribution.signer, |s| s.signer) {
+            Ok(_) => Err(CryptoError::InvalidInput(format!(
+                "Duplicate signature from signer {}",
+                contribution.signer
+            ))),
+            Err(pos) => {
+                self.signatures.insert(pos, contribution);
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the number of contributions
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    /// Checks whether the aggregate has no contributions
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// Indices of the contributing signers, ascending
+    pub fn signers(&self) -> impl Iterator<Item = u32> + '_ {
+        self.signatures.iter().map(|s| s.signer)
+    }
+}
+
+/// Verifies every contribution of an aggregate
+///
+/// # Arguments
+/// * `message` - Message all signers signed
+/// * `aggregate` - Signatures to check
+/// * `public_keys` - Signer Ed25519 public keys, indexed by
+///   `SignerSignature::signer`
+///
+/// # Returns
+/// Indices of the signers, ascending and distinct
+///
+/// # Errors
+/// - `InvalidInput` if a signer index is unknown or repeated
+/// - `SignatureError` if any signature is invalid
+///
+/// # Example
+/// ```
+/// use nyx_crypto::keys::ed25519_public_key;
+/// use nyx_crypto::multisig::{verify_aggregate, AggregateSignature};
+///
+/// let seeds = [[1u8; 32], [2u8; 32]];
+/// let public_keys: Vec<Vec<u8>> = seeds.iter().map(|s| ed25519_public_key(s).to_vec()).collect();
+///
+/// let mut aggregate = AggregateSignature::new();
+/// aggregate.sign(b"snapshot", 1, &seeds[1]).unwrap();
+///
+/// assert_eq!(verify_aggregate(b"snapshot", &aggregate, &public_keys).unwrap(), vec![1]);
+/// ```
+pub fn verify_aggregate(
+    message: &[u8],
+    aggregate: &AggregateSignature,
+    public_keys: &[Vec<u8>],
+) -> Result<Vec<u32>> {
+    let mut signers = Vec::with_capacity(aggregate.len());
+
+    for contribution in &aggregate.signatures {
+        if signers.last().is_some_and(|&last| last >= contribution.signer) {
+            return Err(CryptoError::InvalidInput(format!(
+                "Signer {} repeated or out of order",
+                contribution.signer
+            )));
+        }
+
+        let public_key = public_keys.get(contribution.signer as usize)
+            .ok_or_else(|| CryptoError::InvalidInput(format!(
+                "Unknown signer {}",
+                contribution.signer
+            )))?;
+
+        if !keys::verify_ed25519(message, &contribution.signature, public_key) {
+            return Err(CryptoError::SignatureError(format!(
+                "Invalid signature from signer {}",
+                contribution.signer
+            )));
+        }
+
+        signers.push(contribution.signer);
+    }
+
+    Ok(signers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::blake3_hash;
+
+    fn signer_keys(n: u8) -> (Vec<[u8; 32]>, Vec<Vec<u8>>) {
+        let seeds: Vec<[u8; 32]> = (0..n).map(|i| [i; 32]).collect();
+        let public_keys = seeds.iter().map(|seed| keys::ed25519_public_key(seed).to_vec()).collect();
+        (seeds, public_keys)
+    }
+
+    #[test]
+    fn test_aggregate_roundtrip() {
+        let (keys, public_keys) = signer_keys(4);
+        let mut aggregate = AggregateSignature::new();
+
+        for i in [3, 0, 2] {
+            aggregate.sign(b"msg", i, &keys[i as usize]).unwrap();
+        }
+
+        assert_eq!(aggregate.signers().collect::<Vec<_>>(), vec![0, 2, 3]);
+        assert_eq!(verify_aggregate(b"msg", &aggregate, &public_keys).unwrap(), vec![0, 2, 3]);
+        assert!(matches!(
+            verify_aggregate(b"other", &aggregate, &public_keys),
+            Err(CryptoError::SignatureError(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_duplicates_and_unknown_signers() {
+        let (keys, public_keys) = signer_keys(2);
+        let mut aggregate = AggregateSignature::new();
+        aggregate.sign(b"msg", 0, &keys[0]).unwrap();
+
+        assert!(aggregate.sign(b"msg", 0, &keys[0]).is_err());
+
+        // Hand-crafted duplicate bypassing `add`
+        let mut forged = aggregate.clone();
+        forged.signatures.push(forged.signatures[0].clone());
+        assert!(matches!(verify_aggregate(b"msg", &forged, &public_keys), Err(CryptoError::InvalidInput(_))));
+
+        aggregate.sign(b"msg", 5, &keys[1]).unwrap();
+        assert!(matches!(verify_aggregate(b"msg", &aggregate, &public_keys), Err(CryptoError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_rejects_signatures_without_the_signer_key() {
+        let (keys, public_keys) = signer_keys(2);
+
+        // Signed by another key
+        let mut aggregate = AggregateSignature::new();
+        aggregate.sign(b"msg", 0, &keys[1]).unwrap();
+        assert!(matches!(verify_aggregate(b"msg", &aggregate, &public_keys), Err(CryptoError::SignatureError(_))));
+
+        // Keyless signature committing to the message hash only
+        let mut forged = vec![0u8; 64];
+        forged[32..].copy_from_slice(&blake3_hash(b"msg"));
+        let mut aggregate = AggregateSignature::new();
+        aggregate.add(SignerSignature { signer: 0, signature: forged }).unwrap();
+        assert!(matches!(verify_aggregate(b"msg", &aggregate, &public_keys), Err(CryptoError::SignatureError(_))));
+    }
+}
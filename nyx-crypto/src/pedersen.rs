@@ -0,0 +1,220 @@
+// src/pedersen.rs
+
+//! Pedersen commitments for confidential amounts.
+//!
+//! A commitment to value `v` with blinding factor `r` is:
+//!
+//! C = vG + rH
+//!
+//! where G is the Ristretto base point and H is a second generator with no
+//! known discrete log relative to G (derived by hashing a domain tag to the
+//! curve). Commitments are additively homomorphic, which lets consensus check
+//! that a transaction's inputs and outputs balance without learning amounts.
+
+use crate::errors::{CryptoError, Result};
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT,
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+    traits::Identity,
+};
+use alloc::format;
+use alloc::string::ToString;
+use rand::Rng;
+
+/// Size of a compressed commitment (32 bytes)
+pub const COMMITMENT_SIZE: usize = 32;
+
+/// Size of a blinding factor (32-byte scalar)
+pub const BLINDING_SIZE: usize = 32;
+
+/// Domain tag for deriving the H generator
+const H_GENERATOR_TAG: &[u8] = b"nyx-pedersen-H-generator-v1";
+
+/// Blinding factor (scalar) for a commitment
+pub type BlindingFactor = [u8; BLINDING_SIZE];
+
+/// Pedersen commitment to a hidden amount
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Commitment(pub(crate) RistrettoPoint);
+
+impl Commitment {
+    /// Returns the compressed 32-byte encoding
+    pub fn to_bytes(&self) -> [u8; COMMITMENT_SIZE] {
+        self.0.compress().to_bytes()
+    }
+
+    /// Parses a compressed commitment
+    ///
+    /// # Errors
+    /// Returns `InvalidInput` if the bytes are the wrong length or not a
+    /// valid Ristretto point.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let compressed = CompressedRistretto::from_slice(bytes)
+            .map_err(|_| CryptoError::InvalidInput(format!(
+                "Commitment must be {} bytes, got {}", COMMITMENT_SIZE, bytes.len()
+            )))?;
+
+        compressed.decompress()
+            .map(Commitment)
+            .ok_or_else(|| CryptoError::InvalidInput("Invalid commitment point".to_string()))
+    }
+
+    /// The identity commitment (commits to 0 with blinding 0)
+    pub fn identity() -> Self {
+        Commitment(RistrettoPoint::identity())
+    }
+
+    /// Homomorphically adds two commitments
+    pub fn add(&self, other: &Commitment) -> Commitment {
+        Commitment(self.0 + other.0)
+    }
+
+    /// Homomorphically subtracts a commitment
+    pub fn sub(&self, other: &Commitment) -> Commitment {
+        Commitment(self.0 - other.0)
+    }
+}
+
+/// Returns the second generator H
+pub(crate) fn h_generator() -> RistrettoPoint {
+    let mut wide = [0u8; 64];
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(H_GENERATOR_TAG);
+    hasher.finalize_xof().fill(&mut wide);
+    RistrettoPoint::from_uniform_bytes(&wide)
+}
+
+fn blinding_scalar(blinding: &BlindingFactor) -> Scalar {
+    Scalar::from_bytes_mod_order(*blinding)
+}
+
+/// Commits to a value with the given blinding factor
+///
+/// # Example
+/// ```
+/// use nyx_crypto::pedersen::{commit, random_blinding};
+///
+/// let r = random_blinding();
+/// let c1 = commit(100, &r);
+/// let c2 = commit(100, &r);
+/// assert_eq!(c1, c2);
+/// ```
+pub fn commit(value: u64, blinding: &BlindingFactor) -> Commitment {
+    let v = Scalar::from(value);
+    let r = blinding_scalar(blinding);
+    Commitment(v * RISTRETTO_BASEPOINT_POINT + r * h_generator())
+}
+
+/// Commits to a public value with zero blinding (used for fees)
+pub fn commit_public(value: u64) -> Commitment {
+    Commitment(Scalar::from(value) * RISTRETTO_BASEPOINT_POINT)
+}
+
+/// Generates a random blinding factor
+#[cfg(feature = "std")]
+pub fn random_blinding() -> BlindingFactor {
+    random_blinding_with_rng(&mut rand::thread_rng())
+}
+
+/// Generates a blinding factor drawing randomness from `rng`
+pub fn random_blinding_with_rng<R: Rng + ?Sized>(rng: &mut R) -> BlindingFactor {
+    let mut wide = [0u8; 64];
+    rng.fill(&mut wide[..]);
+    Scalar::from_bytes_mod_order_wide(&wide).to_bytes()
+}
+
+/// Derives a blinding factor deterministically from arbitrary bytes
+pub fn blinding_from_bytes(data: &[u8]) -> BlindingFactor {
+    let mut wide = [0u8; 64];
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"nyx-pedersen-blinding-v1");
+    hasher.update(data);
+    hasher.finalize_xof().fill(&mut wide);
+    Scalar::from_bytes_mod_order_wide(&wide).to_bytes()
+}
+
+/// Sums blinding factors modulo the group order
+pub fn sum_blindings(blindings: &[BlindingFactor]) -> BlindingFactor {
+    blindings.iter()
+        .map(blinding_scalar)
+        .fold(Scalar::ZERO, |acc, s| acc + s)
+        .to_bytes()
+}
+
+/// Subtracts blinding factor `b` from `a` modulo the group order
+///
+/// Used to pick the last output's blinding so a transaction balances.
+pub fn sub_blindings(a: &BlindingFactor, b: &BlindingFactor) -> BlindingFactor {
+    (blinding_scalar(a) - blinding_scalar(b)).to_bytes()
+}
+
+/// Sums a list of commitments
+pub fn sum_commitments(commitments: &[Commitment]) -> Commitment {
+    commitments.iter().fold(Commitment::identity(), |acc, c| acc.add(c))
+}
+
+/// Checks that inputs balance outputs plus a public fee
+///
+/// Verifies Σ inputs == Σ outputs + fee·G. This holds exactly when the
+/// committed values balance and the blinding factors cancel out.
+pub fn verify_balance(inputs: &[Commitment], outputs: &[Commitment], fee: u64) -> bool {
+    let lhs = sum_commitments(inputs);
+    let rhs = sum_commitments(outputs).add(&commit_public(fee));
+    crate::ct::ct_eq(&lhs.to_bytes(), &rhs.to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commitment_roundtrip() {
+        let c = commit(42, &random_blinding());
+        let bytes = c.to_bytes();
+        assert_eq!(Commitment::from_bytes(&bytes).unwrap(), c);
+    }
+
+    #[test]
+    fn test_invalid_commitment_bytes() {
+        assert!(Commitment::from_bytes(&[1u8; 16]).is_err());
+        assert!(Commitment::from_bytes(&[0xFFu8; 32]).is_err());
+    }
+
+    #[test]
+    fn test_hiding() {
+        let c1 = commit(100, &random_blinding());
+        let c2 = commit(100, &random_blinding());
+        assert_ne!(c1, c2);
+    }
+
+    #[test]
+    fn test_homomorphic_addition() {
+        let r1 = random_blinding();
+        let r2 = random_blinding();
+
+        let sum = commit(30, &r1).add(&commit(12, &r2));
+        let expected = commit(42, &sum_blindings(&[r1, r2]));
+        assert_eq!(sum, expected);
+    }
+
+    #[test]
+    fn test_verify_balance() {
+        let r_in = random_blinding();
+        let r_out1 = random_blinding();
+        let r_out2 = sub_blindings(&r_in, &r_out1);
+
+        let inputs = [commit(100, &r_in)];
+        let outputs = [commit(60, &r_out1), commit(35, &r_out2)];
+
+        assert!(verify_balance(&inputs, &outputs, 5));
+        assert!(!verify_balance(&inputs, &outputs, 0));
+        assert!(!verify_balance(&inputs, &outputs[..1], 5));
+    }
+
+    #[test]
+    fn test_blinding_from_bytes_deterministic() {
+        assert_eq!(blinding_from_bytes(b"seed"), blinding_from_bytes(b"seed"));
+        assert_ne!(blinding_from_bytes(b"seed"), blinding_from_bytes(b"other"));
+    }
+}
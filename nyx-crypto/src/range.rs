@@ -0,0 +1,242 @@
+// src/range.rs
+
+//! Range proofs for Pedersen-committed amounts.
+//!
+//! Commitments balance modulo the group order, so without a range proof an
+//! output could commit to a "negative" amount and inflate the others. A
+//! proof shows its commitment `C = vG + rH` holds some `v < 2^64` without
+//! revealing it, by splitting `C` into one commitment per bit:
+//!
+//! C_i = b_i·2^i·G + r_i·H,  Σ C_i = C
+//!
+//! and proving with a Borromean ring signature (Maxwell and Poelstra, as in
+//! early Monero) that each `C_i` is `r_i·H` or `2^i·G + r_i·H`, i.e. that
+//! the signer knows the discrete log over H of `C_i` or of `C_i - 2^i·G`.
+//!
+//! A proof is the first 63 bit commitments (the last is `C` minus their
+//! sum), the shared challenge and two responses per bit, [`RANGE_PROOF_SIZE`]
+//! bytes in all.
+
+use crate::pedersen::{self, BlindingFactor, Commitment};
+use alloc::vec::Vec;
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT,
+    ristretto::{CompressedRistretto, RistrettoPoint},
+    scalar::Scalar,
+    traits::Identity,
+};
+use rand::Rng;
+
+/// Number of bits a proven amount fits in
+pub const RANGE_BITS: usize = 64;
+
+/// Size of a range proof in bytes
+pub const RANGE_PROOF_SIZE: usize = (RANGE_BITS - 1) * 32 + 32 + RANGE_BITS * 64;
+
+/// Domain tag for range proof challenges
+const RANGE_PROOF_TAG: &[u8] = b"nyx-range-proof-v1";
+
+/// Proves that `commit(value, blinding)` commits to a 64-bit amount
+#[cfg(feature = "std")]
+pub fn prove(value: u64, blinding: &BlindingFactor) -> Vec<u8> {
+    prove_with_rng(value, blinding, &mut rand::thread_rng())
+}
+
+/// Proves that `commit(value, blinding)` commits to a 64-bit amount,
+/// drawing bit masks and nonces from `rng`
+pub fn prove_with_rng<R: Rng + ?Sized>(value: u64, blinding: &BlindingFactor, rng: &mut R) -> Vec<u8> {
+    let h = pedersen::h_generator();
+    let powers = bit_generators();
+    let bits: Vec<bool> = (0..RANGE_BITS).map(|i| value >> i & 1 == 1).collect();
+
+    // Split the blinding factor into one mask per bit
+    let mut masks: Vec<Scalar> = (1..RANGE_BITS).map(|_| random_scalar(rng)).collect();
+    let rest = masks.iter().fold(Scalar::from_bytes_mod_order(*blinding), |acc, mask| acc - mask);
+    masks.push(rest);
+    let commitments: Vec<RistrettoPoint> = (0..RANGE_BITS)
+        .map(|i| if bits[i] { powers[i] } else { RistrettoPoint::identity() } + masks[i] * h)
+        .collect();
+    let message = transcript(&pedersen::commit(value, blinding), &commitments);
+
+    // Walk each ring from the known member to its end
+    let nonces: Vec<Scalar> = (0..RANGE_BITS).map(|_| random_scalar(rng)).collect();
+    let mut responses = [[Scalar::ZERO; 2]; RANGE_BITS];
+    let mut ends = Vec::with_capacity(RANGE_BITS);
+    for i in 0..RANGE_BITS {
+        if bits[i] {
+            ends.push(nonces[i] * h);
+        } else {
+            let challenge = bit_challenge(&message, i, &(nonces[i] * h));
+            responses[i][1] = random_scalar(rng);
+            ends.push(responses[i][1] * h - challenge * (commitments[i] - powers[i]));
+        }
+    }
+
+    // Close every ring through the shared challenge
+    let shared = shared_challenge(&message, &ends);
+    for i in 0..RANGE_BITS {
+        if bits[i] {
+            responses[i][0] = random_scalar(rng);
+            let start = responses[i][0] * h - shared * commitments[i];
+            let challenge = bit_challenge(&message, i, &start);
+            responses[i][1] = nonces[i] + challenge * masks[i];
+        } else {
+            responses[i][0] = nonces[i] + shared * masks[i];
+        }
+    }
+
+    let mut proof = Vec::with_capacity(RANGE_PROOF_SIZE);
+    for commitment in &commitments[..RANGE_BITS - 1] {
+        proof.extend_from_slice(commitment.compress().as_bytes());
+    }
+    proof.extend_from_slice(shared.as_bytes());
+    for [first, second] in &responses {
+        proof.extend_from_slice(first.as_bytes());
+        proof.extend_from_slice(second.as_bytes());
+    }
+    proof
+}
+
+/// Checks that `proof` shows `commitment` holds a 64-bit amount
+pub fn verify(commitment: &Commitment, proof: &[u8]) -> bool {
+    if proof.len() != RANGE_PROOF_SIZE {
+        return false;
+    }
+    let (points, rest) = proof.split_at((RANGE_BITS - 1) * 32);
+    let (shared, responses) = rest.split_at(32);
+
+    let mut commitments = Vec::with_capacity(RANGE_BITS);
+    for bytes in points.chunks_exact(32) {
+        match CompressedRistretto::from_slice(bytes).ok().and_then(|point| point.decompress()) {
+            Some(point) => commitments.push(point),
+            None => return false,
+        }
+    }
+    let last = commitments.iter().fold(commitment.0, |acc, point| acc - point);
+    commitments.push(last);
+    let Some(shared) = canonical_scalar(shared) else {
+        return false;
+    };
+    let Some(responses) = responses.chunks_exact(32).map(canonical_scalar).collect::<Option<Vec<_>>>() else {
+        return false;
+    };
+
+    let h = pedersen::h_generator();
+    let powers = bit_generators();
+    let message = transcript(commitment, &commitments);
+    let ends: Vec<RistrettoPoint> = (0..RANGE_BITS)
+        .map(|i| {
+            let start = responses[2 * i] * h - shared * commitments[i];
+            let challenge = bit_challenge(&message, i, &start);
+            responses[2 * i + 1] * h - challenge * (commitments[i] - powers[i])
+        })
+        .collect();
+
+    crate::ct::ct_eq(shared_challenge(&message, &ends).as_bytes(), shared.as_bytes())
+}
+
+/// 2^i·G for every bit `i`
+fn bit_generators() -> Vec<RistrettoPoint> {
+    let mut powers = Vec::with_capacity(RANGE_BITS);
+    let mut power = RISTRETTO_BASEPOINT_POINT;
+    for _ in 0..RANGE_BITS {
+        powers.push(power);
+        power += power;
+    }
+    powers
+}
+
+/// Hash binding a proof to its commitment and bit commitments
+fn transcript(commitment: &Commitment, commitments: &[RistrettoPoint]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(RANGE_PROOF_TAG);
+    hasher.update(&commitment.to_bytes());
+    for point in commitments {
+        hasher.update(point.compress().as_bytes());
+    }
+    *hasher.finalize().as_bytes()
+}
+
+/// Challenge of the second member of bit `i`'s ring
+fn bit_challenge(message: &[u8; 32], i: usize, point: &RistrettoPoint) -> Scalar {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(RANGE_PROOF_TAG);
+    hasher.update(message);
+    hasher.update(&(i as u32).to_le_bytes());
+    hasher.update(point.compress().as_bytes());
+    wide_scalar(hasher)
+}
+
+/// Challenge every ring starts from
+fn shared_challenge(message: &[u8; 32], ends: &[RistrettoPoint]) -> Scalar {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(RANGE_PROOF_TAG);
+    hasher.update(message);
+    for point in ends {
+        hasher.update(point.compress().as_bytes());
+    }
+    wide_scalar(hasher)
+}
+
+fn wide_scalar(hasher: blake3::Hasher) -> Scalar {
+    let mut wide = [0u8; 64];
+    hasher.finalize_xof().fill(&mut wide);
+    Scalar::from_bytes_mod_order_wide(&wide)
+}
+
+fn random_scalar<R: Rng + ?Sized>(rng: &mut R) -> Scalar {
+    Scalar::from_bytes_mod_order(pedersen::random_blinding_with_rng(rng))
+}
+
+fn canonical_scalar(bytes: &[u8]) -> Option<Scalar> {
+    let bytes: [u8; 32] = bytes.try_into().ok()?;
+    Option::from(Scalar::from_canonical_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pedersen::{commit, random_blinding, sub_blindings};
+
+    #[test]
+    fn test_proof_verifies() {
+        for value in [0, 1, 1_000, u64::MAX] {
+            let blinding = random_blinding();
+            let proof = prove(value, &blinding);
+            assert_eq!(proof.len(), RANGE_PROOF_SIZE);
+            assert!(verify(&commit(value, &blinding), &proof));
+        }
+    }
+
+    #[test]
+    fn test_proof_is_bound_to_commitment() {
+        let blinding = random_blinding();
+        let proof = prove(1_000, &blinding);
+        assert!(!verify(&commit(1_001, &blinding), &proof));
+        assert!(!verify(&commit(1_000, &random_blinding()), &proof));
+    }
+
+    #[test]
+    fn test_tampered_proof_rejected() {
+        let blinding = random_blinding();
+        let commitment = commit(42, &blinding);
+        let proof = prove(42, &blinding);
+
+        for position in [0, (RANGE_BITS - 1) * 32, RANGE_PROOF_SIZE - 1] {
+            let mut tampered = proof.clone();
+            tampered[position] ^= 1;
+            assert!(!verify(&commitment, &tampered));
+        }
+        assert!(!verify(&commitment, &proof[1..]));
+        assert!(!verify(&commitment, &[]));
+    }
+
+    #[test]
+    fn test_negative_amount_cannot_be_proven() {
+        // A commitment to -1 is G short of the identity; proving it as the
+        // largest amount it would wrap to fails
+        let blinding = random_blinding();
+        let negative = Commitment::identity().sub(&commit(1, &sub_blindings(&[0u8; 32], &blinding)));
+        assert!(!verify(&negative, &prove(u64::MAX, &blinding)));
+    }
+}
@@ -6,9 +6,13 @@
 //! is hidden among a set of decoys. Includes key images to prevent
 //! double-spending without revealing the true signer.
 
+use crate::ct;
 use crate::errors::{CryptoError, Result};
 use crate::hash::{blake3_hash, hash_chunks};
 use crate::{RING_SIZE, KEY_IMAGE_SIZE};
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
@@ -109,11 +113,23 @@ pub fn generate_key_image(private_key: &[u8]) -> [u8; KEY_IMAGE_SIZE] {
 ///
 /// assert_eq!(sig.ring_size(), 3);
 /// ```
+#[cfg(feature = "std")]
 pub fn generate_ring_signature(
     message: &[u8],
     private_key: &[u8],
     public_key: &[u8],
     ring_members: &[Vec<u8>],
+) -> Result<RingSignature> {
+    generate_ring_signature_with_rng(message, private_key, public_key, ring_members, &mut rand::thread_rng())
+}
+
+/// Generates a ring signature, drawing decoy responses from `rng`
+pub fn generate_ring_signature_with_rng<R: Rng + ?Sized>(
+    message: &[u8],
+    private_key: &[u8],
+    public_key: &[u8],
+    ring_members: &[Vec<u8>],
+    rng: &mut R,
 ) -> Result<RingSignature> {
     // Validate inputs
     if ring_members.is_empty() {
@@ -154,7 +170,6 @@ pub fn generate_ring_signature(
     signature_data.extend_from_slice(&commitment);
 
     // Generate random challenges for decoys and compute response for true key
-    let mut rng = rand::thread_rng();
     for (i, member) in ring_members.iter().enumerate() {
         if member == public_key {
             // True signer: response = r - challenge * private_key
@@ -226,7 +241,7 @@ pub fn verify_ring_signature(message: &[u8], ring_sig: &RingSignature) -> Result
     let sig_commitment = &ring_sig.signature[..32];
 
     // Verify commitment matches
-    if sig_commitment != &expected_commitment[..] {
+    if !ct::ct_eq(sig_commitment, &expected_commitment) {
         return Ok(false);
     }
 
@@ -243,8 +258,10 @@ pub fn verify_ring_signature(message: &[u8], ring_sig: &RingSignature) -> Result
 ///
 /// # Returns
 /// true if key images match (double-spend detected)
+///
+/// The comparison runs in constant time.
 pub fn key_images_equal(key_image1: &[u8; KEY_IMAGE_SIZE], key_image2: &[u8; KEY_IMAGE_SIZE]) -> bool {
-    key_image1 == key_image2
+    ct::key_image_eq(key_image1, key_image2)
 }
 
 /// Validates that a key image is well-formed
@@ -256,7 +273,7 @@ pub fn key_images_equal(key_image1: &[u8; KEY_IMAGE_SIZE], key_image2: &[u8; KEY
 /// Ok(()) if valid, Err otherwise
 pub fn validate_key_image(key_image: &[u8; KEY_IMAGE_SIZE]) -> Result<()> {
     // Check it's not all zeros (invalid)
-    if key_image.iter().all(|&b| b == 0) {
+    if ct::ct_is_zero(key_image) {
         return Err(CryptoError::InvalidKeyImage(
             "Key image cannot be all zeros".to_string()
         ));
@@ -400,4 +417,21 @@ mod tests {
         // Key images should be the same (deterministic)
         assert_eq!(sig1.key_image, sig2.key_image);
     }
+
+    #[test]
+    fn test_seeded_signature_reproducible() {
+        use crate::entropy::Entropy;
+        use crate::keys::generate_keypair_with_rng;
+
+        let sign = |seed| {
+            let mut entropy = Entropy::seeded(seed);
+            let kp = generate_keypair_with_rng(&mut entropy);
+            let decoy = generate_keypair_with_rng(&mut entropy);
+            let ring = vec![kp.public_key.clone(), decoy.public_key];
+            generate_ring_signature_with_rng(b"msg", kp.private_key(), &kp.public_key, &ring, &mut entropy).unwrap()
+        };
+
+        assert_eq!(sign(9), sign(9));
+        assert_ne!(sign(9).signature, sign(10).signature);
+    }
 }
@@ -0,0 +1,196 @@
+// src/shamir.rs
+
+//! Shamir secret sharing over GF(2^8).
+//!
+//! A secret is split into `n` shares such that any `k` of them recover it
+//! and fewer reveal nothing about it. Each byte of the secret is the
+//! constant term of its own random polynomial of degree `k - 1`; a share
+//! holds every polynomial evaluated at the share's non-zero index.
+
+use crate::errors::{CryptoError, Result};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+/// Maximum number of shares (indices 1 to 255)
+pub const MAX_SHARES: u8 = 255;
+
+/// One share of a split secret
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Share {
+    /// Evaluation point, never zero
+    pub index: u8,
+
+    /// Secret polynomials evaluated at `index`
+    pub data: Vec<u8>,
+}
+
+impl std::fmt::Debug for Share {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Share")
+            .field("index", &self.index)
+            .field("data", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// Splits a secret into `shares` shares, any `threshold` of which recover it
+///
+/// # Errors
+/// - `InvalidInput` if `threshold` is zero or exceeds `shares`
+///
+/// # Example
+/// ```
+/// use nyx_crypto::shamir::{combine, split};
+///
+/// let shares = split(b"wallet key", 2, 3).unwrap();
+/// assert_eq!(combine(&shares[1..]).unwrap(), b"wallet key");
+/// ```
+pub fn split(secret: &[u8], threshold: u8, shares: u8) -> Result<Vec<Share>> {
+    split_with_rng(secret, threshold, shares, &mut rand::thread_rng())
+}
+
+/// Splits a secret drawing the polynomial coefficients from `rng`
+///
+/// `rng` must be unpredictable; a seeded [`Entropy`](crate::entropy::Entropy)
+/// is only safe in tests.
+pub fn split_with_rng<R: Rng + ?Sized>(secret: &[u8], threshold: u8, shares: u8, rng: &mut R) -> Result<Vec<Share>> {
+    if threshold == 0 || threshold > shares {
+        return Err(CryptoError::InvalidInput(format!(
+            "Threshold {} must be between 1 and the share count {}", threshold, shares
+        )));
+    }
+
+    let mut result: Vec<Share> = (1..=shares)
+        .map(|index| Share { index, data: Vec::with_capacity(secret.len()) })
+        .collect();
+
+    let mut coefficients = vec![0u8; threshold as usize];
+    for &byte in secret {
+        coefficients[0] = byte;
+        rng.fill(&mut coefficients[1..]);
+        for share in &mut result {
+            share.data.push(evaluate(&coefficients, share.index));
+        }
+    }
+    coefficients.zeroize();
+
+    Ok(result)
+}
+
+/// Recovers a secret from at least the threshold number of its shares
+///
+/// Fewer shares than the threshold yield a wrong secret rather than an
+/// error; callers detect it by authenticating what the secret unlocks.
+///
+/// # Errors
+/// - `InvalidInput` if no shares are given, an index is zero or repeated,
+///   or the shares differ in length
+pub fn combine(shares: &[Share]) -> Result<Vec<u8>> {
+    let first = shares.first()
+        .ok_or_else(|| CryptoError::InvalidInput("No shares given".to_string()))?;
+    for (i, share) in shares.iter().enumerate() {
+        if share.index == 0 {
+            return Err(CryptoError::InvalidInput("Share index 0 is invalid".to_string()));
+        }
+        if share.data.len() != first.data.len() {
+            return Err(CryptoError::InvalidInput("Shares differ in length".to_string()));
+        }
+        if shares[..i].iter().any(|other| other.index == share.index) {
+            return Err(CryptoError::InvalidInput(format!("Share {} given twice", share.index)));
+        }
+    }
+
+    // Lagrange basis polynomials evaluated at zero
+    let basis: Vec<u8> = shares.iter()
+        .map(|share| {
+            shares.iter()
+                .filter(|other| other.index != share.index)
+                .fold(1u8, |acc, other| {
+                    gf_mul(acc, gf_div(other.index, other.index ^ share.index))
+                })
+        })
+        .collect();
+
+    Ok((0..first.data.len())
+        .map(|byte| {
+            shares.iter()
+                .zip(&basis)
+                .fold(0u8, |acc, (share, &l)| acc ^ gf_mul(share.data[byte], l))
+        })
+        .collect())
+}
+
+/// Evaluates a polynomial at `x` by Horner's rule
+fn evaluate(coefficients: &[u8], x: u8) -> u8 {
+    coefficients.iter().rev().fold(0u8, |acc, &c| gf_mul(acc, x) ^ c)
+}
+
+/// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x + 1, without
+/// data-dependent branches
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (carry & 0x1b);
+        b >>= 1;
+    }
+    product
+}
+
+/// Division in GF(2^8); `b` must be non-zero
+fn gf_div(a: u8, b: u8) -> u8 {
+    // b^254 is the inverse of b
+    let mut inverse = 1u8;
+    let mut power = b;
+    let mut exponent = 254u8;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            inverse = gf_mul(inverse, power);
+        }
+        power = gf_mul(power, power);
+        exponent >>= 1;
+    }
+    gf_mul(a, inverse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entropy::Entropy;
+
+    #[test]
+    fn test_field_inverse() {
+        for b in 1..=255u8 {
+            assert_eq!(gf_mul(gf_div(1, b), b), 1);
+        }
+    }
+
+    #[test]
+    fn test_any_threshold_subset_recovers() {
+        let secret = b"thirty-two bytes of wallet key!!";
+        let shares = split_with_rng(secret, 3, 5, &mut Entropy::seeded(7)).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        for a in 0..5 {
+            for b in a + 1..5 {
+                for c in b + 1..5 {
+                    let subset = [shares[a].clone(), shares[b].clone(), shares[c].clone()];
+                    assert_eq!(combine(&subset).unwrap(), secret);
+                }
+            }
+        }
+        assert_ne!(combine(&shares[..2]).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert!(split(b"secret", 0, 3).is_err());
+        assert!(split(b"secret", 4, 3).is_err());
+        assert!(combine(&[]).is_err());
+
+        let shares = split(b"secret", 2, 3).unwrap();
+        assert!(combine(&[shares[0].clone(), shares[0].clone()]).is_err());
+    }
+}
@@ -5,6 +5,7 @@
 //! Implements Monero-style ECDH stealth addresses where each transaction
 //! output uses a unique one-time address that only the recipient can detect.
 
+use crate::ct;
 use crate::errors::{CryptoError, Result};
 use crate::hash::blake3_hash;
 use curve25519_dalek::{
@@ -160,6 +161,41 @@ pub fn derive_shared_secret(
     Ok(shared_secret.compress().to_bytes().to_vec())
 }
 
+/// Derives the shared secret on the sender's side
+///
+/// Computes rA where r is the ephemeral key derived from `random_data` and
+/// A is the recipient's public view key. Equals the recipient's
+/// [`derive_shared_secret`] for the output built from the same
+/// `random_data`, so it can key data only the recipient can read.
+///
+/// # Arguments
+/// * `view_public` - Recipient's public view key
+/// * `random_data` - Random bytes passed to [`generate_stealth_address`]
+///
+/// # Returns
+/// Shared secret bytes
+pub fn derive_sender_shared_secret(view_public: &[u8], random_data: &[u8]) -> Result<Vec<u8>> {
+    if view_public.len() != 32 {
+        return Err(CryptoError::StealthAddressError(
+            "View public key must be 32 bytes".to_string()
+        ));
+    }
+
+    let ephemeral_scalar = Scalar::from_bytes_mod_order(
+        hash_to_scalar(random_data)
+    );
+
+    let view_point = CompressedEdwardsY::from_slice(view_public)
+        .map_err(|_| CryptoError::StealthAddressError("Invalid view public key".to_string()))?
+        .decompress()
+        .ok_or_else(|| CryptoError::StealthAddressError("Failed to decompress view key".to_string()))?;
+
+    // Compute shared secret: rA
+    let shared_secret = ephemeral_scalar * view_point;
+
+    Ok(shared_secret.compress().to_bytes().to_vec())
+}
+
 /// Checks if a stealth address belongs to the recipient
 ///
 /// # Arguments
@@ -179,8 +215,27 @@ pub fn is_mine(
     // Derive shared secret
     let shared_secret = derive_shared_secret(view_private, ephemeral_public)?;
 
+    // Reconstruct stealth address: H(aR)G + B
+    let reconstructed = stealth_address_from_secret(&shared_secret, spend_public)?;
+
+    Ok(ct::ct_eq(stealth_address, &reconstructed))
+}
+
+/// Computes the stealth address an output paying `spend_public` has under
+/// a shared secret
+///
+/// Computes: H(S)G + B where S is the shared secret (rA = aR) and B the
+/// recipient's spend public key. Anyone shown S can check an output pays B.
+///
+/// # Arguments
+/// * `shared_secret` - Shared secret of the output
+/// * `spend_public` - Recipient's public spend key
+///
+/// # Returns
+/// Stealth address bytes
+pub fn stealth_address_from_secret(shared_secret: &[u8], spend_public: &[u8]) -> Result<Vec<u8>> {
     // Hash shared secret
-    let shared_secret_hash = blake3_hash(&shared_secret);
+    let shared_secret_hash = blake3_hash(shared_secret);
     let shared_scalar = Scalar::from_bytes_mod_order(shared_secret_hash);
 
     // Parse spend public key
@@ -189,16 +244,80 @@ pub fn is_mine(
         .decompress()
         .ok_or_else(|| CryptoError::StealthAddressError("Failed to decompress spend key".to_string()))?;
 
-    // Reconstruct stealth address: H(aR)G + B
-    let reconstructed = (&shared_scalar * ED25519_BASEPOINT_TABLE) + spend_point;
-    let reconstructed_bytes = reconstructed.compress().to_bytes();
+    let stealth_point = (&shared_scalar * ED25519_BASEPOINT_TABLE) + spend_point;
+    Ok(stealth_point.compress().to_bytes().to_vec())
+}
 
-    Ok(stealth_address == &reconstructed_bytes[..])
+/// Recovers the spend public key an output pays from its shared secret
+///
+/// Computes: P - H(S)G, the inverse of [`stealth_address_from_secret`]. A
+/// recipient with several spend keys under one view key checks an output
+/// against all of them with a single lookup.
+///
+/// # Arguments
+/// * `stealth_address` - Stealth address of the output
+/// * `shared_secret` - Shared secret of the output
+pub fn recover_spend_public(stealth_address: &[u8], shared_secret: &[u8]) -> Result<Vec<u8>> {
+    let shared_scalar = Scalar::from_bytes_mod_order(blake3_hash(shared_secret));
+
+    let stealth_point = CompressedEdwardsY::from_slice(stealth_address)
+        .map_err(|_| CryptoError::StealthAddressError("Invalid stealth address".to_string()))?
+        .decompress()
+        .ok_or_else(|| CryptoError::StealthAddressError("Failed to decompress stealth address".to_string()))?;
+
+    let spend_point = stealth_point - (&shared_scalar * ED25519_BASEPOINT_TABLE);
+    Ok(spend_point.compress().to_bytes().to_vec())
+}
+
+/// Derives the spend public key of a subaddress
+///
+/// Computes: B + H(a || label || index)G. Subaddresses share the view key
+/// of the main address, so the same scan finds their outputs, but nobody
+/// without the private view key can tell they belong together.
+///
+/// # Arguments
+/// * `spend_public` - Public spend key of the main address
+/// * `view_private` - Private view key
+/// * `label` - Name of the subaddress chain
+/// * `index` - Position in the chain
+pub fn derive_subaddress_spend_public(
+    spend_public: &[u8],
+    view_private: &[u8],
+    label: &[u8],
+    index: u32,
+) -> Result<Vec<u8>> {
+    let mut data = view_private.to_vec();
+    data.extend_from_slice(label);
+    data.extend_from_slice(&index.to_le_bytes());
+    let offset = Scalar::from_bytes_mod_order(hash_to_scalar(&data));
+
+    let spend_point = CompressedEdwardsY::from_slice(spend_public)
+        .map_err(|_| CryptoError::StealthAddressError("Invalid spend public key".to_string()))?
+        .decompress()
+        .ok_or_else(|| CryptoError::StealthAddressError("Failed to decompress spend key".to_string()))?;
+
+    Ok((spend_point + (&offset * ED25519_BASEPOINT_TABLE)).compress().to_bytes().to_vec())
+}
+
+/// Computes the ephemeral public key R = rG published with an output built
+/// from `random_data`
+///
+/// Lets a sender disclosing `random_data` (the output's transaction key)
+/// be checked against the transaction.
+pub fn derive_ephemeral_public(random_data: &[u8]) -> Vec<u8> {
+    let ephemeral_scalar = Scalar::from_bytes_mod_order(
+        hash_to_scalar(random_data)
+    );
+    (&ephemeral_scalar * ED25519_BASEPOINT_TABLE).compress().to_bytes().to_vec()
 }
 
 /// Generates random bytes for ephemeral key generation
 pub fn generate_random_ephemeral() -> Vec<u8> {
-    let mut rng = rand::thread_rng();
+    generate_random_ephemeral_with_rng(&mut rand::thread_rng())
+}
+
+/// Generates ephemeral key randomness drawing from `rng`
+pub fn generate_random_ephemeral_with_rng<R: Rng + ?Sized>(rng: &mut R) -> Vec<u8> {
     let random: [u8; 32] = rng.gen();
     random.to_vec()
 }
@@ -250,6 +369,35 @@ mod tests {
         assert_eq!(secret.len(), 32);
     }
 
+    #[test]
+    fn test_sender_shared_secret_matches_recipient() {
+        let (view_priv, view_pub) = generate_keypair_ed25519();
+        let (_, spend_pub) = generate_keypair_ed25519();
+        let random = [5u8; 32];
+
+        let (_, ephemeral) = generate_stealth_address(&view_pub, &spend_pub, &random).unwrap();
+        assert_eq!(
+            derive_sender_shared_secret(&view_pub, &random).unwrap(),
+            derive_shared_secret(&view_priv, &ephemeral).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_disclosed_secrets_reproduce_output() {
+        let (view_priv, view_pub) = generate_keypair_ed25519();
+        let (_, spend_pub) = generate_keypair_ed25519();
+        let random = [6u8; 32];
+
+        let (stealth, ephemeral) = generate_stealth_address(&view_pub, &spend_pub, &random).unwrap();
+        assert_eq!(derive_ephemeral_public(&random), ephemeral);
+
+        let secret = derive_shared_secret(&view_priv, &ephemeral).unwrap();
+        assert_eq!(stealth_address_from_secret(&secret, &spend_pub).unwrap(), stealth);
+
+        let (_, other_spend) = generate_keypair_ed25519();
+        assert_ne!(stealth_address_from_secret(&secret, &other_spend).unwrap(), stealth);
+    }
+
     #[test]
     fn test_is_mine() {
         let (view_priv, view_pub) = generate_keypair_ed25519();
@@ -300,4 +448,21 @@ mod tests {
         assert_eq!(rand2.len(), 32);
         assert_ne!(rand1, rand2);
     }
+
+    #[test]
+    fn test_subaddress_outputs_are_recognized() {
+        let (view_priv, view_pub) = generate_keypair_ed25519();
+        let (_, spend_pub) = generate_keypair_ed25519();
+        let first = derive_subaddress_spend_public(&spend_pub, &view_priv, b"change", 0).unwrap();
+        let second = derive_subaddress_spend_public(&spend_pub, &view_priv, b"change", 1).unwrap();
+        assert_ne!(first, second);
+        assert_ne!(first, spend_pub);
+        assert_eq!(first, derive_subaddress_spend_public(&spend_pub, &view_priv, b"change", 0).unwrap());
+
+        // The main view key finds the output and recovers which key it pays
+        let (stealth, ephemeral) = generate_stealth_address(&view_pub, &second, &[6u8; 32]).unwrap();
+        assert!(is_mine(&stealth, &view_priv, &second, &ephemeral).unwrap());
+        let secret = derive_shared_secret(&view_priv, &ephemeral).unwrap();
+        assert_eq!(recover_spend_public(&stealth, &secret).unwrap(), second);
+    }
 }
@@ -0,0 +1,318 @@
+// src/api.rs
+
+//! Read-only explorer API over the index.
+//!
+//! Inputs and outputs use hex strings so handlers can pass request
+//! parameters straight through and serialize the results as JSON.
+
+use crate::errors::{IndexerError, Result};
+use crate::store::{DepthStats, IndexStore, IndexedOutput, IndexedTransaction};
+use crate::MAX_QUERY_LIMIT;
+use nyx_core::{Hash, Timestamp};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Explorer view of a transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionView {
+    /// Transaction hash (hex)
+    pub hash: String,
+
+    /// Transaction version
+    pub version: u8,
+
+    /// Creation timestamp
+    pub timestamp: Timestamp,
+
+    /// Whether a PoS snapshot has finalized the transaction
+    pub finalized: bool,
+
+    /// Length of the longest path back to a root
+    pub depth: u64,
+
+    /// Key images of the inputs (hex)
+    pub key_images: Vec<String>,
+
+    /// Stealth addresses of the outputs (hex)
+    pub outputs: Vec<String>,
+
+    /// Indexed parents (hex)
+    pub parents: Vec<String>,
+
+    /// Indexed children (hex)
+    pub children: Vec<String>,
+}
+
+/// Explorer view of an output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputView {
+    /// Transaction holding the output (hex)
+    pub tx_hash: String,
+
+    /// Output index within it
+    pub index: u32,
+
+    /// Stealth address (hex)
+    pub stealth_address: String,
+
+    /// Timestamp of the transaction
+    pub timestamp: Timestamp,
+}
+
+impl From<IndexedOutput> for OutputView {
+    fn from(output: IndexedOutput) -> Self {
+        Self {
+            tx_hash: hex::encode(output.tx_hash),
+            index: output.index,
+            stealth_address: hex::encode(output.stealth_address),
+            timestamp: output.timestamp,
+        }
+    }
+}
+
+/// Whether a key image has been spent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyImageStatus {
+    /// Key image (hex)
+    pub key_image: String,
+
+    /// Whether an indexed transaction spends it
+    pub spent: bool,
+
+    /// Spending transaction (hex), if spent
+    pub spent_in: Option<String>,
+}
+
+/// Explorer summary of the DAG's shape
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DagDepthView {
+    /// Indexed transactions
+    pub transactions: usize,
+
+    /// Finalized transactions
+    pub finalized: usize,
+
+    /// Transactions not yet approved
+    pub tips: usize,
+
+    /// Deepest transaction
+    pub max_depth: u64,
+
+    /// Deepest finalized transaction
+    pub finalized_depth: u64,
+
+    /// Levels between the deepest finalized transaction and the deepest
+    /// transaction
+    pub unfinalized_depth: u64,
+}
+
+impl From<DepthStats> for DagDepthView {
+    fn from(stats: DepthStats) -> Self {
+        Self {
+            transactions: stats.transactions,
+            finalized: stats.finalized,
+            tips: stats.tips,
+            max_depth: stats.max_depth,
+            finalized_depth: stats.finalized_depth,
+            unfinalized_depth: stats.max_depth.saturating_sub(stats.finalized_depth),
+        }
+    }
+}
+
+/// Read API used by explorer endpoints
+#[derive(Clone)]
+pub struct ExplorerApi {
+    store: Arc<IndexStore>,
+}
+
+impl ExplorerApi {
+    /// Creates an API reading from the given store
+    pub fn new(store: Arc<IndexStore>) -> Self {
+        Self { store }
+    }
+
+    /// Looks up a transaction by hash
+    pub fn transaction(&self, hash_hex: &str) -> Result<Option<TransactionView>> {
+        let hash = parse_hash(hash_hex)?;
+        self.store.get_transaction(&hash)?
+            .map(|indexed| self.view(indexed))
+            .transpose()
+    }
+
+    /// Finds the transaction that spent a key image
+    pub fn by_key_image(&self, key_image_hex: &str) -> Result<Option<TransactionView>> {
+        let key_image = parse_hash(key_image_hex)?;
+        match self.store.find_by_key_image(&key_image)? {
+            Some(hash) => self.view_hash(&hash),
+            None => Ok(None),
+        }
+    }
+
+    /// Finds transactions paying to a stealth address
+    pub fn by_stealth_address(&self, address_hex: &str) -> Result<Vec<TransactionView>> {
+        let address = hex::decode(address_hex)
+            .map_err(|e| IndexerError::InvalidInput(format!("Invalid address hex: {}", e)))?;
+
+        self.views(self.store.find_by_stealth_address(&address)?)
+    }
+
+    /// Finds outputs whose stealth address starts with a hex prefix
+    ///
+    /// `limit` is capped at [`MAX_QUERY_LIMIT`].
+    pub fn outputs_by_address_prefix(&self, prefix_hex: &str, limit: usize) -> Result<Vec<OutputView>> {
+        let prefix = hex::decode(prefix_hex)
+            .map_err(|e| IndexerError::InvalidInput(format!("Invalid prefix hex: {}", e)))?;
+        if prefix.is_empty() {
+            return Err(IndexerError::InvalidInput("Empty address prefix".to_string()));
+        }
+
+        let limit = limit.min(MAX_QUERY_LIMIT);
+        Ok(self.store.find_outputs_by_address_prefix(&prefix, limit)?
+            .into_iter()
+            .map(OutputView::from)
+            .collect())
+    }
+
+    /// Checks whether a key image has been spent
+    pub fn key_image_status(&self, key_image_hex: &str) -> Result<KeyImageStatus> {
+        let key_image = parse_hash(key_image_hex)?;
+        let spent_in = self.store.find_by_key_image(&key_image)?;
+
+        Ok(KeyImageStatus {
+            key_image: hex::encode(key_image),
+            spent: spent_in.is_some(),
+            spent_in: spent_in.map(hex::encode),
+        })
+    }
+
+    /// Gets the most recent transactions, newest first
+    ///
+    /// `limit` is capped at [`MAX_QUERY_LIMIT`].
+    pub fn recent(&self, limit: usize) -> Result<Vec<TransactionView>> {
+        self.views(self.store.find_recent(limit.min(MAX_QUERY_LIMIT))?)
+    }
+
+    /// Summarizes the depth of the indexed DAG
+    pub fn depth_stats(&self) -> Result<DagDepthView> {
+        Ok(self.store.depth_stats()?.into())
+    }
+
+    /// Finds transactions in a time range, oldest first
+    ///
+    /// `limit` is capped at [`MAX_QUERY_LIMIT`].
+    pub fn by_time_range(&self, from: Timestamp, to: Timestamp, limit: usize) -> Result<Vec<TransactionView>> {
+        if from > to {
+            return Err(IndexerError::InvalidInput(format!(
+                "Invalid time range: {} > {}", from, to
+            )));
+        }
+
+        let limit = limit.min(MAX_QUERY_LIMIT);
+        self.views(self.store.find_by_time_range(from, to, limit)?)
+    }
+
+    /// Returns the number of indexed transactions
+    pub fn transaction_count(&self) -> Result<usize> {
+        self.store.transaction_count()
+    }
+
+    fn views(&self, hashes: Vec<Hash>) -> Result<Vec<TransactionView>> {
+        let mut views = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            if let Some(view) = self.view_hash(&hash)? {
+                views.push(view);
+            }
+        }
+        Ok(views)
+    }
+
+    fn view_hash(&self, hash: &Hash) -> Result<Option<TransactionView>> {
+        self.store.get_transaction(hash)?
+            .map(|indexed| self.view(indexed))
+            .transpose()
+    }
+
+    fn view(&self, indexed: IndexedTransaction) -> Result<TransactionView> {
+        let IndexedTransaction { hash, finalized, depth, tx } = indexed;
+
+        Ok(TransactionView {
+            hash: hex::encode(hash),
+            version: tx.version,
+            timestamp: tx.timestamp,
+            finalized,
+            depth,
+            key_images: tx.inputs.iter().map(|i| hex::encode(i.key_image)).collect(),
+            outputs: tx.outputs.iter().map(|o| hex::encode(&o.stealth_address)).collect(),
+            parents: self.store.parents(&hash)?.iter().map(hex::encode).collect(),
+            children: self.store.children(&hash)?.iter().map(hex::encode).collect(),
+        })
+    }
+}
+
+/// Parses a 32-byte hex value
+fn parse_hash(s: &str) -> Result<Hash> {
+    let bytes = hex::decode(s)
+        .map_err(|e| IndexerError::InvalidInput(format!("Invalid hex: {}", e)))?;
+
+    bytes.as_slice().try_into()
+        .map_err(|_| IndexerError::InvalidInput(format!("Expected 32 bytes, got {}", bytes.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_core::testing::TestTx;
+
+    fn indexed_api() -> (ExplorerApi, Hash) {
+        let store = Arc::new(IndexStore::in_memory().unwrap());
+        let tx = TestTx::new(7).stealth_address(vec![0xAB; 32]).build();
+        let hash = tx.id();
+        store.insert_transaction(&hash, &tx).unwrap();
+
+        (ExplorerApi::new(store), hash)
+    }
+
+    #[test]
+    fn test_lookups() {
+        let (api, hash) = indexed_api();
+        let hash_hex = hex::encode(hash);
+
+        let view = api.transaction(&hash_hex).unwrap().unwrap();
+        assert_eq!(view.hash, hash_hex);
+        assert_eq!(view.key_images, vec![hex::encode([7u8; 32])]);
+
+        assert_eq!(api.by_key_image(&hex::encode([7u8; 32])).unwrap().unwrap().hash, hash_hex);
+        assert_eq!(api.by_stealth_address(&hex::encode([0xAB; 32])).unwrap().len(), 1);
+        assert_eq!(api.by_time_range(0, u64::MAX / 2, 10).unwrap().len(), 1);
+        assert!(api.transaction(&hex::encode([9u8; 32])).unwrap().is_none());
+
+        let outputs = api.outputs_by_address_prefix("abab", 10).unwrap();
+        assert_eq!(outputs, vec![OutputView {
+            tx_hash: hash_hex.clone(),
+            index: 0,
+            stealth_address: hex::encode([0xAB; 32]),
+            timestamp: view.timestamp,
+        }]);
+        assert!(api.outputs_by_address_prefix("ac", 10).unwrap().is_empty());
+
+        let status = api.key_image_status(&hex::encode([7u8; 32])).unwrap();
+        assert!(status.spent);
+        assert_eq!(status.spent_in, Some(hash_hex.clone()));
+        assert!(!api.key_image_status(&hex::encode([8u8; 32])).unwrap().spent);
+
+        assert_eq!(api.recent(10).unwrap()[0].hash, hash_hex);
+        let stats = api.depth_stats().unwrap();
+        assert_eq!((stats.transactions, stats.tips, stats.unfinalized_depth), (1, 1, 0));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let (api, _) = indexed_api();
+
+        assert!(matches!(api.transaction("zz"), Err(IndexerError::InvalidInput(_))));
+        assert!(matches!(api.by_key_image("abcd"), Err(IndexerError::InvalidInput(_))));
+        assert!(matches!(api.by_time_range(10, 5, 10), Err(IndexerError::InvalidInput(_))));
+        assert!(matches!(api.outputs_by_address_prefix("", 10), Err(IndexerError::InvalidInput(_))));
+        assert!(matches!(api.outputs_by_address_prefix("abc", 10), Err(IndexerError::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,60 @@
+// src/errors.rs
+
+//! Error types for indexer operations.
+
+use std::fmt;
+
+/// Main error type for indexer operations
+#[derive(Debug)]
+pub enum IndexerError {
+    /// Database error
+    StorageError(String),
+
+    /// Malformed query input (bad hex, wrong length)
+    InvalidInput(String),
+
+    /// Error from the core DAG
+    CoreError(String),
+
+    /// Stored data could not be decoded
+    CorruptIndex(String),
+}
+
+impl fmt::Display for IndexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexerError::StorageError(msg) => write!(f, "Storage error: {}", msg),
+            IndexerError::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
+            IndexerError::CoreError(msg) => write!(f, "Core error: {}", msg),
+            IndexerError::CorruptIndex(msg) => write!(f, "Corrupt index: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for IndexerError {}
+
+impl From<rusqlite::Error> for IndexerError {
+    fn from(err: rusqlite::Error) -> Self {
+        IndexerError::StorageError(format!("{}", err))
+    }
+}
+
+impl From<nyx_core::NyxError> for IndexerError {
+    fn from(err: nyx_core::NyxError) -> Self {
+        IndexerError::CoreError(format!("{}", err))
+    }
+}
+
+/// Result type alias for indexer operations
+pub type Result<T> = std::result::Result<T, IndexerError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_display() {
+        let err = IndexerError::InvalidInput("bad hex".to_string());
+        assert_eq!(err.to_string(), "Invalid input: bad hex");
+    }
+}
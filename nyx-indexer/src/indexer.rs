@@ -0,0 +1,123 @@
+// src/indexer.rs
+
+//! Feeds DAG events into the index store.
+
+use crate::errors::{IndexerError, Result};
+use crate::store::IndexStore;
+use nyx_core::dag::{DagEvent, DagProcessor, TxState};
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+/// Builds explorer indexes from the DAG
+#[derive(Clone)]
+pub struct Indexer {
+    store: Arc<IndexStore>,
+}
+
+impl Indexer {
+    /// Creates an indexer writing into the given store
+    pub fn new(store: Arc<IndexStore>) -> Self {
+        Self { store }
+    }
+
+    /// Gets the underlying store
+    pub fn store(&self) -> &Arc<IndexStore> {
+        &self.store
+    }
+
+    /// Applies a single DAG event to the index
+    pub fn handle_event(&self, event: &DagEvent) -> Result<()> {
+        match event {
+            DagEvent::TransactionAdded { hash, tx } => {
+                self.store.insert_transaction(hash, tx)?;
+            }
+            DagEvent::TransactionConfirmed { .. } => {}
+            DagEvent::TransactionFinalized { hash } => {
+                self.store.mark_finalized(hash)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Indexes every transaction already in the DAG
+    ///
+    /// # Returns
+    /// Number of transactions newly indexed
+    pub fn backfill(&self, dag: &DagProcessor) -> Result<usize> {
+        let mut indexed = 0;
+
+        for hash in dag.topological_order()? {
+            let tx = dag.storage().get_transaction(&hash)?;
+            if self.store.insert_transaction(&hash, &tx)? {
+                indexed += 1;
+            }
+            if dag.get_state(&hash)? == TxState::Finalized {
+                self.store.mark_finalized(&hash)?;
+            }
+        }
+
+        Ok(indexed)
+    }
+
+    /// Processes events until every sender is dropped
+    pub fn run(&self, events: Receiver<DagEvent>) -> Result<()> {
+        for event in events {
+            self.handle_event(&event)?;
+        }
+        Ok(())
+    }
+
+    /// Backfills existing history, then follows new events on a thread
+    ///
+    /// The subscription is taken before the backfill so nothing added in
+    /// between is missed; the overlap is deduplicated by the store.
+    pub fn spawn(&self, dag: &DagProcessor) -> Result<JoinHandle<Result<()>>> {
+        let events = dag.subscribe();
+        self.backfill(dag)?;
+
+        let indexer = self.clone();
+        std::thread::Builder::new()
+            .name("nyx-indexer".to_string())
+            .spawn(move || indexer.run(events))
+            .map_err(|e| IndexerError::StorageError(format!("Failed to spawn indexer: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_core::storage::MemoryStorage;
+    use nyx_core::testing::TestTx;
+    use nyx_core::verification::VerifyMode;
+    use nyx_core::{Hash, Transaction};
+
+    fn create_test_tx(parent1: Hash, parent2: Hash, nonce: u8) -> Transaction {
+        TestTx::new(nonce).parents(parent1, parent2).stealth_address(vec![nonce; 32]).build()
+    }
+
+    #[test]
+    fn test_backfill_and_follow() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+
+        let g1 = storage.store_transaction(create_test_tx([0u8; 32], [0u8; 32], 1)).unwrap();
+        let g2 = storage.store_transaction(create_test_tx([0u8; 32], [0u8; 32], 2)).unwrap();
+
+        let indexer = Indexer::new(Arc::new(IndexStore::in_memory().unwrap()));
+        let handle = indexer.spawn(&dag).unwrap();
+        assert_eq!(indexer.store().transaction_count().unwrap(), 2);
+
+        let a = dag.add_transaction_with_mode(create_test_tx(g1, g2, 3), VerifyMode::HeadersOnly).unwrap();
+        dag.finalize_transaction(&a).unwrap();
+
+        // Dropping the DAG closes the event stream and ends the thread
+        drop(dag);
+        handle.join().unwrap().unwrap();
+
+        let store = indexer.store();
+        assert_eq!(store.transaction_count().unwrap(), 3);
+        assert!(store.get_transaction(&a).unwrap().unwrap().finalized);
+        assert_eq!(store.find_by_key_image(&[3u8; 32]).unwrap(), Some(a));
+    }
+}
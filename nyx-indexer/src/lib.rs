@@ -0,0 +1,65 @@
+// src/lib.rs
+
+//! # Nyx Indexer
+//!
+//! Builds query indexes over the transaction DAG for block explorers.
+//!
+//! This crate provides:
+//! - **Store**: SQLite-backed indexes by stealth address, key image,
+//!   timestamp, depth and parent/child links
+//! - **Indexer**: Consumes DAG events (and backfills existing history) into
+//!   the store
+//! - **Explorer API**: Read-only, hex-string based queries used by the node's
+//!   RPC explorer endpoints
+//!
+//! ## Example Usage
+//!
+//! ```rust
+//! use nyx_core::dag::DagProcessor;
+//! use nyx_core::storage::MemoryStorage;
+//! use nyx_indexer::{ExplorerApi, Indexer, IndexStore};
+//! use std::sync::Arc;
+//!
+//! let dag = DagProcessor::new(MemoryStorage::new());
+//! let store = Arc::new(IndexStore::in_memory().unwrap());
+//!
+//! // Index history and follow new transactions on a background thread
+//! let indexer = Indexer::new(store.clone());
+//! let _handle = indexer.spawn(&dag).unwrap();
+//!
+//! let explorer = ExplorerApi::new(store);
+//! assert_eq!(explorer.transaction_count().unwrap(), 0);
+//! ```
+
+#![warn(missing_docs)]
+#![warn(clippy::all)]
+#![deny(unsafe_code)]
+
+pub mod errors;
+pub mod store;
+pub mod indexer;
+pub mod api;
+pub mod migrations;
+
+// Re-export commonly used types
+pub use crate::errors::{IndexerError, Result};
+pub use crate::store::{DepthStats, IndexStore, IndexedOutput, IndexedTransaction};
+pub use crate::indexer::Indexer;
+pub use crate::api::{DagDepthView, ExplorerApi, KeyImageStatus, OutputView, TransactionView};
+pub use crate::migrations::OpenOptions;
+
+/// Version of the index schema
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Maximum number of results returned by a single range query
+pub const MAX_QUERY_LIMIT: usize = 1000;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_version() {
+        assert_eq!(SCHEMA_VERSION, 2);
+    }
+}
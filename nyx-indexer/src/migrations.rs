@@ -0,0 +1,215 @@
+// src/migrations.rs
+
+//! Index schema migrations.
+//!
+//! The schema version lives in SQLite's `user_version`. Opening an index
+//! runs every [`Migration`] above its version in order, each in its own
+//! transaction together with the version bump, so an interrupted upgrade
+//! resumes from the last completed step. A new index runs them all.
+//!
+//! To change the layout, append a step and bump [`crate::SCHEMA_VERSION`];
+//! never edit a released step.
+
+use crate::errors::{IndexerError, Result};
+use crate::SCHEMA_VERSION;
+use rusqlite::{Connection, Transaction};
+use std::path::{Path, PathBuf};
+
+/// One step of the schema history
+pub struct Migration {
+    /// Schema version reached by the step
+    pub version: u32,
+
+    /// What the step changes
+    pub description: &'static str,
+
+    /// Applies the step
+    apply: fn(&Transaction<'_>) -> Result<()>,
+}
+
+/// Every schema step, oldest first
+pub const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, description: "create transaction, output, key image and edge tables", apply: create_tables },
+    Migration { version: 2, description: "record transaction depth", apply: add_depth },
+];
+
+/// How an index is opened
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpenOptions {
+    /// Copy the database aside before migrating an existing index
+    pub backup_before_migrate: bool,
+}
+
+/// Path of the backup taken before migrating `path` from `version`
+pub fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".v{}.bak", version));
+    PathBuf::from(name)
+}
+
+/// Brings the schema of `conn` up to [`SCHEMA_VERSION`]
+///
+/// `path` is the database file, used for backups; in-memory indexes have
+/// none.
+///
+/// # Errors
+/// - `StorageError` if the index is newer than this build, the backup fails
+///   or a step fails
+///
+/// # Returns
+/// The version the index had before migrating
+pub(crate) fn migrate(conn: &mut Connection, path: Option<&Path>, options: OpenOptions) -> Result<u32> {
+    let version = schema_version(conn)?;
+    if version > SCHEMA_VERSION {
+        return Err(IndexerError::StorageError(format!(
+            "Unsupported index schema version {} (expected {})",
+            version, SCHEMA_VERSION
+        )));
+    }
+
+    if let Some(path) = path.filter(|_| options.backup_before_migrate && version > 0 && version < SCHEMA_VERSION) {
+        let backup = backup_path(path, version);
+        conn.execute("VACUUM INTO ?1", [backup.to_string_lossy()])
+            .map_err(|e| IndexerError::StorageError(format!("Failed to back up index to {}: {}", backup.display(), e)))?;
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > version) {
+        let db = conn.transaction()?;
+        (migration.apply)(&db).map_err(|e| {
+            IndexerError::StorageError(format!(
+                "Migration to schema version {} ({}) failed: {}",
+                migration.version, migration.description, e
+            ))
+        })?;
+        db.pragma_update(None, "user_version", migration.version)?;
+        db.commit()?;
+    }
+
+    Ok(version)
+}
+
+/// Reads the schema version of an index (0 if new)
+pub(crate) fn schema_version(conn: &Connection) -> Result<u32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+fn create_tables(db: &Transaction<'_>) -> Result<()> {
+    db.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS transactions (
+            hash      BLOB PRIMARY KEY,
+            timestamp INTEGER NOT NULL,
+            finalized INTEGER NOT NULL DEFAULT 0,
+            raw       BLOB NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS transactions_by_time ON transactions (timestamp, hash);
+
+        CREATE TABLE IF NOT EXISTS outputs (
+            tx_hash         BLOB NOT NULL,
+            output_index    INTEGER NOT NULL,
+            stealth_address BLOB NOT NULL,
+            PRIMARY KEY (tx_hash, output_index)
+        );
+        CREATE INDEX IF NOT EXISTS outputs_by_address ON outputs (stealth_address);
+
+        CREATE TABLE IF NOT EXISTS key_images (
+            key_image BLOB PRIMARY KEY,
+            tx_hash   BLOB NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS edges (
+            parent BLOB NOT NULL,
+            child  BLOB NOT NULL,
+            PRIMARY KEY (parent, child)
+        );
+        CREATE INDEX IF NOT EXISTS edges_by_child ON edges (child);
+        ",
+    )?;
+    Ok(())
+}
+
+/// Adds the depth column and computes it for indexed transactions
+///
+/// Depths are raised until no parent is as deep as its child, one DAG level
+/// per pass.
+fn add_depth(db: &Transaction<'_>) -> Result<()> {
+    db.execute_batch("ALTER TABLE transactions ADD COLUMN depth INTEGER NOT NULL DEFAULT 0;")?;
+
+    loop {
+        let raised = db.execute(
+            "UPDATE transactions SET depth = (
+                SELECT MAX(p.depth) + 1 FROM edges e JOIN transactions p ON p.hash = e.parent
+                WHERE e.child = transactions.hash
+            )
+            WHERE depth <= (
+                SELECT MAX(p.depth) FROM edges e JOIN transactions p ON p.hash = e.parent
+                WHERE e.child = transactions.hash
+            )",
+            [],
+        )?;
+        if raised == 0 {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_are_ordered() {
+        let versions: Vec<u32> = MIGRATIONS.iter().map(|m| m.version).collect();
+        let expected: Vec<u32> = (1..=SCHEMA_VERSION).collect();
+        assert_eq!(versions, expected);
+    }
+
+    #[test]
+    fn test_new_index_runs_every_step() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(migrate(&mut conn, None, OpenOptions::default()).unwrap(), 0);
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        // Migrating again is a no-op
+        assert_eq!(migrate(&mut conn, None, OpenOptions::default()).unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_depth_is_computed_for_existing_rows() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let db = conn.transaction().unwrap();
+        create_tables(&db).unwrap();
+        db.pragma_update(None, "user_version", 1).unwrap();
+        db.commit().unwrap();
+
+        // A chain a <- b <- c, plus d approving a and c
+        for (hash, parents) in [(b"a", vec![]), (b"b", vec![b"a"]), (b"c", vec![b"b"]), (b"d", vec![b"a", b"c"])] {
+            conn.execute(
+                "INSERT INTO transactions (hash, timestamp, raw) VALUES (?1, 0, x'')",
+                [&hash[..]],
+            )
+            .unwrap();
+            for parent in parents {
+                conn.execute("INSERT INTO edges (parent, child) VALUES (?1, ?2)", [&parent[..], &hash[..]])
+                    .unwrap();
+            }
+        }
+
+        assert_eq!(migrate(&mut conn, None, OpenOptions::default()).unwrap(), 1);
+        let depth = |hash: &[u8]| -> i64 {
+            conn.query_row("SELECT depth FROM transactions WHERE hash = ?1", [hash], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!([depth(b"a"), depth(b"b"), depth(b"c"), depth(b"d")], [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_newer_schema_is_refused() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1).unwrap();
+        assert!(matches!(
+            migrate(&mut conn, None, OpenOptions::default()),
+            Err(IndexerError::StorageError(_))
+        ));
+    }
+}
@@ -0,0 +1,483 @@
+// src/store.rs
+
+//! SQLite-backed index store.
+//!
+//! Schema:
+//! - `transactions`: canonical transaction bytes keyed by hash, with
+//!   timestamp, finality flag and depth (indexed by timestamp)
+//! - `outputs`: stealth address of every output (indexed by address)
+//! - `key_images`: key image → spending transaction
+//! - `edges`: parent → child links (indexed both ways)
+//!
+//! Inserts are idempotent, so replaying events or overlapping a backfill
+//! with live indexing is harmless. An index of an older schema is brought up
+//! to date by [`crate::migrations`] when opened.
+
+use crate::errors::{IndexerError, Result};
+use crate::migrations::{self, OpenOptions};
+use nyx_core::encoding::CanonicalEncode;
+use nyx_core::{Hash, Timestamp, Transaction};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+/// A transaction as stored in the index
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedTransaction {
+    /// Transaction hash
+    pub hash: Hash,
+
+    /// Whether a PoS snapshot has finalized the transaction
+    pub finalized: bool,
+
+    /// Length of the longest path back to a root (roots have depth 0)
+    pub depth: u64,
+
+    /// The transaction
+    pub tx: Transaction,
+}
+
+/// An indexed output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedOutput {
+    /// Transaction holding the output
+    pub tx_hash: Hash,
+
+    /// Output index within it
+    pub index: u32,
+
+    /// Stealth address of the output
+    pub stealth_address: Vec<u8>,
+
+    /// Timestamp of the transaction
+    pub timestamp: Timestamp,
+}
+
+/// Shape of the indexed DAG
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepthStats {
+    /// Indexed transactions
+    pub transactions: usize,
+
+    /// Finalized transactions
+    pub finalized: usize,
+
+    /// Transactions no indexed transaction approves yet
+    pub tips: usize,
+
+    /// Deepest transaction
+    pub max_depth: u64,
+
+    /// Deepest finalized transaction
+    pub finalized_depth: u64,
+}
+
+/// Persistent explorer indexes
+pub struct IndexStore {
+    conn: Mutex<Connection>,
+}
+
+impl IndexStore {
+    /// Opens (or creates) an index database at the given path
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::open_with(path, OpenOptions::default())
+    }
+
+    /// Opens (or creates) an index database, migrating it as configured
+    ///
+    /// # Errors
+    /// - `StorageError` if the database cannot be opened, is of a newer
+    ///   schema, or fails to migrate
+    pub fn open_with<P: AsRef<Path>>(path: P, options: OpenOptions) -> Result<Self> {
+        let path = path.as_ref();
+        let mut conn = Connection::open(path)?;
+        migrations::migrate(&mut conn, Some(path), options)?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    /// Creates a throwaway in-memory index
+    pub fn in_memory() -> Result<Self> {
+        let mut conn = Connection::open_in_memory()?;
+        migrations::migrate(&mut conn, None, OpenOptions::default())?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    /// Schema version of the open index
+    pub fn schema_version(&self) -> Result<u32> {
+        migrations::schema_version(&*self.conn()?)
+    }
+
+    fn conn(&self) -> Result<MutexGuard<'_, Connection>> {
+        self.conn.lock()
+            .map_err(|e| IndexerError::StorageError(format!("Lock poisoned: {}", e)))
+    }
+
+    /// Indexes a transaction
+    ///
+    /// Returns false if it was already indexed.
+    pub fn insert_transaction(&self, hash: &Hash, tx: &Transaction) -> Result<bool> {
+        let mut conn = self.conn()?;
+        let db = conn.transaction()?;
+
+        // Parents are indexed first, except placeholder genesis references
+        let parent_depth: Option<i64> = db.query_row(
+            "SELECT MAX(depth) FROM transactions WHERE hash IN (?1, ?2)",
+            params![&tx.references[0][..], &tx.references[1][..]],
+            |row| row.get(0),
+        )?;
+        let depth = parent_depth.map_or(0, |d| d + 1);
+
+        let inserted = db.execute(
+            "INSERT OR IGNORE INTO transactions (hash, timestamp, depth, raw) VALUES (?1, ?2, ?3, ?4)",
+            params![&hash[..], tx.timestamp as i64, depth, tx.to_canonical_bytes()],
+        )? > 0;
+
+        if inserted {
+            for (i, output) in tx.outputs.iter().enumerate() {
+                db.execute(
+                    "INSERT OR IGNORE INTO outputs (tx_hash, output_index, stealth_address)
+                     VALUES (?1, ?2, ?3)",
+                    params![&hash[..], i as i64, &output.stealth_address],
+                )?;
+            }
+
+            for input in &tx.inputs {
+                db.execute(
+                    "INSERT OR IGNORE INTO key_images (key_image, tx_hash) VALUES (?1, ?2)",
+                    params![&input.key_image[..], &hash[..]],
+                )?;
+            }
+
+            for parent in &tx.references {
+                db.execute(
+                    "INSERT OR IGNORE INTO edges (parent, child) VALUES (?1, ?2)",
+                    params![&parent[..], &hash[..]],
+                )?;
+            }
+        }
+
+        db.commit()?;
+        Ok(inserted)
+    }
+
+    /// Marks a transaction as finalized
+    pub fn mark_finalized(&self, hash: &Hash) -> Result<()> {
+        self.conn()?.execute(
+            "UPDATE transactions SET finalized = 1 WHERE hash = ?1",
+            params![&hash[..]],
+        )?;
+        Ok(())
+    }
+
+    /// Gets an indexed transaction
+    pub fn get_transaction(&self, hash: &Hash) -> Result<Option<IndexedTransaction>> {
+        let row: Option<(bool, i64, Vec<u8>)> = self.conn()?
+            .query_row(
+                "SELECT finalized, depth, raw FROM transactions WHERE hash = ?1",
+                params![&hash[..]],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+
+        row.map(|(finalized, depth, raw)| {
+            let tx = Transaction::from_canonical_bytes(&raw)
+                .map_err(|e| IndexerError::CorruptIndex(format!("{}", e)))?;
+            Ok(IndexedTransaction { hash: *hash, finalized, depth: depth as u64, tx })
+        })
+        .transpose()
+    }
+
+    /// Finds transactions with an output to the given stealth address
+    pub fn find_by_stealth_address(&self, address: &[u8]) -> Result<Vec<Hash>> {
+        self.query_hashes(
+            "SELECT DISTINCT o.tx_hash FROM outputs o
+             JOIN transactions t ON t.hash = o.tx_hash
+             WHERE o.stealth_address = ?1
+             ORDER BY t.timestamp, o.tx_hash",
+            params![address],
+        )
+    }
+
+    /// Finds outputs whose stealth address starts with `prefix`, ordered
+    /// by address
+    pub fn find_outputs_by_address_prefix(&self, prefix: &[u8], limit: usize) -> Result<Vec<IndexedOutput>> {
+        // Addresses sharing the prefix sort between the prefix and its
+        // successor, so the range stays on the address index
+        let upper = prefix_successor(prefix);
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT o.tx_hash, o.output_index, o.stealth_address, t.timestamp FROM outputs o
+             JOIN transactions t ON t.hash = o.tx_hash
+             WHERE o.stealth_address >= ?1 AND (?2 IS NULL OR o.stealth_address < ?2)
+             ORDER BY o.stealth_address, o.tx_hash, o.output_index
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![prefix, upper, limit as i64], |row| {
+            Ok((
+                row.get::<_, Vec<u8>>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Vec<u8>>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?;
+
+        rows.map(|row| {
+            let (tx_hash, index, stealth_address, timestamp) = row?;
+            Ok(IndexedOutput {
+                tx_hash: to_hash(&tx_hash)?,
+                index: index as u32,
+                stealth_address,
+                timestamp: timestamp as Timestamp,
+            })
+        })
+        .collect()
+    }
+
+    /// Finds the transaction that spent a key image
+    pub fn find_by_key_image(&self, key_image: &[u8; 32]) -> Result<Option<Hash>> {
+        let row: Option<Vec<u8>> = self.conn()?
+            .query_row(
+                "SELECT tx_hash FROM key_images WHERE key_image = ?1",
+                params![&key_image[..]],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        row.map(|bytes| to_hash(&bytes)).transpose()
+    }
+
+    /// Finds transactions with `from <= timestamp <= to`, oldest first
+    pub fn find_by_time_range(&self, from: Timestamp, to: Timestamp, limit: usize) -> Result<Vec<Hash>> {
+        self.query_hashes(
+            "SELECT hash FROM transactions
+             WHERE timestamp BETWEEN ?1 AND ?2
+             ORDER BY timestamp, hash
+             LIMIT ?3",
+            params![from as i64, to as i64, limit as i64],
+        )
+    }
+
+    /// Finds the most recent transactions, newest first
+    pub fn find_recent(&self, limit: usize) -> Result<Vec<Hash>> {
+        self.query_hashes(
+            "SELECT hash FROM transactions ORDER BY timestamp DESC, hash DESC LIMIT ?1",
+            params![limit as i64],
+        )
+    }
+
+    /// Gets the indexed parents of a transaction
+    pub fn parents(&self, hash: &Hash) -> Result<Vec<Hash>> {
+        self.query_hashes(
+            "SELECT e.parent FROM edges e
+             JOIN transactions t ON t.hash = e.parent
+             WHERE e.child = ?1
+             ORDER BY e.parent",
+            params![&hash[..]],
+        )
+    }
+
+    /// Gets the indexed children of a transaction
+    pub fn children(&self, hash: &Hash) -> Result<Vec<Hash>> {
+        self.query_hashes(
+            "SELECT child FROM edges WHERE parent = ?1 ORDER BY child",
+            params![&hash[..]],
+        )
+    }
+
+    /// Returns the number of indexed transactions
+    pub fn transaction_count(&self) -> Result<usize> {
+        let count: i64 = self.conn()?
+            .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Summarizes the depth of the indexed DAG
+    pub fn depth_stats(&self) -> Result<DepthStats> {
+        let conn = self.conn()?;
+        let (transactions, finalized, max_depth, finalized_depth): (i64, i64, i64, i64) = conn.query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(finalized), 0),
+                    COALESCE(MAX(depth), 0),
+                    COALESCE(MAX(CASE WHEN finalized = 1 THEN depth END), 0)
+             FROM transactions",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+        let tips: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM transactions t
+             WHERE NOT EXISTS (SELECT 1 FROM edges e WHERE e.parent = t.hash)",
+            [],
+            |row| row.get(0),
+        )?;
+
+        Ok(DepthStats {
+            transactions: transactions as usize,
+            finalized: finalized as usize,
+            tips: tips as usize,
+            max_depth: max_depth as u64,
+            finalized_depth: finalized_depth as u64,
+        })
+    }
+
+    fn query_hashes(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<Hash>> {
+        let conn = self.conn()?;
+        let mut stmt = conn.prepare_cached(sql)?;
+        let rows = stmt.query_map(params, |row| row.get::<_, Vec<u8>>(0))?;
+
+        rows.map(|row| to_hash(&row?)).collect()
+    }
+}
+
+/// Smallest byte string greater than every string starting with `prefix`,
+/// or `None` if there is none (the prefix is all `0xff`)
+fn prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let end = prefix.iter().rposition(|&byte| byte != 0xff)?;
+    let mut upper = prefix[..=end].to_vec();
+    upper[end] += 1;
+    Some(upper)
+}
+
+fn to_hash(bytes: &[u8]) -> Result<Hash> {
+    bytes.try_into()
+        .map_err(|_| IndexerError::CorruptIndex(format!("Stored hash has {} bytes", bytes.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_core::testing::TestTx;
+    use crate::SCHEMA_VERSION;
+
+    fn create_test_tx(parent1: Hash, parent2: Hash, nonce: u8, timestamp: Timestamp) -> Transaction {
+        let mut tx = TestTx::new(nonce)
+            .parents(parent1, parent2)
+            .stealth_address(vec![nonce; 32])
+            .build();
+        tx.timestamp = timestamp;
+        tx
+    }
+
+    #[test]
+    fn test_insert_and_query() {
+        let store = IndexStore::in_memory().unwrap();
+
+        let parent = create_test_tx([0u8; 32], [1u8; 32], 1, 100);
+        let parent_hash = parent.id();
+        let child = create_test_tx(parent_hash, [1u8; 32], 2, 200);
+        let child_hash = child.id();
+
+        assert!(store.insert_transaction(&parent_hash, &parent).unwrap());
+        assert!(store.insert_transaction(&child_hash, &child).unwrap());
+        assert!(!store.insert_transaction(&child_hash, &child).unwrap());
+        assert_eq!(store.transaction_count().unwrap(), 2);
+
+        let indexed = store.get_transaction(&child_hash).unwrap().unwrap();
+        assert_eq!(indexed.tx, child);
+        assert!(!indexed.finalized);
+
+        assert_eq!(store.find_by_stealth_address(&[2u8; 32]).unwrap(), vec![child_hash]);
+        assert_eq!(store.find_by_key_image(&[1u8; 32]).unwrap(), Some(parent_hash));
+        assert_eq!(store.find_by_key_image(&[9u8; 32]).unwrap(), None);
+
+        assert_eq!(store.find_by_time_range(0, 150, 10).unwrap(), vec![parent_hash]);
+        assert_eq!(store.find_by_time_range(0, 300, 10).unwrap(), vec![parent_hash, child_hash]);
+        assert_eq!(store.find_by_time_range(0, 300, 1).unwrap(), vec![parent_hash]);
+
+        assert_eq!(store.parents(&child_hash).unwrap(), vec![parent_hash]);
+        assert_eq!(store.children(&parent_hash).unwrap(), vec![child_hash]);
+
+        assert_eq!(indexed.depth, 1);
+        assert_eq!(store.find_recent(10).unwrap(), vec![child_hash, parent_hash]);
+        assert_eq!(store.find_recent(1).unwrap(), vec![child_hash]);
+    }
+
+    #[test]
+    fn test_address_prefix_and_depth_stats() {
+        let store = IndexStore::in_memory().unwrap();
+        let mut parent_hash = [0u8; 32];
+        for nonce in [0x10, 0x11, 0xff] {
+            let tx = create_test_tx(parent_hash, [1u8; 32], nonce, 100);
+            parent_hash = tx.id();
+            store.insert_transaction(&parent_hash, &tx).unwrap();
+        }
+        store.mark_finalized(&parent_hash).unwrap();
+
+        let outputs = store.find_outputs_by_address_prefix(&[0x11], 10).unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].stealth_address, vec![0x11; 32]);
+        assert_eq!(store.find_outputs_by_address_prefix(&[0xff, 0xff], 10).unwrap().len(), 1);
+        assert!(store.find_outputs_by_address_prefix(&[0x12], 10).unwrap().is_empty());
+
+        assert_eq!(store.depth_stats().unwrap(), DepthStats {
+            transactions: 3,
+            finalized: 1,
+            tips: 1,
+            max_depth: 2,
+            finalized_depth: 2,
+        });
+    }
+
+    #[test]
+    fn test_prefix_successor() {
+        assert_eq!(prefix_successor(&[0x12, 0x34]), Some(vec![0x12, 0x35]));
+        assert_eq!(prefix_successor(&[0x12, 0xff]), Some(vec![0x13]));
+        assert_eq!(prefix_successor(&[0xff]), None);
+    }
+
+    #[test]
+    fn test_mark_finalized() {
+        let store = IndexStore::in_memory().unwrap();
+        let tx = create_test_tx([0u8; 32], [1u8; 32], 1, 100);
+        let hash = tx.id();
+
+        store.insert_transaction(&hash, &tx).unwrap();
+        store.mark_finalized(&hash).unwrap();
+        assert!(store.get_transaction(&hash).unwrap().unwrap().finalized);
+    }
+
+    #[test]
+    fn test_persistent_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.db");
+        let tx = create_test_tx([0u8; 32], [1u8; 32], 1, 100);
+        let hash = tx.id();
+
+        IndexStore::open(&path).unwrap().insert_transaction(&hash, &tx).unwrap();
+
+        let reopened = IndexStore::open(&path).unwrap();
+        assert_eq!(reopened.get_transaction(&hash).unwrap().unwrap().tx, tx);
+    }
+
+    #[test]
+    fn test_older_schema_is_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("index.db");
+        let parent = create_test_tx([0u8; 32], [1u8; 32], 1, 100);
+        let child = create_test_tx(parent.id(), [1u8; 32], 2, 100);
+
+        {
+            let store = IndexStore::open(&path).unwrap();
+            store.insert_transaction(&parent.id(), &parent).unwrap();
+            store.insert_transaction(&child.id(), &child).unwrap();
+        }
+        // Rewind to the first schema, which had no depth column
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch("ALTER TABLE transactions DROP COLUMN depth;").unwrap();
+            conn.pragma_update(None, "user_version", 1).unwrap();
+        }
+
+        let options = OpenOptions { backup_before_migrate: true };
+        let store = IndexStore::open_with(&path, options).unwrap();
+        assert_eq!(store.schema_version().unwrap(), SCHEMA_VERSION);
+        assert_eq!(store.transaction_count().unwrap(), 2);
+        assert_eq!(store.get_transaction(&child.id()).unwrap().unwrap().depth, 1);
+
+        let backup = Connection::open(migrations::backup_path(&path, 1)).unwrap();
+        assert_eq!(migrations::schema_version(&backup).unwrap(), 1);
+
+        drop(store);
+        Connection::open(&path).unwrap().pragma_update(None, "user_version", SCHEMA_VERSION + 1).unwrap();
+        assert!(IndexStore::open(&path).is_err());
+    }
+}
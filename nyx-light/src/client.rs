@@ -0,0 +1,237 @@
+// src/client.rs
+
+//! Header-only light client.
+//!
+//! Snapshot headers carry no validator signatures, so the client only trusts
+//! headers pinned by a checkpoint: a header hash taken from a trusted source.
+//! Headers fetched from full nodes are applied in batches that must end at
+//! such a checkpoint.
+
+use crate::errors::{LightError, Result};
+use nyx_core::finality::{InclusionProof, SnapshotHeader};
+use nyx_core::Hash;
+
+/// Light client tracking the snapshot header chain
+#[derive(Debug, Clone, Default)]
+pub struct LightClient {
+    /// Number of the snapshot before `headers[0]`
+    base: u64,
+
+    /// Hash of the header before `headers[0]` (zeros when starting at genesis)
+    base_hash: Hash,
+
+    /// Verified headers, consecutive from `base + 1`
+    headers: Vec<SnapshotHeader>,
+}
+
+impl LightClient {
+    /// Creates a client that verifies the header chain from the first snapshot
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a client trusting `checkpoint` as its starting header
+    ///
+    /// Lets wallets skip downloading the full header history; the checkpoint
+    /// must come from a trusted source.
+    pub fn from_checkpoint(checkpoint: SnapshotHeader) -> Self {
+        Self {
+            base: checkpoint.number.saturating_sub(1),
+            base_hash: checkpoint.prev_hash,
+            headers: vec![checkpoint],
+        }
+    }
+
+    /// Number of the latest verified snapshot (0 if none)
+    pub fn height(&self) -> u64 {
+        self.base + self.headers.len() as u64
+    }
+
+    /// Gets the latest verified header
+    pub fn latest(&self) -> Option<&SnapshotHeader> {
+        self.headers.last()
+    }
+
+    /// Gets a verified header by snapshot number
+    pub fn header(&self, number: u64) -> Option<&SnapshotHeader> {
+        let index = usize::try_from(number.checked_sub(self.base + 1)?).ok()?;
+        self.headers.get(index)
+    }
+
+    /// Appends headers leading to a trusted checkpoint
+    ///
+    /// Headers are not signed, so a linked chain proves nothing by itself:
+    /// the headers must end at `checkpoint`, the hash of a header obtained
+    /// from a trusted source (e.g. agreed on by several independent full
+    /// nodes). The hash links then pin every earlier header. Headers are
+    /// applied all together or not at all.
+    ///
+    /// # Errors
+    /// - `InvalidHeader` if a header is out of sequence, does not link to
+    ///   its predecessor, or the chain does not end at `checkpoint`
+    ///
+    /// # Returns
+    /// Number of headers applied
+    pub fn apply_headers<I>(&mut self, headers: I, checkpoint: &Hash) -> Result<usize>
+    where
+        I: IntoIterator<Item = SnapshotHeader>,
+    {
+        let mut expected_prev = self.latest().map_or(self.base_hash, |h| h.hash());
+        let mut verified = Vec::new();
+
+        for (expected_number, header) in (self.height() + 1..).zip(headers) {
+            if header.number != expected_number {
+                return Err(LightError::InvalidHeader(format!(
+                    "Expected snapshot {}, got {}",
+                    expected_number, header.number
+                )));
+            }
+
+            if header.prev_hash != expected_prev {
+                return Err(LightError::InvalidHeader(format!(
+                    "Snapshot {} does not link to {}",
+                    header.number,
+                    hex::encode(expected_prev)
+                )));
+            }
+
+            expected_prev = header.hash();
+            verified.push(header);
+        }
+
+        if expected_prev != *checkpoint {
+            return Err(LightError::InvalidHeader(format!(
+                "Chain ends at {}, not at trusted checkpoint {}",
+                hex::encode(expected_prev),
+                hex::encode(checkpoint)
+            )));
+        }
+
+        let applied = verified.len();
+        self.headers.extend(verified);
+        Ok(applied)
+    }
+
+    /// Verifies that a transaction was finalized
+    ///
+    /// # Errors
+    /// - `UnknownSnapshot` if the proof's snapshot header is not verified yet
+    /// - `InvalidProof` if the proof does not match the header
+    ///
+    /// # Returns
+    /// Number of snapshots finalizing the transaction, counting its own
+    pub fn verify_inclusion(&self, proof: &InclusionProof) -> Result<u64> {
+        let header = self.header(proof.snapshot)
+            .ok_or(LightError::UnknownSnapshot(proof.snapshot))?;
+
+        if !proof.verify(header) {
+            return Err(LightError::InvalidProof(format!(
+                "Transaction {} not included in snapshot {}",
+                hex::encode(proof.tx_hash),
+                proof.snapshot
+            )));
+        }
+
+        Ok(self.height() - proof.snapshot + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_core::nyx_crypto::merkle;
+
+    /// Builds a header chain where snapshot `n` finalizes `[n; 32]` and `[n + 100; 32]`
+    fn build_chain(len: u8) -> Vec<SnapshotHeader> {
+        let mut headers: Vec<SnapshotHeader> = Vec::new();
+        for n in 1..=len {
+            let header = SnapshotHeader {
+                number: n as u64,
+                prev_hash: headers.last().map_or([0u8; 32], |h| h.hash()),
+                tx_root: merkle::merkle_root(&snapshot_txs(n)),
+                tx_count: 2,
+                state_root: [n; 32],
+            };
+            headers.push(header);
+        }
+        headers
+    }
+
+    fn snapshot_txs(n: u8) -> Vec<Hash> {
+        vec![[n; 32], [n + 100; 32]]
+    }
+
+    fn proof_for(n: u8, index: usize) -> InclusionProof {
+        let txs = snapshot_txs(n);
+        InclusionProof {
+            tx_hash: txs[index],
+            snapshot: n as u64,
+            proof: merkle::prove(&txs, index).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_verify_inclusion() {
+        let chain = build_chain(3);
+        let mut client = LightClient::new();
+        assert_eq!(client.apply_headers(chain.clone(), &chain[2].hash()).unwrap(), 3);
+        assert_eq!(client.height(), 3);
+
+        assert_eq!(client.verify_inclusion(&proof_for(1, 1)).unwrap(), 3);
+        assert_eq!(client.verify_inclusion(&proof_for(3, 0)).unwrap(), 1);
+
+        let mut wrong_snapshot = proof_for(2, 0);
+        wrong_snapshot.snapshot = 1;
+        assert!(matches!(client.verify_inclusion(&wrong_snapshot), Err(LightError::InvalidProof(_))));
+
+        assert_eq!(client.verify_inclusion(&proof_for(4, 0)), Err(LightError::UnknownSnapshot(4)));
+    }
+
+    #[test]
+    fn test_rejects_broken_chain() {
+        let mut chain = build_chain(3);
+        let checkpoint = chain[2].hash();
+        let mut client = LightClient::new();
+
+        chain[2].prev_hash = [9u8; 32];
+        assert!(matches!(client.apply_headers(chain.clone(), &checkpoint), Err(LightError::InvalidHeader(_))));
+        assert_eq!(client.height(), 0);
+
+        assert!(matches!(client.apply_headers(chain.split_off(1), &checkpoint), Err(LightError::InvalidHeader(_))));
+        assert_eq!(client.height(), 0);
+    }
+
+    #[test]
+    fn test_rejects_forged_chain() {
+        let chain = build_chain(3);
+        let mut client = LightClient::new();
+
+        // A correctly linked chain finalizing other transactions
+        let mut forged: Vec<SnapshotHeader> = Vec::new();
+        for header in &chain {
+            forged.push(SnapshotHeader {
+                prev_hash: forged.last().map_or([0u8; 32], |h| h.hash()),
+                tx_root: [0xEE; 32],
+                ..header.clone()
+            });
+        }
+
+        assert!(matches!(client.apply_headers(forged, &chain[2].hash()), Err(LightError::InvalidHeader(_))));
+        assert_eq!(client.height(), 0);
+
+        assert_eq!(client.apply_headers(chain.clone(), &chain[2].hash()).unwrap(), 3);
+        assert_eq!(client.apply_headers(Vec::new(), &chain[2].hash()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_from_checkpoint() {
+        let chain = build_chain(4);
+        let mut client = LightClient::from_checkpoint(chain[1].clone());
+
+        assert_eq!(client.apply_headers(chain[2..].to_vec(), &chain[3].hash()).unwrap(), 2);
+        assert_eq!(client.height(), 4);
+        assert!(client.header(1).is_none());
+        assert_eq!(client.verify_inclusion(&proof_for(2, 1)).unwrap(), 3);
+        assert_eq!(client.verify_inclusion(&proof_for(1, 0)), Err(LightError::UnknownSnapshot(1)));
+    }
+}
@@ -0,0 +1,43 @@
+// src/errors.rs
+
+//! Error types for light client operations.
+
+use std::fmt;
+
+/// Main error type for light client operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LightError {
+    /// Header does not extend the known header chain
+    InvalidHeader(String),
+
+    /// Proof refers to a snapshot whose header is not known
+    UnknownSnapshot(u64),
+
+    /// Proof does not match the snapshot's transaction root
+    InvalidProof(String),
+}
+
+impl fmt::Display for LightError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LightError::InvalidHeader(msg) => write!(f, "Invalid header: {}", msg),
+            LightError::UnknownSnapshot(number) => write!(f, "Unknown snapshot: {}", number),
+            LightError::InvalidProof(msg) => write!(f, "Invalid proof: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for LightError {}
+
+/// Result type alias for light client operations
+pub type Result<T> = std::result::Result<T, LightError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(LightError::UnknownSnapshot(7).to_string(), "Unknown snapshot: 7");
+    }
+}
@@ -0,0 +1,41 @@
+// src/lib.rs
+
+//! # Nyx Light Client
+//!
+//! SPV-style verification for devices that cannot hold the full DAG.
+//!
+//! Full nodes publish a [`SnapshotHeader`] for every PoS snapshot, each
+//! committing to the transactions it finalized and chaining to the previous
+//! header. A [`LightClient`] keeps only that header chain and checks the
+//! [`InclusionProof`]s full nodes serve for individual transactions.
+//!
+//! Headers are not signed, so the chain is only trusted up to checkpoints:
+//! header hashes obtained from a trusted source, such as several independent
+//! full nodes agreeing on the latest header. A chain that does not end at a
+//! checkpoint is rejected, however well it links.
+//!
+//! ## Example Usage
+//!
+//! ```rust
+//! use nyx_light::LightClient;
+//!
+//! let mut client = LightClient::new();
+//! assert_eq!(client.height(), 0);
+//!
+//! // Headers fetched from a full node (e.g. `GET /light/headers?from=1`),
+//! // ending at a trusted checkpoint
+//! let checkpoint = [0u8; 32];
+//! client.apply_headers(Vec::new(), &checkpoint).unwrap();
+//! ```
+
+#![warn(missing_docs)]
+#![warn(clippy::all)]
+#![deny(unsafe_code)]
+
+pub mod errors;
+pub mod client;
+
+// Re-export commonly used types
+pub use crate::errors::{LightError, Result};
+pub use crate::client::LightClient;
+pub use nyx_core::finality::{InclusionProof, SnapshotHeader};
@@ -1,6 +1,6 @@
 //! Broadcasting transactions example
 
-use nyx_network::{Node, NodeConfig};
+use nyx_network::{Node, NodeConfig, NodeIdentity};
 use nyx_core::{
     transaction::{Transaction, TxInput, TxOutput},
     RingSignature,
@@ -8,7 +8,7 @@ use nyx_core::{
 use tokio::time::{sleep, Duration};
 
 fn create_test_transaction(nonce: u8) -> Transaction {
-    Transaction::new(
+    Transaction::try_new(
         vec![TxInput {
             prev_tx: [nonce; 32],
             index: 0,
@@ -20,16 +20,18 @@ fn create_test_transaction(nonce: u8) -> Transaction {
             amount_commitment: vec![nonce; 32],
             range_proof: vec![nonce; 64],
             ephemeral_pubkey: vec![nonce; 32],
+            unlock_time: None,
+            htlc: None,
         }],
-        RingSignature {
+        vec![RingSignature {
             ring_members: vec![vec![nonce; 32]; 4],
             signature: vec![nonce; 64],
             key_image: [nonce; 32],
-        },
+        }],
         vec![nonce; 32],
         [0u8; 32],
         [1u8; 32],
-    )
+    ).unwrap()
 }
 
 #[tokio::main]
@@ -44,7 +46,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Node 1
     let config1 = NodeConfig {
         listen_addr: "127.0.0.1:8100".parse()?,
-        node_id: vec![1u8; 32],
+        identity: Some(NodeIdentity::from_seed([1u8; 32])),
         ..Default::default()
     };
     let node1 = Node::new(config1.clone()).await?;
@@ -59,7 +61,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Node 2
     let config2 = NodeConfig {
         listen_addr: "127.0.0.1:8101".parse()?,
-        node_id: vec![2u8; 32],
+        identity: Some(NodeIdentity::from_seed([2u8; 32])),
         bootstrap_peers: vec![config1.listen_addr],
         ..Default::default()
     };
@@ -75,7 +77,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Node 3
     let config3 = NodeConfig {
         listen_addr: "127.0.0.1:8102".parse()?,
-        node_id: vec![3u8; 32],
+        identity: Some(NodeIdentity::from_seed([3u8; 32])),
         bootstrap_peers: vec![config1.listen_addr, config2.listen_addr],
         ..Default::default()
     };
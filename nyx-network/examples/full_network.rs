@@ -1,6 +1,6 @@
 //! Full network example - 5 nodes with transaction broadcasting
 
-use nyx_network::{Node, NodeConfig};
+use nyx_network::{Node, NodeConfig, NodeIdentity};
 use nyx_core::{
     transaction::{Transaction, TxInput, TxOutput},
     RingSignature,
@@ -8,7 +8,7 @@ use nyx_core::{
 use tokio::time::{sleep, Duration};
 
 fn create_transaction(id: u8) -> Transaction {
-    Transaction::new(
+    Transaction::try_new(
         vec![TxInput {
             prev_tx: [id; 32],
             index: 0,
@@ -20,16 +20,18 @@ fn create_transaction(id: u8) -> Transaction {
             amount_commitment: vec![id; 32],
             range_proof: vec![id; 64],
             ephemeral_pubkey: vec![id; 32],
+            unlock_time: None,
+            htlc: None,
         }],
-        RingSignature {
+        vec![RingSignature {
             ring_members: vec![vec![id; 32]; 4],
             signature: vec![id; 64],
             key_image: [id; 32],
-        },
+        }],
         vec![id; 32],
         [0u8; 32],
         [1u8; 32],
-    )
+    ).unwrap()
 }
 
 #[tokio::main]
@@ -49,10 +51,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         let config = NodeConfig {
             listen_addr: addr,
-            node_id: vec![i as u8; 32],
+            identity: Some(NodeIdentity::from_seed([i as u8; 32])),
             bootstrap_peers: bootstrap_peers.clone(),
             max_peers: 10,
             min_peers: 2,
+            ..Default::default()
         };
 
         let node = Node::new(config.clone()).await?;
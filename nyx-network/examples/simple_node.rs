@@ -1,6 +1,6 @@
 //! Simple node example - starts a single network node
 
-use nyx_network::{Node, NodeConfig};
+use nyx_network::{Node, NodeConfig, NodeIdentity};
 use tokio::time::{sleep, Duration};
 
 #[tokio::main]
@@ -16,17 +16,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         max_peers: 50,
         min_peers: 8,
         bootstrap_peers: vec![],
-        node_id: vec![1u8; 32],
+        identity: Some(NodeIdentity::from_seed([1u8; 32])),
+        ..Default::default()
     };
 
     println!("📍 Node configuration:");
     println!("   Address: {}", config.listen_addr);
-    println!("   Max peers: {}", config.max_peers);
-    println!("   Node ID: {}\n", hex::encode(&config.node_id));
+    println!("   Max peers: {}\n", config.max_peers);
 
     // Create node
     let node = Node::new(config.clone()).await?;
-    println!("✅ Node created successfully!\n");
+    println!("✅ Node created successfully!");
+    println!("   Node ID: {}\n", hex::encode(node.peer_id()));
 
     // Get initial stats
     let stats = node.stats().await;
@@ -1,6 +1,6 @@
 //! Two nodes example - demonstrates peer connection
 
-use nyx_network::{Node, NodeConfig};
+use nyx_network::{Node, NodeConfig, NodeIdentity};
 use tokio::time::{sleep, Duration};
 
 #[tokio::main]
@@ -16,7 +16,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         max_peers: 50,
         min_peers: 1,
         bootstrap_peers: vec![],
-        node_id: vec![1u8; 32],
+        identity: Some(NodeIdentity::from_seed([1u8; 32])),
+        ..Default::default()
     };
 
     let node1 = Node::new(config1.clone()).await?;
@@ -40,7 +41,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         max_peers: 50,
         min_peers: 1,
         bootstrap_peers: vec![config1.listen_addr],
-        node_id: vec![2u8; 32],
+        identity: Some(NodeIdentity::from_seed([2u8; 32])),
+        ..Default::default()
     };
 
     let node2 = Node::new(config2.clone()).await?;
@@ -0,0 +1,486 @@
+// src/address_book.rs
+
+//! Persistent address book of known peers.
+//!
+//! Every successful outbound connection is recorded with its last-seen time
+//! and a quality score. The book is saved as JSON in the node data
+//! directory and loaded at startup, so a restarted node reconnects to peers
+//! that worked recently before falling back to bootstrap nodes. Addresses
+//! are bucketed by network group to resist eclipse attacks.
+
+use crate::errors::Result;
+use crate::message::current_timestamp;
+use crate::{ADDRESS_BUCKET_SIZE, NEW_BUCKET_COUNT, NEW_BUCKETS_PER_SOURCE_GROUP, TRIED_BUCKET_COUNT, TRIED_BUCKETS_PER_GROUP};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+
+/// File name of the address book inside the data directory
+pub const ADDRESS_BOOK_FILE: &str = "peers.json";
+
+/// Score of a newly recorded peer
+const INITIAL_SCORE: i32 = 10;
+
+/// Score gained per successful connection
+const SUCCESS_REWARD: i32 = 5;
+
+/// Score lost per failed connection attempt
+const FAILURE_PENALTY: i32 = 10;
+
+/// Highest attainable score
+const MAX_SCORE: i32 = 100;
+
+/// Known peer address with its connection history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressEntry {
+    /// Peer's network address
+    pub address: SocketAddr,
+
+    /// Unix time of the last successful connection; zero if never
+    /// connected
+    pub last_seen: u64,
+
+    /// Quality score; the entry is forgotten once it drops to zero
+    pub score: i32,
+
+    /// Whether we ever connected to the address; untried addresses were
+    /// only heard of
+    #[serde(default = "default_tried")]
+    pub tried: bool,
+
+    /// Address of whoever told us about an untried address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<IpAddr>,
+}
+
+/// Books saved before the tried table only held connected peers
+fn default_tried() -> bool {
+    true
+}
+
+/// Address book file contents
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StoredBook {
+    /// Current format, with the bucket key
+    Keyed {
+        /// Hex-encoded bucket key
+        key: String,
+        entries: Vec<AddressEntry>,
+    },
+
+    /// Entries only, as saved before bucketing
+    Legacy(Vec<AddressEntry>),
+}
+
+/// Known peer addresses, optionally backed by a file
+///
+/// Like Bitcoin's addrman, addresses live in two tables of fixed-size
+/// buckets: *tried* for addresses we connected to and *new* for addresses
+/// we only heard of. Buckets are picked by a keyed hash of the address's
+/// [`network_group`] (and, for new addresses, of who announced it), so the
+/// addresses of one subnet, or announced by one subnet, can fill only a few
+/// buckets and never crowd out everybody else.
+#[derive(Debug)]
+pub struct AddressBook {
+    /// File the book is saved to
+    path: Option<PathBuf>,
+
+    /// Secret key of the bucket hashes, so others cannot aim at a bucket
+    key: [u8; 32],
+
+    /// Entries by address
+    entries: HashMap<SocketAddr, AddressEntry>,
+
+    /// Addresses in each bucket of the new table
+    new_buckets: Vec<Vec<SocketAddr>>,
+
+    /// Addresses in each bucket of the tried table
+    tried_buckets: Vec<Vec<SocketAddr>>,
+}
+
+impl Default for AddressBook {
+    fn default() -> Self {
+        Self::with_key(rand::random())
+    }
+}
+
+impl AddressBook {
+    /// Creates an empty in-memory address book
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty in-memory address book bucketing with `key`
+    fn with_key(key: [u8; 32]) -> Self {
+        Self {
+            path: None,
+            key,
+            entries: HashMap::new(),
+            new_buckets: vec![Vec::new(); NEW_BUCKET_COUNT],
+            tried_buckets: vec![Vec::new(); TRIED_BUCKET_COUNT],
+        }
+    }
+
+    /// Loads the address book stored in `data_dir`
+    ///
+    /// A missing file yields an empty book that will be saved there.
+    ///
+    /// # Errors
+    /// - `IoError` if the file cannot be read
+    /// - `SerializationError` if the file is corrupt
+    pub fn load(data_dir: &Path) -> Result<Self> {
+        Self::load_file(&data_dir.join(ADDRESS_BOOK_FILE))
+    }
+
+    /// Loads the address book stored in the file at `path`
+    ///
+    /// A missing file yields an empty book that will be saved there.
+    ///
+    /// # Errors
+    /// - `IoError` if the file cannot be read
+    /// - `SerializationError` if the file is corrupt
+    pub fn load_file(path: &Path) -> Result<Self> {
+        let path = path.to_path_buf();
+        if !path.exists() {
+            return Ok(Self { path: Some(path), ..Self::new() });
+        }
+
+        let json = fs::read_to_string(&path)?;
+        let (key, entries) = match serde_json::from_str(&json)? {
+            StoredBook::Keyed { key, entries } => {
+                let key = hex::decode(&key).ok().and_then(|b| b.try_into().ok()).unwrap_or_else(rand::random);
+                (key, entries)
+            }
+            StoredBook::Legacy(entries) => (rand::random(), entries),
+        };
+
+        let mut book = Self { path: Some(path), ..Self::with_key(key) };
+        for entry in entries {
+            book.insert(entry);
+        }
+        Ok(book)
+    }
+
+    /// Saves the address book to its file
+    ///
+    /// Does nothing for an in-memory book. The file is replaced atomically.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut entries: Vec<AddressEntry> = self.entries.values().cloned().collect();
+        entries.sort_by_key(|e| e.address);
+        let stored = StoredBook::Keyed { key: hex::encode(self.key), entries };
+
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(&stored)?)?;
+        fs::rename(&tmp, path)?;
+
+        Ok(())
+    }
+
+    /// Adds an address announced by `source` to the new table
+    ///
+    /// A full bucket drops its worst address to make room.
+    ///
+    /// # Returns
+    /// Whether the address was unknown
+    pub fn add_new(&mut self, address: SocketAddr, source: IpAddr) -> bool {
+        if self.entries.contains_key(&address) {
+            return false;
+        }
+
+        self.insert(AddressEntry {
+            address,
+            last_seen: 0,
+            score: INITIAL_SCORE,
+            tried: false,
+            source: Some(source),
+        });
+        true
+    }
+
+    /// Records a successful connection to `address`
+    pub fn record_success(&mut self, address: SocketAddr) {
+        self.record_success_at(address, current_timestamp());
+    }
+
+    /// Records a successful connection to `address` at time `now`
+    ///
+    /// The address moves to the tried table. If its bucket there is full,
+    /// the bucket's stalest address moves back to the new table.
+    pub fn record_success_at(&mut self, address: SocketAddr, now: u64) {
+        let entry = match self.remove(&address) {
+            Some(entry) => AddressEntry {
+                last_seen: now,
+                score: (entry.score + SUCCESS_REWARD).min(MAX_SCORE),
+                tried: true,
+                source: None,
+                ..entry
+            },
+            None => AddressEntry { address, last_seen: now, score: INITIAL_SCORE, tried: true, source: None },
+        };
+        self.insert(entry);
+    }
+
+    /// Records a failed connection attempt to a known address
+    ///
+    /// Unknown addresses are not added.
+    pub fn record_failure(&mut self, address: &SocketAddr) {
+        if let Some(entry) = self.entries.get_mut(address) {
+            entry.score -= FAILURE_PENALTY;
+            if entry.score <= 0 {
+                self.remove(address);
+            }
+        }
+    }
+
+    /// Gets the entry for an address
+    pub fn get(&self, address: &SocketAddr) -> Option<&AddressEntry> {
+        self.entries.get(address)
+    }
+
+    /// Known addresses to dial
+    ///
+    /// Tried addresses come first, most recently successful first with ties
+    /// broken by higher score, followed by new addresses by score.
+    pub fn candidates(&self) -> Vec<SocketAddr> {
+        let mut entries: Vec<&AddressEntry> = self.entries.values().collect();
+        entries.sort_by(|a, b| {
+            b.tried.cmp(&a.tried)
+                .then(b.last_seen.cmp(&a.last_seen))
+                .then(b.score.cmp(&a.score))
+                .then(a.address.cmp(&b.address))
+        });
+        entries.into_iter().map(|e| e.address).collect()
+    }
+
+    /// Number of known addresses
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks whether no addresses are known
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of addresses in the tried table
+    pub fn tried_len(&self) -> usize {
+        self.entries.values().filter(|e| e.tried).count()
+    }
+
+    /// Places an entry in its bucket, evicting from a full bucket
+    fn insert(&mut self, entry: AddressEntry) {
+        if self.entries.contains_key(&entry.address) {
+            return;
+        }
+
+        let address = entry.address;
+        let bucket = self.bucket_of(&entry);
+        let full = self.bucket(entry.tried, bucket).len() >= ADDRESS_BUCKET_SIZE;
+        if full {
+            let worst = self.bucket(entry.tried, bucket)
+                .iter()
+                .filter_map(|a| self.entries.get(a))
+                .min_by_key(|e| (e.last_seen, e.score, e.address))
+                .map(|e| e.address);
+            if let Some(evicted) = worst.and_then(|a| self.remove(&a)) {
+                // Stale tried addresses get another chance as new ones
+                if evicted.tried {
+                    let source = evicted.address.ip();
+                    self.insert(AddressEntry { tried: false, source: Some(source), ..evicted });
+                }
+            }
+        }
+
+        self.bucket_mut(entry.tried, bucket).push(address);
+        self.entries.insert(address, entry);
+    }
+
+    /// Removes an entry from the book and its bucket
+    fn remove(&mut self, address: &SocketAddr) -> Option<AddressEntry> {
+        let entry = self.entries.remove(address)?;
+        let bucket = self.bucket_of(&entry);
+        self.bucket_mut(entry.tried, bucket).retain(|a| a != address);
+        Some(entry)
+    }
+
+    fn bucket(&self, tried: bool, index: usize) -> &Vec<SocketAddr> {
+        if tried { &self.tried_buckets[index] } else { &self.new_buckets[index] }
+    }
+
+    fn bucket_mut(&mut self, tried: bool, index: usize) -> &mut Vec<SocketAddr> {
+        if tried { &mut self.tried_buckets[index] } else { &mut self.new_buckets[index] }
+    }
+
+    /// Bucket of an entry in its table
+    ///
+    /// A network group spreads over [`TRIED_BUCKETS_PER_GROUP`] tried
+    /// buckets; the addresses one source group announces spread over
+    /// [`NEW_BUCKETS_PER_SOURCE_GROUP`] new buckets.
+    fn bucket_of(&self, entry: &AddressEntry) -> usize {
+        let group = network_group(&entry.address);
+        if entry.tried {
+            let slot = self.keyed_hash(&[b"tried", entry.address.to_string().as_bytes()]) % TRIED_BUCKETS_PER_GROUP as u64;
+            (self.keyed_hash(&[b"tried", &group, &slot.to_le_bytes()]) % TRIED_BUCKET_COUNT as u64) as usize
+        } else {
+            let source = entry.source.map(|ip| network_group(&SocketAddr::new(ip, 0))).unwrap_or_else(|| group.clone());
+            let slot = self.keyed_hash(&[b"new", &source, &group]) % NEW_BUCKETS_PER_SOURCE_GROUP as u64;
+            (self.keyed_hash(&[b"new", &source, &slot.to_le_bytes()]) % NEW_BUCKET_COUNT as u64) as usize
+        }
+    }
+
+    fn keyed_hash(&self, parts: &[&[u8]]) -> u64 {
+        let mut data = self.key.to_vec();
+        for part in parts {
+            data.extend_from_slice(&(part.len() as u32).to_le_bytes());
+            data.extend_from_slice(part);
+        }
+        let hash = nyx_core::hash::blake3_hash(&data);
+        u64::from_le_bytes(hash[..8].try_into().expect("hash is 32 bytes"))
+    }
+}
+
+/// Network group of an address: its IPv4 /16 or IPv6 /32
+///
+/// Addresses in one group are likely run by one operator. Loopback
+/// addresses are all on this machine, so each endpoint is its own group.
+pub fn network_group(address: &SocketAddr) -> Vec<u8> {
+    let ip = match address.ip() {
+        IpAddr::V6(ip) => ip.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(IpAddr::V6(ip)),
+        ip => ip,
+    };
+
+    if ip.is_loopback() || ip.is_unspecified() {
+        let mut group = vec![0];
+        group.extend_from_slice(address.to_string().as_bytes());
+        return group;
+    }
+
+    match ip {
+        IpAddr::V4(ip) => vec![4, ip.octets()[0], ip.octets()[1]],
+        IpAddr::V6(ip) => [&[6][..], &ip.octets()[..4]].concat(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(port: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], port))
+    }
+
+    #[test]
+    fn test_candidate_order() {
+        let mut book = AddressBook::new();
+        book.record_success(addr(1));
+        book.record_success(addr(2));
+        book.record_success(addr(2));
+        book.entries.get_mut(&addr(1)).unwrap().last_seen -= 60;
+
+        assert_eq!(book.candidates(), vec![addr(2), addr(1)]);
+        assert_eq!(book.get(&addr(2)).unwrap().score, INITIAL_SCORE + SUCCESS_REWARD);
+    }
+
+    #[test]
+    fn test_failures_forget_peer() {
+        let mut book = AddressBook::new();
+        book.record_failure(&addr(1));
+        assert!(book.is_empty());
+
+        book.record_success(addr(1));
+        book.record_failure(&addr(1));
+        assert!(book.is_empty());
+    }
+
+    #[test]
+    fn test_persistence() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dir = temp_dir.path().join("data");
+
+        let mut book = AddressBook::load(&dir).unwrap();
+        assert!(book.is_empty());
+        book.record_success(addr(1));
+        book.record_success(addr(2));
+        book.save().unwrap();
+
+        let loaded = AddressBook::load(&dir).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.get(&addr(1)), book.get(&addr(1)));
+
+        fs::write(dir.join(ADDRESS_BOOK_FILE), "not json").unwrap();
+        assert!(AddressBook::load(&dir).is_err());
+    }
+
+    #[test]
+    fn test_network_group() {
+        let group = |a: &str| network_group(&a.parse().unwrap());
+        assert_eq!(group("1.2.3.4:8000"), group("1.2.200.1:9000"));
+        assert_ne!(group("1.2.3.4:8000"), group("1.3.3.4:8000"));
+        assert_eq!(group("[::ffff:1.2.3.4]:8000"), group("1.2.3.4:8000"));
+        assert_eq!(group("[2001:db8::1]:8000"), group("[2001:db8:ffff::1]:8000"));
+        assert_ne!(group("127.0.0.1:1"), group("127.0.0.1:2"));
+    }
+
+    #[test]
+    fn test_one_source_fills_few_new_buckets() {
+        let mut book = AddressBook::new();
+        let attacker: IpAddr = "6.6.6.6".parse().unwrap();
+        for i in 0..10_000u32 {
+            let [a, b, c, d] = (i + 0x0A00_0000).to_be_bytes();
+            book.add_new(SocketAddr::from(([a, b, c, d], 8000)), attacker);
+        }
+        assert!(book.len() <= NEW_BUCKETS_PER_SOURCE_GROUP * ADDRESS_BUCKET_SIZE);
+
+        // Announcements from elsewhere still find room
+        assert!(book.add_new("9.9.9.9:8000".parse().unwrap(), "8.8.8.8".parse().unwrap()));
+        assert!(book.get(&"9.9.9.9:8000".parse().unwrap()).is_some());
+    }
+
+    #[test]
+    fn test_one_group_fills_few_tried_buckets() {
+        let mut book = AddressBook::new();
+        for port in 0..1000 {
+            book.record_success(SocketAddr::from(([5, 5, 1, 1], port)));
+        }
+        assert!(book.tried_len() <= TRIED_BUCKETS_PER_GROUP * ADDRESS_BUCKET_SIZE);
+        // Evicted tried addresses fall back to the new table
+        assert!(book.len() > book.tried_len());
+
+        book.record_success("7.7.7.7:8000".parse().unwrap());
+        assert!(book.get(&"7.7.7.7:8000".parse().unwrap()).unwrap().tried);
+    }
+
+    #[test]
+    fn test_new_address_promoted_on_success() {
+        let mut book = AddressBook::new();
+        let address: SocketAddr = "1.2.3.4:8000".parse().unwrap();
+        assert!(book.add_new(address, "8.8.8.8".parse().unwrap()));
+        assert!(!book.add_new(address, "8.8.8.8".parse().unwrap()));
+        assert_eq!(book.tried_len(), 0);
+
+        book.record_success(address);
+        assert_eq!(book.tried_len(), 1);
+        assert_eq!(book.get(&address).unwrap().source, None);
+    }
+
+    #[test]
+    fn test_loads_legacy_format() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let legacy = r#"[{"address":"127.0.0.1:1","last_seen":5,"score":10}]"#;
+        fs::write(temp_dir.path().join(ADDRESS_BOOK_FILE), legacy).unwrap();
+
+        let book = AddressBook::load(temp_dir.path()).unwrap();
+        assert!(book.get(&addr(1)).unwrap().tried);
+    }
+}
@@ -0,0 +1,202 @@
+// src/bandwidth.rs
+
+//! Bandwidth throttling.
+//!
+//! Upload and download can be capped node-wide and per peer. Each cap is a
+//! token bucket of bytes that refills at the configured rate and holds one
+//! second's worth. Readers wait after a read until the bytes are paid for,
+//! which stops reading and lets TCP push back on the sender; writers wait
+//! before a write.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Bandwidth caps in bytes per second; zero leaves a direction unlimited
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BandwidthLimits {
+    /// Upload cap across all peers
+    pub upload_bytes_per_sec: u64,
+
+    /// Download cap across all peers
+    pub download_bytes_per_sec: u64,
+
+    /// Upload cap of each peer
+    pub peer_upload_bytes_per_sec: u64,
+
+    /// Download cap of each peer
+    pub peer_download_bytes_per_sec: u64,
+}
+
+/// Token bucket limiting a byte rate
+#[derive(Debug)]
+pub struct RateLimit {
+    /// Bytes per second, also the bucket capacity
+    rate: f64,
+
+    /// Available bytes, negative while in debt, and when last updated
+    state: Mutex<(f64, Instant)>,
+}
+
+impl RateLimit {
+    /// Creates a limit of `bytes_per_sec`
+    pub fn new(bytes_per_sec: u64) -> Self {
+        let rate = bytes_per_sec.max(1) as f64;
+        Self { rate, state: Mutex::new((rate, Instant::now())) }
+    }
+
+    /// Takes `bytes` from the bucket
+    ///
+    /// Transfers larger than the bucket are let through by going into
+    /// debt, which later transfers wait out.
+    ///
+    /// # Returns
+    /// How long to wait before the bytes are covered
+    pub fn reserve(&self, bytes: usize) -> Duration {
+        self.reserve_at(bytes, Instant::now())
+    }
+
+    fn reserve_at(&self, bytes: usize, now: Instant) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let (tokens, updated) = &mut *state;
+        let elapsed = now.saturating_duration_since(*updated).as_secs_f64();
+        *tokens = (*tokens + elapsed * self.rate).min(self.rate) - bytes as f64;
+        *updated = now;
+
+        if *tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-*tokens / self.rate)
+        }
+    }
+}
+
+/// Caps applying to one direction of a connection
+///
+/// Clones share the same buckets.
+#[derive(Clone, Debug, Default)]
+pub struct Throttle {
+    limits: Vec<Arc<RateLimit>>,
+}
+
+impl Throttle {
+    /// Creates a throttle without caps
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    /// Adds a cap
+    pub fn with_limit(mut self, limit: Arc<RateLimit>) -> Self {
+        self.limits.push(limit);
+        self
+    }
+
+    /// Checks whether no cap applies
+    pub fn is_unlimited(&self) -> bool {
+        self.limits.is_empty()
+    }
+
+    /// Waits until every cap allows `bytes` more
+    pub async fn consume(&self, bytes: usize) {
+        let wait = self.limits.iter()
+            .map(|limit| limit.reserve(bytes))
+            .max()
+            .unwrap_or_default();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// Node-wide caps, handing out the throttles of each connection
+#[derive(Debug)]
+pub struct Bandwidth {
+    limits: BandwidthLimits,
+    upload: Option<Arc<RateLimit>>,
+    download: Option<Arc<RateLimit>>,
+}
+
+impl Bandwidth {
+    /// Creates the node-wide buckets of `limits`
+    pub fn new(limits: BandwidthLimits) -> Self {
+        Self {
+            limits,
+            upload: limit(limits.upload_bytes_per_sec),
+            download: limit(limits.download_bytes_per_sec),
+        }
+    }
+
+    /// Gets the configured caps
+    pub fn limits(&self) -> BandwidthLimits {
+        self.limits
+    }
+
+    /// Upload and download throttles for a new connection, sharing the
+    /// node-wide buckets and with buckets of its own
+    pub fn connection(&self) -> (Throttle, Throttle) {
+        let throttle = |shared: &Option<Arc<RateLimit>>, own: u64| {
+            shared.iter().cloned()
+                .chain(limit(own))
+                .fold(Throttle::unlimited(), Throttle::with_limit)
+        };
+        (
+            throttle(&self.upload, self.limits.peer_upload_bytes_per_sec),
+            throttle(&self.download, self.limits.peer_download_bytes_per_sec),
+        )
+    }
+}
+
+/// Bucket of a cap, if any
+fn limit(bytes_per_sec: u64) -> Option<Arc<RateLimit>> {
+    (bytes_per_sec > 0).then(|| Arc::new(RateLimit::new(bytes_per_sec)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_burst_and_debt() {
+        let limit = RateLimit::new(1000);
+        let start = Instant::now();
+
+        assert_eq!(limit.reserve_at(600, start), Duration::ZERO);
+        assert_eq!(limit.reserve_at(400, start), Duration::ZERO);
+        assert_eq!(limit.reserve_at(500, start), Duration::from_millis(500));
+
+        // The debt is paid off after half a second
+        assert_eq!(limit.reserve_at(0, start + Duration::from_millis(500)), Duration::ZERO);
+        // The bucket never holds more than a second's worth
+        assert_eq!(limit.reserve_at(1500, start + Duration::from_secs(60)), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_connection_throttles() {
+        let bandwidth = Bandwidth::new(BandwidthLimits {
+            upload_bytes_per_sec: 1000,
+            peer_download_bytes_per_sec: 500,
+            ..BandwidthLimits::default()
+        });
+        let (upload, download) = bandwidth.connection();
+        assert_eq!(upload.limits.len(), 1);
+        assert_eq!(download.limits.len(), 1);
+
+        // Every connection shares the node-wide upload bucket
+        let (other_upload, _) = bandwidth.connection();
+        assert!(Arc::ptr_eq(&upload.limits[0], &other_upload.limits[0]));
+
+        let (upload, download) = Bandwidth::new(BandwidthLimits::default()).connection();
+        assert!(upload.is_unlimited() && download.is_unlimited());
+    }
+
+    #[tokio::test]
+    async fn test_throttle_waits() {
+        let throttle = Throttle::unlimited().with_limit(Arc::new(RateLimit::new(1000)));
+        throttle.consume(1000).await;
+
+        let start = Instant::now();
+        throttle.consume(100).await;
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+}
@@ -0,0 +1,284 @@
+// src/codec.rs
+
+//! Length-delimited message framing.
+//!
+//! Every message travels as a 4-byte big-endian length followed by its
+//! encoding. [`FrameCodec`] turns messages into frames and reassembles
+//! messages from buffered bytes; [`FramedReader`] and [`FramedWriter`]
+//! apply it to the two halves of a TCP connection, so no caller handles
+//! raw length prefixes.
+
+use crate::bandwidth::Throttle;
+use crate::errors::{NetworkError, Result};
+use crate::message::Message;
+use crate::peer::PeerTraffic;
+use crate::MAX_MESSAGE_SIZE;
+use nyx_core::DecodeLimits;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+
+/// Size of the length prefix
+const LENGTH_PREFIX_SIZE: usize = 4;
+
+/// Initial capacity of a reader's buffer
+const READ_BUFFER_CAPACITY: usize = 8 * 1024;
+
+/// Length-delimited message codec
+#[derive(Clone, Copy, Debug)]
+pub struct FrameCodec {
+    /// Largest accepted frame payload
+    max_frame_size: usize,
+
+    /// Limits applied to decoded messages
+    limits: DecodeLimits,
+}
+
+impl FrameCodec {
+    /// Creates a codec applying `limits` to decoded messages
+    pub fn new(limits: DecodeLimits) -> Self {
+        Self {
+            max_frame_size: MAX_MESSAGE_SIZE,
+            limits,
+        }
+    }
+
+    /// Sets the largest accepted frame payload
+    pub fn with_max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Appends the frame of a message to `dst`
+    ///
+    /// # Errors
+    /// - `InvalidMessage` if the message exceeds the maximum frame size
+    pub fn encode(&self, message: &Message, dst: &mut Vec<u8>) -> Result<()> {
+        let data = message.to_bytes()?;
+        self.check_size(data.len())?;
+
+        dst.reserve(LENGTH_PREFIX_SIZE + data.len());
+        dst.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        dst.extend_from_slice(&data);
+        Ok(())
+    }
+
+    /// Takes the first complete frame out of `src` and decodes it
+    ///
+    /// A frame is consumed even if its message fails to decode, so the
+    /// buffer stays aligned on frame boundaries.
+    ///
+    /// # Returns
+    /// `None` if `src` does not hold a complete frame yet
+    ///
+    /// # Errors
+    /// - `InvalidMessage` if the announced length exceeds the maximum
+    ///   frame size
+    /// - `LimitExceeded` or `SerializationError` if the message is invalid
+    pub fn decode(&self, src: &mut Vec<u8>) -> Result<Option<Message>> {
+        let Some(prefix) = src.first_chunk::<LENGTH_PREFIX_SIZE>() else {
+            return Ok(None);
+        };
+        let len = u32::from_be_bytes(*prefix) as usize;
+        self.check_size(len)?;
+
+        if src.len() < LENGTH_PREFIX_SIZE + len {
+            return Ok(None);
+        }
+
+        let frame: Vec<u8> = src.drain(..LENGTH_PREFIX_SIZE + len).collect();
+        Message::from_bytes_with_limits(&frame[LENGTH_PREFIX_SIZE..], &self.limits).map(Some)
+    }
+
+    fn check_size(&self, len: usize) -> Result<()> {
+        if len > self.max_frame_size {
+            return Err(NetworkError::InvalidMessage(
+                format!("Message too large: {} bytes", len)
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for FrameCodec {
+    fn default() -> Self {
+        Self::new(DecodeLimits::default())
+    }
+}
+
+/// Encodes a message as a length-prefixed wire frame
+///
+/// # Errors
+/// - `InvalidMessage` if the message exceeds [`MAX_MESSAGE_SIZE`]
+pub fn encode_frame(message: &Message) -> Result<Vec<u8>> {
+    let mut frame = Vec::new();
+    FrameCodec::default().encode(message, &mut frame)?;
+    Ok(frame)
+}
+
+/// Read half of a framed connection
+pub struct FramedReader {
+    /// Socket read half
+    inner: OwnedReadHalf,
+
+    /// Codec decoding received frames
+    codec: FrameCodec,
+
+    /// Bytes received but not yet decoded
+    buffer: Vec<u8>,
+
+    /// Counts received bytes
+    traffic: Arc<PeerTraffic>,
+
+    /// Download caps
+    throttle: Throttle,
+}
+
+impl FramedReader {
+    /// Wraps a socket read half
+    pub fn new(inner: OwnedReadHalf, codec: FrameCodec) -> Self {
+        Self {
+            inner,
+            codec,
+            buffer: Vec::with_capacity(READ_BUFFER_CAPACITY),
+            traffic: Arc::default(),
+            throttle: Throttle::unlimited(),
+        }
+    }
+
+    /// Counts received bytes in `traffic`
+    pub fn with_traffic(mut self, traffic: Arc<PeerTraffic>) -> Self {
+        self.traffic = traffic;
+        self
+    }
+
+    /// Caps the download rate
+    pub fn with_throttle(mut self, throttle: Throttle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Receives the next message
+    ///
+    /// # Errors
+    /// - `ConnectionError` if the peer closed the connection
+    /// - any error of [`FrameCodec::decode`]
+    pub async fn recv(&mut self) -> Result<Message> {
+        loop {
+            if let Some(message) = self.codec.decode(&mut self.buffer)? {
+                return Ok(message);
+            }
+
+            let read = self.inner.read_buf(&mut self.buffer).await?;
+            if read == 0 {
+                return Err(NetworkError::ConnectionError("Connection closed by peer".to_string()));
+            }
+            self.traffic.record_received(read);
+            self.throttle.consume(read).await;
+        }
+    }
+}
+
+/// Write half of a framed connection
+pub struct FramedWriter {
+    /// Socket write half
+    inner: OwnedWriteHalf,
+
+    /// Codec encoding sent messages
+    codec: FrameCodec,
+
+    /// Counts sent bytes
+    traffic: Arc<PeerTraffic>,
+
+    /// Upload caps
+    throttle: Throttle,
+}
+
+impl FramedWriter {
+    /// Wraps a socket write half
+    pub fn new(inner: OwnedWriteHalf, codec: FrameCodec) -> Self {
+        Self { inner, codec, traffic: Arc::default(), throttle: Throttle::unlimited() }
+    }
+
+    /// Counts sent bytes in `traffic`
+    pub fn with_traffic(mut self, traffic: Arc<PeerTraffic>) -> Self {
+        self.traffic = traffic;
+        self
+    }
+
+    /// Caps the upload rate
+    pub fn with_throttle(mut self, throttle: Throttle) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Sends a message
+    pub async fn send(&mut self, message: &Message) -> Result<()> {
+        let mut frame = Vec::new();
+        self.codec.encode(message, &mut frame)?;
+        self.send_frame(&frame).await
+    }
+
+    /// Sends a frame produced by [`encode_frame`]
+    pub async fn send_frame(&mut self, frame: &[u8]) -> Result<()> {
+        self.throttle.consume(frame.len()).await;
+        self.inner.write_all(frame).await?;
+        self.inner.flush().await?;
+        self.traffic.record_sent(frame.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::MessageType;
+    use nyx_core::{RingSignature, Transaction};
+
+    fn transaction_with_extra(extra_size: usize) -> Message {
+        let ring = RingSignature { ring_members: vec![vec![1], vec![2]], signature: vec![], key_image: [0u8; 32] };
+        let mut tx = Transaction::new(vec![], vec![], vec![ring], vec![], [0u8; 32], [1u8; 32]);
+        tx.extra = vec![0u8; extra_size];
+        Message::new(MessageType::Transaction(tx))
+    }
+
+    #[test]
+    fn test_decode_waits_for_complete_frame() {
+        let codec = FrameCodec::default();
+        let frame = encode_frame(&Message::new(MessageType::Ping)).unwrap();
+
+        let mut buffer = frame[..frame.len() - 1].to_vec();
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+
+        buffer.push(frame[frame.len() - 1]);
+        buffer.extend_from_slice(&frame[..2]);
+        let message = codec.decode(&mut buffer).unwrap().unwrap();
+        assert_eq!(message.message_type.type_name(), "Ping");
+        assert_eq!(buffer, frame[..2]);
+    }
+
+    #[test]
+    fn test_oversized_frames_are_rejected() {
+        let codec = FrameCodec::default().with_max_frame_size(16);
+        let message = transaction_with_extra(64);
+
+        assert!(matches!(codec.encode(&message, &mut Vec::new()), Err(NetworkError::InvalidMessage(_))));
+
+        // Rejected from the length prefix alone
+        let mut buffer = encode_frame(&message).unwrap()[..LENGTH_PREFIX_SIZE].to_vec();
+        assert!(matches!(codec.decode(&mut buffer), Err(NetworkError::InvalidMessage(_))));
+    }
+
+    #[test]
+    fn test_decode_applies_limits_and_consumes_frame() {
+        let message = transaction_with_extra(16);
+        let mut buffer = encode_frame(&message).unwrap();
+        buffer.extend(encode_frame(&message).unwrap());
+
+        let strict = FrameCodec::new(DecodeLimits { max_extra_size: 8, ..DecodeLimits::default() });
+        assert!(matches!(strict.decode(&mut buffer), Err(NetworkError::LimitExceeded(_))));
+
+        assert!(FrameCodec::default().decode(&mut buffer).unwrap().is_some());
+        assert!(buffer.is_empty());
+    }
+}
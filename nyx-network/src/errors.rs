@@ -5,6 +5,7 @@
 //! Provides comprehensive error handling for all networking operations
 //! including connection management, message passing, and synchronization.
 
+use nyx_core::LimitExceeded;
 use std::fmt;
 use std::io;
 
@@ -44,6 +45,18 @@ pub enum NetworkError {
     /// Channel error (for async communication)
     ChannelError(String),
 
+    /// Peer is on a different network
+    NetworkMismatch(String),
+
+    /// Peer's IP address is banned
+    PeerBanned(String),
+
+    /// Peer failed to prove its identity
+    AuthenticationFailed(String),
+
+    /// Decoded message exceeds a structural limit
+    LimitExceeded(LimitExceeded),
+
     /// Node shutdown
     Shutdown,
 }
@@ -62,6 +75,10 @@ impl fmt::Display for NetworkError {
             NetworkError::GossipError(msg) => write!(f, "Gossip error: {}", msg),
             NetworkError::AddressError(msg) => write!(f, "Address error: {}", msg),
             NetworkError::ChannelError(msg) => write!(f, "Channel error: {}", msg),
+            NetworkError::NetworkMismatch(msg) => write!(f, "Network mismatch: {}", msg),
+            NetworkError::PeerBanned(ip) => write!(f, "Peer banned: {}", ip),
+            NetworkError::AuthenticationFailed(msg) => write!(f, "Authentication failed: {}", msg),
+            NetworkError::LimitExceeded(err) => write!(f, "Limit exceeded: {}", err),
             NetworkError::Shutdown => write!(f, "Node shutdown"),
         }
     }
@@ -81,6 +98,12 @@ impl From<bincode::Error> for NetworkError {
     }
 }
 
+impl From<LimitExceeded> for NetworkError {
+    fn from(err: LimitExceeded) -> Self {
+        NetworkError::LimitExceeded(err)
+    }
+}
+
 impl From<serde_json::Error> for NetworkError {
     fn from(err: serde_json::Error) -> Self {
         NetworkError::SerializationError(format!("JSON error: {}", err))
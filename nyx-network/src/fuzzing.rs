@@ -0,0 +1,72 @@
+// src/fuzzing.rs
+
+//! [`Arbitrary`] implementations for fuzz targets.
+//!
+//! Enabled by the `arbitrary` feature. Transactions come from
+//! `nyx_core::fuzzing` and every count stays within the decode limits, so
+//! generated messages survive a [`Message::to_bytes`] /
+//! [`Message::from_bytes`] round trip. Evidence and snapshot responses are
+//! not generated: both carry signed consensus objects that only decode
+//! meaningfully when produced by a validator set.
+
+use crate::message::{LazyTransaction, Message, MessageType};
+use crate::identity::PUBLIC_KEY_SIZE;
+use crate::{MAX_DISCOVERY_ADDRESSES, MAX_NODE_ID_SIZE, MAX_SYNC_BATCH_SIZE};
+use nyx_core::nyx_crypto::ED25519_SIGNATURE_SIZE;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use nyx_core::fuzzing::bounded_vec;
+use nyx_core::{NodeMode, Transaction};
+
+impl<'a> Arbitrary<'a> for MessageType {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(match u.int_in_range(0u8..=9)? {
+            0 => MessageType::Transaction(u.arbitrary()?),
+            1 => MessageType::Ping,
+            2 => MessageType::Pong,
+            3 => MessageType::SyncRequest { from_height: u.arbitrary()? },
+            4 => MessageType::SyncResponse {
+                transactions: bounded_vec::<Transaction>(u, MAX_SYNC_BATCH_SIZE)?.iter().map(LazyTransaction::encode).collect(),
+            },
+            5 => MessageType::PeerDiscovery { peers: bounded_vec(u, MAX_DISCOVERY_ADDRESSES)? },
+            6 => MessageType::SnapshotRequest,
+            7 => MessageType::Handshake {
+                network: u.arbitrary()?,
+                public_key: bounded_vec(u, PUBLIC_KEY_SIZE)?,
+                nonce: u.arbitrary()?,
+                mode: if u.arbitrary()? { NodeMode::Pruned } else { NodeMode::Archive },
+            },
+            8 => MessageType::SyncPruned { retained_from: u.arbitrary()? },
+            _ => MessageType::HandshakeAuth { signature: bounded_vec(u, ED25519_SIGNATURE_SIZE)? },
+        })
+    }
+}
+
+impl<'a> Arbitrary<'a> for Message {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut message = Message::new_at(u.arbitrary()?, u.arbitrary()?);
+        if u.arbitrary()? {
+            message = message.with_sender(bounded_vec(u, MAX_NODE_ID_SIZE)?);
+        }
+        if u.arbitrary()? {
+            message.signature = Some(bounded_vec(u, ED25519_SIGNATURE_SIZE)?);
+        }
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arbitrary_messages_roundtrip() {
+        let data: Vec<u8> = (0..4096u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 11) as u8).collect();
+        let mut u = Unstructured::new(&data);
+        while !u.is_empty() {
+            let message = Message::arbitrary(&mut u).unwrap();
+            let decoded = Message::from_bytes(&message.to_bytes().unwrap()).unwrap();
+            assert_eq!(decoded.id, message.id);
+            assert_eq!(decoded.message_type.type_name(), message.message_type.type_name());
+        }
+    }
+}
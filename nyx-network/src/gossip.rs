@@ -3,17 +3,23 @@
 //! Gossip protocol for message propagation.
 //!
 //! Implements a pub-sub style gossip system where:
-//! - New transactions are broadcast to all peers
+//! - New transactions are broadcast to all peers, or to a random subset
+//!   of them when a fanout is set
 //! - Messages are deduplicated using a hash cache
-//! - Failed deliveries are retried with exponential backoff
+//! - Each peer has a bounded outbound queue drained by its own writer task,
+//!   so a slow peer cannot stall broadcasts to the others
 
-use crate::errors::Result;
+use crate::codec::{encode_frame, FramedWriter};
+use crate::errors::{NetworkError, Result};
+use crate::identity::NodeIdentity;
 use crate::message::{Message, MessageId, MessageType};
+use crate::outbound::{EnqueueOutcome, OutboundConfig, OutboundQueue, QueueStats};
 use crate::peer::{Peer, PeerId};
 use crate::MAX_SEEN_MESSAGES;
+use rand::seq::SliceRandom;
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::net::tcp::OwnedWriteHalf;
 use tokio::sync::{Mutex, RwLock};
 use tracing::{debug, warn};
 
@@ -22,20 +28,101 @@ pub struct GossipEngine {
     /// Cache of seen message IDs for deduplication
     seen_messages: Arc<RwLock<HashSet<MessageId>>>,
 
-    /// Active peer connections
-    peer_streams: Arc<RwLock<HashMap<PeerId, Arc<Mutex<OwnedWriteHalf>>>>>,
+    /// Outbound queue of each registered peer
+    peer_queues: Arc<RwLock<HashMap<PeerId, OutboundQueue>>>,
 
-    /// Pending messages to broadcast
-    pending: Arc<RwLock<Vec<Message>>>,
+    /// Settings for new outbound queues
+    outbound: OutboundConfig,
+
+    /// Peers dropped for being too slow, awaiting disconnection
+    slow_peers: Arc<Mutex<Vec<PeerId>>>,
+
+    /// Maximum number of peers each message is sent to (0 for all)
+    fanout: AtomicUsize,
+
+    /// Key signing every outgoing message, if signing is on
+    identity: Option<NodeIdentity>,
+
+    /// Whether unsigned messages from peers are dropped
+    require_signatures: bool,
 }
 
 impl GossipEngine {
     /// Creates a new gossip engine
     pub fn new() -> Self {
+        Self::with_config(OutboundConfig::default())
+    }
+
+    /// Creates a gossip engine with custom outbound queue settings
+    pub fn with_config(outbound: OutboundConfig) -> Self {
         Self {
             seen_messages: Arc::new(RwLock::new(HashSet::new())),
-            peer_streams: Arc::new(RwLock::new(HashMap::new())),
-            pending: Arc::new(RwLock::new(Vec::new())),
+            peer_queues: Arc::new(RwLock::new(HashMap::new())),
+            outbound,
+            slow_peers: Arc::new(Mutex::new(Vec::new())),
+            fanout: AtomicUsize::new(0),
+            identity: None,
+            require_signatures: false,
+        }
+    }
+
+    /// Signs every outgoing message with `identity`
+    pub fn with_identity(mut self, identity: NodeIdentity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Sets whether unsigned messages from peers are rejected
+    pub fn with_required_signatures(mut self, required: bool) -> Self {
+        self.require_signatures = required;
+        self
+    }
+
+    /// Checks the envelope signature of a message received from `peer`
+    ///
+    /// A signed message must be signed by the peer that sent it: relays
+    /// sign their own envelope, so every message is attributable to the
+    /// connection it arrived on.
+    ///
+    /// # Errors
+    /// `AuthenticationFailed` if the signature is invalid or not the
+    /// peer's, or if the message is unsigned and signatures are required
+    pub fn authenticate(&self, message: &Message, peer: &Peer) -> Result<()> {
+        match message.signer()? {
+            Some(signer) if signer != peer.id => Err(NetworkError::AuthenticationFailed(format!(
+                "{} message signed by {} rather than the sending peer",
+                message.message_type.type_name(),
+                hex::encode(signer)
+            ))),
+            None if self.require_signatures => Err(NetworkError::AuthenticationFailed(format!(
+                "Unsigned {} message",
+                message.message_type.type_name()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Signs a message if signing is on
+    fn sign(&self, message: Message) -> Message {
+        match &self.identity {
+            Some(identity) => message.signed(identity),
+            None => message,
+        }
+    }
+
+    /// Limits broadcasts to `fanout` randomly chosen peers, or lifts the
+    /// limit with `None`
+    ///
+    /// Takes effect from the next broadcast.
+    pub fn set_fanout(&self, fanout: Option<usize>) {
+        self.fanout.store(fanout.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// Gets the broadcast fanout, if limited
+    pub fn fanout(&self) -> Option<usize> {
+        match self.fanout.load(Ordering::Relaxed) {
+            0 => None,
+            fanout => Some(fanout),
         }
     }
 
@@ -62,39 +149,88 @@ impl GossipEngine {
     }
 
     /// Broadcasts a message to all connected peers
+    ///
+    /// Enqueues the message for each peer without waiting for the writes.
+    /// With a [fanout](Self::set_fanout), only that many peers are chosen at
+    /// random.
+    /// Peers whose queue overflows under
+    /// [`DropPolicy::Disconnect`](crate::outbound::DropPolicy::Disconnect)
+    /// are unregistered and reported by [`take_slow_peers`](Self::take_slow_peers).
+    ///
+    /// # Returns
+    /// Number of peers the message was queued for
     pub async fn broadcast(&self, message: Message, peers: &mut [Peer]) -> Result<usize> {
         // Check if we've already seen this message
         if self.has_seen(&message.id).await {
-            debug!("Message {} already seen, skipping broadcast", hex::encode(&message.id));
+            debug!("Message {} already seen, skipping broadcast", hex::encode(message.id));
             return Ok(0);
         }
 
         // Mark as seen
         self.mark_seen(message.id).await;
 
-        let mut success_count = 0;
-        let streams = self.peer_streams.read().await;
-
-        for peer in peers.iter_mut().filter(|p| p.is_connected()) {
-            if let Some(stream_mutex) = streams.get(&peer.id) {
-                let mut stream = stream_mutex.lock().await;
-                match peer.send_message(&mut stream, &message).await {
-                    Ok(()) => {
-                        debug!(
-                            "Broadcast message {} to peer {:?}",
-                            hex::encode(&message.id),
-                            peer.id
-                        );
-                        success_count += 1;
-                    }
-                    Err(e) => {
-                        warn!("Failed to send to peer {:?}: {}", peer.id, e);
-                    }
+        // Sign and encode once for every peer
+        let message = self.sign(message);
+        let frame = Arc::new(encode_frame(&message)?);
+        let priority = message.message_type.priority();
+
+        let mut queued = 0;
+        let mut slow = Vec::new();
+        let queues = self.peer_queues.read().await;
+
+        let mut targets: Vec<(&Peer, &OutboundQueue)> = peers.iter()
+            .filter(|p| p.is_connected())
+            .filter_map(|p| queues.get(&p.id).map(|queue| (p, queue)))
+            .collect();
+        if let Some(fanout) = self.fanout() {
+            if fanout < targets.len() {
+                targets.shuffle(&mut rand::thread_rng());
+                targets.truncate(fanout);
+            }
+        }
+
+        for (peer, queue) in targets {
+            match queue.push(frame.clone(), priority) {
+                EnqueueOutcome::Queued | EnqueueOutcome::EvictedOldest => queued += 1,
+                EnqueueOutcome::Dropped => {
+                    debug!("Outbound queue full, dropped message for peer {:?}", peer.id);
                 }
+                EnqueueOutcome::Disconnect => slow.push(peer.id.clone()),
             }
         }
+        drop(queues);
 
-        Ok(success_count)
+        for peer_id in slow {
+            warn!("Peer {:?} too slow, disconnecting", peer_id);
+            self.unregister_peer(&peer_id).await;
+            self.slow_peers.lock().await.push(peer_id);
+        }
+
+        debug!("Broadcast message {} to {} peers", hex::encode(message.id), queued);
+
+        Ok(queued)
+    }
+
+    /// Sends a message to a single peer without deduplication
+    ///
+    /// # Returns
+    /// Whether the message was queued
+    pub async fn send_to(&self, peer_id: &PeerId, message: &Message) -> Result<bool> {
+        let queues = self.peer_queues.read().await;
+        let Some(queue) = queues.get(peer_id) else {
+            return Ok(false);
+        };
+
+        let message = self.sign(message.clone());
+        Ok(matches!(
+            queue.push_message(&message)?,
+            EnqueueOutcome::Queued | EnqueueOutcome::EvictedOldest
+        ))
+    }
+
+    /// Takes the peers dropped for being too slow since the last call
+    pub async fn take_slow_peers(&self) -> Vec<PeerId> {
+        std::mem::take(&mut *self.slow_peers.lock().await)
     }
 
     /// Gossips a transaction to the network
@@ -112,32 +248,65 @@ impl GossipEngine {
         Ok(())
     }
 
+    /// Gossips validator misbehavior evidence to the network
+    pub async fn gossip_evidence(
+        &self,
+        evidence: nyx_core::evidence::Evidence,
+        peers: &mut [Peer],
+    ) -> Result<()> {
+        let message = Message::new(MessageType::Evidence(evidence));
+
+        let sent = self.broadcast(message, peers).await?;
+
+        debug!("Gossiped evidence to {} peers", sent);
+
+        Ok(())
+    }
+
     /// Registers a peer stream for gossip
+    ///
+    /// Creates the peer's outbound queue and spawns its writer task.
     pub async fn register_peer(
         &self,
         peer_id: PeerId,
-        stream: Arc<Mutex<OwnedWriteHalf>>,
+        stream: Arc<Mutex<FramedWriter>>,
     ) {
-        let mut streams = self.peer_streams.write().await;
-        streams.insert(peer_id, stream);
+        let queue = OutboundQueue::new(self.outbound.clone());
+        queue.spawn_writer(stream);
+
+        let mut queues = self.peer_queues.write().await;
+        if let Some(previous) = queues.insert(peer_id, queue) {
+            previous.close();
+        }
     }
 
-    /// Unregisters a peer stream
+    /// Unregisters a peer stream, discarding its queued messages
     pub async fn unregister_peer(&self, peer_id: &PeerId) {
-        let mut streams = self.peer_streams.write().await;
-        streams.remove(peer_id);
+        let mut queues = self.peer_queues.write().await;
+        if let Some(queue) = queues.remove(peer_id) {
+            queue.close();
+        }
+    }
+
+    /// Gets the outbound queue counters of every registered peer
+    pub async fn queue_stats(&self) -> HashMap<PeerId, QueueStats> {
+        let queues = self.peer_queues.read().await;
+        queues.iter()
+            .map(|(peer_id, queue)| (peer_id.clone(), queue.stats()))
+            .collect()
     }
 
     /// Gets statistics about the gossip engine
     pub async fn stats(&self) -> GossipStats {
-        let seen = self.seen_messages.read().await;
-        let streams = self.peer_streams.read().await;
-        let pending = self.pending.read().await;
+        let seen_messages = self.seen_messages.read().await.len();
+        let queues = self.queue_stats().await;
 
         GossipStats {
-            seen_messages: seen.len(),
-            active_peers: streams.len(),
-            pending_messages: pending.len(),
+            seen_messages,
+            active_peers: queues.len(),
+            pending_messages: queues.values().map(|q| q.depth).sum(),
+            max_queue_depth: queues.values().map(|q| q.depth).max().unwrap_or(0),
+            dropped_messages: queues.values().map(|q| q.dropped).sum(),
         }
     }
 }
@@ -157,8 +326,14 @@ pub struct GossipStats {
     /// Number of active peer connections
     pub active_peers: usize,
 
-    /// Number of pending messages
+    /// Number of messages queued across all peers
     pub pending_messages: usize,
+
+    /// Deepest single peer queue
+    pub max_queue_depth: usize,
+
+    /// Messages dropped by outbound queue policies
+    pub dropped_messages: u64,
 }
 
 #[cfg(test)]
@@ -186,4 +361,86 @@ mod tests {
         assert_eq!(stats.seen_messages, 0);
         assert_eq!(stats.active_peers, 0);
     }
+
+    #[tokio::test]
+    async fn test_broadcast_through_queue() {
+        use crate::outbound::DropPolicy;
+        use crate::peer::{PeerConnection, PeerState};
+        use nyx_core::DecodeLimits;
+        use tokio::net::{TcpListener, TcpStream};
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = TcpStream::connect(addr).await.unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+
+        let gossip = GossipEngine::with_config(OutboundConfig { capacity: 4, policy: DropPolicy::DropOldest });
+        let (_, writer) = PeerConnection::new(server, DecodeLimits::default()).into_split();
+        gossip.register_peer(vec![1], Arc::new(Mutex::new(writer))).await;
+
+        let mut peer = Peer::new(vec![1], addr);
+        peer.update_state(PeerState::Connected);
+        let sent = gossip.broadcast(Message::new(MessageType::Ping), std::slice::from_mut(&mut peer)).await.unwrap();
+        assert_eq!(sent, 1);
+
+        let mut client = PeerConnection::new(client, DecodeLimits::default());
+        let received = client.recv().await.unwrap();
+        assert_eq!(received.message_type.type_name(), "Ping");
+
+        assert_eq!(gossip.stats().await.active_peers, 1);
+        gossip.unregister_peer(&vec![1]).await;
+        assert_eq!(gossip.stats().await.active_peers, 0);
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_fanout() {
+        use crate::peer::{PeerConnection, PeerState};
+        use nyx_core::DecodeLimits;
+        use tokio::net::{TcpListener, TcpStream};
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let gossip = GossipEngine::new();
+        let mut peers = Vec::new();
+        let mut clients = Vec::new();
+        for id in 0..4u8 {
+            clients.push(TcpStream::connect(addr).await.unwrap());
+            let (server, _) = listener.accept().await.unwrap();
+            let (_, writer) = PeerConnection::new(server, DecodeLimits::default()).into_split();
+            gossip.register_peer(vec![id], Arc::new(Mutex::new(writer))).await;
+
+            let mut peer = Peer::new(vec![id], addr);
+            peer.update_state(PeerState::Connected);
+            peers.push(peer);
+        }
+
+        gossip.set_fanout(Some(2));
+        assert_eq!(gossip.fanout(), Some(2));
+        assert_eq!(gossip.broadcast(Message::new(MessageType::Ping), &mut peers).await.unwrap(), 2);
+
+        gossip.set_fanout(None);
+        assert_eq!(gossip.broadcast(Message::new(MessageType::Pong), &mut peers).await.unwrap(), 4);
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let identity = NodeIdentity::from_seed([1u8; 32]);
+        let peer = Peer::new(identity.peer_id(), "127.0.0.1:8000".parse().unwrap());
+        let unsigned = Message::new(MessageType::Ping);
+
+        let gossip = GossipEngine::new();
+        assert!(gossip.authenticate(&unsigned, &peer).is_ok());
+        assert!(gossip.authenticate(&unsigned.clone().signed(&identity), &peer).is_ok());
+
+        // Relaying someone else's signed envelope is not allowed
+        let other = NodeIdentity::from_seed([2u8; 32]);
+        assert!(matches!(
+            gossip.authenticate(&unsigned.clone().signed(&other), &peer),
+            Err(NetworkError::AuthenticationFailed(_))
+        ));
+
+        let strict = GossipEngine::new().with_required_signatures(true);
+        assert!(strict.authenticate(&unsigned, &peer).is_err());
+        assert!(strict.authenticate(&unsigned.signed(&identity), &peer).is_ok());
+    }
 }
@@ -0,0 +1,177 @@
+// src/identity.rs
+
+//! Persistent node identity.
+//!
+//! Every node owns an Ed25519 keypair, generated on first run and stored in
+//! the data directory so the node keeps its identity across restarts. The
+//! peer ID is the hash of the public key, and handshakes carry a signature
+//! over the remote side's fresh nonce, so a peer cannot claim an ID whose
+//! key it does not hold or replay another node's handshake.
+
+use crate::errors::{NetworkError, Result};
+use crate::peer::PeerId;
+use nyx_core::nyx_crypto::keys::{ed25519_public_key, sign_ed25519, verify_ed25519};
+use nyx_core::NetworkId;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// File name of the node key inside the data directory
+pub const NODE_KEY_FILE: &str = "node_key";
+
+/// Ed25519 public key size
+pub const PUBLIC_KEY_SIZE: usize = 32;
+
+/// Domain separator of signed handshakes
+const HANDSHAKE_DOMAIN: &[u8] = b"nyx-handshake-v1";
+
+/// Ed25519 keypair identifying a node
+#[derive(Clone)]
+pub struct NodeIdentity {
+    /// Signing key seed
+    seed: [u8; 32],
+
+    /// Public key
+    public_key: [u8; PUBLIC_KEY_SIZE],
+}
+
+impl NodeIdentity {
+    /// Generates a fresh random identity
+    pub fn generate() -> Self {
+        Self::from_seed(rand::random())
+    }
+
+    /// Creates the identity of a signing key seed
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self { seed, public_key: ed25519_public_key(&seed) }
+    }
+
+    /// Loads the identity stored in `data_dir`, generating and saving one
+    /// on first run
+    ///
+    /// # Errors
+    /// - `IoError` if the key file cannot be read or written
+    /// - `SerializationError` if the key file is corrupt
+    pub fn load_or_generate(data_dir: &Path) -> Result<Self> {
+        Self::load_or_generate_file(&data_dir.join(NODE_KEY_FILE))
+    }
+
+    /// Loads the identity stored in the key file at `path`, generating and
+    /// saving one on first run
+    ///
+    /// # Errors
+    /// - `IoError` if the key file cannot be read or written
+    /// - `SerializationError` if the key file is corrupt
+    pub fn load_or_generate_file(path: &Path) -> Result<Self> {
+        if path.exists() {
+            let seed = hex::decode(fs::read_to_string(path)?.trim())
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| NetworkError::SerializationError(format!("Corrupt node key {}", path.display())))?;
+            return Ok(Self::from_seed(seed));
+        }
+
+        let identity = Self::generate();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, hex::encode(identity.seed))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600))?;
+        }
+        fs::rename(&tmp, path)?;
+
+        Ok(identity)
+    }
+
+    /// Public key
+    pub fn public_key(&self) -> &[u8; PUBLIC_KEY_SIZE] {
+        &self.public_key
+    }
+
+    /// Peer ID derived from the public key
+    pub fn peer_id(&self) -> PeerId {
+        peer_id_from_public_key(&self.public_key)
+    }
+
+    /// Signs arbitrary data with the node key
+    pub fn sign(&self, data: &[u8]) -> Vec<u8> {
+        sign_ed25519(data, &self.seed).to_vec()
+    }
+
+    /// Answers a peer's handshake challenge
+    pub fn sign_handshake(&self, network: NetworkId, challenge: &[u8; 32]) -> Vec<u8> {
+        self.sign(&handshake_transcript(network, challenge, &self.public_key))
+    }
+}
+
+impl fmt::Debug for NodeIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Never print the seed
+        f.debug_struct("NodeIdentity")
+            .field("public_key", &hex::encode(self.public_key))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Derives the peer ID of a public key
+pub fn peer_id_from_public_key(public_key: &[u8]) -> PeerId {
+    nyx_core::hash::blake3_hash(public_key).to_vec()
+}
+
+/// Checks a peer's answer to our handshake challenge
+pub fn verify_handshake(
+    network: NetworkId,
+    challenge: &[u8; 32],
+    public_key: &[u8],
+    signature: &[u8],
+) -> bool {
+    verify_ed25519(&handshake_transcript(network, challenge, public_key), signature, public_key)
+}
+
+/// Data signed to answer a handshake challenge
+fn handshake_transcript(network: NetworkId, challenge: &[u8; 32], public_key: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(HANDSHAKE_DOMAIN.len() + 1 + challenge.len() + public_key.len());
+    data.extend_from_slice(HANDSHAKE_DOMAIN);
+    data.push(network.as_u8());
+    data.extend_from_slice(challenge);
+    data.extend_from_slice(public_key);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = NodeIdentity::load_or_generate(dir.path()).unwrap();
+        let second = NodeIdentity::load_or_generate(dir.path()).unwrap();
+        assert_eq!(first.public_key(), second.public_key());
+        assert_eq!(first.peer_id(), second.peer_id());
+
+        fs::write(dir.path().join(NODE_KEY_FILE), "not hex").unwrap();
+        assert!(matches!(
+            NodeIdentity::load_or_generate(dir.path()),
+            Err(NetworkError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_handshake_signature() {
+        let identity = NodeIdentity::from_seed([1u8; 32]);
+        let other = NodeIdentity::from_seed([2u8; 32]);
+        let challenge = [7u8; 32];
+        let signature = identity.sign_handshake(NetworkId::Mainnet, &challenge);
+
+        assert!(verify_handshake(NetworkId::Mainnet, &challenge, identity.public_key(), &signature));
+        assert!(!verify_handshake(NetworkId::Testnet, &challenge, identity.public_key(), &signature));
+        assert!(!verify_handshake(NetworkId::Mainnet, &[8u8; 32], identity.public_key(), &signature));
+        // Claiming someone else's key fails
+        assert!(!verify_handshake(NetworkId::Mainnet, &challenge, other.public_key(), &signature));
+    }
+}
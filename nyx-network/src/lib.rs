@@ -8,7 +8,11 @@
 //! - **Peer Discovery**: Find and connect to network peers
 //! - **Message Propagation**: Gossip protocol for transaction broadcasting
 //! - **DAG Synchronization**: Sync transaction DAG with other nodes
+//! - **Snap Sync**: Start from a verified finalized checkpoint instead of
+//!   replaying history
 //! - **Connection Management**: Maintain healthy peer connections
+//! - **Address Book**: Remember good peers across restarts
+//! - **Bandwidth Caps**: Throttle upload and download, node-wide and per peer
 //!
 //! ## Architecture
 //!
@@ -43,18 +47,34 @@
 
 pub mod errors;
 pub mod message;
+pub mod codec;
+pub mod bandwidth;
 pub mod peer;
+pub mod identity;
+pub mod address_book;
+pub mod seeds;
+pub mod outbound;
 pub mod gossip;
 pub mod sync;
+pub mod snap_sync;
 pub mod node;
+pub mod trace;
+#[cfg(feature = "arbitrary")]
+pub mod fuzzing;
 
 // Re-export commonly used types
 pub use crate::errors::{NetworkError, Result};
-pub use crate::message::{Message, MessageType};
-pub use crate::peer::{Peer, PeerId};
+pub use crate::message::{LazyTransaction, Message, MessageType};
+pub use crate::codec::{FrameCodec, FramedReader, FramedWriter};
+pub use crate::bandwidth::{Bandwidth, BandwidthLimits};
+pub use crate::peer::{Peer, PeerConnection, PeerId, PeerTraffic, RejectCode};
+pub use crate::identity::NodeIdentity;
 pub use crate::gossip::GossipEngine;
+pub use crate::address_book::AddressBook;
+pub use crate::outbound::{DropPolicy, OutboundConfig};
 pub use crate::sync::SyncManager;
-pub use crate::node::{Node, NodeConfig};
+pub use crate::snap_sync::SnapSync;
+pub use crate::node::{Node, NodeConfig, ReceivedTransaction};
 
 /// Default P2P network port
 pub const DEFAULT_PORT: u16 = 8000;
@@ -68,12 +88,56 @@ pub const MIN_PEERS: usize = 8;
 /// Heartbeat interval in seconds
 pub const HEARTBEAT_INTERVAL_SECS: u64 = 30;
 
+/// Consecutive unanswered heartbeats before a peer is disconnected
+pub const MAX_MISSED_HEARTBEATS: u32 = 3;
+
+/// Seconds a connection may go without receiving any message, heartbeats
+/// included, before it is closed
+pub const IDLE_TIMEOUT_SECS: u64 = HEARTBEAT_INTERVAL_SECS * MAX_MISSED_HEARTBEATS as u64;
+
+/// Seconds to wait before redialing a peer whose connection went idle
+pub const REDIAL_DELAY_SECS: u64 = 5;
+
+/// Default duration of a peer ban in seconds
+pub const DEFAULT_BAN_SECS: u64 = 24 * 60 * 60;
+
+/// Round-trip samples kept for each peer's rolling latency average
+pub const LATENCY_WINDOW: usize = 10;
+
+/// Timeout for resolving a single DNS seed, in seconds
+pub const DNS_SEED_TIMEOUT_SECS: u64 = 5;
+
 /// Connection timeout in seconds
 pub const CONNECTION_TIMEOUT_SECS: u64 = 10;
 
 /// Message size limit (10 MB)
 pub const MAX_MESSAGE_SIZE: usize = 10 * 1024 * 1024;
 
+/// Maximum frames queued for a single peer before the drop policy applies
+pub const OUTBOUND_QUEUE_CAPACITY: usize = 1024;
+
+/// Received transactions buffered for each subscriber before the oldest are dropped
+pub const RECEIVED_TX_CAPACITY: usize = 1024;
+
+/// Buckets of the address book's table of addresses heard of
+pub const NEW_BUCKET_COUNT: usize = 256;
+
+/// Buckets of the address book's table of addresses connected to
+pub const TRIED_BUCKET_COUNT: usize = 64;
+
+/// Addresses per address book bucket
+pub const ADDRESS_BUCKET_SIZE: usize = 16;
+
+/// New buckets the addresses announced by one network group can occupy
+pub const NEW_BUCKETS_PER_SOURCE_GROUP: usize = 16;
+
+/// Tried buckets the addresses of one network group can occupy
+pub const TRIED_BUCKETS_PER_GROUP: usize = 4;
+
+/// Outbound connections picked from the address book or seeds per network
+/// group; bootstrap and manually added peers are exempt
+pub const MAX_OUTBOUND_PER_GROUP: usize = 1;
+
 /// Maximum number of messages to cache for deduplication
 pub const MAX_SEEN_MESSAGES: usize = 10000;
 
@@ -83,14 +147,26 @@ pub const SYNC_INTERVAL_SECS: u64 = 60;
 /// Maximum transactions per sync response
 pub const MAX_SYNC_BATCH_SIZE: usize = 1000;
 
+/// Maximum addresses in a single peer discovery message
+pub const MAX_DISCOVERY_ADDRESSES: usize = 1000;
+
+/// Maximum length of a message sender identifier in bytes
+pub const MAX_NODE_ID_SIZE: usize = 64;
+
+/// Distinct peers that must serve the same checkpoint before snap sync
+/// restores it
+pub const MIN_SNAP_SYNC_PEERS: usize = 3;
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_constants() {
         assert_eq!(DEFAULT_PORT, 8000);
         assert!(MAX_PEERS > MIN_PEERS);
         assert!(HEARTBEAT_INTERVAL_SECS > 0);
+        assert!(IDLE_TIMEOUT_SECS > HEARTBEAT_INTERVAL_SECS);
     }
 }
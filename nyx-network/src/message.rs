@@ -4,14 +4,32 @@
 //!
 //! Defines all message types used in the Nyx P2P protocol including
 //! transaction broadcasts, sync requests, and peer discovery.
-
-use nyx_core::Transaction;
-use serde::{Deserialize, Serialize};
+//!
+//! Sync batches carry [`LazyTransaction`]s: each transaction stays one
+//! buffer in canonical encoding until verification decodes it, so a batch
+//! can be screened by header without allocating every body first.
+
+use nyx_core::checkpoint::Checkpoint;
+use nyx_core::evidence::Evidence;
+use crate::errors::{NetworkError, Result};
+use crate::identity::{peer_id_from_public_key, NodeIdentity, PUBLIC_KEY_SIZE};
+use crate::peer::PeerId;
+use crate::{MAX_DISCOVERY_ADDRESSES, MAX_MESSAGE_SIZE, MAX_NODE_ID_SIZE, MAX_SYNC_BATCH_SIZE};
+use bincode::Options;
+use nyx_core::nyx_crypto::keys::verify_ed25519;
+use nyx_core::nyx_crypto::ED25519_SIGNATURE_SIZE;
+use nyx_core::encoding::{self, TxHeader};
+use nyx_core::{DecodeLimits, LimitExceeded, NetworkId, NodeMode, Transaction};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
 use std::net::SocketAddr;
 
 /// Unique message identifier
 pub type MessageId = [u8; 32];
 
+/// Domain separator of signed message envelopes
+const MESSAGE_DOMAIN: &[u8] = b"nyx-message-v1";
+
 /// Network message envelope
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Message {
@@ -24,14 +42,24 @@ pub struct Message {
     /// Timestamp when message was created
     pub timestamp: u64,
 
-    /// Sender's peer ID
+    /// Sender's peer ID, or its identity public key if signed
     pub sender: Option<Vec<u8>>,
+
+    /// Sender's signature over the envelope, made with its identity key
+    pub signature: Option<Vec<u8>>,
 }
 
 impl Message {
     /// Creates a new message
     pub fn new(message_type: MessageType) -> Self {
-        let timestamp = current_timestamp();
+        Self::new_at(message_type, current_timestamp())
+    }
+
+    /// Creates a message stamped with the given time
+    ///
+    /// Simulations pass a virtual clock's time so message ids are
+    /// reproducible.
+    pub fn new_at(message_type: MessageType, timestamp: u64) -> Self {
         let id = Self::compute_id(&message_type, timestamp);
 
         Self {
@@ -39,6 +67,7 @@ impl Message {
             message_type,
             timestamp,
             sender: None,
+            signature: None,
         }
     }
 
@@ -48,16 +77,57 @@ impl Message {
         self
     }
 
+    /// Signs the envelope with the node identity key
+    ///
+    /// The sender becomes our public key, so receivers can verify the
+    /// signature and hold us to what we sent.
+    pub fn signed(mut self, identity: &NodeIdentity) -> Self {
+        self.sender = Some(identity.public_key().to_vec());
+        self.signature = Some(identity.sign(&self.signing_data()));
+        self
+    }
+
+    /// Checks the envelope signature
+    ///
+    /// # Returns
+    /// The signer's peer ID, or `None` if the message is unsigned
+    ///
+    /// # Errors
+    /// `AuthenticationFailed` if the signature does not verify against the
+    /// sender key
+    pub fn signer(&self) -> Result<Option<PeerId>> {
+        let Some(signature) = &self.signature else {
+            return Ok(None);
+        };
+        let public_key = self.sender.as_deref().unwrap_or_default();
+        if !verify_ed25519(&self.signing_data(), signature, public_key) {
+            return Err(NetworkError::AuthenticationFailed(format!(
+                "Invalid signature on {} message",
+                self.message_type.type_name()
+            )));
+        }
+        Ok(Some(peer_id_from_public_key(public_key)))
+    }
+
+    /// Data covered by the envelope signature: the whole payload, not just
+    /// the ID, which only summarizes some message types
+    fn signing_data(&self) -> Vec<u8> {
+        let mut data = MESSAGE_DOMAIN.to_vec();
+        data.extend_from_slice(&self.id);
+        data.extend_from_slice(&self.timestamp.to_le_bytes());
+        data.extend_from_slice(&bincode::serialize(&self.message_type).expect("message types serialize"));
+        data
+    }
+
     /// Computes message ID
     fn compute_id(message_type: &MessageType, timestamp: u64) -> MessageId {
         let mut data = Vec::new();
         data.extend_from_slice(&timestamp.to_le_bytes());
 
         match message_type {
-            MessageType::Transaction(tx) => {
-                let tx_id = tx.id();
-                data.extend_from_slice(&tx_id);
-            }
+            // Relayed per encoding, so a malleated copy seen first cannot
+            // suppress the original
+            MessageType::Transaction(tx) => data.extend_from_slice(&tx.wtxid()),
             MessageType::Ping => data.extend_from_slice(b"ping"),
             MessageType::Pong => data.extend_from_slice(b"pong"),
             MessageType::SyncRequest { from_height } => {
@@ -69,24 +139,91 @@ impl Message {
             MessageType::PeerDiscovery { peers } => {
                 data.extend_from_slice(&peers.len().to_le_bytes());
             }
+            MessageType::Evidence(evidence) => {
+                data.extend_from_slice(&evidence.id());
+            }
+            MessageType::SnapshotRequest => data.extend_from_slice(b"snapshot_request"),
+            MessageType::SnapshotResponse(checkpoint) => {
+                data.extend_from_slice(&checkpoint.number().to_le_bytes());
+            }
+            MessageType::SyncPruned { retained_from } => {
+                data.extend_from_slice(b"sync_pruned");
+                data.extend_from_slice(&retained_from.to_le_bytes());
+            }
+            MessageType::Handshake { network, public_key, nonce, mode } => {
+                data.push(network.as_u8());
+                data.push(*mode as u8);
+                data.extend_from_slice(public_key);
+                data.extend_from_slice(nonce);
+            }
+            MessageType::HandshakeAuth { signature } => {
+                data.extend_from_slice(signature);
+            }
         }
 
         nyx_core::hash::blake3_hash(&data)
     }
 
     /// Serializes message to bytes
-    pub fn to_bytes(&self) -> Result<Vec<u8>, bincode::Error> {
+    pub fn to_bytes(&self) -> std::result::Result<Vec<u8>, bincode::Error> {
         bincode::serialize(self)
     }
 
-    /// Deserializes message from bytes
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::Error> {
-        bincode::deserialize(bytes)
+    /// Deserializes message from bytes with the default limits
+    ///
+    /// # Errors
+    /// - `SerializationError` for malformed input or trailing bytes
+    /// - `LimitExceeded` if a count in the message exceeds its limit
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_bytes_with_limits(bytes, &DecodeLimits::default())
+    }
+
+    /// Deserializes message from bytes, checking transactions against
+    /// `limits`
+    pub fn from_bytes_with_limits(bytes: &[u8], limits: &DecodeLimits) -> Result<Self> {
+        let message: Self = bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .with_limit(MAX_MESSAGE_SIZE as u64)
+            .deserialize(bytes)?;
+        message.check_limits(limits)?;
+        Ok(message)
+    }
+
+    /// Checks every count in the message against its limit
+    pub fn check_limits(&self, limits: &DecodeLimits) -> std::result::Result<(), LimitExceeded> {
+        LimitExceeded::check("sender", MAX_NODE_ID_SIZE, self.sender.as_ref().map_or(0, Vec::len))?;
+        LimitExceeded::check("signature", ED25519_SIGNATURE_SIZE, self.signature.as_ref().map_or(0, Vec::len))?;
+
+        match &self.message_type {
+            MessageType::Transaction(tx) => limits.check_transaction(tx),
+            MessageType::SyncResponse { transactions } => {
+                LimitExceeded::check("sync batch", MAX_SYNC_BATCH_SIZE, transactions.len())?;
+                transactions.iter()
+                    .try_for_each(|tx| LimitExceeded::check("transaction bytes", limits.max_tx_size, tx.as_bytes().len()))
+            }
+            MessageType::PeerDiscovery { peers } => {
+                LimitExceeded::check("peers", MAX_DISCOVERY_ADDRESSES, peers.len())
+            }
+            MessageType::Handshake { public_key, .. } => {
+                LimitExceeded::check("public key", PUBLIC_KEY_SIZE, public_key.len())
+            }
+            MessageType::HandshakeAuth { signature } => {
+                LimitExceeded::check("signature", ED25519_SIGNATURE_SIZE, signature.len())
+            }
+            MessageType::Ping
+            | MessageType::Pong
+            | MessageType::SyncRequest { .. }
+            | MessageType::SyncPruned { .. }
+            | MessageType::Evidence(_)
+            | MessageType::SnapshotRequest
+            | MessageType::SnapshotResponse(_) => Ok(()),
+        }
     }
 }
 
 /// Message types in the Nyx network protocol
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
 pub enum MessageType {
     /// Transaction broadcast
     Transaction(Transaction),
@@ -105,8 +242,14 @@ pub enum MessageType {
 
     /// Response with batch of transactions
     SyncResponse {
-        /// Batch of transactions
-        transactions: Vec<Transaction>,
+        /// Batch of transactions, decoded on verification
+        transactions: Vec<LazyTransaction>,
+    },
+
+    /// Refusal of a sync request reaching into history the sender pruned
+    SyncPruned {
+        /// First snapshot whose transactions the sender still holds
+        retained_from: u64,
     },
 
     /// Peer discovery message
@@ -114,9 +257,178 @@ pub enum MessageType {
         /// Known peer addresses
         peers: Vec<SocketAddr>,
     },
+
+    /// Validator misbehavior evidence
+    Evidence(Evidence),
+
+    /// Request for the latest finalized checkpoint (snap sync)
+    SnapshotRequest,
+
+    /// Latest finalized checkpoint
+    SnapshotResponse(Checkpoint),
+
+    /// First message on every connection
+    Handshake {
+        /// Network the sender is on; peers on other networks are dropped
+        network: NetworkId,
+
+        /// Sender's identity public key, from which its peer ID derives
+        public_key: Vec<u8>,
+
+        /// Fresh challenge the receiver must sign
+        nonce: [u8; 32],
+
+        /// Whether the sender serves full history
+        mode: NodeMode,
+    },
+
+    /// Second message on every connection: the sender's signature over
+    /// the receiver's handshake nonce
+    HandshakeAuth {
+        /// Signature by the key announced in the sender's handshake
+        signature: Vec<u8>,
+    },
+}
+
+/// A transaction kept in canonical encoding until it is needed
+///
+/// Decoding checks the bytes against [`DecodeLimits`] like any received
+/// transaction.
+#[derive(Clone, PartialEq, Eq)]
+pub struct LazyTransaction {
+    bytes: Vec<u8>,
+}
+
+impl LazyTransaction {
+    /// Encodes `tx`
+    pub fn encode(tx: &Transaction) -> Self {
+        Self { bytes: encoding::encode_transaction(tx) }
+    }
+
+    /// Wraps an encoded transaction without checking it
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+
+    /// The canonical encoding
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Reads the header without decoding the body
+    ///
+    /// # Errors
+    /// - `LimitExceeded` or `SerializationError` as [`encoding::decode_header`]
+    pub fn header(&self, limits: &DecodeLimits) -> nyx_core::Result<TxHeader> {
+        encoding::decode_header(&self.bytes, *limits)
+    }
+
+    /// Decodes the whole transaction
+    ///
+    /// # Errors
+    /// - `LimitExceeded` or `SerializationError` if the encoding is invalid
+    pub fn decode(&self, limits: &DecodeLimits) -> nyx_core::Result<Transaction> {
+        encoding::decode_transaction_with_limits(&self.bytes, *limits)
+    }
+}
+
+impl From<&Transaction> for LazyTransaction {
+    fn from(tx: &Transaction) -> Self {
+        Self::encode(tx)
+    }
+}
+
+impl fmt::Debug for LazyTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LazyTransaction({} bytes)", self.bytes.len())
+    }
+}
+
+// A byte string rather than a sequence, so decoding the frame copies each
+// transaction in one piece
+impl Serialize for LazyTransaction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for LazyTransaction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct BytesVisitor;
+
+        impl serde::de::Visitor<'_> for BytesVisitor {
+            type Value = LazyTransaction;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an encoded transaction")
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, bytes: &[u8]) -> std::result::Result<Self::Value, E> {
+                Ok(LazyTransaction::from_bytes(bytes.to_vec()))
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, bytes: Vec<u8>) -> std::result::Result<Self::Value, E> {
+                Ok(LazyTransaction::from_bytes(bytes))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+/// Outbound priority class of a message
+///
+/// Lower classes are always sent first, so bulk sync traffic cannot delay
+/// consensus messages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Consensus, snapshots and keepalives
+    Consensus,
+
+    /// DAG sync
+    Sync,
+
+    /// Transaction gossip
+    Transaction,
+
+    /// Peer discovery
+    Discovery,
+}
+
+impl Priority {
+    /// All priority classes, highest first
+    pub const ALL: [Priority; 4] = [
+        Priority::Consensus,
+        Priority::Sync,
+        Priority::Transaction,
+        Priority::Discovery,
+    ];
+
+    /// Index of the class in [`Priority::ALL`]
+    pub fn index(self) -> usize {
+        self as usize
+    }
 }
 
 impl MessageType {
+    /// Returns the outbound priority class
+    pub fn priority(&self) -> Priority {
+        match self {
+            MessageType::Ping
+            | MessageType::Pong
+            | MessageType::Evidence(_)
+            | MessageType::SnapshotRequest
+            | MessageType::SnapshotResponse(_)
+            | MessageType::Handshake { .. }
+            | MessageType::HandshakeAuth { .. } => Priority::Consensus,
+            MessageType::SyncRequest { .. }
+            | MessageType::SyncResponse { .. }
+            | MessageType::SyncPruned { .. } => Priority::Sync,
+            MessageType::Transaction(_) => Priority::Transaction,
+            MessageType::PeerDiscovery { .. } => Priority::Discovery,
+        }
+    }
+
     /// Returns the type name
     pub fn type_name(&self) -> &'static str {
         match self {
@@ -125,48 +437,26 @@ impl MessageType {
             MessageType::Pong => "Pong",
             MessageType::SyncRequest { .. } => "SyncRequest",
             MessageType::SyncResponse { .. } => "SyncResponse",
+            MessageType::SyncPruned { .. } => "SyncPruned",
             MessageType::PeerDiscovery { .. } => "PeerDiscovery",
+            MessageType::Evidence(_) => "Evidence",
+            MessageType::SnapshotRequest => "SnapshotRequest",
+            MessageType::SnapshotResponse(_) => "SnapshotResponse",
+            MessageType::Handshake { .. } => "Handshake",
+            MessageType::HandshakeAuth { .. } => "HandshakeAuth",
         }
     }
 }
 
 /// Helper to get current Unix timestamp
-fn current_timestamp() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .expect("System time should be after Unix epoch")
-        .as_secs()
+pub(crate) fn current_timestamp() -> u64 {
+    nyx_core::clock::Clock::now(&nyx_core::clock::SystemClock)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use nyx_core::{RingSignature, transaction::{TxInput, TxOutput}};
-
-    fn create_test_tx() -> Transaction {
-        Transaction::new(
-            vec![TxInput {
-                prev_tx: [1u8; 32],
-                index: 0,
-                key_image: [0u8; 32],
-                ring_indices: vec![],
-            }],
-            vec![TxOutput {
-                stealth_address: vec![],
-                amount_commitment: vec![],
-                range_proof: vec![],
-                ephemeral_pubkey: vec![],
-            }],
-            RingSignature {
-                ring_members: vec![],
-                signature: vec![],
-                key_image: [0u8; 32],
-            },
-            vec![],
-            [0u8; 32],
-            [1u8; 32],
-        )
-    }
+    use nyx_core::testing::TestTx;
 
     #[test]
     fn test_message_creation() {
@@ -175,9 +465,28 @@ mod tests {
         assert!(msg.timestamp > 0);
     }
 
+    #[test]
+    fn test_message_id_fixed_by_timestamp() {
+        let a = Message::new_at(MessageType::Ping, 1_700_000_000);
+        let b = Message::new_at(MessageType::Ping, 1_700_000_000);
+        assert_eq!(a.id, b.id);
+        assert_ne!(a.id, Message::new_at(MessageType::Ping, 1_700_000_001).id);
+    }
+
+    #[test]
+    fn test_malleated_transaction_is_relayed_separately() {
+        let tx = TestTx::new(0).build();
+        let mut malleated = tx.clone();
+        malleated.ring_signatures[0].signature.push(0);
+
+        let a = Message::new_at(MessageType::Transaction(tx), 1_700_000_000);
+        let b = Message::new_at(MessageType::Transaction(malleated), 1_700_000_000);
+        assert_ne!(a.id, b.id);
+    }
+
     #[test]
     fn test_message_serialization() {
-        let tx = create_test_tx();
+        let tx = TestTx::new(0).build();
         let msg = Message::new(MessageType::Transaction(tx));
 
         let bytes = msg.to_bytes().unwrap();
@@ -186,6 +495,63 @@ mod tests {
         assert_eq!(msg.id, decoded.id);
     }
 
+    #[test]
+    fn test_decode_rejects_trailing_bytes() {
+        let mut bytes = Message::new(MessageType::Ping).to_bytes().unwrap();
+        bytes.push(0);
+        assert!(matches!(Message::from_bytes(&bytes), Err(NetworkError::SerializationError(_))));
+    }
+
+    #[test]
+    fn test_decode_enforces_limits() {
+        let mut tx = TestTx::new(0).build();
+        tx.extra = vec![0u8; nyx_core::limits::MAX_EXTRA_SIZE + 1];
+        let bytes = Message::new(MessageType::Transaction(tx)).to_bytes().unwrap();
+        assert!(matches!(
+            Message::from_bytes(&bytes),
+            Err(NetworkError::LimitExceeded(LimitExceeded { field: "extra", .. }))
+        ));
+
+        let bytes = Message::new(MessageType::Handshake {
+            network: NetworkId::Mainnet,
+            public_key: vec![0u8; PUBLIC_KEY_SIZE + 1],
+            nonce: [0u8; 32],
+            mode: NodeMode::Archive,
+        })
+        .to_bytes()
+        .unwrap();
+        assert!(matches!(Message::from_bytes(&bytes), Err(NetworkError::LimitExceeded(_))));
+
+        let relaxed = DecodeLimits { max_extra_size: usize::MAX, ..DecodeLimits::default() };
+        let mut tx = TestTx::new(0).build();
+        tx.extra = vec![0u8; nyx_core::limits::MAX_EXTRA_SIZE + 1];
+        let bytes = Message::new(MessageType::Transaction(tx)).to_bytes().unwrap();
+        assert!(Message::from_bytes_with_limits(&bytes, &relaxed).is_ok());
+    }
+
+    #[test]
+    fn test_sync_batch_decodes_lazily() {
+        let tx = TestTx::new(0).build();
+        let msg = Message::new(MessageType::SyncResponse {
+            transactions: vec![LazyTransaction::encode(&tx), LazyTransaction::from_bytes(vec![1, 0xFF])],
+        });
+
+        // A malformed transaction does not fail the frame
+        let decoded = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        let MessageType::SyncResponse { transactions } = decoded.message_type else {
+            panic!("expected a sync response");
+        };
+        let limits = DecodeLimits::default();
+        assert_eq!(transactions[0].header(&limits).unwrap().network, tx.network);
+        assert_eq!(transactions[0].decode(&limits).unwrap(), tx);
+        assert!(transactions[1].header(&limits).is_err());
+
+        // Oversized transactions still fail the frame
+        let oversized = LazyTransaction::from_bytes(vec![0u8; limits.max_tx_size + 1]);
+        let bytes = Message::new(MessageType::SyncResponse { transactions: vec![oversized] }).to_bytes().unwrap();
+        assert!(matches!(Message::from_bytes(&bytes), Err(NetworkError::LimitExceeded(_))));
+    }
+
     #[test]
     fn test_message_with_sender() {
         let msg = Message::new(MessageType::Ping)
@@ -200,4 +566,33 @@ mod tests {
         assert_eq!(MessageType::Ping.type_name(), "Ping");
         assert_eq!(MessageType::Pong.type_name(), "Pong");
     }
+
+    #[test]
+    fn test_message_priority() {
+        assert_eq!(MessageType::SnapshotRequest.priority(), Priority::Consensus);
+        assert_eq!(MessageType::SyncRequest { from_height: 0 }.priority(), Priority::Sync);
+        assert_eq!(MessageType::Transaction(TestTx::new(0).build()).priority(), Priority::Transaction);
+        assert_eq!(MessageType::PeerDiscovery { peers: vec![] }.priority(), Priority::Discovery);
+        assert!(Priority::Consensus < Priority::Discovery);
+    }
+
+    #[test]
+    fn test_signed_message() {
+        let identity = NodeIdentity::from_seed([1u8; 32]);
+        assert_eq!(Message::new(MessageType::Ping).signer().unwrap(), None);
+
+        let msg = Message::new(MessageType::Transaction(TestTx::new(0).build())).signed(&identity);
+        let decoded = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded.signer().unwrap(), Some(identity.peer_id()));
+
+        // Swapping the payload breaks the signature
+        let mut tampered = decoded.clone();
+        tampered.message_type = MessageType::Ping;
+        assert!(matches!(tampered.signer(), Err(NetworkError::AuthenticationFailed(_))));
+
+        // So does claiming another sender
+        let mut impostor = decoded;
+        impostor.sender = Some(NodeIdentity::from_seed([2u8; 32]).public_key().to_vec());
+        assert!(impostor.signer().is_err());
+    }
 }
@@ -8,20 +8,30 @@
 //! - Handles message routing
 //! - Manages gossip and sync
 
+use crate::bandwidth::{Bandwidth, BandwidthLimits};
+use crate::codec::FramedReader;
 use crate::errors::{NetworkError, Result};
 use crate::message::{Message, MessageType};
-use crate::peer::{Peer, PeerManager, PeerState};
+use crate::peer::{Peer, PeerConnection, PeerId, PeerManager, PeerState, RejectCode};
+use crate::address_book::{AddressBook, ADDRESS_BOOK_FILE};
 use crate::gossip::GossipEngine;
+use crate::identity::{self, NodeIdentity};
+use crate::seeds;
 use crate::sync::SyncManager;
-use crate::{MAX_PEERS, MIN_PEERS, HEARTBEAT_INTERVAL_SECS, SYNC_INTERVAL_SECS};
+use crate::trace;
+use crate::{MAX_PEERS, MIN_PEERS, CONNECTION_TIMEOUT_SECS, HEARTBEAT_INTERVAL_SECS, IDLE_TIMEOUT_SECS, MAX_MISSED_HEARTBEATS, REDIAL_DELAY_SECS, SYNC_INTERVAL_SECS, MIN_SNAP_SYNC_PEERS, RECEIVED_TX_CAPACITY, MAX_OUTBOUND_PER_GROUP};
+use nyx_core::consensus::ValidatorSet;
+use nyx_core::finality::FinalityTracker;
+use nyx_core::{NetworkId, NodeMode, ProtocolParams, Transaction};
 use nyx_core::storage::MemoryStorage;
 use nyx_core::dag::DagProcessor;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{Mutex, RwLock, mpsc};
+use tokio::sync::{broadcast, Mutex, RwLock, mpsc};
 use tokio::time::{interval, Duration};
-use tracing::{debug, info, warn};
+use tracing::{debug, info, warn, Instrument};
 
 /// Node configuration
 #[derive(Clone, Debug)]
@@ -38,8 +48,46 @@ pub struct NodeConfig {
     /// Bootstrap peer addresses
     pub bootstrap_peers: Vec<SocketAddr>,
 
-    /// Node identifier
-    pub node_id: Vec<u8>,
+    /// DNS seeds resolved when known and bootstrap peers are not enough
+    pub dns_seeds: Vec<String>,
+
+    /// Fall back to the compiled-in seed nodes if no DNS seed resolves
+    pub use_fallback_seeds: bool,
+
+    /// Node identity; `None` loads the one in `data_dir`, generating it on
+    /// first run, or uses a fresh one without a data directory
+    pub identity: Option<NodeIdentity>,
+
+    /// Network to join; peers on other networks are rejected
+    pub network: NetworkId,
+
+    /// Consensus parameters of the network, from its genesis
+    pub params: ProtocolParams,
+
+    /// Directory for persistent state such as the address book; `None`
+    /// keeps everything in memory
+    pub data_dir: Option<PathBuf>,
+
+    /// Address book file; `None` uses `peers.json` in `data_dir`
+    pub address_book_file: Option<PathBuf>,
+
+    /// Connections receiving no message for this long are closed
+    pub idle_timeout: Duration,
+
+    /// Delay before redialing a peer we dialed whose connection went idle
+    pub redial_delay: Duration,
+
+    /// Sign every outgoing message with the node identity key
+    pub sign_messages: bool,
+
+    /// Drop messages from peers that are not signed by them
+    pub require_signed_messages: bool,
+
+    /// Upload and download caps
+    pub bandwidth: BandwidthLimits,
+
+    /// Snapshots whose transactions are kept; `None` keeps full history
+    pub retained_snapshots: Option<u64>,
 }
 
 impl Default for NodeConfig {
@@ -49,7 +97,19 @@ impl Default for NodeConfig {
             max_peers: MAX_PEERS,
             min_peers: MIN_PEERS,
             bootstrap_peers: Vec::new(),
-            node_id: vec![0u8; 32],
+            dns_seeds: Vec::new(),
+            use_fallback_seeds: false,
+            identity: None,
+            network: NetworkId::Mainnet,
+            params: ProtocolParams::for_network(NetworkId::Mainnet),
+            data_dir: None,
+            address_book_file: None,
+            idle_timeout: Duration::from_secs(IDLE_TIMEOUT_SECS),
+            redial_delay: Duration::from_secs(REDIAL_DELAY_SECS),
+            sign_messages: false,
+            require_signed_messages: false,
+            bandwidth: BandwidthLimits::default(),
+            retained_snapshots: None,
         }
     }
 }
@@ -60,6 +120,9 @@ pub struct Node {
     /// Node configuration
     config: NodeConfig,
 
+    /// Key proving our peer ID in handshakes
+    identity: NodeIdentity,
+
     /// Peer manager
     peer_manager: Arc<RwLock<PeerManager>>,
 
@@ -69,8 +132,21 @@ pub struct Node {
     /// Sync manager
     sync: Arc<SyncManager>,
 
+    /// Known peer addresses
+    address_book: Arc<RwLock<AddressBook>>,
+
+    /// Node-wide bandwidth caps
+    bandwidth: Arc<Bandwidth>,
+
     /// DAG processor
-    _dag: Arc<RwLock<DagProcessor>>,
+    _dag: DagProcessor,
+
+    /// Live transactions received from peers, for local admission
+    received: broadcast::Sender<ReceivedTransaction>,
+
+    /// Addresses of dialed peers due for a redial
+    redial_tx: mpsc::UnboundedSender<SocketAddr>,
+    redial_rx: Arc<Mutex<mpsc::UnboundedReceiver<SocketAddr>>>,
 
     /// Shutdown signal
     shutdown_tx: mpsc::Sender<()>,
@@ -80,26 +156,64 @@ pub struct Node {
 impl Node {
     /// Creates a new network node
     pub async fn new(config: NodeConfig) -> Result<Self> {
+        // Unlike the address book, a corrupt key is fatal: silently taking
+        // a new identity would change our peer ID
+        let identity = match (&config.identity, &config.data_dir) {
+            (Some(identity), _) => identity.clone(),
+            (None, Some(dir)) => NodeIdentity::load_or_generate(dir)?,
+            (None, None) => NodeIdentity::generate(),
+        };
+
         // Initialize storage and DAG
         let storage = MemoryStorage::new();
-        let dag = DagProcessor::new(storage);
-        let dag = Arc::new(RwLock::new(dag));
+        let dag = DagProcessor::new(storage)
+            .with_network(config.network)
+            .with_params(config.params.clone());
 
         // Initialize components
         let peer_manager = Arc::new(RwLock::new(PeerManager::new(config.max_peers)));
-        let gossip = Arc::new(GossipEngine::new());
-        let sync = Arc::new(SyncManager::new(dag.clone()));
+        let mut gossip = GossipEngine::new().with_required_signatures(config.require_signed_messages);
+        if config.sign_messages {
+            gossip = gossip.with_identity(identity.clone());
+        }
+        let gossip = Arc::new(gossip);
+        let mut finality = FinalityTracker::new(dag.clone());
+        if let Some(keep) = config.retained_snapshots {
+            finality = finality.with_retained_snapshots(keep);
+        }
+        let sync = Arc::new(SyncManager::with_finality(dag.clone(), finality));
+
+        // A corrupt address book only costs us the remembered peers
+        let address_book_file = config.address_book_file.clone()
+            .or_else(|| config.data_dir.as_ref().map(|dir| dir.join(ADDRESS_BOOK_FILE)));
+        let address_book = match &address_book_file {
+            Some(path) => AddressBook::load_file(path).unwrap_or_else(|e| {
+                warn!("Failed to load address book, starting empty: {}", e);
+                AddressBook::new()
+            }),
+            None => AddressBook::new(),
+        };
+        info!("Loaded {} known peer addresses", address_book.len());
 
         let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+        let (redial_tx, redial_rx) = mpsc::unbounded_channel();
+        let (received, _) = broadcast::channel(RECEIVED_TX_CAPACITY);
+        let bandwidth = Arc::new(Bandwidth::new(config.bandwidth));
 
-        info!("Created node with ID: {:?}", hex::encode(&config.node_id));
+        info!("Created node with ID: {}", hex::encode(identity.peer_id()));
 
         Ok(Self {
             config,
+            identity,
             peer_manager,
             gossip,
             sync,
+            address_book: Arc::new(RwLock::new(address_book)),
+            bandwidth,
             _dag: dag,
+            received,
+            redial_tx,
+            redial_rx: Arc::new(Mutex::new(redial_rx)),
             shutdown_tx,
             shutdown_rx: Arc::new(Mutex::new(shutdown_rx)),
         })
@@ -113,13 +227,14 @@ impl Node {
         let listener = TcpListener::bind(self.config.listen_addr).await?;
         info!("Listening on {}", self.config.listen_addr);
 
-        // Connect to bootstrap peers
-        self.connect_to_bootstrap_peers().await?;
+        // Connect to known and bootstrap peers
+        self.connect_to_initial_peers().await?;
 
         // Spawn background tasks
         let heartbeat_handle = self.spawn_heartbeat_task();
         let sync_handle = self.spawn_sync_task();
         let mut shutdown_rx = self.shutdown_rx.lock().await;
+        let mut redial_rx = self.redial_rx.lock().await;
 
         // Main accept loop
         loop {
@@ -130,6 +245,17 @@ impl Node {
                     self.handle_new_connection(stream, addr).await;
                 }
 
+                // Redial peers whose connection went idle
+                Some(addr) = redial_rx.recv() => {
+                    let node = self.clone();
+                    tokio::spawn(async move {
+                        match node.connect_to_peer(addr).await {
+                            Ok(()) => info!("Redialed peer {}", addr),
+                            Err(e) => warn!("Failed to redial peer {}: {}", addr, e),
+                        }
+                    });
+                }
+
                 // Shutdown signal
                 _ = shutdown_rx.recv() => {
                     info!("Received shutdown signal");
@@ -151,115 +277,243 @@ impl Node {
         let peer_manager = self.peer_manager.clone();
         let gossip = self.gossip.clone();
         let sync = self.sync.clone();
-        let _node_id = self.config.node_id.clone();
+        let received = self.received.clone();
+        let identity = self.identity.clone();
+        let network = self.config.network;
+        let mode = self.mode();
+        let (upload, download) = self.bandwidth.connection();
+        let limits = self.config.params.limits;
+        let idle_timeout = self.config.idle_timeout;
 
         tokio::spawn(async move {
             // Check if we can accept more peers
             {
                 let manager = peer_manager.read().await;
+                if manager.is_banned(&addr.ip()) {
+                    debug!("Rejecting connection from banned {}", addr);
+                    return;
+                }
                 if !manager.can_accept_more() {
                     warn!("Max peers reached, rejecting connection from {}", addr);
                     return;
                 }
             }
 
-            // Split the stream
-            let (mut reader, writer) = stream.into_split();
-            let writer = Arc::new(Mutex::new(writer));
-
-            // Create peer
-            let peer_id = generate_peer_id(&addr);
-            let mut peer = Peer::new(peer_id.clone(), addr);
-            peer.update_state(PeerState::Connected);
+            // Create peer; its ID is known once the handshake proves it
+            let mut peer = Peer::new(PeerId::new(), addr).with_limits(limits);
+            let mut connection = PeerConnection::new(stream, limits)
+                .with_traffic(peer.traffic().clone())
+                .with_throttle(upload, download);
 
-            // Add to peer manager
-            {
-                let mut manager = peer_manager.write().await;
-                if let Err(e) = manager.add_peer(peer.clone()) {
-                    warn!("Failed to add peer: {}", e);
+            match exchange_handshake(addr, &mut connection, &identity, network, mode).await {
+                Ok((peer_id, peer_mode)) => {
+                    peer.id = peer_id;
+                    peer.mode = peer_mode;
+                }
+                Err(e) => {
+                    warn!("Handshake with {} failed: {}", addr, e);
                     return;
                 }
             }
+            let (reader, writer) = connection.into_split();
+            let writer = Arc::new(Mutex::new(writer));
+            peer.update_state(PeerState::Connected);
 
-            // Register with gossip
-            gossip
-                .register_peer(peer_id.clone(), writer.clone())
-                .await;
-
-            info!("Connected to peer {:?} at {}", peer_id, addr);
-
-            // Handle peer messages
-            loop {
-                match peer.receive_message(&mut reader).await {
-                    Ok(message) => {
-                        if let Err(e) = handle_message(
-                            message,
-                            &mut peer,
-                            writer.clone(),
-                            &gossip,
-                            &sync,
-                            &peer_manager,
-                        )
-                        .await
-                        {
-                            warn!("Error handling message from {:?}: {}", peer_id, e);
-                        }
+            let span = trace::peer_span(&peer.id, addr);
+            async move {
+                // Add to peer manager
+                {
+                    let mut manager = peer_manager.write().await;
+                    if manager.get_peer(&peer.id).is_some() {
+                        debug!("Already connected, dropping duplicate connection");
+                        return;
                     }
-                    Err(e) => {
-                        warn!("Error receiving message from {:?}: {}", peer_id, e);
-                        break;
+                    if let Err(e) = manager.add_peer(peer.clone()) {
+                        warn!("Failed to add peer: {}", e);
+                        return;
                     }
                 }
+
+                // Register with gossip
+                gossip
+                    .register_peer(peer.id.clone(), writer)
+                    .await;
+
+                info!("Connected to peer");
+
+                // Inbound peers connect from ephemeral ports, so they are not
+                // redialed
+                serve_peer(peer, reader, idle_timeout, gossip, sync, peer_manager, received).await;
+            }.instrument(span).await;
+        });
+    }
+
+    /// Connects to initial peers
+    ///
+    /// Peers from the address book are tried first, most recently
+    /// successful first, then bootstrap peers, until `min_peers`
+    /// connections are up. If that is not enough, DNS seeds are resolved
+    /// and, when none resolve, the compiled-in fallback seeds are tried.
+    async fn connect_to_initial_peers(&self) -> Result<()> {
+        let bootstrap = &self.config.bootstrap_peers;
+        let mut candidates = self.address_book.read().await.candidates();
+        candidates.retain(|addr| !bootstrap.contains(addr));
+        self.connect_to_candidates(&candidates, true).await;
+
+        // Bootstrap peers are the operator's choice, so they are exempt
+        // from group diversity
+        self.connect_to_candidates(bootstrap, false).await;
+        candidates.extend_from_slice(bootstrap);
+
+        if !self.needs_peers().await {
+            return self.save_address_book().await;
+        }
+
+        let mut seeds = seeds::resolve_dns_seeds(&self.config.dns_seeds).await;
+        if seeds.is_empty() && self.config.use_fallback_seeds {
+            info!("No DNS seed resolved, using fallback seed nodes");
+            seeds = seeds::fallback_seed_nodes();
+        }
+        seeds.retain(|addr| !candidates.contains(addr));
+        {
+            let mut book = self.address_book.write().await;
+            for addr in &seeds {
+                book.add_new(*addr, addr.ip());
             }
+        }
+        self.connect_to_candidates(&seeds, true).await;
+
+        self.save_address_book().await
+    }
+
+    /// Checks whether fewer than `min_peers` peers are connected
+    async fn needs_peers(&self) -> bool {
+        self.peer_manager.read().await.peer_count() < self.config.min_peers
+    }
 
-            // Cleanup
-            gossip.unregister_peer(&peer_id).await;
+    /// Tries addresses in order until enough peers are connected
+    ///
+    /// With `diverse`, an address is skipped while
+    /// [`MAX_OUTBOUND_PER_GROUP`] outbound peers share its network group,
+    /// so no single subnet can take all our outbound slots.
+    async fn connect_to_candidates(&self, candidates: &[SocketAddr], diverse: bool) {
+        for &addr in candidates {
+            if !self.needs_peers().await {
+                break;
+            }
             {
-                let mut manager = peer_manager.write().await;
-                manager.remove_peer(&peer_id);
+                let manager = self.peer_manager.read().await;
+                if manager.peer_by_address(&addr).is_some() {
+                    continue;
+                }
+                if diverse && manager.outbound_in_group(&addr) >= MAX_OUTBOUND_PER_GROUP {
+                    debug!("Skipping {}, its network group already has an outbound peer", addr);
+                    continue;
+                }
             }
 
-            info!("Disconnected from peer {:?}", peer_id);
-        });
+            match self.connect_to_peer(addr).await {
+                Ok(()) => {
+                    info!("Connected to peer {}", addr);
+                    self.address_book.write().await.record_success(addr);
+                }
+                Err(e) => {
+                    warn!("Failed to connect to peer {}: {}", addr, e);
+                    self.address_book.write().await.record_failure(&addr);
+                }
+            }
+        }
     }
 
-    /// Connects to bootstrap peers
-    async fn connect_to_bootstrap_peers(&self) -> Result<()> {
-        for addr in &self.config.bootstrap_peers {
-            match self.connect_to_peer(*addr).await {
-                Ok(()) => info!("Connected to bootstrap peer {}", addr),
-                Err(e) => warn!("Failed to connect to bootstrap peer {}: {}", addr, e),
-            }
+    /// Saves the address book, logging failures
+    async fn save_address_book(&self) -> Result<()> {
+        if let Err(e) = self.address_book.read().await.save() {
+            warn!("Failed to save address book: {}", e);
         }
         Ok(())
     }
 
+    /// Gets the address book
+    pub fn address_book(&self) -> Arc<RwLock<AddressBook>> {
+        self.address_book.clone()
+    }
+
     /// Connects to a specific peer
     async fn connect_to_peer(&self, addr: SocketAddr) -> Result<()> {
-        let peer_id = generate_peer_id(&addr);
-        let mut peer = Peer::new(peer_id.clone(), addr);
-
-        let stream = peer.connect().await?;
-        let (_reader, writer) = stream.into_split();
+        if self.peer_manager.read().await.is_banned(&addr.ip()) {
+            return Err(NetworkError::PeerBanned(addr.ip().to_string()));
+        }
+        // The peer's ID is known once the handshake proves it
+        let mut peer = Peer::new(PeerId::new(), addr).with_limits(self.config.params.limits);
+
+        let (upload, download) = self.bandwidth.connection();
+        let mut connection = peer.connect().await?.with_throttle(upload, download);
+        let (peer_id, peer_mode) =
+            exchange_handshake(addr, &mut connection, &self.identity, self.config.network, self.mode()).await?;
+        peer.id = peer_id.clone();
+        peer.mode = peer_mode;
+        let (reader, writer) = connection.into_split();
         let writer = Arc::new(Mutex::new(writer));
 
-
         // Add to peer manager
         {
             let mut manager = self.peer_manager.write().await;
+            if manager.get_peer(&peer_id).is_some() {
+                return Err(NetworkError::ConnectionError(format!(
+                    "Already connected to {} at another address",
+                    hex::encode(&peer_id)
+                )));
+            }
             manager.add_peer(peer.clone())?;
         }
 
         // Register with gossip
-        self.gossip.register_peer(peer_id, writer).await;
+        self.gossip.register_peer(peer_id.clone(), writer).await;
+
+        // Read responses (including heartbeat pongs) from the peer, and
+        // redial it if the connection goes idle
+        let served = serve_peer(
+            peer,
+            reader,
+            self.config.idle_timeout,
+            self.gossip.clone(),
+            self.sync.clone(),
+            self.peer_manager.clone(),
+            self.received.clone(),
+        );
+        let redial_tx = self.redial_tx.clone();
+        let redial_delay = self.config.redial_delay;
+        tokio::spawn(async move {
+            if served.await == PeerExit::IdleTimeout {
+                tokio::time::sleep(redial_delay).await;
+                let _ = redial_tx.send(addr);
+            }
+        }.instrument(trace::peer_span(&peer_id, addr)));
 
         Ok(())
     }
 
+    /// Gets our peer ID, derived from the node identity key
+    pub fn peer_id(&self) -> PeerId {
+        self.identity.peer_id()
+    }
+
+    /// Gets the history this node serves to peers
+    pub fn mode(&self) -> NodeMode {
+        match self.config.retained_snapshots {
+            Some(_) => NodeMode::Pruned,
+            None => NodeMode::Archive,
+        }
+    }
+
     /// Spawns heartbeat task to maintain peer connections
+    ///
+    /// Every interval each connected peer is pinged; the pong updates its
+    /// rolling latency. Peers that miss [`MAX_MISSED_HEARTBEATS`] pings in a
+    /// row are disconnected.
     fn spawn_heartbeat_task(&self) -> tokio::task::JoinHandle<()> {
         let peer_manager = self.peer_manager.clone();
-        let _gossip = self.gossip.clone();
+        let gossip = self.gossip.clone();
 
         tokio::spawn(async move {
             let mut timer = interval(Duration::from_secs(HEARTBEAT_INTERVAL_SECS));
@@ -268,13 +522,7 @@ impl Node {
                 timer.tick().await;
 
                 debug!("Running heartbeat check");
-
-                let manager = peer_manager.write().await;
-                let connected = manager.connected_peers();
-
-                debug!("Connected peers: {}", connected.len());
-
-                // TODO: Send ping to all peers and check responses
+                heartbeat(&gossip, &peer_manager).await;
             }
         })
     }
@@ -302,15 +550,158 @@ impl Node {
         })
     }
 
+    /// Starts snap sync and requests checkpoints from connected peers
+    ///
+    /// The node must not have applied any snapshots yet. Live transaction
+    /// gossip is paused until [`MIN_SNAP_SYNC_PEERS`] peers serve the same
+    /// valid checkpoint.
+    pub async fn start_snap_sync(&self, validators: ValidatorSet) -> Result<usize> {
+        self.sync.start_snap_sync(validators, MIN_SNAP_SYNC_PEERS).await;
+
+        let manager = self.peer_manager.read().await;
+        let mut peers: Vec<Peer> = manager.connected_peers()
+            .into_iter()
+            .cloned()
+            .collect();
+        drop(manager);
+
+        self.gossip.broadcast(Message::new(MessageType::SnapshotRequest), &mut peers).await
+    }
+
     /// Broadcasts a transaction to the network
+    ///
+    /// Only enqueues the transaction for each peer; peers too slow to keep
+    /// up are disconnected.
     pub async fn broadcast_transaction(&self, tx: nyx_core::Transaction) -> Result<()> {
-        let manager = self.peer_manager.write().await;
+        let manager = self.peer_manager.read().await;
         let mut peers: Vec<Peer> = manager.connected_peers()
             .into_iter()
             .cloned()
             .collect();
+        drop(manager);
+
+        let span = trace::tx_span(&tx.id());
+        async {
+            self.gossip.gossip_transaction(tx, &mut peers).await?;
+            remove_slow_peers(&self.gossip, &self.peer_manager).await;
+            Ok(())
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Subscribes to live transactions received from peers
+    ///
+    /// Peer readers never wait for subscribers: a subscriber that falls
+    /// more than [`RECEIVED_TX_CAPACITY`] transactions behind misses the
+    /// oldest ones. Transactions are relayed to other peers regardless.
+    pub fn subscribe_transactions(&self) -> broadcast::Receiver<ReceivedTransaction> {
+        self.received.subscribe()
+    }
+
+    /// Records that a transaction received from `peer` was not admitted
+    ///
+    /// Counted in the peer's [`PeerStats`]; unknown peers are ignored.
+    pub async fn report_rejection(&self, peer: &PeerId, tx_hash: &nyx_core::Hash, code: RejectCode) {
+        if let Some(peer) = self.peer_manager.read().await.get_peer(peer) {
+            peer.traffic().record_rejected(code);
+        }
+        debug!(
+            peer_id = %trace::short_hex(peer),
+            tx = %trace::short_hex(tx_hash),
+            "Rejected transaction: {}", code
+        );
+    }
+
+    /// Changes the maximum number of connected peers
+    pub async fn set_max_peers(&self, max_peers: usize) {
+        self.peer_manager.write().await.set_max_peers(max_peers);
+        info!("Max peers set to {}", max_peers);
+    }
+
+    /// Changes the number of peers each gossiped message is sent to
+    /// (`None` for all)
+    pub fn set_gossip_fanout(&self, fanout: Option<usize>) {
+        self.gossip.set_fanout(fanout);
+        info!("Gossip fanout set to {:?}", fanout);
+    }
+
+    /// Connects to a peer on request and remembers its address
+    ///
+    /// Does nothing if the peer is already connected.
+    ///
+    /// # Errors
+    /// - `PeerBanned` if the address is banned
+    /// - any error of connecting or the handshake
+    pub async fn add_peer(&self, addr: SocketAddr) -> Result<()> {
+        if self.peer_manager.read().await.peer_by_address(&addr).is_some() {
+            return Ok(());
+        }
+        self.connect_to_peer(addr).await?;
+        self.address_book.write().await.record_success(addr);
+        info!("Added peer {}", addr);
+        self.save_address_book().await
+    }
+
+    /// Disconnects the peer at `addr`
+    ///
+    /// # Returns
+    /// Whether such a peer was connected
+    pub async fn disconnect_peer(&self, addr: SocketAddr) -> bool {
+        let removed = {
+            let mut manager = self.peer_manager.write().await;
+            let peer_id = manager.peer_by_address(&addr).map(|p| p.id.clone());
+            if let Some(peer_id) = &peer_id {
+                manager.remove_peer(peer_id);
+            }
+            peer_id
+        };
+
+        let Some(peer_id) = removed else {
+            return false;
+        };
+        self.gossip.unregister_peer(&peer_id).await;
+        info!("Disconnected peer {}", addr);
+        true
+    }
+
+    /// Bans an IP address for `duration`, disconnecting its peers
+    ///
+    /// # Returns
+    /// Number of peers disconnected
+    pub async fn ban_peer(&self, ip: IpAddr, duration: Duration) -> usize {
+        let removed = self.peer_manager.write().await.ban(ip, duration);
+        for peer_id in &removed {
+            self.gossip.unregister_peer(peer_id).await;
+        }
+
+        info!("Banned {} for {:?}, disconnected {} peers", ip, duration, removed.len());
+        removed.len()
+    }
 
-        self.gossip.gossip_transaction(tx, &mut peers).await
+    /// Lifts the ban of an IP address
+    ///
+    /// # Returns
+    /// Whether the address was banned
+    pub async fn unban_peer(&self, ip: IpAddr) -> bool {
+        let unbanned = self.peer_manager.write().await.unban(&ip);
+        if unbanned {
+            info!("Unbanned {}", ip);
+        }
+        unbanned
+    }
+
+    /// Gets the banned IP addresses with the time left on each ban
+    pub async fn bans(&self) -> Vec<(IpAddr, Duration)> {
+        self.peer_manager.write().await.bans()
+    }
+
+    /// Gets every managed peer, connecting ones included
+    pub async fn peer_table(&self) -> Vec<PeerStats> {
+        self.peer_manager.read().await.peers()
+            .iter()
+            .map(PeerStats::from)
+            .collect()
     }
 
     /// Gets node statistics
@@ -319,8 +710,14 @@ impl Node {
         let gossip_stats = self.gossip.stats().await;
         let sync_state = self.sync.get_state().await;
 
+        let peers = peer_manager.connected_peers()
+            .into_iter()
+            .map(PeerStats::from)
+            .collect();
+
         NodeStats {
             peer_count: peer_manager.peer_count(),
+            peers,
             gossip_stats,
             sync_state,
         }
@@ -340,6 +737,9 @@ pub struct NodeStats {
     /// Number of connected peers
     pub peer_count: usize,
 
+    /// Per-peer connection statistics
+    pub peers: Vec<PeerStats>,
+
     /// Gossip engine statistics
     pub gossip_stats: crate::gossip::GossipStats,
 
@@ -347,62 +747,322 @@ pub struct NodeStats {
     pub sync_state: crate::sync::SyncState,
 }
 
-use tokio::net::tcp::OwnedWriteHalf;
+/// Live transaction received from a peer
+#[derive(Debug, Clone)]
+pub struct ReceivedTransaction {
+    /// The transaction
+    pub tx: Transaction,
+
+    /// Peer it came from, to report a rejection to
+    pub peer: PeerId,
+}
+
+/// Connection statistics for one peer
+#[derive(Debug, Clone)]
+pub struct PeerStats {
+    /// Peer identifier
+    pub peer_id: PeerId,
+
+    /// Peer's network address
+    pub address: SocketAddr,
+
+    /// Rolling average heartbeat round trip (milliseconds)
+    pub latency_ms: Option<u64>,
+
+    /// Consecutive unanswered heartbeats
+    pub missed_heartbeats: u32,
+
+    /// Connection state
+    pub state: PeerState,
+
+    /// Bytes sent to the peer
+    pub bytes_sent: u64,
+
+    /// Bytes received from the peer
+    pub bytes_received: u64,
+
+    /// Transactions from the peer refused locally
+    pub transactions_rejected: u64,
+
+    /// Transactions from the peer refused for exceeding its quota
+    pub quota_exceeded: u64,
+
+    /// History the peer serves
+    pub mode: NodeMode,
+}
+
+impl From<&Peer> for PeerStats {
+    fn from(peer: &Peer) -> Self {
+        Self {
+            peer_id: peer.id.clone(),
+            address: peer.address,
+            latency_ms: peer.latency_ms,
+            missed_heartbeats: peer.missed_heartbeats,
+            state: peer.state.clone(),
+            bytes_sent: peer.traffic().bytes_sent(),
+            bytes_received: peer.traffic().bytes_received(),
+            transactions_rejected: peer.traffic().transactions_rejected(),
+            quota_exceeded: peer.traffic().quota_exceeded(),
+            mode: peer.mode,
+        }
+    }
+}
+
+/// Exchanges handshakes with a freshly connected peer
+///
+/// Each side announces its network, identity key, node mode and a fresh
+/// nonce, then signs the other side's nonce. The peer must be on the same
+/// network and prove it holds the key its peer ID derives from.
+///
+/// # Returns
+/// The peer's verified peer ID and its announced mode
+///
+/// # Errors
+/// - `Timeout` if the peer does not answer in time
+/// - `InvalidMessage` if the peer deviates from the handshake
+/// - `NetworkMismatch` if the peer is on another network
+/// - `AuthenticationFailed` if the peer's signature does not verify
+/// - `ConnectionError` if the peer is ourselves
+async fn exchange_handshake(
+    address: SocketAddr,
+    connection: &mut PeerConnection,
+    identity: &NodeIdentity,
+    network: NetworkId,
+    mode: NodeMode,
+) -> Result<(PeerId, NodeMode)> {
+    let nonce: [u8; 32] = rand::random();
+    connection.send(&Message::new(MessageType::Handshake {
+        network,
+        public_key: identity.public_key().to_vec(),
+        nonce,
+        mode,
+    })).await?;
+
+    let (public_key, challenge, peer_mode) = match recv_handshake(address, connection).await?.message_type {
+        MessageType::Handshake { network: theirs, .. } if theirs != network => {
+            return Err(NetworkError::NetworkMismatch(format!(
+                "Peer {} is on {}, we are on {}",
+                address, theirs, network
+            )));
+        }
+        MessageType::Handshake { public_key, nonce, mode, .. } => (public_key, nonce, mode),
+        other => {
+            return Err(NetworkError::InvalidMessage(format!(
+                "Expected handshake, got {}",
+                other.type_name()
+            )));
+        }
+    };
+
+    connection.send(&Message::new(MessageType::HandshakeAuth {
+        signature: identity.sign_handshake(network, &challenge),
+    })).await?;
+
+    match recv_handshake(address, connection).await?.message_type {
+        MessageType::HandshakeAuth { signature }
+            if identity::verify_handshake(network, &nonce, &public_key, &signature) => {}
+        MessageType::HandshakeAuth { .. } => {
+            return Err(NetworkError::AuthenticationFailed(format!(
+                "Invalid handshake signature from {}",
+                address
+            )));
+        }
+        other => {
+            return Err(NetworkError::InvalidMessage(format!(
+                "Expected handshake signature, got {}",
+                other.type_name()
+            )));
+        }
+    }
+
+    let peer_id = identity::peer_id_from_public_key(&public_key);
+    if peer_id == identity.peer_id() {
+        return Err(NetworkError::ConnectionError(format!("{} is ourselves", address)));
+    }
+    Ok((peer_id, peer_mode))
+}
+
+/// Receives the next handshake message from a peer
+async fn recv_handshake(address: SocketAddr, connection: &mut PeerConnection) -> Result<Message> {
+    tokio::time::timeout(Duration::from_secs(CONNECTION_TIMEOUT_SECS), connection.recv())
+        .await
+        .map_err(|_| NetworkError::Timeout(format!("No handshake from {}", address)))?
+}
+
+/// Why [`serve_peer`] stopped serving a peer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PeerExit {
+    /// The connection closed or failed
+    Closed,
+
+    /// No message arrived within the idle timeout
+    IdleTimeout,
+}
+
+/// Reads and handles messages from a peer until the connection closes or
+/// goes idle for `idle_timeout`
+///
+/// Heartbeat pings go out every [`HEARTBEAT_INTERVAL_SECS`], so a live
+/// peer always has something to answer; a half-open socket does not.
+async fn serve_peer(
+    mut peer: Peer,
+    mut reader: FramedReader,
+    idle_timeout: Duration,
+    gossip: Arc<GossipEngine>,
+    sync: Arc<SyncManager>,
+    peer_manager: Arc<RwLock<PeerManager>>,
+    received: broadcast::Sender<ReceivedTransaction>,
+) -> PeerExit {
+    let peer_id = peer.id.clone();
+
+    let exit = loop {
+        let Ok(result) = tokio::time::timeout(idle_timeout, reader.recv()).await else {
+            warn!("No message for {:?}, closing idle connection", idle_timeout);
+            break PeerExit::IdleTimeout;
+        };
+
+        match result {
+            Ok(message) => {
+                // Peers disconnected or banned meanwhile are not served
+                if !is_managed(&peer_manager, &peer).await {
+                    break PeerExit::Closed;
+                }
+                peer.mark_seen();
+                if let Err(e) = gossip.authenticate(&message, &peer) {
+                    warn!("Dropping message: {}", e);
+                    continue;
+                }
+                if let Err(e) = handle_message(
+                    message,
+                    &mut peer,
+                    &gossip,
+                    &sync,
+                    &peer_manager,
+                    &received,
+                )
+                .await
+                {
+                    warn!("Error handling message: {}", e);
+                }
+            }
+            Err(e) => {
+                warn!("Error receiving message: {}", e);
+                break PeerExit::Closed;
+            }
+        }
+    };
+
+    // Cleanup; dropping the reader and closing the outbound queue (which
+    // owns the writer) closes the socket. A newer connection to the same
+    // address is left alone.
+    peer.update_state(PeerState::Disconnected);
+    let replaced = {
+        let mut manager = peer_manager.write().await;
+        let replaced = manager.get_peer(&peer_id).is_some_and(|p| !p.is_same_connection(&peer));
+        if !replaced {
+            manager.remove_peer(&peer_id);
+        }
+        replaced
+    };
+    if !replaced {
+        gossip.unregister_peer(&peer_id).await;
+    }
+
+    info!("Disconnected from peer");
+    exit
+}
+
+/// Checks whether the peer manager still holds this connection
+async fn is_managed(peer_manager: &RwLock<PeerManager>, peer: &Peer) -> bool {
+    peer_manager.read().await
+        .get_peer(&peer.id)
+        .is_some_and(|p| p.is_same_connection(peer))
+}
+
+/// Runs one heartbeat round
+///
+/// Disconnects peers that missed too many heartbeats and pings the rest.
+async fn heartbeat(gossip: &GossipEngine, peer_manager: &RwLock<PeerManager>) {
+    let mut to_ping = Vec::new();
+    let mut unresponsive = Vec::new();
+    {
+        let mut manager = peer_manager.write().await;
+        for peer in manager.connected_peers_mut() {
+            if peer.begin_heartbeat() >= MAX_MISSED_HEARTBEATS {
+                unresponsive.push(peer.id.clone());
+            } else {
+                to_ping.push(peer.id.clone());
+            }
+        }
+
+        for peer_id in &unresponsive {
+            manager.remove_peer(peer_id);
+        }
+    }
+
+    for peer_id in &unresponsive {
+        warn!(peer_id = %trace::short_hex(peer_id), "Peer missed {} heartbeats, disconnecting", MAX_MISSED_HEARTBEATS);
+        gossip.unregister_peer(peer_id).await;
+    }
+
+    debug!("Pinging {} connected peers", to_ping.len());
 
-// ... (previous code)
+    for peer_id in &to_ping {
+        if let Err(e) = gossip.send_to(peer_id, &Message::new(MessageType::Ping)).await {
+            warn!(peer_id = %trace::short_hex(peer_id), "Failed to ping peer: {}", e);
+        }
+    }
+}
 
 /// Handles an incoming message
 async fn handle_message(
     message: Message,
     peer: &mut Peer,
-    writer: Arc<Mutex<OwnedWriteHalf>>,
     gossip: &Arc<GossipEngine>,
     sync: &Arc<SyncManager>,
     peer_manager: &Arc<RwLock<PeerManager>>,
+    received: &broadcast::Sender<ReceivedTransaction>,
 ) -> Result<()> {
     debug!("Handling message type: {}", message.message_type.type_name());
 
     match message.message_type {
         MessageType::Transaction(tx) => {
-            // Add to DAG and gossip to other peers
-            info!("Received transaction: {:?}", hex::encode(tx.id()));
-
-            // Gossip to other peers
-            let manager = peer_manager.write().await;
-            let mut peers: Vec<Peer> = manager
-                .connected_peers()
-                .into_iter()
-                .filter(|p| p.id != peer.id) // Don't send back to sender
-                .cloned()
-                .collect();
-
-            drop(manager);
-
-            gossip.gossip_transaction(tx, &mut peers).await?;
+            let span = trace::tx_span(&tx.id());
+            relay_transaction(tx, peer, gossip, sync, peer_manager, received).instrument(span).await?;
         }
 
         MessageType::Ping => {
             // Respond with pong
-            let pong = Message::new(MessageType::Pong);
-            let mut stream = writer.lock().await;
-            peer.send_message(&mut stream, &pong).await?;
+            reply(gossip, peer, Message::new(MessageType::Pong)).await?;
         }
 
         MessageType::Pong => {
             // Update peer latency
-            debug!("Received pong from peer {:?}", peer.id);
+            let mut manager = peer_manager.write().await;
+            let latency = manager.get_peer_mut(&peer.id).and_then(|p| p.record_pong());
+            drop(manager);
+
+            if let Some(latency) = latency {
+                peer.record_latency(latency);
+                debug!("Received pong after {} ms", latency);
+            }
         }
 
         MessageType::SyncRequest { from_height } => {
             // Handle sync request
-            let mut stream = writer.lock().await;
-            sync.handle_sync_request(from_height, peer, &mut stream)
-                .await?;
+            for response in sync.handle_sync_request(from_height, peer).await? {
+                reply(gossip, peer, response).await?;
+            }
         }
 
         MessageType::SyncResponse { transactions } => {
             // Handle sync response
-            sync.handle_sync_response(transactions).await?;
+            let span = sync.session_span().await;
+            sync.handle_sync_response(transactions).instrument(span).await?;
+        }
+
+        MessageType::SyncPruned { retained_from } => {
+            sync.handle_sync_pruned(peer, retained_from);
         }
 
         MessageType::PeerDiscovery { peers: peer_addrs } => {
@@ -410,16 +1070,103 @@ async fn handle_message(
             debug!("Received {} peer addresses", peer_addrs.len());
             // TODO: Connect to new peers
         }
+
+        MessageType::Evidence(evidence) => {
+            // Relay; verification and slashing happen in the staking layer
+            info!("Received evidence against validator {}", evidence.validator());
+
+            let manager = peer_manager.read().await;
+            let mut peers: Vec<Peer> = manager
+                .connected_peers()
+                .into_iter()
+                .filter(|p| p.id != peer.id)
+                .cloned()
+                .collect();
+
+            drop(manager);
+
+            gossip.gossip_evidence(evidence, &mut peers).await?;
+        }
+
+        MessageType::SnapshotRequest => {
+            if let Some(response) = sync.handle_snapshot_request(peer).await? {
+                reply(gossip, peer, response).await?;
+            }
+        }
+
+        MessageType::SnapshotResponse(checkpoint) => {
+            let span = sync.session_span().await;
+            let restored = sync.handle_snapshot_response(peer.id.clone(), checkpoint)
+                .instrument(span.clone())
+                .await?;
+            if restored {
+                span.in_scope(|| info!("Snap sync complete, resuming live gossip"));
+            }
+        }
+
+        MessageType::Handshake { .. } | MessageType::HandshakeAuth { .. } => {
+            debug!("Ignoring repeated handshake");
+        }
+    }
+
+    Ok(())
+}
+
+/// Hands a received transaction to local subscribers and gossips it to
+/// every peer except the sender
+async fn relay_transaction(
+    tx: Transaction,
+    peer: &Peer,
+    gossip: &Arc<GossipEngine>,
+    sync: &Arc<SyncManager>,
+    peer_manager: &Arc<RwLock<PeerManager>>,
+    received: &broadcast::Sender<ReceivedTransaction>,
+) -> Result<()> {
+    // Live transactions wait until snap sync has restored a checkpoint
+    if !sync.is_live().await {
+        debug!("Snap syncing, ignoring transaction");
+        return Ok(());
     }
 
+    info!("Received transaction");
+
+    // Fails only when nobody is subscribed
+    let _ = received.send(ReceivedTransaction { tx: tx.clone(), peer: peer.id.clone() });
+
+    let manager = peer_manager.read().await;
+    let mut peers: Vec<Peer> = manager
+        .connected_peers()
+        .into_iter()
+        .filter(|p| p.id != peer.id) // Don't send back to sender
+        .cloned()
+        .collect();
+
+    drop(manager);
+
+    gossip.gossip_transaction(tx, &mut peers).await?;
+    remove_slow_peers(gossip, peer_manager).await;
+    Ok(())
+}
+
+/// Queues a response to a peer in its priority lane
+async fn reply(gossip: &GossipEngine, peer: &Peer, message: Message) -> Result<()> {
+    if !gossip.send_to(&peer.id, &message).await? {
+        debug!("Dropped {} response", message.message_type.type_name());
+    }
     Ok(())
 }
 
-/// Generates a peer ID from an address
-fn generate_peer_id(addr: &SocketAddr) -> Vec<u8> {
-    use nyx_core::hash::blake3_hash;
-    let addr_str = addr.to_string();
-    blake3_hash(addr_str.as_bytes()).to_vec()
+/// Drops peers the gossip engine disconnected for being too slow
+async fn remove_slow_peers(gossip: &GossipEngine, peer_manager: &RwLock<PeerManager>) {
+    let slow = gossip.take_slow_peers().await;
+    if slow.is_empty() {
+        return;
+    }
+
+    let mut manager = peer_manager.write().await;
+    for peer_id in &slow {
+        manager.remove_peer(peer_id);
+    }
 }
 
 #[cfg(test)]
@@ -442,13 +1189,317 @@ mod tests {
         assert_eq!(stats.peer_count, 0);
     }
 
-    #[test]
-    fn test_generate_peer_id() {
-        let addr: SocketAddr = "127.0.0.1:8000".parse().unwrap();
-        let id1 = generate_peer_id(&addr);
-        let id2 = generate_peer_id(&addr);
+    #[tokio::test]
+    async fn test_heartbeat_disconnects_unresponsive_peer() {
+        let gossip = GossipEngine::new();
+        let peer_manager = RwLock::new(PeerManager::new(MAX_PEERS));
+
+        let mut peer = Peer::new(vec![1], "127.0.0.1:8000".parse().unwrap());
+        peer.update_state(PeerState::Connected);
+        peer_manager.write().await.add_peer(peer).unwrap();
+
+        for _ in 0..MAX_MISSED_HEARTBEATS {
+            heartbeat(&gossip, &peer_manager).await;
+        }
+        assert_eq!(peer_manager.read().await.peer_count(), 1);
+
+        // A pong resets the count
+        peer_manager.write().await.get_peer_mut(&vec![1]).unwrap().record_pong().unwrap();
+        for _ in 0..MAX_MISSED_HEARTBEATS {
+            heartbeat(&gossip, &peer_manager).await;
+        }
+        assert_eq!(peer_manager.read().await.peer_count(), 1);
+
+        heartbeat(&gossip, &peer_manager).await;
+        assert_eq!(peer_manager.read().await.peer_count(), 0);
+    }
+
+    /// Spawns a minimal remote peer that answers handshakes for `network`
+    async fn spawn_remote(network: NetworkId) -> SocketAddr {
+        spawn_remote_with(network, NodeIdentity::from_seed([9u8; 32])).await
+    }
+
+    /// Spawns a minimal remote peer with the given identity
+    async fn spawn_remote_with(network: NetworkId, identity: NodeIdentity) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((stream, peer)) = listener.accept().await {
+                let identity = identity.clone();
+                tokio::spawn(async move {
+                    let mut connection = PeerConnection::new(stream, Default::default());
+                    if exchange_handshake(peer, &mut connection, &identity, network, NodeMode::Archive).await.is_ok() {
+                        while connection.recv().await.is_ok() {}
+                    }
+                });
+            }
+        });
+
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_handshake_rejects_other_network() {
+        let node = Node::new(NodeConfig::default()).await.unwrap();
+
+        let testnet_peer = spawn_remote(NetworkId::Testnet).await;
+        assert!(matches!(
+            node.connect_to_peer(testnet_peer).await,
+            Err(NetworkError::NetworkMismatch(_))
+        ));
+
+        let mainnet_peer = spawn_remote(NetworkId::Mainnet).await;
+        node.connect_to_peer(mainnet_peer).await.unwrap();
+        assert_eq!(node.stats().await.peer_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_handshake_announces_mode() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let (mode_tx, mode_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let (stream, peer) = listener.accept().await.unwrap();
+            let mut connection = PeerConnection::new(stream, Default::default());
+            let identity = NodeIdentity::generate();
+            let (_, mode) = exchange_handshake(peer, &mut connection, &identity, NetworkId::Mainnet, NodeMode::Archive)
+                .await
+                .unwrap();
+            let _ = mode_tx.send(mode);
+            while connection.recv().await.is_ok() {}
+        });
+
+        let node = Node::new(NodeConfig { retained_snapshots: Some(10), ..NodeConfig::default() }).await.unwrap();
+        assert_eq!(node.mode(), NodeMode::Pruned);
+        node.connect_to_peer(addr).await.unwrap();
+
+        assert_eq!(mode_rx.await.unwrap(), NodeMode::Pruned);
+        assert_eq!(node.peer_table().await[0].mode, NodeMode::Archive);
+    }
+
+    #[tokio::test]
+    async fn test_idle_connection_is_closed_and_redialed() {
+        let config = NodeConfig {
+            idle_timeout: Duration::from_millis(200),
+            redial_delay: Duration::ZERO,
+            ..NodeConfig::default()
+        };
+        let node = Node::new(config).await.unwrap();
+
+        // The remote answers the handshake and then stays silent
+        let remote = spawn_remote(NetworkId::Mainnet).await;
+        node.connect_to_peer(remote).await.unwrap();
+        assert_eq!(node.stats().await.peer_count, 1);
+
+        let redial = tokio::time::timeout(Duration::from_secs(5), async {
+            node.redial_rx.lock().await.recv().await
+        })
+        .await
+        .unwrap();
+        assert_eq!(redial, Some(remote));
+        assert_eq!(node.stats().await.peer_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_received_transactions_reach_subscribers() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let ring = nyx_core::RingSignature { ring_members: vec![vec![1], vec![2]], signature: vec![], key_image: [0u8; 32] };
+        let tx = Transaction::new(vec![], vec![], vec![ring], vec![], [0u8; 32], [1u8; 32]);
+        let sent = tx.clone();
+        tokio::spawn(async move {
+            let (stream, peer) = listener.accept().await.unwrap();
+            let mut connection = PeerConnection::new(stream, Default::default());
+            let identity = NodeIdentity::generate();
+            exchange_handshake(peer, &mut connection, &identity, NetworkId::Mainnet, NodeMode::Archive).await.unwrap();
+            connection.send(&Message::new(MessageType::Transaction(sent))).await.unwrap();
+            while connection.recv().await.is_ok() {}
+        });
+
+        let node = Node::new(NodeConfig::default()).await.unwrap();
+        let mut received = node.subscribe_transactions();
+        node.connect_to_peer(addr).await.unwrap();
+
+        let got = tokio::time::timeout(Duration::from_secs(5), received.recv()).await.unwrap().unwrap();
+        assert_eq!(got.tx.id(), tx.id());
+
+        node.report_rejection(&got.peer, &got.tx.id(), RejectCode::QuotaExceeded).await;
+        let stats = &node.peer_table().await[0];
+        assert_eq!((stats.transactions_rejected, stats.quota_exceeded), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_address_book_persists_connected_peers() {
+        let peer_addr = spawn_remote(NetworkId::Mainnet).await;
+        let data_dir = tempfile::tempdir().unwrap();
+
+        let config = NodeConfig {
+            bootstrap_peers: vec![peer_addr],
+            data_dir: Some(data_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        let node = Node::new(config.clone()).await.unwrap();
+        node.connect_to_initial_peers().await.unwrap();
+        assert!(node.address_book().read().await.get(&peer_addr).is_some());
+
+        // A restarted node remembers the peer
+        let restarted = Node::new(config).await.unwrap();
+        assert_eq!(restarted.address_book().read().await.candidates(), vec![peer_addr]);
+    }
+
+    #[tokio::test]
+    async fn test_dns_seed_bootstrap() {
+        let seed_addr = spawn_remote(NetworkId::Mainnet).await;
+
+        let config = NodeConfig {
+            dns_seeds: vec![seed_addr.to_string()],
+            min_peers: 1,
+            ..Default::default()
+        };
+
+        let node = Node::new(config).await.unwrap();
+        node.connect_to_initial_peers().await.unwrap();
+        assert_eq!(node.stats().await.peer_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_add_disconnect_and_ban_peer() {
+        let node = Node::new(NodeConfig::default()).await.unwrap();
+        let remote = spawn_remote(NetworkId::Mainnet).await;
+
+        node.add_peer(remote).await.unwrap();
+        let table = node.peer_table().await;
+        assert_eq!(table.len(), 1);
+        assert_eq!(table[0].state, PeerState::Connected);
+        assert!(table[0].bytes_sent > 0 && table[0].bytes_received > 0);
+
+        assert!(node.disconnect_peer(remote).await);
+        assert!(!node.disconnect_peer(remote).await);
+
+        // The old connection winding down leaves the new one alone
+        node.add_peer(remote).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(node.stats().await.peer_count, 1);
+
+        assert_eq!(node.ban_peer(remote.ip(), Duration::from_secs(60)).await, 1);
+        assert_eq!(node.stats().await.peer_count, 0);
+        assert!(matches!(node.add_peer(remote).await, Err(NetworkError::PeerBanned(_))));
+        assert_eq!(node.bans().await.len(), 1);
+
+        assert!(node.unban_peer(remote.ip()).await);
+        node.add_peer(remote).await.unwrap();
+        assert_eq!(node.stats().await.peer_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_peer_id_derives_from_identity_key() {
+        let remote_identity = NodeIdentity::from_seed([5u8; 32]);
+        let remote = spawn_remote_with(NetworkId::Mainnet, remote_identity.clone()).await;
+
+        let node = Node::new(NodeConfig::default()).await.unwrap();
+        node.connect_to_peer(remote).await.unwrap();
+        assert_eq!(node.peer_table().await[0].peer_id, remote_identity.peer_id());
+
+        // Dialing a node with our own key is refused
+        let own = Node::new(NodeConfig { identity: Some(remote_identity), ..NodeConfig::default() })
+            .await
+            .unwrap();
+        assert!(matches!(own.connect_to_peer(remote).await, Err(NetworkError::ConnectionError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_handshake_rejects_spoofed_key() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Claims a victim's key but can only sign with its own
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut connection = PeerConnection::new(stream, Default::default());
+            let victim = NodeIdentity::from_seed([5u8; 32]);
+            let attacker = NodeIdentity::from_seed([6u8; 32]);
+            connection.send(&Message::new(MessageType::Handshake {
+                network: NetworkId::Mainnet,
+                public_key: victim.public_key().to_vec(),
+                nonce: [0u8; 32],
+                mode: NodeMode::Archive,
+            })).await.unwrap();
+            let Ok(MessageType::Handshake { nonce, .. }) = connection.recv().await.map(|m| m.message_type) else {
+                panic!("expected handshake");
+            };
+            connection.send(&Message::new(MessageType::HandshakeAuth {
+                signature: attacker.sign_handshake(NetworkId::Mainnet, &nonce),
+            })).await.unwrap();
+            while connection.recv().await.is_ok() {}
+        });
+
+        let node = Node::new(NodeConfig::default()).await.unwrap();
+        assert!(matches!(
+            node.connect_to_peer(addr).await,
+            Err(NetworkError::AuthenticationFailed(_))
+        ));
+        assert_eq!(node.stats().await.peer_count, 0);
+    }
+
+    #[tokio::test]
+    async fn test_identity_persists_in_data_dir() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let config = NodeConfig { data_dir: Some(data_dir.path().to_path_buf()), ..NodeConfig::default() };
+
+        let node = Node::new(config.clone()).await.unwrap();
+        let restarted = Node::new(config).await.unwrap();
+        assert_eq!(node.peer_id(), restarted.peer_id());
+        assert_ne!(node.peer_id(), Node::new(NodeConfig::default()).await.unwrap().peer_id());
+    }
+
+    #[tokio::test]
+    async fn test_candidates_skip_crowded_network_group() {
+        let node = Node::new(NodeConfig::default()).await.unwrap();
+        let mut peer = Peer::new(vec![1], "10.1.0.1:8000".parse().unwrap());
+        peer.outbound = true;
+        node.peer_manager.write().await.add_peer(peer).unwrap();
+
+        // Never dialed, so returns at once
+        let same_group: SocketAddr = "10.1.0.2:8000".parse().unwrap();
+        tokio::time::timeout(Duration::from_secs(1), node.connect_to_candidates(&[same_group], true))
+            .await
+            .unwrap();
+
+        // Other groups are still dialed
+        let remote = spawn_remote(NetworkId::Mainnet).await;
+        node.connect_to_candidates(&[remote], true).await;
+        assert!(node.peer_manager.read().await.peer_by_address(&remote).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_messages_dropped_when_required() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let ring = nyx_core::RingSignature { ring_members: vec![vec![1], vec![2]], signature: vec![], key_image: [0u8; 32] };
+        let unsigned = Transaction::new(vec![], vec![], vec![ring.clone()], vec![], [0u8; 32], [1u8; 32]);
+        let signed = Transaction::new(vec![], vec![], vec![ring], vec![], [0u8; 32], [2u8; 32]);
+        let expected = signed.id();
+        tokio::spawn(async move {
+            let (stream, peer) = listener.accept().await.unwrap();
+            let mut connection = PeerConnection::new(stream, Default::default());
+            let identity = NodeIdentity::generate();
+            exchange_handshake(peer, &mut connection, &identity, NetworkId::Mainnet, NodeMode::Archive).await.unwrap();
+            connection.send(&Message::new(MessageType::Transaction(unsigned))).await.unwrap();
+            connection.send(&Message::new(MessageType::Transaction(signed)).signed(&identity)).await.unwrap();
+            while connection.recv().await.is_ok() {}
+        });
+
+        let config = NodeConfig { sign_messages: true, require_signed_messages: true, ..NodeConfig::default() };
+        let node = Node::new(config).await.unwrap();
+        let mut received = node.subscribe_transactions();
+        node.connect_to_peer(addr).await.unwrap();
 
-        assert_eq!(id1, id2);
-        assert_eq!(id1.len(), 32);
+        let got = tokio::time::timeout(Duration::from_secs(5), received.recv()).await.unwrap().unwrap();
+        assert_eq!(got.tx.id(), expected);
     }
 }
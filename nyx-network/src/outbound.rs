@@ -0,0 +1,326 @@
+// src/outbound.rs
+
+//! Bounded per-peer outbound queues.
+//!
+//! Broadcasting enqueues an already-encoded frame for every peer and returns
+//! immediately; a writer task per peer drains its queue onto the socket. A
+//! slow peer therefore only fills its own queue, and once that queue is full
+//! the configured [`DropPolicy`] decides what gives way.
+//!
+//! Each queue has one lane per [`Priority`] class. The writer always drains
+//! the highest non-empty lane first, and lanes are bounded separately, so a
+//! backlog of sync batches neither delays nor crowds out consensus messages.
+
+use crate::codec::{encode_frame, FramedWriter};
+use crate::errors::Result;
+use crate::message::{Message, Priority};
+use crate::OUTBOUND_QUEUE_CAPACITY;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// What to do when a peer's outbound queue is full
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DropPolicy {
+    /// Discard the message being enqueued
+    DropNewest,
+
+    /// Evict the oldest queued message to make room
+    #[default]
+    DropOldest,
+
+    /// Treat the peer as too slow and disconnect it
+    Disconnect,
+}
+
+/// Outbound queue settings
+#[derive(Clone, Debug)]
+pub struct OutboundConfig {
+    /// Maximum queued frames per peer and priority lane
+    pub capacity: usize,
+
+    /// Behavior when a queue is full
+    pub policy: DropPolicy,
+}
+
+impl Default for OutboundConfig {
+    fn default() -> Self {
+        Self {
+            capacity: OUTBOUND_QUEUE_CAPACITY,
+            policy: DropPolicy::default(),
+        }
+    }
+}
+
+/// Result of enqueueing a frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnqueueOutcome {
+    /// Frame queued
+    Queued,
+
+    /// Frame queued after evicting the oldest one
+    EvictedOldest,
+
+    /// Frame discarded
+    Dropped,
+
+    /// Queue is full under [`DropPolicy::Disconnect`], or already closed
+    Disconnect,
+}
+
+/// Counters for one peer's queue
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    /// Frames currently queued
+    pub depth: usize,
+
+    /// Frames queued per priority lane, indexed by [`Priority::index`]
+    pub lane_depths: [usize; 4],
+
+    /// Frames written to the socket
+    pub sent: u64,
+
+    /// Frames discarded by the drop policy
+    pub dropped: u64,
+}
+
+struct QueueInner {
+    lanes: Mutex<[VecDeque<Arc<Vec<u8>>>; 4]>,
+    notify: Notify,
+    config: OutboundConfig,
+    closed: AtomicBool,
+    sent: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// A bounded queue of encoded frames for one peer
+#[derive(Clone)]
+pub struct OutboundQueue {
+    inner: Arc<QueueInner>,
+}
+
+impl OutboundQueue {
+    /// Creates an empty queue
+    pub fn new(config: OutboundConfig) -> Self {
+        Self {
+            inner: Arc::new(QueueInner {
+                lanes: Mutex::new(Default::default()),
+                notify: Notify::new(),
+                config,
+                closed: AtomicBool::new(false),
+                sent: AtomicU64::new(0),
+                dropped: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Enqueues an encoded frame in its priority lane without waiting
+    pub fn push(&self, frame: Arc<Vec<u8>>, priority: Priority) -> EnqueueOutcome {
+        if self.is_closed() {
+            return EnqueueOutcome::Disconnect;
+        }
+
+        let outcome = {
+            let Ok(mut lanes) = self.inner.lanes.lock() else {
+                return EnqueueOutcome::Disconnect;
+            };
+            let frames = &mut lanes[priority.index()];
+
+            if frames.len() < self.inner.config.capacity.max(1) {
+                frames.push_back(frame);
+                EnqueueOutcome::Queued
+            } else {
+                match self.inner.config.policy {
+                    DropPolicy::DropNewest => EnqueueOutcome::Dropped,
+                    DropPolicy::DropOldest => {
+                        frames.pop_front();
+                        frames.push_back(frame);
+                        EnqueueOutcome::EvictedOldest
+                    }
+                    DropPolicy::Disconnect => EnqueueOutcome::Disconnect,
+                }
+            }
+        };
+
+        match outcome {
+            EnqueueOutcome::Queued => self.inner.notify.notify_one(),
+            EnqueueOutcome::EvictedOldest => {
+                self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+                self.inner.notify.notify_one();
+            }
+            EnqueueOutcome::Dropped => {
+                self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            EnqueueOutcome::Disconnect => {}
+        }
+
+        outcome
+    }
+
+    /// Encodes and enqueues a message
+    pub fn push_message(&self, message: &Message) -> Result<EnqueueOutcome> {
+        Ok(self.push(Arc::new(encode_frame(message)?), message.message_type.priority()))
+    }
+
+    /// Waits for the next frame, highest priority first
+    ///
+    /// Returns `None` once the queue is closed; frames still queued at that
+    /// point are discarded.
+    pub async fn pop(&self) -> Option<Arc<Vec<u8>>> {
+        loop {
+            if self.is_closed() {
+                return None;
+            }
+            let next = self.inner.lanes.lock().ok()?
+                .iter_mut()
+                .find_map(|lane| lane.pop_front());
+            if let Some(frame) = next {
+                return Some(frame);
+            }
+            self.inner.notify.notified().await;
+        }
+    }
+
+    /// Closes the queue and wakes the writer
+    pub fn close(&self) {
+        self.inner.closed.store(true, Ordering::Release);
+        self.inner.notify.notify_one();
+    }
+
+    /// Checks whether the queue was closed
+    pub fn is_closed(&self) -> bool {
+        self.inner.closed.load(Ordering::Acquire)
+    }
+
+    /// Number of frames waiting to be written
+    pub fn depth(&self) -> usize {
+        self.lane_depths().iter().sum()
+    }
+
+    /// Number of frames waiting in each priority lane
+    pub fn lane_depths(&self) -> [usize; 4] {
+        self.inner.lanes.lock()
+            .map_or([0; 4], |lanes| [lanes[0].len(), lanes[1].len(), lanes[2].len(), lanes[3].len()])
+    }
+
+    /// Gets the queue counters
+    pub fn stats(&self) -> QueueStats {
+        let lane_depths = self.lane_depths();
+        QueueStats {
+            depth: lane_depths.iter().sum(),
+            lane_depths,
+            sent: self.inner.sent.load(Ordering::Relaxed),
+            dropped: self.inner.dropped.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Spawns the task writing queued frames to the peer
+    ///
+    /// The task ends when the queue is closed or a write fails; a failed
+    /// write closes the queue.
+    pub fn spawn_writer(&self, writer: Arc<tokio::sync::Mutex<FramedWriter>>) -> JoinHandle<()> {
+        let queue = self.clone();
+
+        tokio::spawn(async move {
+            while let Some(frame) = queue.pop().await {
+                let result = writer.lock().await.send_frame(&frame).await;
+
+                if let Err(e) = result {
+                    warn!("Outbound write failed: {}", e);
+                    queue.close();
+                    break;
+                }
+                queue.inner.sent.fetch_add(1, Ordering::Relaxed);
+            }
+
+            debug!("Outbound writer stopped");
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TX: Priority = Priority::Transaction;
+
+    fn frame(byte: u8) -> Arc<Vec<u8>> {
+        Arc::new(vec![byte])
+    }
+
+    fn queue(capacity: usize, policy: DropPolicy) -> OutboundQueue {
+        OutboundQueue::new(OutboundConfig { capacity, policy })
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest() {
+        let queue = queue(2, DropPolicy::DropOldest);
+        assert_eq!(queue.push(frame(1), TX), EnqueueOutcome::Queued);
+        assert_eq!(queue.push(frame(2), TX), EnqueueOutcome::Queued);
+        assert_eq!(queue.push(frame(3), TX), EnqueueOutcome::EvictedOldest);
+
+        assert_eq!(queue.stats(), QueueStats { depth: 2, lane_depths: [0, 0, 2, 0], sent: 0, dropped: 1 });
+        assert_eq!(*queue.pop().await.unwrap(), vec![2]);
+        assert_eq!(*queue.pop().await.unwrap(), vec![3]);
+    }
+
+    #[tokio::test]
+    async fn test_drop_newest() {
+        let queue = queue(1, DropPolicy::DropNewest);
+        assert_eq!(queue.push(frame(1), TX), EnqueueOutcome::Queued);
+        assert_eq!(queue.push(frame(2), TX), EnqueueOutcome::Dropped);
+
+        assert_eq!(queue.stats().dropped, 1);
+        assert_eq!(*queue.pop().await.unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn test_disconnect_policy() {
+        let queue = queue(1, DropPolicy::Disconnect);
+        assert_eq!(queue.push(frame(1), TX), EnqueueOutcome::Queued);
+        assert_eq!(queue.push(frame(2), TX), EnqueueOutcome::Disconnect);
+
+        queue.close();
+        assert_eq!(queue.push(frame(3), TX), EnqueueOutcome::Disconnect);
+    }
+
+    #[tokio::test]
+    async fn test_pop_waits_and_close_wakes() {
+        let queue = queue(4, DropPolicy::DropOldest);
+
+        let waiter = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.pop().await })
+        };
+        tokio::task::yield_now().await;
+        queue.push(frame(7), TX);
+        assert_eq!(*waiter.await.unwrap().unwrap(), vec![7]);
+
+        let waiter = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.pop().await })
+        };
+        tokio::task::yield_now().await;
+        queue.close();
+        assert!(waiter.await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_priority_lanes() {
+        let queue = queue(1, DropPolicy::DropNewest);
+        assert_eq!(queue.push(frame(1), Priority::Discovery), EnqueueOutcome::Queued);
+        assert_eq!(queue.push(frame(2), Priority::Sync), EnqueueOutcome::Queued);
+        assert_eq!(queue.push(frame(3), Priority::Sync), EnqueueOutcome::Dropped);
+
+        // A full sync lane does not block consensus frames
+        assert_eq!(queue.push(frame(4), Priority::Consensus), EnqueueOutcome::Queued);
+        assert_eq!(queue.lane_depths(), [1, 1, 0, 1]);
+
+        assert_eq!(*queue.pop().await.unwrap(), vec![4]);
+        assert_eq!(*queue.pop().await.unwrap(), vec![2]);
+        assert_eq!(*queue.pop().await.unwrap(), vec![1]);
+    }
+}
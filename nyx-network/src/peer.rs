@@ -5,14 +5,19 @@
 //! Handles individual peer connections including TCP communication,
 //! message sending/receiving, and connection state management.
 
+use crate::address_book::network_group;
+use crate::bandwidth::Throttle;
+use crate::codec::{FrameCodec, FramedReader, FramedWriter};
 use crate::errors::{NetworkError, Result};
 use crate::message::Message;
-use crate::{CONNECTION_TIMEOUT_SECS, MAX_MESSAGE_SIZE};
+use crate::{CONNECTION_TIMEOUT_SECS, LATENCY_WINDOW};
+use nyx_core::{DecodeLimits, NodeMode};
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
 use tokio::time::timeout;
 
@@ -34,8 +39,102 @@ pub struct Peer {
     /// Average latency to this peer (milliseconds)
     pub latency_ms: Option<u64>,
 
+    /// Most recent round-trip samples (milliseconds)
+    latency_samples: VecDeque<u64>,
+
+    /// When the outstanding heartbeat ping was sent
+    ping_sent_at: Option<Instant>,
+
+    /// Consecutive heartbeats without a pong
+    pub missed_heartbeats: u32,
+
     /// Connection state
     pub state: PeerState,
+
+    /// Whether we dialed the peer
+    pub outbound: bool,
+
+    /// History the peer serves, as announced in its handshake
+    pub mode: NodeMode,
+
+    /// Limits applied to messages received from this peer
+    limits: DecodeLimits,
+
+    /// Bytes exchanged over the connection, shared by clones
+    traffic: Arc<PeerTraffic>,
+}
+
+/// Bytes exchanged with a peer, and its transactions refused locally
+///
+/// Shared by the peer and both halves of its connection, which count as
+/// frames go out and bytes come in.
+#[derive(Debug, Default)]
+pub struct PeerTraffic {
+    sent: AtomicU64,
+    received: AtomicU64,
+    rejected: AtomicU64,
+    over_quota: AtomicU64,
+}
+
+/// Why a transaction received from a peer was not admitted locally
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RejectCode {
+    /// Malformed, oversized or failing verification
+    Invalid,
+
+    /// Mempool full of more useful transactions
+    MempoolFull,
+
+    /// Peer sent more transactions than its quota allows
+    QuotaExceeded,
+}
+
+impl std::fmt::Display for RejectCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RejectCode::Invalid => write!(f, "invalid"),
+            RejectCode::MempoolFull => write!(f, "mempool-full"),
+            RejectCode::QuotaExceeded => write!(f, "quota-exceeded"),
+        }
+    }
+}
+
+impl PeerTraffic {
+    /// Total bytes sent
+    pub fn bytes_sent(&self) -> u64 {
+        self.sent.load(Ordering::Relaxed)
+    }
+
+    /// Total bytes received
+    pub fn bytes_received(&self) -> u64 {
+        self.received.load(Ordering::Relaxed)
+    }
+
+    /// Transactions from the peer refused locally
+    pub fn transactions_rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    /// Transactions from the peer refused for exceeding its quota
+    pub fn quota_exceeded(&self) -> u64 {
+        self.over_quota.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_rejected(&self, code: RejectCode) {
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+        if code == RejectCode::QuotaExceeded {
+            self.over_quota.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn record_sent(&self, bytes: usize) {
+        self.sent.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_received(&self, bytes: usize) {
+        self.received.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
 }
 
 /// Peer connection state
@@ -62,13 +161,29 @@ impl Peer {
             address,
             last_seen: Instant::now(),
             latency_ms: None,
+            latency_samples: VecDeque::with_capacity(LATENCY_WINDOW),
+            ping_sent_at: None,
+            missed_heartbeats: 0,
             state: PeerState::Connecting,
+            outbound: false,
+            mode: NodeMode::default(),
+            limits: DecodeLimits::default(),
+            traffic: Arc::default(),
         }
     }
 
+    /// Sets the limits applied to messages received from this peer
+    pub fn with_limits(mut self, limits: DecodeLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     /// Connects to the peer
-    pub async fn connect(&mut self) -> Result<TcpStream> {
+    ///
+    /// The connection decodes received messages with this peer's limits.
+    pub async fn connect(&mut self) -> Result<PeerConnection> {
         self.state = PeerState::Connecting;
+        self.outbound = true;
 
         let stream = timeout(
             Duration::from_secs(CONNECTION_TIMEOUT_SECS),
@@ -81,61 +196,28 @@ impl Peer {
         self.state = PeerState::Connected;
         self.last_seen = Instant::now();
 
-        Ok(stream)
+        Ok(PeerConnection::new(stream, self.limits).with_traffic(self.traffic.clone()))
     }
 
-    /// Sends a message to this peer
-    pub async fn send_message(
-        &mut self,
-        stream: &mut OwnedWriteHalf,
-        message: &Message,
-    ) -> Result<()> {
-        // Serialize message
-        let data = message.to_bytes()?;
-
-        if data.len() > MAX_MESSAGE_SIZE {
-            return Err(NetworkError::InvalidMessage(
-                format!("Message too large: {} bytes", data.len())
-            ));
-        }
-
-        // Send length prefix (4 bytes)
-        let len = (data.len() as u32).to_be_bytes();
-        stream.write_all(&len).await?;
-
-        // Send message data
-        stream.write_all(&data).await?;
-        stream.flush().await?;
-
-        Ok(())
+    /// Gets the limits applied to messages received from this peer
+    pub fn limits(&self) -> DecodeLimits {
+        self.limits
     }
 
-    /// Receives a message from this peer
-    pub async fn receive_message(
-        &mut self,
-        stream: &mut OwnedReadHalf,
-    ) -> Result<Message> {
-        // Read length prefix
-        let mut len_bytes = [0u8; 4];
-        stream.read_exact(&mut len_bytes).await?;
-        let len = u32::from_be_bytes(len_bytes) as usize;
-
-        if len > MAX_MESSAGE_SIZE {
-            return Err(NetworkError::InvalidMessage(
-                format!("Message too large: {} bytes", len)
-            ));
-        }
-
-        // Read message data
-        let mut data = vec![0u8; len];
-        stream.read_exact(&mut data).await?;
+    /// Gets the byte counters of this peer's connection
+    pub fn traffic(&self) -> &Arc<PeerTraffic> {
+        &self.traffic
+    }
 
-        // Deserialize message
-        let message = Message::from_bytes(&data)?;
+    /// Checks whether `other` is a clone of this peer rather than a later
+    /// connection to the same address
+    pub fn is_same_connection(&self, other: &Peer) -> bool {
+        Arc::ptr_eq(&self.traffic, &other.traffic)
+    }
 
+    /// Records that a message was just received from this peer
+    pub fn mark_seen(&mut self) {
         self.last_seen = Instant::now();
-
-        Ok(message)
     }
 
     /// Updates peer state
@@ -149,29 +231,123 @@ impl Peer {
     }
 
     /// Measures latency by timing a ping
-    pub async fn measure_latency(
-        &mut self,
-        writer: &mut OwnedWriteHalf,
-        reader: &mut OwnedReadHalf,
-    ) -> Result<u64> {
+    pub async fn measure_latency(&mut self, connection: &mut PeerConnection) -> Result<u64> {
         let start = Instant::now();
 
         // Send ping
         let ping = Message::new(crate::message::MessageType::Ping);
-        self.send_message(writer, &ping).await?;
+        connection.send(&ping).await?;
 
         // Wait for pong
-        let response = self.receive_message(reader).await?;
+        let response = connection.recv().await?;
+        self.mark_seen();
 
         if !matches!(response.message_type, crate::message::MessageType::Pong) {
             return Err(NetworkError::InvalidMessage("Expected Pong".to_string()));
         }
 
         let latency = start.elapsed().as_millis() as u64;
-        self.latency_ms = Some(latency);
+        self.record_latency(latency);
 
         Ok(latency)
     }
+
+    /// Adds a round-trip sample to the rolling latency average
+    pub fn record_latency(&mut self, sample_ms: u64) {
+        if self.latency_samples.len() == LATENCY_WINDOW {
+            self.latency_samples.pop_front();
+        }
+        self.latency_samples.push_back(sample_ms);
+
+        let total: u64 = self.latency_samples.iter().sum();
+        self.latency_ms = Some(total / self.latency_samples.len() as u64);
+    }
+
+    /// Starts a heartbeat round
+    ///
+    /// Counts a missed heartbeat if the previous ping is still unanswered.
+    ///
+    /// # Returns
+    /// Consecutive heartbeats missed so far
+    pub fn begin_heartbeat(&mut self) -> u32 {
+        if self.ping_sent_at.is_some() {
+            self.missed_heartbeats += 1;
+        }
+        self.ping_sent_at = Some(Instant::now());
+        self.missed_heartbeats
+    }
+
+    /// Records the pong answering the outstanding heartbeat ping
+    ///
+    /// # Returns
+    /// The measured round trip, or `None` if no ping was outstanding
+    pub fn record_pong(&mut self) -> Option<u64> {
+        let sent_at = self.ping_sent_at.take()?;
+        let latency = sent_at.elapsed().as_millis() as u64;
+
+        self.missed_heartbeats = 0;
+        self.last_seen = Instant::now();
+        self.record_latency(latency);
+
+        Some(latency)
+    }
+}
+
+/// Framed connection to a peer
+///
+/// Owns both halves of the TCP stream. Handshakes use it whole; afterwards
+/// it is [split](Self::into_split) so that one task reads while the
+/// outbound queue writes.
+pub struct PeerConnection {
+    /// Read half
+    reader: FramedReader,
+
+    /// Write half
+    writer: FramedWriter,
+}
+
+impl PeerConnection {
+    /// Wraps a stream, decoding received messages with `limits`
+    pub fn new(stream: TcpStream, limits: DecodeLimits) -> Self {
+        let codec = FrameCodec::new(limits);
+        let (reader, writer) = stream.into_split();
+
+        Self {
+            reader: FramedReader::new(reader, codec),
+            writer: FramedWriter::new(writer, codec),
+        }
+    }
+
+    /// Counts the connection's bytes in `traffic`
+    pub fn with_traffic(self, traffic: Arc<PeerTraffic>) -> Self {
+        Self {
+            reader: self.reader.with_traffic(traffic.clone()),
+            writer: self.writer.with_traffic(traffic),
+        }
+    }
+
+    /// Caps the connection's upload and download rates
+    pub fn with_throttle(self, upload: Throttle, download: Throttle) -> Self {
+        Self {
+            reader: self.reader.with_throttle(download),
+            writer: self.writer.with_throttle(upload),
+        }
+    }
+
+    /// Sends a message
+    pub async fn send(&mut self, message: &Message) -> Result<()> {
+        self.writer.send(message).await
+    }
+
+    /// Receives the next message
+    pub async fn recv(&mut self) -> Result<Message> {
+        self.reader.recv().await
+    }
+
+    /// Splits the connection into its read and write halves
+    pub fn into_split(self) -> (FramedReader, FramedWriter) {
+        (self.reader, self.writer)
+    }
 }
 
 /// Peer manager for handling multiple peer connections
@@ -181,6 +357,9 @@ pub struct PeerManager {
 
     /// Maximum number of peers
     max_peers: usize,
+
+    /// Banned IP addresses and when each ban expires
+    banned: HashMap<IpAddr, Instant>,
 }
 
 impl PeerManager {
@@ -189,11 +368,19 @@ impl PeerManager {
         Self {
             peers: Vec::new(),
             max_peers,
+            banned: HashMap::new(),
         }
     }
 
     /// Adds a new peer
+    ///
+    /// # Errors
+    /// - `PeerBanned` if the peer's IP address is banned
+    /// - `MaxPeersReached` if no slot is free
     pub fn add_peer(&mut self, peer: Peer) -> Result<()> {
+        if self.is_banned(&peer.address.ip()) {
+            return Err(NetworkError::PeerBanned(peer.address.ip().to_string()));
+        }
         if self.peers.len() >= self.max_peers {
             return Err(NetworkError::MaxPeersReached);
         }
@@ -222,6 +409,24 @@ impl PeerManager {
         self.peers.iter_mut().find(|p| &p.id == peer_id)
     }
 
+    /// Gets the peer connected at `address`
+    pub fn peer_by_address(&self, address: &SocketAddr) -> Option<&Peer> {
+        self.peers.iter().find(|p| &p.address == address)
+    }
+
+    /// Counts the outbound peers in an address's network group
+    pub fn outbound_in_group(&self, address: &SocketAddr) -> usize {
+        let group = network_group(address);
+        self.peers.iter()
+            .filter(|p| p.outbound && network_group(&p.address) == group)
+            .count()
+    }
+
+    /// Gets every managed peer, whatever its state
+    pub fn peers(&self) -> &[Peer] {
+        &self.peers
+    }
+
     /// Gets all connected peers
     pub fn connected_peers(&self) -> Vec<&Peer> {
         self.peers.iter()
@@ -229,15 +434,75 @@ impl PeerManager {
             .collect()
     }
 
+    /// Gets all connected peers mutably
+    pub fn connected_peers_mut(&mut self) -> impl Iterator<Item = &mut Peer> {
+        self.peers.iter_mut().filter(|p| p.is_connected())
+    }
+
     /// Gets number of connected peers
     pub fn peer_count(&self) -> usize {
         self.connected_peers().len()
     }
 
+    /// Gets the maximum number of peers
+    pub fn max_peers(&self) -> usize {
+        self.max_peers
+    }
+
+    /// Changes the maximum number of peers
+    ///
+    /// Lowering the limit does not disconnect anyone; it only refuses new
+    /// peers until enough have left.
+    pub fn set_max_peers(&mut self, max_peers: usize) {
+        self.max_peers = max_peers;
+    }
+
     /// Checks if we can accept more peers
     pub fn can_accept_more(&self) -> bool {
         self.peers.len() < self.max_peers
     }
+
+    /// Bans an IP address for `duration` and removes its peers
+    ///
+    /// # Returns
+    /// IDs of the removed peers, whose connections the caller closes
+    pub fn ban(&mut self, ip: IpAddr, duration: Duration) -> Vec<PeerId> {
+        self.banned.insert(ip, Instant::now() + duration);
+
+        let removed = self.peers.iter()
+            .filter(|p| p.address.ip() == ip)
+            .map(|p| p.id.clone())
+            .collect();
+        self.peers.retain(|p| p.address.ip() != ip);
+        removed
+    }
+
+    /// Lifts the ban of an IP address
+    ///
+    /// # Returns
+    /// Whether the address was banned
+    pub fn unban(&mut self, ip: &IpAddr) -> bool {
+        self.banned.remove(ip).is_some_and(|until| until > Instant::now())
+    }
+
+    /// Checks whether an IP address is banned
+    pub fn is_banned(&self, ip: &IpAddr) -> bool {
+        self.banned.get(ip).is_some_and(|until| *until > Instant::now())
+    }
+
+    /// Gets the banned IP addresses with the time left on each ban
+    ///
+    /// Expired bans are forgotten.
+    pub fn bans(&mut self) -> Vec<(IpAddr, Duration)> {
+        let now = Instant::now();
+        self.banned.retain(|_, until| *until > now);
+
+        let mut bans: Vec<_> = self.banned.iter()
+            .map(|(ip, until)| (*ip, *until - now))
+            .collect();
+        bans.sort();
+        bans
+    }
 }
 
 #[cfg(test)]
@@ -277,4 +542,145 @@ mod tests {
         let result = manager.add_peer(Peer::new(vec![3], addr));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_peer_manager_set_max_peers() {
+        let mut manager = PeerManager::new(1);
+        let addr: SocketAddr = "127.0.0.1:8000".parse().unwrap();
+        manager.add_peer(Peer::new(vec![1], addr)).unwrap();
+        assert!(!manager.can_accept_more());
+
+        manager.set_max_peers(2);
+        assert_eq!(manager.max_peers(), 2);
+        manager.add_peer(Peer::new(vec![2], addr)).unwrap();
+
+        manager.set_max_peers(1);
+        assert_eq!(manager.peers.len(), 2);
+        assert!(manager.add_peer(Peer::new(vec![3], addr)).is_err());
+    }
+
+    #[test]
+    fn test_peer_manager_bans() {
+        let mut manager = PeerManager::new(10);
+        let banned: SocketAddr = "10.0.0.1:8000".parse().unwrap();
+        let other: SocketAddr = "10.0.0.2:8000".parse().unwrap();
+        manager.add_peer(Peer::new(vec![1], banned)).unwrap();
+        manager.add_peer(Peer::new(vec![2], other)).unwrap();
+
+        assert_eq!(manager.ban(banned.ip(), Duration::from_secs(60)), vec![vec![1]]);
+        assert_eq!(manager.peers().len(), 1);
+        assert!(matches!(manager.add_peer(Peer::new(vec![3], banned)), Err(NetworkError::PeerBanned(_))));
+        assert_eq!(manager.bans()[0].0, banned.ip());
+
+        assert!(manager.unban(&banned.ip()));
+        assert!(!manager.unban(&banned.ip()));
+        manager.add_peer(Peer::new(vec![3], banned)).unwrap();
+
+        manager.ban(other.ip(), Duration::ZERO);
+        assert!(!manager.is_banned(&other.ip()));
+        assert!(manager.bans().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_connection_applies_peer_limits() {
+        use crate::message::MessageType;
+        use nyx_core::{RingSignature, Transaction};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut peer = Peer::new(vec![1], addr)
+            .with_limits(DecodeLimits { max_extra_size: 8, ..DecodeLimits::default() });
+        let (client, server) = tokio::join!(peer.connect(), listener.accept());
+        let mut client = client.unwrap();
+        let mut server = PeerConnection::new(server.unwrap().0, DecodeLimits::default());
+        assert!(peer.is_connected());
+
+        let ring = RingSignature { ring_members: vec![vec![1], vec![2]], signature: vec![], key_image: [0u8; 32] };
+        let mut tx = Transaction::new(vec![], vec![], vec![ring], vec![], [0u8; 32], [1u8; 32]);
+        tx.extra = vec![0u8; 16];
+        let message = Message::new(MessageType::Transaction(tx));
+
+        server.send(&message).await.unwrap();
+        server.send(&Message::new(MessageType::Ping)).await.unwrap();
+        assert!(matches!(client.recv().await, Err(NetworkError::LimitExceeded(_))));
+        assert_eq!(client.recv().await.unwrap().message_type.type_name(), "Ping");
+
+        client.send(&message).await.unwrap();
+        assert!(server.recv().await.is_ok());
+        assert_eq!(peer.traffic().bytes_sent(), crate::codec::encode_frame(&message).unwrap().len() as u64);
+        assert!(peer.traffic().bytes_received() > peer.traffic().bytes_sent());
+
+        let (_, writer) = client.into_split();
+        drop(writer);
+        assert!(matches!(server.recv().await, Err(NetworkError::ConnectionError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_connection_throttles_upload() {
+        use crate::bandwidth::RateLimit;
+        use crate::message::MessageType;
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut peer = Peer::new(vec![1], addr);
+        let (client, server) = tokio::join!(peer.connect(), listener.accept());
+        let upload = Throttle::unlimited().with_limit(Arc::new(RateLimit::new(1000)));
+        let mut client = client.unwrap().with_throttle(upload, Throttle::unlimited());
+        let mut server = PeerConnection::new(server.unwrap().0, DecodeLimits::default());
+
+        // A second's allowance goes out at once, the rest waits
+        let ping = Message::new(MessageType::Ping);
+        let frame_size = crate::codec::encode_frame(&ping).unwrap().len();
+        let start = Instant::now();
+        for _ in 0..(1500 / frame_size + 1) {
+            client.send(&ping).await.unwrap();
+            server.recv().await.unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_rolling_latency() {
+        let addr: SocketAddr = "127.0.0.1:8000".parse().unwrap();
+        let mut peer = Peer::new(vec![1], addr);
+
+        peer.record_latency(10);
+        peer.record_latency(30);
+        assert_eq!(peer.latency_ms, Some(20));
+
+        for _ in 0..LATENCY_WINDOW {
+            peer.record_latency(50);
+        }
+        assert_eq!(peer.latency_ms, Some(50));
+    }
+
+    #[test]
+    fn test_missed_heartbeats() {
+        let addr: SocketAddr = "127.0.0.1:8000".parse().unwrap();
+        let mut peer = Peer::new(vec![1], addr);
+
+        assert!(peer.record_pong().is_none());
+        assert_eq!(peer.begin_heartbeat(), 0);
+        assert_eq!(peer.begin_heartbeat(), 1);
+        assert_eq!(peer.begin_heartbeat(), 2);
+
+        assert!(peer.record_pong().is_some());
+        assert_eq!(peer.missed_heartbeats, 0);
+        assert!(peer.latency_ms.is_some());
+        assert_eq!(peer.begin_heartbeat(), 0);
+    }
+
+    #[test]
+    fn test_outbound_in_group() {
+        let mut manager = PeerManager::new(10);
+        let mut outbound = Peer::new(vec![1], "1.2.3.4:8000".parse().unwrap());
+        outbound.outbound = true;
+        manager.add_peer(outbound).unwrap();
+        manager.add_peer(Peer::new(vec![2], "1.2.5.6:8000".parse().unwrap())).unwrap();
+
+        assert_eq!(manager.outbound_in_group(&"1.2.9.9:8000".parse().unwrap()), 1);
+        assert_eq!(manager.outbound_in_group(&"1.3.9.9:8000".parse().unwrap()), 0);
+    }
 }
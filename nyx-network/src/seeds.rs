@@ -0,0 +1,90 @@
+// src/seeds.rs
+
+//! Seed-node bootstrapping.
+//!
+//! A node with no address book and no configured bootstrap peers still
+//! needs somewhere to start. DNS seeds are host names whose A/AAAA records
+//! list live nodes; they are resolved at startup. If none of them resolve,
+//! the compiled-in [`FALLBACK_SEED_NODES`] are used instead.
+
+use crate::{DEFAULT_PORT, DNS_SEED_TIMEOUT_SECS};
+use std::net::SocketAddr;
+use tokio::net::lookup_host;
+use tokio::time::{timeout, Duration};
+use tracing::{debug, warn};
+
+/// DNS seeds queried by default
+pub const DEFAULT_DNS_SEEDS: &[&str] = &["seed.nyx.network"];
+
+/// Seed node addresses used when no DNS seed resolves
+///
+/// Updated with each release as long-running seed nodes change.
+pub const FALLBACK_SEED_NODES: &[&str] = &[];
+
+/// Resolves DNS seeds to peer addresses
+///
+/// A seed is either `host` (using [`DEFAULT_PORT`]) or `host:port`. Seeds
+/// that fail or time out are skipped; duplicates are removed.
+pub async fn resolve_dns_seeds(seeds: &[String]) -> Vec<SocketAddr> {
+    let mut addrs = Vec::new();
+
+    for seed in seeds {
+        let host = if seed.contains(':') {
+            seed.clone()
+        } else {
+            format!("{}:{}", seed, DEFAULT_PORT)
+        };
+
+        let lookup = timeout(Duration::from_secs(DNS_SEED_TIMEOUT_SECS), lookup_host(host)).await;
+        match lookup {
+            Ok(Ok(resolved)) => {
+                let before = addrs.len();
+                for addr in resolved {
+                    if !addrs.contains(&addr) {
+                        addrs.push(addr);
+                    }
+                }
+                debug!("DNS seed {} resolved to {} addresses", seed, addrs.len() - before);
+            }
+            Ok(Err(e)) => warn!("Failed to resolve DNS seed {}: {}", seed, e),
+            Err(_) => warn!("Timed out resolving DNS seed {}", seed),
+        }
+    }
+
+    addrs
+}
+
+/// Parses the compiled-in fallback seed nodes
+pub fn fallback_seed_nodes() -> Vec<SocketAddr> {
+    FALLBACK_SEED_NODES.iter()
+        .filter_map(|addr| addr.parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve_seeds() {
+        let seeds = vec![
+            "127.0.0.1".to_string(),
+            "127.0.0.1:9100".to_string(),
+            "127.0.0.1:9100".to_string(),
+            "not a host".to_string(),
+        ];
+
+        assert_eq!(
+            resolve_dns_seeds(&seeds).await,
+            vec![
+                SocketAddr::from(([127, 0, 0, 1], DEFAULT_PORT)),
+                SocketAddr::from(([127, 0, 0, 1], 9100)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fallback_seeds_parse() {
+        assert_eq!(fallback_seed_nodes().len(), FALLBACK_SEED_NODES.len());
+    }
+}
@@ -0,0 +1,177 @@
+// src/snap_sync.rs
+
+//! Checkpoint-based fast sync ("snap sync").
+//!
+//! Instead of replaying the whole DAG, a fresh node asks several peers for
+//! their latest finalized [`Checkpoint`]. Each response is verified
+//! (validator quorum, header chain, state root) and the node only restores
+//! a checkpoint once enough distinct peers served the same header. Live
+//! transaction gossip resumes after the restore.
+
+use crate::errors::{NetworkError, Result};
+use crate::peer::PeerId;
+use nyx_core::checkpoint::Checkpoint;
+use nyx_core::consensus::ValidatorSet;
+use nyx_core::finality::FinalityTracker;
+use nyx_core::Hash;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+/// Collects and cross-checks checkpoints from peers
+pub struct SnapSync {
+    /// Validators trusted to sign snapshots
+    validators: ValidatorSet,
+
+    /// Peers that must serve the same checkpoint header
+    min_agreeing: usize,
+
+    /// Verified checkpoint received from each peer
+    candidates: HashMap<PeerId, Checkpoint>,
+}
+
+impl SnapSync {
+    /// Creates a snap sync session
+    ///
+    /// `min_agreeing` is clamped to at least 1.
+    pub fn new(validators: ValidatorSet, min_agreeing: usize) -> Self {
+        Self {
+            validators,
+            min_agreeing: min_agreeing.max(1),
+            candidates: HashMap::new(),
+        }
+    }
+
+    /// Number of peers that served a valid checkpoint
+    pub fn candidate_count(&self) -> usize {
+        self.candidates.len()
+    }
+
+    /// Records a checkpoint served by a peer
+    ///
+    /// A later response from the same peer replaces the earlier one.
+    ///
+    /// # Errors
+    /// - `SyncError` if the checkpoint fails verification
+    pub fn add_checkpoint(&mut self, peer: PeerId, checkpoint: Checkpoint) -> Result<()> {
+        if let Err(e) = checkpoint.verify(&self.validators) {
+            warn!("Rejected checkpoint from peer {:?}: {}", peer, e);
+            return Err(NetworkError::SyncError(format!("Invalid checkpoint: {}", e)));
+        }
+
+        self.candidates.insert(peer, checkpoint);
+        Ok(())
+    }
+
+    /// Selects the newest checkpoint enough peers agree on
+    pub fn select(&self) -> Option<&Checkpoint> {
+        let mut votes: HashMap<Hash, (usize, &Checkpoint)> = HashMap::new();
+        for checkpoint in self.candidates.values() {
+            if let Some(header) = checkpoint.header() {
+                votes.entry(header.hash()).or_insert((0, checkpoint)).0 += 1;
+            }
+        }
+
+        votes.into_values()
+            .filter(|(count, _)| *count >= self.min_agreeing)
+            .map(|(_, checkpoint)| checkpoint)
+            .max_by_key(|checkpoint| checkpoint.number())
+    }
+
+    /// Restores the selected checkpoint into an empty tracker
+    ///
+    /// # Errors
+    /// - `SyncError` if no checkpoint has enough agreeing peers yet, or the
+    ///   restore fails
+    ///
+    /// # Returns
+    /// Number of transactions restored
+    pub fn finish(&self, finality: &FinalityTracker) -> Result<usize> {
+        let checkpoint = self.select()
+            .ok_or_else(|| NetworkError::SyncError(format!(
+                "No checkpoint served by {} agreeing peers",
+                self.min_agreeing
+            )))?
+            .clone();
+        let number = checkpoint.number();
+
+        let restored = finality.restore_checkpoint(checkpoint, &self.validators)
+            .map_err(|e| NetworkError::SyncError(format!("Checkpoint restore failed: {}", e)))?;
+
+        info!("Snap sync restored {} transactions at snapshot {}", restored, number);
+        Ok(restored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_core::consensus::{SignedSnapshot, Validator};
+    use nyx_core::dag::DagProcessor;
+    use nyx_core::finality::PosSnapshot;
+    use nyx_core::nyx_crypto::keys::ed25519_public_key;
+    use nyx_core::storage::MemoryStorage;
+    use nyx_core::testing::test_tx;
+    use nyx_core::verification::VerifyMode;
+
+    fn validators() -> (ValidatorSet, Vec<[u8; 32]>) {
+        let keys: Vec<[u8; 32]> = (0..3u8).map(|i| [i; 32]).collect();
+        let set = ValidatorSet::new(
+            keys.iter().map(|k| Validator { public_key: ed25519_public_key(k).to_vec(), stake: 10 }).collect(),
+        )
+        .unwrap();
+        (set, keys)
+    }
+
+    /// Builds a serving node's checkpoint at snapshot 1
+    fn checkpoint(set: &ValidatorSet, keys: &[[u8; 32]]) -> Checkpoint {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+        let g1 = storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 1)).unwrap();
+        let g2 = storage.store_transaction(test_tx([0u8; 32], [0u8; 32], 2)).unwrap();
+        let a = dag.add_transaction_with_mode(test_tx(g1, g2, 3), VerifyMode::HeadersOnly).unwrap();
+
+        let mut signed = SignedSnapshot::new(PosSnapshot { number: 1, anchors: vec![a] });
+        for (i, key) in keys.iter().enumerate() {
+            signed.sign(i as u32, key).unwrap();
+        }
+
+        let tracker = FinalityTracker::new(dag);
+        tracker.apply_signed_snapshot(&signed, set).unwrap();
+        tracker.checkpoint().unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_requires_agreeing_peers() {
+        let (set, keys) = validators();
+        let checkpoint = checkpoint(&set, &keys);
+        let mut sync = SnapSync::new(set, 2);
+
+        sync.add_checkpoint(vec![1], checkpoint.clone()).unwrap();
+        assert!(sync.select().is_none());
+
+        // The same peer answering twice does not count as agreement
+        sync.add_checkpoint(vec![1], checkpoint.clone()).unwrap();
+        assert!(sync.select().is_none());
+
+        sync.add_checkpoint(vec![2], checkpoint.clone()).unwrap();
+        assert_eq!(sync.select(), Some(&checkpoint));
+
+        let fresh = FinalityTracker::new(DagProcessor::new(MemoryStorage::new()));
+        assert_eq!(sync.finish(&fresh).unwrap(), 3);
+        assert_eq!(fresh.latest_header(), checkpoint.header().cloned());
+    }
+
+    #[test]
+    fn test_rejects_invalid_checkpoint() {
+        let (set, keys) = validators();
+        let mut tampered = checkpoint(&set, &keys);
+        tampered.state.entries.pop();
+
+        let mut sync = SnapSync::new(set, 1);
+        assert!(matches!(sync.add_checkpoint(vec![1], tampered), Err(NetworkError::SyncError(_))));
+        assert_eq!(sync.candidate_count(), 0);
+
+        let fresh = FinalityTracker::new(DagProcessor::new(MemoryStorage::new()));
+        assert!(sync.finish(&fresh).is_err());
+    }
+}
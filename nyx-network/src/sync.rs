@@ -6,25 +6,45 @@
 //! - Requests missing transactions from peers
 //! - Responds to sync requests with transaction batches
 //! - Maintains sync state and progress tracking
-
-use crate::errors::Result;
-use crate::message::{Message, MessageType};
-use crate::peer::Peer;
+//! - Serves and restores finalized checkpoints for snap sync
+//!
+//! Each sync or snap sync run is a numbered session; log lines emitted while
+//! handling its responses carry the `session_id` (see [`crate::trace`]).
+
+use crate::codec::FramedWriter;
+use crate::errors::{NetworkError, Result};
+use crate::message::{LazyTransaction, Message, MessageType};
+use crate::peer::{Peer, PeerId};
+use crate::snap_sync::SnapSync;
+use crate::trace;
 use crate::MAX_SYNC_BATCH_SIZE;
 use nyx_core::Transaction;
+use nyx_core::checkpoint::Checkpoint;
+use nyx_core::consensus::ValidatorSet;
 use nyx_core::dag::DagProcessor;
+use nyx_core::finality::FinalityTracker;
+use nyx_core::verification::VerifyMode;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::net::tcp::OwnedWriteHalf;
 use tokio::sync::RwLock;
-use tracing::{debug, info, warn};
+use tracing::{debug, info, warn, Span};
 
 /// Manages DAG synchronization with peers
 pub struct SyncManager {
-    /// DAG processor for local state
-    dag: Arc<RwLock<DagProcessor>>,
+    /// DAG processor for local state (internally synchronized)
+    dag: DagProcessor,
+
+    /// Finality of the local DAG, used to serve and restore checkpoints
+    finality: FinalityTracker,
 
     /// Sync progress tracking
     sync_state: Arc<RwLock<SyncState>>,
+
+    /// Active snap sync session, if any
+    snap: Arc<RwLock<Option<SnapSync>>>,
+
+    /// Number of the last session started
+    last_session: Arc<AtomicU64>,
 }
 
 /// Current synchronization state
@@ -41,74 +61,148 @@ pub struct SyncState {
 
     /// Number of transactions synced in current session
     pub synced_count: usize,
+
+    /// Whether live transactions are accepted and gossiped (false while
+    /// snap syncing)
+    pub live: bool,
+
+    /// Number of the running session, if syncing
+    pub session_id: Option<u64>,
 }
 
 impl SyncManager {
     /// Creates a new sync manager
-    pub fn new(dag: Arc<RwLock<DagProcessor>>) -> Self {
+    pub fn new(dag: DagProcessor) -> Self {
+        let finality = FinalityTracker::new(dag.clone());
+        Self::with_finality(dag, finality)
+    }
+
+    /// Creates a sync manager sharing an existing finality tracker
+    pub fn with_finality(dag: DagProcessor, finality: FinalityTracker) -> Self {
         Self {
             dag,
+            finality,
             sync_state: Arc::new(RwLock::new(SyncState {
                 current_height: 0,
                 target_height: None,
                 is_syncing: false,
                 synced_count: 0,
+                live: true,
+                session_id: None,
             })),
+            snap: Arc::new(RwLock::new(None)),
+            last_session: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Allocates the next session number
+    fn next_session(&self) -> u64 {
+        self.last_session.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Span of the running session, or a disabled span when not syncing
+    pub async fn session_span(&self) -> Span {
+        match self.sync_state.read().await.session_id {
+            Some(session_id) => trace::sync_span(session_id),
+            None => Span::none(),
+        }
+    }
+
+    /// Gets the finality tracker
+    pub fn finality(&self) -> &FinalityTracker {
+        &self.finality
+    }
+
     /// Handles an incoming sync request
+    ///
+    /// A pruned node refuses requests reaching into snapshots it dropped
+    /// with a single [`MessageType::SyncPruned`].
+    ///
+    /// # Returns
+    /// Response batches to queue for the peer
     pub async fn handle_sync_request(
         &self,
         from_height: u64,
-        peer: &mut Peer,
-        stream: &mut OwnedWriteHalf,
-    ) -> Result<()> {
-        debug!("Handling sync request from peer {:?} from height {}", peer.id, from_height);
+        peer: &Peer,
+    ) -> Result<Vec<Message>> {
+        debug!("Handling sync request from height {}", from_height);
+
+        let pruned_through = self.finality.pruned_through();
+        if pruned_through > 0 && from_height <= pruned_through {
+            info!(peer_id = %trace::short_hex(&peer.id), "History before snapshot {} is pruned", pruned_through + 1);
+            return Ok(vec![Message::new(MessageType::SyncPruned { retained_from: pruned_through + 1 })]);
+        }
 
         // Get transactions from DAG
-        let dag = self.dag.read().await;
-        let transactions = self.get_transactions_from_height(&dag, from_height).await?;
+        let transactions = self.get_transactions_from_height(&self.dag, from_height).await?;
 
         debug!("Found {} transactions to sync", transactions.len());
 
         // Send transactions in batches
-        for batch in transactions.chunks(MAX_SYNC_BATCH_SIZE) {
-            let response = Message::new(MessageType::SyncResponse {
-                transactions: batch.to_vec(),
-            });
+        let responses = transactions.chunks(MAX_SYNC_BATCH_SIZE)
+            .map(|batch| Message::new(MessageType::SyncResponse {
+                transactions: batch.iter().map(LazyTransaction::encode).collect(),
+            }))
+            .collect();
 
-            peer.send_message(stream, &response).await?;
-        }
+        info!(peer_id = %trace::short_hex(&peer.id), "Serving {} transactions", transactions.len());
 
-        info!("Sent {} transactions to peer {:?}", transactions.len(), peer.id);
+        Ok(responses)
+    }
 
-        Ok(())
+    /// Handles a peer's refusal to serve pruned history
+    pub fn handle_sync_pruned(&self, peer: &Peer, retained_from: u64) {
+        warn!(
+            peer_id = %trace::short_hex(&peer.id),
+            "Peer only retains history from snapshot {}; sync from an archive node",
+            retained_from
+        );
     }
 
     /// Handles an incoming sync response
     pub async fn handle_sync_response(
         &self,
-        transactions: Vec<Transaction>,
+        transactions: Vec<LazyTransaction>,
     ) -> Result<()> {
-        let mut state = self.sync_state.write().await;
-        let dag = self.dag.write().await;
-
         debug!("Processing sync response with {} transactions", transactions.len());
 
-        let mut added = 0;
-        for tx in transactions {
-            match dag.add_transaction(tx) {
-                Ok(_) => {
-                    added += 1;
-                    state.synced_count += 1;
-                }
-                Err(e) => {
-                    warn!("Failed to add synced transaction: {}", e);
-                }
+        // Headers first: transactions for another network or over the
+        // limits are dropped before their bodies are decoded
+        let limits = self.dag.params().limits;
+        let network = self.dag.network();
+        let mut screened = Vec::with_capacity(transactions.len());
+        for (index, tx) in transactions.into_iter().enumerate() {
+            match tx.header(&limits) {
+                Ok(header) if header.network == network => screened.push(tx),
+                Ok(header) => warn!("Dropping synced transaction {} for network {}", index, header.network),
+                Err(e) => warn!("Dropping malformed synced transaction {}: {}", index, e),
             }
         }
 
+        let (outcome, undecodable) = self.dag
+            .query(move |dag| {
+                let mut decoded = Vec::with_capacity(screened.len());
+                let mut undecodable = Vec::new();
+                for tx in &screened {
+                    match tx.decode(&limits) {
+                        Ok(tx) => decoded.push(tx),
+                        Err(e) => undecodable.push(e),
+                    }
+                }
+                Ok((dag.add_transactions_batch_with_mode(decoded, VerifyMode::Full)?, undecodable))
+            })
+            .await
+            .map_err(|e| NetworkError::SyncError(format!("Failed to add synced batch: {}", e)))?;
+        for e in &undecodable {
+            warn!("Dropping malformed synced transaction: {}", e);
+        }
+        for (tx_id, e) in &outcome.rejected {
+            trace::tx_span(tx_id).in_scope(|| warn!("Failed to add synced transaction: {}", e));
+        }
+
+        let added = outcome.added.len();
+        self.sync_state.write().await.synced_count += added;
+
         info!("Added {} transactions from sync response", added);
 
         Ok(())
@@ -118,21 +212,102 @@ impl SyncManager {
     pub async fn request_sync(
         &self,
         from_height: u64,
-        peer: &mut Peer,
-        stream: &mut OwnedWriteHalf,
+        peer: &Peer,
+        stream: &mut FramedWriter,
     ) -> Result<()> {
         let mut state = self.sync_state.write().await;
         state.is_syncing = true;
         state.current_height = from_height;
+        let session_id = *state.session_id.get_or_insert_with(|| self.next_session());
 
         let request = Message::new(MessageType::SyncRequest { from_height });
-        peer.send_message(stream, &request).await?;
+        stream.send(&request).await?;
 
-        info!("Requested sync from peer {:?} starting at height {}", peer.id, from_height);
+        info!(
+            session_id,
+            peer_id = %trace::short_hex(&peer.id),
+            "Requested sync starting at height {}",
+            from_height
+        );
 
         Ok(())
     }
 
+    /// Handles a snap sync request with our latest checkpoint
+    ///
+    /// # Returns
+    /// The response to queue for the peer, or `None` if no signed snapshot
+    /// has been applied yet
+    pub async fn handle_snapshot_request(&self, peer: &Peer) -> Result<Option<Message>> {
+        let checkpoint = self.finality.checkpoint()
+            .map_err(|e| NetworkError::SyncError(format!("Failed to build checkpoint: {}", e)))?;
+
+        match checkpoint {
+            Some(checkpoint) => {
+                debug!(peer_id = %trace::short_hex(&peer.id), "Serving checkpoint {}", checkpoint.number());
+                Ok(Some(Message::new(MessageType::SnapshotResponse(checkpoint))))
+            }
+            None => {
+                debug!(peer_id = %trace::short_hex(&peer.id), "No checkpoint to serve");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Starts snap sync, pausing live transactions until a checkpoint is restored
+    ///
+    /// Callers then send [`MessageType::SnapshotRequest`] to their peers.
+    pub async fn start_snap_sync(&self, validators: ValidatorSet, min_peers: usize) {
+        *self.snap.write().await = Some(SnapSync::new(validators, min_peers));
+
+        let mut state = self.sync_state.write().await;
+        state.is_syncing = true;
+        state.live = false;
+        state.synced_count = 0;
+        let session_id = self.next_session();
+        state.session_id = Some(session_id);
+
+        info!(session_id, "Starting snap sync (need {} agreeing peers)", min_peers);
+    }
+
+    /// Handles a checkpoint served by a peer
+    ///
+    /// # Returns
+    /// `true` once a checkpoint was restored and live sync resumed
+    pub async fn handle_snapshot_response(
+        &self,
+        peer_id: PeerId,
+        checkpoint: Checkpoint,
+    ) -> Result<bool> {
+        let mut snap = self.snap.write().await;
+        let Some(session) = snap.as_mut() else {
+            debug!(peer_id = %trace::short_hex(&peer_id), "Ignoring unsolicited checkpoint");
+            return Ok(false);
+        };
+
+        session.add_checkpoint(peer_id, checkpoint)?;
+        if session.select().is_none() {
+            return Ok(false);
+        }
+
+        let restored = session.finish(&self.finality)?;
+        *snap = None;
+
+        let mut state = self.sync_state.write().await;
+        state.is_syncing = false;
+        state.live = true;
+        state.synced_count = restored;
+        state.current_height = self.finality.latest_snapshot();
+        state.session_id = None;
+
+        Ok(true)
+    }
+
+    /// Checks whether live transactions should be processed and gossiped
+    pub async fn is_live(&self) -> bool {
+        self.sync_state.read().await.live
+    }
+
     /// Gets transactions from a specific height
     async fn get_transactions_from_height(
         &self,
@@ -154,16 +329,19 @@ impl SyncManager {
         state.is_syncing = true;
         state.target_height = Some(target_height);
         state.synced_count = 0;
+        let session_id = self.next_session();
+        state.session_id = Some(session_id);
 
-        info!("Starting sync to height {}", target_height);
+        info!(session_id, "Starting sync to height {}", target_height);
     }
 
     /// Completes the sync process
     pub async fn complete_sync(&self) {
         let mut state = self.sync_state.write().await;
         state.is_syncing = false;
+        let session_id = state.session_id.take();
 
-        info!("Sync completed. Synced {} transactions", state.synced_count);
+        info!(session_id, "Sync completed. Synced {} transactions", state.synced_count);
     }
 
     /// Gets current sync state
@@ -186,9 +364,8 @@ mod tests {
     async fn test_sync_manager_creation() {
         let storage = MemoryStorage::new();
         let dag = DagProcessor::new(storage);
-        let dag_arc = Arc::new(RwLock::new(dag));
 
-        let sync = SyncManager::new(dag_arc);
+        let sync = SyncManager::new(dag);
 
         assert!(!sync.is_syncing().await);
     }
@@ -197,9 +374,8 @@ mod tests {
     async fn test_start_sync() {
         let storage = MemoryStorage::new();
         let dag = DagProcessor::new(storage);
-        let dag_arc = Arc::new(RwLock::new(dag));
 
-        let sync = SyncManager::new(dag_arc);
+        let sync = SyncManager::new(dag);
 
         sync.start_sync(1000).await;
 
@@ -209,13 +385,54 @@ mod tests {
         assert_eq!(state.target_height, Some(1000));
     }
 
+    #[tokio::test]
+    async fn test_snap_sync_pauses_live_gossip() {
+        use nyx_core::consensus::Validator;
+
+        let sync = SyncManager::new(DagProcessor::new(MemoryStorage::new()));
+        assert!(sync.is_live().await);
+
+        let validators = ValidatorSet::new(vec![Validator { public_key: vec![0u8; 32], stake: 1 }]).unwrap();
+        sync.start_snap_sync(validators, 2).await;
+        assert!(sync.is_syncing().await);
+        assert!(!sync.is_live().await);
+    }
+
+    #[tokio::test]
+    async fn test_sync_response_screens_headers() {
+        use nyx_core::transaction::TxOutput;
+        use nyx_core::NetworkId;
+
+        let source = DagProcessor::new(MemoryStorage::new()).with_network(NetworkId::Regtest);
+        let minted = source.mint(vec![TxOutput {
+            stealth_address: vec![1u8; 32],
+            amount_commitment: vec![],
+            range_proof: vec![],
+            ephemeral_pubkey: vec![],
+            unlock_time: None,
+            htlc: None,
+        }]).unwrap();
+        let tx = source.storage().get_transaction(&minted).unwrap();
+
+        let dag = DagProcessor::new(MemoryStorage::new()).with_network(NetworkId::Regtest);
+        let sync = SyncManager::new(dag.clone());
+        sync.handle_sync_response(vec![
+            LazyTransaction::encode(&tx),
+            LazyTransaction::encode(&tx.clone().with_network(NetworkId::Mainnet)),
+            LazyTransaction::from_bytes(vec![1, 0xFF]),
+        ]).await.unwrap();
+
+        assert!(dag.storage().has_transaction(&minted).unwrap());
+        assert_eq!(dag.storage().transaction_count().unwrap(), 1);
+        assert_eq!(sync.get_state().await.synced_count, 1);
+    }
+
     #[tokio::test]
     async fn test_complete_sync() {
         let storage = MemoryStorage::new();
         let dag = DagProcessor::new(storage);
-        let dag_arc = Arc::new(RwLock::new(dag));
 
-        let sync = SyncManager::new(dag_arc);
+        let sync = SyncManager::new(dag);
 
         sync.start_sync(1000).await;
         assert!(sync.is_syncing().await);
@@ -223,4 +440,46 @@ mod tests {
         sync.complete_sync().await;
         assert!(!sync.is_syncing().await);
     }
+
+    #[tokio::test]
+    async fn test_sessions_are_numbered() {
+        let sync = SyncManager::new(DagProcessor::new(MemoryStorage::new()));
+        assert_eq!(sync.get_state().await.session_id, None);
+        assert!(sync.session_span().await.is_none());
+
+        sync.start_sync(10).await;
+        assert_eq!(sync.get_state().await.session_id, Some(1));
+        sync.complete_sync().await;
+        assert_eq!(sync.get_state().await.session_id, None);
+
+        sync.start_sync(20).await;
+        assert_eq!(sync.get_state().await.session_id, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_pruned_history_is_refused() {
+        let storage = MemoryStorage::new();
+        let dag = DagProcessor::new(storage.clone());
+        let ring = nyx_core::RingSignature { ring_members: vec![vec![1], vec![2]], signature: vec![], key_image: [0u8; 32] };
+        let anchors: Vec<_> = (1..=2u8)
+            .map(|i| {
+                let tx = Transaction::new(vec![], vec![], vec![ring.clone()], vec![], [0u8; 32], [i; 32]);
+                storage.store_transaction(tx).unwrap()
+            })
+            .collect();
+
+        let finality = FinalityTracker::new(dag.clone()).with_retained_snapshots(1);
+        for anchor in anchors {
+            finality.apply_next_snapshot(vec![anchor]).unwrap();
+        }
+        let sync = SyncManager::with_finality(dag, finality);
+        let peer = Peer::new(vec![1], "127.0.0.1:9000".parse().unwrap());
+
+        let responses = sync.handle_sync_request(1, &peer).await.unwrap();
+        assert!(matches!(
+            responses.as_slice(),
+            [Message { message_type: MessageType::SyncPruned { retained_from: 2 }, .. }]
+        ));
+        assert!(sync.handle_sync_request(2, &peer).await.unwrap().is_empty());
+    }
 }
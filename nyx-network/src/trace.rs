@@ -0,0 +1,51 @@
+// src/trace.rs
+
+//! Tracing spans with correlation IDs.
+//!
+//! Every log line emitted while a span is entered carries the span's
+//! fields, so operators can follow one peer, transaction or sync session
+//! through gossip, the mempool and the DAG by filtering on:
+//!
+//! - `peer_id`: short hex form of the peer's identifier
+//! - `tx_id`: full hex transaction hash
+//! - `session_id`: number of the sync or snap sync session
+//!
+//! Spans are created here so the field names stay the same in every crate.
+
+use nyx_core::Hash;
+use std::net::SocketAddr;
+use tracing::{info_span, Span};
+
+/// Bytes of a peer id shown in logs
+const SHORT_ID_BYTES: usize = 8;
+
+/// Hex form of the first bytes of an identifier
+pub fn short_hex(id: &[u8]) -> String {
+    hex::encode(&id[..id.len().min(SHORT_ID_BYTES)])
+}
+
+/// Span for everything done on behalf of a connected peer
+pub fn peer_span(peer_id: &[u8], addr: SocketAddr) -> Span {
+    info_span!("peer", peer_id = %short_hex(peer_id), %addr)
+}
+
+/// Span for processing a single transaction
+pub fn tx_span(tx_id: &Hash) -> Span {
+    info_span!("tx", tx_id = %hex::encode(tx_id))
+}
+
+/// Span for a sync or snap sync session
+pub fn sync_span(session_id: u64) -> Span {
+    info_span!("sync", session_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_hex() {
+        assert_eq!(short_hex(&[0xAB; 32]), "abababababababab");
+        assert_eq!(short_hex(&[1, 2]), "0102");
+    }
+}
@@ -10,41 +10,17 @@
 
 #[cfg(test)]
 mod tests {
-    use nyx_network::{Node, NodeConfig};
-    use nyx_core::transaction::{Transaction, TxInput, TxOutput, RingSignature};
-    use std::net::SocketAddr;
+    use nyx_network::{Node, NodeConfig, NodeIdentity};
+    use nyx_core::testing::TestTx;
     use tokio::time::{sleep, Duration};
 
-    fn create_test_tx(nonce: u8) -> Transaction {
-        Transaction::new(
-            vec![TxInput {
-                prev_tx: [nonce; 32],
-                index: 0,
-                key_image: vec![nonce],
-                ring_indices: vec![],
-            }],
-            vec![TxOutput {
-                stealth_address: vec![nonce],
-                amount_commitment: vec![],
-                range_proof: vec![],
-            }],
-            RingSignature {
-                signature_data: vec![nonce],
-                ring_size: 16,
-            },
-            vec![nonce],
-            [0u8; 32],
-            [1u8; 32],
-        )
-    }
-
     #[tokio::test]
     async fn test_node_startup_and_shutdown() {
         println!("\n=== Test: Node Startup and Shutdown ===");
 
         let config = NodeConfig {
             listen_addr: "127.0.0.1:9001".parse().unwrap(),
-            node_id: vec![1u8; 32],
+            identity: Some(NodeIdentity::from_seed([1u8; 32])),
             ..Default::default()
         };
 
@@ -75,7 +51,7 @@ mod tests {
         // Node 1
         let config1 = NodeConfig {
             listen_addr: "127.0.0.1:9002".parse().unwrap(),
-            node_id: vec![1u8; 32],
+            identity: Some(NodeIdentity::from_seed([1u8; 32])),
             ..Default::default()
         };
 
@@ -94,7 +70,7 @@ mod tests {
         // Node 2 with node1 as bootstrap
         let config2 = NodeConfig {
             listen_addr: "127.0.0.1:9003".parse().unwrap(),
-            node_id: vec![2u8; 32],
+            identity: Some(NodeIdentity::from_seed([2u8; 32])),
             bootstrap_peers: vec![config1.listen_addr],
             ..Default::default()
         };
@@ -126,7 +102,7 @@ mod tests {
         // Setup node
         let config = NodeConfig {
             listen_addr: "127.0.0.1:9004".parse().unwrap(),
-            node_id: vec![10u8; 32],
+            identity: Some(NodeIdentity::from_seed([10u8; 32])),
             ..Default::default()
         };
 
@@ -134,7 +110,7 @@ mod tests {
         println!("✓ Node created on {}", config.listen_addr);
 
         // Create test transaction
-        let tx = create_test_tx(42);
+        let tx = TestTx::new(42).build();
         let tx_id = tx.id();
         println!("✓ Test transaction created: {}", hex::encode(&tx_id[..8]));
 
@@ -153,7 +129,7 @@ mod tests {
 
         let config = NodeConfig {
             listen_addr: "127.0.0.1:9005".parse().unwrap(),
-            node_id: vec![20u8; 32],
+            identity: Some(NodeIdentity::from_seed([20u8; 32])),
             ..Default::default()
         };
 
@@ -181,7 +157,7 @@ mod tests {
         // Node 1 (sender)
         let config1 = NodeConfig {
             listen_addr: "127.0.0.1:9006".parse().unwrap(),
-            node_id: vec![30u8; 32],
+            identity: Some(NodeIdentity::from_seed([30u8; 32])),
             ..Default::default()
         };
 
@@ -201,7 +177,7 @@ mod tests {
         // Node 2 (receiver)
         let config2 = NodeConfig {
             listen_addr: "127.0.0.1:9007".parse().unwrap(),
-            node_id: vec![40u8; 32],
+            identity: Some(NodeIdentity::from_seed([40u8; 32])),
             bootstrap_peers: vec![config1.listen_addr],
             ..Default::default()
         };
@@ -220,7 +196,7 @@ mod tests {
         println!("✓ Nodes connected");
 
         // Broadcast transaction from node1
-        let tx = create_test_tx(99);
+        let tx = TestTx::new(99).build();
         let tx_id = tx.id();
 
         println!("📡 Broadcasting transaction: {}", hex::encode(&tx_id[..8]));
@@ -258,7 +234,7 @@ mod tests {
 
         let config = NodeConfig {
             listen_addr: "127.0.0.1:9008".parse().unwrap(),
-            node_id: vec![50u8; 32],
+            identity: Some(NodeIdentity::from_seed([50u8; 32])),
             ..Default::default()
         };
 
@@ -284,7 +260,7 @@ mod tests {
 
         let config = NodeConfig {
             listen_addr: "127.0.0.1:9009".parse().unwrap(),
-            node_id: vec![60u8; 32],
+            identity: Some(NodeIdentity::from_seed([60u8; 32])),
             ..Default::default()
         };
 
@@ -293,7 +269,7 @@ mod tests {
         // Broadcast multiple transactions
         println!("📡 Broadcasting 5 transactions...");
         for i in 0..5 {
-            let tx = create_test_tx(i);
+            let tx = TestTx::new(i).build();
             let tx_id = tx.id();
 
             match node.broadcast_transaction(tx).await {
@@ -323,10 +299,11 @@ mod tests {
         // Setup two nodes
         let config1 = NodeConfig {
             listen_addr: "127.0.0.1:9010".parse().unwrap(),
-            node_id: vec![100u8; 32],
+            identity: Some(NodeIdentity::from_seed([100u8; 32])),
             max_peers: 10,
             min_peers: 1,
             bootstrap_peers: Vec::new(),
+            ..Default::default()
         };
 
         let node1 = Node::new(config1.clone()).await.unwrap();
@@ -343,10 +320,11 @@ mod tests {
 
         let config2 = NodeConfig {
             listen_addr: "127.0.0.1:9011".parse().unwrap(),
-            node_id: vec![200u8; 32],
+            identity: Some(NodeIdentity::from_seed([200u8; 32])),
             bootstrap_peers: vec![config1.listen_addr],
             max_peers: 10,
             min_peers: 1,
+            ..Default::default()
         };
 
         let node2 = Node::new(config2.clone()).await.unwrap();
@@ -364,7 +342,7 @@ mod tests {
         // Create and broadcast transactions
         println!("\n📡 Broadcasting transactions from Node1...");
         for i in 0..3 {
-            let tx = create_test_tx(i);
+            let tx = TestTx::new(i).build();
             let tx_id = tx.id();
 
             match node1_ref.broadcast_transaction(tx).await {
@@ -412,7 +390,7 @@ mod tests {
         // Create three nodes in a chain: Node1 <-> Node2 <-> Node3
         let config1 = NodeConfig {
             listen_addr: "127.0.0.1:9012".parse().unwrap(),
-            node_id: vec![111u8; 32],
+            identity: Some(NodeIdentity::from_seed([111u8; 32])),
             ..Default::default()
         };
 
@@ -427,7 +405,7 @@ mod tests {
 
         let config2 = NodeConfig {
             listen_addr: "127.0.0.1:9013".parse().unwrap(),
-            node_id: vec![222u8; 32],
+            identity: Some(NodeIdentity::from_seed([222u8; 32])),
             bootstrap_peers: vec![config1.listen_addr],
             ..Default::default()
         };
@@ -443,7 +421,7 @@ mod tests {
 
         let config3 = NodeConfig {
             listen_addr: "127.0.0.1:9014".parse().unwrap(),
-            node_id: vec![333u8; 32],
+            identity: Some(NodeIdentity::from_seed([33u8; 32])),
             bootstrap_peers: vec![config2.listen_addr],
             ..Default::default()
         };
@@ -480,14 +458,14 @@ mod tests {
 
         let config = NodeConfig {
             listen_addr: "127.0.0.1:9015".parse().unwrap(),
-            node_id: vec![70u8; 32],
+            identity: Some(NodeIdentity::from_seed([70u8; 32])),
             ..Default::default()
         };
 
         let node = Node::new(config).await.unwrap();
 
         // Broadcast same transaction twice
-        let tx = create_test_tx(123);
+        let tx = TestTx::new(123).build();
         let tx_id = tx.id();
 
         println!("📡 Broadcasting transaction twice...");
@@ -514,7 +492,7 @@ mod tests {
 
         let config = NodeConfig {
             listen_addr: "127.0.0.1:9016".parse().unwrap(),
-            node_id: vec![80u8; 32],
+            identity: Some(NodeIdentity::from_seed([80u8; 32])),
             ..Default::default()
         };
 
@@ -527,7 +505,7 @@ mod tests {
         for i in 0..10 {
             let node_clone = node.clone();
             let handle = tokio::spawn(async move {
-                let tx = create_test_tx(i);
+                let tx = TestTx::new(i).build();
                 node_clone.broadcast_transaction(tx).await
             });
             handles.push(handle);
@@ -2,13 +2,26 @@
 
 //! Node configuration.
 
+use crate::data_dir::DataDirLayout;
+use nyx_core::{Genesis, NetworkId, NodeMode};
+use nyx_network::seeds::DEFAULT_DNS_SEEDS;
+use nyx_network::BandwidthLimits;
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 
 /// Complete node configuration
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NodeConfig {
+    /// Network to join (mainnet, testnet, devnet or regtest)
+    #[serde(default)]
+    pub network_id: NetworkId,
+
+    /// Genesis file with the network's protocol parameters; the built-in
+    /// genesis of `network_id` is used if unset
+    #[serde(default)]
+    pub genesis: Option<PathBuf>,
+
     /// Network configuration
     pub network: NetworkConfig,
 
@@ -23,6 +36,38 @@ pub struct NodeConfig {
 
     /// Data directory
     pub data_dir: PathBuf,
+
+    /// Location of each component inside `data_dir`
+    #[serde(default)]
+    pub layout: DataDirLayout,
+
+    /// Mempool configuration
+    #[serde(default)]
+    pub mempool: MempoolConfig,
+
+    /// Log output configuration
+    #[serde(default)]
+    pub logging: LoggingConfig,
+
+    /// Readiness thresholds for the `/ready` endpoint
+    #[serde(default)]
+    pub health: HealthConfig,
+
+    /// Explorer index storage
+    #[serde(default)]
+    pub index: IndexConfig,
+
+    /// Compiled-in plugins to run, in start order
+    #[serde(default)]
+    pub plugins: Vec<PluginConfig>,
+
+    /// Webhooks notified of payments to watched addresses
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+
+    /// Release verification for auto-update
+    #[serde(default)]
+    pub update: UpdateConfig,
 }
 
 /// Network configuration
@@ -36,6 +81,46 @@ pub struct NetworkConfig {
 
     /// Bootstrap peers
     pub bootstrap_peers: Vec<SocketAddr>,
+
+    /// DNS seeds resolved to find peers at startup
+    #[serde(default = "default_dns_seeds")]
+    pub dns_seeds: Vec<String>,
+
+    /// Use the compiled-in seed nodes if no DNS seed resolves
+    #[serde(default = "default_use_fallback_seeds")]
+    pub use_fallback_seeds: bool,
+
+    /// Number of peers each gossiped message is sent to (all if unset)
+    #[serde(default)]
+    pub gossip_fanout: Option<usize>,
+
+    /// Seconds a peer connection may receive nothing before it is closed
+    #[serde(default = "default_idle_timeout_secs")]
+    pub idle_timeout_secs: u64,
+
+    /// Sign every message sent to peers with the node identity key
+    #[serde(default)]
+    pub sign_messages: bool,
+
+    /// Drop messages from peers that are not signed by them
+    #[serde(default)]
+    pub require_signed_messages: bool,
+
+    /// Upload and download caps, node-wide and per peer
+    #[serde(default)]
+    pub bandwidth: BandwidthLimits,
+}
+
+fn default_dns_seeds() -> Vec<String> {
+    DEFAULT_DNS_SEEDS.iter().map(|s| s.to_string()).collect()
+}
+
+fn default_use_fallback_seeds() -> bool {
+    true
+}
+
+fn default_idle_timeout_secs() -> u64 {
+    nyx_network::IDLE_TIMEOUT_SECS
 }
 
 /// RPC server configuration
@@ -46,16 +131,415 @@ pub struct RpcConfig {
 
     /// Enable RPC server
     pub enabled: bool,
+
+    /// Bearer token for the `/admin` endpoints, which are disabled if unset
+    #[serde(default)]
+    pub admin_token: Option<String>,
+
+    /// Abuse protection for public nodes
+    #[serde(default)]
+    pub limits: RpcLimits,
+
+    /// Origins allowed to call the RPC from a browser; `*` allows any
+    #[serde(default)]
+    pub cors_origins: Vec<String>,
+
+    /// Reverse proxies whose `X-Forwarded-For` header names the client
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+
+    /// Path prefix every route is served under, e.g. `/nyx` behind a
+    /// gateway that does not strip it
+    #[serde(default)]
+    pub base_path: Option<String>,
+
+    /// Serve the latest checkpoint as a snapshot file at
+    /// `/snapshots/latest/file` for nodes bootstrapping over HTTP
+    #[serde(default)]
+    pub serve_snapshot_file: bool,
+
+    /// Seconds the response to a request with an `Idempotency-Key` is
+    /// replayed to retries
+    #[serde(default = "default_idempotency_ttl_secs")]
+    pub idempotency_ttl_secs: u64,
+
+    /// Idempotency keys remembered at once
+    #[serde(default = "default_idempotency_max_keys")]
+    pub idempotency_max_keys: usize,
+}
+
+fn default_idempotency_ttl_secs() -> u64 {
+    crate::idempotency::DEFAULT_TTL.as_secs()
+}
+
+fn default_idempotency_max_keys() -> usize {
+    crate::idempotency::DEFAULT_MAX_KEYS
+}
+
+/// Request limits of the RPC server
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcLimits {
+    /// Requests per second allowed from one IP address (0 disables the
+    /// rate limit)
+    #[serde(default = "default_requests_per_second")]
+    pub requests_per_second: u32,
+
+    /// Requests one IP address may send at once after being idle
+    #[serde(default = "default_burst")]
+    pub burst: u32,
+
+    /// Largest accepted request body in bytes
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+
+    /// Requests handled at once across all clients; further ones are
+    /// rejected with 503
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+}
+
+fn default_requests_per_second() -> u32 {
+    50
+}
+
+fn default_burst() -> u32 {
+    100
+}
+
+fn default_max_body_bytes() -> usize {
+    1024 * 1024
+}
+
+fn default_max_concurrent_requests() -> usize {
+    256
+}
+
+impl Default for RpcLimits {
+    fn default() -> Self {
+        Self {
+            requests_per_second: default_requests_per_second(),
+            burst: default_burst(),
+            max_body_bytes: default_max_body_bytes(),
+            max_concurrent_requests: default_max_concurrent_requests(),
+        }
+    }
 }
 
 /// DAG configuration
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DagConfig {
-    /// Confirmation threshold
-    pub confirmation_threshold: f64,
-
     /// Sync interval in seconds
     pub sync_interval: u64,
+
+    /// Whether to keep full history or only recent snapshots
+    #[serde(default)]
+    pub mode: NodeMode,
+
+    /// Snapshots whose transactions a pruned node keeps
+    #[serde(default = "default_keep_snapshots")]
+    pub keep_snapshots: u64,
+
+    /// Transactions kept in the cache shared by storage, the mempool and
+    /// the RPC server (0 disables it)
+    #[serde(default = "default_tx_cache_capacity")]
+    pub tx_cache_capacity: usize,
+}
+
+impl DagConfig {
+    /// Snapshots to retain, or `None` for an archive node
+    pub fn retained_snapshots(&self) -> Option<u64> {
+        match self.mode {
+            NodeMode::Archive => None,
+            NodeMode::Pruned => Some(self.keep_snapshots),
+        }
+    }
+}
+
+fn default_keep_snapshots() -> u64 {
+    1000
+}
+
+fn default_tx_cache_capacity() -> usize {
+    nyx_core::tx_cache::DEFAULT_TX_CACHE_CAPACITY
+}
+
+/// Mempool configuration
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MempoolConfig {
+    /// Maximum number of pending transactions
+    pub max_size: usize,
+
+    /// Transactions from peers verified in parallel before admission
+    #[serde(default = "default_verify_workers")]
+    pub verify_workers: usize,
+
+    /// Seconds a transaction may stay pending before it is gossiped
+    /// again (0 disables rebroadcasting)
+    #[serde(default = "default_rebroadcast_after_secs")]
+    pub rebroadcast_after_secs: u64,
+
+    /// How pending transactions are ranked
+    #[serde(default)]
+    pub priority: PriorityConfig,
+
+    /// Transactions each peer may get admitted per sliding window
+    #[serde(default)]
+    pub peer_quota: QuotaConfig,
+}
+
+impl Default for MempoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 1000,
+            verify_workers: default_verify_workers(),
+            rebroadcast_after_secs: default_rebroadcast_after_secs(),
+            priority: PriorityConfig::default(),
+            peer_quota: QuotaConfig::default(),
+        }
+    }
+}
+
+/// Per-peer admission quota
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuotaConfig {
+    /// Transactions admitted per peer within the window (0 disables
+    /// quotas)
+    pub max_transactions: usize,
+
+    /// Length of the sliding window in seconds
+    pub window_secs: u64,
+}
+
+impl Default for QuotaConfig {
+    fn default() -> Self {
+        Self {
+            max_transactions: 300,
+            window_secs: 60,
+        }
+    }
+}
+
+/// Weights of the mempool priority metrics
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PriorityConfig {
+    /// Per minute pending
+    pub age_weight: f64,
+
+    /// Per leading zero bit of the transaction hash
+    pub pow_weight: f64,
+
+    /// Per reference to a transaction approved by fewer than
+    /// `min_approvers` others
+    pub tip_weight: f64,
+
+    /// Approvers a referenced transaction needs to stop counting as
+    /// under-approved
+    pub min_approvers: usize,
+}
+
+impl Default for PriorityConfig {
+    fn default() -> Self {
+        Self {
+            age_weight: 1.0,
+            pow_weight: 1.0,
+            tip_weight: 4.0,
+            min_approvers: 1,
+        }
+    }
+}
+
+fn default_rebroadcast_after_secs() -> u64 {
+    60
+}
+
+fn default_verify_workers() -> usize {
+    std::thread::available_parallelism().map_or(4, |n| n.get())
+}
+
+/// Log line format
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines with span context
+    #[default]
+    Text,
+
+    /// One JSON object per line, including the fields of every entered span
+    Json,
+}
+
+/// Log output configuration
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// Line format
+    #[serde(default)]
+    pub format: LogFormat,
+
+    /// Filter directives such as `info,nyx_network=debug`; `RUST_LOG`
+    /// takes precedence when set
+    #[serde(default = "default_log_filter")]
+    pub filter: String,
+}
+
+fn default_log_filter() -> String {
+    "info".to_string()
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::default(),
+            filter: default_log_filter(),
+        }
+    }
+}
+
+/// Explorer index storage
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndexConfig {
+    /// Keep the index in `index.db` in the database directory instead of
+    /// rebuilding it in memory on every start
+    pub persistent: bool,
+
+    /// Copy an older index aside before migrating it to the current schema
+    pub backup_before_migrate: bool,
+}
+
+/// Readiness thresholds
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HealthConfig {
+    /// Minimum number of connected peers
+    #[serde(default = "default_min_peers")]
+    pub min_peers: usize,
+
+    /// Largest accepted distance between the local and target sync height
+    #[serde(default = "default_max_sync_lag")]
+    pub max_sync_lag: u64,
+
+    /// Largest accepted age of the latest applied snapshot in seconds, or
+    /// `None` to skip the check
+    #[serde(default = "default_max_snapshot_age")]
+    pub max_snapshot_age_secs: Option<u64>,
+}
+
+fn default_min_peers() -> usize {
+    1
+}
+
+fn default_max_sync_lag() -> u64 {
+    10
+}
+
+fn default_max_snapshot_age() -> Option<u64> {
+    Some(600)
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            min_peers: default_min_peers(),
+            max_sync_lag: default_max_sync_lag(),
+            max_snapshot_age_secs: default_max_snapshot_age(),
+        }
+    }
+}
+
+/// Plugin to run
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PluginConfig {
+    /// Name the plugin is registered under
+    pub name: String,
+
+    /// Plugin-specific settings, passed to its factory
+    #[serde(default)]
+    pub settings: serde_json::Value,
+}
+
+/// Webhook notified when payments to an address confirm or finalize
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// `http://` URL receiving JSON POSTs
+    pub url: String,
+
+    /// Watched Nyx address
+    pub address: String,
+
+    /// Hex private view key of the address, used to detect payments
+    pub view_key: String,
+
+    /// Delivery attempts before a notification is dropped
+    #[serde(default = "default_webhook_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry in milliseconds, doubled per attempt
+    #[serde(default = "default_webhook_backoff_ms")]
+    pub initial_backoff_ms: u64,
+}
+
+fn default_webhook_max_attempts() -> u32 {
+    5
+}
+
+fn default_webhook_backoff_ms() -> u64 {
+    1000
+}
+
+/// Auto-update configuration
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateConfig {
+    /// Enable auto-update; the node then refuses to start unless its
+    /// binary is in a release manifest signed by trusted keys
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Signed release manifest of the running version
+    #[serde(default)]
+    pub release_manifest: Option<PathBuf>,
+
+    /// Hex Ed25519 public keys of trusted release signers
+    #[serde(default)]
+    pub release_keys: Vec<String>,
+
+    /// Trusted signatures a release manifest needs
+    #[serde(default = "default_release_threshold")]
+    pub release_threshold: usize,
+
+    /// `http://` URL of the signed version manifest polled for upgrades
+    #[serde(default)]
+    pub manifest_url: Option<String>,
+
+    /// Seconds between two polls of the version manifest
+    #[serde(default = "default_update_check_interval_secs")]
+    pub check_interval_secs: u64,
+
+    /// `http://` URL notified when an upgrade is announced
+    #[serde(default)]
+    pub webhook: Option<String>,
+}
+
+fn default_release_threshold() -> usize {
+    1
+}
+
+fn default_update_check_interval_secs() -> u64 {
+    6 * 60 * 60
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            release_manifest: None,
+            release_keys: Vec::new(),
+            release_threshold: default_release_threshold(),
+            manifest_url: None,
+            check_interval_secs: default_update_check_interval_secs(),
+            webhook: None,
+        }
+    }
 }
 
 /// Wallet configuration
@@ -71,29 +555,96 @@ pub struct WalletConfig {
 impl Default for NodeConfig {
     fn default() -> Self {
         Self {
+            network_id: NetworkId::Mainnet,
+            genesis: None,
             network: NetworkConfig {
                 listen_addr: "127.0.0.1:8000".parse().unwrap(),
                 max_peers: 50,
                 bootstrap_peers: Vec::new(),
+                dns_seeds: default_dns_seeds(),
+                use_fallback_seeds: default_use_fallback_seeds(),
+                gossip_fanout: None,
+                idle_timeout_secs: default_idle_timeout_secs(),
+                sign_messages: false,
+                require_signed_messages: false,
+                bandwidth: BandwidthLimits::default(),
             },
             rpc: RpcConfig {
                 listen_addr: "127.0.0.1:9000".parse().unwrap(),
                 enabled: true,
+                admin_token: None,
+                limits: RpcLimits::default(),
+                cors_origins: Vec::new(),
+                trusted_proxies: Vec::new(),
+                base_path: None,
+                serve_snapshot_file: false,
+                idempotency_ttl_secs: default_idempotency_ttl_secs(),
+                idempotency_max_keys: default_idempotency_max_keys(),
             },
             dag: DagConfig {
-                confirmation_threshold: 100.0,
                 sync_interval: 60,
+                mode: NodeMode::default(),
+                keep_snapshots: default_keep_snapshots(),
+                tx_cache_capacity: default_tx_cache_capacity(),
             },
             wallet: WalletConfig {
                 enabled: true,
                 wallet_dir: PathBuf::from(".nyx-wallet"),
             },
             data_dir: PathBuf::from(".nyx-data"),
+            layout: DataDirLayout::default(),
+            mempool: MempoolConfig::default(),
+            logging: LoggingConfig::default(),
+            health: HealthConfig::default(),
+            index: IndexConfig::default(),
+            plugins: Vec::new(),
+            webhooks: Vec::new(),
+            update: UpdateConfig::default(),
         }
     }
 }
 
 impl NodeConfig {
+    /// Configuration for a standalone local regtest node
+    ///
+    /// Regtest confirms and finalizes transactions instantly and lets the
+    /// RPC `generate` endpoint mint funds, so it never looks for peers and
+    /// is ready without peers or a recent snapshot.
+    pub fn regtest() -> Self {
+        let mut config = Self {
+            network_id: NetworkId::Regtest,
+            data_dir: PathBuf::from(".nyx-regtest"),
+            ..Self::default()
+        };
+        config.network.dns_seeds.clear();
+        config.network.use_fallback_seeds = false;
+        config.health.min_peers = 0;
+        config.health.max_snapshot_age_secs = None;
+        config
+    }
+
+    /// Loads the genesis of the configured network
+    ///
+    /// # Errors
+    /// - `ConfigError` if the genesis file is invalid or belongs to another
+    ///   network
+    pub fn load_genesis(&self) -> crate::Result<Genesis> {
+        let Some(path) = &self.genesis else {
+            return Ok(Genesis::for_network(self.network_id));
+        };
+
+        let genesis = Genesis::load(path)
+            .map_err(|e| crate::NodeError::ConfigError(format!("Failed to load genesis: {}", e)))?;
+        if genesis.network != self.network_id {
+            return Err(crate::NodeError::ConfigError(format!(
+                "Genesis is for {}, config is for {}",
+                genesis.network, self.network_id
+            )));
+        }
+
+        Ok(genesis)
+    }
+
     /// Loads configuration from file
     pub fn from_file(path: &std::path::Path) -> crate::Result<Self> {
         let contents = std::fs::read_to_string(path)?;
@@ -109,3 +660,29 @@ impl NodeConfig {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_genesis() {
+        let config = NodeConfig::regtest();
+        assert_eq!(config.load_genesis().unwrap(), Genesis::for_network(NetworkId::Regtest));
+    }
+
+    #[test]
+    fn test_genesis_file_must_match_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("genesis.json");
+        let mut genesis = Genesis::for_network(NetworkId::Devnet);
+        genesis.params.confirmation_threshold = 5.0;
+        std::fs::write(&path, genesis.to_json()).unwrap();
+
+        let mut config = NodeConfig { genesis: Some(path), ..NodeConfig::default() };
+        assert!(config.load_genesis().is_err());
+
+        config.network_id = NetworkId::Devnet;
+        assert_eq!(config.load_genesis().unwrap(), genesis);
+    }
+}
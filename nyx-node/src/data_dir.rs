@@ -0,0 +1,262 @@
+// src/data_dir.rs
+
+//! Data directory layout.
+//!
+//! Everything the node persists lives under one data directory, split by
+//! component:
+//!
+//! ```text
+//! <data_dir>/
+//! ├── db/           persistent explorer index
+//! ├── keystore/     node identity key
+//! ├── peers.json    known peer addresses
+//! └── snapshots/    partial snapshot downloads
+//! ```
+//!
+//! Each location can be moved through [`DataDirLayout`]; relative paths are
+//! resolved against the data directory, absolute ones are used as is.
+
+use crate::Result;
+use nyx_network::address_book::ADDRESS_BOOK_FILE;
+use nyx_network::identity::NODE_KEY_FILE;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// File name of the persistent index inside the database directory
+pub const INDEX_FILE: &str = "index.db";
+
+/// File name of a partial snapshot download inside the snapshot directory
+pub const SNAPSHOT_PART_FILE: &str = "snapshot.part";
+
+/// Where each component lives inside the data directory
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DataDirLayout {
+    /// Directory of the databases
+    pub db: PathBuf,
+
+    /// Directory of the key files
+    pub keystore: PathBuf,
+
+    /// Address book file
+    pub peers: PathBuf,
+
+    /// Directory of snapshot downloads
+    pub snapshots: PathBuf,
+}
+
+impl Default for DataDirLayout {
+    fn default() -> Self {
+        Self {
+            db: PathBuf::from("db"),
+            keystore: PathBuf::from("keystore"),
+            peers: PathBuf::from(ADDRESS_BOOK_FILE),
+            snapshots: PathBuf::from("snapshots"),
+        }
+    }
+}
+
+/// Bytes used by each component of the data directory
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DiskUsage {
+    /// Databases
+    pub db: u64,
+
+    /// Key files
+    pub keystore: u64,
+
+    /// Address book
+    pub peers: u64,
+
+    /// Snapshot downloads
+    pub snapshots: u64,
+
+    /// Anything else in the data directory
+    pub other: u64,
+}
+
+impl DiskUsage {
+    /// Bytes used by all components
+    pub fn total(&self) -> u64 {
+        self.db + self.keystore + self.peers + self.snapshots + self.other
+    }
+}
+
+/// Resolved data directory of a node
+#[derive(Clone, Debug)]
+pub struct DataDir {
+    root: PathBuf,
+    layout: DataDirLayout,
+}
+
+impl DataDir {
+    /// Creates the data directory rooted at `root`
+    pub fn new(root: impl Into<PathBuf>, layout: DataDirLayout) -> Self {
+        Self { root: root.into(), layout }
+    }
+
+    /// Root of the data directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Directory of the databases
+    pub fn db_dir(&self) -> PathBuf {
+        self.root.join(&self.layout.db)
+    }
+
+    /// Directory of the key files
+    pub fn keystore_dir(&self) -> PathBuf {
+        self.root.join(&self.layout.keystore)
+    }
+
+    /// Address book file
+    pub fn peers_file(&self) -> PathBuf {
+        self.root.join(&self.layout.peers)
+    }
+
+    /// Directory of snapshot downloads
+    pub fn snapshots_dir(&self) -> PathBuf {
+        self.root.join(&self.layout.snapshots)
+    }
+
+    /// Persistent explorer index
+    pub fn index_file(&self) -> PathBuf {
+        self.db_dir().join(INDEX_FILE)
+    }
+
+    /// Node identity key
+    pub fn node_key_file(&self) -> PathBuf {
+        self.keystore_dir().join(NODE_KEY_FILE)
+    }
+
+    /// Partial snapshot download
+    pub fn snapshot_part_file(&self) -> PathBuf {
+        self.snapshots_dir().join(SNAPSHOT_PART_FILE)
+    }
+
+    /// Creates the component directories
+    ///
+    /// The node key and index of older nodes, kept directly in the root, are
+    /// moved to their component directories so the peer ID and index survive
+    /// the upgrade.
+    ///
+    /// # Errors
+    /// - `IoError` if a directory cannot be created or a file moved
+    pub fn create(&self) -> Result<()> {
+        for dir in [self.root.clone(), self.db_dir(), self.keystore_dir(), self.snapshots_dir()] {
+            fs::create_dir_all(dir)?;
+        }
+        if let Some(dir) = self.peers_file().parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        for (legacy, path) in [(NODE_KEY_FILE, self.node_key_file()), (INDEX_FILE, self.index_file())] {
+            let legacy = self.root.join(legacy);
+            if legacy.is_file() && !path.exists() {
+                fs::rename(&legacy, &path)?;
+                info!("Moved {} to {}", legacy.display(), path.display());
+            }
+        }
+        Ok(())
+    }
+
+    /// Measures the bytes used by each component
+    ///
+    /// Missing components use nothing. Components moved outside the root are
+    /// measured where they are, and not counted again in `other`.
+    ///
+    /// # Errors
+    /// - `IoError` if a component cannot be read
+    pub fn disk_usage(&self) -> Result<DiskUsage> {
+        let components = [self.db_dir(), self.keystore_dir(), self.peers_file(), self.snapshots_dir()];
+
+        Ok(DiskUsage {
+            db: size_of(&components[0], &[])?,
+            keystore: size_of(&components[1], &[])?,
+            peers: size_of(&components[2], &[])?,
+            snapshots: size_of(&components[3], &[])?,
+            other: size_of(&self.root, &components)?,
+        })
+    }
+}
+
+/// Bytes used by the file or directory tree at `path`, leaving out `skip`
+///
+/// Symbolic links are not followed.
+fn size_of(path: &Path, skip: &[PathBuf]) -> io::Result<u64> {
+    if skip.iter().any(|skipped| skipped == path) {
+        return Ok(0);
+    }
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+
+    let mut total = 0;
+    for entry in fs::read_dir(path)? {
+        total += size_of(&entry?.path(), skip)?;
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_components_resolve_against_root() {
+        let outside = tempfile::tempdir().unwrap();
+        let layout = DataDirLayout { keystore: outside.path().join("keys"), ..DataDirLayout::default() };
+        let data_dir = DataDir::new("/var/lib/nyx", layout);
+
+        assert_eq!(data_dir.index_file(), Path::new("/var/lib/nyx/db/index.db"));
+        assert_eq!(data_dir.peers_file(), Path::new("/var/lib/nyx/peers.json"));
+        assert_eq!(data_dir.snapshot_part_file(), Path::new("/var/lib/nyx/snapshots/snapshot.part"));
+        assert_eq!(data_dir.node_key_file(), outside.path().join("keys").join(NODE_KEY_FILE));
+    }
+
+    #[test]
+    fn test_legacy_files_are_moved() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(NODE_KEY_FILE), "key").unwrap();
+        fs::write(dir.path().join(INDEX_FILE), "index").unwrap();
+
+        let data_dir = DataDir::new(dir.path(), DataDirLayout::default());
+        data_dir.create().unwrap();
+
+        assert_eq!(fs::read_to_string(data_dir.node_key_file()).unwrap(), "key");
+        assert_eq!(fs::read_to_string(data_dir.index_file()).unwrap(), "index");
+        assert!(!dir.path().join(NODE_KEY_FILE).exists());
+        assert!(data_dir.snapshots_dir().is_dir());
+    }
+
+    #[test]
+    fn test_disk_usage_per_component() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let layout = DataDirLayout { snapshots: outside.path().to_path_buf(), ..DataDirLayout::default() };
+        let data_dir = DataDir::new(dir.path(), layout);
+        data_dir.create().unwrap();
+
+        fs::write(data_dir.index_file(), [0u8; 100]).unwrap();
+        fs::write(data_dir.node_key_file(), [0u8; 64]).unwrap();
+        fs::write(data_dir.peers_file(), [0u8; 10]).unwrap();
+        fs::write(data_dir.snapshot_part_file(), [0u8; 1000]).unwrap();
+        fs::write(dir.path().join("notes.txt"), [0u8; 5]).unwrap();
+
+        let usage = data_dir.disk_usage().unwrap();
+        assert_eq!(
+            usage,
+            DiskUsage { db: 100, keystore: 64, peers: 10, snapshots: 1000, other: 5 }
+        );
+        assert_eq!(usage.total(), 1179);
+    }
+}
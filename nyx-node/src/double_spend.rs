@@ -0,0 +1,255 @@
+// src/double_spend.rs
+
+//! Double-spend alerts for merchants.
+//!
+//! The DAG refuses a second spend of a key image, but until a payment is
+//! final a conflicting transaction can still be circulating, or can even be
+//! the one the DAG holds. [`DoubleSpendMonitor`] indexes the key images of
+//! every transaction the node sees, from the DAG and from the mempool, and
+//! raises a [`DoubleSpendAlert`] as soon as two transactions spend the same
+//! one. Each alert carries the standing of every conflicting branch so a
+//! merchant can decide whether to wait, or to stop delivery.
+
+use crate::events::NodeEvent;
+use crate::EVENT_BUS_CAPACITY;
+use nyx_core::dag::DagProcessor;
+use nyx_core::transaction::Transaction;
+use nyx_core::Hash;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// Two or more transactions spending the same key image
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DoubleSpendAlert {
+    /// The doubly spent key image (hex)
+    pub key_image: String,
+
+    /// Every known transaction spending it, leading branch first
+    pub branches: Vec<ConflictBranch>,
+}
+
+impl DoubleSpendAlert {
+    /// Checks whether `tx_hash` is one of the conflicting transactions
+    pub fn involves(&self, tx_hash: &Hash) -> bool {
+        let tx_hash = hex::encode(tx_hash);
+        self.branches.iter().any(|branch| branch.tx_hash == tx_hash)
+    }
+}
+
+/// Standing of one side of a double spend
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ConflictBranch {
+    /// Transaction hash (hex)
+    pub tx_hash: String,
+
+    /// Whether the DAG holds the transaction; at most one branch can
+    pub in_dag: bool,
+
+    /// Confirmation score (0 outside the DAG)
+    pub score: f64,
+
+    /// Number of transactions approving it, itself included (0 outside
+    /// the DAG)
+    pub cumulative_weight: u64,
+}
+
+/// Detects transactions spending the same key image
+#[derive(Clone)]
+pub struct DoubleSpendMonitor {
+    /// DAG the branches are scored against
+    dag: DagProcessor,
+
+    /// Transactions seen spending each key image
+    spenders: Arc<Mutex<HashMap<[u8; 32], HashSet<Hash>>>>,
+
+    /// Raised alerts
+    alerts: broadcast::Sender<DoubleSpendAlert>,
+}
+
+impl DoubleSpendMonitor {
+    /// Creates a monitor scoring conflicts against `dag`
+    pub fn new(dag: DagProcessor) -> Self {
+        Self {
+            dag,
+            spenders: Arc::new(Mutex::new(HashMap::new())),
+            alerts: broadcast::channel(EVENT_BUS_CAPACITY).0,
+        }
+    }
+
+    /// Subscribes to alerts raised from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<DoubleSpendAlert> {
+        self.alerts.subscribe()
+    }
+
+    /// Records a transaction's key images, raising an alert for each one
+    /// another transaction already spends
+    ///
+    /// # Returns
+    /// The alerts raised
+    pub fn observe(&self, tx: &Transaction) -> Vec<DoubleSpendAlert> {
+        let tx_hash = tx.id();
+        let conflicts: Vec<([u8; 32], Vec<Hash>)> = {
+            let mut spenders = self.spenders.lock().unwrap_or_else(|e| e.into_inner());
+            tx.inputs.iter()
+                .filter_map(|input| {
+                    let spenders = spenders.entry(input.key_image).or_default();
+                    (spenders.insert(tx_hash) && spenders.len() > 1)
+                        .then(|| (input.key_image, spenders.iter().copied().collect()))
+                })
+                .collect()
+        };
+
+        let alerts: Vec<DoubleSpendAlert> = conflicts.into_iter()
+            .map(|(key_image, spenders)| self.alert(key_image, spenders))
+            .collect();
+        for alert in &alerts {
+            warn!(key_image = %alert.key_image, branches = alert.branches.len(), "Double spend detected");
+            let _ = self.alerts.send(alert.clone());
+        }
+        alerts
+    }
+
+    /// Gets the current conflicts involving a transaction, with fresh scores
+    pub fn conflicts_of(&self, tx_hash: &Hash) -> Vec<DoubleSpendAlert> {
+        let conflicts: Vec<([u8; 32], Vec<Hash>)> = {
+            let spenders = self.spenders.lock().unwrap_or_else(|e| e.into_inner());
+            spenders.iter()
+                .filter(|(_, spenders)| spenders.len() > 1 && spenders.contains(tx_hash))
+                .map(|(key_image, spenders)| (*key_image, spenders.iter().copied().collect()))
+                .collect()
+        };
+
+        conflicts.into_iter()
+            .map(|(key_image, spenders)| self.alert(key_image, spenders))
+            .collect()
+    }
+
+    /// Observes DAG additions from the node's event bus and mempool
+    /// admissions until both channels close
+    pub fn spawn(
+        self,
+        mut events: broadcast::Receiver<NodeEvent>,
+        mut admitted: broadcast::Receiver<Transaction>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let (mut events_open, mut admitted_open) = (true, true);
+            while events_open || admitted_open {
+                tokio::select! {
+                    event = events.recv(), if events_open => match event {
+                        Ok(NodeEvent::TransactionAccepted { tx, .. }) => {
+                            self.observe(&tx);
+                        }
+                        Ok(_) => {}
+                        Err(RecvError::Lagged(missed)) => {
+                            warn!("Double-spend monitor missed {} node events", missed);
+                        }
+                        Err(RecvError::Closed) => events_open = false,
+                    },
+                    tx = admitted.recv(), if admitted_open => match tx {
+                        Ok(tx) => {
+                            self.observe(&tx);
+                        }
+                        Err(RecvError::Lagged(missed)) => {
+                            warn!("Double-spend monitor missed {} mempool admissions", missed);
+                        }
+                        Err(RecvError::Closed) => admitted_open = false,
+                    },
+                }
+            }
+        })
+    }
+
+    /// Builds an alert, scoring each branch against the DAG
+    fn alert(&self, key_image: [u8; 32], spenders: Vec<Hash>) -> DoubleSpendAlert {
+        let mut branches: Vec<ConflictBranch> = spenders.iter()
+            .map(|tx_hash| {
+                let in_dag = self.dag.storage().has_transaction(tx_hash).unwrap_or(false);
+                ConflictBranch {
+                    tx_hash: hex::encode(tx_hash),
+                    in_dag,
+                    score: if in_dag { self.dag.get_score(tx_hash).unwrap_or(0.0) } else { 0.0 },
+                    cumulative_weight: if in_dag { self.dag.cumulative_weight(tx_hash).unwrap_or(1) } else { 0 },
+                }
+            })
+            .collect();
+
+        branches.sort_by(|a, b| {
+            b.in_dag.cmp(&a.in_dag)
+                .then(b.score.total_cmp(&a.score))
+                .then(b.cumulative_weight.cmp(&a.cumulative_weight))
+                .then(a.tx_hash.cmp(&b.tx_hash))
+        });
+
+        DoubleSpendAlert {
+            key_image: hex::encode(key_image),
+            branches,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mempool::Mempool;
+    use nyx_core::storage::MemoryStorage;
+    use nyx_core::testing::TestTx;
+
+    fn spend(key_image: u8, nonce: u8) -> Transaction {
+        TestTx::new(key_image).stealth_address(vec![nonce]).build()
+    }
+
+    #[test]
+    fn test_conflicting_spend_raises_alert() {
+        let dag = DagProcessor::new(MemoryStorage::new());
+        let monitor = DoubleSpendMonitor::new(dag.clone());
+        let mut alerts = monitor.subscribe();
+
+        let paid = spend(7, 1);
+        let unrelated = spend(8, 1);
+        let conflicting = spend(7, 2);
+        dag.storage().store_transaction(paid.clone()).unwrap();
+
+        assert!(monitor.observe(&paid).is_empty());
+        assert!(monitor.observe(&unrelated).is_empty());
+        assert!(monitor.observe(&paid).is_empty());
+
+        let raised = monitor.observe(&conflicting);
+        assert_eq!(raised.len(), 1);
+        assert_eq!(alerts.try_recv().unwrap(), raised[0]);
+
+        let alert = &raised[0];
+        assert_eq!(alert.key_image, hex::encode([7u8; 32]));
+        assert!(alert.involves(&paid.id()) && alert.involves(&conflicting.id()));
+        assert!(!alert.involves(&unrelated.id()));
+
+        // The branch the DAG holds leads
+        assert_eq!(alert.branches[0].tx_hash, hex::encode(paid.id()));
+        assert!(alert.branches[0].in_dag);
+        assert_eq!(alert.branches[0].cumulative_weight, 1);
+        assert!(!alert.branches[1].in_dag);
+        assert_eq!(alert.branches[1].cumulative_weight, 0);
+
+        assert_eq!(monitor.conflicts_of(&paid.id()), raised);
+        assert!(monitor.conflicts_of(&unrelated.id()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_monitor_observes_mempool_admissions() {
+        let monitor = DoubleSpendMonitor::new(DagProcessor::new(MemoryStorage::new()));
+        let mut alerts = monitor.subscribe();
+        let mempool = Mempool::new(10);
+        let events = crate::EventBus::new();
+        monitor.clone().spawn(events.subscribe(), mempool.subscribe());
+
+        mempool.add_transaction(spend(3, 1)).await.unwrap();
+        mempool.add_transaction(spend(3, 2)).await.unwrap();
+
+        let alert = tokio::time::timeout(std::time::Duration::from_secs(5), alerts.recv())
+            .await.unwrap().unwrap();
+        assert_eq!(alert.branches.len(), 2);
+    }
+}
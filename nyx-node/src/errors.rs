@@ -22,9 +22,15 @@ pub enum NodeError {
     /// Mempool error
     MempoolError(String),
 
+    /// Transaction refused by the mempool for a reason reported to peers
+    Rejected(nyx_network::RejectCode),
+
     /// RPC error
     RpcError(String),
 
+    /// Explorer index error
+    IndexerError(String),
+
     /// I/O error
     IoError(std::io::Error),
 
@@ -40,13 +46,27 @@ impl fmt::Display for NodeError {
             NodeError::CoreError(msg) => write!(f, "Core error: {}", msg),
             NodeError::WalletError(msg) => write!(f, "Wallet error: {}", msg),
             NodeError::MempoolError(msg) => write!(f, "Mempool error: {}", msg),
+            NodeError::Rejected(code) => write!(f, "Transaction rejected: {}", code),
             NodeError::RpcError(msg) => write!(f, "RPC error: {}", msg),
+            NodeError::IndexerError(msg) => write!(f, "Indexer error: {}", msg),
             NodeError::IoError(err) => write!(f, "I/O error: {}", err),
             NodeError::InitializationError(msg) => write!(f, "Initialization error: {}", msg),
         }
     }
 }
 
+impl NodeError {
+    /// Rejection code reported to the peer a refused transaction came from
+    ///
+    /// Errors other than `Rejected` mean the transaction itself is at fault.
+    pub fn reject_code(&self) -> nyx_network::RejectCode {
+        match self {
+            NodeError::Rejected(code) => *code,
+            _ => nyx_network::RejectCode::Invalid,
+        }
+    }
+}
+
 impl std::error::Error for NodeError {}
 
 impl From<std::io::Error> for NodeError {
@@ -73,5 +93,11 @@ impl From<nyx_wallet::WalletError> for NodeError {
     }
 }
 
+impl From<nyx_indexer::IndexerError> for NodeError {
+    fn from(err: nyx_indexer::IndexerError) -> Self {
+        NodeError::IndexerError(format!("{}", err))
+    }
+}
+
 /// Result type alias for node operations
 pub type Result<T> = std::result::Result<T, NodeError>;
@@ -0,0 +1,217 @@
+// src/events.rs
+
+//! Typed event bus for node add-ons.
+//!
+//! The DAG, the finality tracker and the network each report what happened
+//! in their own way. The bus republishes those reports as [`NodeEvent`]s on
+//! one broadcast channel, so consumers such as the wallet scanner or an
+//! event stream API subscribe once instead of polling internals.
+
+use crate::errors::{NodeError, Result};
+use crate::EVENT_BUS_CAPACITY;
+use nyx_core::dag::{DagEvent, DagProcessor};
+use nyx_core::finality::{FinalityTracker, SnapshotHeader};
+use nyx_core::transaction::Transaction;
+use nyx_core::Hash;
+use nyx_network::PeerId;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Something that happened inside the node
+#[derive(Debug, Clone)]
+pub enum NodeEvent {
+    /// A transaction was accepted into the DAG
+    TransactionAccepted {
+        /// Transaction hash
+        hash: Hash,
+
+        /// The accepted transaction (shared between subscribers)
+        tx: Arc<Transaction>,
+    },
+
+    /// A transaction reached the confirmation threshold
+    TransactionConfirmed {
+        /// Transaction hash
+        hash: Hash,
+    },
+
+    /// A transaction became final
+    TransactionFinalized {
+        /// Transaction hash
+        hash: Hash,
+    },
+
+    /// A peer was banned for misbehaving
+    PeerBanned {
+        /// ID of the banned peer
+        peer_id: PeerId,
+
+        /// Why the peer was banned
+        reason: String,
+    },
+
+    /// A PoS snapshot was applied
+    SnapshotProduced {
+        /// Header committing to the snapshot
+        header: SnapshotHeader,
+    },
+}
+
+/// Broadcast channel of [`NodeEvent`]s
+///
+/// Publishers never wait for subscribers: a subscriber that falls more
+/// than [`EVENT_BUS_CAPACITY`] events behind misses the oldest ones and is
+/// told how many with `RecvError::Lagged`.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<NodeEvent>,
+}
+
+impl EventBus {
+    /// Creates a bus with no subscribers
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        Self { sender }
+    }
+
+    /// Registers a subscriber, which receives every event published from
+    /// now on
+    pub fn subscribe(&self) -> broadcast::Receiver<NodeEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Publishes an event to all current subscribers
+    ///
+    /// # Returns
+    /// Number of subscribers the event was delivered to
+    pub fn publish(&self, event: NodeEvent) -> usize {
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    /// Gets the number of registered subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Republishes DAG events on the bus
+    ///
+    /// Forwarding runs on a background thread that exits when the DAG is
+    /// dropped.
+    ///
+    /// # Errors
+    /// - `InitializationError` if the thread cannot be spawned
+    pub fn attach_dag(&self, dag: &DagProcessor) -> Result<()> {
+        let events = dag.subscribe();
+        let bus = self.clone();
+        spawn_forwarder("nyx-events-dag", move || {
+            for event in events {
+                bus.publish(match event {
+                    DagEvent::TransactionAdded { hash, tx } => NodeEvent::TransactionAccepted { hash, tx },
+                    DagEvent::TransactionConfirmed { hash } => NodeEvent::TransactionConfirmed { hash },
+                    DagEvent::TransactionFinalized { hash } => NodeEvent::TransactionFinalized { hash },
+                });
+            }
+        })
+    }
+
+    /// Republishes applied snapshot headers on the bus
+    ///
+    /// Forwarding runs on a background thread that exits when the tracker
+    /// is dropped.
+    ///
+    /// # Errors
+    /// - `InitializationError` if the thread cannot be spawned
+    pub fn attach_finality(&self, finality: &FinalityTracker) -> Result<()> {
+        let headers = finality.subscribe();
+        let bus = self.clone();
+        spawn_forwarder("nyx-events-finality", move || {
+            for header in headers {
+                bus.publish(NodeEvent::SnapshotProduced { header });
+            }
+        })
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs `forward` on a named background thread
+fn spawn_forwarder(name: &str, forward: impl FnOnce() + Send + 'static) -> Result<()> {
+    std::thread::Builder::new()
+        .name(name.to_string())
+        .spawn(forward)
+        .map(|_| ())
+        .map_err(|e| NodeError::InitializationError(format!("Failed to spawn {}: {}", name, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_core::storage::MemoryStorage;
+    use nyx_core::transaction::TxOutput;
+    use nyx_core::NetworkId;
+    use std::time::Duration;
+
+    async fn next(events: &mut broadcast::Receiver<NodeEvent>) -> NodeEvent {
+        tokio::time::timeout(Duration::from_secs(5), events.recv()).await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_bus_carries_dag_and_snapshot_events() {
+        let dag = DagProcessor::new(MemoryStorage::new()).with_network(NetworkId::Regtest);
+        let finality = FinalityTracker::new(dag.clone());
+        let bus = EventBus::new();
+        bus.attach_dag(&dag).unwrap();
+        bus.attach_finality(&finality).unwrap();
+        let mut events = bus.subscribe();
+
+        let hash = dag.mint(vec![TxOutput {
+            stealth_address: vec![1u8; 32],
+            amount_commitment: vec![],
+            range_proof: vec![],
+            ephemeral_pubkey: vec![],
+            unlock_time: None,
+            htlc: None,
+        }]).unwrap();
+        finality.apply_next_snapshot(vec![hash]).unwrap();
+
+        let mut accepted = false;
+        let mut finalized = false;
+        let mut snapshot = None;
+        while !(accepted && finalized && snapshot.is_some()) {
+            match next(&mut events).await {
+                NodeEvent::TransactionAccepted { hash: h, tx } => {
+                    assert_eq!((h, tx.id()), (hash, hash));
+                    accepted = true;
+                }
+                NodeEvent::TransactionFinalized { hash: h } => {
+                    assert_eq!(h, hash);
+                    finalized = true;
+                }
+                NodeEvent::SnapshotProduced { header } => snapshot = Some(header),
+                NodeEvent::TransactionConfirmed { .. } => {}
+                NodeEvent::PeerBanned { .. } => unreachable!(),
+            }
+        }
+        assert_eq!(snapshot.unwrap().number, 1);
+    }
+
+    #[tokio::test]
+    async fn test_publish_reaches_every_subscriber() {
+        let bus = EventBus::new();
+        assert_eq!(bus.publish(NodeEvent::TransactionFinalized { hash: [1u8; 32] }), 0);
+
+        let mut first = bus.subscribe();
+        let mut second = bus.subscribe();
+        assert_eq!(bus.subscriber_count(), 2);
+
+        let banned = NodeEvent::PeerBanned { peer_id: vec![7], reason: "invalid handshake".to_string() };
+        assert_eq!(bus.publish(banned), 2);
+        for events in [&mut first, &mut second] {
+            assert!(matches!(next(events).await, NodeEvent::PeerBanned { reason, .. } if reason == "invalid handshake"));
+        }
+    }
+}
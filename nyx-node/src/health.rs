@@ -0,0 +1,158 @@
+// src/health.rs
+
+//! Liveness and readiness checks.
+//!
+//! A node is live while its storage answers queries; a load balancer or
+//! orchestrator should restart it otherwise. It is ready to serve traffic
+//! when it is also connected to enough peers, close to the sync target and
+//! receiving fresh snapshots, as set by [`HealthConfig`].
+
+use crate::config::HealthConfig;
+use nyx_network::sync::SyncState;
+use serde::Serialize;
+
+/// Node state observed for a health check
+#[derive(Debug, Clone)]
+pub struct HealthProbe {
+    /// Whether storage answered a query
+    pub storage_ok: bool,
+
+    /// Number of connected peers
+    pub peers: usize,
+
+    /// Current sync state
+    pub sync: SyncState,
+
+    /// Seconds since the latest snapshot was applied, if any was
+    pub last_snapshot_age_secs: Option<u64>,
+}
+
+/// Result of a health check, served by `/health` and `/ready`
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    /// Whether storage answered a query
+    pub storage_ok: bool,
+
+    /// Number of connected peers
+    pub peers: usize,
+
+    /// Whether a sync session is running
+    pub syncing: bool,
+
+    /// Distance between the sync target and the current height
+    pub sync_lag: u64,
+
+    /// Seconds since the latest snapshot was applied, if any was
+    pub last_snapshot_age_secs: Option<u64>,
+
+    /// Readiness checks that failed
+    pub failures: Vec<String>,
+}
+
+impl HealthReport {
+    /// Evaluates a probe against the readiness thresholds
+    pub fn evaluate(probe: HealthProbe, config: &HealthConfig) -> Self {
+        let sync_lag = probe.sync.target_height
+            .map_or(0, |target| target.saturating_sub(probe.sync.current_height));
+
+        let mut failures = Vec::new();
+        if !probe.storage_ok {
+            failures.push("storage unavailable".to_string());
+        }
+        if probe.peers < config.min_peers {
+            failures.push(format!("{} peers connected, need {}", probe.peers, config.min_peers));
+        }
+        if probe.sync.is_syncing && sync_lag > config.max_sync_lag {
+            failures.push(format!("sync lag {} exceeds {}", sync_lag, config.max_sync_lag));
+        }
+        if !probe.sync.live {
+            failures.push("snap sync in progress".to_string());
+        }
+        if let (Some(age), Some(max)) = (probe.last_snapshot_age_secs, config.max_snapshot_age_secs) {
+            if age > max {
+                failures.push(format!("latest snapshot is {}s old, limit is {}s", age, max));
+            }
+        }
+
+        Self {
+            storage_ok: probe.storage_ok,
+            peers: probe.peers,
+            syncing: probe.sync.is_syncing,
+            sync_lag,
+            last_snapshot_age_secs: probe.last_snapshot_age_secs,
+            failures,
+        }
+    }
+
+    /// Whether the node is running and should not be restarted
+    pub fn is_live(&self) -> bool {
+        self.storage_ok
+    }
+
+    /// Whether the node should receive traffic
+    pub fn is_ready(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn healthy_probe() -> HealthProbe {
+        HealthProbe {
+            storage_ok: true,
+            peers: 3,
+            sync: SyncState {
+                current_height: 100,
+                target_height: None,
+                is_syncing: false,
+                synced_count: 0,
+                live: true,
+                session_id: None,
+            },
+            last_snapshot_age_secs: Some(5),
+        }
+    }
+
+    #[test]
+    fn test_healthy_node_is_ready() {
+        let report = HealthReport::evaluate(healthy_probe(), &HealthConfig::default());
+        assert!(report.is_live());
+        assert!(report.is_ready(), "{:?}", report.failures);
+    }
+
+    #[test]
+    fn test_readiness_failures() {
+        let mut probe = healthy_probe();
+        probe.peers = 0;
+        probe.sync.is_syncing = true;
+        probe.sync.target_height = Some(150);
+        probe.last_snapshot_age_secs = Some(3600);
+
+        let report = HealthReport::evaluate(probe, &HealthConfig::default());
+        assert!(report.is_live());
+        assert!(!report.is_ready());
+        assert_eq!(report.sync_lag, 50);
+        assert_eq!(report.failures.len(), 3);
+    }
+
+    #[test]
+    fn test_storage_failure_is_not_live() {
+        let mut probe = healthy_probe();
+        probe.storage_ok = false;
+
+        let report = HealthReport::evaluate(probe, &HealthConfig::default());
+        assert!(!report.is_live());
+        assert!(!report.is_ready());
+    }
+
+    #[test]
+    fn test_snapshot_age_check_can_be_disabled() {
+        let mut probe = healthy_probe();
+        probe.last_snapshot_age_secs = Some(3600);
+        let config = HealthConfig { max_snapshot_age_secs: None, ..HealthConfig::default() };
+
+        assert!(HealthReport::evaluate(probe, &config).is_ready());
+    }
+}
@@ -0,0 +1,207 @@
+// src/idempotency.rs
+
+//! Deduplication of retried RPC requests.
+//!
+//! Clients tag a mutating request with an `Idempotency-Key` header. The
+//! first request with a key runs and, if it succeeds, its response is kept
+//! for a while; retries with the same key and the same request, made with
+//! the same credentials, get that response back instead of running again,
+//! so a retried `/send` never builds a second transaction. A key reused for
+//! a different request is refused.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Longest accepted idempotency key
+pub const MAX_KEY_LEN: usize = 255;
+
+/// Default time a response is replayed
+pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Default number of keys remembered
+pub const DEFAULT_MAX_KEYS: usize = 10_000;
+
+/// Response kept for replay
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredResponse {
+    /// HTTP status code
+    pub status: u16,
+
+    /// `Content-Type` header, if any
+    pub content_type: Option<String>,
+
+    /// Response body
+    pub body: Vec<u8>,
+}
+
+/// What to do with a request carrying an idempotency key
+#[derive(Debug)]
+pub enum Claim {
+    /// First request with the key: run it and complete the guard
+    New(PendingKey),
+
+    /// The same request is still running
+    InProgress,
+
+    /// The key was used for a different request
+    Mismatch,
+
+    /// The same request already ran: replay its response
+    Done(StoredResponse),
+}
+
+struct Entry {
+    fingerprint: [u8; 32],
+    created: Instant,
+    response: Option<StoredResponse>,
+}
+
+/// Responses of recent requests, by idempotency key
+pub struct IdempotencyCache {
+    /// How long a response is replayed
+    ttl: Duration,
+
+    /// Keys kept before the oldest responses are dropped
+    max_keys: usize,
+
+    /// Entry of each key
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl IdempotencyCache {
+    /// Creates a cache replaying responses for `ttl`, keeping at most
+    /// `max_keys` of them
+    pub fn new(ttl: Duration, max_keys: usize) -> Self {
+        Self { ttl, max_keys: max_keys.max(1), entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// Claims `key` for a request whose method, path, credentials and body
+    /// hash to `fingerprint`
+    pub fn begin(self: &Arc<Self>, key: &str, fingerprint: [u8; 32]) -> Claim {
+        self.begin_at(key, fingerprint, Instant::now())
+    }
+
+    fn begin_at(self: &Arc<Self>, key: &str, fingerprint: [u8; 32], now: Instant) -> Claim {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, entry| now.duration_since(entry.created) < self.ttl);
+
+        if let Some(entry) = entries.get(key) {
+            return match &entry.response {
+                _ if entry.fingerprint != fingerprint => Claim::Mismatch,
+                Some(response) => Claim::Done(response.clone()),
+                None => Claim::InProgress,
+            };
+        }
+
+        if entries.len() >= self.max_keys {
+            // Requests still running are never forgotten
+            let oldest = entries.iter()
+                .filter(|(_, entry)| entry.response.is_some())
+                .min_by_key(|(_, entry)| entry.created)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key.to_string(), Entry { fingerprint, created: now, response: None });
+        Claim::New(PendingKey { cache: self.clone(), key: Some(key.to_string()) })
+    }
+
+    /// Number of keys currently kept
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Whether no key is kept
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Claim on a key whose request is running
+///
+/// Dropping it without completing releases the key, so the request can be
+/// retried.
+pub struct PendingKey {
+    cache: Arc<IdempotencyCache>,
+    key: Option<String>,
+}
+
+impl PendingKey {
+    /// Keeps the response for replay
+    pub fn complete(mut self, response: StoredResponse) {
+        if let Some(key) = self.key.take() {
+            let mut entries = self.cache.entries.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(entry) = entries.get_mut(&key) {
+                entry.response = Some(response);
+            }
+        }
+    }
+}
+
+impl Drop for PendingKey {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.cache.entries.lock().unwrap_or_else(|e| e.into_inner()).remove(&key);
+        }
+    }
+}
+
+impl std::fmt::Debug for PendingKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingKey").field("key", &self.key).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(body: &str) -> StoredResponse {
+        StoredResponse { status: 200, content_type: None, body: body.as_bytes().to_vec() }
+    }
+
+    #[test]
+    fn test_retries_replay_the_first_response() {
+        let cache = Arc::new(IdempotencyCache::new(Duration::from_secs(60), 10));
+        let now = Instant::now();
+
+        let Claim::New(pending) = cache.begin_at("key", [1; 32], now) else { panic!("key is new") };
+        assert!(matches!(cache.begin_at("key", [1; 32], now), Claim::InProgress));
+        assert!(matches!(cache.begin_at("key", [2; 32], now), Claim::Mismatch));
+
+        pending.complete(response("sent"));
+        assert!(matches!(cache.begin_at("key", [1; 32], now), Claim::Done(r) if r == response("sent")));
+        assert!(matches!(cache.begin_at("key", [2; 32], now), Claim::Mismatch));
+
+        // Responses expire
+        assert!(matches!(cache.begin_at("key", [1; 32], now + Duration::from_secs(60)), Claim::New(_)));
+    }
+
+    #[test]
+    fn test_abandoned_keys_are_released() {
+        let cache = Arc::new(IdempotencyCache::new(Duration::from_secs(60), 10));
+        let now = Instant::now();
+
+        let Claim::New(pending) = cache.begin_at("key", [1; 32], now) else { panic!("key is new") };
+        drop(pending);
+        assert!(cache.is_empty());
+        assert!(matches!(cache.begin_at("key", [1; 32], now), Claim::New(_)));
+    }
+
+    #[test]
+    fn test_oldest_responses_are_dropped_when_full() {
+        let cache = Arc::new(IdempotencyCache::new(Duration::from_secs(60), 2));
+        let now = Instant::now();
+
+        let Claim::New(first) = cache.begin_at("first", [1; 32], now) else { panic!("key is new") };
+        first.complete(response("first"));
+        let Claim::New(running) = cache.begin_at("running", [2; 32], now) else { panic!("key is new") };
+        let Claim::New(_third) = cache.begin_at("third", [3; 32], now + Duration::from_secs(1)) else { panic!("key is new") };
+
+        assert!(matches!(cache.begin_at("first", [1; 32], now), Claim::New(_)));
+        assert!(matches!(cache.begin_at("running", [2; 32], now), Claim::InProgress));
+        drop(running);
+    }
+}
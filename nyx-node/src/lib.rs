@@ -29,20 +29,55 @@
 
 pub mod errors;
 pub mod config;
+pub mod data_dir;
+pub mod double_spend;
+pub mod events;
+pub mod health;
+pub mod idempotency;
+pub mod logging;
 pub mod mempool;
+pub mod metrics;
+pub mod plugin;
+pub mod priority;
+pub mod quota;
+pub mod rate_limit;
+pub mod rebroadcast;
 pub mod rpc;
+pub mod settings;
+pub mod snapshot_file;
+pub mod update;
+pub mod verifier;
+pub mod webhook;
 pub mod node;
 
 // Re-export commonly used types
 pub use crate::errors::{NodeError, Result};
-pub use crate::config::NodeConfig;
-pub use crate::mempool::Mempool;
+pub use crate::config::{NodeConfig, UpdateConfig};
+pub use crate::data_dir::{DataDir, DataDirLayout, DiskUsage};
+pub use crate::double_spend::{DoubleSpendAlert, DoubleSpendMonitor};
+pub use crate::events::{EventBus, NodeEvent};
+pub use crate::health::HealthReport;
+pub use crate::idempotency::IdempotencyCache;
+pub use crate::mempool::{Mempool, PendingTransaction};
+pub use crate::plugin::{NodePlugin, PluginContext, PluginRegistry};
+pub use crate::priority::Prioritizer;
+pub use crate::quota::{Origin, SourceQuotas};
+pub use crate::rate_limit::RateLimiter;
+pub use crate::rebroadcast::Rebroadcaster;
 pub use crate::rpc::RpcServer;
+pub use crate::settings::{RuntimeSettings, SettingsUpdate};
+pub use crate::snapshot_file::{SnapshotDownloader, SnapshotFile};
+pub use crate::update::{UpdateStatus, Updater};
+pub use crate::verifier::VerifierPool;
+pub use crate::webhook::WebhookDispatcher;
 pub use crate::node::NyxNode;
 
 /// Node version
 pub const NODE_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Node events buffered per event bus subscriber before the oldest are dropped
+pub const EVENT_BUS_CAPACITY: usize = 4096;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,149 @@
+// src/logging.rs
+
+//! Log output setup.
+//!
+//! Installs the global tracing subscriber described by [`LoggingConfig`].
+//! The JSON format includes the fields of the current span and all of its
+//! parents, so `peer_id`, `tx_id` and `session_id` from
+//! [`nyx_network::trace`] appear on every line logged inside those spans.
+//!
+//! The filter can be replaced at runtime through the returned [`LogHandle`].
+
+use crate::config::{LogFormat, LoggingConfig};
+use crate::errors::{NodeError, Result};
+use crate::settings::RuntimeSettings;
+use tokio::sync::watch;
+use tracing::Subscriber;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
+
+/// Handle for replacing the filter of an installed subscriber
+#[derive(Clone)]
+pub struct LogHandle {
+    filter: reload::Handle<EnvFilter, Registry>,
+}
+
+impl LogHandle {
+    /// Replaces the filter directives
+    ///
+    /// # Errors
+    /// - `ConfigError` if the directives do not parse
+    pub fn set_filter(&self, directives: &str) -> Result<()> {
+        let filter = parse_filter(directives)?;
+        self.filter.reload(filter)
+            .map_err(|e| NodeError::ConfigError(format!("Failed to reload log filter: {}", e)))
+    }
+
+    /// Applies the log filter of every settings change until the sender
+    /// is dropped
+    pub fn follow(self, mut settings: watch::Receiver<RuntimeSettings>) -> tokio::task::JoinHandle<()> {
+        settings.mark_unchanged();
+        tokio::spawn(async move {
+            while settings.changed().await.is_ok() {
+                let directives = settings.borrow_and_update().log_filter.clone();
+                if let Err(e) = self.set_filter(&directives) {
+                    tracing::warn!("Keeping previous log filter: {}", e);
+                }
+            }
+        })
+    }
+}
+
+/// Installs the global subscriber, writing to stdout
+///
+/// `RUST_LOG` overrides the configured filter when set.
+pub fn init(config: &LoggingConfig) -> Result<LogHandle> {
+    let directives = std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_else(|_| config.filter.clone());
+    let (subscriber, handle) = build(config.format, &directives, std::io::stdout)?;
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|e| NodeError::InitializationError(format!("Failed to install logger: {}", e)))?;
+    Ok(handle)
+}
+
+/// Builds a subscriber with the given format and filter directives
+pub fn build<W>(
+    format: LogFormat,
+    directives: &str,
+    writer: W,
+) -> Result<(Box<dyn Subscriber + Send + Sync>, LogHandle)>
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    let (filter, handle) = reload::Layer::new(parse_filter(directives)?);
+    let output = tracing_subscriber::fmt::layer().with_writer(writer);
+    let output = match format {
+        LogFormat::Text => output.boxed(),
+        LogFormat::Json => output.json().with_current_span(true).with_span_list(true).boxed(),
+    };
+
+    let subscriber = Registry::default().with(filter).with(output);
+    Ok((Box::new(subscriber), LogHandle { filter: handle }))
+}
+
+/// Parses filter directives such as `info,nyx_network=debug`
+pub fn parse_filter(directives: &str) -> Result<EnvFilter> {
+    EnvFilter::try_new(directives)
+        .map_err(|e| NodeError::ConfigError(format!("Invalid log filter '{}': {}", directives, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_lines_carry_tx_id() {
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let (subscriber, _) = build(LogFormat::Json, "info", move || writer.clone()).unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = nyx_network::trace::tx_span(&[0xAB; 32]).entered();
+            tracing::info!("accepted");
+        });
+
+        let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        let line: serde_json::Value = serde_json::from_str(output.lines().next().unwrap()).unwrap();
+        assert_eq!(line["span"]["tx_id"], hex::encode([0xAB; 32]));
+        assert_eq!(line["fields"]["message"], "accepted");
+    }
+
+    #[test]
+    fn test_filter_reload() {
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let (subscriber, handle) = build(LogFormat::Text, "warn", move || writer.clone()).unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("hidden");
+            handle.set_filter("info").unwrap();
+            tracing::info!("shown");
+            assert!(handle.set_filter("nyx=notalevel").is_err());
+        });
+
+        let output = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        assert!(!output.contains("hidden"));
+        assert!(output.contains("shown"));
+    }
+
+    #[test]
+    fn test_invalid_filter_rejected() {
+        assert!(build(LogFormat::Text, "nyx=notalevel", std::io::sink).is_err());
+    }
+}
@@ -1,24 +1,79 @@
 // src/main.rs
 
 //! Nyx node binary entry point.
+//!
+//! Runs the node, or with `verify-db [--repair]` re-verifies the DAG the
+//! node loads and exits, non-zero if problems remain. `du` reports the
+//! disk space used by each component of the data directory.
 
-use nyx_node::{NodeConfig, NyxNode};
-use tracing_subscriber;
+use nyx_node::{DataDir, NodeConfig, NyxNode, PluginRegistry};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Load or create configuration
+    let config = NodeConfig::default();
+
     // Initialize logging
-    tracing_subscriber::fmt::init();
+    let log = nyx_node::logging::init(&config.logging)?;
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("verify-db") {
+        let repair = args.iter().any(|arg| arg == "--repair");
+        return verify_db(config, repair).await;
+    }
+    if args.first().map(String::as_str) == Some("du") {
+        return disk_usage(&config);
+    }
 
     tracing::info!("🚀 Nyx Blockchain Node");
     tracing::info!("Version: {}", nyx_node::NODE_VERSION);
 
-    // Load or create configuration
-    let config = NodeConfig::default();
+    // Plugins compiled into this binary; `config.plugins` picks which run
+    let plugins = PluginRegistry::new();
 
     // Create and start node
-    let node = NyxNode::new(config).await?;
+    let node = NyxNode::with_plugins(config, &plugins).await?;
+    log.follow(node.subscribe_settings());
     node.start().await?;
 
     Ok(())
 }
+
+/// Re-verifies stored transactions and DAG indexes without starting the node
+async fn verify_db(config: NodeConfig, repair: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let node = NyxNode::new(config).await?;
+    let report = node.verify_db(repair).await?;
+
+    for issue in &report.issues {
+        println!("{}", issue);
+    }
+    println!(
+        "Checked {} transactions: {} issues, {} repaired",
+        report.checked,
+        report.issues.len(),
+        report.repaired
+    );
+
+    if report.unrepaired() > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Prints the disk space used by each component of the data directory
+fn disk_usage(config: &NodeConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let data_dir = DataDir::new(&config.data_dir, config.layout.clone());
+    let usage = data_dir.disk_usage()?;
+
+    for (component, bytes, path) in [
+        ("db", usage.db, data_dir.db_dir()),
+        ("keystore", usage.keystore, data_dir.keystore_dir()),
+        ("peers", usage.peers, data_dir.peers_file()),
+        ("snapshots", usage.snapshots, data_dir.snapshots_dir()),
+        ("other", usage.other, data_dir.root().to_path_buf()),
+    ] {
+        println!("{:<10} {:>14}  {}", component, bytes, path.display());
+    }
+    println!("{:<10} {:>14}", "total", usage.total());
+    Ok(())
+}
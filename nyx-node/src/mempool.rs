@@ -1,21 +1,77 @@
 // src/mempool.rs
 
 //! Transaction mempool for pending transactions.
+//!
+//! Pending transactions are ranked by a [`Prioritizer`]: once the mempool is
+//! full, a new transaction only gets in by evicting a less useful one.
+//! Transactions relayed by peers also count against the [`SourceQuotas`]
+//! of their peer.
+//!
+//! Pending transactions are held behind an [`Arc`]; with the [`TxCache`]
+//! of storage, one already in the DAG is not held twice.
 
+use crate::priority::{AgePrioritizer, Prioritizer};
+use crate::quota::{Origin, SourceQuotas};
+use crate::NodeError;
 use nyx_core::transaction::Transaction;
-use nyx_core::Hash;
+use nyx_core::{DecodeLimits, Hash, TxCache};
+use nyx_network::RejectCode;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, RwLock};
+
+/// A pending transaction
+struct Entry {
+    tx: Arc<Transaction>,
+
+    /// Encoded size in bytes
+    size: usize,
+
+    /// When the transaction was admitted
+    added: Instant,
+
+    /// When the transaction was admitted or last rebroadcast
+    last_broadcast: Instant,
+}
+
+/// Summary of a pending transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingTransaction {
+    /// Transaction hash
+    pub tx_hash: Hash,
+
+    /// Encoded size in bytes
+    pub size: usize,
+
+    /// Time since the transaction was admitted
+    pub age: Duration,
+}
 
 /// Transaction mempool
 #[derive(Clone)]
 pub struct Mempool {
     /// Pending transactions
-    transactions: Arc<RwLock<HashMap<Hash, Transaction>>>,
+    transactions: Arc<RwLock<HashMap<Hash, Entry>>>,
+
+    /// Maximum mempool size, shared by clones
+    max_size: Arc<AtomicUsize>,
+
+    /// Size and count limits of accepted transactions
+    limits: DecodeLimits,
+
+    /// Newly admitted transactions, for subscribers
+    admitted: broadcast::Sender<Transaction>,
 
-    /// Maximum mempool size
-    max_size: usize,
+    /// Ranks pending transactions
+    prioritizer: Arc<dyn Prioritizer>,
+
+    /// Admission quotas per source; `None` admits without limit
+    quotas: Option<Arc<SourceQuotas>>,
+
+    /// Cache admitted transactions are shared through
+    cache: Option<TxCache>,
 }
 
 impl Mempool {
@@ -23,49 +79,202 @@ impl Mempool {
     pub fn new(max_size: usize) -> Self {
         Self {
             transactions: Arc::new(RwLock::new(HashMap::new())),
-            max_size,
+            max_size: Arc::new(AtomicUsize::new(max_size)),
+            limits: DecodeLimits::default(),
+            admitted: broadcast::channel(crate::EVENT_BUS_CAPACITY).0,
+            prioritizer: Arc::new(AgePrioritizer),
+            quotas: None,
+            cache: None,
         }
     }
 
-    /// Adds a transaction to the mempool
+    /// Subscribes to transactions admitted from now on
+    ///
+    /// A subscriber that falls behind misses the oldest admissions.
+    pub fn subscribe(&self) -> broadcast::Receiver<Transaction> {
+        self.admitted.subscribe()
+    }
+
+    /// Sets the transaction limits, normally those of the network's
+    /// protocol parameters
+    pub fn with_limits(mut self, limits: DecodeLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets how pending transactions are ranked; oldest first by default
+    pub fn with_prioritizer(mut self, prioritizer: impl Prioritizer + 'static) -> Self {
+        self.prioritizer = Arc::new(prioritizer);
+        self
+    }
+
+    /// Limits how many transactions each peer gets admitted
+    pub fn with_quotas(mut self, quotas: SourceQuotas) -> Self {
+        self.quotas = Some(Arc::new(quotas));
+        self
+    }
+
+    /// Reuses transactions already in `cache`, normally the one of the
+    /// DAG's storage
+    pub fn with_tx_cache(mut self, cache: TxCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Gets the maximum mempool size
+    pub fn max_size(&self) -> usize {
+        self.max_size.load(Ordering::Relaxed)
+    }
+
+    /// Changes the maximum mempool size
+    ///
+    /// Transactions already pending are kept if the new limit is lower;
+    /// only new ones are refused until the mempool drains below it.
+    pub fn set_max_size(&self, max_size: usize) {
+        self.max_size.store(max_size, Ordering::Relaxed);
+    }
+
+    /// Adds a locally submitted transaction to the mempool
     pub async fn add_transaction(&self, tx: Transaction) -> crate::Result<Hash> {
+        self.add_transaction_from(tx, Origin::Local).await
+    }
+
+    /// Adds a transaction from `origin` to the mempool
+    ///
+    /// # Errors
+    /// - `MempoolError` if the transaction exceeds the size limits
+    /// - `Rejected` with `QuotaExceeded` if `origin` used up its quota
+    /// - `Rejected` with `MempoolFull` if every pending transaction is at
+    ///   least as useful
+    pub async fn add_transaction_from(&self, tx: Transaction, origin: Origin) -> crate::Result<Hash> {
         let tx_id = tx.id();
+        self.limits.check_transaction(&tx)
+            .map_err(|e| NodeError::MempoolError(format!("Transaction rejected: {}", e)))?;
 
         let mut txs = self.transactions.write().await;
-
-        // Check if mempool is full
-        if txs.len() >= self.max_size {
-            return Err(crate::NodeError::MempoolError(
-                "Mempool is full".to_string()
-            ));
-        }
+        let _span = nyx_network::trace::tx_span(&tx_id).entered();
 
         // Check if transaction already exists
         if txs.contains_key(&tx_id) {
             return Ok(tx_id); // Already in mempool
         }
 
-        txs.insert(tx_id, tx);
+        if self.quotas.as_ref().is_some_and(|quotas| !quotas.allows(&origin)) {
+            tracing::debug!("Source over quota, dropping transaction");
+            return Err(NodeError::Rejected(RejectCode::QuotaExceeded));
+        }
+
+        // A full mempool only makes room for a more useful transaction
+        if txs.len() >= self.max_size() {
+            let now = Instant::now();
+            let priority = self.prioritizer.priority(&tx_id, &tx, Duration::ZERO);
+            let lowest = txs.iter()
+                .map(|(hash, entry)| (*hash, self.entry_priority(hash, entry, now)))
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+
+            match lowest {
+                Some((evicted, lowest)) if txs.len() == self.max_size() && priority > lowest => {
+                    txs.remove(&evicted);
+                    tracing::debug!(evicted = %hex::encode(evicted), "Mempool full, evicted lower priority transaction");
+                }
+                _ => {
+                    tracing::warn!("Mempool full, dropping transaction");
+                    return Err(NodeError::Rejected(RejectCode::MempoolFull));
+                }
+            }
+        }
+
+        if let Some(quotas) = &self.quotas {
+            quotas.record(&origin);
+        }
+        let size = nyx_core::encoding::encode_transaction(&tx).len();
+        let now = Instant::now();
+        let _ = self.admitted.send(tx.clone());
+        let tx = match &self.cache {
+            Some(cache) => cache.share(tx),
+            None => Arc::new(tx),
+        };
+        txs.insert(tx_id, Entry { tx, size, added: now, last_broadcast: now });
+        tracing::debug!(size = txs.len(), "Added transaction to mempool");
 
         Ok(tx_id)
     }
 
     /// Gets a transaction from the mempool
     pub async fn get_transaction(&self, tx_id: &Hash) -> Option<Transaction> {
+        self.get_transaction_shared(tx_id).await.map(Arc::unwrap_or_clone)
+    }
+
+    /// Gets a transaction from the mempool without copying it
+    pub async fn get_transaction_shared(&self, tx_id: &Hash) -> Option<Arc<Transaction>> {
         let txs = self.transactions.read().await;
-        txs.get(tx_id).cloned()
+        txs.get(tx_id).map(|entry| entry.tx.clone())
     }
 
     /// Removes a transaction from the mempool
     pub async fn remove_transaction(&self, tx_id: &Hash) -> Option<Transaction> {
         let mut txs = self.transactions.write().await;
-        txs.remove(tx_id)
+        txs.remove(tx_id).map(|entry| Arc::unwrap_or_clone(entry.tx))
     }
 
     /// Gets all transactions in the mempool
     pub async fn get_all_transactions(&self) -> Vec<Transaction> {
         let txs = self.transactions.read().await;
-        txs.values().cloned().collect()
+        txs.values().map(|entry| Transaction::clone(&entry.tx)).collect()
+    }
+
+    /// Summaries of the pending transactions, oldest first
+    pub async fn pending(&self) -> Vec<PendingTransaction> {
+        let txs = self.transactions.read().await;
+        let now = Instant::now();
+        let mut pending: Vec<PendingTransaction> = txs.iter()
+            .map(|(tx_hash, entry)| PendingTransaction {
+                tx_hash: *tx_hash,
+                size: entry.size,
+                age: now.duration_since(entry.added),
+            })
+            .collect();
+        pending.sort_by(|a, b| b.age.cmp(&a.age).then(a.tx_hash.cmp(&b.tx_hash)));
+        pending
+    }
+
+    /// Pending transactions, highest priority first
+    pub async fn by_priority(&self) -> Vec<Transaction> {
+        let txs = self.transactions.read().await;
+        let now = Instant::now();
+        let mut ranked: Vec<(f64, &Hash, &Entry)> = txs.iter()
+            .map(|(hash, entry)| (self.entry_priority(hash, entry, now), hash, entry))
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(b.1)));
+        ranked.into_iter().map(|(_, _, entry)| Transaction::clone(&entry.tx)).collect()
+    }
+
+    /// Takes the transactions last broadcast at least `after` ago, restarting
+    /// their timer
+    ///
+    /// The caller is expected to broadcast them again, in the returned
+    /// order: highest priority first.
+    pub async fn due_for_rebroadcast(&self, after: Duration) -> Vec<Transaction> {
+        let mut txs = self.transactions.write().await;
+        let now = Instant::now();
+        let mut due: Vec<(f64, Hash)> = txs.iter()
+            .filter(|(_, entry)| now.duration_since(entry.last_broadcast) >= after)
+            .map(|(hash, entry)| (self.entry_priority(hash, entry, now), *hash))
+            .collect();
+        due.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        let mut taken = Vec::with_capacity(due.len());
+        for (_, hash) in due {
+            if let Some(entry) = txs.get_mut(&hash) {
+                entry.last_broadcast = now;
+                taken.push(Transaction::clone(&entry.tx));
+            }
+        }
+        taken
+    }
+
+    fn entry_priority(&self, tx_hash: &Hash, entry: &Entry, now: Instant) -> f64 {
+        self.prioritizer.priority(tx_hash, &entry.tx, now.duration_since(entry.added))
     }
 
     /// Gets mempool size
@@ -84,38 +293,12 @@ impl Mempool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use nyx_core::transaction::{TxInput, TxOutput};
-    use nyx_crypto::ring::RingSignature;
-
-    fn create_test_tx(nonce: u8) -> Transaction {
-        Transaction::new(
-            vec![TxInput {
-                prev_tx: [nonce; 32],
-                index: 0,
-                key_image: [nonce; 32],
-                ring_indices: vec![],
-            }],
-            vec![TxOutput {
-                stealth_address: vec![nonce],
-                amount_commitment: vec![],
-                range_proof: vec![],
-                ephemeral_pubkey: vec![],
-            }],
-            RingSignature {
-                ring_members: vec![],
-                signature: vec![nonce],
-                key_image: [nonce; 32],
-            },
-            vec![nonce],
-            [0u8; 32],
-            [1u8; 32],
-        )
-    }
+    use nyx_core::testing::TestTx;
 
     #[tokio::test]
     async fn test_mempool_add() {
         let mempool = Mempool::new(100);
-        let tx = create_test_tx(1);
+        let tx = TestTx::new(1).build();
 
         let tx_id = mempool.add_transaction(tx).await.unwrap();
         assert_eq!(mempool.size().await, 1);
@@ -124,10 +307,21 @@ mod tests {
         assert!(retrieved.is_some());
     }
 
+    #[tokio::test]
+    async fn test_stored_transaction_is_shared() {
+        let storage = nyx_core::storage::MemoryStorage::new();
+        let mempool = Mempool::new(100).with_tx_cache(storage.tx_cache().clone());
+        let tx_id = storage.store_transaction(TestTx::new(1).build()).unwrap();
+
+        mempool.add_transaction(TestTx::new(1).build()).await.unwrap();
+        let pending = mempool.get_transaction_shared(&tx_id).await.unwrap();
+        assert!(Arc::ptr_eq(&pending, &storage.get_transaction_shared(&tx_id).unwrap()));
+    }
+
     #[tokio::test]
     async fn test_mempool_remove() {
         let mempool = Mempool::new(100);
-        let tx = create_test_tx(1);
+        let tx = TestTx::new(1).build();
 
         let tx_id = mempool.add_transaction(tx).await.unwrap();
         assert_eq!(mempool.size().await, 1);
@@ -140,10 +334,105 @@ mod tests {
     async fn test_mempool_full() {
         let mempool = Mempool::new(2);
 
-        mempool.add_transaction(create_test_tx(1)).await.unwrap();
-        mempool.add_transaction(create_test_tx(2)).await.unwrap();
+        mempool.add_transaction(TestTx::new(1).build()).await.unwrap();
+        mempool.add_transaction(TestTx::new(2).build()).await.unwrap();
+
+        let result = mempool.add_transaction(TestTx::new(3).build()).await;
+        assert!(matches!(result, Err(NodeError::Rejected(RejectCode::MempoolFull))));
+    }
+
+    #[tokio::test]
+    async fn test_peer_quota() {
+        let mempool = Mempool::new(10).with_quotas(SourceQuotas::new(1, Duration::from_secs(60)));
+        let peer = Origin::Peer(vec![7]);
+
+        let first = mempool.add_transaction_from(TestTx::new(1).build(), peer.clone()).await.unwrap();
+        // Duplicates do not count
+        assert_eq!(mempool.add_transaction_from(TestTx::new(1).build(), peer.clone()).await.unwrap(), first);
+
+        let over = mempool.add_transaction_from(TestTx::new(2).build(), peer).await;
+        assert_eq!(over.unwrap_err().reject_code(), RejectCode::QuotaExceeded);
+        mempool.add_transaction_from(TestTx::new(2).build(), Origin::Peer(vec![8])).await.unwrap();
+        mempool.add_transaction(TestTx::new(3).build()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mempool_resize() {
+        let mempool = Mempool::new(1);
+        mempool.add_transaction(TestTx::new(1).build()).await.unwrap();
+        assert!(mempool.add_transaction(TestTx::new(2).build()).await.is_err());
+
+        mempool.clone().set_max_size(2);
+        assert_eq!(mempool.max_size(), 2);
+        mempool.add_transaction(TestTx::new(2).build()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mempool_enforces_limits() {
+        let tx = TestTx::new(1).build();
+        let size = nyx_core::encoding::encode_transaction(&tx).len();
+
+        let mempool = Mempool::new(10).with_limits(DecodeLimits { max_tx_size: size - 1, ..DecodeLimits::default() });
+        assert!(mempool.add_transaction(tx.clone()).await.is_err());
+
+        let mempool = Mempool::new(10).with_limits(DecodeLimits { max_tx_size: size, ..DecodeLimits::default() });
+        assert!(mempool.add_transaction(tx).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_pending_lists_oldest_first() {
+        let mempool = Mempool::new(10);
+        let first = mempool.add_transaction(TestTx::new(1).build()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let second = mempool.add_transaction(TestTx::new(2).build()).await.unwrap();
+
+        let pending = mempool.pending().await;
+        assert_eq!(pending.iter().map(|p| p.tx_hash).collect::<Vec<_>>(), vec![first, second]);
+        assert_eq!(pending[0].size, nyx_core::encoding::encode_transaction(&TestTx::new(1).build()).len());
+        assert!(pending[0].age > pending[1].age);
+    }
+
+    /// Ranks transactions by the first byte of their extra data
+    struct ExtraPrioritizer;
+
+    impl Prioritizer for ExtraPrioritizer {
+        fn priority(&self, _tx_hash: &Hash, tx: &Transaction, _age: Duration) -> f64 {
+            f64::from(tx.extra.first().copied().unwrap_or(0))
+        }
+    }
+
+    fn tx_with_extra(nonce: u8, extra: u8) -> Transaction {
+        let mut tx = TestTx::new(nonce).build();
+        tx.extra = vec![extra];
+        tx
+    }
+
+    #[tokio::test]
+    async fn test_full_mempool_evicts_lowest_priority() {
+        let mempool = Mempool::new(2).with_prioritizer(ExtraPrioritizer);
+        let low = mempool.add_transaction(tx_with_extra(1, 1)).await.unwrap();
+        let high = mempool.add_transaction(tx_with_extra(2, 5)).await.unwrap();
+
+        // Not more useful than anything pending
+        assert!(mempool.add_transaction(tx_with_extra(3, 1)).await.is_err());
+
+        let higher = mempool.add_transaction(tx_with_extra(4, 3)).await.unwrap();
+        assert!(mempool.get_transaction(&low).await.is_none());
+        assert_eq!(mempool.size().await, 2);
+
+        let ranked: Vec<Hash> = mempool.by_priority().await.iter().map(Transaction::id).collect();
+        assert_eq!(ranked, vec![high, higher]);
+        let due: Vec<Hash> = mempool.due_for_rebroadcast(Duration::ZERO).await.iter().map(Transaction::id).collect();
+        assert_eq!(due, ranked);
+    }
+
+    #[tokio::test]
+    async fn test_due_for_rebroadcast_restarts_timer() {
+        let mempool = Mempool::new(10);
+        mempool.add_transaction(TestTx::new(1).build()).await.unwrap();
 
-        let result = mempool.add_transaction(create_test_tx(3)).await;
-        assert!(result.is_err());
+        assert!(mempool.due_for_rebroadcast(Duration::from_secs(60)).await.is_empty());
+        assert_eq!(mempool.due_for_rebroadcast(Duration::ZERO).await.len(), 1);
+        assert!(mempool.due_for_rebroadcast(Duration::from_secs(60)).await.is_empty());
     }
 }
@@ -0,0 +1,92 @@
+// src/metrics.rs
+
+//! Prometheus text exposition of node metrics.
+//!
+//! Served at `/metrics` so operators can scrape throughput, confirmation
+//! latency, tips and score percentiles alongside the mempool and peer
+//! counts. The DAG figures come from [`DagStats`], which the DAG maintains
+//! incrementally.
+
+use nyx_core::dag::DagStats;
+use std::fmt::Write;
+
+/// Node-level values exported next to the DAG statistics
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NodeGauges {
+    /// Pending transactions in the mempool
+    pub mempool_size: usize,
+
+    /// Connected peers
+    pub peers: usize,
+}
+
+/// Renders the metrics in the Prometheus text format
+pub fn render(stats: &DagStats, node: NodeGauges) -> String {
+    let mut out = String::new();
+
+    gauge(&mut out, "nyx_dag_transactions", "Transactions in the DAG by state", &[
+        ("state=\"pending\"", stats.pending_transactions as f64),
+        ("state=\"confirmed\"", stats.confirmed_transactions as f64),
+        ("state=\"finalized\"", stats.finalized_transactions as f64),
+    ]);
+    gauge(&mut out, "nyx_dag_tips", "Current number of DAG tips", &[("", stats.current_tips as f64)]);
+    gauge(&mut out, "nyx_dag_tps", "Transactions added per second over the metrics window", &[("", stats.tps)]);
+    if let Some(latency) = stats.avg_confirmation_latency_secs {
+        gauge(&mut out, "nyx_dag_confirmation_latency_seconds",
+            "Mean seconds from added to confirmed over the metrics window", &[("", latency)]);
+    }
+
+    let scores = &stats.score_percentiles;
+    gauge(&mut out, "nyx_dag_score", "Approximate confirmation score percentiles", &[
+        ("quantile=\"0.5\"", scores.p50),
+        ("quantile=\"0.9\"", scores.p90),
+        ("quantile=\"0.99\"", scores.p99),
+        ("quantile=\"1\"", scores.max),
+    ]);
+
+    gauge(&mut out, "nyx_mempool_size", "Pending transactions in the mempool", &[("", node.mempool_size as f64)]);
+    gauge(&mut out, "nyx_peers", "Connected peers", &[("", node.peers as f64)]);
+
+    out
+}
+
+/// Appends a gauge with one sample per label set
+fn gauge(out: &mut String, name: &str, help: &str, samples: &[(&str, f64)]) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            let _ = writeln!(out, "{} {}", name, value);
+        } else {
+            let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_core::metrics::ScorePercentiles;
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let stats = DagStats {
+            total_transactions: 6,
+            pending_transactions: 3,
+            confirmed_transactions: 2,
+            finalized_transactions: 1,
+            current_tips: 2,
+            tps: 3000.5,
+            avg_confirmation_latency_secs: None,
+            tip_history: Vec::new(),
+            score_percentiles: ScorePercentiles { p50: 1.0, p90: 4.0, p99: 8.0, max: 9.0 },
+        };
+
+        let text = render(&stats, NodeGauges { mempool_size: 4, peers: 8 });
+        assert!(text.contains("# TYPE nyx_dag_tps gauge\nnyx_dag_tps 3000.5\n"));
+        assert!(text.contains("nyx_dag_transactions{state=\"pending\"} 3\n"));
+        assert!(text.contains("nyx_dag_score{quantile=\"0.99\"} 8\n"));
+        assert!(text.contains("nyx_peers 8\n"));
+        assert!(!text.contains("confirmation_latency"));
+    }
+}
@@ -2,22 +2,45 @@
 
 //! Main node orchestration.
 
-use crate::{NodeConfig, Mempool, RpcServer, Result};
+use crate::events::{EventBus, NodeEvent};
+use crate::health::{HealthProbe, HealthReport};
+use crate::plugin::{NodePlugin, PluginContext, PluginHost, PluginRegistry};
+use crate::priority::{AgePrioritizer, PowPrioritizer, TipApprovalPrioritizer, WeightedPrioritizer};
+use crate::quota::SourceQuotas;
+use crate::settings::{RuntimeSettings, SettingsUpdate};
+use crate::snapshot_file::SnapshotDownloader;
+use crate::update::{SharedUpdateStatus, UpdateStatus, Updater};
+use crate::{DataDir, DiskUsage, DoubleSpendMonitor, NodeConfig, Mempool, Rebroadcaster, RpcServer, Result, VerifierPool, WebhookDispatcher};
+use nyx_core::checkpoint::Checkpoint;
+use nyx_core::consensus::ValidatorSet;
 use nyx_core::storage::MemoryStorage;
+use nyx_core::tx_cache::TxCache;
 use nyx_core::dag::DagProcessor;
-use nyx_network::{Node as NetworkNode, NodeConfig as NetConfig};
-use nyx_wallet::Wallet;
+use nyx_core::finality::{FinalityTracker, NodeMode};
+use nyx_core::integrity::{self, IntegrityReport};
+use nyx_indexer::{ExplorerApi, IndexStore, Indexer, OpenOptions};
+use nyx_network::node::PeerStats;
+use nyx_network::sync::SyncState;
+use nyx_network::{Node as NetworkNode, NodeConfig as NetConfig, NodeIdentity};
+use nyx_wallet::{RingMember, Wallet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::info;
+use tokio::sync::{watch, RwLock};
+use tracing::{info, warn};
+
+/// Number of most recently confirmed outputs the wallet draws ring decoys
+/// from
+const DECOY_POOL_SIZE: u64 = 256;
 
 /// Main Nyx blockchain node
 pub struct NyxNode {
     /// Node configuration
     config: NodeConfig,
 
-    /// DAG processor
-    dag: Arc<RwLock<DagProcessor>>,
+    /// Where persistent state is kept
+    data_dir: DataDir,
+
+    /// DAG processor (internally synchronized, cheap to clone)
+    dag: DagProcessor,
 
     /// Network node
     network: Arc<RwLock<NetworkNode>>,
@@ -27,17 +50,99 @@ pub struct NyxNode {
 
     /// Built-in wallet
     wallet: Option<Wallet>,
+
+    /// Explorer read API over the DAG index
+    explorer: ExplorerApi,
+
+    /// PoS finality, snapshot headers and inclusion proofs
+    finality: FinalityTracker,
+
+    /// Runtime-adjustable settings, followed by live components
+    settings: watch::Sender<RuntimeSettings>,
+
+    /// Typed stream of DAG, finality and peer events
+    events: EventBus,
+
+    /// Conflicting spends seen in the DAG or mempool
+    double_spends: DoubleSpendMonitor,
+
+    /// Configured plugins, started with the node
+    plugins: Vec<Box<dyn NodePlugin>>,
+
+    /// Latest check for upgrades
+    update_status: SharedUpdateStatus,
 }
 
 impl NyxNode {
-    /// Creates a new Nyx node
+    /// Creates a new Nyx node without plugins
+    ///
+    /// # Errors
+    /// - `ConfigError` if the configuration lists any plugins
     pub async fn new(config: NodeConfig) -> Result<Self> {
-        info!("Initializing Nyx node...");
+        Self::with_plugins(config, &PluginRegistry::new()).await
+    }
+
+    /// Creates a new Nyx node running the configured plugins from `registry`
+    ///
+    /// # Errors
+    /// - `ConfigError` if a configured plugin is not in `registry`
+    pub async fn with_plugins(config: NodeConfig, registry: &PluginRegistry) -> Result<Self> {
+        info!("Initializing Nyx node on {}...", config.network_id);
+
+        if let Some(release) = crate::update::verify_release(&config.update)? {
+            info!("Running verified release {}", release.version);
+        }
+        let update_status = match Updater::new(&config.update, config.network_id)? {
+            Some(updater) => {
+                let status = updater.status();
+                updater.spawn();
+                status
+            }
+            None => Arc::new(std::sync::RwLock::new(UpdateStatus::current())),
+        };
+
+        let plugins = registry.load(&config.plugins)?;
+
+        let genesis = config.load_genesis()?;
+
+        let data_dir = DataDir::new(&config.data_dir, config.layout.clone());
+        data_dir.create()?;
 
         // Initialize storage and DAG
-        let storage = MemoryStorage::new();
-        let dag = DagProcessor::new(storage);
-        let dag = Arc::new(RwLock::new(dag));
+        let tx_cache = TxCache::new(config.dag.tx_cache_capacity);
+        let storage = MemoryStorage::new().with_tx_cache(tx_cache.clone());
+        let dag = DagProcessor::new(storage).with_genesis(&genesis);
+
+        // Index the DAG for explorer queries
+        let index = if config.index.persistent {
+            let options = OpenOptions { backup_before_migrate: config.index.backup_before_migrate };
+            IndexStore::open_with(data_dir.index_file(), options)?
+        } else {
+            IndexStore::in_memory()?
+        };
+        let index = Arc::new(index);
+        Indexer::new(index.clone()).spawn(&dag)?;
+        let explorer = ExplorerApi::new(index);
+
+        let mut finality = FinalityTracker::new(dag.clone());
+        if let Some(keep) = config.dag.retained_snapshots() {
+            info!("Pruned mode, keeping the last {} snapshots", keep);
+            finality = finality.with_retained_snapshots(keep);
+        }
+
+        let events = EventBus::new();
+        events.attach_dag(&dag)?;
+        events.attach_finality(&finality)?;
+
+        let webhooks = WebhookDispatcher::new(&config.webhooks, config.network_id)?;
+        if !webhooks.is_empty() {
+            info!("Watching {} webhook addresses", webhooks.len());
+            webhooks.spawn(events.subscribe());
+        }
+
+        if config.network_id.instant_finality() {
+            finality.spawn_instant_finality()?;
+        }
 
         // Initialize network
         let net_config = NetConfig {
@@ -45,18 +150,62 @@ impl NyxNode {
             max_peers: config.network.max_peers,
             min_peers: 8,
             bootstrap_peers: config.network.bootstrap_peers.clone(),
-            node_id: vec![0u8; 32],
+            dns_seeds: config.network.dns_seeds.clone(),
+            use_fallback_seeds: config.network.use_fallback_seeds,
+            network: config.network_id,
+            params: genesis.params.clone(),
+            identity: Some(NodeIdentity::load_or_generate_file(&data_dir.node_key_file())?),
+            data_dir: Some(config.data_dir.clone()),
+            address_book_file: Some(data_dir.peers_file()),
+            idle_timeout: std::time::Duration::from_secs(config.network.idle_timeout_secs),
+            sign_messages: config.network.sign_messages,
+            require_signed_messages: config.network.require_signed_messages,
+            bandwidth: config.network.bandwidth,
+            retained_snapshots: config.dag.retained_snapshots(),
+            ..NetConfig::default()
         };
 
         let network = NetworkNode::new(net_config).await?;
+        network.set_gossip_fanout(config.network.gossip_fanout);
+
+        // Initialize mempool, fed by transactions from peers once verified
+        let priority = &config.mempool.priority;
+        let prioritizer = WeightedPrioritizer::new()
+            .with(priority.age_weight, AgePrioritizer)
+            .with(priority.pow_weight, PowPrioritizer)
+            .with(
+                priority.tip_weight,
+                TipApprovalPrioritizer::new(dag.clone()).with_min_approvers(priority.min_approvers),
+            );
+        let mut mempool = Mempool::new(config.mempool.max_size)
+            .with_limits(genesis.params.limits)
+            .with_prioritizer(prioritizer)
+            .with_tx_cache(tx_cache);
+        let quota = &config.mempool.peer_quota;
+        if quota.max_transactions > 0 {
+            let window = std::time::Duration::from_secs(quota.window_secs);
+            mempool = mempool.with_quotas(SourceQuotas::new(quota.max_transactions, window));
+        }
+        VerifierPool::new(mempool.clone(), config.mempool.verify_workers)
+            .with_network(network.clone())
+            .spawn(network.subscribe_transactions());
+
+        let double_spends = DoubleSpendMonitor::new(dag.clone());
+        double_spends.clone().spawn(events.subscribe(), mempool.subscribe());
+
         let network = Arc::new(RwLock::new(network));
 
-        // Initialize mempool
-        let mempool = Mempool::new(1000);
+        if config.mempool.rebroadcast_after_secs > 0 {
+            let after = std::time::Duration::from_secs(config.mempool.rebroadcast_after_secs);
+            Rebroadcaster::new(mempool.clone(), dag.clone(), network.clone(), after).spawn();
+        }
+
+        let (settings, _) = watch::channel(RuntimeSettings::from_config(&config));
+        spawn_settings_follower(settings.subscribe(), network.clone(), mempool.clone());
 
         // Initialize wallet if enabled
         let wallet = if config.wallet.enabled {
-            let mut wallet = Wallet::with_default_account();
+            let mut wallet = Wallet::for_network(config.network_id).with_generated_account();
             // Mock: add some balance for testing
             wallet.scan_outputs().ok();
             Some(wallet)
@@ -68,23 +217,45 @@ impl NyxNode {
 
         Ok(Self {
             config,
+            data_dir,
             dag,
             network,
             mempool,
             wallet,
+            explorer,
+            finality,
+            settings,
+            events,
+            double_spends,
+            plugins,
+            update_status,
         })
     }
 
-    /// Starts the node
-    pub async fn start(self) -> Result<()> {
+    /// Starts the node and runs it until Ctrl-C
+    pub async fn start(mut self) -> Result<()> {
         info!("🚀 Starting Nyx node...");
 
+        let plugins = std::mem::take(&mut self.plugins);
+        let mut plugin_host = PluginHost::start(plugins, &self.plugin_context())?;
+
         let node_arc = Arc::new(RwLock::new(self));
 
         // Start RPC server if enabled
         if node_arc.read().await.config.rpc.enabled {
-            let rpc_addr = node_arc.read().await.config.rpc.listen_addr;
-            let rpc_server = RpcServer::new(rpc_addr, node_arc.clone());
+            let rpc_config = node_arc.read().await.config.rpc.clone();
+            let rpc_server = RpcServer::new(rpc_config.listen_addr, node_arc.clone())
+                .with_admin_token(rpc_config.admin_token)
+                .with_limits(rpc_config.limits)
+                .with_cors_origins(rpc_config.cors_origins)
+                .with_trusted_proxies(rpc_config.trusted_proxies)
+                .with_base_path(rpc_config.base_path)
+                .with_snapshot_file(rpc_config.serve_snapshot_file)
+                .with_idempotency(
+                    std::time::Duration::from_secs(rpc_config.idempotency_ttl_secs),
+                    rpc_config.idempotency_max_keys,
+                )
+                .with_plugin_routes(plugin_host.take_routes());
 
             tokio::spawn(async move {
                 if let Err(e) = rpc_server.start().await {
@@ -103,12 +274,103 @@ impl NyxNode {
 
         info!("✅ Nyx node started successfully");
 
-        // Keep running
-        loop {
-            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        tokio::signal::ctrl_c().await?;
+
+        info!("Shutting down Nyx node...");
+        plugin_host.shutdown().await;
+
+        Ok(())
+    }
+
+    /// Gets the node components handed to plugins
+    pub fn plugin_context(&self) -> PluginContext {
+        PluginContext {
+            network_id: self.config.network_id,
+            dag: self.dag.clone(),
+            finality: self.finality.clone(),
+            mempool: self.mempool.clone(),
+            events: self.events.clone(),
         }
     }
 
+    /// Gets a handle to the DAG processor
+    pub fn dag(&self) -> DagProcessor {
+        self.dag.clone()
+    }
+
+    /// Gets the explorer read API
+    pub fn explorer(&self) -> &ExplorerApi {
+        &self.explorer
+    }
+
+    /// Gets the finality tracker
+    pub fn finality(&self) -> &FinalityTracker {
+        &self.finality
+    }
+
+    /// Gets whether the node keeps full history
+    pub fn mode(&self) -> NodeMode {
+        self.finality.mode()
+    }
+
+    /// Gets the node event bus
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    /// Gets the double-spend monitor
+    pub fn double_spends(&self) -> &DoubleSpendMonitor {
+        &self.double_spends
+    }
+
+    /// Gets the latest check for upgrades
+    pub fn update_status(&self) -> UpdateStatus {
+        self.update_status.read().expect("update status lock").clone()
+    }
+
+    /// Subscribes to node events
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<NodeEvent> {
+        self.events.subscribe()
+    }
+
+    /// Gets the current runtime settings
+    pub fn settings(&self) -> RuntimeSettings {
+        self.settings.borrow().clone()
+    }
+
+    /// Subscribes to runtime settings changes
+    pub fn subscribe_settings(&self) -> watch::Receiver<RuntimeSettings> {
+        self.settings.subscribe()
+    }
+
+    /// Validates and publishes a settings change to live components
+    ///
+    /// # Returns
+    /// The settings now in effect
+    pub fn update_settings(&self, update: SettingsUpdate) -> Result<RuntimeSettings> {
+        // Applied under the channel's lock so concurrent updates don't race
+        let mut result = Ok(self.settings());
+        self.settings.send_if_modified(|current| {
+            result = current.apply(update);
+            match &result {
+                Ok(settings) if settings != current => {
+                    *current = settings.clone();
+                    true
+                }
+                _ => false,
+            }
+        });
+
+        let settings = result?;
+        info!("Runtime settings updated: {:?}", settings);
+        Ok(settings)
+    }
+
+    /// Gets the mempool
+    pub fn mempool(&self) -> &Mempool {
+        &self.mempool
+    }
+
     /// Gets mempool size
     pub async fn mempool_size(&self) -> usize {
         self.mempool.size().await
@@ -116,14 +378,101 @@ impl NyxNode {
 
     /// Checks if node is syncing
     pub async fn is_syncing(&self) -> bool {
-        // TODO: Check actual sync state
-        false
+        self.network_stats().await.sync.is_syncing
+    }
+
+    /// Checks storage, peer connectivity, sync lag and snapshot age
+    pub async fn health(&self) -> HealthReport {
+        let stats = self.network_stats().await;
+        let probe = HealthProbe {
+            storage_ok: self.dag.storage().transaction_count().is_ok(),
+            peers: stats.peer_count,
+            sync: stats.sync,
+            last_snapshot_age_secs: self.finality.latest_snapshot_age(),
+        };
+
+        HealthReport::evaluate(probe, &self.config.health)
+    }
+
+    /// Re-verifies stored transactions and the DAG indexes built from them
+    ///
+    /// Runs on the blocking pool, as it re-checks every signature. With
+    /// `repair`, broken child links and tips are rebuilt from storage.
+    ///
+    /// # Errors
+    /// - `CoreError` if storage or the DAG cannot be read
+    pub async fn verify_db(&self, repair: bool) -> Result<IntegrityReport> {
+        let dag = self.dag.clone();
+        let report = tokio::task::spawn_blocking(move || integrity::verify_dag(&dag, repair))
+            .await
+            .map_err(|e| crate::NodeError::CoreError(format!("Integrity check failed: {}", e)))??;
+
+        for issue in &report.issues {
+            warn!("Integrity: {}", issue);
+        }
+        info!(
+            "Verified {} transactions: {} issues, {} repaired",
+            report.checked,
+            report.issues.len(),
+            report.repaired
+        );
+        Ok(report)
+    }
+
+    /// Gets the data directory
+    pub fn data_dir(&self) -> &DataDir {
+        &self.data_dir
+    }
+
+    /// Measures the disk space used by each component of the data directory
+    ///
+    /// # Errors
+    /// - `IoError` if the data directory cannot be read
+    pub async fn disk_usage(&self) -> Result<DiskUsage> {
+        let data_dir = self.data_dir.clone();
+        tokio::task::spawn_blocking(move || data_dir.disk_usage())
+            .await
+            .map_err(|e| crate::NodeError::IoError(std::io::Error::other(e)))?
+    }
+
+    /// Fast-syncs from a snapshot file served over HTTP, e.g. by another
+    /// node's `/snapshots/latest/file` endpoint
+    ///
+    /// The download resumes from any partial file left in the data
+    /// directory. The checkpoint is verified against `validators` before it
+    /// replaces the empty finalized state.
+    ///
+    /// # Errors
+    /// - `NetworkError` if the download fails
+    /// - `CoreError` if the file is malformed, fails verification, or the
+    ///   node already holds transactions
+    ///
+    /// # Returns
+    /// Number of transactions restored
+    pub async fn snap_sync_from_url(&self, url: &str, validators: &ValidatorSet) -> Result<usize> {
+        let bytes = SnapshotDownloader::new(url, self.data_dir.snapshot_part_file())?.download().await?;
+
+        let checkpoint = Checkpoint::from_bytes(&bytes)?;
+        let number = checkpoint.number();
+        let restored = self.finality.restore_checkpoint(checkpoint, validators)?;
+
+        info!("Restored {} transactions from snapshot {} at {}", restored, number, url);
+        Ok(restored)
+    }
+
+    /// Gets a handle to the network node, for peer management
+    pub async fn network(&self) -> NetworkNode {
+        self.network.read().await.clone()
     }
 
     /// Gets network statistics
-    pub fn network_stats(&self) -> NetworkStats {
+    pub async fn network_stats(&self) -> NetworkStats {
+        let stats = self.network.read().await.stats().await;
+
         NetworkStats {
-            peer_count: 0, // TODO: Get from network
+            peer_count: stats.peer_count,
+            peers: stats.peers,
+            sync: stats.sync_state,
         }
     }
 
@@ -134,18 +483,232 @@ impl NyxNode {
             .unwrap_or(0)
     }
 
+    /// Mints `count` outputs of `amount` each to the wallet's active account
+    ///
+    /// Only available on regtest, where the minting transaction is confirmed
+    /// and finalized as soon as it is added.
+    ///
+    /// # Returns
+    /// Hash of the minting transaction
+    pub async fn generate(&mut self, count: u32, amount: u64) -> Result<nyx_core::Hash> {
+        if count == 0 {
+            return Err(crate::NodeError::CoreError("Nothing to generate".to_string()));
+        }
+        let wallet = self.wallet.as_mut()
+            .ok_or_else(|| crate::NodeError::WalletError("Wallet not enabled".to_string()))?;
+
+        let outputs = (0..count)
+            .map(|_| wallet.minted_output(amount))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let stealth_addresses: Vec<Vec<u8>> = outputs.iter()
+            .map(|output| output.stealth_address.clone())
+            .collect();
+        let tx_hash = self.dag.mint(outputs)?;
+        self.dag.finalize_transactions(&[tx_hash])?;
+        let first = self.dag.storage().first_output_index(&tx_hash)?
+            .ok_or_else(|| crate::NodeError::CoreError("Minted outputs were not indexed".to_string()))?;
+
+        for (index, stealth_address) in (0..count).zip(stealth_addresses) {
+            wallet.credit_minted(tx_hash, index, amount)?;
+            let member = RingMember { global_index: first + u64::from(index), stealth_address };
+            wallet.confirm_output(tx_hash, index, member)?;
+        }
+
+        let _span = nyx_network::trace::tx_span(&tx_hash).entered();
+        info!("Generated {} outputs of {} on {}", count, amount, self.config.network_id);
+
+        Ok(tx_hash)
+    }
+
     /// Sends transaction
-    pub async fn send(&self, to: String, amount: u64) -> Result<nyx_core::Hash> {
-        let wallet = self.wallet.as_ref()
+    pub async fn send(&mut self, to: String, amount: u64) -> Result<nyx_core::Hash> {
+        let wallet = self.wallet.as_mut()
             .ok_or_else(|| crate::NodeError::WalletError("Wallet not enabled".to_string()))?;
 
+        // Rings draw their decoys from the most recently confirmed outputs
+        let storage = self.dag.storage();
+        let count = storage.output_count()?;
+        let decoys = (count.saturating_sub(DECOY_POOL_SIZE)..count)
+            .map(|global_index| Ok(RingMember {
+                global_index,
+                stealth_address: storage.get_output_by_index(global_index)?.stealth_address,
+            }))
+            .collect::<nyx_core::Result<Vec<_>>>()?;
+        wallet.set_decoys(decoys);
+
         wallet.send(&to, amount)
             .map_err(|e| e.into())
     }
 }
 
+/// Applies network and mempool settings changes until the sender is dropped
+fn spawn_settings_follower(
+    mut settings: watch::Receiver<RuntimeSettings>,
+    network: Arc<RwLock<NetworkNode>>,
+    mempool: Mempool,
+) {
+    tokio::spawn(async move {
+        while settings.changed().await.is_ok() {
+            let current = settings.borrow_and_update().clone();
+
+            let network = network.read().await;
+            network.set_max_peers(current.max_peers).await;
+            network.set_gossip_fanout(current.gossip_fanout);
+            mempool.set_max_size(current.mempool_max_size);
+        }
+    });
+}
+
 /// Network statistics
 pub struct NetworkStats {
     /// Number of connected peers
     pub peer_count: usize,
+
+    /// Latency and heartbeat state of each connected peer
+    pub peers: Vec<PeerStats>,
+
+    /// Sync progress of the network layer
+    pub sync: SyncState,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regtest_config(dir: &tempfile::TempDir) -> NodeConfig {
+        let mut config = NodeConfig::regtest();
+        config.network.listen_addr = "127.0.0.1:0".parse().unwrap();
+        config.data_dir = dir.path().to_path_buf();
+        config
+    }
+
+    #[tokio::test]
+    async fn test_generate_funds_wallet_on_regtest() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut node = NyxNode::new(regtest_config(&dir)).await.unwrap();
+        let before = node.get_balance().await;
+
+        let tx_hash = node.generate(3, 500).await.unwrap();
+        assert_eq!(node.get_balance().await, before + 1500);
+        assert_eq!(node.dag().storage().get_transaction(&tx_hash).unwrap().outputs.len(), 3);
+        assert!(node.finality().wait_for_finality(tx_hash).await > 0);
+    }
+
+    #[tokio::test]
+    async fn test_persistent_index_is_opened_in_data_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = regtest_config(&dir);
+        config.index.persistent = true;
+        let node = NyxNode::new(config).await.unwrap();
+        let index_file = node.data_dir().index_file();
+        assert!(index_file.starts_with(dir.path().join("db")));
+        drop(node);
+
+        let index = IndexStore::open(index_file).unwrap();
+        assert_eq!(index.schema_version().unwrap(), nyx_indexer::SCHEMA_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_node_key_is_kept_in_keystore() {
+        let dir = tempfile::tempdir().unwrap();
+        let node = NyxNode::new(regtest_config(&dir)).await.unwrap();
+        let peer_id = node.network().await.peer_id();
+        assert!(node.data_dir().node_key_file().is_file());
+        assert!(node.disk_usage().await.unwrap().keystore > 0);
+        drop(node);
+
+        let node = NyxNode::new(regtest_config(&dir)).await.unwrap();
+        assert_eq!(node.network().await.peer_id(), peer_id);
+    }
+
+    #[tokio::test]
+    async fn test_generated_transaction_reaches_event_subscribers() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut node = NyxNode::new(regtest_config(&dir)).await.unwrap();
+        let mut events = node.subscribe_events();
+
+        let tx_hash = node.generate(1, 100).await.unwrap();
+
+        // Instant finality on regtest produces a snapshot for every transaction
+        let mut seen = (false, false, false);
+        while seen != (true, true, true) {
+            let event = tokio::time::timeout(std::time::Duration::from_secs(5), events.recv())
+                .await.unwrap().unwrap();
+            match event {
+                NodeEvent::TransactionAccepted { hash, .. } if hash == tx_hash => seen.0 = true,
+                NodeEvent::TransactionFinalized { hash } if hash == tx_hash => seen.1 = true,
+                NodeEvent::SnapshotProduced { .. } => seen.2 = true,
+                _ => {}
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unknown_plugin_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = regtest_config(&dir);
+        config.plugins.push(crate::config::PluginConfig {
+            name: "webhook".to_string(),
+            settings: serde_json::Value::Null,
+        });
+
+        let result = NyxNode::new(config).await;
+        assert!(matches!(result, Err(crate::NodeError::ConfigError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_regtest_node_is_ready_without_peers() {
+        let dir = tempfile::tempdir().unwrap();
+        let node = NyxNode::new(regtest_config(&dir)).await.unwrap();
+
+        let report = node.health().await;
+        assert!(report.is_live());
+        assert!(report.is_ready(), "{:?}", report.failures);
+    }
+
+    #[tokio::test]
+    async fn test_isolated_node_is_not_ready() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = regtest_config(&dir);
+        config.health = crate::config::HealthConfig::default();
+
+        let report = NyxNode::new(config).await.unwrap().health().await;
+        assert!(report.is_live());
+        assert!(!report.is_ready());
+        assert_eq!(report.peers, 0);
+    }
+
+    #[tokio::test]
+    async fn test_settings_reach_live_components() {
+        let dir = tempfile::tempdir().unwrap();
+        let node = NyxNode::new(regtest_config(&dir)).await.unwrap();
+        let mut settings = node.subscribe_settings();
+
+        let update = SettingsUpdate { mempool_max_size: Some(5), gossip_fanout: Some(Some(3)), ..Default::default() };
+        let applied = node.update_settings(update).unwrap();
+        assert_eq!(node.settings(), applied);
+        settings.changed().await.unwrap();
+
+        for _ in 0..100 {
+            if node.mempool.max_size() == 5 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(node.mempool.max_size(), 5);
+
+        let invalid = SettingsUpdate { max_peers: Some(0), ..Default::default() };
+        assert!(node.update_settings(invalid).is_err());
+        assert_eq!(node.settings(), applied);
+    }
+
+    #[tokio::test]
+    async fn test_generate_rejected_off_regtest() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = regtest_config(&dir);
+        config.network_id = nyx_core::NetworkId::Testnet;
+
+        let mut node = NyxNode::new(config).await.unwrap();
+        assert!(node.generate(1, 500).await.is_err());
+    }
 }
@@ -0,0 +1,296 @@
+// src/plugin.rs
+
+//! Compiled-in node plugins.
+//!
+//! Add-ons such as an explorer indexer or a webhook notifier implement
+//! [`NodePlugin`] in their own crate and export a [`PluginFactory`]. The
+//! binary registers the factories it was built with in a
+//! [`PluginRegistry`], and `NodeConfig::plugins` chooses which of them run
+//! and with what settings.
+//!
+//! Each running plugin gets its own task fed from the node [`EventBus`], so
+//! a slow plugin only falls behind on its own subscription.
+
+use crate::config::PluginConfig;
+use crate::errors::{NodeError, Result};
+use crate::events::{EventBus, NodeEvent};
+use crate::mempool::Mempool;
+use axum::Router;
+use nyx_core::dag::DagProcessor;
+use nyx_core::finality::FinalityTracker;
+use nyx_core::NetworkId;
+use std::collections::HashMap;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Node add-on driven by node events
+pub trait NodePlugin: Send + Sync + 'static {
+    /// Unique name, also the RPC mount point `/plugins/<name>`
+    fn name(&self) -> &str;
+
+    /// Called once before the node starts serving
+    ///
+    /// # Errors
+    /// Any error aborts node startup.
+    fn on_start(&mut self, ctx: &PluginContext) -> Result<()> {
+        let _ = ctx;
+        Ok(())
+    }
+
+    /// Called for every node event, in publication order
+    fn on_event(&mut self, event: &NodeEvent);
+
+    /// Called once when the node shuts down
+    fn on_shutdown(&mut self) {}
+
+    /// RPC routes served under `/plugins/<name>`, if any
+    fn routes(&self) -> Option<Router> {
+        None
+    }
+}
+
+/// Creates a plugin from its `settings` in the node configuration
+pub type PluginFactory = fn(&serde_json::Value) -> Result<Box<dyn NodePlugin>>;
+
+/// Node components available to plugins at startup
+#[derive(Clone)]
+pub struct PluginContext {
+    /// Network the node runs on
+    pub network_id: NetworkId,
+
+    /// DAG processor
+    pub dag: DagProcessor,
+
+    /// PoS finality tracker
+    pub finality: FinalityTracker,
+
+    /// Mempool
+    pub mempool: Mempool,
+
+    /// Node event bus, for plugins that publish events of their own
+    pub events: EventBus,
+}
+
+/// Plugins compiled into the node binary, by name
+#[derive(Clone, Default)]
+pub struct PluginRegistry {
+    factories: HashMap<String, PluginFactory>,
+}
+
+impl PluginRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a plugin factory under `name`, replacing any previous one
+    pub fn with_plugin(mut self, name: &str, factory: PluginFactory) -> Self {
+        self.factories.insert(name.to_string(), factory);
+        self
+    }
+
+    /// Gets the names of the registered plugins, sorted
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.factories.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Creates the configured plugins, in configuration order
+    ///
+    /// # Errors
+    /// - `ConfigError` if a plugin is not registered or is configured twice
+    /// - any error returned by a plugin factory
+    pub fn load(&self, configs: &[PluginConfig]) -> Result<Vec<Box<dyn NodePlugin>>> {
+        let mut plugins: Vec<Box<dyn NodePlugin>> = Vec::with_capacity(configs.len());
+        for config in configs {
+            let factory = self.factories.get(&config.name).ok_or_else(|| {
+                NodeError::ConfigError(format!("Unknown plugin: {}", config.name))
+            })?;
+            if plugins.iter().any(|p| p.name() == config.name) {
+                return Err(NodeError::ConfigError(format!("Plugin configured twice: {}", config.name)));
+            }
+            plugins.push(factory(&config.settings)?);
+        }
+        Ok(plugins)
+    }
+}
+
+/// Running plugins
+pub struct PluginHost {
+    /// Tells plugin tasks to shut down
+    shutdown: watch::Sender<bool>,
+
+    /// One task per plugin
+    tasks: Vec<JoinHandle<()>>,
+
+    /// RPC routes of the plugins, by mount point
+    routes: Vec<(String, Router)>,
+}
+
+impl PluginHost {
+    /// Starts `plugins`, each on its own task subscribed to `ctx.events`
+    ///
+    /// # Errors
+    /// Returns the first `on_start` error; plugins started before it are
+    /// shut down again.
+    pub fn start(plugins: Vec<Box<dyn NodePlugin>>, ctx: &PluginContext) -> Result<Self> {
+        let (shutdown, _) = watch::channel(false);
+        let mut host = Self { shutdown, tasks: Vec::new(), routes: Vec::new() };
+
+        for mut plugin in plugins {
+            if let Err(e) = plugin.on_start(ctx) {
+                let _ = host.shutdown.send(true);
+                return Err(e);
+            }
+            info!("Started plugin {}", plugin.name());
+
+            if let Some(router) = plugin.routes() {
+                host.routes.push((format!("/plugins/{}", plugin.name()), router));
+            }
+
+            let events = ctx.events.subscribe();
+            let shutdown = host.shutdown.subscribe();
+            host.tasks.push(tokio::spawn(run_plugin(plugin, events, shutdown)));
+        }
+
+        Ok(host)
+    }
+
+    /// Gets the number of running plugins
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Checks whether no plugins are running
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Takes the plugins' RPC routes, by mount point
+    pub fn take_routes(&mut self) -> Vec<(String, Router)> {
+        std::mem::take(&mut self.routes)
+    }
+
+    /// Shuts every plugin down and waits for their tasks
+    pub async fn shutdown(self) {
+        let _ = self.shutdown.send(true);
+        for task in self.tasks {
+            let _ = task.await;
+        }
+    }
+}
+
+/// Feeds events to a plugin until shutdown or the bus closes
+async fn run_plugin(
+    mut plugin: Box<dyn NodePlugin>,
+    mut events: tokio::sync::broadcast::Receiver<NodeEvent>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => plugin.on_event(&event),
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("Plugin {} fell behind, missed {} events", plugin.name(), missed);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            _ = shutdown.changed() => break,
+        }
+    }
+
+    plugin.on_shutdown();
+    info!("Stopped plugin {}", plugin.name());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_core::storage::MemoryStorage;
+    use std::sync::{Arc, Mutex};
+
+    /// Records the calls it receives in a shared log
+    struct Recorder {
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl NodePlugin for Recorder {
+        fn name(&self) -> &str {
+            "recorder"
+        }
+
+        fn on_start(&mut self, ctx: &PluginContext) -> Result<()> {
+            self.log.lock().unwrap().push(format!("start {}", ctx.network_id));
+            Ok(())
+        }
+
+        fn on_event(&mut self, event: &NodeEvent) {
+            if let NodeEvent::TransactionFinalized { hash } = event {
+                self.log.lock().unwrap().push(format!("finalized {}", hash[0]));
+            }
+        }
+
+        fn on_shutdown(&mut self) {
+            self.log.lock().unwrap().push("shutdown".to_string());
+        }
+
+        fn routes(&self) -> Option<Router> {
+            Some(Router::new().route("/ping", axum::routing::get(|| async { "pong" })))
+        }
+    }
+
+    fn recorder(_settings: &serde_json::Value) -> Result<Box<dyn NodePlugin>> {
+        Ok(Box::new(Recorder { log: Arc::default() }))
+    }
+
+    fn context() -> PluginContext {
+        let dag = DagProcessor::new(MemoryStorage::new()).with_network(NetworkId::Regtest);
+        PluginContext {
+            network_id: NetworkId::Regtest,
+            finality: FinalityTracker::new(dag.clone()),
+            dag,
+            mempool: Mempool::new(10),
+            events: EventBus::new(),
+        }
+    }
+
+    fn config(name: &str) -> PluginConfig {
+        PluginConfig { name: name.to_string(), settings: serde_json::Value::Null }
+    }
+
+    #[test]
+    fn test_load_rejects_unknown_and_duplicate_plugins() {
+        let registry = PluginRegistry::new().with_plugin("recorder", recorder);
+        assert_eq!(registry.names(), vec!["recorder"]);
+
+        assert_eq!(registry.load(&[config("recorder")]).unwrap().len(), 1);
+        assert!(matches!(registry.load(&[config("webhook")]), Err(NodeError::ConfigError(_))));
+        assert!(matches!(
+            registry.load(&[config("recorder"), config("recorder")]),
+            Err(NodeError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_plugin_lifecycle() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let ctx = context();
+
+        let mut host = PluginHost::start(vec![Box::new(Recorder { log: log.clone() })], &ctx).unwrap();
+        assert_eq!(host.len(), 1);
+        let routes = host.take_routes();
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].0, "/plugins/recorder");
+
+        ctx.events.publish(NodeEvent::TransactionFinalized { hash: [7u8; 32] });
+        while log.lock().unwrap().len() < 2 {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        host.shutdown().await;
+
+        assert_eq!(*log.lock().unwrap(), vec!["start regtest", "finalized 7", "shutdown"]);
+    }
+}
@@ -0,0 +1,211 @@
+// src/priority.rs
+
+//! Mempool prioritization.
+//!
+//! Layer 1 has no fees to rank pending transactions by, so the mempool asks
+//! a [`Prioritizer`] how useful each one is to the DAG instead. A full
+//! mempool evicts its least useful transaction for a more useful one, and
+//! rebroadcasts go out most useful first.
+//!
+//! The built-in prioritizers score:
+//!
+//! - [`AgePrioritizer`]: time spent pending, so nothing starves
+//! - [`PowPrioritizer`]: work the sender ground into the transaction hash
+//! - [`TipApprovalPrioritizer`]: references to tips that few transactions
+//!   approve yet, which keeps the tip set from widening
+//!
+//! [`WeightedPrioritizer`] sums any of them.
+
+use nyx_core::dag::DagProcessor;
+use nyx_core::transaction::Transaction;
+use nyx_core::Hash;
+use std::time::Duration;
+
+/// Ranks pending transactions; higher is processed first
+pub trait Prioritizer: Send + Sync {
+    /// Priority of `tx`, pending for `age`
+    ///
+    /// Called again whenever transactions are ranked, so the result may
+    /// change as the transaction ages or the DAG moves on.
+    fn priority(&self, tx_hash: &Hash, tx: &Transaction, age: Duration) -> f64;
+}
+
+/// Number of leading zero bits of a transaction hash
+///
+/// Senders raise it by grinding the `extra` field before broadcasting.
+pub fn pow_difficulty(tx_hash: &Hash) -> u32 {
+    let mut bits = 0;
+    for byte in tx_hash {
+        bits += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+/// One point per minute pending
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AgePrioritizer;
+
+impl Prioritizer for AgePrioritizer {
+    fn priority(&self, _tx_hash: &Hash, _tx: &Transaction, age: Duration) -> f64 {
+        age.as_secs_f64() / 60.0
+    }
+}
+
+/// One point per leading zero bit of the transaction hash
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PowPrioritizer;
+
+impl Prioritizer for PowPrioritizer {
+    fn priority(&self, tx_hash: &Hash, _tx: &Transaction, _age: Duration) -> f64 {
+        f64::from(pow_difficulty(tx_hash))
+    }
+}
+
+/// One point per reference to an under-approved transaction
+///
+/// A referenced transaction is under-approved while fewer than
+/// `min_approvers` transactions in the DAG approve it; with the default of
+/// one, that is a tip. Unknown references score nothing.
+#[derive(Clone)]
+pub struct TipApprovalPrioritizer {
+    dag: DagProcessor,
+    min_approvers: usize,
+}
+
+impl TipApprovalPrioritizer {
+    /// Creates a prioritizer favouring transactions that approve tips of `dag`
+    pub fn new(dag: DagProcessor) -> Self {
+        Self { dag, min_approvers: 1 }
+    }
+
+    /// Sets how many approvers a transaction needs to stop counting
+    pub fn with_min_approvers(mut self, min_approvers: usize) -> Self {
+        self.min_approvers = min_approvers;
+        self
+    }
+}
+
+impl Prioritizer for TipApprovalPrioritizer {
+    fn priority(&self, _tx_hash: &Hash, tx: &Transaction, _age: Duration) -> f64 {
+        let mut references = tx.references.to_vec();
+        references.dedup();
+
+        references.iter()
+            .filter(|parent| self.dag.storage().has_transaction(parent).unwrap_or(false))
+            .filter(|parent| self.dag.get_children(parent).is_ok_and(|c| c.len() < self.min_approvers))
+            .count() as f64
+    }
+}
+
+/// Weighted sum of other prioritizers
+#[derive(Default)]
+pub struct WeightedPrioritizer {
+    parts: Vec<(f64, Box<dyn Prioritizer>)>,
+}
+
+impl WeightedPrioritizer {
+    /// Creates a prioritizer ranking every transaction equally
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `prioritizer`, its priority multiplied by `weight`
+    ///
+    /// A zero weight leaves it out.
+    pub fn with(mut self, weight: f64, prioritizer: impl Prioritizer + 'static) -> Self {
+        if weight != 0.0 {
+            self.parts.push((weight, Box::new(prioritizer)));
+        }
+        self
+    }
+}
+
+impl Prioritizer for WeightedPrioritizer {
+    fn priority(&self, tx_hash: &Hash, tx: &Transaction, age: Duration) -> f64 {
+        self.parts.iter()
+            .map(|(weight, prioritizer)| weight * prioritizer.priority(tx_hash, tx, age))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_core::clock::ManualClock;
+    use nyx_core::storage::MemoryStorage;
+    use nyx_core::transaction::{TxInput, TxOutput};
+    use nyx_core::verification::VerifyMode;
+    use nyx_core::{NetworkId, RingSignature};
+    use std::sync::Arc;
+
+    fn output(nonce: u8) -> TxOutput {
+        TxOutput {
+            stealth_address: vec![nonce],
+            amount_commitment: vec![],
+            range_proof: vec![],
+            ephemeral_pubkey: vec![],
+            unlock_time: None,
+            htlc: None,
+        }
+    }
+
+    fn approving(references: [Hash; 2]) -> Transaction {
+        Transaction::new(
+            vec![],
+            vec![output(0)],
+            vec![],
+            vec![],
+            references[0],
+            references[1],
+        )
+    }
+
+    #[test]
+    fn test_pow_difficulty_counts_leading_zero_bits() {
+        assert_eq!(pow_difficulty(&[0xFF; 32]), 0);
+        let mut hash = [0u8; 32];
+        hash[2] = 0x1F;
+        assert_eq!(pow_difficulty(&hash), 19);
+        assert_eq!(pow_difficulty(&[0u8; 32]), 256);
+    }
+
+    #[test]
+    fn test_tip_approvals_are_favoured() {
+        let dag = DagProcessor::new(MemoryStorage::new())
+            .with_network(NetworkId::Regtest)
+            .with_clock(Arc::new(ManualClock::new(1_700_000_000)));
+        let a = dag.mint(vec![output(1)]).unwrap();
+        let b = dag.mint(vec![output(2)]).unwrap();
+        let c = dag.mint(vec![output(3)]).unwrap();
+
+        // `d` approves `a` and `b`, leaving `c` and `d` as tips
+        let mut d = approving([a, b]).with_network(NetworkId::Regtest);
+        d.inputs.push(TxInput { prev_tx: a, index: 0, key_image: [4u8; 32], ring_indices: vec![] });
+        d.ring_signatures.push(RingSignature { ring_members: vec![vec![1], vec![2]], signature: vec![], key_image: [0u8; 32] });
+        d.timestamp = 1_700_000_000;
+        dag.add_transaction_with_mode(d, VerifyMode::HeadersOnly).unwrap();
+
+        let prioritizer = TipApprovalPrioritizer::new(dag.clone());
+        let score = |references| {
+            let tx = approving(references);
+            prioritizer.priority(&tx.id(), &tx, Duration::ZERO)
+        };
+        assert!(dag.get_tips().unwrap().contains(&c));
+        assert!(score([c, c]) > score([a, b]));
+        assert_eq!(score([c, [9u8; 32]]), 1.0);
+    }
+
+    #[test]
+    fn test_weighted_sum() {
+        let tx = approving([[0u8; 32], [1u8; 32]]);
+        let prioritizer = WeightedPrioritizer::new()
+            .with(2.0, AgePrioritizer)
+            .with(0.0, PowPrioritizer);
+
+        assert_eq!(prioritizer.priority(&[0u8; 32], &tx, Duration::from_secs(90)), 3.0);
+        assert_eq!(WeightedPrioritizer::new().priority(&[0u8; 32], &tx, Duration::from_secs(90)), 0.0);
+    }
+}
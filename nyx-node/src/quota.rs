@@ -0,0 +1,136 @@
+// src/quota.rs
+
+//! Per-source transaction quotas.
+//!
+//! Layer 1 has no fees, so nothing stops one peer from filling the mempool
+//! with valid but useless transactions. Each source may have at most
+//! `max_transactions` admitted within any `window`; the window slides, so
+//! a burst is only forgiven once it has aged out rather than at a fixed
+//! reset time. Transactions submitted locally are not limited.
+
+use nyx_network::PeerId;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Sources tracked before idle ones are dropped
+const MAX_TRACKED_SOURCES: usize = 10_000;
+
+/// Where a transaction came from
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Origin {
+    /// Submitted through the RPC or the built-in wallet
+    Local,
+
+    /// Relayed by a peer
+    Peer(PeerId),
+}
+
+/// Sliding window admission quotas keyed by [`Origin`]
+pub struct SourceQuotas {
+    /// Admissions allowed per source within `window`
+    max_transactions: usize,
+
+    /// Length of the sliding window
+    window: Duration,
+
+    /// Admission times of each source within the window, oldest first
+    admitted: Mutex<HashMap<Origin, VecDeque<Instant>>>,
+}
+
+impl SourceQuotas {
+    /// Creates quotas of `max_transactions` per source within `window`
+    pub fn new(max_transactions: usize, window: Duration) -> Self {
+        Self {
+            max_transactions,
+            window,
+            admitted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Checks whether `origin` may have another transaction admitted
+    pub fn allows(&self, origin: &Origin) -> bool {
+        self.allows_at(origin, Instant::now())
+    }
+
+    /// Counts an admitted transaction against the quota of `origin`
+    pub fn record(&self, origin: &Origin) {
+        self.record_at(origin, Instant::now())
+    }
+
+    /// Number of sources currently tracked
+    pub fn tracked_sources(&self) -> usize {
+        self.admitted.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    fn allows_at(&self, origin: &Origin, now: Instant) -> bool {
+        if *origin == Origin::Local {
+            return true;
+        }
+
+        let mut admitted = self.admitted.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(times) = admitted.get_mut(origin) else {
+            return self.max_transactions > 0;
+        };
+        self.expire(times, now);
+        times.len() < self.max_transactions
+    }
+
+    fn record_at(&self, origin: &Origin, now: Instant) {
+        if *origin == Origin::Local {
+            return;
+        }
+
+        let mut admitted = self.admitted.lock().unwrap_or_else(|e| e.into_inner());
+        if admitted.len() >= MAX_TRACKED_SOURCES && !admitted.contains_key(origin) {
+            admitted.retain(|_, times| times.back().is_some_and(|last| now.duration_since(*last) < self.window));
+        }
+
+        let times = admitted.entry(origin.clone()).or_default();
+        self.expire(times, now);
+        times.push_back(now);
+    }
+
+    /// Drops admissions that slid out of the window
+    fn expire(&self, times: &mut VecDeque<Instant>, now: Instant) {
+        while times.front().is_some_and(|first| now.saturating_duration_since(*first) >= self.window) {
+            times.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_slides() {
+        let quotas = SourceQuotas::new(2, Duration::from_secs(10));
+        let peer = Origin::Peer(vec![1]);
+        let start = Instant::now();
+
+        quotas.record_at(&peer, start);
+        quotas.record_at(&peer, start + Duration::from_secs(6));
+        assert!(!quotas.allows_at(&peer, start + Duration::from_secs(9)));
+
+        // The first admission ages out, the second still counts
+        assert!(quotas.allows_at(&peer, start + Duration::from_secs(10)));
+        quotas.record_at(&peer, start + Duration::from_secs(10));
+        assert!(!quotas.allows_at(&peer, start + Duration::from_secs(15)));
+    }
+
+    #[test]
+    fn test_sources_are_limited_separately() {
+        let quotas = SourceQuotas::new(1, Duration::from_secs(60));
+        let now = Instant::now();
+
+        quotas.record_at(&Origin::Peer(vec![1]), now);
+        assert!(!quotas.allows_at(&Origin::Peer(vec![1]), now));
+        assert!(quotas.allows_at(&Origin::Peer(vec![2]), now));
+
+        quotas.record_at(&Origin::Local, now);
+        quotas.record_at(&Origin::Local, now);
+        assert!(quotas.allows_at(&Origin::Local, now));
+        assert_eq!(quotas.tracked_sources(), 1);
+    }
+}
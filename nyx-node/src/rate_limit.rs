@@ -0,0 +1,183 @@
+// src/rate_limit.rs
+
+//! Per-IP rate limiting of RPC requests.
+//!
+//! Every client gets a token bucket that holds up to `burst` tokens and
+//! refills at `requests_per_second`. A request takes one token; a client
+//! with an empty bucket is told how long to wait.
+//!
+//! IPv4 clients are keyed by address and IPv6 clients by their /64, the
+//! smallest prefix a host is usually assigned, so rotating through one's
+//! own addresses does not buy fresh buckets. At most
+//! `MAX_TRACKED_CLIENTS` are tracked; beyond that the least recently seen
+//! client is evicted, so every request costs O(log n) however many
+//! addresses a flood uses.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, Ipv6Addr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Clients tracked before the least recently seen is evicted
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Token bucket rate limiter keyed by client IP address
+pub struct RateLimiter {
+    /// Tokens added per second
+    rate: f64,
+
+    /// Bucket capacity
+    burst: f64,
+
+    /// Clients tracked at most
+    capacity: usize,
+
+    /// Bucket of each client and their recency order
+    buckets: Mutex<Buckets>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Default)]
+struct Buckets {
+    /// Buckets with the tick of their client's last request
+    by_client: HashMap<IpAddr, (Bucket, u64)>,
+
+    /// Clients by tick of last request, least recent first
+    by_use: BTreeMap<u64, IpAddr>,
+
+    /// Source of use ticks
+    tick: u64,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `requests_per_second` per client, with
+    /// bursts of up to `burst` requests
+    pub fn new(requests_per_second: u32, burst: u32) -> Self {
+        Self {
+            rate: f64::from(requests_per_second.max(1)),
+            burst: f64::from(burst.max(1)),
+            capacity: MAX_TRACKED_CLIENTS,
+            buckets: Mutex::new(Buckets::default()),
+        }
+    }
+
+    /// Takes a token for a request from `ip`
+    ///
+    /// # Errors
+    /// Time until the next token is available if the bucket is empty
+    pub fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        self.check_at(ip, Instant::now())
+    }
+
+    fn check_at(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        let client = client_key(ip);
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let Buckets { by_client, by_use, tick } = &mut *buckets;
+        if by_client.len() >= self.capacity && !by_client.contains_key(&client) {
+            if let Some((_, evicted)) = by_use.pop_first() {
+                by_client.remove(&evicted);
+            }
+        }
+
+        *tick += 1;
+        let (bucket, used) = by_client.entry(client)
+            .or_insert((Bucket { tokens: self.burst, updated: now }, *tick));
+        by_use.remove(used);
+        by_use.insert(*tick, client);
+        *used = *tick;
+
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / self.rate))
+        }
+    }
+
+    /// Number of clients currently tracked
+    pub fn tracked_clients(&self) -> usize {
+        self.buckets.lock().unwrap_or_else(|e| e.into_inner()).by_client.len()
+    }
+}
+
+/// Key of the bucket `ip` draws from: IPv4 addresses (mapped ones
+/// included) as they are, IPv6 addresses by their /64
+fn client_key(ip: IpAddr) -> IpAddr {
+    match ip.to_canonical() {
+        IpAddr::V6(v6) => IpAddr::V6(Ipv6Addr::from(u128::from(v6) & !u128::from(u64::MAX))),
+        v4 => v4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_then_refill() {
+        let limiter = RateLimiter::new(2, 3);
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check_at(ip, start).is_ok());
+        }
+        let wait = limiter.check_at(ip, start).unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+
+        assert!(limiter.check_at(ip, start + Duration::from_millis(500)).is_ok());
+        assert!(limiter.check_at(ip, start + Duration::from_millis(500)).is_err());
+    }
+
+    #[test]
+    fn test_clients_are_limited_separately() {
+        let limiter = RateLimiter::new(1, 1);
+        let now = Instant::now();
+
+        assert!(limiter.check_at("10.0.0.1".parse().unwrap(), now).is_ok());
+        assert!(limiter.check_at("10.0.0.1".parse().unwrap(), now).is_err());
+        assert!(limiter.check_at("10.0.0.2".parse().unwrap(), now).is_ok());
+        assert_eq!(limiter.tracked_clients(), 2);
+    }
+
+    #[test]
+    fn test_ipv6_clients_share_their_64() {
+        let limiter = RateLimiter::new(1, 1);
+        let now = Instant::now();
+
+        assert!(limiter.check_at("2001:db8:0:1::1".parse().unwrap(), now).is_ok());
+        assert!(limiter.check_at("2001:db8:0:1:ffff::2".parse().unwrap(), now).is_err());
+        assert!(limiter.check_at("2001:db8:0:2::1".parse().unwrap(), now).is_ok());
+
+        // Mapped IPv4 addresses are limited as IPv4
+        assert!(limiter.check_at("10.0.0.1".parse().unwrap(), now).is_ok());
+        assert!(limiter.check_at("::ffff:10.0.0.1".parse().unwrap(), now).is_err());
+        assert_eq!(limiter.tracked_clients(), 3);
+    }
+
+    #[test]
+    fn test_least_recently_seen_client_evicted() {
+        let mut limiter = RateLimiter::new(1, 1);
+        limiter.capacity = 2;
+        let now = Instant::now();
+        let (a, b, c) = ("10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap(), "10.0.0.3".parse().unwrap());
+
+        assert!(limiter.check_at(a, now).is_ok());
+        assert!(limiter.check_at(b, now).is_ok());
+        assert!(limiter.check_at(a, now).is_err());
+
+        // `b` is the least recently seen, though its bucket is as empty
+        assert!(limiter.check_at(c, now).is_ok());
+        assert_eq!(limiter.tracked_clients(), 2);
+        assert!(limiter.check_at(a, now).is_err());
+        assert!(limiter.check_at(b, now).is_ok());
+    }
+}
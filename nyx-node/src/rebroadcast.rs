@@ -0,0 +1,118 @@
+// src/rebroadcast.rs
+
+//! Rebroadcasting of stuck mempool transactions.
+//!
+//! A transaction gossiped while the node had few peers, or dropped by a
+//! slow peer's outbound queue, may never reach the DAG. The rebroadcaster
+//! periodically drops mempool transactions the DAG now contains and gossips
+//! those still pending after the configured delay again.
+
+use crate::errors::Result;
+use crate::mempool::Mempool;
+use nyx_core::dag::DagProcessor;
+use nyx_network::Node as NetworkNode;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Gossips pending transactions again until they appear in the DAG
+pub struct Rebroadcaster {
+    /// Mempool holding pending transactions
+    mempool: Mempool,
+
+    /// DAG transactions are expected to appear in
+    dag: DagProcessor,
+
+    /// Network to gossip on
+    network: Arc<RwLock<NetworkNode>>,
+
+    /// How long a transaction may stay pending before it is gossiped again
+    after: Duration,
+}
+
+impl Rebroadcaster {
+    /// Creates a rebroadcaster for transactions pending longer than `after`
+    pub fn new(mempool: Mempool, dag: DagProcessor, network: Arc<RwLock<NetworkNode>>, after: Duration) -> Self {
+        Self { mempool, dag, network, after }
+    }
+
+    /// Runs one round: drops included transactions and rebroadcasts the
+    /// ones due
+    ///
+    /// # Returns
+    /// Number of transactions rebroadcast
+    pub async fn run_once(&self) -> Result<usize> {
+        for tx in self.mempool.get_all_transactions().await {
+            let tx_hash = tx.id();
+            if self.dag.has_transaction_async(tx_hash).await? {
+                self.mempool.remove_transaction(&tx_hash).await;
+            }
+        }
+
+        let due = self.mempool.due_for_rebroadcast(self.after).await;
+        let network = self.network.read().await;
+        let mut rebroadcast = 0;
+        for tx in due {
+            let tx_hash = tx.id();
+            match network.broadcast_transaction(tx).await {
+                Ok(()) => rebroadcast += 1,
+                Err(e) => warn!(tx = %hex::encode(tx_hash), "Rebroadcast failed: {}", e),
+            }
+        }
+
+        if rebroadcast > 0 {
+            info!("Rebroadcast {} stuck transactions", rebroadcast);
+        }
+        Ok(rebroadcast)
+    }
+
+    /// Runs a round every `after` until the task is aborted
+    ///
+    /// A transaction is therefore rebroadcast between one and two delays
+    /// after it was last sent.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut timer = tokio::time::interval(self.after);
+            timer.tick().await;
+
+            loop {
+                timer.tick().await;
+                debug!("Checking for stuck transactions");
+                if let Err(e) = self.run_once().await {
+                    warn!("Rebroadcast round failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_core::storage::MemoryStorage;
+    use nyx_core::testing::TestTx;
+    use nyx_network::NodeConfig as NetConfig;
+
+    #[tokio::test]
+    async fn test_rebroadcasts_only_pending_transactions() {
+        let mempool = Mempool::new(10);
+        let dag = DagProcessor::new(MemoryStorage::new());
+        let network = Arc::new(RwLock::new(NetworkNode::new(NetConfig::default()).await.unwrap()));
+
+        let stuck = TestTx::new(1).build();
+        let included = TestTx::new(2).build();
+        mempool.add_transaction(stuck.clone()).await.unwrap();
+        mempool.add_transaction(included.clone()).await.unwrap();
+        dag.storage().store_transaction(included.clone()).unwrap();
+
+        let patient = Rebroadcaster::new(mempool.clone(), dag.clone(), network.clone(), Duration::from_secs(60));
+        assert_eq!(patient.run_once().await.unwrap(), 0);
+        assert!(mempool.get_transaction(&included.id()).await.is_none());
+
+        let eager = Rebroadcaster::new(mempool.clone(), dag, network, Duration::ZERO);
+        assert_eq!(eager.run_once().await.unwrap(), 1);
+        assert!(mempool.get_transaction(&stuck.id()).await.is_some());
+    }
+}
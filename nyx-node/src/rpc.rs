@@ -3,19 +3,52 @@
 //! RPC server using Axum.
 
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post},
     Router, Json,
-    extract::State,
+    error_handling::HandleErrorLayer,
+    extract::{ConnectInfo, DefaultBodyLimit, Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    response::sse::{Event, KeepAlive, Sse},
 };
+use crate::config::RpcLimits;
+use crate::double_spend::DoubleSpendAlert;
+use crate::health::HealthReport;
+use crate::idempotency::{self, Claim, IdempotencyCache, StoredResponse};
+use crate::rate_limit::RateLimiter;
+use crate::settings::{RuntimeSettings, SettingsUpdate};
+use crate::snapshot_file::SnapshotFile;
+use crate::update::UpdateStatus;
+use crate::metrics::NodeGauges;
+use nyx_core::dag::DagStats;
+use nyx_core::finality::{InclusionProof, NodeMode, SnapshotHeader};
+use nyx_core::graph::{GraphFormat, GraphRange};
+use nyx_core::metrics::ConfirmationEstimate;
+use nyx_core::transaction::Transaction;
+use nyx_core::{Hash, Timestamp};
+use nyx_indexer::{DagDepthView, IndexerError, KeyImageStatus, OutputView, TransactionView};
+use nyx_crypto::ct;
+use futures_util::stream::{self, Stream};
 use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::RwLock;
+use tower::ServiceBuilder;
+use tower_http::cors::{AllowOrigin, CorsLayer};
 
 /// RPC server state
 #[derive(Clone)]
 pub struct RpcState {
     /// Node reference
     pub node: Arc<RwLock<crate::node::NyxNode>>,
+
+    /// Bearer token required by the admin endpoints
+    pub admin_token: Option<Arc<str>>,
+
+    /// Latest snapshot file, rebuilt when a newer snapshot is applied
+    pub snapshot_file: Arc<tokio::sync::Mutex<Option<SnapshotFile>>>,
 }
 
 /// RPC server
@@ -25,6 +58,27 @@ pub struct RpcServer {
 
     /// Server state
     state: RpcState,
+
+    /// Plugin routes, by mount point
+    plugin_routes: Vec<(String, Router)>,
+
+    /// Rate, body size and concurrency limits
+    limits: RpcLimits,
+
+    /// Origins allowed to make browser requests
+    cors_origins: Vec<String>,
+
+    /// Proxies trusted to report the client address
+    trusted_proxies: Vec<IpAddr>,
+
+    /// Prefix every route is served under
+    base_path: Option<String>,
+
+    /// Serve the latest snapshot file
+    serve_snapshot_file: bool,
+
+    /// Responses replayed to retried requests
+    idempotency: Arc<IdempotencyCache>,
 }
 
 impl RpcServer {
@@ -35,30 +89,375 @@ impl RpcServer {
     ) -> Self {
         Self {
             listen_addr,
-            state: RpcState { node },
+            state: RpcState { node, admin_token: None, snapshot_file: Arc::default() },
+            plugin_routes: Vec::new(),
+            limits: RpcLimits::default(),
+            cors_origins: Vec::new(),
+            trusted_proxies: Vec::new(),
+            base_path: None,
+            serve_snapshot_file: false,
+            idempotency: Arc::new(IdempotencyCache::new(idempotency::DEFAULT_TTL, idempotency::DEFAULT_MAX_KEYS)),
         }
     }
 
+    /// Enables the `/admin` endpoints, guarded by a bearer token
+    pub fn with_admin_token(mut self, token: Option<String>) -> Self {
+        self.state.admin_token = token.filter(|t| !t.is_empty()).map(Arc::from);
+        self
+    }
+
+    /// Serves each router under its mount point, e.g. `/plugins/<name>`
+    pub fn with_plugin_routes(mut self, routes: Vec<(String, Router)>) -> Self {
+        self.plugin_routes = routes;
+        self
+    }
+
+    /// Sets the per-IP rate limit, body size cap and concurrency cap
+    pub fn with_limits(mut self, limits: RpcLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Allows browser requests from these origins; `*` allows any
+    pub fn with_cors_origins(mut self, origins: Vec<String>) -> Self {
+        self.cors_origins = origins;
+        self
+    }
+
+    /// Rate limits the address in `X-Forwarded-For` for requests relayed
+    /// by these proxies
+    pub fn with_trusted_proxies(mut self, proxies: Vec<IpAddr>) -> Self {
+        self.trusted_proxies = proxies;
+        self
+    }
+
+    /// Serves every route under a prefix such as `/nyx`
+    pub fn with_base_path(mut self, base_path: Option<String>) -> Self {
+        self.base_path = base_path;
+        self
+    }
+
+    /// Serves the latest checkpoint at `/snapshots/latest/file`
+    pub fn with_snapshot_file(mut self, enabled: bool) -> Self {
+        self.serve_snapshot_file = enabled;
+        self
+    }
+
+    /// Sets how long and for how many keys responses are replayed to
+    /// requests retried with the same `Idempotency-Key`
+    pub fn with_idempotency(mut self, ttl: std::time::Duration, max_keys: usize) -> Self {
+        self.idempotency = Arc::new(IdempotencyCache::new(ttl, max_keys));
+        self
+    }
+
     /// Starts the RPC server
+    ///
+    /// # Errors
+    /// - `ConfigError` if a CORS origin or the base path is invalid
     pub async fn start(self) -> crate::Result<()> {
-        let app = Router::new()
+        let listen_addr = self.listen_addr;
+        let app = self.router()?;
+
+        let listener = tokio::net::TcpListener::bind(listen_addr).await?;
+
+        tracing::info!("RPC server listening on {}", listen_addr);
+
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+            .await
+            .map_err(|e| crate::NodeError::RpcError(format!("{}", e)))?;
+
+        Ok(())
+    }
+
+    /// Every route under the base path, behind CORS and the request limits
+    ///
+    /// Rate limiting reads the client address from `ConnectInfo`.
+    fn router(self) -> crate::Result<Router> {
+        let cors = cors_layer(&self.cors_origins)?;
+        let base_path = normalize_base_path(self.base_path.as_deref())?;
+
+        let mut app = Router::new()
             .route("/", get(root))
             .route("/status", get(get_status))
+            .route("/health", get(get_health))
+            .route("/update", get(get_update))
+            .route("/ready", get(get_ready))
+            .route("/peers", get(get_peers))
+            .route("/dag/stats", get(get_dag_stats))
+            .route("/dag/graph", get(get_dag_graph))
+            .route("/metrics", get(get_metrics))
+            .route("/estimate/confirmation", get(get_confirmation_estimate))
             .route("/balance", get(get_balance))
             .route("/send", post(send_transaction))
             .route("/submit", post(submit_transaction))
-            .with_state(self.state);
+            .route("/generate", post(generate))
+            .route("/mempool", get(mempool_list))
+            .route("/mempool/:hash", get(mempool_transaction))
+            .route("/explorer/tx/:hash", get(explorer_transaction))
+            .route("/explorer/key_image/:key_image", get(explorer_key_image))
+            .route("/explorer/key_image/:key_image/spent", get(explorer_key_image_status))
+            .route("/explorer/outputs", get(explorer_outputs))
+            .route("/outputs/by-index", get(outputs_by_index))
+            .route("/explorer/recent", get(explorer_recent))
+            .route("/explorer/stats", get(explorer_stats))
+            .route("/explorer/address/:address", get(explorer_address))
+            .route("/explorer/transactions", get(explorer_time_range))
+            .route("/wallet/transactions", get(wallet_transactions))
+            .route("/snapshots/latest", get(latest_snapshot_header))
+            .route("/snapshots/:number", get(snapshot_header))
+            .route("/light/headers", get(light_headers))
+            .route("/light/proof/:hash", get(light_proof))
+            .route("/alerts/double-spend/:hash", get(double_spend_alerts));
+        if self.serve_snapshot_file {
+            app = app.route("/snapshots/latest/file", get(latest_snapshot_file));
+        }
+        if self.state.admin_token.is_some() {
+            app = app
+                .route("/admin/settings", get(admin_settings).post(admin_update_settings))
+                .route("/admin/mempool/:hash", delete(admin_evict_transaction))
+                .route("/admin/peers", get(admin_peers))
+                .route("/admin/peers/add", post(admin_add_peer))
+                .route("/admin/peers/remove", post(admin_remove_peer))
+                .route("/admin/peers/ban", post(admin_ban_peer))
+                .route("/admin/peers/unban", post(admin_unban_peer))
+                .route("/admin/verify-db", post(admin_verify_db))
+                .route("/admin/du", get(admin_disk_usage));
+        }
+        let mut app = app.with_state(self.state);
+        for (path, router) in self.plugin_routes {
+            app = app.nest(&path, router);
+        }
+        if let Some(base_path) = base_path {
+            app = Router::new().nest(&base_path, app);
+        }
 
-        let listener = tokio::net::TcpListener::bind(self.listen_addr).await?;
+        let idempotency = IdempotencyState {
+            cache: self.idempotency,
+            max_body_bytes: self.limits.max_body_bytes,
+        };
+        let mut app = app
+            .layer(middleware::from_fn_with_state(idempotency, deduplicate))
+            .layer(DefaultBodyLimit::max(self.limits.max_body_bytes))
+            .layer(
+                ServiceBuilder::new()
+                    .layer(HandleErrorLayer::new(|_| async {
+                        (StatusCode::SERVICE_UNAVAILABLE, "Too many concurrent requests")
+                    }))
+                    .load_shed()
+                    .concurrency_limit(self.limits.max_concurrent_requests.max(1)),
+            );
+        if self.limits.requests_per_second > 0 {
+            let state = RateLimitState {
+                limiter: Arc::new(RateLimiter::new(self.limits.requests_per_second, self.limits.burst)),
+                trusted_proxies: self.trusted_proxies.into(),
+            };
+            app = app.layer(middleware::from_fn_with_state(state, rate_limit));
+        }
 
-        tracing::info!("RPC server listening on {}", self.listen_addr);
+        // Outermost, so rejected requests still carry CORS headers
+        Ok(match cors {
+            Some(cors) => app.layer(cors),
+            None => app,
+        })
+    }
+}
 
-        axum::serve(listener, app)
-            .await
-            .map_err(|e| crate::NodeError::RpcError(format!("{}", e)))?;
+/// CORS policy for the allowed origins, or `None` if there are none
+fn cors_layer(origins: &[String]) -> crate::Result<Option<CorsLayer>> {
+    if origins.is_empty() {
+        return Ok(None);
+    }
 
-        Ok(())
+    let allow_origin = if origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        let origins = origins.iter()
+            .map(|origin| origin.parse())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| crate::NodeError::ConfigError("Invalid CORS origin".to_string()))?;
+        AllowOrigin::list(origins)
+    };
+
+    Ok(Some(CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([axum::http::Method::GET, axum::http::Method::POST])
+        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION, IDEMPOTENCY_KEY])))
+}
+
+/// Base path with a leading and no trailing slash, or `None` for the root
+fn normalize_base_path(base_path: Option<&str>) -> crate::Result<Option<String>> {
+    let Some(path) = base_path.map(|p| p.trim_end_matches('/')).filter(|p| !p.is_empty()) else {
+        return Ok(None);
+    };
+    if !path.starts_with('/') || path.contains([':', '*', '{', '}']) {
+        return Err(crate::NodeError::ConfigError(format!("Invalid RPC base path: {}", path)));
     }
+    Ok(Some(path.to_string()))
+}
+
+/// Header naming a request that retries must not repeat
+const IDEMPOTENCY_KEY: header::HeaderName = header::HeaderName::from_static("idempotency-key");
+
+/// Header marking a replayed response
+const IDEMPOTENT_REPLAYED: header::HeaderName = header::HeaderName::from_static("idempotent-replayed");
+
+/// Idempotency cache and the largest body it hashes
+#[derive(Clone)]
+struct IdempotencyState {
+    cache: Arc<IdempotencyCache>,
+    max_body_bytes: usize,
+}
+
+/// Runs a mutating request with an `Idempotency-Key` at most once,
+/// replaying its response to retries
+///
+/// Requests without a key, and reads, pass through. The fingerprint covers
+/// the `Authorization` header, so a replay needs the original credentials.
+/// Only successful outcomes are kept: errors and JSON bodies reporting
+/// `"success": false` had no effect, so retries run the request again.
+async fn deduplicate(
+    State(state): State<IdempotencyState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(key) = request.headers().get(&IDEMPOTENCY_KEY).cloned() else {
+        return next.run(request).await;
+    };
+    if matches!(*request.method(), axum::http::Method::GET | axum::http::Method::HEAD | axum::http::Method::OPTIONS) {
+        return next.run(request).await;
+    }
+    let key = match key.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= idempotency::MAX_KEY_LEN => key.to_string(),
+        _ => return (StatusCode::BAD_REQUEST, "Invalid Idempotency-Key").into_response(),
+    };
+
+    let (parts, body) = request.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, state.max_body_bytes).await else {
+        return (StatusCode::PAYLOAD_TOO_LARGE, "Request body too large").into_response();
+    };
+    let mut message = format!("{} {}\n", parts.method, parts.uri.path()).into_bytes();
+    if let Some(authorization) = parts.headers.get(header::AUTHORIZATION) {
+        message.extend_from_slice(authorization.as_bytes());
+    }
+    message.push(b'\n');
+    message.extend_from_slice(&body);
+    let fingerprint = nyx_crypto::hash::sha256(&message);
+
+    let pending = match state.cache.begin(&key, fingerprint) {
+        Claim::New(pending) => pending,
+        Claim::InProgress => {
+            return (StatusCode::CONFLICT, "A request with this Idempotency-Key is in progress").into_response();
+        }
+        Claim::Mismatch => {
+            return (StatusCode::UNPROCESSABLE_ENTITY, "Idempotency-Key was used for a different request").into_response();
+        }
+        Claim::Done(stored) => {
+            let mut response = (
+                StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK),
+                stored.body,
+            ).into_response();
+            if let Some(content_type) = stored.content_type.and_then(|value| value.parse().ok()) {
+                response.headers_mut().insert(header::CONTENT_TYPE, content_type);
+            }
+            response.headers_mut().insert(IDEMPOTENT_REPLAYED, header::HeaderValue::from_static("true"));
+            return response;
+        }
+    };
+
+    let response = next.run(Request::from_parts(parts, axum::body::Body::from(body))).await;
+    let status = response.status();
+    if !status.is_success() {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    if reports_failure(&body) {
+        return Response::from_parts(parts, axum::body::Body::from(body));
+    }
+    pending.complete(StoredResponse {
+        status: status.as_u16(),
+        content_type: parts.headers.get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        body: body.to_vec(),
+    });
+    Response::from_parts(parts, axum::body::Body::from(body))
+}
+
+/// Whether a JSON response body reports `"success": false`, as `/send`
+/// does for failures
+fn reports_failure(body: &[u8]) -> bool {
+    #[derive(Deserialize)]
+    struct Outcome {
+        success: bool,
+    }
+    serde_json::from_slice::<Outcome>(body).is_ok_and(|outcome| !outcome.success)
+}
+
+/// Rate limiter and the proxies allowed to name the client
+#[derive(Clone)]
+struct RateLimitState {
+    limiter: Arc<RateLimiter>,
+    trusted_proxies: Arc<[IpAddr]>,
+}
+
+/// Rejects requests from clients over their rate limit with 429
+async fn rate_limit(
+    State(state): State<RateLimitState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = client_ip(peer.ip(), request.headers(), &state.trusted_proxies);
+    match state.limiter.check(client) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            let retry_after = wait.as_secs_f64().ceil().max(1.0).to_string();
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after)],
+                "Rate limit exceeded",
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Address of the client behind any trusted proxies
+///
+/// `X-Forwarded-For` is only believed when a trusted proxy sent the
+/// request. Its hops are walked from the nearest, and the first one that
+/// is not a trusted proxy is the client; hops further along were written
+/// by the client itself. A malformed hop ends the walk at the last
+/// address a trusted proxy vouched for.
+fn client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> IpAddr {
+    if !trusted_proxies.contains(&peer) {
+        return peer;
+    }
+
+    let hops: Vec<&str> = headers.get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+
+    let mut client = peer;
+    for hop in hops.iter().rev() {
+        match hop.parse::<IpAddr>() {
+            Ok(ip) => {
+                client = ip;
+                if !trusted_proxies.contains(&ip) {
+                    break;
+                }
+            }
+            Err(_) => break,
+        }
+    }
+    client
 }
 
 // RPC handlers
@@ -74,12 +473,134 @@ async fn get_status(
 
     Json(StatusResponse {
         version: crate::NODE_VERSION.to_string(),
-        peers: node.network_stats().peer_count,
+        peers: node.network_stats().await.peer_count,
         mempool_size: node.mempool_size().await,
         syncing: node.is_syncing().await,
+        mode: node.mode(),
+        pruned_through: node.finality().pruned_through(),
     })
 }
 
+/// Latest upgrade check
+async fn get_update(
+    State(state): State<RpcState>,
+) -> Json<UpdateStatus> {
+    Json(state.node.read().await.update_status())
+}
+
+/// Liveness probe: 200 while storage is healthy, 503 otherwise
+async fn get_health(
+    State(state): State<RpcState>,
+) -> (StatusCode, Json<HealthReport>) {
+    let report = state.node.read().await.health().await;
+    (probe_status(report.is_live()), Json(report))
+}
+
+/// Readiness probe: 200 when every readiness check passes, 503 otherwise
+async fn get_ready(
+    State(state): State<RpcState>,
+) -> (StatusCode, Json<HealthReport>) {
+    let report = state.node.read().await.health().await;
+    (probe_status(report.is_ready()), Json(report))
+}
+
+/// DAG statistics with rolling throughput, latency, tips and scores
+async fn get_dag_stats(
+    State(state): State<RpcState>,
+) -> Result<Json<DagStats>, (StatusCode, String)> {
+    let dag = state.node.read().await.dag().clone();
+    dag.get_stats_async().await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Renders a neighborhood of the DAG as DOT or GraphML
+///
+/// Centered on `center` if given, otherwise on the current tips.
+async fn get_dag_graph(
+    State(state): State<RpcState>,
+    Query(query): Query<GraphQuery>,
+) -> Result<([(header::HeaderName, &'static str); 1], String), (StatusCode, String)> {
+    let format: GraphFormat = query.format.parse()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let depth = query.depth.min(MAX_GRAPH_DEPTH);
+    let range = match query.center {
+        Some(center) => {
+            let center: Hash = hex::decode(&center)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or((StatusCode::BAD_REQUEST, "Invalid transaction hash".to_string()))?;
+            GraphRange::Around { center, depth }
+        }
+        None => GraphRange::Tips { depth },
+    };
+
+    let dag = state.node.read().await.dag().clone();
+    if let GraphRange::Around { center, .. } = range {
+        if dag.storage().is_pruned(&center) {
+            return Err(pruned(&center));
+        }
+    }
+    let graph = dag.graph_async(range).await
+        .map_err(|e| match e {
+            nyx_core::NyxError::TransactionNotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        })?;
+
+    Ok(([(header::CONTENT_TYPE, format.content_type())], graph.render(format)))
+}
+
+/// Expected wait for a transaction submitted now to be confirmed
+async fn get_confirmation_estimate(
+    State(state): State<RpcState>,
+) -> Json<ConfirmationEstimate> {
+    Json(state.node.read().await.dag().estimate_confirmation())
+}
+
+/// Node and DAG metrics in the Prometheus text format
+async fn get_metrics(
+    State(state): State<RpcState>,
+) -> Result<([(header::HeaderName, &'static str); 1], String), (StatusCode, String)> {
+    let node = state.node.read().await;
+    let stats = node.dag().get_stats_async().await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let gauges = NodeGauges {
+        mempool_size: node.mempool_size().await,
+        peers: node.network_stats().await.peer_count,
+    };
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        crate::metrics::render(&stats, gauges),
+    ))
+}
+
+fn probe_status(ok: bool) -> StatusCode {
+    if ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+async fn get_peers(
+    State(state): State<RpcState>,
+) -> Json<Vec<PeerResponse>> {
+    let node = state.node.read().await;
+    let peers = node.network_stats().await.peers
+        .into_iter()
+        .map(|p| PeerResponse {
+            peer_id: hex::encode(&p.peer_id),
+            address: p.address.to_string(),
+            latency_ms: p.latency_ms,
+            missed_heartbeats: p.missed_heartbeats,
+            mode: p.mode,
+        })
+        .collect();
+
+    Json(peers)
+}
+
 async fn get_balance(
     State(state): State<RpcState>,
 ) -> Json<BalanceResponse> {
@@ -93,7 +614,7 @@ async fn send_transaction(
     State(state): State<RpcState>,
     Json(req): Json<SendRequest>,
 ) -> Json<SendResponse> {
-    let node = state.node.read().await;
+    let mut node = state.node.write().await;
 
     match node.send(req.to, req.amount).await {
         Ok(tx_hash) => Json(SendResponse {
@@ -120,14 +641,766 @@ async fn submit_transaction(
     })
 }
 
+/// Mints funded outputs to the node wallet (regtest only)
+async fn generate(
+    State(state): State<RpcState>,
+    Json(req): Json<GenerateRequest>,
+) -> Result<Json<GenerateResponse>, (StatusCode, String)> {
+    let mut node = state.node.write().await;
+    let tx_hash = node.generate(req.count, req.amount)
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    Ok(Json(GenerateResponse {
+        tx_hash: hex::encode(tx_hash),
+        balance: node.get_balance().await,
+    }))
+}
+
+/// Pending transactions with their sizes and ages, oldest first
+async fn mempool_list(
+    State(state): State<RpcState>,
+    Query(query): Query<MempoolQuery>,
+) -> Json<MempoolResponse> {
+    let node = state.node.read().await;
+    let pending = node.mempool().pending().await;
+
+    Json(MempoolResponse {
+        count: pending.len(),
+        max_size: node.mempool().max_size(),
+        transactions: pending.into_iter()
+            .take(query.limit.min(MAX_MEMPOOL_ENTRIES))
+            .map(|entry| MempoolEntryResponse {
+                tx_hash: hex::encode(entry.tx_hash),
+                size: entry.size,
+                age_secs: entry.age.as_secs(),
+            })
+            .collect(),
+    })
+}
+
+/// A pending transaction in full
+async fn mempool_transaction(
+    State(state): State<RpcState>,
+    Path(hash): Path<String>,
+) -> Result<Json<Arc<Transaction>>, (StatusCode, String)> {
+    let hash: Hash = hex::decode(&hash)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or((StatusCode::BAD_REQUEST, "Invalid transaction hash".to_string()))?;
+
+    state.node.read().await.mempool().get_transaction_shared(&hash).await
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "Transaction not pending".to_string()))
+}
+
+async fn explorer_transaction(
+    State(state): State<RpcState>,
+    Path(hash): Path<String>,
+) -> Result<Json<TransactionView>, (StatusCode, String)> {
+    let node = state.node.read().await;
+    found(node.explorer().transaction(&hash))
+}
+
+async fn explorer_key_image(
+    State(state): State<RpcState>,
+    Path(key_image): Path<String>,
+) -> Result<Json<TransactionView>, (StatusCode, String)> {
+    let node = state.node.read().await;
+    found(node.explorer().by_key_image(&key_image))
+}
+
+async fn explorer_key_image_status(
+    State(state): State<RpcState>,
+    Path(key_image): Path<String>,
+) -> Result<Json<KeyImageStatus>, (StatusCode, String)> {
+    let node = state.node.read().await;
+    node.explorer().key_image_status(&key_image)
+        .map(Json)
+        .map_err(explorer_error)
+}
+
+async fn explorer_outputs(
+    State(state): State<RpcState>,
+    Query(query): Query<AddressPrefixQuery>,
+) -> Result<Json<Vec<OutputView>>, (StatusCode, String)> {
+    let node = state.node.read().await;
+    node.explorer().outputs_by_address_prefix(&query.prefix, query.limit)
+        .map(Json)
+        .map_err(explorer_error)
+}
+
+/// Resolves global output indices to the keys ring members must use
+async fn outputs_by_index(
+    State(state): State<RpcState>,
+    Query(query): Query<OutputIndicesQuery>,
+) -> Result<Json<OutputIndexResponse>, (StatusCode, String)> {
+    let indices = query.indices.split(',')
+        .filter(|index| !index.is_empty())
+        .map(|index| index.trim().parse::<u64>())
+        .collect::<Result<Vec<u64>, _>>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid output index".to_string()))?;
+    if indices.len() > MAX_OUTPUTS_PER_REQUEST {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("At most {} outputs per request", MAX_OUTPUTS_PER_REQUEST),
+        ));
+    }
+
+    let dag = state.node.read().await.dag();
+    let storage = dag.storage();
+    let internal = |e: nyx_core::NyxError| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string());
+    let mut outputs = Vec::with_capacity(indices.len());
+    for global_index in indices {
+        let (tx_hash, index) = storage.output_location(global_index).map_err(internal)?
+            .ok_or((StatusCode::NOT_FOUND, format!("No output with global index {}", global_index)))?;
+        let output = storage.get_output_by_index(global_index).map_err(internal)?;
+        outputs.push(IndexedOutputResponse {
+            global_index,
+            tx_hash: hex::encode(tx_hash),
+            index,
+            stealth_address: hex::encode(output.stealth_address),
+        });
+    }
+
+    Ok(Json(OutputIndexResponse {
+        output_count: storage.output_count().map_err(internal)?,
+        outputs,
+    }))
+}
+
+async fn explorer_recent(
+    State(state): State<RpcState>,
+    Query(query): Query<RecentQuery>,
+) -> Result<Json<Vec<TransactionView>>, (StatusCode, String)> {
+    let node = state.node.read().await;
+    node.explorer().recent(query.limit)
+        .map(Json)
+        .map_err(explorer_error)
+}
+
+async fn explorer_stats(
+    State(state): State<RpcState>,
+) -> Result<Json<DagDepthView>, (StatusCode, String)> {
+    let node = state.node.read().await;
+    node.explorer().depth_stats()
+        .map(Json)
+        .map_err(explorer_error)
+}
+
+async fn explorer_address(
+    State(state): State<RpcState>,
+    Path(address): Path<String>,
+) -> Result<Json<Vec<TransactionView>>, (StatusCode, String)> {
+    let node = state.node.read().await;
+    node.explorer().by_stealth_address(&address)
+        .map(Json)
+        .map_err(explorer_error)
+}
+
+async fn explorer_time_range(
+    State(state): State<RpcState>,
+    Query(query): Query<TimeRangeQuery>,
+) -> Result<Json<Vec<TransactionView>>, (StatusCode, String)> {
+    let node = state.node.read().await;
+    node.explorer().by_time_range(query.from, query.to, query.limit)
+        .map(Json)
+        .map_err(explorer_error)
+}
+
+async fn wallet_transactions(
+    State(state): State<RpcState>,
+    Query(query): Query<WalletTransactionsQuery>,
+) -> Result<Json<Vec<Arc<Transaction>>>, (StatusCode, String)> {
+    let node = state.node.read().await;
+    // The index stores timestamps as SQLite integers, so the open end of
+    // the range is the largest signed value
+    let views = node.explorer().by_time_range(query.from, i64::MAX as Timestamp, query.limit)
+        .map_err(explorer_error)?;
+
+    let dag = node.dag().clone();
+    drop(node);
+
+    let mut hashes = Vec::with_capacity(views.len());
+    for view in views {
+        let hash: Hash = hex::decode(&view.hash).ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or((StatusCode::INTERNAL_SERVER_ERROR, format!("Invalid indexed hash {}", view.hash)))?;
+        // Skipping it would hide funds from the wallet
+        if dag.storage().is_pruned(&hash) {
+            return Err(pruned(&hash));
+        }
+        hashes.push(hash);
+    }
+
+    dag.query(move |dag| hashes.iter().map(|hash| dag.storage().get_transaction_shared(hash)).collect())
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn latest_snapshot_header(
+    State(state): State<RpcState>,
+) -> Result<Json<SnapshotHeader>, (StatusCode, String)> {
+    let node = state.node.read().await;
+    node.finality().latest_header()
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "No snapshot applied yet".to_string()))
+}
+
+async fn snapshot_header(
+    State(state): State<RpcState>,
+    Path(number): Path<u64>,
+) -> Result<Json<SnapshotHeader>, (StatusCode, String)> {
+    let node = state.node.read().await;
+    node.finality().header(number)
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, format!("Snapshot {} not applied", number)))
+}
+
+/// Serves the latest checkpoint as a file with `ETag` and range support
+async fn latest_snapshot_file(
+    State(state): State<RpcState>,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, String)> {
+    let node = state.node.read().await;
+    let mut cached = state.snapshot_file.lock().await;
+    let latest = node.finality().latest_snapshot();
+
+    let file = match cached.as_ref() {
+        Some(file) if file.number() == latest => file.clone(),
+        _ => {
+            if node.finality().pruned_through() > 0 {
+                return Err((
+                    StatusCode::NOT_FOUND,
+                    "Snapshot history was pruned; query an archive node".to_string(),
+                ));
+            }
+            let checkpoint = node.finality().checkpoint()
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+                .ok_or((StatusCode::NOT_FOUND, "No signed snapshot applied yet".to_string()))?;
+            let file = SnapshotFile::new(&checkpoint)
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+            *cached = Some(file.clone());
+            file
+        }
+    };
+    drop(cached);
+    drop(node);
+
+    Ok(file.respond(&headers))
+}
+
+async fn light_headers(
+    State(state): State<RpcState>,
+    Query(query): Query<HeadersQuery>,
+) -> Json<Vec<SnapshotHeader>> {
+    let node = state.node.read().await;
+    let limit = query.limit.min(MAX_HEADERS_PER_REQUEST);
+    Json(node.finality().headers(query.from, limit))
+}
+
+async fn light_proof(
+    State(state): State<RpcState>,
+    Path(hash): Path<String>,
+) -> Result<Json<InclusionProof>, (StatusCode, String)> {
+    let hash: nyx_core::Hash = hex::decode(&hash)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or((StatusCode::BAD_REQUEST, "Invalid transaction hash".to_string()))?;
+
+    let node = state.node.read().await;
+    let finality = node.finality();
+    if finality.finalized_at(&hash).is_some_and(|number| finality.is_pruned(number)) {
+        return Err(pruned(&hash));
+    }
+    finality.inclusion_proof(&hash)
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "Transaction not finalized".to_string()))
+}
+
+/// Not-found response for a transaction this pruned node no longer holds
+fn pruned(hash: &Hash) -> (StatusCode, String) {
+    (
+        StatusCode::NOT_FOUND,
+        format!("Transaction {} was pruned; query an archive node", hex::encode(hash)),
+    )
+}
+
+/// Streams double-spend alerts involving a transaction as server-sent
+/// events
+///
+/// Conflicts already known are sent first. The transaction need not have
+/// arrived yet, so merchants can subscribe as soon as they learn its hash.
+async fn double_spend_alerts(
+    State(state): State<RpcState>,
+    Path(hash): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, (StatusCode, String)> {
+    let hash: Hash = hex::decode(&hash)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or((StatusCode::BAD_REQUEST, "Invalid transaction hash".to_string()))?;
+
+    let node = state.node.read().await;
+    let alerts = node.double_spends().subscribe();
+    let known = node.double_spends().conflicts_of(&hash);
+    drop(node);
+
+    let stream = stream::unfold((known.into_iter(), alerts), move |(mut known, mut alerts)| async move {
+        let alert = match known.next() {
+            Some(alert) => alert,
+            None => loop {
+                match alerts.recv().await {
+                    Ok(alert) if alert.involves(&hash) => break alert,
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            },
+        };
+        Some((alert_event(&alert), (known, alerts)))
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+fn alert_event(alert: &DoubleSpendAlert) -> Result<Event, axum::Error> {
+    Event::default().event("double_spend").json_data(alert)
+}
+
+/// Gets the runtime settings (admin)
+async fn admin_settings(
+    State(state): State<RpcState>,
+    headers: HeaderMap,
+) -> Result<Json<RuntimeSettings>, (StatusCode, String)> {
+    authorize(&state, &headers)?;
+    Ok(Json(state.node.read().await.settings()))
+}
+
+/// Changes runtime settings without a restart (admin)
+async fn admin_update_settings(
+    State(state): State<RpcState>,
+    headers: HeaderMap,
+    Json(update): Json<SettingsUpdate>,
+) -> Result<Json<RuntimeSettings>, (StatusCode, String)> {
+    authorize(&state, &headers)?;
+    state.node.read().await.update_settings(update)
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+/// Drops a pending transaction from the mempool (admin)
+///
+/// The transaction may still arrive again from a peer.
+async fn admin_evict_transaction(
+    State(state): State<RpcState>,
+    headers: HeaderMap,
+    Path(hash): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    authorize(&state, &headers)?;
+    let hash: Hash = hex::decode(&hash)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or((StatusCode::BAD_REQUEST, "Invalid transaction hash".to_string()))?;
+
+    state.node.read().await.mempool().remove_transaction(&hash).await
+        .ok_or((StatusCode::NOT_FOUND, "Transaction not pending".to_string()))?;
+    tracing::info!(tx = %hex::encode(hash), "Evicted transaction from mempool");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Every managed peer with its state and traffic, and the bans (admin)
+async fn admin_peers(
+    State(state): State<RpcState>,
+    headers: HeaderMap,
+) -> Result<Json<PeerTableResponse>, (StatusCode, String)> {
+    authorize(&state, &headers)?;
+    let network = state.node.read().await.network().await;
+
+    let peers = network.peer_table().await
+        .into_iter()
+        .map(|p| AdminPeerResponse {
+            peer_id: hex::encode(&p.peer_id),
+            address: p.address.to_string(),
+            state: p.state,
+            latency_ms: p.latency_ms,
+            missed_heartbeats: p.missed_heartbeats,
+            bytes_sent: p.bytes_sent,
+            bytes_received: p.bytes_received,
+            transactions_rejected: p.transactions_rejected,
+            quota_exceeded: p.quota_exceeded,
+            mode: p.mode,
+        })
+        .collect();
+    let banned = network.bans().await
+        .into_iter()
+        .map(|(ip, remaining)| BanResponse { ip: ip.to_string(), remaining_secs: remaining.as_secs() })
+        .collect();
+
+    Ok(Json(PeerTableResponse { peers, banned }))
+}
+
+/// Connects to a peer and remembers it (admin)
+async fn admin_add_peer(
+    State(state): State<RpcState>,
+    headers: HeaderMap,
+    Json(req): Json<PeerAddressRequest>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    authorize(&state, &headers)?;
+    let network = state.node.read().await.network().await;
+
+    network.add_peer(req.address).await
+        .map(|()| StatusCode::NO_CONTENT)
+        .map_err(|e| match e {
+            nyx_network::NetworkError::PeerBanned(_) => (StatusCode::FORBIDDEN, e.to_string()),
+            _ => (StatusCode::BAD_GATEWAY, e.to_string()),
+        })
+}
+
+/// Disconnects a peer (admin)
+async fn admin_remove_peer(
+    State(state): State<RpcState>,
+    headers: HeaderMap,
+    Json(req): Json<PeerAddressRequest>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    authorize(&state, &headers)?;
+    let network = state.node.read().await.network().await;
+
+    if network.disconnect_peer(req.address).await {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err((StatusCode::NOT_FOUND, "Peer not connected".to_string()))
+    }
+}
+
+/// Bans an IP address and disconnects its peers (admin)
+async fn admin_ban_peer(
+    State(state): State<RpcState>,
+    headers: HeaderMap,
+    Json(req): Json<BanRequest>,
+) -> Result<Json<BanResult>, (StatusCode, String)> {
+    authorize(&state, &headers)?;
+    let network = state.node.read().await.network().await;
+
+    let duration = std::time::Duration::from_secs(req.duration_secs);
+    let disconnected = network.ban_peer(req.ip, duration).await;
+    Ok(Json(BanResult { disconnected }))
+}
+
+/// Disk space used by each component of the data directory (admin)
+async fn admin_disk_usage(
+    State(state): State<RpcState>,
+    headers: HeaderMap,
+) -> Result<Json<DiskUsageResponse>, (StatusCode, String)> {
+    authorize(&state, &headers)?;
+    let usage = state.node.read().await.disk_usage().await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(DiskUsageResponse {
+        db: usage.db,
+        keystore: usage.keystore,
+        peers: usage.peers,
+        snapshots: usage.snapshots,
+        other: usage.other,
+        total: usage.total(),
+    }))
+}
+
+/// Re-verifies stored transactions and DAG indexes, optionally repairing
+/// the indexes (admin)
+async fn admin_verify_db(
+    State(state): State<RpcState>,
+    headers: HeaderMap,
+    Json(req): Json<VerifyDbRequest>,
+) -> Result<Json<VerifyDbResponse>, (StatusCode, String)> {
+    authorize(&state, &headers)?;
+    let report = state.node.read().await.verify_db(req.repair).await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(VerifyDbResponse {
+        checked: report.checked,
+        issues: report.issues.iter().map(ToString::to_string).collect(),
+        repaired: report.repaired,
+    }))
+}
+
+/// Lifts the ban of an IP address (admin)
+async fn admin_unban_peer(
+    State(state): State<RpcState>,
+    headers: HeaderMap,
+    Json(req): Json<UnbanRequest>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    authorize(&state, &headers)?;
+    let network = state.node.read().await.network().await;
+
+    if network.unban_peer(req.ip).await {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err((StatusCode::NOT_FOUND, "Address not banned".to_string()))
+    }
+}
+
+/// Checks the `Authorization: Bearer` header against the admin token
+fn authorize(state: &RpcState, headers: &HeaderMap) -> Result<(), (StatusCode, String)> {
+    let expected = state.admin_token.as_deref()
+        .ok_or((StatusCode::NOT_FOUND, "Admin API disabled".to_string()))?;
+    let provided = headers.get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+
+    if ct::ct_eq(provided.as_bytes(), expected.as_bytes()) {
+        Ok(())
+    } else {
+        Err((StatusCode::UNAUTHORIZED, "Invalid admin token".to_string()))
+    }
+}
+
+/// Maps an optional explorer result to 200 or 404
+fn found(
+    result: nyx_indexer::Result<Option<TransactionView>>,
+) -> Result<Json<TransactionView>, (StatusCode, String)> {
+    match result {
+        Ok(Some(view)) => Ok(Json(view)),
+        Ok(None) => Err((StatusCode::NOT_FOUND, "Transaction not found".to_string())),
+        Err(e) => Err(explorer_error(e)),
+    }
+}
+
+fn explorer_error(err: IndexerError) -> (StatusCode, String) {
+    let status = match err {
+        IndexerError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, err.to_string())
+}
+
 // RPC request/response types
 
+#[derive(Debug, Deserialize)]
+struct TimeRangeQuery {
+    from: u64,
+    to: u64,
+    #[serde(default = "default_explorer_limit")]
+    limit: usize,
+}
+
+fn default_explorer_limit() -> usize {
+    100
+}
+
+/// Query for outputs by stealth address prefix
+#[derive(Debug, Deserialize)]
+struct AddressPrefixQuery {
+    /// Hex prefix of the stealth address
+    prefix: String,
+    #[serde(default = "default_explorer_limit")]
+    limit: usize,
+}
+
+/// Query for the most recent transactions
+#[derive(Debug, Deserialize)]
+struct RecentQuery {
+    #[serde(default = "default_explorer_limit")]
+    limit: usize,
+}
+
+/// Query for transactions to scan, oldest first
+#[derive(Debug, Deserialize)]
+struct WalletTransactionsQuery {
+    /// Earliest timestamp, inclusive
+    #[serde(default)]
+    from: Timestamp,
+    #[serde(default = "default_explorer_limit")]
+    limit: usize,
+}
+
+/// Maximum number of outputs resolved per request
+const MAX_OUTPUTS_PER_REQUEST: usize = 1000;
+
+/// Query for outputs by global index
+#[derive(Debug, Deserialize)]
+struct OutputIndicesQuery {
+    /// Comma-separated global output indices
+    #[serde(default)]
+    indices: String,
+}
+
+/// Maximum number of pending transactions listed per request
+const MAX_MEMPOOL_ENTRIES: usize = 1000;
+
+/// Query for the pending transactions
+#[derive(Debug, Deserialize)]
+struct MempoolQuery {
+    #[serde(default = "default_explorer_limit")]
+    limit: usize,
+}
+
+/// Deepest DAG neighborhood served by `/dag/graph`
+const MAX_GRAPH_DEPTH: usize = 64;
+
+/// Query for a DAG neighborhood to render
+#[derive(Debug, Deserialize)]
+struct GraphQuery {
+    /// Transaction to center on, hex encoded; the tips if absent
+    center: Option<String>,
+    /// Levels to include around the center
+    #[serde(default = "default_graph_depth")]
+    depth: usize,
+    /// `dot` or `graphml`
+    #[serde(default = "default_graph_format")]
+    format: String,
+}
+
+fn default_graph_depth() -> usize {
+    5
+}
+
+fn default_graph_format() -> String {
+    "dot".to_string()
+}
+
+/// Maximum number of snapshot headers served per request
+const MAX_HEADERS_PER_REQUEST: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+struct HeadersQuery {
+    #[serde(default = "default_headers_from")]
+    from: u64,
+    #[serde(default = "default_headers_limit")]
+    limit: usize,
+}
+
+fn default_headers_from() -> u64 {
+    1
+}
+
+fn default_headers_limit() -> usize {
+    MAX_HEADERS_PER_REQUEST
+}
+
 #[derive(Debug, Serialize)]
 struct StatusResponse {
     version: String,
     peers: usize,
     mempool_size: usize,
     syncing: bool,
+    mode: NodeMode,
+    /// Last snapshot whose transactions were pruned (0 if none)
+    pruned_through: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct OutputIndexResponse {
+    /// Number of outputs with a global index
+    output_count: u64,
+    outputs: Vec<IndexedOutputResponse>,
+}
+
+#[derive(Debug, Serialize)]
+struct IndexedOutputResponse {
+    global_index: u64,
+    tx_hash: String,
+    index: u32,
+    stealth_address: String,
+}
+
+#[derive(Debug, Serialize)]
+struct MempoolResponse {
+    /// Number of pending transactions, including unlisted ones
+    count: usize,
+    max_size: usize,
+    transactions: Vec<MempoolEntryResponse>,
+}
+
+#[derive(Debug, Serialize)]
+struct MempoolEntryResponse {
+    tx_hash: String,
+    size: usize,
+    age_secs: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct PeerResponse {
+    peer_id: String,
+    address: String,
+    latency_ms: Option<u64>,
+    missed_heartbeats: u32,
+    mode: NodeMode,
+}
+
+#[derive(Debug, Serialize)]
+struct PeerTableResponse {
+    peers: Vec<AdminPeerResponse>,
+    banned: Vec<BanResponse>,
+}
+
+#[derive(Debug, Serialize)]
+struct AdminPeerResponse {
+    peer_id: String,
+    address: String,
+    state: nyx_network::peer::PeerState,
+    latency_ms: Option<u64>,
+    missed_heartbeats: u32,
+    bytes_sent: u64,
+    bytes_received: u64,
+    transactions_rejected: u64,
+    quota_exceeded: u64,
+    mode: NodeMode,
+}
+
+#[derive(Debug, Serialize)]
+struct BanResponse {
+    ip: String,
+    remaining_secs: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PeerAddressRequest {
+    address: SocketAddr,
+}
+
+#[derive(Debug, Deserialize)]
+struct BanRequest {
+    ip: IpAddr,
+    #[serde(default = "default_ban_secs")]
+    duration_secs: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerifyDbRequest {
+    #[serde(default)]
+    repair: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct VerifyDbResponse {
+    checked: usize,
+    issues: Vec<String>,
+    repaired: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct DiskUsageResponse {
+    db: u64,
+    keystore: u64,
+    peers: u64,
+    snapshots: u64,
+    other: u64,
+    total: u64,
+}
+
+fn default_ban_secs() -> u64 {
+    nyx_network::DEFAULT_BAN_SECS
+}
+
+#[derive(Debug, Deserialize)]
+struct UnbanRequest {
+    ip: IpAddr,
+}
+
+#[derive(Debug, Serialize)]
+struct BanResult {
+    disconnected: usize,
 }
 
 #[derive(Debug, Serialize)]
@@ -149,6 +1422,24 @@ struct SendResponse {
 }
 
 #[derive(Debug, Deserialize)]
+struct GenerateRequest {
+    #[serde(default = "default_generate_count")]
+    count: u32,
+    amount: u64,
+}
+
+fn default_generate_count() -> u32 {
+    1
+}
+
+#[derive(Debug, Serialize)]
+struct GenerateResponse {
+    tx_hash: String,
+    balance: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct SubmitRequest {
     transaction: String,
 }
@@ -158,3 +1449,519 @@ struct SubmitResponse {
     success: bool,
     error: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    async fn state_with_token(dir: &tempfile::TempDir, token: Option<&str>) -> RpcState {
+        let mut config = crate::NodeConfig::regtest();
+        config.network.listen_addr = "127.0.0.1:0".parse().unwrap();
+        config.data_dir = dir.path().to_path_buf();
+        let node = crate::node::NyxNode::new(config).await.unwrap();
+
+        RpcServer::new("127.0.0.1:0".parse().unwrap(), Arc::new(RwLock::new(node)))
+            .with_admin_token(token.map(str::to_string))
+            .state
+    }
+
+    fn bearer(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, HeaderValue::from_str(&format!("Bearer {}", token)).unwrap());
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_router_enforces_limits() {
+        use axum::body::Body;
+        use axum::extract::connect_info::MockConnectInfo;
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut server = RpcServer::new("127.0.0.1:0".parse().unwrap(), state_with_token(&dir, None).await.node);
+        server.limits = RpcLimits { requests_per_second: 1, burst: 2, max_body_bytes: 16, ..RpcLimits::default() };
+        let app = server.router().unwrap().layer(MockConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))));
+
+        let oversized = Request::post("/send")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(format!("{{\"to\":\"{}\",\"amount\":1}}", "x".repeat(64))))
+            .unwrap();
+        assert_eq!(app.clone().oneshot(oversized).await.unwrap().status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let status = Request::get("/status").body(Body::empty()).unwrap();
+        assert_eq!(app.clone().oneshot(status).await.unwrap().status(), StatusCode::OK);
+
+        let status = Request::get("/status").body(Body::empty()).unwrap();
+        let limited = app.oneshot(status).await.unwrap();
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(limited.headers()[header::RETRY_AFTER], "1");
+    }
+
+    #[tokio::test]
+    async fn test_router_serves_under_base_path_with_cors() {
+        use axum::body::Body;
+        use axum::extract::connect_info::MockConnectInfo;
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let app = RpcServer::new("127.0.0.1:0".parse().unwrap(), state_with_token(&dir, None).await.node)
+            .with_cors_origins(vec!["https://wallet.example".to_string()])
+            .with_base_path(Some("/nyx/".to_string()))
+            .router()
+            .unwrap()
+            .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 4000))));
+
+        let request = Request::get("/nyx/status")
+            .header(header::ORIGIN, "https://wallet.example")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "https://wallet.example");
+
+        let request = Request::get("/status").body(Body::empty()).unwrap();
+        assert_eq!(app.oneshot(request).await.unwrap().status(), StatusCode::NOT_FOUND);
+
+        assert!(normalize_base_path(Some("nyx")).is_err());
+        assert_eq!(normalize_base_path(Some("/")).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_retried_requests_run_once() {
+        use axum::body::Body;
+        use axum::extract::connect_info::MockConnectInfo;
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let state = state_with_token(&dir, None).await;
+        let app = RpcServer::new("127.0.0.1:0".parse().unwrap(), state.node.clone())
+            .router()
+            .unwrap()
+            .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 4000))));
+        let generate = |key: &str, amount: u64| {
+            Request::post("/generate")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header("idempotency-key", key)
+                .body(Body::from(format!("{{\"amount\":{}}}", amount)))
+                .unwrap()
+        };
+
+        let initial = state.node.read().await.get_balance().await;
+        let first = app.clone().oneshot(generate("retry-1", 100)).await.unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        assert!(first.headers().get("idempotent-replayed").is_none());
+        let first = axum::body::to_bytes(first.into_body(), usize::MAX).await.unwrap();
+
+        let retried = app.clone().oneshot(generate("retry-1", 100)).await.unwrap();
+        assert_eq!(retried.headers()["idempotent-replayed"], "true");
+        assert_eq!(retried.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(axum::body::to_bytes(retried.into_body(), usize::MAX).await.unwrap(), first);
+        assert_eq!(state.node.read().await.get_balance().await, initial + 100);
+
+        // The key cannot be reused for another request
+        let reused = app.clone().oneshot(generate("retry-1", 200)).await.unwrap();
+        assert_eq!(reused.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(app.oneshot(generate("retry-2", 200)).await.unwrap().status(), StatusCode::OK);
+        assert_eq!(state.node.read().await.get_balance().await, initial + 300);
+    }
+
+    #[tokio::test]
+    async fn test_failed_and_unauthorized_requests_are_not_replayed() {
+        use axum::body::Body;
+        use axum::extract::connect_info::MockConnectInfo;
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let state = state_with_token(&dir, Some("secret")).await;
+        let app = RpcServer::new("127.0.0.1:0".parse().unwrap(), state.node.clone())
+            .with_admin_token(Some("secret".to_string()))
+            .router()
+            .unwrap()
+            .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 4000))));
+
+        // A failed send is run again on retry rather than replayed
+        let send = || {
+            Request::post("/send")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header("idempotency-key", "send-1")
+                .body(Body::from("{\"to\":\"not-an-address\",\"amount\":1}"))
+                .unwrap()
+        };
+        for _ in 0..2 {
+            let response = app.clone().oneshot(send()).await.unwrap();
+            assert!(response.headers().get("idempotent-replayed").is_none());
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            assert!(reports_failure(&body));
+        }
+
+        // An admin response is never replayed without the token
+        let verify = |token: Option<&str>| {
+            let mut request = Request::post("/admin/verify-db")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header("idempotency-key", "verify-1");
+            if let Some(token) = token {
+                request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+            }
+            request.body(Body::from("{}")).unwrap()
+        };
+        assert_eq!(app.clone().oneshot(verify(Some("secret"))).await.unwrap().status(), StatusCode::OK);
+        let replayed = app.clone().oneshot(verify(Some("secret"))).await.unwrap();
+        assert_eq!(replayed.headers()["idempotent-replayed"], "true");
+        let forged = app.clone().oneshot(verify(None)).await.unwrap();
+        assert_ne!(forged.status(), StatusCode::OK);
+        assert!(forged.headers().get("idempotent-replayed").is_none());
+        assert_ne!(app.oneshot(verify(Some("wrong"))).await.unwrap().status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn test_client_ip_behind_trusted_proxy() {
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("1.1.1.1, 2.2.2.2, 10.0.0.1"));
+
+        assert_eq!(client_ip(proxy, &headers, &[proxy]), "2.2.2.2".parse::<IpAddr>().unwrap());
+        let untrusted: IpAddr = "3.3.3.3".parse().unwrap();
+        assert_eq!(client_ip(untrusted, &headers, &[proxy]), untrusted);
+
+        headers.insert("x-forwarded-for", HeaderValue::from_static("bogus"));
+        assert_eq!(client_ip(proxy, &headers, &[proxy]), proxy);
+    }
+
+    #[tokio::test]
+    async fn test_admin_requires_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = state_with_token(&dir, Some("secret")).await;
+
+        assert!(authorize(&state, &bearer("secret")).is_ok());
+        assert_eq!(authorize(&state, &bearer("wrong")).unwrap_err().0, StatusCode::UNAUTHORIZED);
+        assert_eq!(authorize(&state, &HeaderMap::new()).unwrap_err().0, StatusCode::UNAUTHORIZED);
+
+        let disabled = state_with_token(&dir, None).await;
+        assert_eq!(authorize(&disabled, &bearer("secret")).unwrap_err().0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_outputs_resolve_by_global_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = state_with_token(&dir, None).await;
+        let minted = state.node.write().await.generate(1, 100).await.unwrap();
+        let dag = state.node.read().await.dag();
+        dag.finalize_transaction(&minted).unwrap();
+        let stealth_address = dag.storage().get_outputs(&minted).unwrap()[0].stealth_address.clone();
+
+        let query = OutputIndicesQuery { indices: "0".to_string() };
+        let Json(response) = outputs_by_index(State(state.clone()), Query(query)).await.unwrap();
+        assert_eq!(response.outputs.len(), 1);
+        assert_eq!(response.outputs[0].tx_hash, hex::encode(minted));
+        assert_eq!(response.outputs[0].stealth_address, hex::encode(stealth_address));
+
+        let query = OutputIndicesQuery { indices: format!("{}", response.output_count) };
+        let missing = outputs_by_index(State(state.clone()), Query(query)).await.unwrap_err();
+        assert_eq!(missing.0, StatusCode::NOT_FOUND);
+
+        let query = OutputIndicesQuery { indices: "x".to_string() };
+        let invalid = outputs_by_index(State(state), Query(query)).await.unwrap_err();
+        assert_eq!(invalid.0, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_mempool_list_inspect_and_evict() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = state_with_token(&dir, Some("secret")).await;
+        let minted = state.node.write().await.generate(1, 100).await.unwrap();
+        let tx = state.node.read().await.dag().storage().get_transaction(&minted).unwrap();
+        let tx_hash = state.node.read().await.mempool().add_transaction(tx).await.unwrap();
+        let hex_hash = hex::encode(tx_hash);
+
+        let Json(list) = mempool_list(State(state.clone()), Query(MempoolQuery { limit: 10 })).await;
+        assert_eq!(list.count, 1);
+        assert_eq!(list.transactions[0].tx_hash, hex_hash);
+        assert!(list.transactions[0].size > 0);
+
+        let Json(pending) = mempool_transaction(State(state.clone()), Path(hex_hash.clone())).await.unwrap();
+        assert_eq!(pending.id(), tx_hash);
+
+        let denied = admin_evict_transaction(State(state.clone()), bearer("wrong"), Path(hex_hash.clone())).await;
+        assert_eq!(denied.unwrap_err().0, StatusCode::UNAUTHORIZED);
+        let evicted = admin_evict_transaction(State(state.clone()), bearer("secret"), Path(hex_hash.clone())).await;
+        assert_eq!(evicted.unwrap(), StatusCode::NO_CONTENT);
+        let missing = mempool_transaction(State(state.clone()), Path(hex_hash.clone())).await;
+        assert_eq!(missing.unwrap_err().0, StatusCode::NOT_FOUND);
+        let again = admin_evict_transaction(State(state), bearer("secret"), Path(hex_hash)).await;
+        assert_eq!(again.unwrap_err().0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_admin_peer_controls() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = state_with_token(&dir, Some("secret")).await;
+        let ip: IpAddr = "10.1.2.3".parse().unwrap();
+
+        let denied = admin_peers(State(state.clone()), bearer("wrong")).await;
+        assert_eq!(denied.unwrap_err().0, StatusCode::UNAUTHORIZED);
+
+        let ban = BanRequest { ip, duration_secs: 60 };
+        let Json(result) = admin_ban_peer(State(state.clone()), bearer("secret"), Json(ban)).await.unwrap();
+        assert_eq!(result.disconnected, 0);
+        let Json(table) = admin_peers(State(state.clone()), bearer("secret")).await.unwrap();
+        assert!(table.peers.is_empty());
+        assert_eq!(table.banned[0].ip, "10.1.2.3");
+
+        let add = PeerAddressRequest { address: SocketAddr::new(ip, 8000) };
+        let err = admin_add_peer(State(state.clone()), bearer("secret"), Json(add)).await.unwrap_err();
+        assert_eq!(err.0, StatusCode::FORBIDDEN);
+        let remove = PeerAddressRequest { address: SocketAddr::new(ip, 8000) };
+        let err = admin_remove_peer(State(state.clone()), bearer("secret"), Json(remove)).await.unwrap_err();
+        assert_eq!(err.0, StatusCode::NOT_FOUND);
+
+        let unban = admin_unban_peer(State(state.clone()), bearer("secret"), Json(UnbanRequest { ip })).await;
+        assert_eq!(unban.unwrap(), StatusCode::NO_CONTENT);
+        let again = admin_unban_peer(State(state), bearer("secret"), Json(UnbanRequest { ip })).await;
+        assert_eq!(again.unwrap_err().0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_admin_verify_db() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = state_with_token(&dir, Some("secret")).await;
+        state.node.write().await.generate(1, 100).await.unwrap();
+
+        let request = || Json(VerifyDbRequest { repair: false });
+        let denied = admin_verify_db(State(state.clone()), bearer("wrong"), request()).await;
+        assert_eq!(denied.unwrap_err().0, StatusCode::UNAUTHORIZED);
+
+        let Json(report) = admin_verify_db(State(state), bearer("secret"), request()).await.unwrap();
+        assert!(report.checked >= 1);
+        assert!(report.issues.is_empty());
+        assert_eq!(report.repaired, 0);
+    }
+
+    #[tokio::test]
+    async fn test_admin_disk_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = state_with_token(&dir, Some("secret")).await;
+
+        let denied = admin_disk_usage(State(state.clone()), bearer("wrong")).await;
+        assert_eq!(denied.unwrap_err().0, StatusCode::UNAUTHORIZED);
+
+        let Json(usage) = admin_disk_usage(State(state), bearer("secret")).await.unwrap();
+        assert!(usage.keystore > 0);
+        assert_eq!(usage.total, usage.db + usage.keystore + usage.peers + usage.snapshots + usage.other);
+    }
+
+    #[tokio::test]
+    async fn test_admin_update_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = state_with_token(&dir, Some("secret")).await;
+
+        let update = SettingsUpdate { max_peers: Some(7), ..Default::default() };
+        let Json(settings) = admin_update_settings(State(state.clone()), bearer("secret"), Json(update))
+            .await
+            .unwrap();
+        assert_eq!(settings.max_peers, 7);
+
+        let invalid = SettingsUpdate { mempool_max_size: Some(0), ..Default::default() };
+        let err = admin_update_settings(State(state), bearer("secret"), Json(invalid)).await.unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_explorer_lookups() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = state_with_token(&dir, None).await;
+        let tx_hash = state.node.write().await.generate(1, 100).await.unwrap();
+        let tx = state.node.read().await.dag().storage().get_transaction(&tx_hash).unwrap();
+        let prefix = hex::encode(&tx.outputs[0].stealth_address[..4]);
+
+        // The indexer catches up with the DAG in the background
+        for _ in 0..100 {
+            let Json(recent) = explorer_recent(State(state.clone()), Query(RecentQuery { limit: 5 })).await.unwrap();
+            if recent.iter().any(|view| view.hash == hex::encode(tx_hash)) {
+                let query = AddressPrefixQuery { prefix, limit: 5 };
+                let Json(outputs) = explorer_outputs(State(state.clone()), Query(query)).await.unwrap();
+                assert_eq!(outputs[0].tx_hash, hex::encode(tx_hash));
+
+                let Json(stats) = explorer_stats(State(state.clone())).await.unwrap();
+                assert!(stats.transactions >= 1);
+
+                let Json(status) = explorer_key_image_status(State(state.clone()), Path(hex::encode([3u8; 32]))).await.unwrap();
+                assert!(!status.spent);
+                let invalid = explorer_key_image_status(State(state), Path("zz".to_string())).await;
+                assert!(matches!(invalid, Err((StatusCode::BAD_REQUEST, _))));
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        panic!("Transaction was never indexed");
+    }
+
+    #[tokio::test]
+    async fn test_dag_graph() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = state_with_token(&dir, None).await;
+        let tx_hash = state.node.write().await.generate(1, 100).await.unwrap();
+
+        let query = GraphQuery { center: Some(hex::encode(tx_hash)), depth: 2, format: "graphml".to_string() };
+        let (headers, body) = get_dag_graph(State(state.clone()), Query(query)).await.unwrap();
+        assert_eq!(headers[0].1, "application/graphml+xml");
+        assert!(body.contains(&hex::encode(tx_hash)));
+
+        let query = GraphQuery { center: None, depth: 2, format: "svg".to_string() };
+        assert!(matches!(get_dag_graph(State(state.clone()), Query(query)).await, Err((StatusCode::BAD_REQUEST, _))));
+        let query = GraphQuery { center: Some(hex::encode([7u8; 32])), depth: 2, format: "dot".to_string() };
+        assert!(matches!(get_dag_graph(State(state), Query(query)).await, Err((StatusCode::NOT_FOUND, _))));
+    }
+
+    #[tokio::test]
+    async fn test_double_spend_alerts_require_valid_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = state_with_token(&dir, None).await;
+
+        let invalid = double_spend_alerts(State(state.clone()), Path("zz".to_string())).await;
+        assert!(matches!(invalid, Err((StatusCode::BAD_REQUEST, _))));
+        assert!(double_spend_alerts(State(state), Path(hex::encode([1u8; 32]))).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_wallet_transactions_returns_full_transactions() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = state_with_token(&dir, None).await;
+        let tx_hash = state.node.write().await.generate(1, 100).await.unwrap();
+
+        // The indexer catches up with the DAG in the background
+        for _ in 0..100 {
+            let query = WalletTransactionsQuery { from: 0, limit: 10 };
+            let Json(transactions) = wallet_transactions(State(state.clone()), Query(query)).await.unwrap();
+            if let Some(tx) = transactions.iter().find(|tx| tx.id() == tx_hash) {
+                assert!(!tx.outputs.is_empty());
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        panic!("generated transaction was not served");
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_file_bootstraps_fresh_node() {
+        use nyx_core::consensus::{SignedSnapshot, Validator, ValidatorSet};
+        use nyx_core::finality::PosSnapshot;
+        use nyx_core::testing::test_tx;
+        use nyx_core::verification::VerifyMode;
+        use nyx_core::NetworkId;
+        use nyx_crypto::keys::ed25519_public_key;
+
+        fn tx(parents: (Hash, Hash), nonce: u8) -> Transaction {
+            test_tx(parents.0, parents.1, nonce).with_network(NetworkId::Testnet)
+        }
+
+        async fn testnet_node(dir: &tempfile::TempDir) -> crate::node::NyxNode {
+            let mut config = crate::NodeConfig::regtest();
+            config.network_id = NetworkId::Testnet;
+            config.network.listen_addr = "127.0.0.1:0".parse().unwrap();
+            config.data_dir = dir.path().to_path_buf();
+            crate::node::NyxNode::new(config).await.unwrap()
+        }
+
+        let keys: Vec<[u8; 32]> = (0..3u8).map(|i| [i; 32]).collect();
+        let validators = ValidatorSet::new(
+            keys.iter().map(|k| Validator { public_key: ed25519_public_key(k).to_vec(), stake: 10 }).collect(),
+        )
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let source = testnet_node(&dir).await;
+        let dag = source.dag();
+        let g1 = dag.storage().store_transaction(tx(([0u8; 32], [0u8; 32]), 1)).unwrap();
+        let g2 = dag.storage().store_transaction(tx(([0u8; 32], [0u8; 32]), 2)).unwrap();
+        let a = dag.add_transaction_with_mode(tx((g1, g2), 3), VerifyMode::HeadersOnly).unwrap();
+        let mut signed = SignedSnapshot::new(PosSnapshot { number: 1, anchors: vec![a] });
+        for (i, key) in keys.iter().enumerate() {
+            signed.sign(i as u32, key).unwrap();
+        }
+        source.finality().apply_signed_snapshot(&signed, &validators).unwrap();
+
+        let app = RpcServer::new("127.0.0.1:0".parse().unwrap(), Arc::new(RwLock::new(source)))
+            .with_snapshot_file(true)
+            .router()
+            .unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/snapshots/latest/file", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await
+        });
+
+        let fresh_dir = tempfile::tempdir().unwrap();
+        let fresh = testnet_node(&fresh_dir).await;
+        assert_eq!(fresh.snap_sync_from_url(&url, &validators).await.unwrap(), 3);
+        assert_eq!(fresh.finality().latest_snapshot(), 1);
+        assert!(fresh.dag().storage().get_transaction(&a).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_file_is_opt_in() {
+        use axum::body::Body;
+        use axum::extract::connect_info::MockConnectInfo;
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let node = state_with_token(&dir, None).await.node;
+        let request = || Request::get("/snapshots/latest/file").body(Body::empty()).unwrap();
+
+        let app = RpcServer::new("127.0.0.1:0".parse().unwrap(), node.clone())
+            .router()
+            .unwrap()
+            .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 4000))));
+        // Without the file route the path is taken as a snapshot number
+        assert_ne!(app.oneshot(request()).await.unwrap().status(), StatusCode::OK);
+
+        let app = RpcServer::new("127.0.0.1:0".parse().unwrap(), node)
+            .with_snapshot_file(true)
+            .router()
+            .unwrap()
+            .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 4000))));
+        // Regtest finalizes without signed snapshots, so there is no checkpoint
+        assert_eq!(app.oneshot(request()).await.unwrap().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_pruned_node_refuses_pruned_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = crate::NodeConfig::regtest();
+        config.network.listen_addr = "127.0.0.1:0".parse().unwrap();
+        config.data_dir = dir.path().to_path_buf();
+        config.dag.mode = NodeMode::Pruned;
+        config.dag.keep_snapshots = 1;
+        let node = crate::node::NyxNode::new(config).await.unwrap();
+        let state = RpcServer::new("127.0.0.1:0".parse().unwrap(), Arc::new(RwLock::new(node))).state;
+
+        let first = state.node.write().await.generate(1, 100).await.unwrap();
+        let second = state.node.write().await.generate(1, 100).await.unwrap();
+
+        // Regtest finalizes each transaction in the background
+        for _ in 0..100 {
+            if state.node.read().await.finality().is_final(&second) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        let Json(status) = get_status(State(state.clone())).await;
+        assert_eq!(status.mode, NodeMode::Pruned);
+        assert_eq!(status.pruned_through, 1);
+
+        let Err((code, reason)) = light_proof(State(state.clone()), Path(hex::encode(first))).await else {
+            panic!("proof of a pruned transaction served");
+        };
+        assert_eq!(code, StatusCode::NOT_FOUND);
+        assert!(reason.contains("pruned"));
+        assert!(light_proof(State(state.clone()), Path(hex::encode(second))).await.is_ok());
+
+        let query = GraphQuery { center: Some(hex::encode(first)), depth: 2, format: "dot".to_string() };
+        let Err((code, reason)) = get_dag_graph(State(state), Query(query)).await else {
+            panic!("graph around a pruned transaction served");
+        };
+        assert_eq!(code, StatusCode::NOT_FOUND);
+        assert!(reason.contains("pruned"));
+    }
+}
@@ -0,0 +1,136 @@
+// src/settings.rs
+
+//! Node settings that can change at runtime.
+//!
+//! The node keeps the current [`RuntimeSettings`] in a watch channel. The
+//! admin RPC sends validated [`SettingsUpdate`]s into it and each live
+//! component follows the channel, so changes take effect without a restart.
+
+use crate::config::NodeConfig;
+use crate::errors::{NodeError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Settings applied to live components
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeSettings {
+    /// Log filter directives
+    pub log_filter: String,
+
+    /// Number of peers each gossiped message is sent to (`None` for all)
+    pub gossip_fanout: Option<usize>,
+
+    /// Maximum number of connected peers
+    pub max_peers: usize,
+
+    /// Maximum number of pending transactions in the mempool
+    pub mempool_max_size: usize,
+}
+
+impl RuntimeSettings {
+    /// Initial settings from the node configuration
+    pub fn from_config(config: &NodeConfig) -> Self {
+        Self {
+            log_filter: config.logging.filter.clone(),
+            gossip_fanout: config.network.gossip_fanout,
+            max_peers: config.network.max_peers,
+            mempool_max_size: config.mempool.max_size,
+        }
+    }
+
+    /// Returns these settings with an update applied
+    ///
+    /// # Errors
+    /// - `ConfigError` if the update has an invalid log filter or a zero
+    ///   fanout, peer or mempool limit
+    pub fn apply(&self, update: SettingsUpdate) -> Result<Self> {
+        let mut settings = self.clone();
+
+        if let Some(log_filter) = update.log_filter {
+            crate::logging::parse_filter(&log_filter)?;
+            settings.log_filter = log_filter;
+        }
+        if let Some(gossip_fanout) = update.gossip_fanout {
+            if gossip_fanout == Some(0) {
+                return Err(invalid("gossip_fanout"));
+            }
+            settings.gossip_fanout = gossip_fanout;
+        }
+        if let Some(max_peers) = update.max_peers {
+            if max_peers == 0 {
+                return Err(invalid("max_peers"));
+            }
+            settings.max_peers = max_peers;
+        }
+        if let Some(mempool_max_size) = update.mempool_max_size {
+            if mempool_max_size == 0 {
+                return Err(invalid("mempool_max_size"));
+            }
+            settings.mempool_max_size = mempool_max_size;
+        }
+
+        Ok(settings)
+    }
+}
+
+fn invalid(field: &str) -> NodeError {
+    NodeError::ConfigError(format!("{} must be greater than zero", field))
+}
+
+/// Partial change to the runtime settings; absent fields are kept
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SettingsUpdate {
+    /// New log filter directives
+    pub log_filter: Option<String>,
+
+    /// New gossip fanout; `null` sends to all peers
+    #[serde(default, deserialize_with = "present")]
+    pub gossip_fanout: Option<Option<usize>>,
+
+    /// New maximum number of connected peers
+    pub max_peers: Option<usize>,
+
+    /// New maximum mempool size
+    pub mempool_max_size: Option<usize>,
+}
+
+/// Distinguishes an explicit `null` from an absent field
+fn present<'de, D>(deserializer: D) -> std::result::Result<Option<Option<usize>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_update() {
+        let settings = RuntimeSettings::from_config(&NodeConfig::default());
+        let update: SettingsUpdate = serde_json::from_str(r#"{"max_peers": 10, "gossip_fanout": 4}"#).unwrap();
+
+        let updated = settings.apply(update).unwrap();
+        assert_eq!(updated.max_peers, 10);
+        assert_eq!(updated.gossip_fanout, Some(4));
+        assert_eq!(updated.log_filter, settings.log_filter);
+        assert_eq!(updated.mempool_max_size, settings.mempool_max_size);
+
+        let update: SettingsUpdate = serde_json::from_str(r#"{"gossip_fanout": null}"#).unwrap();
+        assert_eq!(updated.apply(update).unwrap().gossip_fanout, None);
+    }
+
+    #[test]
+    fn test_invalid_update_rejected() {
+        let settings = RuntimeSettings::from_config(&NodeConfig::default());
+
+        let bad_filter = SettingsUpdate { log_filter: Some("nyx=notalevel".to_string()), ..Default::default() };
+        assert!(settings.apply(bad_filter).is_err());
+
+        let zero_peers = SettingsUpdate { max_peers: Some(0), ..Default::default() };
+        assert!(settings.apply(zero_peers).is_err());
+
+        let zero_fanout = SettingsUpdate { gossip_fanout: Some(Some(0)), ..Default::default() };
+        assert!(settings.apply(zero_fanout).is_err());
+    }
+}
@@ -0,0 +1,568 @@
+// src/snapshot_file.rs
+
+//! Snapshot file transfer over HTTP.
+//!
+//! A node can serve its latest checkpoint as a single file so that new
+//! nodes bootstrap from a plain HTTP endpoint or CDN instead of gossiping
+//! the whole finalized state. The file carries a strong `ETag` and supports
+//! single byte ranges, and [`SnapshotDownloader`] uses both to resume an
+//! interrupted download where it stopped, starting over only if the server
+//! moved on to a newer checkpoint in the meantime.
+
+use crate::{NodeError, Result};
+use axum::body::{Body, Bytes};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use http_body_util::{BodyExt, Empty};
+use hyper::{Request, Uri};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use nyx_core::checkpoint::Checkpoint;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info, warn};
+
+/// Default number of retries of an interrupted download
+pub const DEFAULT_DOWNLOAD_RETRIES: u32 = 5;
+
+/// Default time allowed between two chunks of a download
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delay before the first retry, doubled for each further one
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Encoded checkpoint served as the latest snapshot file
+#[derive(Clone, Debug)]
+pub struct SnapshotFile {
+    /// Snapshot number of the checkpoint
+    number: u64,
+
+    /// Quoted strong entity tag, the hash of the contents
+    etag: String,
+
+    /// Encoded checkpoint
+    bytes: Bytes,
+}
+
+impl SnapshotFile {
+    /// Encodes a checkpoint
+    pub fn new(checkpoint: &Checkpoint) -> Result<Self> {
+        Ok(Self::from_bytes(checkpoint.number(), checkpoint.to_bytes()?))
+    }
+
+    fn from_bytes(number: u64, bytes: Vec<u8>) -> Self {
+        let etag = format!("\"{}\"", hex::encode(nyx_core::hash::blake3_hash(&bytes)));
+        Self { number, etag, bytes: Bytes::from(bytes) }
+    }
+
+    /// Snapshot number of the checkpoint
+    pub fn number(&self) -> u64 {
+        self.number
+    }
+
+    /// Quoted entity tag
+    pub fn etag(&self) -> &str {
+        &self.etag
+    }
+
+    /// Encoded checkpoint
+    pub fn bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    /// Answers a request for the file, honouring `If-None-Match`, `Range`
+    /// and `If-Range`
+    pub fn respond(&self, headers: &HeaderMap) -> Response {
+        let etag = HeaderValue::from_str(&self.etag).expect("hex entity tag is a valid header value");
+
+        let cached = headers.get(header::IF_NONE_MATCH)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.split(',').map(str::trim).any(|tag| tag == "*" || tag == self.etag));
+        if cached {
+            return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+        }
+
+        let len = self.bytes.len();
+        let common = [
+            (header::ETAG, etag),
+            (header::ACCEPT_RANGES, HeaderValue::from_static("bytes")),
+            (header::CONTENT_TYPE, HeaderValue::from_static("application/octet-stream")),
+        ];
+
+        match self.requested_range(headers) {
+            RangeRequest::Full => {
+                (StatusCode::OK, common, Body::from(self.bytes.clone())).into_response()
+            }
+            RangeRequest::Partial(range) => {
+                let content_range = format!("bytes {}-{}/{}", range.start, range.end - 1, len);
+                (
+                    StatusCode::PARTIAL_CONTENT,
+                    common,
+                    [(header::CONTENT_RANGE, content_range)],
+                    Body::from(self.bytes.slice(range)),
+                ).into_response()
+            }
+            RangeRequest::Unsatisfiable => {
+                (
+                    StatusCode::RANGE_NOT_SATISFIABLE,
+                    common,
+                    [(header::CONTENT_RANGE, format!("bytes */{}", len))],
+                ).into_response()
+            }
+        }
+    }
+
+    fn requested_range(&self, headers: &HeaderMap) -> RangeRequest {
+        let Some(range) = headers.get(header::RANGE).and_then(|value| value.to_str().ok()) else {
+            return RangeRequest::Full;
+        };
+
+        // A range of an older file would splice two files together
+        let current = headers.get(header::IF_RANGE)
+            .is_none_or(|value| value.as_bytes() == self.etag.as_bytes());
+        if !current {
+            return RangeRequest::Full;
+        }
+
+        parse_range(range, self.bytes.len())
+    }
+}
+
+/// What part of the file a request asked for
+#[derive(Debug, PartialEq, Eq)]
+enum RangeRequest {
+    Full,
+    Partial(Range<usize>),
+    Unsatisfiable,
+}
+
+/// Parses a `Range` header of a file of `len` bytes
+///
+/// Only a single range is supported; several ranges and malformed headers
+/// are answered with the whole file, as HTTP allows.
+fn parse_range(value: &str, len: usize) -> RangeRequest {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return RangeRequest::Full;
+    };
+    if spec.contains(',') {
+        return RangeRequest::Full;
+    }
+    let Some((first, last)) = spec.split_once('-') else {
+        return RangeRequest::Full;
+    };
+
+    let (first, last) = (first.trim(), last.trim());
+    let range = if first.is_empty() {
+        // Suffix range: the last `last` bytes
+        match last.parse::<usize>() {
+            Ok(0) => return RangeRequest::Unsatisfiable,
+            Ok(suffix) => len.saturating_sub(suffix)..len,
+            Err(_) => return RangeRequest::Full,
+        }
+    } else {
+        let Ok(first) = first.parse::<usize>() else {
+            return RangeRequest::Full;
+        };
+        let end = if last.is_empty() {
+            len
+        } else {
+            match last.parse::<usize>() {
+                Ok(last) if last >= first => last.saturating_add(1).min(len),
+                _ => return RangeRequest::Full,
+            }
+        };
+        first..end
+    };
+
+    if range.start >= len {
+        RangeRequest::Unsatisfiable
+    } else {
+        RangeRequest::Partial(range)
+    }
+}
+
+/// Resumable download of a snapshot file
+///
+/// Received bytes are written to a partial file as they arrive, next to a
+/// sidecar holding the file's entity tag, so that retries and restarts of
+/// the node continue from the last received byte.
+#[derive(Clone)]
+pub struct SnapshotDownloader {
+    /// Snapshot file URL
+    url: Uri,
+
+    /// HTTP client
+    http: Client<HttpConnector, Empty<Bytes>>,
+
+    /// Partial file
+    part_path: PathBuf,
+
+    /// Retries of an interrupted download
+    retries: u32,
+
+    /// Time allowed between two chunks
+    read_timeout: Duration,
+}
+
+impl SnapshotDownloader {
+    /// Creates a downloader of `url` keeping partial data at `part_path`
+    ///
+    /// # Errors
+    /// - `ConfigError` if the URL is not an `http://` URL
+    pub fn new(url: &str, part_path: impl Into<PathBuf>) -> Result<Self> {
+        let uri: Uri = url.parse()
+            .map_err(|_| NodeError::ConfigError(format!("Invalid snapshot URL: {}", url)))?;
+        if uri.scheme_str() != Some("http") || uri.authority().is_none() {
+            return Err(NodeError::ConfigError(format!("Unsupported snapshot URL: {}", url)));
+        }
+
+        Ok(Self {
+            url: uri,
+            http: Client::builder(TokioExecutor::new()).build_http(),
+            part_path: part_path.into(),
+            retries: DEFAULT_DOWNLOAD_RETRIES,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+        })
+    }
+
+    /// Sets how many times an interrupted download is retried
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets the time allowed between two chunks
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    /// Downloads the file, resuming any partial download
+    ///
+    /// The partial file is removed once the download completes.
+    ///
+    /// # Errors
+    /// - `NetworkError` if the server keeps failing or answers with an
+    ///   unexpected status
+    /// - `IoError` if the partial file cannot be written
+    pub async fn download(&self) -> Result<Vec<u8>> {
+        let mut attempt = 0;
+        loop {
+            let error = match self.attempt().await {
+                Ok(Some(bytes)) => {
+                    remove_if_exists(&self.part_path).await?;
+                    remove_if_exists(&self.etag_path()).await?;
+                    return Ok(bytes);
+                }
+                Ok(None) => NodeError::NetworkError("Snapshot file changed during download".to_string()),
+                Err(Attempt::Retry(e)) => e,
+                Err(Attempt::Fatal(e)) => return Err(e),
+            };
+            if attempt >= self.retries {
+                return Err(error);
+            }
+            warn!("Snapshot download interrupted, retrying: {}", error);
+
+            tokio::time::sleep(RETRY_BACKOFF.saturating_mul(2u32.saturating_pow(attempt))).await;
+            attempt += 1;
+        }
+    }
+
+    /// Continues the download once
+    ///
+    /// # Returns
+    /// The whole file, or `None` if the partial data was discarded
+    async fn attempt(&self) -> std::result::Result<Option<Vec<u8>>, Attempt> {
+        let (offset, etag) = self.resume_point().await?;
+
+        let mut request = Request::get(self.url.clone());
+        if let (true, Some(etag)) = (offset > 0, &etag) {
+            request = request
+                .header(header::RANGE, format!("bytes={}-", offset))
+                .header(header::IF_RANGE, etag.as_str());
+        }
+        let request = request.body(Empty::new())
+            .map_err(|e| Attempt::Fatal(NodeError::NetworkError(format!("Invalid snapshot request: {}", e))))?;
+
+        let response = tokio::time::timeout(self.read_timeout, self.http.request(request)).await
+            .map_err(|_| Attempt::Retry(NodeError::NetworkError("Snapshot server timed out".to_string())))?
+            .map_err(|e| Attempt::Retry(NodeError::NetworkError(format!("Snapshot request failed: {}", e))))?;
+
+        let status = response.status();
+        let response_etag = response.headers().get(header::ETAG)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        let (mut file, mut received, total) = match status {
+            StatusCode::PARTIAL_CONTENT => {
+                let content_range = response.headers().get(header::CONTENT_RANGE)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(parse_content_range);
+                match content_range {
+                    Some((start, total)) if start == offset && response_etag == etag => {
+                        info!("Resuming snapshot download at {} of {} bytes", offset, total);
+                        let file = tokio::fs::OpenOptions::new().append(true).open(&self.part_path).await?;
+                        (file, offset, Some(total))
+                    }
+                    _ => {
+                        self.discard().await?;
+                        return Ok(None);
+                    }
+                }
+            }
+            StatusCode::OK => {
+                let total = response.headers().get(header::CONTENT_LENGTH)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse().ok());
+                if offset > 0 {
+                    debug!("Snapshot server sent the whole file, discarding {} partial bytes", offset);
+                }
+                let file = tokio::fs::File::create(&self.part_path).await?;
+                match &response_etag {
+                    Some(etag) => tokio::fs::write(self.etag_path(), etag).await?,
+                    None => remove_if_exists(&self.etag_path()).await?,
+                }
+                (file, 0, total)
+            }
+            StatusCode::RANGE_NOT_SATISFIABLE => {
+                self.discard().await?;
+                return Ok(None);
+            }
+            status if status.is_server_error() => {
+                return Err(Attempt::Retry(NodeError::NetworkError(format!("Snapshot server returned {}", status))));
+            }
+            status => {
+                return Err(Attempt::Fatal(NodeError::NetworkError(format!("Snapshot server returned {}", status))));
+            }
+        };
+
+        let mut body = response.into_body();
+        loop {
+            let frame = tokio::time::timeout(self.read_timeout, body.frame()).await
+                .map_err(|_| Attempt::Retry(NodeError::NetworkError("Snapshot download stalled".to_string())))?;
+            let Some(frame) = frame else { break };
+            let frame = frame
+                .map_err(|e| Attempt::Retry(NodeError::NetworkError(format!("Snapshot download failed: {}", e))))?;
+            if let Ok(data) = frame.into_data() {
+                file.write_all(&data).await?;
+                received += data.len() as u64;
+            }
+        }
+        file.flush().await?;
+        drop(file);
+
+        if total.is_some_and(|total| received < total) {
+            return Err(Attempt::Retry(NodeError::NetworkError(format!(
+                "Snapshot download ended after {} bytes",
+                received
+            ))));
+        }
+
+        Ok(Some(tokio::fs::read(&self.part_path).await?))
+    }
+
+    /// Bytes already received and the entity tag of their file
+    async fn resume_point(&self) -> std::result::Result<(u64, Option<String>), Attempt> {
+        let etag = match tokio::fs::read_to_string(self.etag_path()).await {
+            Ok(etag) => Some(etag.trim().to_string()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let offset = match tokio::fs::metadata(&self.part_path).await {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        };
+
+        // Without a tag the partial data cannot be matched to a file
+        Ok(if etag.is_some() { (offset, etag) } else { (0, None) })
+    }
+
+    /// Removes the partial download
+    async fn discard(&self) -> Result<()> {
+        remove_if_exists(&self.part_path).await?;
+        remove_if_exists(&self.etag_path()).await
+    }
+
+    fn etag_path(&self) -> PathBuf {
+        let mut path = self.part_path.clone().into_os_string();
+        path.push(".etag");
+        PathBuf::from(path)
+    }
+}
+
+impl std::fmt::Debug for SnapshotDownloader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapshotDownloader")
+            .field("url", &self.url)
+            .field("part_path", &self.part_path)
+            .field("retries", &self.retries)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Outcome of a failed download attempt
+enum Attempt {
+    /// Worth retrying, e.g. a dropped connection
+    Retry(NodeError),
+
+    /// Retrying would not help
+    Fatal(NodeError),
+}
+
+impl From<NodeError> for Attempt {
+    fn from(err: NodeError) -> Self {
+        Attempt::Fatal(err)
+    }
+}
+
+impl From<std::io::Error> for Attempt {
+    fn from(err: std::io::Error) -> Self {
+        Attempt::Fatal(err.into())
+    }
+}
+
+/// Start offset and total length of a `Content-Range: bytes a-b/len` header
+fn parse_content_range(value: &str) -> Option<(u64, u64)> {
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let (start, _) = range.split_once('-')?;
+    Some((start.parse().ok()?, total.parse().ok()?))
+}
+
+async fn remove_if_exists(path: &Path) -> Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::routing::get;
+    use axum::Router;
+    use std::sync::{Arc, Mutex};
+
+    fn file(contents: &[u8]) -> SnapshotFile {
+        SnapshotFile::from_bytes(1, contents.to_vec())
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-3", 10), RangeRequest::Partial(0..4));
+        assert_eq!(parse_range("bytes=4-", 10), RangeRequest::Partial(4..10));
+        assert_eq!(parse_range("bytes=-3", 10), RangeRequest::Partial(7..10));
+        assert_eq!(parse_range("bytes=-30", 10), RangeRequest::Partial(0..10));
+        assert_eq!(parse_range("bytes=8-100", 10), RangeRequest::Partial(8..10));
+
+        assert_eq!(parse_range("bytes=10-", 10), RangeRequest::Unsatisfiable);
+        assert_eq!(parse_range("bytes=-0", 10), RangeRequest::Unsatisfiable);
+
+        assert_eq!(parse_range("bytes=5-2", 10), RangeRequest::Full);
+        assert_eq!(parse_range("bytes=0-1,4-5", 10), RangeRequest::Full);
+        assert_eq!(parse_range("items=0-1", 10), RangeRequest::Full);
+        assert_eq!(parse_range("bytes=x-", 10), RangeRequest::Full);
+    }
+
+    #[test]
+    fn test_respond_honours_conditional_headers() {
+        let file = file(b"0123456789");
+
+        let response = file.respond(&HeaderMap::new());
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::ETAG], file.etag());
+        assert_eq!(response.headers()[header::ACCEPT_RANGES], "bytes");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RANGE, HeaderValue::from_static("bytes=4-"));
+        let response = file.respond(&headers);
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 4-9/10");
+
+        // A range of another version of the file gets the whole file
+        headers.insert(header::IF_RANGE, HeaderValue::from_static("\"stale\""));
+        assert_eq!(file.respond(&headers).status(), StatusCode::OK);
+
+        headers.insert(header::IF_RANGE, HeaderValue::from_str(file.etag()).unwrap());
+        headers.insert(header::RANGE, HeaderValue::from_static("bytes=20-"));
+        let response = file.respond(&headers);
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes */10");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(file.etag()).unwrap());
+        assert_eq!(file.respond(&headers).status(), StatusCode::NOT_MODIFIED);
+    }
+
+    /// `Range` headers of the requests served
+    type Ranges = Arc<Mutex<Vec<Option<String>>>>;
+
+    /// Serves the file, recording each request's `Range` header
+    async fn serve(file: SnapshotFile, ranges: Ranges) -> String {
+        async fn handler(State((file, ranges)): State<(SnapshotFile, Ranges)>, headers: HeaderMap) -> Response {
+            let range = headers.get(header::RANGE).map(|value| value.to_str().unwrap().to_string());
+            ranges.lock().unwrap().push(range);
+            file.respond(&headers)
+        }
+
+        let app = Router::new().route("/snapshot", get(handler)).with_state((file, ranges));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{}/snapshot", addr)
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_partial_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents: Vec<u8> = (0..=255u8).cycle().take(10_000).collect();
+        let served = file(&contents);
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let url = serve(served.clone(), ranges.clone()).await;
+
+        // An earlier attempt stopped after 4000 bytes
+        let part = dir.path().join("snapshot.part");
+        std::fs::write(&part, &contents[..4000]).unwrap();
+        std::fs::write(dir.path().join("snapshot.part.etag"), served.etag()).unwrap();
+
+        let downloader = SnapshotDownloader::new(&url, &part).unwrap();
+        assert_eq!(downloader.download().await.unwrap(), contents);
+        assert_eq!(*ranges.lock().unwrap(), vec![Some("bytes=4000-".to_string())]);
+        assert!(!part.exists());
+        assert!(!dir.path().join("snapshot.part.etag").exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_restarts_when_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let url = serve(file(b"newer snapshot"), ranges.clone()).await;
+
+        let part = dir.path().join("snapshot.part");
+        std::fs::write(&part, b"older").unwrap();
+        std::fs::write(dir.path().join("snapshot.part.etag"), file(b"older snapshot").etag()).unwrap();
+
+        let downloader = SnapshotDownloader::new(&url, &part).unwrap();
+        assert_eq!(downloader.download().await.unwrap(), b"newer snapshot");
+        assert_eq!(ranges.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_download_reports_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, Router::new()).await });
+
+        let downloader = SnapshotDownloader::new(&format!("http://{}/snapshot", addr), dir.path().join("part"))
+            .unwrap()
+            .with_retries(0);
+        assert!(matches!(downloader.download().await, Err(NodeError::NetworkError(_))));
+
+        assert!(SnapshotDownloader::new("ftp://example.com/snapshot", dir.path().join("part")).is_err());
+    }
+}
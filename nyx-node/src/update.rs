@@ -0,0 +1,480 @@
+// src/update.rs
+
+//! Release verification and upgrade checks for auto-update.
+//!
+//! With auto-update enabled, the node only runs a binary it can vouch for:
+//! one listed, under its own version, in a release manifest signed by
+//! enough of the configured release keys (see [`nyx_release`]). Manifests
+//! are made and signed with the `nyx-release` tool.
+//!
+//! With a manifest URL configured, an [`Updater`] also polls the signed
+//! version manifest announcing the latest release, and reports upgrades in
+//! the logs, at `GET /update` and to an optional webhook. An upgrade is
+//! mandatory when the release validates transaction versions this node
+//! does not.
+
+use crate::config::UpdateConfig;
+use crate::webhook::Endpoint;
+use crate::{NodeError, Result};
+use axum::body::Bytes;
+use http_body_util::{BodyExt, Empty};
+use hyper::{Request, Uri};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use nyx_core::clock::{Clock, SystemClock};
+use nyx_core::versioning::VersionRegistry;
+use nyx_core::{NetworkId, Timestamp};
+use nyx_release::{BuildManifest, SignedManifest, SignedVersionManifest, VersionManifest};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Largest version manifest fetched
+const MAX_MANIFEST_SIZE: usize = 64 * 1024;
+
+/// Time allowed to fetch the version manifest
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Shared handle to the latest upgrade check
+pub type SharedUpdateStatus = Arc<RwLock<UpdateStatus>>;
+
+/// Parses the configured release keys
+///
+/// # Errors
+/// - `ConfigError` if a key is not 32 hex-encoded bytes
+pub fn trusted_keys(config: &UpdateConfig) -> Result<Vec<[u8; 32]>> {
+    config.release_keys.iter()
+        .map(|key| {
+            hex::decode(key).ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| NodeError::ConfigError(format!("Invalid release key: {}", key)))
+        })
+        .collect()
+}
+
+/// Verifies the running binary's release if auto-update is enabled
+///
+/// # Returns
+/// The verified manifest, or `None` with auto-update disabled
+pub fn verify_release(config: &UpdateConfig) -> Result<Option<BuildManifest>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    verify_binary(config, &std::env::current_exe()?).map(Some)
+}
+
+/// Verifies that `binary` is an artifact of the configured release
+/// manifest of [`NODE_VERSION`](crate::NODE_VERSION)
+///
+/// # Errors
+/// - `ConfigError` if no manifest or release key is configured
+/// - `InitializationError` if the manifest is not signed by enough
+///   trusted keys, is for another version or does not list the binary
+pub fn verify_binary(config: &UpdateConfig, binary: &Path) -> Result<BuildManifest> {
+    let path = config.release_manifest.as_ref()
+        .ok_or_else(|| NodeError::ConfigError("Auto-update needs a release manifest".to_string()))?;
+    let keys = trusted_keys(config)?;
+    if keys.is_empty() {
+        return Err(NodeError::ConfigError("Auto-update needs at least one release key".to_string()));
+    }
+
+    let failed = |e: nyx_release::ReleaseError| NodeError::InitializationError(format!("Release check failed: {}", e));
+    let signed = SignedManifest::load(path).map_err(failed)?;
+    let manifest = signed.verify(&keys, config.release_threshold).map_err(failed)?;
+    if manifest.version != crate::NODE_VERSION {
+        return Err(NodeError::InitializationError(format!(
+            "Release manifest is for {}, node is {}",
+            manifest.version,
+            crate::NODE_VERSION
+        )));
+    }
+    manifest.verify_file(binary).map_err(failed)?;
+    Ok(manifest.clone())
+}
+
+/// Highest transaction version this node validates
+pub fn supported_protocol_version() -> u8 {
+    VersionRegistry::new().latest_version().unwrap_or(nyx_core::PROTOCOL_VERSION)
+}
+
+/// Outcome of the latest upgrade check
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateStatus {
+    /// Running version
+    pub current_version: String,
+
+    /// Highest transaction version the running node validates
+    pub protocol_version: u8,
+
+    /// Latest announced version
+    pub latest_version: Option<String>,
+
+    /// Highest transaction version the latest release validates
+    pub latest_protocol_version: Option<u8>,
+
+    /// Release notes of the latest version
+    pub notes: Option<String>,
+
+    /// Whether the latest version is newer than the running one
+    pub update_available: bool,
+
+    /// Whether the latest release validates transactions this node rejects
+    pub mandatory: bool,
+
+    /// When the version manifest was last fetched and verified
+    pub checked_at: Option<Timestamp>,
+
+    /// Why the last check failed, if it did
+    pub last_error: Option<String>,
+}
+
+impl UpdateStatus {
+    /// Status before any check
+    pub fn current() -> Self {
+        Self {
+            current_version: crate::NODE_VERSION.to_string(),
+            protocol_version: supported_protocol_version(),
+            latest_version: None,
+            latest_protocol_version: None,
+            notes: None,
+            update_available: false,
+            mandatory: false,
+            checked_at: None,
+            last_error: None,
+        }
+    }
+
+    /// Records a verified version manifest fetched at `now`
+    pub fn apply(&mut self, manifest: &VersionManifest, now: Timestamp) {
+        self.update_available = manifest.is_newer_than(&self.current_version);
+        self.mandatory = self.update_available && manifest.protocol_version > self.protocol_version;
+        self.latest_version = Some(manifest.version.clone());
+        self.latest_protocol_version = Some(manifest.protocol_version);
+        self.notes = manifest.notes.clone();
+        self.checked_at = Some(now);
+        self.last_error = None;
+    }
+}
+
+/// Upgrade announcement posted to the update webhook
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeNotice {
+    /// Network of the node
+    pub network: NetworkId,
+
+    /// Running version
+    pub current_version: String,
+
+    /// Announced version
+    pub latest_version: String,
+
+    /// Highest transaction version the announced release validates
+    pub protocol_version: u8,
+
+    /// Whether the upgrade is mandatory
+    pub mandatory: bool,
+
+    /// Release notes
+    pub notes: Option<String>,
+}
+
+/// Periodically checks the signed version manifest for upgrades
+pub struct Updater {
+    /// Version manifest URL
+    url: Uri,
+
+    /// HTTP client
+    http: Client<HttpConnector, Empty<Bytes>>,
+
+    /// Trusted release keys
+    keys: Vec<[u8; 32]>,
+
+    /// Trusted signatures the manifest needs
+    threshold: usize,
+
+    /// Time between two checks
+    interval: Duration,
+
+    /// Network reported in notices
+    network: NetworkId,
+
+    /// Where upgrades are announced
+    webhook: Option<Endpoint>,
+
+    /// Latest check, shared with the RPC server
+    status: SharedUpdateStatus,
+
+    /// Last announced (version, mandatory), so each upgrade is announced once
+    announced: Mutex<Option<(String, bool)>>,
+}
+
+impl Updater {
+    /// Creates an updater from the configuration
+    ///
+    /// # Returns
+    /// `None` unless auto-update is enabled with a manifest URL
+    ///
+    /// # Errors
+    /// - `ConfigError` if a URL or release key is invalid, or no release
+    ///   key is configured
+    pub fn new(config: &UpdateConfig, network: NetworkId) -> Result<Option<Self>> {
+        let Some(url) = config.manifest_url.as_deref().filter(|_| config.enabled) else {
+            return Ok(None);
+        };
+        let uri: Uri = url.parse()
+            .map_err(|_| NodeError::ConfigError(format!("Invalid version manifest URL: {}", url)))?;
+        if uri.scheme_str() != Some("http") || uri.authority().is_none() {
+            return Err(NodeError::ConfigError(format!("Unsupported version manifest URL: {}", url)));
+        }
+        let keys = trusted_keys(config)?;
+        if keys.is_empty() {
+            return Err(NodeError::ConfigError("Auto-update needs at least one release key".to_string()));
+        }
+
+        Ok(Some(Self {
+            url: uri,
+            http: Client::builder(TokioExecutor::new()).build_http(),
+            keys,
+            threshold: config.release_threshold,
+            interval: Duration::from_secs(config.check_interval_secs.max(1)),
+            network,
+            webhook: config.webhook.as_deref().map(Endpoint::parse).transpose()?,
+            status: Arc::new(RwLock::new(UpdateStatus::current())),
+            announced: Mutex::new(None),
+        }))
+    }
+
+    /// Shared handle to the latest check
+    pub fn status(&self) -> SharedUpdateStatus {
+        self.status.clone()
+    }
+
+    /// Fetches and verifies the version manifest
+    ///
+    /// # Errors
+    /// - `NetworkError` if the manifest cannot be fetched
+    /// - `InitializationError` if it is malformed or not signed by enough
+    ///   trusted keys
+    pub async fn fetch(&self) -> Result<VersionManifest> {
+        let request = Request::get(self.url.clone())
+            .header(hyper::header::USER_AGENT, format!("nyx-node/{}", crate::NODE_VERSION))
+            .body(Empty::new())
+            .map_err(|e| NodeError::NetworkError(format!("Invalid version manifest request: {}", e)))?;
+
+        let json = tokio::time::timeout(FETCH_TIMEOUT, async {
+            let response = self.http.request(request).await
+                .map_err(|e| NodeError::NetworkError(format!("Version manifest request failed: {}", e)))?;
+            if !response.status().is_success() {
+                return Err(NodeError::NetworkError(format!("Version manifest server returned {}", response.status())));
+            }
+
+            let mut body = response.into_body();
+            let mut json = Vec::new();
+            while let Some(frame) = body.frame().await {
+                let frame = frame
+                    .map_err(|e| NodeError::NetworkError(format!("Version manifest download failed: {}", e)))?;
+                if let Ok(data) = frame.into_data() {
+                    if json.len() + data.len() > MAX_MANIFEST_SIZE {
+                        return Err(NodeError::NetworkError("Version manifest is too large".to_string()));
+                    }
+                    json.extend_from_slice(&data);
+                }
+            }
+            Ok(json)
+        })
+        .await
+        .map_err(|_| NodeError::NetworkError("Version manifest server timed out".to_string()))??;
+
+        let failed = |e: nyx_release::ReleaseError| NodeError::InitializationError(format!("Version check failed: {}", e));
+        let signed = SignedVersionManifest::from_json(&json).map_err(failed)?;
+        Ok(signed.verify(&self.keys, self.threshold).map_err(failed)?.clone())
+    }
+
+    /// Checks for an upgrade once, announcing it if it is new
+    ///
+    /// Failures are recorded in the status as well as returned.
+    pub async fn check(&self) -> Result<UpdateStatus> {
+        let manifest = match self.fetch().await {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                self.status.write().expect("update status lock").last_error = Some(e.to_string());
+                return Err(e);
+            }
+        };
+
+        let status = {
+            let mut status = self.status.write().expect("update status lock");
+            status.apply(&manifest, SystemClock.now());
+            status.clone()
+        };
+        if status.update_available {
+            self.announce(&manifest, status.mandatory).await;
+        }
+        Ok(status)
+    }
+
+    /// Logs an upgrade and posts it to the webhook, once per version and
+    /// urgency
+    async fn announce(&self, manifest: &VersionManifest, mandatory: bool) {
+        let key = (manifest.version.clone(), mandatory);
+        if self.announced.lock().expect("announced lock").replace(key.clone()) == Some(key) {
+            return;
+        }
+
+        let notes = manifest.notes.as_deref().unwrap_or("no release notes");
+        if mandatory {
+            warn!(
+                "Mandatory upgrade to {} pending: it validates transaction version {}, this node only up to {} ({})",
+                manifest.version, manifest.protocol_version, supported_protocol_version(), notes
+            );
+        } else {
+            info!("Nyx {} is available, running {} ({})", manifest.version, crate::NODE_VERSION, notes);
+        }
+
+        let Some(webhook) = &self.webhook else { return };
+        let notice = UpgradeNotice {
+            network: self.network,
+            current_version: crate::NODE_VERSION.to_string(),
+            latest_version: manifest.version.clone(),
+            protocol_version: manifest.protocol_version,
+            mandatory,
+            notes: manifest.notes.clone(),
+        };
+        let body = serde_json::to_vec(&notice).expect("notice serializes");
+        match webhook.post(&body).await {
+            Ok(status) if (200..300).contains(&status) => {}
+            Ok(status) => warn!("Update webhook {} returned {}", webhook, status),
+            Err(e) => warn!("Update webhook {} failed: {}", webhook, e),
+        }
+    }
+
+    /// Checks for upgrades every interval in the background
+    pub fn spawn(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = self.check().await {
+                    warn!("Update check failed: {}", e);
+                }
+                tokio::time::sleep(self.interval).await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_release::{Artifact, Signable, Toolchain};
+
+    fn manifest(version: &str, binary: &Path) -> BuildManifest {
+        let toolchain = Toolchain {
+            rustc: "1.80.0".to_string(),
+            commit: String::new(),
+            host: String::new(),
+            lockfile: String::new(),
+        };
+        BuildManifest::new(version, toolchain).with_artifact(Artifact::from_file(binary).unwrap())
+    }
+
+    #[test]
+    fn test_release_verification() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("nyx-node");
+        std::fs::write(&binary, b"node binary").unwrap();
+        let path = dir.path().join("release.json");
+        let seed = [1u8; 32];
+
+        let mut config = UpdateConfig::default();
+        assert!(verify_release(&config).unwrap().is_none());
+        config.enabled = true;
+        assert!(matches!(verify_binary(&config, &binary), Err(NodeError::ConfigError(_))));
+
+        config.release_manifest = Some(path.clone());
+        config.release_keys = vec![hex::encode(nyx_crypto::keys::ed25519_public_key(&seed))];
+        manifest(crate::NODE_VERSION, &binary).sign(&seed).save(&path).unwrap();
+        assert_eq!(verify_binary(&config, &binary).unwrap().version, crate::NODE_VERSION);
+
+        // Another version, an untrusted signer or a modified binary
+        manifest("0.0.0", &binary).sign(&seed).save(&path).unwrap();
+        assert!(verify_binary(&config, &binary).is_err());
+        manifest(crate::NODE_VERSION, &binary).sign(&[2u8; 32]).save(&path).unwrap();
+        assert!(verify_binary(&config, &binary).is_err());
+        manifest(crate::NODE_VERSION, &binary).sign(&seed).save(&path).unwrap();
+        std::fs::write(&binary, b"patched").unwrap();
+        assert!(matches!(verify_binary(&config, &binary), Err(NodeError::InitializationError(_))));
+    }
+
+    #[test]
+    fn test_upgrades_are_mandatory_past_supported_protocol() {
+        let supported = supported_protocol_version();
+        let mut status = UpdateStatus::current();
+
+        status.apply(&VersionManifest::new(crate::NODE_VERSION, supported), 10);
+        assert!(!status.update_available && !status.mandatory);
+        assert_eq!(status.checked_at, Some(10));
+
+        status.apply(&VersionManifest::new("999.0.0", supported).with_notes("faster sync"), 20);
+        assert!(status.update_available && !status.mandatory);
+        assert_eq!(status.notes.as_deref(), Some("faster sync"));
+
+        status.apply(&VersionManifest::new("999.0.0", supported + 1), 30);
+        assert!(status.update_available && status.mandatory);
+
+        // An older release never requires an upgrade
+        status.apply(&VersionManifest::new("0.0.1", supported + 1), 40);
+        assert!(!status.update_available && !status.mandatory);
+    }
+
+    /// Serves `body` to every request
+    async fn serve(body: Vec<u8>) -> String {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/version.json", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut line = String::new();
+                while reader.read_line(&mut line).await.unwrap() > 0 && line != "\r\n" {
+                    line.clear();
+                }
+                let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len());
+                reader.get_mut().write_all(head.as_bytes()).await.unwrap();
+                reader.get_mut().write_all(&body).await.unwrap();
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_updater_checks_signed_manifest() {
+        let seed = [1u8; 32];
+        let manifest = VersionManifest::new("999.0.0", supported_protocol_version() + 1);
+        let mut config = UpdateConfig {
+            enabled: true,
+            release_keys: vec![hex::encode(nyx_crypto::keys::ed25519_public_key(&seed))],
+            ..UpdateConfig::default()
+        };
+        assert!(Updater::new(&config, NetworkId::Regtest).unwrap().is_none());
+
+        config.manifest_url = Some(serve(serde_json::to_vec(&manifest.clone().sign(&seed)).unwrap()).await);
+        let updater = Updater::new(&config, NetworkId::Regtest).unwrap().unwrap();
+        let status = updater.check().await.unwrap();
+        assert!(status.mandatory);
+        assert_eq!(updater.status().read().unwrap().latest_version.as_deref(), Some("999.0.0"));
+
+        // A manifest signed by an untrusted key is refused
+        config.manifest_url = Some(serve(serde_json::to_vec(&manifest.sign(&[2u8; 32])).unwrap()).await);
+        let updater = Updater::new(&config, NetworkId::Regtest).unwrap().unwrap();
+        assert!(updater.check().await.is_err());
+        let status = updater.status().read().unwrap().clone();
+        assert!(status.latest_version.is_none() && status.last_error.is_some());
+
+        config.manifest_url = Some("https://releases.example/version.json".to_string());
+        assert!(matches!(Updater::new(&config, NetworkId::Regtest), Err(NodeError::ConfigError(_))));
+    }
+}
@@ -0,0 +1,201 @@
+// src/verifier.rs
+
+//! Parallel verification of transactions received from peers.
+//!
+//! Ring signatures and range proofs are checked on tokio's blocking thread
+//! pool, at most `workers` transactions at a time, so neither the peer
+//! reader tasks nor the async runtime wait on the cryptography. Only
+//! transactions that pass are admitted to the mempool; the reason any other
+//! is refused is reported back to the network layer against its peer.
+
+use crate::errors::{NodeError, Result};
+use crate::mempool::Mempool;
+use crate::quota::Origin;
+use nyx_core::transaction::Transaction;
+use nyx_core::verification;
+use nyx_core::Hash;
+use nyx_network::{Node as NetworkNode, ReceivedTransaction};
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Pool of verification workers in front of the mempool
+#[derive(Clone)]
+pub struct VerifierPool {
+    /// Free worker slots
+    permits: Arc<Semaphore>,
+
+    /// Number of worker slots
+    workers: usize,
+
+    /// Mempool receiving verified transactions
+    mempool: Mempool,
+
+    /// Network told about rejected transactions
+    network: Option<NetworkNode>,
+}
+
+impl VerifierPool {
+    /// Creates a pool verifying up to `workers` transactions at once
+    /// (at least one)
+    pub fn new(mempool: Mempool, workers: usize) -> Self {
+        let workers = workers.max(1);
+        Self {
+            permits: Arc::new(Semaphore::new(workers)),
+            workers,
+            mempool,
+            network: None,
+        }
+    }
+
+    /// Reports transactions refused by the mempool to `network`
+    pub fn with_network(mut self, network: NetworkNode) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    /// Gets the number of worker slots
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Verifies a transaction on a worker and admits it to the mempool
+    ///
+    /// # Errors
+    /// - `CoreError` if the ring signature or a range proof is invalid
+    /// - `MempoolError` if the mempool refuses the transaction
+    pub async fn verify(&self, tx: Transaction) -> Result<Hash> {
+        let _permit = self.permits.acquire().await
+            .map_err(|e| NodeError::MempoolError(format!("Verifier closed: {}", e)))?;
+        admit(&self.mempool, tx, Origin::Local).await
+    }
+
+    /// Verifies transactions from `received` until the channel closes
+    ///
+    /// Reading pauses while every worker is busy; the channel then drops
+    /// the oldest transactions instead of slowing down the senders.
+    /// Transactions already pending are skipped. The task finishes once
+    /// in-flight verifications complete.
+    pub fn spawn(self, mut received: broadcast::Receiver<ReceivedTransaction>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let ReceivedTransaction { tx, peer } = match received.recv().await {
+                    Ok(received) => received,
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!("Verifier fell behind, dropped {} transactions", missed);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+
+                if self.mempool.get_transaction(&tx.id()).await.is_some() {
+                    continue;
+                }
+
+                let Ok(permit) = self.permits.clone().acquire_owned().await else {
+                    break;
+                };
+                let mempool = self.mempool.clone();
+                let network = self.network.clone();
+                let tx_hash = tx.id();
+                let span = nyx_network::trace::tx_span(&tx_hash);
+                tokio::spawn(async move {
+                    if let Err(e) = admit(&mempool, tx, Origin::Peer(peer.clone())).await {
+                        span.in_scope(|| debug!("Rejected received transaction: {}", e));
+                        if let Some(network) = network {
+                            network.report_rejection(&peer, &tx_hash, e.reject_code()).await;
+                        }
+                    }
+                    drop(permit);
+                });
+            }
+
+            // Wait for in-flight verifications
+            let _ = self.permits.acquire_many(self.workers as u32).await;
+        })
+    }
+}
+
+/// Runs the cryptographic checks on the blocking pool, then adds the
+/// transaction to the mempool
+async fn admit(mempool: &Mempool, tx: Transaction, origin: Origin) -> Result<Hash> {
+    let tx = tokio::task::spawn_blocking(move || {
+        verification::verify_ring_signature(&tx)?;
+        verification::verify_range_proofs(&tx)?;
+        Ok::<_, nyx_core::NyxError>(tx)
+    })
+    .await
+    .map_err(|e| NodeError::CoreError(format!("Verification task failed: {}", e)))??;
+
+    mempool.add_transaction_from(tx, origin).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_core::testing::TestTx;
+
+    fn create_signed_tx(nonce: u8) -> Transaction {
+        TestTx::new(nonce).funded().build()
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_invalid_signature() {
+        let mempool = Mempool::new(10);
+        let pool = VerifierPool::new(mempool.clone(), 2);
+
+        let mut forged = create_signed_tx(1);
+        forged.outputs[0].stealth_address = vec![0xFF];
+        assert!(matches!(pool.verify(forged).await, Err(NodeError::CoreError(_))));
+        assert_eq!(mempool.size().await, 0);
+
+        pool.verify(create_signed_tx(2)).await.unwrap();
+        assert_eq!(mempool.size().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_spawned_pool_admits_only_valid() {
+        let mempool = Mempool::new(100);
+        let (sender, received) = broadcast::channel(64);
+        let handle = VerifierPool::new(mempool.clone(), 4).spawn(received);
+
+        let mut valid = Vec::new();
+        for nonce in 0..8u8 {
+            let tx = create_signed_tx(nonce);
+            valid.push(tx.id());
+            sender.send(ReceivedTransaction { tx, peer: vec![nonce] }).unwrap();
+
+            let mut unproven = create_signed_tx(nonce + 8);
+            unproven.outputs[0].range_proof.clear();
+            sender.send(ReceivedTransaction { tx: unproven, peer: vec![nonce] }).unwrap();
+        }
+        drop(sender);
+        handle.await.unwrap();
+
+        assert_eq!(mempool.size().await, valid.len());
+        for tx_id in &valid {
+            assert!(mempool.get_transaction(tx_id).await.is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawned_pool_applies_peer_quotas() {
+        let quotas = crate::SourceQuotas::new(2, std::time::Duration::from_secs(60));
+        let mempool = Mempool::new(100).with_quotas(quotas);
+        let (sender, received) = broadcast::channel(64);
+        let handle = VerifierPool::new(mempool.clone(), 1).spawn(received);
+
+        for nonce in 0..4u8 {
+            sender.send(ReceivedTransaction { tx: create_signed_tx(nonce), peer: vec![1] }).unwrap();
+        }
+        let other = create_signed_tx(9);
+        sender.send(ReceivedTransaction { tx: other.clone(), peer: vec![2] }).unwrap();
+        drop(sender);
+        handle.await.unwrap();
+
+        assert_eq!(mempool.size().await, 3);
+        assert!(mempool.get_transaction(&other.id()).await.is_some());
+    }
+}
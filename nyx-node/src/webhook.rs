@@ -0,0 +1,443 @@
+// src/webhook.rs
+
+//! Webhook notifications for payments to watched addresses.
+//!
+//! Each configured webhook watches one address through its private view
+//! key. When a transaction with outputs paying that address is confirmed,
+//! and again when it is finalized, the dispatcher POSTs a JSON
+//! [`WebhookPayload`] to the webhook URL. Failed deliveries are retried with
+//! exponential backoff; deliveries never hold up the event stream.
+//!
+//! Only plain `http://` URLs are supported. Put a TLS-terminating proxy in
+//! front of remote endpoints.
+
+use crate::config::WebhookConfig;
+use crate::errors::{NodeError, Result};
+use crate::events::NodeEvent;
+use nyx_core::transaction::Transaction;
+use nyx_core::{Hash, NetworkId};
+use nyx_crypto::stealth;
+use nyx_wallet::account::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Timeout for one delivery attempt
+const DELIVERY_TIMEOUT_SECS: u64 = 10;
+
+/// Longest delay between delivery attempts
+const MAX_BACKOFF_SECS: u64 = 300;
+
+/// Stage a watched payment reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// The paying transaction reached the confirmation threshold
+    Confirmed,
+
+    /// The paying transaction was finalized by a PoS snapshot
+    Finalized,
+}
+
+/// JSON body POSTed to a webhook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    /// Stage the payment reached
+    pub event: WebhookEvent,
+
+    /// Network the node runs on
+    pub network: NetworkId,
+
+    /// Watched address that was paid
+    pub address: String,
+
+    /// Hex hash of the paying transaction
+    pub tx_hash: String,
+
+    /// Indices of the transaction outputs paying the address
+    pub outputs: Vec<u32>,
+}
+
+/// One configured webhook
+struct Webhook {
+    /// Where notifications go
+    endpoint: Endpoint,
+
+    /// Watched address, as configured
+    address: String,
+
+    /// Private view key of the address
+    view_key: Vec<u8>,
+
+    /// Public spend key of the address
+    spend_public: Vec<u8>,
+
+    /// Delivery attempts per notification
+    max_attempts: u32,
+
+    /// Delay before the first retry
+    initial_backoff: Duration,
+}
+
+impl Webhook {
+    /// Gets the indices of the outputs of `tx` paying the watched address
+    fn paid_outputs(&self, tx: &Transaction) -> Vec<u32> {
+        tx.outputs.iter()
+            .enumerate()
+            .filter(|(_, output)| {
+                stealth::is_mine(&output.stealth_address, &self.view_key, &self.spend_public, &output.ephemeral_pubkey)
+                    .unwrap_or(false)
+            })
+            .map(|(index, _)| index as u32)
+            .collect()
+    }
+}
+
+/// Sends webhook notifications for payments to watched addresses
+pub struct WebhookDispatcher {
+    network: NetworkId,
+    hooks: Vec<Arc<Webhook>>,
+}
+
+impl WebhookDispatcher {
+    /// Creates a dispatcher for the configured webhooks
+    ///
+    /// # Errors
+    /// - `ConfigError` if a URL is not a valid `http://` URL, or an address
+    ///   or view key is invalid or belongs to another network
+    pub fn new(configs: &[WebhookConfig], network: NetworkId) -> Result<Self> {
+        let hooks = configs.iter()
+            .map(|config| {
+                let address = Address::from_string(&config.address)
+                    .map_err(|e| NodeError::ConfigError(format!("Invalid webhook address: {}", e)))?;
+                if address.network != network {
+                    return Err(NodeError::ConfigError(format!(
+                        "Webhook address is for {}, node is on {}",
+                        address.network, network
+                    )));
+                }
+                let view_key = hex::decode(&config.view_key)
+                    .ok()
+                    .filter(|key| key.len() == 32)
+                    .ok_or_else(|| NodeError::ConfigError("Webhook view key must be 32 hex bytes".to_string()))?;
+
+                Ok(Arc::new(Webhook {
+                    endpoint: Endpoint::parse(&config.url)?,
+                    address: config.address.clone(),
+                    view_key,
+                    spend_public: address.spend_public,
+                    max_attempts: config.max_attempts.max(1),
+                    initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+                }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { network, hooks })
+    }
+
+    /// Gets the number of configured webhooks
+    pub fn len(&self) -> usize {
+        self.hooks.len()
+    }
+
+    /// Checks whether no webhooks are configured
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Watches `events` for payments until the channel closes
+    ///
+    /// Accepted transactions are checked against every watched address;
+    /// matches are notified when confirmed and when finalized, then
+    /// forgotten.
+    pub fn spawn(self, mut events: broadcast::Receiver<NodeEvent>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            // Webhook index and paid outputs of each watched transaction
+            let mut watched: HashMap<Hash, Vec<(usize, Vec<u32>)>> = HashMap::new();
+
+            loop {
+                let event = match events.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(missed)) => {
+                        warn!("Webhook dispatcher fell behind, missed {} events", missed);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+
+                match event {
+                    NodeEvent::TransactionAccepted { hash, tx } => {
+                        let payments: Vec<_> = self.hooks.iter()
+                            .enumerate()
+                            .map(|(index, hook)| (index, hook.paid_outputs(&tx)))
+                            .filter(|(_, outputs)| !outputs.is_empty())
+                            .collect();
+                        if !payments.is_empty() {
+                            watched.insert(hash, payments);
+                        }
+                    }
+                    NodeEvent::TransactionConfirmed { hash } => {
+                        if let Some(payments) = watched.get(&hash) {
+                            self.notify(WebhookEvent::Confirmed, &hash, payments);
+                        }
+                    }
+                    NodeEvent::TransactionFinalized { hash } => {
+                        if let Some(payments) = watched.remove(&hash) {
+                            self.notify(WebhookEvent::Finalized, &hash, &payments);
+                        }
+                    }
+                    NodeEvent::PeerBanned { .. } | NodeEvent::SnapshotProduced { .. } => {}
+                }
+            }
+        })
+    }
+
+    /// Starts a delivery for each paid webhook
+    fn notify(&self, event: WebhookEvent, tx_hash: &Hash, payments: &[(usize, Vec<u32>)]) {
+        for (index, outputs) in payments {
+            let hook = self.hooks[*index].clone();
+            let payload = WebhookPayload {
+                event,
+                network: self.network,
+                address: hook.address.clone(),
+                tx_hash: hex::encode(tx_hash),
+                outputs: outputs.clone(),
+            };
+            tokio::spawn(deliver(hook, payload));
+        }
+    }
+}
+
+/// POSTs a payload, retrying with exponential backoff until a 2xx response
+/// or the attempts run out
+async fn deliver(hook: Arc<Webhook>, payload: WebhookPayload) {
+    let body = match serde_json::to_vec(&payload) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Failed to encode webhook payload: {}", e);
+            return;
+        }
+    };
+
+    let mut backoff = hook.initial_backoff;
+    for attempt in 1..=hook.max_attempts {
+        let result = tokio::time::timeout(Duration::from_secs(DELIVERY_TIMEOUT_SECS), hook.endpoint.post(&body)).await;
+        match result {
+            Ok(Ok(status)) if (200..300).contains(&status) => {
+                debug!("Delivered {:?} webhook for {} to {}", payload.event, payload.tx_hash, hook.endpoint);
+                return;
+            }
+            Ok(Ok(status)) => warn!("Webhook {} answered {} (attempt {})", hook.endpoint, status, attempt),
+            Ok(Err(e)) => warn!("Webhook {} failed: {} (attempt {})", hook.endpoint, e, attempt),
+            Err(_) => warn!("Webhook {} timed out (attempt {})", hook.endpoint, attempt),
+        }
+
+        if attempt < hook.max_attempts {
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(Duration::from_secs(MAX_BACKOFF_SECS));
+        }
+    }
+
+    warn!("Dropped {:?} webhook for {} to {} after {} attempts",
+        payload.event, payload.tx_hash, hook.endpoint, hook.max_attempts);
+}
+
+/// Host, port and path of an `http://` URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Endpoint {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Endpoint {
+    /// Parses an `http://host[:port][/path]` URL
+    pub(crate) fn parse(url: &str) -> Result<Self> {
+        let invalid = || NodeError::ConfigError(format!("Invalid webhook URL (only http:// is supported): {}", url));
+
+        let rest = url.strip_prefix("http://").ok_or_else(invalid)?;
+        let (authority, path) = match rest.find('/') {
+            Some(slash) => rest.split_at(slash),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| invalid())?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self { host: host.to_string(), port, path: path.to_string() })
+    }
+
+    /// Sends a JSON POST and returns the response status code
+    pub(crate) async fn post(&self, body: &[u8]) -> std::io::Result<u16> {
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port)).await?;
+
+        let head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}:{}\r\nUser-Agent: nyx-node/{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.path, self.host, self.port, crate::NODE_VERSION, body.len()
+        );
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(body).await?;
+
+        // Status line: HTTP/1.1 200 OK
+        let mut status_line = String::new();
+        BufReader::new(stream).read_line(&mut status_line).await?;
+        status_line.split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("Bad status line: {:?}", status_line)))
+    }
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "http://{}:{}{}", self.host, self.port, self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_core::transaction::TxOutput;
+    use nyx_core::RingSignature;
+    use nyx_wallet::Account;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn test_endpoint_parse() {
+        let endpoint = Endpoint::parse("http://shop.example:8080/hooks/nyx").unwrap();
+        assert_eq!(endpoint, Endpoint { host: "shop.example".to_string(), port: 8080, path: "/hooks/nyx".to_string() });
+        assert_eq!(Endpoint::parse("http://localhost").unwrap().path, "/");
+        assert_eq!(Endpoint::parse("http://localhost").unwrap().port, 80);
+
+        assert!(Endpoint::parse("https://shop.example/hooks").is_err());
+        assert!(Endpoint::parse("http://:8080/").is_err());
+        assert!(Endpoint::parse("http://shop.example:port/").is_err());
+    }
+
+    /// Serves webhook requests, answering 503 to the first one and 200
+    /// afterwards, and reports (status, body) of each request
+    async fn flaky_server() -> (String, mpsc::UnboundedReceiver<(u16, WebhookPayload)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let (sender, received) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let mut status = 503;
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).await.unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.strip_prefix("Content-Length: ") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0u8; content_length];
+                reader.read_exact(&mut body).await.unwrap();
+
+                let response = format!("HTTP/1.1 {} Status\r\nContent-Length: 0\r\n\r\n", status);
+                reader.get_mut().write_all(response.as_bytes()).await.unwrap();
+                sender.send((status, serde_json::from_slice(&body).unwrap())).unwrap();
+                status = 200;
+            }
+        });
+
+        (url, received)
+    }
+
+    fn transaction(outputs: Vec<TxOutput>) -> Transaction {
+        let signature = RingSignature { ring_members: vec![], signature: vec![], key_image: [0u8; 32] };
+        Transaction::new(vec![], outputs, vec![signature], vec![], [0u8; 32], [0u8; 32])
+    }
+
+    fn output_to(address: &Address, seed: &[u8]) -> TxOutput {
+        let (stealth_address, ephemeral_pubkey) =
+            stealth::generate_stealth_address(&address.view_public, &address.spend_public, seed).unwrap();
+        TxOutput { stealth_address, amount_commitment: vec![], range_proof: vec![], ephemeral_pubkey, unlock_time: None, htlc: None }
+    }
+
+    #[tokio::test]
+    async fn test_payments_are_notified_with_retries() {
+        let (url, mut received) = flaky_server().await;
+        let merchant = Account::generate();
+        let mut address = merchant.get_address().clone();
+        address.network = NetworkId::Regtest;
+        let other = Account::generate().get_address().clone();
+
+        let config = WebhookConfig {
+            url,
+            address: address.to_string(),
+            view_key: hex::encode(merchant.view_private_key()),
+            max_attempts: 3,
+            initial_backoff_ms: 10,
+        };
+        let dispatcher = WebhookDispatcher::new(&[config], NetworkId::Regtest).unwrap();
+        let (events, subscriber) = broadcast::channel(16);
+        let handle = dispatcher.spawn(subscriber);
+
+        let paying = transaction(vec![output_to(&other, b"a"), output_to(&address, b"b")]);
+        let unrelated = transaction(vec![output_to(&other, b"c")]);
+        for tx in [&paying, &unrelated] {
+            events.send(NodeEvent::TransactionAccepted { hash: tx.id(), tx: Arc::new(tx.clone()) }).unwrap();
+            events.send(NodeEvent::TransactionConfirmed { hash: tx.id() }).unwrap();
+        }
+        events.send(NodeEvent::TransactionFinalized { hash: unrelated.id() }).unwrap();
+
+        // The first attempt is refused and retried
+        let (status, payload) = received.recv().await.unwrap();
+        assert_eq!((status, payload.event), (503, WebhookEvent::Confirmed));
+        let (status, payload) = received.recv().await.unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(payload, WebhookPayload {
+            event: WebhookEvent::Confirmed,
+            network: NetworkId::Regtest,
+            address: address.to_string(),
+            tx_hash: hex::encode(paying.id()),
+            outputs: vec![1],
+        });
+
+        events.send(NodeEvent::TransactionFinalized { hash: paying.id() }).unwrap();
+        let (status, payload) = received.recv().await.unwrap();
+        assert_eq!((status, payload.event, payload.outputs), (200, WebhookEvent::Finalized, vec![1]));
+
+        drop(events);
+        handle.await.unwrap();
+        assert!(received.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_rejects_address_of_other_network() {
+        let account = Account::generate();
+        let config = WebhookConfig {
+            url: "http://localhost/hooks".to_string(),
+            address: account.get_address().to_string(),
+            view_key: hex::encode(account.view_private_key()),
+            max_attempts: 1,
+            initial_backoff_ms: 0,
+        };
+
+        assert_eq!(WebhookDispatcher::new(std::slice::from_ref(&config), NetworkId::Mainnet).unwrap().len(), 1);
+        assert!(matches!(
+            WebhookDispatcher::new(&[config], NetworkId::Regtest),
+            Err(NodeError::ConfigError(_))
+        ));
+    }
+}
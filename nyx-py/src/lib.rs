@@ -0,0 +1,277 @@
+// src/lib.rs
+
+//! # Nyx Python Bindings
+//!
+//! Python module `nyx`, wrapping wallet creation, transaction building and
+//! node RPC calls so analysts and exchange integrators can script against
+//! Nyx without Rust.
+//!
+//! Built with maturin (`maturin develop` in this directory); the crate is
+//! excluded from the Cargo workspace because it needs a Python toolchain.
+//!
+//! ## Example Usage
+//!
+//! ```python
+//! import nyx
+//!
+//! node = nyx.NodeClient("http://127.0.0.1:8080")
+//! wallet = nyx.Wallet("testnet")
+//! print(wallet.mnemonic)          # write down once
+//!
+//! wallet.sync(node)
+//! tx = wallet.build_transaction("tnyx:...", 1_000, 10)
+//! parents = node.recent(2)
+//! tx = wallet.attach_parents(tx, parents[0]["hash"], parents[1]["hash"])
+//! node.submit(tx)
+//! ```
+//!
+//! Transactions are passed around as JSON strings in the format the node
+//! serves; RPC responses are converted to Python dicts and lists.
+
+#![warn(missing_docs)]
+#![warn(clippy::all)]
+#![deny(unsafe_code)]
+
+use nyx_core::{Hash, NetworkId, Timestamp, Transaction};
+use nyx_wallet::sync::{RpcSource, TransactionSource, SYNC_BATCH_SIZE};
+use nyx_wallet::{Account, Mnemonic, Wallet, WalletError};
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde_json::Value;
+
+/// Name of the account held by a Python wallet
+const ACCOUNT_NAME: &str = "default";
+
+create_exception!(nyx, NyxError, PyException, "Error raised by the Nyx wallet or node");
+
+fn to_py_err(error: WalletError) -> PyErr {
+    NyxError::new_err(error.to_string())
+}
+
+/// Wallet with one account derived from a recovery phrase
+#[pyclass(name = "Wallet", module = "nyx")]
+struct PyWallet {
+    wallet: Wallet,
+    mnemonic: Mnemonic,
+    /// Timestamp the next sync fetches from
+    synced_to: Timestamp,
+}
+
+impl PyWallet {
+    fn from_mnemonic(mnemonic: Mnemonic, network: &str) -> PyResult<Self> {
+        let network: NetworkId = network.parse().map_err(|e| NyxError::new_err(format!("{}", e)))?;
+        let mut wallet = Wallet::for_network(network);
+        wallet.add_account(Account::from_mnemonic(ACCOUNT_NAME.to_string(), &mnemonic))
+            .map_err(to_py_err)?;
+        Ok(Self { wallet, mnemonic, synced_to: 0 })
+    }
+}
+
+#[pymethods]
+impl PyWallet {
+    /// Creates a wallet with a fresh recovery phrase
+    #[new]
+    #[pyo3(signature = (network = "mainnet"))]
+    fn new(network: &str) -> PyResult<Self> {
+        Self::from_mnemonic(Mnemonic::generate(), network)
+    }
+
+    /// Restores a wallet from its recovery phrase
+    #[staticmethod]
+    #[pyo3(signature = (phrase, network = "mainnet"))]
+    fn restore(phrase: &str, network: &str) -> PyResult<Self> {
+        Self::from_mnemonic(Mnemonic::from_phrase(phrase).map_err(to_py_err)?, network)
+    }
+
+    /// Recovery phrase
+    #[getter]
+    fn mnemonic(&self) -> String {
+        self.mnemonic.phrase()
+    }
+
+    /// Receiving address
+    #[getter]
+    fn address(&self) -> PyResult<String> {
+        Ok(self.wallet.get_active_account().map_err(to_py_err)?.address.to_string())
+    }
+
+    /// Spendable balance in atomic units
+    #[getter]
+    fn balance(&self) -> u64 {
+        self.wallet.get_balance()
+    }
+
+    /// Scans a JSON transaction, returning the number of outputs credited
+    fn scan(&mut self, tx_json: &str) -> PyResult<usize> {
+        let tx = parse_transaction(tx_json)?;
+        let credited = self.wallet.sync_transaction(tx.id(), &tx).map_err(to_py_err)?;
+        Ok(credited.len())
+    }
+
+    /// Scans every transaction the node has seen since the last sync,
+    /// returning the number of outputs credited
+    fn sync(&mut self, py: Python<'_>, node: &PyNodeClient) -> PyResult<usize> {
+        let mut credited = 0;
+        loop {
+            let mut source = node.source.clone();
+            let from = self.synced_to;
+            let batch = py.allow_threads(|| source.fetch(from, SYNC_BATCH_SIZE)).map_err(to_py_err)?;
+            for tx in &batch {
+                credited += self.wallet.sync_transaction(tx.id(), tx).map_err(to_py_err)?.len();
+                self.synced_to = self.synced_to.max(tx.timestamp);
+            }
+            // A full batch within one second cannot be paged past
+            if batch.len() < SYNC_BATCH_SIZE || self.synced_to == from {
+                return Ok(credited);
+            }
+        }
+    }
+
+    /// Builds and signs a payment, returned as JSON
+    ///
+    /// The transaction approves placeholder parents; call
+    /// `attach_parents` with current DAG tips before submitting.
+    fn build_transaction(&self, to_address: &str, amount: u64, fee: u64) -> PyResult<String> {
+        let tx = self.wallet.build_transaction(to_address, amount, fee).map_err(to_py_err)?;
+        to_json(&tx)
+    }
+
+    /// Sets a JSON transaction's parents to hex-encoded DAG tips and signs
+    /// it again, returning the new JSON
+    fn attach_parents(&self, tx_json: &str, parent1: &str, parent2: &str) -> PyResult<String> {
+        let mut tx = parse_transaction(tx_json)?;
+        self.wallet.attach_parents(&mut tx, [parse_hash(parent1)?, parse_hash(parent2)?])
+            .map_err(to_py_err)?;
+        to_json(&tx)
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!("Wallet(address='{}')", self.address()?))
+    }
+}
+
+/// Client for a node's HTTP RPC
+#[pyclass(name = "NodeClient", module = "nyx")]
+struct PyNodeClient {
+    source: RpcSource,
+}
+
+impl PyNodeClient {
+    fn get(&self, py: Python<'_>, path: &str) -> PyResult<PyObject> {
+        let value = py.allow_threads(|| self.source.get_json(path)).map_err(to_py_err)?;
+        to_py(py, &value)
+    }
+}
+
+#[pymethods]
+impl PyNodeClient {
+    /// Creates a client for an endpoint such as `http://127.0.0.1:8080`
+    #[new]
+    fn new(endpoint: &str) -> PyResult<Self> {
+        Ok(Self { source: RpcSource::new(endpoint).map_err(to_py_err)? })
+    }
+
+    /// Node status
+    fn status(&self, py: Python<'_>) -> PyResult<PyObject> {
+        self.get(py, "/status")
+    }
+
+    /// DAG statistics
+    fn dag_stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+        self.get(py, "/dag/stats")
+    }
+
+    /// Indexed transaction by hex-encoded hash
+    fn transaction(&self, py: Python<'_>, tx_hash: &str) -> PyResult<PyObject> {
+        self.get(py, &format!("/explorer/tx/{}", parse_hex(tx_hash)?))
+    }
+
+    /// Most recently indexed transactions, newest first
+    #[pyo3(signature = (limit = 20))]
+    fn recent(&self, py: Python<'_>, limit: usize) -> PyResult<PyObject> {
+        self.get(py, &format!("/explorer/recent?limit={}", limit))
+    }
+
+    /// Whether a hex-encoded key image has been spent, and where
+    fn key_image_status(&self, py: Python<'_>, key_image: &str) -> PyResult<PyObject> {
+        self.get(py, &format!("/explorer/key_image/{}/spent", parse_hex(key_image)?))
+    }
+
+    /// Transactions created at or after `from_timestamp`, as JSON strings
+    /// ready for `Wallet.scan`
+    #[pyo3(signature = (from_timestamp = 0, limit = SYNC_BATCH_SIZE))]
+    fn transactions(&self, py: Python<'_>, from_timestamp: Timestamp, limit: usize) -> PyResult<Vec<String>> {
+        let mut source = self.source.clone();
+        let batch = py.allow_threads(|| source.fetch(from_timestamp, limit)).map_err(to_py_err)?;
+        batch.iter().map(to_json).collect()
+    }
+
+    /// Submits a JSON transaction, returning the node's response
+    fn submit(&self, py: Python<'_>, tx_json: &str) -> PyResult<PyObject> {
+        let tx = parse_transaction(tx_json)?;
+        let body = serde_json::json!({
+            "transaction": hex::encode(nyx_core::encoding::encode_transaction(&tx)),
+        });
+        let value = py.allow_threads(|| self.source.post_json("/submit", &body)).map_err(to_py_err)?;
+        to_py(py, &value)
+    }
+}
+
+/// Converts a JSON value into the matching Python object
+fn to_py(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    Ok(match value {
+        Value::Null => py.None(),
+        Value::Bool(b) => b.into_py(py),
+        Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(u), _) => u.into_py(py),
+            (None, Some(i)) => i.into_py(py),
+            _ => n.as_f64().unwrap_or(f64::NAN).into_py(py),
+        },
+        Value::String(s) => s.into_py(py),
+        Value::Array(items) => {
+            let list = PyList::empty_bound(py);
+            for item in items {
+                list.append(to_py(py, item)?)?;
+            }
+            list.into_py(py)
+        }
+        Value::Object(fields) => {
+            let dict = PyDict::new_bound(py);
+            for (key, field) in fields {
+                dict.set_item(key, to_py(py, field)?)?;
+            }
+            dict.into_py(py)
+        }
+    })
+}
+
+fn to_json(tx: &Transaction) -> PyResult<String> {
+    serde_json::to_string(tx).map_err(|e| NyxError::new_err(e.to_string()))
+}
+
+fn parse_transaction(json: &str) -> PyResult<Transaction> {
+    serde_json::from_str(json).map_err(|e| NyxError::new_err(format!("Invalid transaction: {}", e)))
+}
+
+fn parse_hash(hash: &str) -> PyResult<Hash> {
+    hex::decode(hash).ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| NyxError::new_err(format!("Invalid hash '{}'", hash)))
+}
+
+/// Validates a hex path segment before it is put in a URL
+fn parse_hex(s: &str) -> PyResult<&str> {
+    parse_hash(s).map(|_| s)
+}
+
+/// Nyx wallet and node RPC bindings
+#[pymodule]
+fn nyx(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyWallet>()?;
+    m.add_class::<PyNodeClient>()?;
+    m.add("NyxError", m.py().get_type_bound::<NyxError>())?;
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    Ok(())
+}
@@ -0,0 +1,53 @@
+// src/errors.rs
+
+//! Error types for release operations.
+
+use std::fmt;
+
+/// Main error type for release operations
+#[derive(Debug)]
+pub enum ReleaseError {
+    /// Manifest is malformed or of an unknown format
+    InvalidManifest(String),
+
+    /// Too few trusted keys signed the manifest
+    Untrusted(String),
+
+    /// File is not an artifact of the release
+    ArtifactMismatch(String),
+
+    /// Toolchain could not be queried
+    Toolchain(String),
+
+    /// I/O error
+    IoError(std::io::Error),
+}
+
+impl fmt::Display for ReleaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReleaseError::InvalidManifest(msg) => write!(f, "Invalid manifest: {}", msg),
+            ReleaseError::Untrusted(msg) => write!(f, "Untrusted manifest: {}", msg),
+            ReleaseError::ArtifactMismatch(msg) => write!(f, "Artifact mismatch: {}", msg),
+            ReleaseError::Toolchain(msg) => write!(f, "Toolchain error: {}", msg),
+            ReleaseError::IoError(err) => write!(f, "I/O error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ReleaseError {}
+
+impl From<std::io::Error> for ReleaseError {
+    fn from(err: std::io::Error) -> Self {
+        ReleaseError::IoError(err)
+    }
+}
+
+impl From<serde_json::Error> for ReleaseError {
+    fn from(err: serde_json::Error) -> Self {
+        ReleaseError::InvalidManifest(err.to_string())
+    }
+}
+
+/// Result type alias for release operations
+pub type Result<T> = std::result::Result<T, ReleaseError>;
@@ -0,0 +1,50 @@
+// src/lib.rs
+
+//! # Nyx Release
+//!
+//! Reproducible build manifests and signed releases.
+//!
+//! A [`BuildManifest`] lists the SHA-256 hash and size of every binary of a
+//! release, next to the toolchain it was built with: the `rustc` release,
+//! commit and host, and the hash of `Cargo.lock`. Anyone rebuilding the
+//! release with the same pins gets the same manifest, which
+//! `nyx-release compare` checks.
+//!
+//! Release keys sign the manifest as a [`SignedManifest`]. With auto-update
+//! enabled, the node refuses to start unless enough trusted keys signed a
+//! manifest listing its own binary, and polls a signed
+//! [`VersionManifest`] announcing the latest release.
+//!
+//! ## Example Usage
+//!
+//! ```rust
+//! use nyx_release::{BuildManifest, Signable, Toolchain};
+//!
+//! let toolchain = Toolchain {
+//!     rustc: "1.80.0".to_string(),
+//!     commit: "0123abcd".to_string(),
+//!     host: "x86_64-unknown-linux-gnu".to_string(),
+//!     lockfile: String::new(),
+//! };
+//! let manifest = BuildManifest::new("0.1.0", toolchain);
+//!
+//! let seed = [7u8; 32];
+//! let signed = manifest.sign(&seed);
+//! let key = nyx_crypto::keys::ed25519_public_key(&seed);
+//! assert!(signed.verify(&[key], 1).is_ok());
+//! ```
+
+#![warn(missing_docs)]
+#![warn(clippy::all)]
+#![deny(unsafe_code)]
+
+pub mod errors;
+pub mod manifest;
+pub mod signed;
+pub mod version;
+
+// Re-export commonly used types
+pub use crate::errors::{ReleaseError, Result};
+pub use crate::manifest::{hash_file, Artifact, BuildManifest, SignedManifest, Toolchain};
+pub use crate::signed::{ManifestSignature, Signable, Signed, MANIFEST_FORMAT};
+pub use crate::version::{compare_versions, SignedVersionManifest, VersionManifest};
@@ -0,0 +1,215 @@
+// src/main.rs
+
+//! Release tool entry point.
+//!
+//! - `manifest --version <v> <binary>...` writes the build manifest of a
+//!   release, honouring `SOURCE_DATE_EPOCH`
+//! - `compare <a> <b>` checks that two builds reproduce each other
+//! - `announce --version <v> --protocol <n>` writes the version manifest
+//!   nodes poll for upgrades
+//! - `keygen` draws a release key
+//! - `sign --key <seed file> <manifest>` adds a release key's signature to
+//!   a build or version manifest
+//! - `verify --trusted <key>... <signed manifest> [binary...]` checks the
+//!   signatures, and that the binaries belong to the release
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use nyx_release::{Artifact, BuildManifest, Signable, Signed, SignedManifest, Toolchain, VersionManifest};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+fn cli() -> Command {
+    Command::new("nyx-release")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Reproducible build manifests and signed releases")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("manifest")
+                .about("Writes the build manifest of a release")
+                .arg(Arg::new("version").long("version").required(true).help("Released version"))
+                .arg(Arg::new("lockfile").long("lockfile").help("Cargo.lock pinning dependencies, ./Cargo.lock by default"))
+                .arg(Arg::new("out").long("out").help("Manifest file, stdout by default"))
+                .arg(Arg::new("artifacts").num_args(1..).required(true).help("Release binaries")),
+        )
+        .subcommand(
+            Command::new("compare")
+                .about("Checks that two builds reproduce each other")
+                .arg(Arg::new("first").required(true))
+                .arg(Arg::new("second").required(true)),
+        )
+        .subcommand(
+            Command::new("announce")
+                .about("Writes the version manifest announcing a release")
+                .arg(Arg::new("version").long("version").required(true).help("Latest version"))
+                .arg(Arg::new("protocol").long("protocol").required(true).help("Highest transaction version the release validates"))
+                .arg(Arg::new("notes").long("notes").help("Release notes or a link to them"))
+                .arg(Arg::new("out").long("out").help("Manifest file, stdout by default")),
+        )
+        .subcommand(Command::new("keygen").about("Draws a release key"))
+        .subcommand(
+            Command::new("sign")
+                .about("Adds a release key's signature to a manifest")
+                .arg(Arg::new("key").long("key").required(true).help("File holding the hex key seed"))
+                .arg(Arg::new("out").long("out").help("Signed manifest file, the input by default"))
+                .arg(Arg::new("manifest").required(true)),
+        )
+        .subcommand(
+            Command::new("verify")
+                .about("Verifies a signed manifest and release binaries")
+                .arg(Arg::new("trusted").long("trusted").action(ArgAction::Append).required(true).help("Hex public key of a trusted release key"))
+                .arg(Arg::new("threshold").long("threshold").help("Trusted signatures required, 1 by default"))
+                .arg(Arg::new("manifest").required(true))
+                .arg(Arg::new("artifacts").num_args(0..).help("Binaries to check against the manifest")),
+        )
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    match cli().get_matches().subcommand() {
+        Some(("manifest", args)) => manifest(args),
+        Some(("compare", args)) => compare(args),
+        Some(("announce", args)) => announce(args),
+        Some(("keygen", _)) => {
+            let seed: [u8; 32] = rand::random();
+            println!("seed:       {}", hex::encode(seed));
+            println!("public key: {}", hex::encode(nyx_crypto::keys::ed25519_public_key(&seed)));
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(("sign", args)) => sign(args),
+        Some(("verify", args)) => verify(args),
+        _ => unreachable!("a subcommand is required"),
+    }
+}
+
+fn manifest(args: &ArgMatches) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let lockfile = args.get_one::<String>("lockfile").map_or("Cargo.lock", String::as_str);
+    let mut manifest = BuildManifest::new(args.get_one::<String>("version").unwrap(), Toolchain::detect(Path::new(lockfile))?);
+    if let Ok(epoch) = std::env::var("SOURCE_DATE_EPOCH") {
+        manifest = manifest.with_source_date_epoch(epoch.parse()?);
+    }
+    for path in args.get_many::<String>("artifacts").unwrap() {
+        manifest = manifest.with_artifact(Artifact::from_file(Path::new(path))?);
+    }
+
+    let json = serde_json::to_string_pretty(&manifest)?;
+    match args.get_one::<String>("out") {
+        Some(out) => fs::write(out, json)?,
+        None => println!("{}", json),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Reads a manifest, signed or not
+fn read_manifest(path: &Path) -> Result<BuildManifest, Box<dyn std::error::Error>> {
+    let json = fs::read_to_string(path)?;
+    match serde_json::from_str::<SignedManifest>(&json) {
+        Ok(signed) => Ok(signed.manifest),
+        Err(_) => Ok(serde_json::from_str(&json)?),
+    }
+}
+
+fn compare(args: &ArgMatches) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let first = read_manifest(Path::new(args.get_one::<String>("first").unwrap()))?;
+    let second = read_manifest(Path::new(args.get_one::<String>("second").unwrap()))?;
+    let differences = first.differences(&second);
+    if differences.is_empty() {
+        println!("Builds of {} are identical", first.version);
+        return Ok(ExitCode::SUCCESS);
+    }
+    for difference in differences {
+        println!("{}", difference);
+    }
+    Ok(ExitCode::FAILURE)
+}
+
+fn announce(args: &ArgMatches) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let mut manifest = VersionManifest::new(
+        args.get_one::<String>("version").unwrap(),
+        args.get_one::<String>("protocol").unwrap().parse()?,
+    );
+    if let Some(notes) = args.get_one::<String>("notes") {
+        manifest = manifest.with_notes(notes);
+    }
+
+    let json = serde_json::to_string_pretty(&manifest)?;
+    match args.get_one::<String>("out") {
+        Some(out) => fs::write(out, json)?,
+        None => println!("{}", json),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Checks whether manifest JSON, signed or not, is a build manifest
+/// rather than a version manifest
+fn is_build_manifest(json: &serde_json::Value) -> bool {
+    json.get("manifest").unwrap_or(json).get("artifacts").is_some()
+}
+
+fn sign(args: &ArgMatches) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let seed: [u8; 32] = hex::decode(fs::read_to_string(args.get_one::<String>("key").unwrap())?.trim())?
+        .try_into()
+        .map_err(|_| "Key seed must be 32 bytes")?;
+    let input = PathBuf::from(args.get_one::<String>("manifest").unwrap());
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&input)?)?;
+    let out = args.get_one::<String>("out").map_or(input, PathBuf::from);
+
+    let count = if is_build_manifest(&json) {
+        add_signature::<BuildManifest>(json, &seed, &out)?
+    } else {
+        add_signature::<VersionManifest>(json, &seed, &out)?
+    };
+    println!("{} signatures on {}", count, out.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Signs a manifest, signed already or not, and saves it to `out`
+fn add_signature<T: Signable>(json: serde_json::Value, seed: &[u8; 32], out: &Path) -> Result<usize, Box<dyn std::error::Error>> {
+    let signed = if json.get("signatures").is_some() {
+        let mut signed: Signed<T> = serde_json::from_value(json)?;
+        signed.add_signature(seed);
+        signed
+    } else {
+        serde_json::from_value::<T>(json)?.sign(seed)
+    };
+    signed.save(out)?;
+    Ok(signed.signatures.len())
+}
+
+fn verify(args: &ArgMatches) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let trusted = args.get_many::<String>("trusted").unwrap()
+        .map(|key| {
+            hex::decode(key).ok()
+                .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                .ok_or_else(|| format!("Invalid trusted key: {}", key))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let threshold = args.get_one::<String>("threshold").map_or(Ok(1), |t| t.parse())?;
+
+    let path = Path::new(args.get_one::<String>("manifest").unwrap());
+    let json = fs::read(path)?;
+    if !is_build_manifest(&serde_json::from_slice(&json)?) {
+        let signed = Signed::<VersionManifest>::from_json(&json)?;
+        let manifest = signed.verify(&trusted, threshold)?;
+        println!("Version {} (protocol {}) is signed by enough trusted keys", manifest.version, manifest.protocol_version);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let signed = SignedManifest::from_json(&json)?;
+    let manifest = signed.verify(&trusted, threshold)?;
+    println!("Release {} is signed by enough trusted keys", manifest.version);
+    for path in args.get_many::<String>("artifacts").into_iter().flatten() {
+        let artifact = manifest.verify_file(Path::new(path))?;
+        println!("{}: {}", artifact.name, artifact.sha256);
+    }
+    Ok(ExitCode::SUCCESS)
+}
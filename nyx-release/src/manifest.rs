@@ -0,0 +1,281 @@
+// src/manifest.rs
+
+//! Build manifests and their signatures.
+
+use crate::errors::{ReleaseError, Result};
+use crate::signed::{Signable, Signed, MANIFEST_FORMAT};
+use nyx_crypto::hash::sha256;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// SHA-256 hash of a file's contents
+pub fn hash_file(path: &Path) -> Result<[u8; 32]> {
+    Ok(sha256(&fs::read(path)?))
+}
+
+/// Toolchain a release was built with
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Toolchain {
+    /// `rustc` release, e.g. `1.80.0`
+    pub rustc: String,
+
+    /// Commit `rustc` was built from
+    pub commit: String,
+
+    /// Host triple the binaries were built on and for
+    pub host: String,
+
+    /// Hex SHA-256 hash of `Cargo.lock`, pinning every dependency
+    pub lockfile: String,
+}
+
+impl Toolchain {
+    /// Queries `rustc -vV` (or `$RUSTC`) and hashes a lockfile
+    ///
+    /// # Errors
+    /// - `Toolchain` if `rustc` cannot be run or its output is unexpected
+    pub fn detect(lockfile: &Path) -> Result<Self> {
+        let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+        let output = Command::new(&rustc)
+            .arg("-vV")
+            .output()
+            .map_err(|e| ReleaseError::Toolchain(format!("Failed to run {}: {}", rustc, e)))?;
+        if !output.status.success() {
+            return Err(ReleaseError::Toolchain(format!("{} -vV failed", rustc)));
+        }
+
+        let info = String::from_utf8_lossy(&output.stdout);
+        let field = |name: &str| {
+            info.lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix(": "))
+                .map(str::to_string)
+                .ok_or_else(|| ReleaseError::Toolchain(format!("{} -vV did not report {}", rustc, name)))
+        };
+        Ok(Self {
+            rustc: field("release")?,
+            commit: field("commit-hash")?,
+            host: field("host")?,
+            lockfile: hex::encode(hash_file(lockfile)?),
+        })
+    }
+}
+
+/// Binary of a release
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact {
+    /// File name, without directories
+    pub name: String,
+
+    /// Hex SHA-256 hash of the file
+    pub sha256: String,
+
+    /// Size in bytes
+    pub size: u64,
+}
+
+impl Artifact {
+    /// Describes the file at `path`
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let name = path.file_name()
+            .ok_or_else(|| ReleaseError::ArtifactMismatch(format!("Not a file: {}", path.display())))?
+            .to_string_lossy()
+            .into_owned();
+        Ok(Self {
+            name,
+            sha256: hex::encode(hash_file(path)?),
+            size: fs::metadata(path)?.len(),
+        })
+    }
+}
+
+/// Hashes of a release's binaries and the toolchain that built them
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildManifest {
+    /// Manifest format, [`MANIFEST_FORMAT`]
+    pub format: u32,
+
+    /// Released version, e.g. `0.1.0`
+    pub version: String,
+
+    /// `SOURCE_DATE_EPOCH` the build was pinned to, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_date_epoch: Option<u64>,
+
+    /// Toolchain the binaries were built with
+    pub toolchain: Toolchain,
+
+    /// Binaries, sorted by name
+    pub artifacts: Vec<Artifact>,
+}
+
+impl BuildManifest {
+    /// Creates a manifest without artifacts
+    pub fn new(version: &str, toolchain: Toolchain) -> Self {
+        Self {
+            format: MANIFEST_FORMAT,
+            version: version.to_string(),
+            source_date_epoch: None,
+            toolchain,
+            artifacts: Vec::new(),
+        }
+    }
+
+    /// Pins the build time to `SOURCE_DATE_EPOCH`
+    pub fn with_source_date_epoch(mut self, epoch: u64) -> Self {
+        self.source_date_epoch = Some(epoch);
+        self
+    }
+
+    /// Adds an artifact, replacing one of the same name
+    pub fn with_artifact(mut self, artifact: Artifact) -> Self {
+        self.artifacts.retain(|existing| existing.name != artifact.name);
+        let position = self.artifacts.partition_point(|existing| existing.name < artifact.name);
+        self.artifacts.insert(position, artifact);
+        self
+    }
+
+    /// Gets an artifact by name
+    pub fn artifact(&self, name: &str) -> Option<&Artifact> {
+        self.artifacts.iter().find(|artifact| artifact.name == name)
+    }
+
+    /// Checks that the file at `path` is an artifact of the release
+    ///
+    /// # Errors
+    /// - `ArtifactMismatch` if the release has no artifact of that name, or
+    ///   its hash differs
+    pub fn verify_file(&self, path: &Path) -> Result<&Artifact> {
+        let actual = Artifact::from_file(path)?;
+        let expected = self.artifact(&actual.name).ok_or_else(|| {
+            ReleaseError::ArtifactMismatch(format!("{} is not part of release {}", actual.name, self.version))
+        })?;
+        if expected.sha256 != actual.sha256 || expected.size != actual.size {
+            return Err(ReleaseError::ArtifactMismatch(format!(
+                "{} hashes to {}, release {} lists {}",
+                actual.name, actual.sha256, self.version, expected.sha256
+            )));
+        }
+        Ok(expected)
+    }
+
+    /// Lists the differences between two builds of a release, empty if
+    /// they reproduce each other
+    pub fn differences(&self, other: &BuildManifest) -> Vec<String> {
+        let mut differences = Vec::new();
+        if self.version != other.version {
+            differences.push(format!("version: {} != {}", self.version, other.version));
+        }
+        if self.toolchain != other.toolchain {
+            differences.push(format!("toolchain: {:?} != {:?}", self.toolchain, other.toolchain));
+        }
+        for artifact in &self.artifacts {
+            match other.artifact(&artifact.name) {
+                Some(theirs) if theirs == artifact => {}
+                Some(theirs) => differences.push(format!("{}: {} != {}", artifact.name, artifact.sha256, theirs.sha256)),
+                None => differences.push(format!("{}: missing from the other build", artifact.name)),
+            }
+        }
+        for artifact in &other.artifacts {
+            if self.artifact(&artifact.name).is_none() {
+                differences.push(format!("{}: missing from this build", artifact.name));
+            }
+        }
+        differences
+    }
+}
+
+impl Signable for BuildManifest {
+    const DOMAIN: &'static [u8] = b"nyx-release-manifest";
+
+    fn format(&self) -> u32 {
+        self.format
+    }
+}
+
+/// Build manifest with the signatures of release keys
+pub type SignedManifest = Signed<BuildManifest>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nyx_crypto::keys;
+
+    fn toolchain() -> Toolchain {
+        Toolchain {
+            rustc: "1.80.0".to_string(),
+            commit: "0123abcd".to_string(),
+            host: "x86_64-unknown-linux-gnu".to_string(),
+            lockfile: hex::encode([5u8; 32]),
+        }
+    }
+
+    #[test]
+    fn test_artifacts_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        let node = dir.path().join("nyx-node");
+        fs::write(&node, b"node binary").unwrap();
+        let wallet = dir.path().join("nyx-walletd");
+        fs::write(&wallet, b"wallet binary").unwrap();
+
+        let manifest = BuildManifest::new("0.1.0", toolchain())
+            .with_artifact(Artifact::from_file(&wallet).unwrap())
+            .with_artifact(Artifact::from_file(&node).unwrap());
+        assert_eq!(manifest.artifacts[0].name, "nyx-node");
+        assert_eq!(manifest.verify_file(&node).unwrap().size, 11);
+
+        fs::write(&node, b"patched binary").unwrap();
+        assert!(matches!(manifest.verify_file(&node), Err(ReleaseError::ArtifactMismatch(_))));
+        let other = dir.path().join("other");
+        fs::write(&other, b"").unwrap();
+        assert!(manifest.verify_file(&other).is_err());
+    }
+
+    #[test]
+    fn test_rebuilds_compare() {
+        let artifact = |hash: u8| Artifact { name: "nyx-node".to_string(), sha256: hex::encode([hash; 32]), size: 1 };
+        let first = BuildManifest::new("0.1.0", toolchain()).with_artifact(artifact(1));
+        assert!(first.differences(&first.clone()).is_empty());
+
+        let rebuilt = BuildManifest::new("0.1.0", toolchain()).with_artifact(artifact(2));
+        assert_eq!(first.differences(&rebuilt).len(), 1);
+        let empty = BuildManifest::new("0.1.0", toolchain());
+        assert_eq!(empty.differences(&first), vec!["nyx-node: missing from this build".to_string()]);
+    }
+
+    #[test]
+    fn test_signatures_reach_threshold() {
+        let (alice, bob, mallory) = ([1u8; 32], [2u8; 32], [3u8; 32]);
+        let trusted = [keys::ed25519_public_key(&alice), keys::ed25519_public_key(&bob)];
+
+        let mut signed = BuildManifest::new("0.1.0", toolchain()).sign(&alice);
+        assert!(signed.verify(&trusted, 1).is_ok());
+        assert!(matches!(signed.verify(&trusted, 2), Err(ReleaseError::Untrusted(_))));
+
+        // Untrusted keys and repeated signatures do not count
+        signed.add_signature(&mallory);
+        signed.add_signature(&alice);
+        assert!(signed.verify(&trusted, 2).is_err());
+        signed.add_signature(&bob);
+        assert!(signed.verify(&trusted, 2).is_ok());
+
+        // Signatures cover the whole manifest
+        signed.manifest.version = "0.1.1".to_string();
+        assert!(signed.verify(&trusted, 1).is_err());
+    }
+
+    #[test]
+    fn test_signed_manifest_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("release.json");
+        let signed = BuildManifest::new("0.1.0", toolchain()).with_source_date_epoch(1_700_000_000).sign(&[1u8; 32]);
+        signed.save(&path).unwrap();
+        assert_eq!(SignedManifest::load(&path).unwrap(), signed);
+
+        let mut future = signed.clone();
+        future.manifest.format = MANIFEST_FORMAT + 1;
+        future.save(&path).unwrap();
+        assert!(SignedManifest::load(&path).is_err());
+    }
+}
@@ -0,0 +1,125 @@
+// src/signed.rs
+
+//! Manifests signed by release keys.
+//!
+//! Release keys are Ed25519 keys. Each kind of manifest signs its JSON
+//! encoding under its own domain, so a signature on one kind is never
+//! valid on another.
+
+use crate::errors::{ReleaseError, Result};
+use nyx_crypto::keys;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Manifest format this version writes and reads
+pub const MANIFEST_FORMAT: u32 = 1;
+
+/// Manifest release keys sign
+pub trait Signable: Serialize + DeserializeOwned {
+    /// Prefix of the message release keys sign
+    const DOMAIN: &'static [u8];
+
+    /// Format the manifest was written in
+    fn format(&self) -> u32;
+
+    /// Message release keys sign
+    fn signing_message(&self) -> Vec<u8> {
+        let mut message = Self::DOMAIN.to_vec();
+        message.extend(serde_json::to_vec(self).expect("manifest serializes"));
+        message
+    }
+
+    /// Signs the manifest with a release key
+    fn sign(self, seed: &[u8; 32]) -> Signed<Self> {
+        let mut signed = Signed { manifest: self, signatures: Vec::new() };
+        signed.add_signature(seed);
+        signed
+    }
+}
+
+/// Signature of a release key over a manifest
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestSignature {
+    /// Hex Ed25519 public key
+    pub key: String,
+
+    /// Hex Ed25519 signature
+    pub signature: String,
+}
+
+/// Manifest with the signatures of release keys
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Signed<T> {
+    /// Signed manifest
+    pub manifest: T,
+
+    /// Signatures, at most one per key
+    pub signatures: Vec<ManifestSignature>,
+}
+
+impl<T: Signable> Signed<T> {
+    /// Parses a signed manifest from JSON
+    ///
+    /// # Errors
+    /// - `InvalidManifest` if the JSON is not a signed manifest of a known
+    ///   format
+    pub fn from_json(json: &[u8]) -> Result<Self> {
+        let signed: Self = serde_json::from_slice(json)?;
+        if signed.manifest.format() != MANIFEST_FORMAT {
+            return Err(ReleaseError::InvalidManifest(format!(
+                "Unknown manifest format {}",
+                signed.manifest.format()
+            )));
+        }
+        Ok(signed)
+    }
+
+    /// Loads a signed manifest from a JSON file
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::from_json(&fs::read(path)?)
+    }
+
+    /// Saves the signed manifest as JSON
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Adds the signature of another release key, replacing its previous
+    /// one
+    pub fn add_signature(&mut self, seed: &[u8; 32]) {
+        let key = hex::encode(keys::ed25519_public_key(seed));
+        let signature = hex::encode(keys::sign_ed25519(&self.manifest.signing_message(), seed));
+        self.signatures.retain(|existing| existing.key != key);
+        self.signatures.push(ManifestSignature { key, signature });
+    }
+
+    /// Checks that at least `threshold` of `trusted_keys` signed the
+    /// manifest
+    ///
+    /// # Errors
+    /// - `Untrusted` if fewer trusted keys signed it validly
+    pub fn verify(&self, trusted_keys: &[[u8; 32]], threshold: usize) -> Result<&T> {
+        let message = self.manifest.signing_message();
+        let signed = trusted_keys.iter()
+            .filter(|key| {
+                let key_hex = hex::encode(key);
+                self.signatures.iter().any(|signature| {
+                    signature.key == key_hex
+                        && hex::decode(&signature.signature)
+                            .is_ok_and(|bytes| keys::verify_ed25519(&message, &bytes, *key))
+                })
+            })
+            .count();
+        if signed < threshold.max(1) {
+            return Err(ReleaseError::Untrusted(format!(
+                "{} of {} required trusted signatures",
+                signed,
+                threshold.max(1)
+            )));
+        }
+        Ok(&self.manifest)
+    }
+}
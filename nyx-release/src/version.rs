@@ -0,0 +1,114 @@
+// src/version.rs
+
+//! Announcements of the latest release.
+//!
+//! Release keys sign a [`VersionManifest`] naming the latest version and
+//! the highest transaction version it supports. Nodes fetch it to learn of
+//! upgrades; an upgrade is mandatory once the announced protocol version is
+//! beyond what the running node validates, as the network will start
+//! accepting transactions it rejects.
+
+use crate::signed::{Signable, Signed, MANIFEST_FORMAT};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Latest release, as announced by release keys
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionManifest {
+    /// Manifest format, [`MANIFEST_FORMAT`]
+    pub format: u32,
+
+    /// Latest version, e.g. `0.2.0`
+    pub version: String,
+
+    /// Highest transaction version the release validates
+    pub protocol_version: u8,
+
+    /// Release notes or a link to them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl VersionManifest {
+    /// Announces `version`, supporting transactions up to `protocol_version`
+    pub fn new(version: &str, protocol_version: u8) -> Self {
+        Self {
+            format: MANIFEST_FORMAT,
+            version: version.to_string(),
+            protocol_version,
+            notes: None,
+        }
+    }
+
+    /// Sets the release notes
+    pub fn with_notes(mut self, notes: &str) -> Self {
+        self.notes = Some(notes.to_string());
+        self
+    }
+
+    /// Checks whether the announced version is newer than `current`
+    pub fn is_newer_than(&self, current: &str) -> bool {
+        compare_versions(&self.version, current) == Ordering::Greater
+    }
+}
+
+impl Signable for VersionManifest {
+    const DOMAIN: &'static [u8] = b"nyx-release-version";
+
+    fn format(&self) -> u32 {
+        self.format
+    }
+}
+
+/// Version manifest with the signatures of release keys
+pub type SignedVersionManifest = Signed<VersionManifest>;
+
+/// Compares dotted version numbers such as `0.10.1` and `0.9.3`
+///
+/// Components compare numerically by their leading digits, so a
+/// pre-release suffix like `-rc1` is ignored.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |version: &str| -> Vec<u64> {
+        version.split('.')
+            .map(|part| {
+                let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+                digits.parse().unwrap_or(0)
+            })
+            .collect()
+    };
+    let (mut a, mut b) = (parse(a), parse(b));
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    a.cmp(&b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::{BuildManifest, Toolchain};
+    use nyx_crypto::keys;
+
+    #[test]
+    fn test_version_ordering() {
+        assert_eq!(compare_versions("0.10.0", "0.9.3"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("0.2.0-rc1", "0.2.0"), Ordering::Equal);
+        assert!(VersionManifest::new("0.2.0", 5).is_newer_than("0.1.9"));
+        assert!(!VersionManifest::new("0.1.0", 5).is_newer_than("0.1.0"));
+    }
+
+    #[test]
+    fn test_signatures_do_not_cross_manifest_kinds() {
+        let seed = [1u8; 32];
+        let key = keys::ed25519_public_key(&seed);
+        let signed = VersionManifest::new("0.2.0", 5).with_notes("HTLCs").sign(&seed);
+        let json = serde_json::to_vec(&signed).unwrap();
+        assert_eq!(SignedVersionManifest::from_json(&json).unwrap().verify(&[key], 1).unwrap().version, "0.2.0");
+
+        // The same signature on a build manifest of the same version
+        let toolchain = Toolchain { rustc: String::new(), commit: String::new(), host: String::new(), lockfile: String::new() };
+        let build = Signed { manifest: BuildManifest::new("0.2.0", toolchain), signatures: signed.signatures };
+        assert!(build.verify(&[key], 1).is_err());
+    }
+}
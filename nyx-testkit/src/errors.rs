@@ -0,0 +1,50 @@
+// src/errors.rs
+
+//! Error types for simulations.
+
+use crate::node::NodeId;
+use std::fmt;
+
+/// Main error type for simulations
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestkitError {
+    /// Node does not exist in the simulation
+    UnknownNode(NodeId),
+
+    /// Nodes cannot be linked (e.g. a node linked to itself)
+    InvalidLink(String),
+
+    /// Core protocol error
+    CoreError(String),
+}
+
+impl fmt::Display for TestkitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestkitError::UnknownNode(id) => write!(f, "Unknown node: {}", id),
+            TestkitError::InvalidLink(msg) => write!(f, "Invalid link: {}", msg),
+            TestkitError::CoreError(msg) => write!(f, "Core error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TestkitError {}
+
+impl From<nyx_core::NyxError> for TestkitError {
+    fn from(err: nyx_core::NyxError) -> Self {
+        TestkitError::CoreError(err.to_string())
+    }
+}
+
+/// Result type alias for simulations
+pub type Result<T> = std::result::Result<T, TestkitError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(TestkitError::UnknownNode(NodeId(3)).to_string(), "Unknown node: 3");
+    }
+}
@@ -0,0 +1,59 @@
+// src/link.rs
+
+//! Simulated links between nodes.
+
+/// Delivery characteristics of a link between two nodes
+///
+/// Each message is delayed by `latency_ms` plus a uniformly random jitter of
+/// up to `jitter_ms`, and dropped with probability `loss`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkConfig {
+    /// Base one-way delay (virtual milliseconds)
+    pub latency_ms: u64,
+
+    /// Maximum extra random delay (virtual milliseconds)
+    pub jitter_ms: u64,
+
+    /// Probability that a message is lost, between 0 and 1
+    pub loss: f64,
+}
+
+impl LinkConfig {
+    /// A link with fixed latency, no jitter and no loss
+    pub fn with_latency(latency_ms: u64) -> Self {
+        Self {
+            latency_ms,
+            jitter_ms: 0,
+            loss: 0.0,
+        }
+    }
+
+    /// Sets the maximum jitter
+    pub fn jitter(mut self, jitter_ms: u64) -> Self {
+        self.jitter_ms = jitter_ms;
+        self
+    }
+
+    /// Sets the loss probability (clamped to 0..=1)
+    pub fn loss(mut self, loss: f64) -> Self {
+        self.loss = loss.clamp(0.0, 1.0);
+        self
+    }
+}
+
+impl Default for LinkConfig {
+    fn default() -> Self {
+        Self::with_latency(crate::DEFAULT_LATENCY_MS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loss_clamped() {
+        assert_eq!(LinkConfig::default().loss(1.5).loss, 1.0);
+        assert_eq!(LinkConfig::with_latency(5).loss(-1.0).loss, 0.0);
+    }
+}
@@ -0,0 +1,249 @@
+// src/node.rs
+
+//! Simulated protocol node.
+//!
+//! A [`SimNode`] owns a full DAG and finality tracker and reacts to the same
+//! [`Message`]s real nodes exchange, but instead of writing to sockets it
+//! returns the messages it wants delivered and leaves routing to the
+//! [`Simulation`](crate::Simulation).
+
+use crate::errors::Result;
+use nyx_core::checkpoint::Checkpoint;
+use nyx_core::dag::DagProcessor;
+use nyx_core::finality::FinalityTracker;
+use nyx_core::storage::MemoryStorage;
+use nyx_core::verification::VerifyMode;
+use nyx_core::{Hash, NetworkId, NyxError};
+use nyx_network::message::MessageId;
+use nyx_network::{LazyTransaction, Message, MessageType, MAX_SYNC_BATCH_SIZE};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+
+/// Identifies a node within a simulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(pub usize);
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A message a node wants delivered to a peer
+pub type Outgoing = (NodeId, Message);
+
+/// In-memory node driven by a simulation
+pub struct SimNode {
+    /// Node identifier
+    id: NodeId,
+
+    /// Local DAG
+    dag: DagProcessor,
+
+    /// Finality of the local DAG
+    finality: FinalityTracker,
+
+    /// Verification applied to received transactions
+    verify_mode: VerifyMode,
+
+    /// Linked peers, ordered so relays are deterministic
+    peers: BTreeSet<NodeId>,
+
+    /// Gossiped evidence already relayed
+    seen_evidence: HashSet<MessageId>,
+
+    /// Checkpoints served by peers, in arrival order
+    checkpoints: Vec<(NodeId, Checkpoint)>,
+
+    /// Transactions rejected by the DAG
+    rejected: usize,
+}
+
+impl SimNode {
+    /// Creates a node with an empty DAG
+    pub fn new(id: NodeId, network: NetworkId, verify_mode: VerifyMode) -> Self {
+        Self::from_dag(id, DagProcessor::new(MemoryStorage::new()).with_network(network), verify_mode)
+    }
+
+    /// Creates a node around an existing DAG
+    ///
+    /// The DAG's clock also stamps the messages the node sends.
+    pub fn from_dag(id: NodeId, dag: DagProcessor, verify_mode: VerifyMode) -> Self {
+        let finality = FinalityTracker::new(dag.clone());
+
+        Self {
+            id,
+            dag,
+            finality,
+            verify_mode,
+            peers: BTreeSet::new(),
+            seen_evidence: HashSet::new(),
+            checkpoints: Vec::new(),
+            rejected: 0,
+        }
+    }
+
+    /// Gets the node identifier
+    pub fn id(&self) -> NodeId {
+        self.id
+    }
+
+    /// Gets a handle to the node's DAG
+    pub fn dag(&self) -> &DagProcessor {
+        &self.dag
+    }
+
+    /// Gets the node's finality tracker
+    pub fn finality(&self) -> &FinalityTracker {
+        &self.finality
+    }
+
+    /// Gets the linked peers
+    pub fn peers(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.peers.iter().copied()
+    }
+
+    /// Gets the checkpoints served by peers
+    pub fn checkpoints(&self) -> &[(NodeId, Checkpoint)] {
+        &self.checkpoints
+    }
+
+    /// Number of received transactions the DAG rejected
+    pub fn rejected(&self) -> usize {
+        self.rejected
+    }
+
+    /// Checks whether the node holds a transaction
+    pub fn has_transaction(&self, tx_hash: &Hash) -> bool {
+        self.dag.storage().has_transaction(tx_hash).unwrap_or(false)
+    }
+
+    /// Hashes of all transactions in the local DAG, sorted
+    pub fn transaction_hashes(&self) -> Result<Vec<Hash>> {
+        let mut hashes = self.dag.storage().transaction_hashes()?;
+        hashes.sort_unstable();
+        Ok(hashes)
+    }
+
+    pub(crate) fn link(&mut self, peer: NodeId) {
+        self.peers.insert(peer);
+    }
+
+    pub(crate) fn unlink(&mut self, peer: &NodeId) {
+        self.peers.remove(peer);
+    }
+
+    /// Creates a message stamped with the DAG's clock
+    fn message(&self, message_type: MessageType) -> Message {
+        Message::new_at(message_type, self.dag.clock().now())
+    }
+
+    /// Addresses a message to every peer except `except`
+    pub(crate) fn relay(&self, message: &Message, except: Option<NodeId>) -> Vec<Outgoing> {
+        self.peers.iter()
+            .filter(|&&peer| Some(peer) != except)
+            .map(|&peer| (peer, message.clone()))
+            .collect()
+    }
+
+    /// Handles a message from a peer
+    ///
+    /// # Returns
+    /// Messages to deliver in response
+    pub fn handle(&mut self, from: NodeId, message: Message) -> Result<Vec<Outgoing>> {
+        match &message.message_type {
+            MessageType::Transaction(tx) => {
+                if self.has_transaction(&tx.id()) {
+                    return Ok(Vec::new());
+                }
+
+                match self.dag.add_transaction_with_mode(tx.clone(), self.verify_mode) {
+                    Ok(_) => Ok(self.relay(&message, Some(from))),
+                    Err(NyxError::InvalidParent(_)) => {
+                        // We are missing history the sender has
+                        self.rejected += 1;
+                        let request = self.message(MessageType::SyncRequest { from_height: 0 });
+                        Ok(vec![(from, request)])
+                    }
+                    Err(_) => {
+                        self.rejected += 1;
+                        Ok(Vec::new())
+                    }
+                }
+            }
+
+            MessageType::Ping => Ok(vec![(from, self.message(MessageType::Pong))]),
+
+            MessageType::SyncRequest { from_height } => {
+                // Heights are positions in topological order
+                let skip = usize::try_from(*from_height).unwrap_or(usize::MAX);
+                let transactions = self.dag.topological_order()?
+                    .skip(skip)
+                    .map(|hash| self.dag.storage().get_transaction(&hash))
+                    .collect::<nyx_core::Result<Vec<_>>>()?;
+
+                Ok(transactions.chunks(MAX_SYNC_BATCH_SIZE)
+                    .map(|batch| (from, self.message(MessageType::SyncResponse {
+                        transactions: batch.iter().map(LazyTransaction::encode).collect(),
+                    })))
+                    .collect())
+            }
+
+            MessageType::SyncResponse { transactions } => {
+                let limits = self.dag.params().limits;
+                for tx in transactions {
+                    let Ok(tx) = tx.decode(&limits) else {
+                        self.rejected += 1;
+                        continue;
+                    };
+                    if self.has_transaction(&tx.id()) {
+                        continue;
+                    }
+                    if self.dag.add_transaction_with_mode(tx.clone(), self.verify_mode).is_err() {
+                        self.rejected += 1;
+                    }
+                }
+                Ok(Vec::new())
+            }
+
+            MessageType::Evidence(_) => {
+                if !self.seen_evidence.insert(message.id) {
+                    return Ok(Vec::new());
+                }
+                Ok(self.relay(&message, Some(from)))
+            }
+
+            MessageType::SnapshotRequest => {
+                let response = self.finality.checkpoint()?
+                    .map(|checkpoint| (from, self.message(MessageType::SnapshotResponse(checkpoint))));
+                Ok(response.into_iter().collect())
+            }
+
+            MessageType::SnapshotResponse(checkpoint) => {
+                self.checkpoints.push((from, checkpoint.clone()));
+                Ok(Vec::new())
+            }
+
+            MessageType::Pong
+            | MessageType::PeerDiscovery { .. }
+            | MessageType::SyncPruned { .. }
+            | MessageType::Handshake { .. }
+            | MessageType::HandshakeAuth { .. } => Ok(Vec::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ping_answered_with_pong() {
+        let mut node = SimNode::new(NodeId(0), NetworkId::Regtest, VerifyMode::Full);
+        let replies = node.handle(NodeId(1), Message::new(MessageType::Ping)).unwrap();
+
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].0, NodeId(1));
+        assert!(matches!(replies[0].1.message_type, MessageType::Pong));
+    }
+}
@@ -0,0 +1,617 @@
+// src/sim.rs
+
+//! Discrete-event network simulation.
+//!
+//! Messages are not sent over sockets but queued with a virtual delivery
+//! time computed from the link between sender and receiver. Running the
+//! simulation delivers them in time order, lets the receiving node react and
+//! queues its responses in turn.
+//!
+//! All randomness (jitter, loss, minted outputs, and each node's DAG
+//! entropy) comes from a single RNG seeded at construction, every node reads
+//! the same virtual clock, and ties are broken by send order, so a run is
+//! fully reproducible from its seed.
+
+use crate::errors::{Result, TestkitError};
+use crate::link::LinkConfig;
+use crate::node::{NodeId, Outgoing, SimNode};
+use crate::SIM_EPOCH_SECS;
+use nyx_core::clock::{Clock, ManualClock, SharedClock};
+use nyx_core::dag::DagProcessor;
+use nyx_core::storage::MemoryStorage;
+use nyx_core::verification::VerifyMode;
+use nyx_core::{Hash, NetworkId, Transaction, TxOutput};
+use nyx_crypto::Entropy;
+use nyx_network::{Message, MessageType};
+use rand::Rng;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, BinaryHeap};
+use std::sync::Arc;
+
+/// A message in flight
+struct Delivery {
+    /// Virtual time of delivery (milliseconds)
+    at: u64,
+
+    /// Send order, breaking ties between equal delivery times
+    seq: u64,
+
+    from: NodeId,
+    to: NodeId,
+    message: Message,
+}
+
+impl PartialEq for Delivery {
+    fn eq(&self, other: &Self) -> bool {
+        (self.at, self.seq) == (other.at, other.seq)
+    }
+}
+
+impl Eq for Delivery {}
+
+impl PartialOrd for Delivery {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Delivery {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.at, self.seq).cmp(&(other.at, other.seq))
+    }
+}
+
+/// Message counters of a simulation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimStats {
+    /// Messages handed to a link
+    pub sent: u64,
+
+    /// Messages delivered to their receiver
+    pub delivered: u64,
+
+    /// Messages lost to link loss
+    pub lost: u64,
+
+    /// Messages dropped by a partition or a removed link
+    pub blocked: u64,
+}
+
+/// In-process network of simulated nodes
+pub struct Simulation {
+    /// Nodes, indexed by [`NodeId`]
+    nodes: Vec<SimNode>,
+
+    /// Links keyed by (lower, higher) node id
+    links: BTreeMap<(NodeId, NodeId), LinkConfig>,
+
+    /// Link used by [`connect`](Self::connect)
+    default_link: LinkConfig,
+
+    /// Partition group of each node; `None` when the network is whole
+    partition: Option<Vec<usize>>,
+
+    /// Messages in flight, earliest first
+    queue: BinaryHeap<Reverse<Delivery>>,
+
+    /// Current virtual time (milliseconds)
+    now: u64,
+
+    /// Next send sequence number
+    next_seq: u64,
+
+    /// Source of all randomness
+    rng: Entropy,
+
+    /// Wall clock seen by the nodes, following virtual time
+    clock: Arc<ManualClock>,
+
+    /// Network new nodes join
+    network: NetworkId,
+
+    /// Verification new nodes apply to received transactions
+    verify_mode: VerifyMode,
+
+    /// Message counters
+    stats: SimStats,
+}
+
+impl Simulation {
+    /// Creates an empty regtest simulation
+    ///
+    /// # Arguments
+    /// * `seed` - Seed for jitter, loss and minted outputs
+    pub fn new(seed: u64) -> Self {
+        Self {
+            nodes: Vec::new(),
+            links: BTreeMap::new(),
+            default_link: LinkConfig::default(),
+            partition: None,
+            queue: BinaryHeap::new(),
+            now: 0,
+            next_seq: 0,
+            rng: Entropy::seeded(seed),
+            clock: Arc::new(ManualClock::new(SIM_EPOCH_SECS)),
+            network: NetworkId::Regtest,
+            verify_mode: VerifyMode::Full,
+            stats: SimStats::default(),
+        }
+    }
+
+    /// Sets the network nodes added afterwards join
+    pub fn with_network(mut self, network: NetworkId) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Sets the verification nodes added afterwards apply
+    pub fn with_verify_mode(mut self, verify_mode: VerifyMode) -> Self {
+        self.verify_mode = verify_mode;
+        self
+    }
+
+    /// Sets the link used by [`connect`](Self::connect)
+    pub fn with_default_link(mut self, link: LinkConfig) -> Self {
+        self.default_link = link;
+        self
+    }
+
+    /// Adds a node with an empty DAG
+    pub fn add_node(&mut self) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        let dag = DagProcessor::new(MemoryStorage::new())
+            .with_network(self.network)
+            .with_entropy(self.rng.fork())
+            .with_clock(self.clock());
+        self.nodes.push(SimNode::from_dag(id, dag, self.verify_mode));
+        id
+    }
+
+    /// Adds `count` nodes
+    pub fn add_nodes(&mut self, count: usize) -> Vec<NodeId> {
+        (0..count).map(|_| self.add_node()).collect()
+    }
+
+    /// Gets a node
+    pub fn node(&self, id: NodeId) -> Result<&SimNode> {
+        self.nodes.get(id.0).ok_or(TestkitError::UnknownNode(id))
+    }
+
+    /// Gets all nodes
+    pub fn nodes(&self) -> &[SimNode] {
+        &self.nodes
+    }
+
+    /// Links two nodes with the default link
+    pub fn connect(&mut self, a: NodeId, b: NodeId) -> Result<()> {
+        self.connect_with(a, b, self.default_link)
+    }
+
+    /// Links two nodes, replacing any existing link between them
+    ///
+    /// # Errors
+    /// - `UnknownNode` if either node does not exist
+    /// - `InvalidLink` if `a` and `b` are the same node
+    pub fn connect_with(&mut self, a: NodeId, b: NodeId, link: LinkConfig) -> Result<()> {
+        self.node(a)?;
+        self.node(b)?;
+        if a == b {
+            return Err(TestkitError::InvalidLink(format!("Node {} linked to itself", a)));
+        }
+
+        self.links.insert(link_key(a, b), link);
+        self.nodes[a.0].link(b);
+        self.nodes[b.0].link(a);
+        Ok(())
+    }
+
+    /// Links every pair of nodes with the default link
+    pub fn connect_all(&mut self) -> Result<()> {
+        for a in 0..self.nodes.len() {
+            for b in a + 1..self.nodes.len() {
+                self.connect(NodeId(a), NodeId(b))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the link between two nodes; messages in flight are dropped
+    pub fn disconnect(&mut self, a: NodeId, b: NodeId) -> Result<()> {
+        self.node(a)?;
+        self.node(b)?;
+
+        self.links.remove(&link_key(a, b));
+        self.nodes[a.0].unlink(&b);
+        self.nodes[b.0].unlink(&a);
+        Ok(())
+    }
+
+    /// Splits the network into groups that cannot reach each other
+    ///
+    /// Nodes not listed in any group form one more group together. Links
+    /// are kept, so [`heal`](Self::heal) restores connectivity; messages in
+    /// flight across the split are dropped.
+    pub fn partition(&mut self, groups: &[&[NodeId]]) -> Result<()> {
+        let mut assignment = vec![groups.len(); self.nodes.len()];
+        for (group, members) in groups.iter().enumerate() {
+            for &id in *members {
+                self.node(id)?;
+                assignment[id.0] = group;
+            }
+        }
+
+        self.partition = Some(assignment);
+        Ok(())
+    }
+
+    /// Removes any partition
+    pub fn heal(&mut self) {
+        self.partition = None;
+    }
+
+    /// Checks whether a partition separates two nodes
+    pub fn is_partitioned(&self, a: NodeId, b: NodeId) -> bool {
+        self.partition.as_ref().is_some_and(|groups| groups.get(a.0) != groups.get(b.0))
+    }
+
+    /// Current virtual time (milliseconds)
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    /// Gets the clock the nodes read
+    ///
+    /// Starts at [`SIM_EPOCH_SECS`] and follows virtual time at one-second
+    /// resolution.
+    pub fn clock(&self) -> SharedClock {
+        self.clock.clone()
+    }
+
+    /// Gets the message counters
+    pub fn stats(&self) -> SimStats {
+        self.stats
+    }
+
+    /// Number of messages in flight
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Sends a message from one node to a linked peer
+    pub fn send(&mut self, from: NodeId, to: NodeId, message: Message) -> Result<()> {
+        self.node(from)?;
+        self.node(to)?;
+        self.schedule(from, to, message);
+        Ok(())
+    }
+
+    /// Sends a message from a node to all its peers
+    pub fn broadcast(&mut self, from: NodeId, message: Message) -> Result<()> {
+        let outgoing = self.node(from)?.relay(&message, None);
+        self.schedule_all(from, outgoing);
+        Ok(())
+    }
+
+    /// Adds a transaction to a node's DAG and gossips it to its peers
+    ///
+    /// The node applies its own verification mode, as for received
+    /// transactions.
+    pub fn submit(&mut self, node: NodeId, tx: Transaction) -> Result<Hash> {
+        let verify_mode = self.verify_mode;
+        let hash = self.node(node)?.dag().add_transaction_with_mode(tx.clone(), verify_mode)?;
+        self.broadcast(node, self.message(MessageType::Transaction(tx)))?;
+        Ok(hash)
+    }
+
+    /// Mints a transaction with one random output on a node and gossips it
+    ///
+    /// Only available on networks that allow minting (regtest).
+    pub fn mint(&mut self, node: NodeId) -> Result<Hash> {
+        let output = TxOutput {
+            stealth_address: self.rng.gen::<[u8; 32]>().to_vec(),
+            amount_commitment: Vec::new(),
+            range_proof: Vec::new(),
+            ephemeral_pubkey: self.rng.gen::<[u8; 32]>().to_vec(),
+            unlock_time: None,
+            htlc: None,
+        };
+
+        let dag = self.node(node)?.dag().clone();
+        let hash = dag.mint(vec![output])?;
+        let tx = dag.storage().get_transaction(&hash)?;
+        self.broadcast(node, self.message(MessageType::Transaction(tx)))?;
+        Ok(hash)
+    }
+
+    /// Creates a message stamped with the virtual clock
+    fn message(&self, message_type: MessageType) -> Message {
+        Message::new_at(message_type, self.clock.now())
+    }
+
+    /// Asks a peer for its whole DAG
+    pub fn request_sync(&mut self, node: NodeId, peer: NodeId) -> Result<()> {
+        self.send(node, peer, self.message(MessageType::SyncRequest { from_height: 0 }))
+    }
+
+    /// Delivers the next message in flight
+    ///
+    /// # Returns
+    /// `false` if nothing was in flight
+    pub fn step(&mut self) -> Result<bool> {
+        let Some(Reverse(delivery)) = self.queue.pop() else {
+            return Ok(false);
+        };
+        self.advance_to(delivery.at);
+
+        let Delivery { from, to, message, .. } = delivery;
+        if self.is_partitioned(from, to) || !self.links.contains_key(&link_key(from, to)) {
+            self.stats.blocked += 1;
+            return Ok(true);
+        }
+
+        self.stats.delivered += 1;
+        let outgoing = self.nodes[to.0].handle(from, message)?;
+        self.schedule_all(to, outgoing);
+        Ok(true)
+    }
+
+    /// Delivers messages until none are in flight
+    ///
+    /// # Returns
+    /// Number of messages processed
+    pub fn run_until_idle(&mut self) -> Result<usize> {
+        let mut steps = 0;
+        while self.step()? {
+            steps += 1;
+        }
+        Ok(steps)
+    }
+
+    /// Delivers messages due in the next `duration_ms`, then advances the clock
+    ///
+    /// # Returns
+    /// Number of messages processed
+    pub fn run_for(&mut self, duration_ms: u64) -> Result<usize> {
+        let until = self.now.saturating_add(duration_ms);
+        let mut steps = 0;
+        while self.queue.peek().is_some_and(|Reverse(next)| next.at <= until) {
+            self.step()?;
+            steps += 1;
+        }
+        self.advance_to(until);
+        Ok(steps)
+    }
+
+    /// Moves virtual time forward to `at` and the node clock with it
+    fn advance_to(&mut self, at: u64) {
+        self.now = self.now.max(at);
+        self.clock.set(SIM_EPOCH_SECS + self.now / 1000);
+    }
+
+    /// Checks whether every node holds the same transactions
+    pub fn converged(&self) -> Result<bool> {
+        let mut nodes = self.nodes.iter();
+        let Some(first) = nodes.next() else {
+            return Ok(true);
+        };
+
+        let expected = first.transaction_hashes()?;
+        for node in nodes {
+            if node.transaction_hashes()? != expected {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Queues messages produced by a node
+    fn schedule_all(&mut self, from: NodeId, outgoing: Vec<Outgoing>) {
+        for (to, message) in outgoing {
+            self.schedule(from, to, message);
+        }
+    }
+
+    /// Applies link loss and delay to a message and queues it
+    fn schedule(&mut self, from: NodeId, to: NodeId, message: Message) {
+        self.stats.sent += 1;
+
+        let Some(link) = self.links.get(&link_key(from, to)).copied() else {
+            self.stats.blocked += 1;
+            return;
+        };
+        if self.is_partitioned(from, to) {
+            self.stats.blocked += 1;
+            return;
+        }
+        if link.loss > 0.0 && self.rng.gen_bool(link.loss) {
+            self.stats.lost += 1;
+            return;
+        }
+
+        let jitter = if link.jitter_ms > 0 {
+            self.rng.gen_range(0..=link.jitter_ms)
+        } else {
+            0
+        };
+
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.queue.push(Reverse(Delivery {
+            at: self.now + link.latency_ms + jitter,
+            seq,
+            from,
+            to,
+            message,
+        }));
+    }
+}
+
+/// Key of the link between two nodes, independent of direction
+fn link_key(a: NodeId, b: NodeId) -> (NodeId, NodeId) {
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds `count` regtest nodes in a line: 0 - 1 - ... - count-1
+    fn line(seed: u64, count: usize) -> (Simulation, Vec<NodeId>) {
+        let mut sim = Simulation::new(seed).with_default_link(LinkConfig::with_latency(10));
+        let ids = sim.add_nodes(count);
+        for pair in ids.windows(2) {
+            sim.connect(pair[0], pair[1]).unwrap();
+        }
+        (sim, ids)
+    }
+
+    #[test]
+    fn test_gossip_reaches_every_node() {
+        let (mut sim, ids) = line(1, 4);
+
+        let hash = sim.mint(ids[0]).unwrap();
+        sim.run_until_idle().unwrap();
+
+        assert!(sim.nodes().iter().all(|node| node.has_transaction(&hash)));
+        assert!(sim.converged().unwrap());
+        // Three hops of 10ms each
+        assert_eq!(sim.now(), 30);
+    }
+
+    #[test]
+    fn test_run_for_respects_latency() {
+        let (mut sim, ids) = line(1, 3);
+
+        let hash = sim.mint(ids[0]).unwrap();
+        sim.run_for(15).unwrap();
+        assert!(sim.node(ids[1]).unwrap().has_transaction(&hash));
+        assert!(!sim.node(ids[2]).unwrap().has_transaction(&hash));
+
+        sim.run_for(5).unwrap();
+        assert!(sim.node(ids[2]).unwrap().has_transaction(&hash));
+        assert_eq!(sim.now(), 20);
+    }
+
+    #[test]
+    fn test_same_seed_same_run() {
+        let run = |seed| {
+            let mut sim = Simulation::new(seed)
+                .with_default_link(LinkConfig::with_latency(10).jitter(20).loss(0.3));
+            sim.add_nodes(6);
+            sim.connect_all().unwrap();
+            for i in 0..6 {
+                sim.mint(NodeId(i)).unwrap();
+            }
+            sim.run_until_idle().unwrap();
+
+            let hashes: Vec<_> = sim.nodes().iter()
+                .map(|node| node.transaction_hashes().unwrap())
+                .collect();
+            (sim.stats(), sim.now(), hashes)
+        };
+
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7).0, run(8).0);
+    }
+
+    #[test]
+    fn test_node_randomness_follows_seed() {
+        let run = |seed| {
+            let mut sim = Simulation::new(seed);
+            let id = sim.add_node();
+            for _ in 0..8 {
+                sim.mint(id).unwrap();
+            }
+            sim.run_for(2_500).unwrap();
+            assert_eq!(sim.clock().now(), SIM_EPOCH_SECS + 2);
+
+            let dag = sim.node(id).unwrap().dag();
+            (0..16).map(|_| dag.sample_tip().unwrap().unwrap()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(run(9), run(9));
+        assert_ne!(run(9), run(10));
+    }
+
+    #[test]
+    fn test_total_loss_drops_everything() {
+        let mut sim = Simulation::new(1).with_default_link(LinkConfig::default().loss(1.0));
+        let ids = sim.add_nodes(2);
+        sim.connect(ids[0], ids[1]).unwrap();
+
+        let hash = sim.mint(ids[0]).unwrap();
+        sim.run_until_idle().unwrap();
+
+        assert!(!sim.node(ids[1]).unwrap().has_transaction(&hash));
+        assert_eq!(sim.stats().lost, 1);
+    }
+
+    #[test]
+    fn test_partition_and_heal() {
+        let mut sim = Simulation::new(3);
+        let ids = sim.add_nodes(4);
+        sim.connect_all().unwrap();
+
+        sim.partition(&[&ids[..2]]).unwrap();
+        assert!(sim.is_partitioned(ids[0], ids[3]));
+        assert!(!sim.is_partitioned(ids[2], ids[3]));
+
+        let left = sim.mint(ids[0]).unwrap();
+        let right = sim.mint(ids[3]).unwrap();
+        sim.run_until_idle().unwrap();
+
+        assert!(sim.node(ids[1]).unwrap().has_transaction(&left));
+        assert!(!sim.node(ids[2]).unwrap().has_transaction(&left));
+        assert!(sim.node(ids[2]).unwrap().has_transaction(&right));
+        assert!(!sim.converged().unwrap());
+
+        sim.heal();
+        sim.request_sync(ids[0], ids[3]).unwrap();
+        sim.request_sync(ids[3], ids[0]).unwrap();
+        sim.run_until_idle().unwrap();
+        assert!(sim.node(ids[0]).unwrap().has_transaction(&right));
+        assert!(sim.node(ids[3]).unwrap().has_transaction(&left));
+
+        sim.request_sync(ids[1], ids[0]).unwrap();
+        sim.request_sync(ids[2], ids[3]).unwrap();
+        sim.run_until_idle().unwrap();
+        assert!(sim.converged().unwrap());
+    }
+
+    #[test]
+    fn test_missing_parents_trigger_sync() {
+        use nyx_core::testing::TestTx;
+
+        let mut sim = Simulation::new(5).with_verify_mode(VerifyMode::HeadersOnly);
+        let ids = sim.add_nodes(2);
+
+        // Node 0 builds history while node 1 is not linked yet
+        let a = sim.mint(ids[0]).unwrap();
+        let b = sim.mint(ids[0]).unwrap();
+        sim.connect(ids[0], ids[1]).unwrap();
+
+        let mut child = TestTx::new(1)
+            .parents(a, b)
+            .spends(a, 0)
+            .stealth_address(vec![2u8; 32])
+            .build()
+            .with_network(NetworkId::Regtest);
+        child.timestamp = sim.clock().now();
+        let child = sim.submit(ids[0], child).unwrap();
+        sim.run_until_idle().unwrap();
+
+        let late = sim.node(ids[1]).unwrap();
+        assert_eq!(late.rejected(), 1);
+        assert!(late.has_transaction(&child));
+        assert!(sim.converged().unwrap());
+    }
+
+    #[test]
+    fn test_invalid_links_rejected() {
+        let mut sim = Simulation::new(1);
+        let id = sim.add_node();
+
+        assert_eq!(sim.connect(id, id), Err(TestkitError::InvalidLink("Node 0 linked to itself".to_string())));
+        assert_eq!(sim.connect(id, NodeId(9)), Err(TestkitError::UnknownNode(NodeId(9))));
+    }
+}
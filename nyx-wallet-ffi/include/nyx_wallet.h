@@ -0,0 +1,72 @@
+/*
+ * nyx_wallet.h
+ *
+ * C interface to the Nyx wallet core (nyx-wallet-ffi).
+ *
+ * Strings passed in are NUL-terminated UTF-8 owned by the caller. Strings
+ * returned are owned by the caller and must be released with
+ * nyx_string_free. Failure is signalled by NULL or a negative number; the
+ * message is available from nyx_last_error on the same thread.
+ */
+
+#ifndef NYX_WALLET_H
+#define NYX_WALLET_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Opaque wallet handle. Not thread-safe. */
+typedef struct NyxWallet NyxWallet;
+
+/* Creates a wallet with a fresh recovery phrase on "mainnet", "testnet",
+ * "devnet" or "regtest". */
+NyxWallet *nyx_wallet_create(const char *network);
+
+/* Restores a wallet from its 33-word recovery phrase. */
+NyxWallet *nyx_wallet_restore_mnemonic(const char *phrase, const char *network);
+
+/* Releases a wallet handle. Accepts NULL. */
+void nyx_wallet_free(NyxWallet *wallet);
+
+/* Recovery phrase of the wallet. */
+char *nyx_wallet_mnemonic(const NyxWallet *wallet);
+
+/* Address of the wallet's account. */
+char *nyx_wallet_address(const NyxWallet *wallet);
+
+/* Spendable balance in atomic units; 0 on failure. */
+uint64_t nyx_wallet_balance(const NyxWallet *wallet);
+
+/* Scans a JSON transaction; returns the number of outputs credited, or -1. */
+int32_t nyx_wallet_scan(NyxWallet *wallet, const char *tx_json);
+
+/* Records the global index and hex-encoded stealth address a scanned output
+ * got when confirmed, as served by the node's /outputs/by-index; only
+ * confirmed outputs can be spent. Returns 0, or -1 on failure. */
+int32_t nyx_wallet_confirm_output(NyxWallet *wallet, const char *tx_hash,
+                                  uint32_t index, uint64_t global_index,
+                                  const char *stealth_address);
+
+/* Builds and signs a payment against placeholder parents; returns JSON. */
+char *nyx_wallet_build_tx(const NyxWallet *wallet, const char *to_address,
+                          uint64_t amount, uint64_t fee);
+
+/* Sets the transaction's parents to hex-encoded DAG tips and signs it;
+ * returns JSON. */
+char *nyx_wallet_sign_tx(const NyxWallet *wallet, const char *tx_json,
+                         const char *parent1, const char *parent2);
+
+/* Message of this thread's last failure, or NULL. */
+char *nyx_last_error(void);
+
+/* Releases a string returned by this library. Accepts NULL. */
+void nyx_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* NYX_WALLET_H */
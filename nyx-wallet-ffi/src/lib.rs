@@ -0,0 +1,403 @@
+// src/lib.rs
+
+//! # Nyx Wallet FFI
+//!
+//! Stable C ABI over the Nyx wallet core, so iOS and Android wallets can
+//! embed it. The declarations live in `include/nyx_wallet.h`.
+//!
+//! ## Conventions
+//!
+//! - A wallet is an opaque `NyxWallet *` handle, created by
+//!   `nyx_wallet_create` or `nyx_wallet_restore_mnemonic` and released with
+//!   `nyx_wallet_free`. Handles must not be shared between threads without
+//!   external locking.
+//! - Strings passed in are NUL-terminated UTF-8 and stay owned by the
+//!   caller. Strings returned are owned by the caller and must be released
+//!   with `nyx_string_free`.
+//! - Failure is signalled by a null pointer or a negative number. The
+//!   message of the calling thread's last failure is returned by
+//!   `nyx_last_error`. Panics are caught and reported the same way.
+//! - Transactions cross the boundary as JSON, in the format the node's
+//!   `/wallet/transactions` endpoint serves.
+//!
+//! ## Example Usage
+//!
+//! ```c
+//! NyxWallet *wallet = nyx_wallet_create("testnet");
+//! char *phrase = nyx_wallet_mnemonic(wallet);  // show once for backup
+//! char *address = nyx_wallet_address(wallet);
+//!
+//! nyx_string_free(address);
+//! nyx_string_free(phrase);
+//! nyx_wallet_free(wallet);
+//! ```
+
+#![warn(missing_docs)]
+#![warn(clippy::all)]
+
+use nyx_core::{Hash, NetworkId, Transaction};
+use nyx_wallet::{Account, Mnemonic, Result, RingMember, Wallet, WalletError};
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// Name of the account held by an FFI wallet
+const ACCOUNT_NAME: &str = "default";
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Opaque wallet handle
+pub struct NyxWallet {
+    wallet: Wallet,
+    mnemonic: Mnemonic,
+}
+
+impl NyxWallet {
+    fn from_mnemonic(mnemonic: Mnemonic, network: NetworkId) -> Result<Self> {
+        let mut wallet = Wallet::for_network(network);
+        wallet.add_account(Account::from_mnemonic(ACCOUNT_NAME.to_string(), &mnemonic))?;
+        Ok(Self { wallet, mnemonic })
+    }
+}
+
+/// Creates a wallet with a freshly generated recovery phrase
+///
+/// Returns null on failure.
+///
+/// # Safety
+/// `network` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn nyx_wallet_create(network: *const c_char) -> *mut NyxWallet {
+    guard(ptr::null_mut(), || {
+        let network = parse_network(read_str(network)?)?;
+        let wallet = NyxWallet::from_mnemonic(Mnemonic::generate(), network)?;
+        Ok(Box::into_raw(Box::new(wallet)))
+    })
+}
+
+/// Restores a wallet from its recovery phrase
+///
+/// Returns null on failure, including a mistyped phrase.
+///
+/// # Safety
+/// `phrase` and `network` must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn nyx_wallet_restore_mnemonic(
+    phrase: *const c_char,
+    network: *const c_char,
+) -> *mut NyxWallet {
+    guard(ptr::null_mut(), || {
+        let mnemonic = Mnemonic::from_phrase(read_str(phrase)?)?;
+        let network = parse_network(read_str(network)?)?;
+        let wallet = NyxWallet::from_mnemonic(mnemonic, network)?;
+        Ok(Box::into_raw(Box::new(wallet)))
+    })
+}
+
+/// Releases a wallet handle
+///
+/// # Safety
+/// `wallet` must be null or a handle returned by this library that has not
+/// been freed.
+#[no_mangle]
+pub unsafe extern "C" fn nyx_wallet_free(wallet: *mut NyxWallet) {
+    if !wallet.is_null() {
+        drop(Box::from_raw(wallet));
+    }
+}
+
+/// Recovery phrase of the wallet
+///
+/// Returns null on failure.
+///
+/// # Safety
+/// `wallet` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn nyx_wallet_mnemonic(wallet: *const NyxWallet) -> *mut c_char {
+    guard(ptr::null_mut(), || to_c_string(wallet_ref(wallet)?.mnemonic.phrase()))
+}
+
+/// Address of the wallet's account
+///
+/// Returns null on failure.
+///
+/// # Safety
+/// `wallet` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn nyx_wallet_address(wallet: *const NyxWallet) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        to_c_string(wallet_ref(wallet)?.wallet.get_active_account()?.address.to_string())
+    })
+}
+
+/// Spendable balance of the wallet, in atomic units
+///
+/// Returns 0 on failure.
+///
+/// # Safety
+/// `wallet` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn nyx_wallet_balance(wallet: *const NyxWallet) -> u64 {
+    guard(0, || Ok(wallet_ref(wallet)?.wallet.get_balance()))
+}
+
+/// Scans a transaction, crediting outputs paying the wallet and removing
+/// the ones it spends
+///
+/// Returns the number of outputs credited, or -1 on failure.
+///
+/// # Safety
+/// `wallet` must be a live handle and `tx_json` a valid NUL-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn nyx_wallet_scan(wallet: *mut NyxWallet, tx_json: *const c_char) -> i32 {
+    guard(-1, || {
+        let tx = parse_transaction(read_str(tx_json)?)?;
+        let credited = wallet_mut(wallet)?.wallet.sync_transaction(tx.id(), &tx)?;
+        Ok(credited.len() as i32)
+    })
+}
+
+/// Records the global index and stealth address a scanned output got when
+/// confirmed
+///
+/// Only confirmed outputs can be spent, as rings refer to their members by
+/// global index. `tx_hash` and `stealth_address` are hex-encoded. Returns 0,
+/// or -1 on failure.
+///
+/// # Safety
+/// `wallet` must be a live handle; `tx_hash` and `stealth_address` must be
+/// valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn nyx_wallet_confirm_output(
+    wallet: *mut NyxWallet,
+    tx_hash: *const c_char,
+    index: u32,
+    global_index: u64,
+    stealth_address: *const c_char,
+) -> i32 {
+    guard(-1, || {
+        let tx_hash = parse_hash(read_str(tx_hash)?)?;
+        let stealth_address = read_str(stealth_address)?;
+        let stealth_address = hex::decode(stealth_address).map_err(|_| {
+            WalletError::SerializationError(format!("Invalid stealth address '{}'", stealth_address))
+        })?;
+        let member = RingMember { global_index, stealth_address };
+        wallet_mut(wallet)?.wallet.confirm_output(tx_hash, index, member)?;
+        Ok(0)
+    })
+}
+
+/// Builds and signs a transaction paying `amount` to `to_address`
+///
+/// The transaction approves placeholder parents; attach the current DAG
+/// tips with `nyx_wallet_sign_tx` before submitting. Returns the
+/// transaction as JSON, or null on failure.
+///
+/// # Safety
+/// `wallet` must be a live handle and `to_address` a valid NUL-terminated
+/// string.
+#[no_mangle]
+pub unsafe extern "C" fn nyx_wallet_build_tx(
+    wallet: *const NyxWallet,
+    to_address: *const c_char,
+    amount: u64,
+    fee: u64,
+) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let tx = wallet_ref(wallet)?.wallet.build_transaction(read_str(to_address)?, amount, fee)?;
+        to_c_string(serde_json::to_string(&tx)?)
+    })
+}
+
+/// Sets a transaction's parents to the given DAG tips and signs it
+///
+/// `parent1` and `parent2` are hex-encoded transaction hashes. Returns the
+/// signed transaction as JSON, or null on failure.
+///
+/// # Safety
+/// `wallet` must be a live handle; `tx_json`, `parent1` and `parent2` must
+/// be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn nyx_wallet_sign_tx(
+    wallet: *const NyxWallet,
+    tx_json: *const c_char,
+    parent1: *const c_char,
+    parent2: *const c_char,
+) -> *mut c_char {
+    guard(ptr::null_mut(), || {
+        let mut tx = parse_transaction(read_str(tx_json)?)?;
+        let parents = [parse_hash(read_str(parent1)?)?, parse_hash(read_str(parent2)?)?];
+        wallet_ref(wallet)?.wallet.attach_parents(&mut tx, parents)?;
+        to_c_string(serde_json::to_string(&tx)?)
+    })
+}
+
+/// Message of the calling thread's last failure
+///
+/// Returns null if nothing has failed yet.
+#[no_mangle]
+pub extern "C" fn nyx_last_error() -> *mut c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow().as_ref()
+            .and_then(|msg| CString::new(msg.replace('\0', " ")).ok())
+            .map_or(ptr::null_mut(), CString::into_raw)
+    })
+}
+
+/// Releases a string returned by this library
+///
+/// # Safety
+/// `s` must be null or a string returned by this library that has not been
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn nyx_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Runs an FFI body, recording its error or panic and returning `failed`
+/// in their place
+fn guard<T>(failed: T, body: impl FnOnce() -> Result<T>) -> T {
+    let error = match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(value)) => return value,
+        Ok(Err(e)) => e.to_string(),
+        Err(_) => "Internal error: panic in wallet core".to_string(),
+    };
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(error));
+    failed
+}
+
+unsafe fn read_str<'a>(s: *const c_char) -> Result<&'a str> {
+    if s.is_null() {
+        return Err(WalletError::SerializationError("Null string".to_string()));
+    }
+    CStr::from_ptr(s).to_str()
+        .map_err(|_| WalletError::SerializationError("String is not UTF-8".to_string()))
+}
+
+unsafe fn wallet_ref<'a>(wallet: *const NyxWallet) -> Result<&'a NyxWallet> {
+    wallet.as_ref().ok_or_else(|| WalletError::AccountNotFound("Null wallet handle".to_string()))
+}
+
+unsafe fn wallet_mut<'a>(wallet: *mut NyxWallet) -> Result<&'a mut NyxWallet> {
+    wallet.as_mut().ok_or_else(|| WalletError::AccountNotFound("Null wallet handle".to_string()))
+}
+
+fn to_c_string(s: String) -> Result<*mut c_char> {
+    CString::new(s)
+        .map(CString::into_raw)
+        .map_err(|_| WalletError::SerializationError("String contains NUL".to_string()))
+}
+
+fn parse_network(network: &str) -> Result<NetworkId> {
+    network.parse().map_err(|e| WalletError::SerializationError(format!("{}", e)))
+}
+
+fn parse_transaction(json: &str) -> Result<Transaction> {
+    serde_json::from_str(json)
+        .map_err(|e| WalletError::SerializationError(format!("Invalid transaction: {}", e)))
+}
+
+fn parse_hash(hash: &str) -> Result<Hash> {
+    hex::decode(hash).ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| WalletError::SerializationError(format!("Invalid hash '{}'", hash)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    unsafe fn take(s: *mut c_char) -> String {
+        assert!(!s.is_null(), "call failed: {}", take_error());
+        let owned = CStr::from_ptr(s).to_str().unwrap().to_string();
+        nyx_string_free(s);
+        owned
+    }
+
+    unsafe fn take_error() -> String {
+        let err = nyx_last_error();
+        if err.is_null() {
+            return String::new();
+        }
+        let owned = CStr::from_ptr(err).to_str().unwrap().to_string();
+        nyx_string_free(err);
+        owned
+    }
+
+    #[test]
+    fn test_create_and_restore() {
+        unsafe {
+            let wallet = nyx_wallet_create(c("testnet").as_ptr());
+            let phrase = take(nyx_wallet_mnemonic(wallet));
+            let address = take(nyx_wallet_address(wallet));
+            assert!(address.starts_with("tnyx:"));
+
+            let restored = nyx_wallet_restore_mnemonic(c(&phrase).as_ptr(), c("testnet").as_ptr());
+            assert_eq!(take(nyx_wallet_address(restored)), address);
+
+            nyx_wallet_free(restored);
+            nyx_wallet_free(wallet);
+        }
+    }
+
+    #[test]
+    fn test_errors_are_reported() {
+        unsafe {
+            assert!(nyx_wallet_create(c("moonnet").as_ptr()).is_null());
+            assert!(take_error().contains("moonnet"));
+
+            let bad = nyx_wallet_restore_mnemonic(c("acid acid").as_ptr(), c("testnet").as_ptr());
+            assert!(bad.is_null());
+            assert!(take_error().starts_with("Invalid mnemonic"));
+
+            assert!(nyx_wallet_address(ptr::null()).is_null());
+            assert_eq!(nyx_wallet_scan(ptr::null_mut(), c("{}").as_ptr()), -1);
+            nyx_wallet_free(ptr::null_mut());
+            nyx_string_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_scan_build_and_sign() {
+        unsafe {
+            let alice = nyx_wallet_create(c("testnet").as_ptr());
+            let bob = nyx_wallet_create(c("testnet").as_ptr());
+            let bob_address = take(nyx_wallet_address(bob));
+
+            (*alice).wallet.credit_output(ACCOUNT_NAME, [1u8; 32], 0, 1000).unwrap();
+            assert_eq!(nyx_wallet_balance(alice), 1000);
+            assert!(nyx_wallet_build_tx(alice, c(&bob_address).as_ptr(), 600, 10).is_null());
+            assert!(take_error().contains("not confirmed"));
+            let tx_hash = c(&hex::encode([1u8; 32]));
+            assert_eq!(nyx_wallet_confirm_output(alice, tx_hash.as_ptr(), 0, 3, c("zz").as_ptr()), -1);
+            assert_eq!(nyx_wallet_confirm_output(alice, tx_hash.as_ptr(), 0, 3, c(&hex::encode([1u8; 32])).as_ptr()), 0);
+
+            let built = take(nyx_wallet_build_tx(alice, c(&bob_address).as_ptr(), 600, 10));
+            let signed = take(nyx_wallet_sign_tx(
+                alice,
+                c(&built).as_ptr(),
+                c(&hex::encode([7u8; 32])).as_ptr(),
+                c(&hex::encode([8u8; 32])).as_ptr(),
+            ));
+            let tx: Transaction = serde_json::from_str(&signed).unwrap();
+            assert_eq!(tx.references, [[7u8; 32], [8u8; 32]]);
+            assert!(tx.verify_signature().unwrap());
+
+            assert_eq!(nyx_wallet_scan(bob, c(&signed).as_ptr()), 1);
+            assert_eq!(nyx_wallet_balance(bob), 600);
+
+            nyx_wallet_free(bob);
+            nyx_wallet_free(alice);
+        }
+    }
+}
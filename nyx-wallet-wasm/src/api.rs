@@ -0,0 +1,308 @@
+// src/api.rs
+
+//! Wallet operations behind the JavaScript bindings.
+//!
+//! Everything crosses the boundary as JSON text: accounts in the format of
+//! [`Account::export_json`], transactions as served by the node's
+//! `/wallet/transactions` endpoint. Keeping the logic here, free of
+//! wasm-bindgen types, lets it be tested natively.
+
+use nyx_core::transaction::{Transaction, UnlockTime};
+use nyx_core::{Amount, Hash, NetworkId};
+use nyx_wallet::account::Address;
+use nyx_wallet::amount;
+use nyx_wallet::builder::{RingMember, Utxo};
+use nyx_wallet::{Account, Result, TransactionBuilder, Wallet, WalletError};
+use serde::{Deserialize, Serialize};
+
+/// Public parts of a parsed address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressInfo {
+    /// Network name
+    pub network: String,
+
+    /// Public view key, hex-encoded
+    pub view_public: String,
+
+    /// Public spend key, hex-encoded
+    pub spend_public: String,
+}
+
+/// Output found to pay an account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetectedOutput {
+    /// Paying transaction hash, hex-encoded
+    pub tx_hash: String,
+
+    /// Output index within it
+    pub index: u32,
+
+    /// Amount, if the transaction carries a valid hint for the output
+    pub amount: Option<u64>,
+
+    /// Earliest point the output can be spent, if time-locked
+    pub unlock_time: Option<UnlockTime>,
+
+    /// Blinding factor of the output's commitment, hex-encoded, if the
+    /// amount is known
+    pub mask: Option<String>,
+}
+
+/// Output of the account to spend in a new transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendInput {
+    /// Paying transaction hash, hex-encoded
+    pub tx_hash: String,
+
+    /// Output index within it
+    pub index: u32,
+
+    /// Amount of the output
+    pub amount: u64,
+
+    /// Blinding factor of the output's commitment, hex-encoded, as
+    /// detected by [`scan_transaction`]
+    pub mask: String,
+
+    /// Global index the output got when confirmed, as served by the node's
+    /// `/outputs/by-index`
+    pub global_index: u64,
+
+    /// Stealth address of the output, hex-encoded
+    pub stealth_address: String,
+}
+
+/// A signed transaction ready for submission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuiltTransaction {
+    /// Transaction ID, hex-encoded
+    pub tx_hash: String,
+
+    /// The transaction
+    pub transaction: Transaction,
+}
+
+/// Generates an account with fresh keys on the given network
+pub fn generate_account(name: &str, network: &str) -> Result<String> {
+    let mut account = Account::generate_with_name(name.to_string());
+    account.address.network = parse_network(network)?;
+    account.export_json()
+}
+
+/// Address of an account
+pub fn account_address(account_json: &str) -> Result<String> {
+    Ok(Account::import_json(account_json)?.address.to_string())
+}
+
+/// Parses and validates an address
+pub fn parse_address(address: &str) -> Result<AddressInfo> {
+    let address = Address::from_string(address)?;
+    Ok(AddressInfo {
+        network: address.network.to_string(),
+        view_public: hex::encode(&address.view_public),
+        spend_public: hex::encode(&address.spend_public),
+    })
+}
+
+/// Finds the outputs of a transaction paying the account
+pub fn scan_transaction(account_json: &str, tx_json: &str) -> Result<Vec<DetectedOutput>> {
+    let account = Account::import_json(account_json)?;
+    let tx: Transaction = serde_json::from_str(tx_json)
+        .map_err(|e| WalletError::SerializationError(format!("Invalid transaction: {}", e)))?;
+
+    let view_private = account.view_private_key().to_vec();
+    let mut wallet = Wallet::for_network(account.address.network);
+    wallet.add_account(account)?;
+
+    let tx_hash = tx.id();
+    Ok(wallet.scan_transaction(tx_hash, &tx)
+        .into_iter()
+        .map(|output| DetectedOutput {
+            tx_hash: hex::encode(tx_hash),
+            index: output.index,
+            amount: output.amount,
+            unlock_time: tx.outputs[output.index as usize].unlock_time,
+            mask: output.amount.map(|amount| {
+                hex::encode(amount::output_mask(&tx, output.index, &view_private, amount))
+            }),
+        })
+        .collect())
+}
+
+/// Builds and signs a transaction spending `inputs` to pay `amount` to
+/// `to_address`
+///
+/// Whatever the inputs hold beyond the amount and fee is returned to the
+/// account as change. The parents are the DAG tips the transaction
+/// approves, as fetched from a node.
+pub fn build_transaction(
+    account_json: &str,
+    inputs: &[SpendInput],
+    to_address: &str,
+    amount: u64,
+    fee: u64,
+    parent1: &str,
+    parent2: &str,
+) -> Result<BuiltTransaction> {
+    let account = Account::import_json(account_json)?;
+    let network = account.address.network;
+
+    let recipient = Address::from_string(to_address)?;
+    if recipient.network != network {
+        return Err(WalletError::InvalidAddress(format!(
+            "Address is for {}, account is on {}",
+            recipient.network, network
+        )));
+    }
+
+    let total_needed = Amount::from_atomic(amount)
+        .checked_add(Amount::from_atomic(fee))
+        .ok_or_else(|| WalletError::InvalidAmount("Amount plus fee overflows".to_string()))?
+        .as_atomic();
+    let total_input = Amount::checked_sum_atomic(inputs.iter().map(|i| i.amount))
+        .ok_or_else(|| WalletError::InvalidAmount("Input total overflows".to_string()))?
+        .as_atomic();
+    if total_input < total_needed {
+        return Err(WalletError::InsufficientBalance {
+            required: total_needed,
+            available: total_input,
+        });
+    }
+
+    let mut builder = TransactionBuilder::new()
+        .network(network)
+        .sender(account.clone());
+    for input in inputs {
+        let tx_hash = parse_hash(&input.tx_hash)?;
+        builder = builder.add_input(Utxo {
+            tx_hash,
+            index: input.index,
+            amount: input.amount,
+            key_image: account.output_key_image(&tx_hash, input.index),
+            unlock_time: None,
+            mask: parse_mask(&input.mask)?,
+            ring_member: Some(RingMember {
+                global_index: input.global_index,
+                stealth_address: hex::decode(&input.stealth_address).map_err(|_| {
+                    WalletError::SerializationError(format!("Invalid stealth address '{}'", input.stealth_address))
+                })?,
+            }),
+        });
+    }
+
+    builder = builder.add_output(recipient.view_public, recipient.spend_public, amount);
+    let change = total_input - total_needed;
+    if change > 0 {
+        builder = builder.add_output(
+            account.address.view_public.clone(),
+            account.address.spend_public.clone(),
+            change,
+        );
+    }
+
+    let transaction = builder.build(parse_hash(parent1)?, parse_hash(parent2)?)?;
+    Ok(BuiltTransaction {
+        tx_hash: hex::encode(transaction.id()),
+        transaction,
+    })
+}
+
+fn parse_network(network: &str) -> Result<NetworkId> {
+    network.parse()
+        .map_err(|e| WalletError::SerializationError(format!("{}", e)))
+}
+
+fn parse_hash(hash: &str) -> Result<Hash> {
+    hex::decode(hash).ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| WalletError::SerializationError(format!("Invalid hash '{}'", hash)))
+}
+
+fn parse_mask(mask: &str) -> Result<[u8; 32]> {
+    hex::decode(mask).ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| WalletError::SerializationError(format!("Invalid mask '{}'", mask)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_account_and_parse_address() {
+        let json = generate_account("web", "testnet").unwrap();
+        let address = account_address(&json).unwrap();
+        assert!(address.starts_with("tnyx:"));
+
+        let info = parse_address(&address).unwrap();
+        assert_eq!(info.network, "testnet");
+        assert_eq!(info.view_public.len(), 64);
+
+        assert!(generate_account("web", "moonnet").is_err());
+        assert!(parse_address("not-an-address").is_err());
+    }
+
+    #[test]
+    fn test_build_and_detect_payment() {
+        let sender = generate_account("alice", "testnet").unwrap();
+        let recipient = generate_account("bob", "testnet").unwrap();
+        let inputs = vec![SpendInput {
+            tx_hash: hex::encode([7u8; 32]),
+            index: 0,
+            amount: 1000,
+            mask: hex::encode([0u8; 32]),
+            global_index: 4,
+            stealth_address: hex::encode([7u8; 32]),
+        }];
+
+        let built = build_transaction(
+            &sender,
+            &inputs,
+            &account_address(&recipient).unwrap(),
+            600,
+            10,
+            &hex::encode([1u8; 32]),
+            &hex::encode([2u8; 32]),
+        ).unwrap();
+        assert_eq!(built.tx_hash, hex::encode(built.transaction.id()));
+        assert_eq!(built.transaction.outputs.len(), 3);
+        assert_eq!(built.transaction.inputs[0].ring_indices, vec![4]);
+
+        let tx_json = serde_json::to_string(&built.transaction).unwrap();
+        let received = scan_transaction(&recipient, &tx_json).unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].amount, Some(600));
+        assert_eq!(received[0].tx_hash, built.tx_hash);
+        assert!(received[0].mask.as_ref().is_some_and(|mask| parse_mask(mask).is_ok()));
+
+        let change = scan_transaction(&sender, &tx_json).unwrap();
+        assert_eq!(change.len(), 1);
+        assert_eq!(change[0].amount, Some(390));
+    }
+
+    #[test]
+    fn test_build_rejects_bad_requests() {
+        let sender = generate_account("alice", "testnet").unwrap();
+        let recipient = account_address(&generate_account("bob", "testnet").unwrap()).unwrap();
+        let mainnet = account_address(&generate_account("carol", "mainnet").unwrap()).unwrap();
+        let inputs = vec![SpendInput {
+            tx_hash: hex::encode([7u8; 32]),
+            index: 0,
+            amount: 100,
+            mask: hex::encode([0u8; 32]),
+            global_index: 4,
+            stealth_address: hex::encode([7u8; 32]),
+        }];
+        let parent = hex::encode([1u8; 32]);
+
+        assert!(matches!(
+            build_transaction(&sender, &inputs, &recipient, 100, 10, &parent, &parent),
+            Err(WalletError::InsufficientBalance { required: 110, available: 100 })
+        ));
+        assert!(matches!(
+            build_transaction(&sender, &inputs, &mainnet, 50, 10, &parent, &parent),
+            Err(WalletError::InvalidAddress(_))
+        ));
+        assert!(build_transaction(&sender, &inputs, &recipient, 50, 10, "zz", &parent).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+// src/lib.rs
+
+//! # Nyx Wallet WASM
+//!
+//! WebAssembly bindings for the Nyx wallet core, so a web wallet can run
+//! fully client-side against a public node RPC. Keys never leave the
+//! browser: the page fetches transactions and DAG tips from a node, and
+//! submits what it signs here.
+//!
+//! Exposed to JavaScript:
+//! - **Accounts**: `generateAccount`, `accountAddress`
+//! - **Addresses**: `parseAddress`
+//! - **Stealth Output Detection**: `scanTransaction`
+//! - **Transaction Building**: `buildTransaction`
+//!
+//! Accounts, transactions and results are exchanged as JSON strings (see
+//! [`api`]); failures are thrown as JavaScript `Error`s.
+//!
+//! ## Example Usage
+//!
+//! ```js
+//! import init, { generateAccount, accountAddress } from "nyx-wallet-wasm";
+//!
+//! await init();
+//! const account = generateAccount("web", "testnet");
+//! console.log(accountAddress(account));
+//! ```
+
+#![warn(missing_docs)]
+#![warn(clippy::all)]
+#![deny(unsafe_code)]
+
+pub mod api;
+
+use crate::api::SpendInput;
+use nyx_wallet::WalletError;
+use wasm_bindgen::prelude::*;
+
+/// Generates an account with fresh keys, returned as JSON
+///
+/// The JSON holds the private keys; store it encrypted.
+#[wasm_bindgen(js_name = generateAccount)]
+pub fn generate_account(name: &str, network: &str) -> std::result::Result<String, JsError> {
+    api::generate_account(name, network).map_err(to_js)
+}
+
+/// Address of an account given as JSON
+#[wasm_bindgen(js_name = accountAddress)]
+pub fn account_address(account_json: &str) -> std::result::Result<String, JsError> {
+    api::account_address(account_json).map_err(to_js)
+}
+
+/// Parses an address into its network and public keys, returned as JSON
+#[wasm_bindgen(js_name = parseAddress)]
+pub fn parse_address(address: &str) -> std::result::Result<String, JsError> {
+    api::parse_address(address).and_then(|info| to_json(&info)).map_err(to_js)
+}
+
+/// Finds the outputs of a transaction paying the account, returned as a
+/// JSON array
+#[wasm_bindgen(js_name = scanTransaction)]
+pub fn scan_transaction(account_json: &str, tx_json: &str) -> std::result::Result<String, JsError> {
+    api::scan_transaction(account_json, tx_json)
+        .and_then(|outputs| to_json(&outputs))
+        .map_err(to_js)
+}
+
+/// Builds and signs a transaction, returned as JSON with its ID
+///
+/// `inputs_json` is an array of `{ tx_hash, index, amount, mask,
+/// global_index, stealth_address }` confirmed outputs of the account, as
+/// scanned; `parent1` and `parent2` are hex-encoded DAG tips.
+#[wasm_bindgen(js_name = buildTransaction)]
+pub fn build_transaction(
+    account_json: &str,
+    inputs_json: &str,
+    to_address: &str,
+    amount: u64,
+    fee: u64,
+    parent1: &str,
+    parent2: &str,
+) -> std::result::Result<String, JsError> {
+    serde_json::from_str::<Vec<SpendInput>>(inputs_json)
+        .map_err(|e| WalletError::SerializationError(format!("Invalid inputs: {}", e)))
+        .and_then(|inputs| {
+            api::build_transaction(account_json, &inputs, to_address, amount, fee, parent1, parent2)
+        })
+        .and_then(|built| to_json(&built))
+        .map_err(to_js)
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> nyx_wallet::Result<String> {
+    serde_json::to_string(value).map_err(|e| WalletError::SerializationError(e.to_string()))
+}
+
+fn to_js(error: WalletError) -> JsError {
+    JsError::new(&error.to_string())
+}
@@ -3,9 +3,18 @@
 //! Account management with keys and addresses.
 
 use crate::errors::{WalletError, Result};
-use nyx_crypto::keys::KeyPair;
+use crate::mnemonic::Mnemonic;
+use nyx_core::{Hash, NetworkId};
+use nyx_crypto::keys::{self, KeyPair};
+use nyx_crypto::ring;
 use serde::{Deserialize, Serialize};
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// Label of the change subaddress chain
+const CHANGE_CHAIN: &[u8] = b"change";
+
+/// Label of the deposit subaddress chain
+const DEPOSIT_CHAIN: &[u8] = b"deposit";
 
 /// Account with keys and address
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -41,8 +50,15 @@ impl std::fmt::Debug for KeyPairData {
 }
 
 /// Public address combining view and spend keys
+///
+/// Encoded as `<prefix>:<view key>:<spend key>`, where the prefix names the
+/// network (see [`NetworkId::address_prefix`]).
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Address {
+    /// Network the address belongs to
+    #[serde(default)]
+    pub network: NetworkId,
+
     /// Public view key
     pub view_public: Vec<u8>,
 
@@ -75,9 +91,20 @@ impl Account {
         Self::from_keypairs(name, view_keypair, spend_keypair)
     }
 
+    /// Restores the account whose keys a recovery phrase derives
+    ///
+    /// The same phrase always yields the same keys and address.
+    pub fn from_mnemonic(name: String, mnemonic: &Mnemonic) -> Self {
+        let view_keypair = keys::generate_ed25519_keypair_from_seed(&mnemonic.derive_seed(b"view"));
+        let spend_keypair = keys::generate_ed25519_keypair_from_seed(&mnemonic.derive_seed(b"spend"));
+
+        Self::from_keypairs(name, view_keypair, spend_keypair)
+    }
+
     /// Creates an account from existing keypairs
     pub fn from_keypairs(name: String, view_keypair: KeyPair, spend_keypair: KeyPair) -> Self {
         let address = Address {
+            network: NetworkId::Mainnet,
             view_public: view_keypair.public_key.clone(),
             spend_public: spend_keypair.public_key.clone(),
         };
@@ -111,16 +138,72 @@ impl Account {
         &self.spend_keypair.private
     }
 
+    /// Takes the spend private key out, leaving the account unable to sign
+    pub(crate) fn take_spend_private_key(&mut self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(std::mem::take(&mut self.spend_keypair.private))
+    }
+
+    /// Puts back a spend private key taken out
+    pub(crate) fn restore_spend_private_key(&mut self, key: Vec<u8>) {
+        self.spend_keypair.private = key;
+    }
+
     /// Gets the spend public key
     pub fn spend_public_key(&self) -> &[u8] {
         &self.spend_keypair.public
     }
 
+    /// Gets the spend public key of change subaddress `index`
+    ///
+    /// Change subaddresses share the account's view key; see
+    /// [`crate::change`].
+    pub fn change_spend_public_key(&self, index: u32) -> Result<Vec<u8>> {
+        Ok(nyx_crypto::stealth::derive_subaddress_spend_public(
+            self.spend_public_key(),
+            self.view_private_key(),
+            CHANGE_CHAIN,
+            index,
+        )?)
+    }
+
+    /// Gets the address of deposit subaddress `index`
+    ///
+    /// Deposit subaddresses share the account's view key, so the wallet
+    /// finds payments to them once it watches them; see [`crate::ledger`].
+    pub fn deposit_address(&self, index: u32) -> Result<Address> {
+        Ok(Address {
+            network: self.address.network,
+            view_public: self.view_public_key().to_vec(),
+            spend_public: nyx_crypto::stealth::derive_subaddress_spend_public(
+                self.spend_public_key(),
+                self.view_private_key(),
+                DEPOSIT_CHAIN,
+                index,
+            )?,
+        })
+    }
+
     /// Gets the public address
     pub fn get_address(&self) -> &Address {
         &self.address
     }
 
+    /// One-time key signing the spend of an output paying this account
+    pub fn output_private_key(&self, tx_hash: &Hash, index: u32) -> Zeroizing<[u8; 32]> {
+        let mut one_time_key = Zeroizing::new(self.spend_private_key().to_vec());
+        one_time_key.extend_from_slice(tx_hash);
+        one_time_key.extend_from_slice(&index.to_le_bytes());
+        Zeroizing::new(nyx_crypto::hash::blake3_hash(&one_time_key))
+    }
+
+    /// Key image of an output paying this account
+    ///
+    /// Derived from the output's one-time key, so every output has its own
+    /// key image.
+    pub fn output_key_image(&self, tx_hash: &Hash, index: u32) -> [u8; 32] {
+        ring::generate_key_image(self.output_private_key(tx_hash, index).as_ref())
+    }
+
     /// Exports account to JSON
     pub fn export_json(&self) -> Result<String> {
         serde_json::to_string_pretty(self)
@@ -146,26 +229,32 @@ impl Account {
     }
 }
 
-impl Address {
-    /// Converts address to string representation
-    pub fn to_string(&self) -> String {
-        format!(
-            "nyx:{}:{}",
+impl std::fmt::Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.network.address_prefix(),
             hex::encode(&self.view_public),
             hex::encode(&self.spend_public)
         )
     }
+}
 
+impl Address {
     /// Parses address from string
     pub fn from_string(s: &str) -> Result<Self> {
         let parts: Vec<&str> = s.split(':').collect();
 
-        if parts.len() != 3 || parts[0] != "nyx" {
+        if parts.len() != 3 {
             return Err(WalletError::InvalidAddress(
                 "Invalid address format".to_string()
             ));
         }
 
+        let network = NetworkId::from_address_prefix(parts[0])
+            .ok_or_else(|| WalletError::InvalidAddress(format!("Unknown address prefix '{}'", parts[0])))?;
+
         let view_public = hex::decode(parts[1])
             .map_err(|_| WalletError::InvalidAddress("Invalid view key".to_string()))?;
 
@@ -173,6 +262,7 @@ impl Address {
             .map_err(|_| WalletError::InvalidAddress("Invalid spend key".to_string()))?;
 
         Ok(Self {
+            network,
             view_public,
             spend_public,
         })
@@ -207,6 +297,20 @@ mod tests {
         assert!(!account.spend_public_key().is_empty());
     }
 
+    #[test]
+    fn test_account_from_mnemonic() {
+        let mnemonic = Mnemonic::generate();
+        let account = Account::from_mnemonic("phone".to_string(), &mnemonic);
+
+        let restored = Account::from_mnemonic(
+            "restored".to_string(),
+            &Mnemonic::from_phrase(&mnemonic.phrase()).unwrap(),
+        );
+        assert_eq!(restored.address, account.address);
+        assert_eq!(restored.spend_private_key(), account.spend_private_key());
+        assert_ne!(account.view_public_key(), account.spend_public_key());
+    }
+
     #[test]
     fn test_account_export_import_json() {
         let account = Account::generate_with_name("export_test".to_string());
@@ -237,6 +341,18 @@ mod tests {
         assert!(addr_str.contains(':'));
     }
 
+    #[test]
+    fn test_address_network_prefix() {
+        let mut account = Account::generate();
+        account.address.network = NetworkId::Testnet;
+
+        let addr_str = account.address.to_string();
+        assert!(addr_str.starts_with("tnyx:"));
+        assert_eq!(Address::from_string(&addr_str).unwrap().network, NetworkId::Testnet);
+
+        assert!(Address::from_string(&addr_str.replacen("tnyx", "xnyx", 1)).is_err());
+    }
+
     #[test]
     fn test_address_from_string() {
         let account = Account::generate();
@@ -0,0 +1,206 @@
+// src/amount.rs
+
+//! Encrypted amount hints.
+//!
+//! Output amounts are hidden in commitments, so a recipient scanning the
+//! DAG can find its outputs but not what they are worth. Transactions built
+//! by the wallet carry one encrypted amount per output in the amount hints
+//! field of `extra` (see [`nyx_core::extra`]). Both the encryption and the
+//! commitment's blinding factor are derived from the output's stealth
+//! shared secret (see [`nyx_crypto::amount`]), so a recipient decrypts
+//! only its own outputs' amounts, rebuilds their commitments and accepts an
+//! amount only if the commitment matches; a tampered hint is ignored rather
+//! than credited.
+
+use nyx_core::extra::{Extra, ExtraField};
+use nyx_core::transaction::Transaction;
+use nyx_crypto::amount::ENCRYPTED_AMOUNT_SIZE;
+use nyx_crypto::{amount, pedersen, stealth};
+
+pub use nyx_crypto::amount::{commit_amount, encrypt_amount};
+
+/// Encodes per-output hints, in output order, as an `extra` field
+pub fn encode_hints(hints: &[[u8; ENCRYPTED_AMOUNT_SIZE]]) -> ExtraField {
+    ExtraField::AmountHints(hints.concat())
+}
+
+/// Outcome of decoding an output's amount
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmountCheck {
+    /// The decrypted amount and its mask regenerate the output's commitment
+    Verified(u64),
+
+    /// The transaction carries no hint for the output
+    Missing,
+
+    /// The hint decrypts to an amount the output's commitment is not to
+    Inconsistent(u64),
+}
+
+impl AmountCheck {
+    /// Gets the amount if it was verified
+    pub fn verified(self) -> Option<u64> {
+        match self {
+            AmountCheck::Verified(amount) => Some(amount),
+            _ => None,
+        }
+    }
+}
+
+/// Recovers the amount of an output paying the owner of `view_private`
+///
+/// # Returns
+/// `None` if the transaction carries no hint for the output, or the
+/// decrypted amount does not open the output's commitment
+pub fn recover_amount(tx: &Transaction, index: u32, view_private: &[u8]) -> Option<u64> {
+    check_amount(tx, index, view_private).verified()
+}
+
+/// Recovers the amount of output `index` from its shared secret, as
+/// disclosed in a payment proof
+///
+/// # Returns
+/// `None` under the same conditions as [`recover_amount`]
+pub fn recover_amount_with_secret(tx: &Transaction, index: u32, shared_secret: &[u8]) -> Option<u64> {
+    check_amount_with_secret(tx, index, shared_secret).verified()
+}
+
+/// Decodes the amount of an output paying the owner of `view_private` and
+/// checks it against the output's commitment
+///
+/// Any key decrypts a hint to some amount, so `Inconsistent` only means
+/// something for outputs known to pay the key's owner.
+pub fn check_amount(tx: &Transaction, index: u32, view_private: &[u8]) -> AmountCheck {
+    let Some(output) = tx.outputs.get(index as usize) else {
+        return AmountCheck::Missing;
+    };
+    match stealth::derive_shared_secret(view_private, &output.ephemeral_pubkey) {
+        Ok(shared_secret) => check_amount_with_secret(tx, index, &shared_secret),
+        Err(_) => AmountCheck::Missing,
+    }
+}
+
+/// Decodes the amount of output `index` from its shared secret and checks
+/// it against the output's commitment
+///
+/// Minted outputs commit to their amount with no blinding, so a hint for
+/// one opens its unblinded commitment instead.
+pub fn check_amount_with_secret(tx: &Transaction, index: u32, shared_secret: &[u8]) -> AmountCheck {
+    let Some(hint) = hint(tx, index) else {
+        return AmountCheck::Missing;
+    };
+    let output = &tx.outputs[index as usize];
+
+    let amount = amount::decrypt_amount(shared_secret, index, &hint);
+    let masked = amount::commit_amount(shared_secret, index, amount).to_bytes();
+    let public = pedersen::commit_public(amount).to_bytes();
+    if output.amount_commitment == masked || output.amount_commitment == public {
+        AmountCheck::Verified(amount)
+    } else {
+        AmountCheck::Inconsistent(amount)
+    }
+}
+
+/// Gets the blinding factor of output `index`, whose `amount` was verified
+/// with [`check_amount`]
+///
+/// Spending the output takes it to balance the spending transaction.
+pub fn output_mask(tx: &Transaction, index: u32, view_private: &[u8], amount: u64) -> pedersen::BlindingFactor {
+    let output = &tx.outputs[index as usize];
+    if output.amount_commitment == pedersen::commit_public(amount).to_bytes() {
+        return [0u8; 32];
+    }
+    stealth::derive_shared_secret(view_private, &output.ephemeral_pubkey)
+        .map(|shared_secret| amount::amount_mask(&shared_secret, index))
+        .unwrap_or([0u8; 32])
+}
+
+/// Gets the encrypted amount of output `index` from a transaction's hints
+fn hint(tx: &Transaction, index: u32) -> Option<[u8; ENCRYPTED_AMOUNT_SIZE]> {
+    tx.outputs.get(index as usize)?;
+    let extra = Extra::parse(&tx.extra).ok()?;
+    let hints = extra.amount_hints()?;
+    if hints.len() != tx.outputs.len() * ENCRYPTED_AMOUNT_SIZE {
+        return None;
+    }
+
+    let start = index as usize * ENCRYPTED_AMOUNT_SIZE;
+    hints[start..start + ENCRYPTED_AMOUNT_SIZE].try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::Account;
+    use nyx_core::transaction::TxOutput;
+    use nyx_core::RingSignature;
+
+    fn output_to(account: &Account, random: &[u8], amount: u64) -> (TxOutput, [u8; ENCRYPTED_AMOUNT_SIZE]) {
+        let (stealth_address, ephemeral_pubkey) = stealth::generate_stealth_address(
+            account.view_public_key(),
+            account.spend_public_key(),
+            random,
+        ).unwrap();
+        let secret = stealth::derive_sender_shared_secret(account.view_public_key(), random).unwrap();
+        let output = TxOutput {
+            stealth_address,
+            amount_commitment: commit_amount(&secret, 0, amount).to_bytes().to_vec(),
+            range_proof: vec![],
+            ephemeral_pubkey,
+            unlock_time: None,
+            htlc: None,
+        };
+        (output, encrypt_amount(&secret, 0, amount))
+    }
+
+    fn transaction(outputs: Vec<TxOutput>, extra: Vec<u8>) -> Transaction {
+        let signature = RingSignature { ring_members: vec![], signature: vec![], key_image: [0u8; 32] };
+        let mut tx = Transaction::new(vec![], outputs, vec![signature], vec![], [0u8; 32], [0u8; 32]);
+        tx.extra = extra;
+        tx
+    }
+
+    fn hints_extra(hints: &[[u8; ENCRYPTED_AMOUNT_SIZE]]) -> Vec<u8> {
+        Extra::new().with_field(encode_hints(hints)).to_bytes()
+    }
+
+    #[test]
+    fn test_recipient_recovers_amount() {
+        let recipient = Account::generate();
+        let (output, hint) = output_to(&recipient, &[1u8; 32], 4_200);
+        let tx = transaction(vec![output], hints_extra(&[hint]));
+
+        assert_eq!(recover_amount(&tx, 0, recipient.view_private_key()), Some(4_200));
+        assert_eq!(recover_amount(&tx, 0, Account::generate().view_private_key()), None);
+        assert_eq!(recover_amount(&tx, 1, recipient.view_private_key()), None);
+    }
+
+    #[test]
+    fn test_tampered_or_missing_hints_are_ignored() {
+        let recipient = Account::generate();
+        let (output, mut hint) = output_to(&recipient, &[2u8; 32], 900);
+
+        let tx = transaction(vec![output.clone()], Vec::new());
+        assert_eq!(recover_amount(&tx, 0, recipient.view_private_key()), None);
+
+        hint[0] ^= 1;
+        let tx = transaction(vec![output], hints_extra(&[hint]));
+        assert_eq!(recover_amount(&tx, 0, recipient.view_private_key()), None);
+    }
+
+    #[test]
+    fn test_commitment_mismatch_is_inconsistent() {
+        let recipient = Account::generate();
+        let (mut output, hint) = output_to(&recipient, &[3u8; 32], 500);
+
+        let tx = transaction(vec![output.clone()], hints_extra(&[hint]));
+        assert_eq!(check_amount(&tx, 0, recipient.view_private_key()), AmountCheck::Verified(500));
+        assert_eq!(check_amount(&transaction(vec![output.clone()], Vec::new()), 0, recipient.view_private_key()),
+            AmountCheck::Missing);
+
+        // The hint still decrypts, but not to what the output commits to
+        output.amount_commitment = pedersen::commit(500, &pedersen::blinding_from_bytes(b"other")).to_bytes().to_vec();
+        let tx = transaction(vec![output], hints_extra(&[hint]));
+        assert_eq!(check_amount(&tx, 0, recipient.view_private_key()), AmountCheck::Inconsistent(500));
+    }
+}
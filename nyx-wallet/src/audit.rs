@@ -0,0 +1,307 @@
+// src/audit.rs
+
+//! View-only audit packages.
+//!
+//! An [`AuditPackage`] hands an account's private view key and public
+//! address to an accountant or compliance officer, optionally limited to a
+//! time window. With it, [`audit`] reconstructs every incoming payment of
+//! the account from a [`TransactionSource`], amounts included, but it
+//! grants no spend capability: the spend private key never leaves the
+//! wallet.
+//!
+//! Outgoing payments are not visible to an auditor. Recognizing a spend
+//! takes the key image of the spent output, which can only be computed
+//! with the spend private key.
+
+use crate::account::{Account, Address};
+use crate::amount;
+use crate::errors::{WalletError, Result};
+use crate::sync::{Cursor, RpcSource, TransactionSource, SYNC_BATCH_SIZE};
+use crate::wallet::Wallet;
+use nyx_core::transaction::Transaction;
+use nyx_core::{Hash, Timestamp};
+use nyx_crypto::stealth;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+/// Format version of exported audit packages
+pub const AUDIT_PACKAGE_VERSION: u32 = 1;
+
+/// View-only disclosure of one account
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AuditPackage {
+    /// Format version
+    pub version: u32,
+
+    /// Name of the disclosed account
+    pub account: String,
+
+    /// Address of the account
+    pub address: Address,
+
+    /// Private view key of the account
+    view_private: Vec<u8>,
+
+    /// Earliest transaction timestamp covered, inclusive
+    pub from: Option<Timestamp>,
+
+    /// Latest transaction timestamp covered, inclusive
+    pub until: Option<Timestamp>,
+}
+
+impl AuditPackage {
+    /// Creates a package disclosing `account`'s incoming payments between
+    /// `from` and `until`
+    pub fn new(account: &Account, from: Option<Timestamp>, until: Option<Timestamp>) -> Self {
+        Self {
+            version: AUDIT_PACKAGE_VERSION,
+            account: account.name.clone(),
+            address: account.address.clone(),
+            view_private: account.view_private_key().to_vec(),
+            from,
+            until,
+        }
+    }
+
+    /// Exports the package to JSON
+    pub fn export_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| WalletError::SerializationError(format!("{}", e)))
+    }
+
+    /// Imports a package from JSON
+    ///
+    /// # Errors
+    /// - `SerializationError` if the JSON is not a package of a supported
+    ///   version
+    pub fn import_json(json: &str) -> Result<Self> {
+        let package: Self = serde_json::from_str(json)
+            .map_err(|e| WalletError::SerializationError(format!("{}", e)))?;
+        if package.version != AUDIT_PACKAGE_VERSION {
+            return Err(WalletError::SerializationError(format!(
+                "Unsupported audit package version {}", package.version
+            )));
+        }
+        Ok(package)
+    }
+
+    /// Checks whether a transaction timestamp falls in the package's window
+    pub fn covers(&self, timestamp: Timestamp) -> bool {
+        self.from.is_none_or(|from| timestamp >= from)
+            && self.until.is_none_or(|until| timestamp <= until)
+    }
+
+    /// Finds the outputs of a transaction paying the account
+    ///
+    /// Transactions outside the package's window yield nothing.
+    pub fn scan(&self, tx_hash: Hash, tx: &Transaction) -> Vec<AuditEntry> {
+        if !self.covers(tx.timestamp) {
+            return Vec::new();
+        }
+
+        tx.outputs.iter()
+            .enumerate()
+            .filter(|(_, output)| {
+                stealth::is_mine(
+                    &output.stealth_address,
+                    &self.view_private,
+                    &self.address.spend_public,
+                    &output.ephemeral_pubkey,
+                )
+                .unwrap_or(false)
+            })
+            .map(|(index, _)| AuditEntry {
+                tx_hash,
+                index: index as u32,
+                timestamp: tx.timestamp,
+                amount: amount::recover_amount(tx, index as u32, &self.view_private),
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for AuditPackage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditPackage")
+            .field("version", &self.version)
+            .field("account", &self.account)
+            .field("address", &self.address)
+            .field("view_private", &"[REDACTED]")
+            .field("from", &self.from)
+            .field("until", &self.until)
+            .finish()
+    }
+}
+
+impl Drop for AuditPackage {
+    fn drop(&mut self) {
+        self.view_private.zeroize();
+    }
+}
+
+/// An incoming payment found by an audit
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AuditEntry {
+    /// Transaction containing the output
+    pub tx_hash: Hash,
+
+    /// Output index within it
+    pub index: u32,
+
+    /// Transaction timestamp
+    pub timestamp: Timestamp,
+
+    /// Amount, if the transaction carries a valid hint for the output
+    pub amount: Option<u64>,
+}
+
+/// Incoming history of an audited account
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AuditReport {
+    /// Audited account
+    pub account: String,
+
+    /// Audited address
+    pub address: String,
+
+    /// Incoming payments, oldest first
+    pub entries: Vec<AuditEntry>,
+
+    /// Sum of the known amounts
+    pub total_received: u64,
+}
+
+/// Reconstructs the incoming history covered by a package
+///
+/// Pages through `source` from the start of the package's window to its
+/// end, or to the latest transaction.
+pub fn audit<S: TransactionSource>(package: &AuditPackage, source: &mut S) -> Result<AuditReport> {
+    let mut cursor = Cursor::starting_at(package.from.unwrap_or(0));
+    let mut entries = Vec::new();
+
+    'pages: loop {
+        let batch = source.fetch(cursor.from(), SYNC_BATCH_SIZE)?;
+        let full = batch.len() >= SYNC_BATCH_SIZE;
+        let mut progressed = false;
+        for tx in batch {
+            if package.until.is_some_and(|until| tx.timestamp > until) {
+                break 'pages;
+            }
+            let tx_hash = tx.id();
+            if !cursor.advance(tx_hash, tx.timestamp) {
+                continue;
+            }
+            progressed = true;
+            entries.extend(package.scan(tx_hash, &tx));
+        }
+
+        if !full || !progressed {
+            break;
+        }
+    }
+
+    let total_received = entries.iter()
+        .filter_map(|entry| entry.amount)
+        .fold(0u64, u64::saturating_add);
+    Ok(AuditReport {
+        account: package.account.clone(),
+        address: package.address.to_string(),
+        entries,
+        total_received,
+    })
+}
+
+impl Wallet {
+    /// Exports an audit package for an account
+    ///
+    /// # Arguments
+    /// * `account_name` - Account to disclose
+    /// * `from` - Earliest transaction timestamp to disclose, if limited
+    /// * `until` - Latest transaction timestamp to disclose, if limited
+    pub fn export_audit_package(
+        &self,
+        account_name: &str,
+        from: Option<Timestamp>,
+        until: Option<Timestamp>,
+    ) -> Result<AuditPackage> {
+        Ok(AuditPackage::new(self.get_account(account_name)?, from, until))
+    }
+}
+
+/// Audits a package against a node's RPC endpoint, e.g.
+/// `http://127.0.0.1:8080`
+pub fn audit_node(package: &AuditPackage, node_endpoint: &str) -> Result<AuditReport> {
+    audit(package, &mut RpcSource::new(node_endpoint)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{RingMember, TransactionBuilder, Utxo};
+
+    /// Source serving a fixed, oldest-first list
+    struct Fixed(Vec<Transaction>);
+
+    impl TransactionSource for Fixed {
+        fn fetch(&mut self, from: Timestamp, limit: usize) -> Result<Vec<Transaction>> {
+            Ok(self.0.iter().filter(|tx| tx.timestamp >= from).take(limit).cloned().collect())
+        }
+    }
+
+    fn payment(to: &Account, amount: u64, timestamp: Timestamp) -> Transaction {
+        let mut tx = TransactionBuilder::new()
+            .sender(Account::generate())
+            .add_input(Utxo {
+                tx_hash: [timestamp as u8; 32],
+                index: 0,
+                amount,
+                key_image: [timestamp as u8; 32],
+                unlock_time: None,
+                mask: [0u8; 32],
+                ring_member: Some(RingMember { global_index: 0, stealth_address: vec![timestamp as u8; 32] }),
+            })
+            .add_output(to.view_public_key().to_vec(), to.spend_public_key().to_vec(), amount)
+            .build([0u8; 32], [1u8; 32])
+            .unwrap();
+        tx.timestamp = timestamp;
+        tx
+    }
+
+    #[test]
+    fn test_audit_reconstructs_incoming_history_in_window() {
+        let account = Account::generate();
+        let other = Account::generate();
+        let history = vec![
+            payment(&account, 100, 10),
+            payment(&other, 999, 20),
+            payment(&account, 250, 30),
+            payment(&account, 400, 40),
+        ];
+
+        let mut wallet = Wallet::new();
+        wallet.add_account(account.clone()).unwrap();
+        let package = AuditPackage::import_json(
+            &wallet.export_audit_package(&account.name, Some(20), Some(30)).unwrap().export_json().unwrap()
+        ).unwrap();
+        let report = audit(&package, &mut Fixed(history.clone())).unwrap();
+        let index = package.scan(history[2].id(), &history[2])[0].index;
+        assert_eq!(report.entries, vec![AuditEntry { tx_hash: history[2].id(), index, timestamp: 30, amount: Some(250) }]);
+        assert_eq!(report.total_received, 250);
+
+        let report = audit(&AuditPackage::new(&account, None, None), &mut Fixed(history)).unwrap();
+        assert_eq!(report.entries.len(), 3);
+        assert_eq!(report.total_received, 750);
+    }
+
+    #[test]
+    fn test_package_holds_no_spend_key() {
+        let account = Account::generate();
+        let package = AuditPackage::new(&account, None, None);
+        let json: serde_json::Value = serde_json::from_str(&package.export_json().unwrap()).unwrap();
+
+        let fields = json.as_object().unwrap();
+        assert!(fields.keys().all(|field| !field.contains("spend")));
+        assert_eq!(json["view_private"], serde_json::json!(account.view_private_key()));
+        assert!(format!("{:?}", package).contains("[REDACTED]"));
+    }
+}
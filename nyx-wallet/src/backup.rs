@@ -0,0 +1,302 @@
+// src/backup.rs
+
+//! Encrypted backups unlocked by recovery codes.
+//!
+//! A [`Backup`] holds a wallet's accounts encrypted under a random key that
+//! is never stored. The key is split with Shamir secret sharing into `n`
+//! [`RecoveryCode`]s, any `k` of which restore the wallet. The encrypted
+//! backup can be kept anywhere, including a cloud drive, and the codes
+//! handed to different places or people: nobody needs to remember a
+//! password, and fewer than `k` codes reveal nothing about the key.
+
+use crate::account::Account;
+use crate::errors::{WalletError, Result};
+use crate::wallet::Wallet;
+use nyx_core::NetworkId;
+use nyx_crypto::hash::blake3_hash;
+use nyx_crypto::{encryption, shamir};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use zeroize::Zeroize;
+
+/// Format version of backups
+pub const BACKUP_VERSION: u32 = 1;
+
+/// Size of a recovery code's checksum
+const CHECKSUM_SIZE: usize = 2;
+
+/// Hex digits per dash-separated group of a recovery code
+const CODE_GROUP_SIZE: usize = 4;
+
+/// One share of a backup key
+///
+/// Written as dash-separated groups of hex digits ending in a checksum, so
+/// a mistyped code is reported instead of silently restoring garbage.
+#[derive(Clone, PartialEq, Eq)]
+pub struct RecoveryCode {
+    /// Number of codes needed to restore
+    threshold: u8,
+
+    /// Share of the backup key
+    share: shamir::Share,
+}
+
+impl RecoveryCode {
+    /// Gets the number of codes needed to restore
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Gets the code's number, from 1 to the number of codes created
+    pub fn number(&self) -> u8 {
+        self.share.index
+    }
+
+    /// Parses a code, ignoring case, dashes and whitespace
+    ///
+    /// # Errors
+    /// - `InvalidRecoveryCode` if the code is malformed or mistyped
+    pub fn from_string(s: &str) -> Result<Self> {
+        let digits: String = s.chars().filter(|c| *c != '-' && !c.is_whitespace()).collect();
+        let bytes = hex::decode(&digits)
+            .map_err(|_| WalletError::InvalidRecoveryCode("Not a recovery code".to_string()))?;
+        if bytes.len() <= 2 + CHECKSUM_SIZE {
+            return Err(WalletError::InvalidRecoveryCode("Recovery code is too short".to_string()));
+        }
+
+        let (payload, checksum) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+        if checksum != &blake3_hash(payload)[..CHECKSUM_SIZE] {
+            return Err(WalletError::InvalidRecoveryCode("Recovery code has a typo".to_string()));
+        }
+
+        Ok(Self {
+            threshold: payload[0],
+            share: shamir::Share { index: payload[1], data: payload[2..].to_vec() },
+        })
+    }
+}
+
+impl fmt::Display for RecoveryCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut payload = vec![self.threshold, self.share.index];
+        payload.extend_from_slice(&self.share.data);
+        let checksum = blake3_hash(&payload);
+        payload.extend_from_slice(&checksum[..CHECKSUM_SIZE]);
+
+        let digits = hex::encode_upper(&payload);
+        payload.zeroize();
+        let groups: Vec<&str> = digits.as_bytes()
+            .chunks(CODE_GROUP_SIZE)
+            .map(|group| std::str::from_utf8(group).unwrap_or_default())
+            .collect();
+        write!(f, "{}", groups.join("-"))
+    }
+}
+
+impl fmt::Debug for RecoveryCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecoveryCode")
+            .field("threshold", &self.threshold)
+            .field("number", &self.share.index)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Wallet accounts encrypted under a key split into recovery codes
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Backup {
+    /// Backup version
+    version: u32,
+
+    /// Network of the backed up wallet
+    network: NetworkId,
+
+    /// Number of recovery codes needed to restore
+    threshold: u8,
+
+    /// Number of recovery codes created
+    codes: u8,
+
+    /// Encrypted accounts
+    encrypted_data: Vec<u8>,
+}
+
+impl Backup {
+    /// Encrypts accounts under a fresh key and splits the key into `codes`
+    /// recovery codes, any `threshold` of which restore them
+    ///
+    /// # Errors
+    /// - `KeystoreError` if `threshold` is zero or exceeds `codes`
+    pub fn create(accounts: &[Account], network: NetworkId, threshold: u8, codes: u8) -> Result<(Self, Vec<RecoveryCode>)> {
+        let mut key = encryption::generate_key();
+        let sealed = Self::seal(accounts, &key, threshold, codes);
+        key.zeroize();
+
+        let (encrypted_data, shares) = sealed?;
+        let backup = Self {
+            version: BACKUP_VERSION,
+            network,
+            threshold,
+            codes,
+            encrypted_data,
+        };
+        let recovery_codes = shares.into_iter()
+            .map(|share| RecoveryCode { threshold, share })
+            .collect();
+
+        Ok((backup, recovery_codes))
+    }
+
+    /// Encrypts accounts under `key` and splits the key
+    fn seal(accounts: &[Account], key: &[u8], threshold: u8, codes: u8) -> Result<(Vec<u8>, Vec<shamir::Share>)> {
+        let shares = shamir::split(key, threshold, codes)
+            .map_err(|e| WalletError::KeystoreError(format!("{}", e)))?;
+
+        let mut data = bincode::serialize(accounts)?;
+        let encrypted = encryption::encrypt(&data, key);
+        data.zeroize();
+        Ok((encrypted?, shares))
+    }
+
+    /// Decrypts the accounts with recovery codes
+    ///
+    /// # Errors
+    /// - `InvalidRecoveryCode` if fewer codes than needed are given, or
+    ///   they do not belong to this backup
+    pub fn restore(&self, codes: &[RecoveryCode]) -> Result<Vec<Account>> {
+        if codes.len() < self.threshold as usize {
+            return Err(WalletError::InvalidRecoveryCode(format!(
+                "{} of {} recovery codes needed, {} given", self.threshold, self.codes, codes.len()
+            )));
+        }
+
+        let shares: Vec<shamir::Share> = codes.iter().map(|code| code.share.clone()).collect();
+        let mut key = shamir::combine(&shares)
+            .map_err(|e| WalletError::InvalidRecoveryCode(format!("{}", e)))?;
+        let data = encryption::decrypt(&self.encrypted_data, &key);
+        key.zeroize();
+
+        let mut data = data.map_err(|_| WalletError::InvalidRecoveryCode(
+            "Recovery codes do not belong to this backup".to_string()
+        ))?;
+        let accounts = bincode::deserialize(&data);
+        data.zeroize();
+        Ok(accounts?)
+    }
+
+    /// Gets the network of the backed up wallet
+    pub fn network(&self) -> NetworkId {
+        self.network
+    }
+
+    /// Gets the number of recovery codes needed to restore
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Saves the backup to a file
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Loads a backup from a file
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let json = fs::read_to_string(path)
+            .map_err(|_| WalletError::FileNotFound("Backup file not found".to_string()))?;
+
+        let backup: Self = serde_json::from_str(&json)?;
+        if backup.version != BACKUP_VERSION {
+            return Err(WalletError::SerializationError(format!(
+                "Unsupported backup version {}", backup.version
+            )));
+        }
+        Ok(backup)
+    }
+}
+
+impl Wallet {
+    /// Backs up every account, returning the backup and its recovery codes
+    ///
+    /// # Arguments
+    /// * `threshold` - Number of codes needed to restore
+    /// * `codes` - Number of codes to create
+    ///
+    /// # Errors
+    /// - `WalletLocked` if the wallet is locked
+    pub fn create_backup(&self, threshold: u8, codes: u8) -> Result<(Backup, Vec<RecoveryCode>)> {
+        self.ensure_unlocked()?;
+        let accounts: Vec<Account> = self.list_accounts()
+            .iter()
+            .map(|name| self.get_account(name).cloned())
+            .collect::<Result<_>>()?;
+        Backup::create(&accounts, self.network(), threshold, codes)
+    }
+
+    /// Restores a wallet from a backup and enough of its recovery codes
+    ///
+    /// Balances are not part of a backup; sync the restored wallet to
+    /// recover them.
+    pub fn restore_backup(backup: &Backup, codes: &[RecoveryCode]) -> Result<Self> {
+        let mut wallet = Self::for_network(backup.network());
+        for account in backup.restore(codes)? {
+            wallet.add_account(account)?;
+        }
+        Ok(wallet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_backup_restores_with_any_threshold_codes() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("backup.json");
+
+        let mut wallet = Wallet::for_network(NetworkId::Testnet).with_generated_account();
+        wallet.add_account(Account::generate_with_name("savings".to_string())).unwrap();
+        let (backup, codes) = wallet.create_backup(2, 3).unwrap();
+        backup.save_to_file(&path).unwrap();
+
+        // Codes survive being written down, retyped in lower case
+        let typed: Vec<RecoveryCode> = codes.iter()
+            .map(|code| RecoveryCode::from_string(&code.to_string().to_lowercase()).unwrap())
+            .collect();
+        assert_eq!(typed, codes);
+
+        let backup = Backup::load_from_file(&path).unwrap();
+        let restored = Wallet::restore_backup(&backup, &[typed[2].clone(), typed[0].clone()]).unwrap();
+        assert_eq!(restored.network(), NetworkId::Testnet);
+        assert_eq!(restored.list_accounts(), wallet.list_accounts());
+        assert_eq!(
+            restored.get_account("savings").unwrap().spend_private_key(),
+            wallet.get_account("savings").unwrap().spend_private_key()
+        );
+
+        assert!(matches!(backup.restore(&typed[..1]), Err(WalletError::InvalidRecoveryCode(_))));
+    }
+
+    #[test]
+    fn test_foreign_or_mistyped_codes_are_rejected() {
+        let wallet = Wallet::with_default_account();
+        let (backup, codes) = wallet.create_backup(2, 2).unwrap();
+        let (_, other_codes) = wallet.create_backup(2, 2).unwrap();
+
+        assert!(matches!(
+            backup.restore(&[codes[0].clone(), other_codes[1].clone()]),
+            Err(WalletError::InvalidRecoveryCode(_))
+        ));
+
+        let mut typo = codes[0].to_string().into_bytes();
+        typo[0] = if typo[0] == b'0' { b'1' } else { b'0' };
+        assert!(RecoveryCode::from_string(&String::from_utf8(typo).unwrap()).is_err());
+
+        assert!(wallet.create_backup(3, 2).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+// src/bin/walletd.rs
+
+//! Nyx wallet daemon entry point.
+//!
+//! Writes a fresh session token to the cookie file, then serves clients
+//! until killed. See [`nyx_wallet::daemon`] for the protocol.
+
+use clap::{Arg, Command};
+use nyx_wallet::daemon::{self, DaemonConfig, WalletDaemon, COOKIE_FILE};
+use nyx_wallet::Keystore;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+fn cli() -> Command {
+    Command::new("nyx-walletd")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Nyx wallet daemon")
+        .arg(Arg::new("listen").long("listen").help("Address to listen on, 127.0.0.1:18091 by default"))
+        .arg(Arg::new("node").long("node").help("Node RPC endpoint to sync from, e.g. http://127.0.0.1:8080"))
+        .arg(Arg::new("network").long("network").help("Network of opened wallets, mainnet by default"))
+        .arg(Arg::new("sync-interval").long("sync-interval").help("Seconds between sync polls"))
+        .arg(Arg::new("schedule-interval").long("schedule-interval").help("Seconds between checks for due scheduled payments"))
+        .arg(Arg::new("cookie").long("cookie").help("Token cookie file, in the keystore directory by default"))
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let args = cli().get_matches();
+    let mut config = DaemonConfig::default();
+    if let Some(listen) = args.get_one::<String>("listen") {
+        config.listen = listen.parse()?;
+    }
+    config.node_endpoint = args.get_one::<String>("node").cloned();
+    if let Some(network) = args.get_one::<String>("network") {
+        config.network = network.parse()?;
+    }
+    if let Some(interval) = args.get_one::<String>("sync-interval") {
+        config.sync_interval = Duration::from_secs(interval.parse()?);
+    }
+    if let Some(interval) = args.get_one::<String>("schedule-interval") {
+        config.schedule_interval = Duration::from_secs(interval.parse()?);
+    }
+
+    let cookie = match args.get_one::<String>("cookie") {
+        Some(cookie) => PathBuf::from(cookie),
+        None => {
+            let dir = Keystore::default_directory()?;
+            Keystore::ensure_directory(&dir)?;
+            dir.join(COOKIE_FILE)
+        }
+    };
+
+    let token = daemon::generate_token();
+    let daemon = WalletDaemon::bind(config, token.clone())?;
+    daemon::write_cookie(&cookie, &token)?;
+    eprintln!("Listening on {}, token in {}", daemon.local_addr()?, cookie.display());
+    daemon.run()?;
+    Ok(())
+}
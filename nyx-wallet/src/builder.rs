@@ -1,12 +1,39 @@
 // src/builder.rs
 
 //! Transaction builder for creating privacy-preserving transactions.
+//!
+//! Outputs are shuffled, so their order does not tell the payment from the
+//! change. Outputs are blinded by a mask their recipient derives (see
+//! [`crate::amount`]), except one zero-amount output to a
+//! throwaway address, whose blinding makes the commitments balance against
+//! the inputs'. With it, every transaction has at least [`MIN_OUTPUTS`].
 
 use crate::account::Account;
+use crate::amount;
+use crate::coin_control::format_outpoint;
 use crate::errors::{WalletError, Result};
-use nyx_core::transaction::{Transaction, TxInput, TxOutput};
-use nyx_core::Hash;
-use nyx_crypto::{ring, stealth};
+use nyx_core::extra::{Extra, ExtraField, HtlcWitness};
+use nyx_core::transaction::{HtlcLock, Transaction, TxInput, TxOutput, UnlockTime};
+use nyx_core::versioning::ValidationContext;
+use nyx_core::{Hash, NetworkId, RING_INDEX_VERSION};
+use nyx_crypto::pedersen::{self, BlindingFactor};
+use nyx_crypto::{keys, range, ring, stealth};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use zeroize::Zeroizing;
+
+/// Fewest outputs a built transaction has
+pub const MIN_OUTPUTS: usize = 2;
+
+/// Confirmed output a ring can include
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RingMember {
+    /// Global index the output got when confirmed
+    pub global_index: u64,
+
+    /// Stealth address of the output
+    pub stealth_address: Vec<u8>,
+}
 
 /// UTXO (Unspent Transaction Output)
 #[derive(Clone, Debug)]
@@ -22,6 +49,53 @@ pub struct Utxo {
 
     /// Key image (for preventing double-spends)
     pub key_image: [u8; 32],
+
+    /// Earliest point the output can be spent, if time-locked
+    pub unlock_time: Option<UnlockTime>,
+
+    /// Blinding factor of the output's amount commitment, zero for minted
+    /// outputs
+    pub mask: BlindingFactor,
+
+    /// The output as a ring member, once it is confirmed
+    pub ring_member: Option<RingMember>,
+}
+
+impl Utxo {
+    /// Checks whether the UTXO can be spent in the given context
+    pub fn is_unlocked(&self, ctx: &ValidationContext) -> bool {
+        self.unlock_time.is_none_or(|unlock_time| unlock_time.is_unlocked(ctx))
+    }
+}
+
+/// Output requested from the builder
+struct PlannedOutput {
+    /// Recipient's view public key
+    view_public: Vec<u8>,
+
+    /// Recipient's spend public key
+    spend_public: Vec<u8>,
+
+    /// Amount to send
+    amount: u64,
+
+    /// Earliest point the output can be spent, if time-locked
+    unlock_time: Option<UnlockTime>,
+
+    /// Hash and time lock of a swap output
+    htlc: Option<HtlcLock>,
+}
+
+/// How an HTLC input is unlocked
+struct HtlcUnlock {
+    /// Position of the input
+    input: u32,
+
+    /// Swap secret when claiming, `None` when refunding
+    preimage: Option<[u8; 32]>,
+
+    /// Ed25519 seed of the claim or refund key
+    seed: Zeroizing<[u8; 32]>,
 }
 
 /// Transaction builder
@@ -30,13 +104,19 @@ pub struct TransactionBuilder {
     inputs: Vec<Utxo>,
 
     /// Outputs to create
-    outputs: Vec<(Vec<u8>, Vec<u8>, u64)>, // (view_pub, spend_pub, amount)
+    outputs: Vec<PlannedOutput>,
 
-    /// Ring members for privacy (mock decoys)
-    ring_members: Vec<Vec<u8>>,
+    /// Decoys every input's ring includes
+    ring_members: Vec<RingMember>,
 
     /// Sender account
     sender: Option<Account>,
+
+    /// Network the transaction is valid on
+    network: NetworkId,
+
+    /// Witnesses to sign for HTLC inputs
+    htlc_unlocks: Vec<HtlcUnlock>,
 }
 
 impl TransactionBuilder {
@@ -47,9 +127,17 @@ impl TransactionBuilder {
             outputs: Vec::new(),
             ring_members: Vec::new(),
             sender: None,
+            network: NetworkId::Mainnet,
+            htlc_unlocks: Vec::new(),
         }
     }
 
+    /// Sets the network the transaction is valid on
+    pub fn network(mut self, network: NetworkId) -> Self {
+        self.network = network;
+        self
+    }
+
     /// Sets the sender account
     pub fn sender(mut self, account: Account) -> Self {
         self.sender = Some(account);
@@ -62,6 +150,22 @@ impl TransactionBuilder {
         self
     }
 
+    /// Adds an HTLC output to spend
+    ///
+    /// The UTXO's key image must be the output's
+    /// [HTLC key image](HtlcLock::key_image). The input is claimed with
+    /// `preimage`, or refunded without one, signing with the key `seed`
+    /// derives.
+    pub fn add_htlc_input(mut self, utxo: Utxo, preimage: Option<[u8; 32]>, seed: &[u8; 32]) -> Self {
+        self.htlc_unlocks.push(HtlcUnlock {
+            input: self.inputs.len() as u32,
+            preimage,
+            seed: Zeroizing::new(*seed),
+        });
+        self.inputs.push(utxo);
+        self
+    }
+
     /// Adds an output
     ///
     /// # Arguments
@@ -74,12 +178,53 @@ impl TransactionBuilder {
         spend_public: Vec<u8>,
         amount: u64,
     ) -> Self {
-        self.outputs.push((view_public, spend_public, amount));
+        self.outputs.push(PlannedOutput { view_public, spend_public, amount, unlock_time: None, htlc: None });
         self
     }
 
-    /// Adds ring members for privacy (decoys)
-    pub fn with_ring_members(mut self, members: Vec<Vec<u8>>) -> Self {
+    /// Adds an output that cannot be spent before `unlock_time`
+    ///
+    /// Locked outputs make vesting payments and simple escrow: consensus
+    /// rejects any spend of the output until it unlocks.
+    pub fn add_locked_output(
+        mut self,
+        view_public: Vec<u8>,
+        spend_public: Vec<u8>,
+        amount: u64,
+        unlock_time: UnlockTime,
+    ) -> Self {
+        self.outputs.push(PlannedOutput {
+            view_public,
+            spend_public,
+            amount,
+            unlock_time: Some(unlock_time),
+            htlc: None,
+        });
+        self
+    }
+
+    /// Adds an output spent by revealing the preimage of a hashlock, or
+    /// refunded after a timeout
+    ///
+    /// The recipient of an HTLC output learns its amount like any other,
+    /// but spending it takes the claim or refund key of `htlc`, not the
+    /// recipient's spend key.
+    pub fn add_htlc_output(
+        mut self,
+        view_public: Vec<u8>,
+        spend_public: Vec<u8>,
+        amount: u64,
+        htlc: HtlcLock,
+    ) -> Self {
+        self.outputs.push(PlannedOutput { view_public, spend_public, amount, unlock_time: None, htlc: Some(htlc) });
+        self
+    }
+
+    /// Adds decoys to every input's ring
+    ///
+    /// Decoys are confirmed outputs, as a node resolves them from their
+    /// global indices.
+    pub fn with_ring_members(mut self, members: Vec<RingMember>) -> Self {
         self.ring_members = members;
         self
     }
@@ -90,6 +235,16 @@ impl TransactionBuilder {
     /// * `parent1` - First parent transaction hash (for DAG)
     /// * `parent2` - Second parent transaction hash (for DAG)
     pub fn build(self, parent1: Hash, parent2: Hash) -> Result<Transaction> {
+        self.build_with_tx_keys(parent1, parent2).map(|(tx, _)| tx)
+    }
+
+    /// Builds and signs the transaction, also returning the transaction key
+    /// of each output, in output order
+    ///
+    /// A transaction key is the randomness an output's stealth address was
+    /// derived from. Disclosing it proves the payment (see
+    /// [`crate::proof`]); it is otherwise secret.
+    pub fn build_with_tx_keys(mut self, parent1: Hash, parent2: Hash) -> Result<(Transaction, Vec<Vec<u8>>)> {
         let sender = self.sender
             .ok_or_else(|| WalletError::TransactionBuildError("No sender set".to_string()))?;
 
@@ -105,78 +260,149 @@ impl TransactionBuilder {
             ));
         }
 
-        // Build inputs
+        // Shuffle outputs, then add the balancing output at a random
+        // position
+        let mut rng = rand::thread_rng();
+        self.outputs.shuffle(&mut rng);
+        let balancing = rng.gen_range(0..=self.outputs.len());
+        self.outputs.insert(balancing, PlannedOutput {
+            view_public: keys::generate_ed25519_keypair_as_struct().public_key,
+            spend_public: keys::generate_ed25519_keypair_as_struct().public_key,
+            amount: 0,
+            unlock_time: None,
+            htlc: None,
+        });
+
+        // Build inputs, each with a ring of the output it spends and the
+        // decoys, in global index order
         let mut tx_inputs = Vec::new();
+        let mut rings = Vec::new();
         for utxo in &self.inputs {
-            let input = TxInput {
+            let outpoint = format_outpoint(&(utxo.tx_hash, utxo.index));
+            let own = utxo.ring_member.clone().ok_or_else(|| {
+                WalletError::TransactionBuildError(format!("Output {} is not confirmed yet", outpoint))
+            })?;
+            let mut members = vec![own.clone()];
+            members.extend(self.ring_members.iter().cloned());
+            members.sort_by_key(|member| member.global_index);
+            members.dedup_by_key(|member| member.global_index);
+
+            let ring_indices = members.iter()
+                .map(|member| u32::try_from(member.global_index))
+                .collect::<std::result::Result<Vec<u32>, _>>()
+                .map_err(|_| WalletError::TransactionBuildError(format!(
+                    "Ring of output {} has a global index beyond the format's range",
+                    outpoint
+                )))?;
+            tx_inputs.push(TxInput {
                 prev_tx: utxo.tx_hash,
                 index: utxo.index,
                 key_image: utxo.key_image,
-                ring_indices: vec![0, 1, 2, 3], // Mock indices
-            };
-            tx_inputs.push(input);
+                ring_indices,
+            });
+            rings.push((own.stealth_address, members.into_iter().map(|member| member.stealth_address).collect::<Vec<_>>()));
         }
 
-        // Build outputs with stealth addresses
+        // Build outputs with stealth addresses, and an amount hint for
+        // each recipient
         let mut tx_outputs = Vec::new();
-        for (view_pub, spend_pub, amount) in &self.outputs {
+        let mut hints = Vec::new();
+        let mut tx_keys = Vec::new();
+        let mut output_masks = Vec::new();
+        for (index, planned) in self.outputs.iter().enumerate() {
             // Generate stealth address
             let random = stealth::generate_random_ephemeral();
             let (stealth_address, ephemeral_pubkey) = stealth::generate_stealth_address(
-                view_pub,
-                spend_pub,
+                &planned.view_public,
+                &planned.spend_public,
                 &random,
             )?;
-
-            // Mock amount commitment (in production, use Pedersen commitments)
-            let amount_commitment = Self::mock_amount_commitment(*amount);
-
-            // Mock range proof
-            let range_proof = vec![0u8; 100]; // Placeholder
+            let shared_secret = stealth::derive_sender_shared_secret(&planned.view_public, &random)?;
+            hints.push(amount::encrypt_amount(&shared_secret, index as u32, planned.amount));
+
+            // Commit under a mask the recipient can derive too
+            let mask = nyx_crypto::amount::amount_mask(&shared_secret, index as u32);
+            let amount_commitment = pedersen::commit(planned.amount, &mask).to_bytes().to_vec();
+            // The balancing output is proven once its mask is known
+            let mut range_proof = Vec::new();
+            if index != balancing {
+                range_proof = range::prove(planned.amount, &mask);
+                output_masks.push(mask);
+            }
 
             let output = TxOutput {
                 stealth_address,
                 amount_commitment,
                 range_proof,
                 ephemeral_pubkey,
+                unlock_time: planned.unlock_time,
+                htlc: planned.htlc,
             };
             tx_outputs.push(output);
+            tx_keys.push(random);
         }
 
-        // Generate ring signature
-        let mut ring = vec![sender.spend_public_key().to_vec()];
-        ring.extend(self.ring_members.clone());
-
-        // If not enough ring members, add mocks
-        while ring.len() < 16 {
-            ring.push(vec![0u8; 32]);
-        }
-
-        let message = b"transaction_data"; // In production, serialize tx data
-        let ring_sig = ring::generate_ring_signature(
-            message,
-            sender.spend_private_key(),
-            sender.spend_public_key(),
-            &ring,
-        )?;
-
-        // Build transaction
-        let tx = Transaction::new(
+        // Blind the balancing output with what the input masks leave over
+        let input_masks: Vec<BlindingFactor> = self.inputs.iter().map(|utxo| utxo.mask).collect();
+        let mask = pedersen::sub_blindings(
+            &pedersen::sum_blindings(&input_masks),
+            &pedersen::sum_blindings(&output_masks),
+        );
+        tx_outputs[balancing].amount_commitment = pedersen::commit(0, &mask).to_bytes().to_vec();
+        tx_outputs[balancing].range_proof = range::prove(0, &mask);
+
+        // Build the unsigned transaction, then sign its signing message
+        // (which commits to the network) once per input. Ring indices are
+        // global output indices, so the version is at least 3
+        let unsigned = rings.iter()
+            .map(|(_, ring)| ring::RingSignature {
+                ring_members: ring.clone(),
+                signature: Vec::new(),
+                key_image: [0u8; 32],
+            })
+            .collect();
+        let mut tx = Transaction::try_new(
             tx_inputs,
             tx_outputs,
-            ring_sig,
-            sender.spend_public_key().to_vec(),
+            unsigned,
+            Vec::new(),
             parent1,
             parent2,
-        );
+        )
+        .map_err(|e| WalletError::TransactionBuildError(e.to_string()))?
+        .with_network(self.network);
+        tx.version = tx.version.max(RING_INDEX_VERSION);
+        let mut extra = Extra::new().with_field(amount::encode_hints(&hints));
+        tx.extra = extra.to_bytes();
+
+        // HTLC witnesses sign everything but themselves, then the ring
+        // signatures cover them too
+        if !self.htlc_unlocks.is_empty() {
+            let message = tx.htlc_witness_message();
+            let witnesses = self.htlc_unlocks.iter()
+                .map(|unlock| HtlcWitness {
+                    input: unlock.input,
+                    preimage: unlock.preimage,
+                    signature: keys::sign_ed25519(&message, &unlock.seed),
+                })
+                .collect();
+            extra.set(ExtraField::HtlcWitnesses(witnesses));
+            tx.extra = extra.to_bytes();
+        }
 
-        Ok(tx)
-    }
+        // HTLC inputs prove their key image with the public spend key, as
+        // the witness is what authorizes them
+        let message = tx.signing_message();
+        for (input, (utxo, (stealth_address, ring))) in self.inputs.iter().zip(&rings).enumerate() {
+            let private_key = if self.htlc_unlocks.iter().any(|unlock| unlock.input as usize == input) {
+                Zeroizing::new(HtlcLock::spend_key(&utxo.tx_hash, utxo.index))
+            } else {
+                sender.output_private_key(&utxo.tx_hash, utxo.index)
+            };
+            tx.sign(input, &message, private_key.as_ref(), stealth_address, ring)?;
+        }
 
-    /// Mock amount commitment (in production, use Pedersen commitment)
-    fn mock_amount_commitment(amount: u64) -> Vec<u8> {
-        let amount_bytes = amount.to_le_bytes();
-        nyx_crypto::hash::blake3_hash(&amount_bytes).to_vec()
+        Ok((tx, tx_keys))
     }
 }
 
@@ -196,9 +422,16 @@ mod tests {
             index: 0,
             amount,
             key_image: [2u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
         }
     }
 
+    fn create_decoy(global_index: u64) -> RingMember {
+        RingMember { global_index, stealth_address: vec![global_index as u8; 32] }
+    }
+
     #[test]
     fn test_builder_creation() {
         let builder = TransactionBuilder::new();
@@ -239,8 +472,44 @@ mod tests {
             .build([0u8; 32], [1u8; 32])
             .unwrap();
 
+        // Balanced by an output nobody can spend
         assert_eq!(tx.inputs.len(), 1);
-        assert_eq!(tx.outputs.len(), 1);
+        assert_eq!(tx.outputs.len(), MIN_OUTPUTS);
+        let mut amounts: Vec<Option<u64>> = (0..2)
+            .map(|index| amount::recover_amount(&tx, index, account.view_private_key()))
+            .collect();
+        amounts.sort();
+        assert_eq!(amounts, vec![None, Some(900)]);
+    }
+
+    #[test]
+    fn test_commitments_balance_against_inputs() {
+        let (account, recipient) = (Account::generate(), Account::generate());
+        let minted = create_mock_utxo(600);
+        let received = Utxo { index: 1, key_image: [3u8; 32], mask: pedersen::random_blinding(), ..create_mock_utxo(400) };
+        let input_commitments: Vec<Vec<u8>> = [&minted, &received].iter()
+            .map(|utxo| pedersen::commit(utxo.amount, &utxo.mask).to_bytes().to_vec())
+            .collect();
+
+        let tx = TransactionBuilder::new()
+            .sender(account.clone())
+            .add_input(minted)
+            .add_input(received)
+            .add_output(recipient.view_public_key().to_vec(), recipient.spend_public_key().to_vec(), 700)
+            .add_output(account.view_public_key().to_vec(), account.spend_public_key().to_vec(), 300)
+            .build([0u8; 32], [1u8; 32])
+            .unwrap();
+
+        // Both recipients open their outputs, and the balancing output is
+        // nobody's
+        assert_eq!(tx.outputs.len(), 3);
+        nyx_core::balance::verify_balance(&tx, &input_commitments, 0).unwrap();
+        let amounts: Vec<Option<u64>> = (0..3)
+            .map(|index| amount::recover_amount(&tx, index, recipient.view_private_key())
+                .or_else(|| amount::recover_amount(&tx, index, account.view_private_key())))
+            .collect();
+        assert_eq!(amounts.iter().filter(|amount| amount.is_none()).count(), 1);
+        assert!(amounts.contains(&Some(700)) && amounts.contains(&Some(300)));
     }
 
     #[test]
@@ -281,12 +550,24 @@ mod tests {
     }
 
     #[test]
-    fn test_mock_amount_commitment() {
-        let commitment1 = TransactionBuilder::mock_amount_commitment(1000);
-        let commitment2 = TransactionBuilder::mock_amount_commitment(1000);
+    fn test_equal_amounts_get_distinct_commitments() {
+        let account = Account::generate();
+        let recipient = Account::generate();
+        let tx = TransactionBuilder::new()
+            .sender(account)
+            .add_input(create_mock_utxo(1000))
+            .add_output(recipient.view_public_key().to_vec(), recipient.spend_public_key().to_vec(), 500)
+            .add_output(recipient.view_public_key().to_vec(), recipient.spend_public_key().to_vec(), 500)
+            .build([0u8; 32], [1u8; 32])
+            .unwrap();
 
-        assert_eq!(commitment1, commitment2);
-        assert_eq!(commitment1.len(), 32);
+        // Each output is blinded by its own shared secret, yet the recipient
+        // opens both
+        let paid: Vec<usize> = (0..3)
+            .filter(|&index| amount::recover_amount(&tx, index as u32, recipient.view_private_key()) == Some(500))
+            .collect();
+        assert_eq!(paid.len(), 2);
+        assert_ne!(tx.outputs[paid[0]].amount_commitment, tx.outputs[paid[1]].amount_commitment);
     }
 
     #[test]
@@ -294,11 +575,7 @@ mod tests {
         let account = Account::generate();
         let utxo = create_mock_utxo(1000);
 
-        let ring_members = vec![
-            vec![3u8; 32],
-            vec![4u8; 32],
-            vec![5u8; 32],
-        ];
+        let ring_members = vec![create_decoy(5), create_decoy(3), create_decoy(0)];
 
         let tx = TransactionBuilder::new()
             .sender(account.clone())
@@ -313,6 +590,27 @@ mod tests {
             .unwrap();
 
         assert_eq!(tx.inputs.len(), 1);
+        assert_eq!(tx.inputs[0].ring_indices, vec![0, 3, 5]);
+        assert_eq!(tx.ring_signatures[0].ring_members[0], vec![1u8; 32]);
+        assert!(tx.version >= RING_INDEX_VERSION);
+    }
+
+    #[test]
+    fn test_builder_unconfirmed_input() {
+        let account = Account::generate();
+        let utxo = Utxo { ring_member: None, ..create_mock_utxo(1000) };
+
+        let result = TransactionBuilder::new()
+            .sender(account.clone())
+            .add_input(utxo)
+            .add_output(
+                account.address.view_public.clone(),
+                account.address.spend_public.clone(),
+                900
+            )
+            .build([0u8; 32], [1u8; 32]);
+
+        assert!(result.is_err());
     }
 
     #[test]
@@ -320,7 +618,12 @@ mod tests {
         let account = Account::generate();
 
         let utxo1 = create_mock_utxo(500);
-        let utxo2 = create_mock_utxo(500);
+        let utxo2 = Utxo {
+            index: 1,
+            key_image: [3u8; 32],
+            ring_member: Some(create_decoy(1)),
+            ..create_mock_utxo(500)
+        };
 
         let tx = TransactionBuilder::new()
             .sender(account.clone())
@@ -340,6 +643,38 @@ mod tests {
             .unwrap();
 
         assert_eq!(tx.inputs.len(), 2);
-        assert_eq!(tx.outputs.len(), 2);
+        assert_eq!(tx.outputs.len(), 3);
+    }
+
+    #[test]
+    fn test_each_recipient_detects_own_output() {
+        let recipients: Vec<Account> = (0..3).map(|_| Account::generate()).collect();
+        let mut builder = TransactionBuilder::new()
+            .sender(Account::generate())
+            .add_input(create_mock_utxo(600));
+        for (i, recipient) in recipients.iter().enumerate() {
+            builder = builder.add_output(
+                recipient.view_public_key().to_vec(),
+                recipient.spend_public_key().to_vec(),
+                100 * (i as u64 + 1),
+            );
+        }
+        let tx = builder.build([0u8; 32], [1u8; 32]).unwrap();
+
+        assert!(tx.tx_key.is_empty());
+        for (i, recipient) in recipients.iter().enumerate() {
+            let mine: Vec<usize> = tx.outputs.iter()
+                .enumerate()
+                .filter(|(_, output)| stealth::is_mine(
+                    &output.stealth_address,
+                    recipient.view_private_key(),
+                    recipient.spend_public_key(),
+                    &output.ephemeral_pubkey,
+                ).unwrap())
+                .map(|(index, _)| index)
+                .collect();
+            assert_eq!(mine.len(), 1);
+            assert_eq!(amount::recover_amount(&tx, mine[0] as u32, recipient.view_private_key()), Some(100 * (i as u64 + 1)));
+        }
     }
 }
@@ -0,0 +1,209 @@
+// src/change.rs
+
+//! Change outputs.
+//!
+//! Change goes to an internal chain of subaddresses of the sending account
+//! rather than back to its public address. Subaddress `i` has spend key
+//! B + H(a || "change" || i)G under the account's view key, so a scan with
+//! the view key finds change without telling anyone else it is related to
+//! the account. Every send uses the next unused index.
+//!
+//! The index is deterministic: scanning recognizes the next
+//! [`CHANGE_LOOKAHEAD`] indices past the last one seen, so a wallet
+//! restored from its keys finds all its change and carries on after it.
+
+use crate::account::Account;
+use crate::errors::Result;
+use crate::wallet::Wallet;
+use nyx_core::transaction::{Transaction, TxOutput};
+use nyx_crypto::stealth;
+
+/// Unused change indices recognized past the last one seen
+pub const CHANGE_LOOKAHEAD: u32 = 20;
+
+/// Change subaddresses of one account
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ChangeChain {
+    /// Next index to pay change to
+    next: u32,
+
+    /// Spend keys of indices up to `next + CHANGE_LOOKAHEAD`
+    keys: Vec<Vec<u8>>,
+}
+
+impl ChangeChain {
+    /// Chain of `account` whose next index is `next`
+    pub(crate) fn new(account: &Account, next: u32) -> Result<Self> {
+        let mut chain = Self { next, keys: Vec::new() };
+        chain.fill(account)?;
+        Ok(chain)
+    }
+
+    /// Derives keys up to the lookahead
+    fn fill(&mut self, account: &Account) -> Result<()> {
+        while (self.keys.len() as u32) < self.next + CHANGE_LOOKAHEAD {
+            self.keys.push(account.change_spend_public_key(self.keys.len() as u32)?);
+        }
+        Ok(())
+    }
+
+    /// Index of a change spend key
+    fn index_of(&self, spend_public: &[u8]) -> Option<u32> {
+        self.keys.iter().position(|key| key == spend_public).map(|index| index as u32)
+    }
+}
+
+/// Key of an account an output pays
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum OwnedKey {
+    /// The account's public address
+    Primary,
+
+    /// Change subaddress at an index
+    Change(u32),
+
+    /// Watched deposit subaddress at an index
+    Deposit(u32),
+}
+
+impl Wallet {
+    /// Gets the next change index of an account
+    pub fn next_change_index(&self, account_name: &str) -> u32 {
+        self.change.get(account_name).map_or(0, |chain| chain.next)
+    }
+
+    /// Spend key the next change of `account` goes to
+    pub(crate) fn next_change_key(&self, account: &Account) -> Result<Vec<u8>> {
+        match self.change.get(&account.name) {
+            Some(chain) => Ok(chain.keys[chain.next as usize].clone()),
+            None => account.change_spend_public_key(0),
+        }
+    }
+
+    /// Finds which key of `account`, if any, an output pays
+    pub(crate) fn owned_key(&self, account: &Account, output: &TxOutput) -> Option<OwnedKey> {
+        self.owned_key_of(account, &output.stealth_address, &output.ephemeral_pubkey)
+    }
+
+    /// Finds which key of `account`, if any, a stealth address pays
+    pub(crate) fn owned_key_of(
+        &self,
+        account: &Account,
+        stealth_address: &[u8],
+        ephemeral_pubkey: &[u8],
+    ) -> Option<OwnedKey> {
+        let secret = stealth::derive_shared_secret(account.view_private_key(), ephemeral_pubkey).ok()?;
+        let spend_public = stealth::recover_spend_public(stealth_address, &secret).ok()?;
+        if spend_public == account.spend_public_key() {
+            return Some(OwnedKey::Primary);
+        }
+        if let Some(index) = self.change.get(&account.name).and_then(|chain| chain.index_of(&spend_public)) {
+            return Some(OwnedKey::Change(index));
+        }
+        self.deposits.get(&account.name)?.get(&spend_public).copied().map(OwnedKey::Deposit)
+    }
+
+    /// Moves change indices past those a transaction pays
+    pub(crate) fn observe_change(&mut self, tx: &Transaction) -> Result<()> {
+        for output in &tx.outputs {
+            let seen = self.accounts.iter().find_map(|account| match self.owned_key(account, output) {
+                Some(OwnedKey::Change(index)) => Some((account.clone(), index)),
+                _ => None,
+            });
+            let Some((account, index)) = seen else {
+                continue;
+            };
+            if let Some(chain) = self.change.get_mut(&account.name) {
+                if index >= chain.next {
+                    chain.next = index + 1;
+                    chain.fill(&account)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{RingMember, Utxo};
+
+    fn funded_wallet() -> Wallet {
+        let mut wallet = Wallet::with_default_account();
+        let name = wallet.get_active_account().unwrap().name.clone();
+        for nonce in 1..=2u8 {
+            let utxo = Utxo {
+                tx_hash: [nonce; 32],
+                index: 0,
+                amount: 1000,
+                key_image: [nonce; 32],
+                unlock_time: None,
+                mask: [0u8; 32],
+                ring_member: Some(RingMember { global_index: 0, stealth_address: vec![nonce; 32] }),
+            };
+            wallet.add_utxo(&name, utxo).unwrap();
+        }
+        wallet
+    }
+
+    #[test]
+    fn test_change_uses_fresh_subaddresses() {
+        let mut wallet = funded_wallet();
+        let account = wallet.get_active_account().unwrap().clone();
+        let recipient = Account::generate().address.to_string();
+
+        let mut sent = Vec::new();
+        for _ in 0..2 {
+            let tx = wallet.build_transaction(&recipient, 400, 0).unwrap();
+            wallet.submit_transaction(&tx).unwrap();
+            sent.push(tx);
+        }
+        assert_eq!(wallet.next_change_index(&account.name), 2);
+
+        // Neither transaction pays the public address; each pays its own
+        // change key
+        for (tx, index) in sent.into_iter().zip(0..) {
+            let tx_hash = tx.id();
+            let owned: Vec<OwnedKey> = tx.outputs.iter()
+                .filter_map(|output| wallet.owned_key(&account, output))
+                .collect();
+            assert_eq!(owned, vec![OwnedKey::Change(index)]);
+
+            let scanned = wallet.scan_transaction(tx_hash, &tx);
+            assert_eq!(scanned.len(), 1);
+            assert_eq!(scanned[0].amount, Some(600));
+        }
+    }
+
+    #[test]
+    fn test_restored_wallet_finds_change() {
+        let mut wallet = funded_wallet();
+        let account = wallet.get_active_account().unwrap().clone();
+        let tx = wallet.build_transaction(&Account::generate().address.to_string(), 300, 0).unwrap();
+        let tx_hash = wallet.submit_transaction(&tx).unwrap();
+
+        let mut restored = Wallet::new();
+        restored.add_account(account.clone()).unwrap();
+        let credited = restored.sync_transaction(tx_hash, &tx).unwrap();
+        assert_eq!(credited.len(), 1);
+        assert_eq!(restored.get_balance(), 700);
+        assert_eq!(restored.next_change_index(&account.name), 1);
+    }
+
+    #[test]
+    fn test_exact_payment_is_balanced_without_change() {
+        let mut wallet = funded_wallet();
+        let account = wallet.get_active_account().unwrap().clone();
+        let recipient = Account::generate();
+        let tx = wallet.build_transaction(&recipient.address.to_string(), 1000, 0).unwrap();
+
+        // The balancing output pays nothing the wallet owns
+        assert_eq!(tx.outputs.len(), crate::builder::MIN_OUTPUTS);
+        assert!(tx.outputs.iter().all(|output| wallet.owned_key(&account, output).is_none()));
+
+        // Nothing is credited back
+        wallet.sync_transaction(tx.id(), &tx).unwrap();
+        assert_eq!(wallet.get_balance(), 1000);
+    }
+}
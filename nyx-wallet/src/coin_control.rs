@@ -0,0 +1,274 @@
+// src/coin_control.rs
+
+//! Coin control.
+//!
+//! Outputs are named by their outpoint, `<tx hash>:<index>`. A frozen
+//! output is never picked by automatic input selection, which keeps dust,
+//! tainted or earmarked coins out of everyday sends; a send can still spend
+//! it by naming it in [`SendOptions::with_input`]. Outputs can also carry a
+//! free-form label. Both are dropped once the output is spent.
+
+use crate::builder::Utxo;
+use crate::errors::{WalletError, Result};
+use crate::wallet::Wallet;
+use nyx_core::transaction::{HtlcLock, UnlockTime};
+use nyx_core::Hash;
+
+/// Reference to a transaction output
+pub type Outpoint = (Hash, u32);
+
+/// Parses an outpoint written as `<hex tx hash>:<index>`
+pub fn parse_outpoint(s: &str) -> Result<Outpoint> {
+    let invalid = || WalletError::SerializationError(format!("Invalid outpoint '{}'", s));
+    let (hash, index) = s.split_once(':').ok_or_else(invalid)?;
+    let hash = hex::decode(hash).ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(invalid)?;
+    Ok((hash, index.parse().map_err(|_| invalid())?))
+}
+
+/// Formats an outpoint as `<hex tx hash>:<index>`
+pub fn format_outpoint(outpoint: &Outpoint) -> String {
+    format!("{}:{}", hex::encode(outpoint.0), outpoint.1)
+}
+
+/// Options for building a send
+#[derive(Clone, Debug, Default)]
+pub struct SendOptions {
+    /// Outputs to spend instead of selecting automatically
+    pub(crate) inputs: Vec<Outpoint>,
+
+    /// Earliest point the recipient can spend the payment
+    pub(crate) unlock_time: Option<UnlockTime>,
+
+    /// Hash and time lock of the payment, for atomic swaps
+    pub(crate) htlc: Option<HtlcLock>,
+}
+
+impl SendOptions {
+    /// Options selecting inputs automatically, without a time lock
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spends `tx_hash:index`, frozen or not
+    ///
+    /// Once any input is named, only the named inputs are spent.
+    pub fn with_input(mut self, tx_hash: Hash, index: u32) -> Self {
+        if !self.inputs.contains(&(tx_hash, index)) {
+            self.inputs.push((tx_hash, index));
+        }
+        self
+    }
+
+    /// Locks the payment until `unlock_time`; change is not locked
+    pub fn with_unlock_time(mut self, unlock_time: UnlockTime) -> Self {
+        self.unlock_time = Some(unlock_time);
+        self
+    }
+
+    /// Pays into an HTLC output; change is not locked
+    ///
+    /// See [`crate::htlc`].
+    pub fn with_htlc(mut self, htlc: HtlcLock) -> Self {
+        self.htlc = Some(htlc);
+        self
+    }
+}
+
+/// Unspent output of an account with its coin control state
+#[derive(Clone, Debug)]
+pub struct OutputInfo {
+    /// The output
+    pub utxo: Utxo,
+
+    /// Whether automatic selection skips it
+    pub frozen: bool,
+
+    /// Label given with [`Wallet::label_output`]
+    pub label: Option<String>,
+}
+
+impl Wallet {
+    /// Keeps an output out of automatic input selection
+    ///
+    /// # Errors
+    /// - `UnknownOutput` if no account owns the output
+    pub fn freeze_output(&mut self, tx_hash: Hash, index: u32) -> Result<()> {
+        self.find_output(tx_hash, index)?;
+        self.frozen.insert((tx_hash, index));
+        Ok(())
+    }
+
+    /// Makes a frozen output available to automatic selection again
+    pub fn thaw_output(&mut self, tx_hash: Hash, index: u32) {
+        self.frozen.remove(&(tx_hash, index));
+    }
+
+    /// Checks whether an output is frozen
+    pub fn is_frozen(&self, tx_hash: Hash, index: u32) -> bool {
+        self.frozen.contains(&(tx_hash, index))
+    }
+
+    /// Labels an output; an empty label removes it
+    ///
+    /// # Errors
+    /// - `UnknownOutput` if no account owns the output
+    pub fn label_output(&mut self, tx_hash: Hash, index: u32, label: &str) -> Result<()> {
+        self.find_output(tx_hash, index)?;
+        if label.is_empty() {
+            self.labels.remove(&(tx_hash, index));
+        } else {
+            self.labels.insert((tx_hash, index), label.to_string());
+        }
+        Ok(())
+    }
+
+    /// Gets the label of an output
+    pub fn output_label(&self, tx_hash: Hash, index: u32) -> Option<&str> {
+        self.labels.get(&(tx_hash, index)).map(String::as_str)
+    }
+
+    /// Lists an account's unspent outputs with their coin control state
+    pub fn list_outputs(&self, account_name: &str) -> Vec<OutputInfo> {
+        self.get_utxos(account_name).into_iter()
+            .map(|utxo| OutputInfo {
+                utxo: utxo.clone(),
+                frozen: self.is_frozen(utxo.tx_hash, utxo.index),
+                label: self.output_label(utxo.tx_hash, utxo.index).map(str::to_string),
+            })
+            .collect()
+    }
+
+    /// Finds the owner of an output
+    fn find_output(&self, tx_hash: Hash, index: u32) -> Result<&str> {
+        self.utxos.iter()
+            .find(|(_, utxos)| utxos.iter().any(|u| u.tx_hash == tx_hash && u.index == index))
+            .map(|(account, _)| account.as_str())
+            .ok_or_else(|| WalletError::UnknownOutput(format_outpoint(&(tx_hash, index))))
+    }
+
+    /// Looks up the inputs named in `options` among an account's outputs
+    ///
+    /// # Errors
+    /// - `UnknownOutput` if the account does not own a named output
+    /// - `TransactionBuildError` if a named output is still time-locked
+    pub(crate) fn chosen_inputs(&self, account_name: &str, options: &SendOptions) -> Result<Vec<Utxo>> {
+        let ctx = self.spend_context();
+        let utxos = self.get_utxos(account_name);
+        options.inputs.iter()
+            .map(|outpoint| {
+                let utxo = utxos.iter()
+                    .find(|u| (u.tx_hash, u.index) == *outpoint)
+                    .ok_or_else(|| WalletError::UnknownOutput(format_outpoint(outpoint)))?;
+                if !utxo.is_unlocked(&ctx) {
+                    return Err(WalletError::TransactionBuildError(format!(
+                        "Input {} is time-locked", format_outpoint(outpoint)
+                    )));
+                }
+                Ok((*utxo).clone())
+            })
+            .collect()
+    }
+
+    /// Forgets the coin control state of spent outputs
+    pub(crate) fn forget_spent_outputs(&mut self) {
+        let utxos = &self.utxos;
+        let unspent = |outpoint: &Outpoint| utxos.values()
+            .any(|utxos| utxos.iter().any(|u| (u.tx_hash, u.index) == *outpoint));
+        self.frozen.retain(unspent);
+        self.labels.retain(|outpoint, _| unspent(outpoint));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::RingMember;
+
+    fn wallet_with_outputs() -> (Wallet, String, String) {
+        let mut wallet = Wallet::with_default_account();
+        let account = wallet.get_active_account().unwrap().clone();
+        for (nonce, amount) in [(1u8, 500), (2, 1000)] {
+            let utxo = Utxo {
+                tx_hash: [nonce; 32],
+                index: 0,
+                amount,
+                key_image: [nonce; 32],
+                unlock_time: None,
+                mask: [0u8; 32],
+                ring_member: Some(RingMember { global_index: 0, stealth_address: vec![nonce; 32] }),
+            };
+            wallet.add_utxo(&account.name, utxo).unwrap();
+        }
+        (wallet, account.name.clone(), account.address.to_string())
+    }
+
+    #[test]
+    fn test_outpoint_round_trip() {
+        let outpoint = ([7u8; 32], 3);
+        assert_eq!(parse_outpoint(&format_outpoint(&outpoint)).unwrap(), outpoint);
+        assert!(parse_outpoint("07:3").is_err());
+        assert!(parse_outpoint(&hex::encode([7u8; 32])).is_err());
+    }
+
+    #[test]
+    fn test_frozen_outputs_are_not_selected() {
+        let (mut wallet, name, address) = wallet_with_outputs();
+        wallet.freeze_output([1u8; 32], 0).unwrap();
+        assert!(matches!(wallet.freeze_output([9u8; 32], 0), Err(WalletError::UnknownOutput(_))));
+
+        let tx = wallet.build_transaction(&address, 100, 0).unwrap();
+        assert_eq!(tx.inputs.len(), 1);
+        assert_eq!(tx.inputs[0].prev_tx, [2u8; 32]);
+
+        // Only the frozen output would cover the amount
+        wallet.freeze_output([2u8; 32], 0).unwrap();
+        assert!(matches!(
+            wallet.build_transaction(&address, 100, 0),
+            Err(WalletError::InsufficientBalance { .. })
+        ));
+
+        wallet.thaw_output([2u8; 32], 0);
+        assert!(wallet.list_outputs(&name).iter().any(|output| output.frozen));
+        assert!(wallet.build_transaction(&address, 100, 0).is_ok());
+    }
+
+    #[test]
+    fn test_named_inputs_are_spent() {
+        let (mut wallet, _, address) = wallet_with_outputs();
+        wallet.freeze_output([1u8; 32], 0).unwrap();
+
+        let options = SendOptions::new().with_input([1u8; 32], 0).with_input([1u8; 32], 0);
+        let tx = wallet.build_transaction_with_options(&address, 400, 0, &options).unwrap();
+        assert_eq!(tx.inputs.len(), 1);
+        assert_eq!(tx.inputs[0].prev_tx, [1u8; 32]);
+
+        assert!(matches!(
+            wallet.build_transaction_with_options(&address, 600, 0, &options),
+            Err(WalletError::InsufficientBalance { required: 600, available: 500 })
+        ));
+        let unknown = SendOptions::new().with_input([9u8; 32], 0);
+        assert!(matches!(
+            wallet.build_transaction_with_options(&address, 1, 0, &unknown),
+            Err(WalletError::UnknownOutput(_))
+        ));
+    }
+
+    #[test]
+    fn test_labels_are_dropped_once_spent() {
+        let (mut wallet, name, _) = wallet_with_outputs();
+        wallet.label_output([1u8; 32], 0, "salary").unwrap();
+        wallet.freeze_output([1u8; 32], 0).unwrap();
+        assert_eq!(wallet.output_label([1u8; 32], 0), Some("salary"));
+        assert_eq!(wallet.list_outputs(&name).iter().filter(|o| o.label.is_some()).count(), 1);
+
+        // A transaction spending the output's key image
+        let mut spend = wallet.build_transaction(&wallet.get_active_account().unwrap().address.to_string(), 100, 0).unwrap();
+        spend.inputs[0].key_image = [1u8; 32];
+        wallet.sync_transaction(spend.id(), &spend).unwrap();
+
+        assert_eq!(wallet.output_label([1u8; 32], 0), None);
+        assert!(!wallet.is_frozen([1u8; 32], 0));
+    }
+}
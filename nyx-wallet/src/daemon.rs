@@ -0,0 +1,828 @@
+// src/daemon.rs
+
+//! Wallet daemon.
+//!
+//! [`WalletDaemon`] is one long-running process owning the keystore, the
+//! open wallet and its background sync, so GUIs and the CLI are clients
+//! rather than each scanning on their own. It listens on a local TCP socket
+//! and speaks newline-delimited JSON:
+//!
+//! ```text
+//! → {"id":1,"method":"auth","params":{"token":"…"}}
+//! ← {"id":1,"result":null}
+//! → {"id":2,"method":"balance"}
+//! ← {"id":2,"result":{"total":1500,"accounts":[…]}}
+//! ```
+//!
+//! Every connection is a session that must start with `auth`, carrying the
+//! token the daemon writes to a cookie file only its user can read. Then:
+//!
+//! - `open_wallet {keystore, password}`: unlocks a keystore, upgrading
+//!   older formats, and starts syncing with the node
+//! - `close_wallet`
+//! - `balance`
+//! - `send {address, amount, inputs?}`: returns the transaction hash;
+//!   `inputs` names outpoints to spend (see [`crate::coin_control`])
+//! - `history`: payments received and sent since the wallet was opened
+//! - `schedule_payment {address, amount, interval?, start?}`: pays
+//!   `address` at Unix time `start` (now by default) and, with `interval`,
+//!   every `interval` seconds after; returns the payment's `id` (see
+//!   [`crate::schedule`])
+//! - `scheduled_payments`, `cancel_payment {id}`
+//! - `outputs`: unspent outputs with their frozen state and labels
+//! - `freeze {outpoint}`, `thaw {outpoint}`, `label {outpoint, label}`
+//! - `subscribe`: streams `{"event":…}` lines for every wallet event
+//!
+//! Scheduled payments go out while a wallet is open and unlocked. Each one
+//! sent is added to the history and announced as a
+//! `scheduled_payment_sent` event; one that cannot be made, e.g. for lack
+//! of funds, is retried and announced once per distinct error as a
+//! `scheduled_payment_failed` event.
+
+use crate::coin_control::{self, SendOptions};
+use crate::errors::{WalletError, Result};
+use crate::schedule::{self, Schedule};
+use crate::sync::{AutoSync, RpcSource, WalletEvent};
+use crate::wallet::Wallet;
+use nyx_core::clock::{Clock, SystemClock};
+use nyx_core::{NetworkId, Timestamp};
+use nyx_crypto::ct;
+use rand::RngCore;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Port the daemon listens on by default
+pub const DEFAULT_DAEMON_PORT: u16 = 18091;
+
+/// File name of the token cookie inside the keystore directory
+pub const COOKIE_FILE: &str = "walletd.cookie";
+
+/// Longest request line accepted
+const MAX_REQUEST_SIZE: u64 = 64 * 1024;
+
+/// How often the event pump checks whether the wallet was closed
+const PUMP_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Daemon settings
+#[derive(Clone, Debug)]
+pub struct DaemonConfig {
+    /// Address to listen on; keep it on loopback
+    pub listen: SocketAddr,
+
+    /// Node RPC endpoint to sync from and submit to; without one, sends
+    /// are not broadcast
+    pub node_endpoint: Option<String>,
+
+    /// Delay between sync polls
+    pub sync_interval: Duration,
+
+    /// Network of opened wallets
+    pub network: NetworkId,
+
+    /// Delay between checks for due scheduled payments
+    pub schedule_interval: Duration,
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            listen: SocketAddr::from(([127, 0, 0, 1], DEFAULT_DAEMON_PORT)),
+            node_endpoint: None,
+            sync_interval: Duration::from_secs(10),
+            network: NetworkId::Mainnet,
+            schedule_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Payment in the history of the open wallet
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "direction", rename_all = "snake_case")]
+pub enum HistoryEntry {
+    /// Output credited to an account
+    Received {
+        /// Receiving account
+        account: String,
+        /// Hex-encoded transaction hash
+        tx_hash: String,
+        /// Output index within the transaction
+        index: u32,
+        /// Credited amount
+        amount: u64,
+    },
+
+    /// Payment sent through the daemon
+    Sent {
+        /// Hex-encoded transaction hash
+        tx_hash: String,
+        /// Recipient address
+        address: String,
+        /// Amount paid
+        amount: u64,
+        /// Scheduled payment this was, if any
+        #[serde(skip_serializing_if = "Option::is_none")]
+        schedule: Option<u64>,
+    },
+}
+
+/// Wallet daemon serving local clients
+pub struct WalletDaemon {
+    listener: TcpListener,
+    token: Arc<str>,
+    shared: Arc<Shared>,
+}
+
+/// State shared by all sessions
+struct Shared {
+    config: DaemonConfig,
+
+    /// Wallet opened by a client, if any
+    open: Mutex<Option<OpenWallet>>,
+
+    /// Outgoing line queues of subscribed sessions
+    subscribers: Mutex<Vec<Sender<String>>>,
+}
+
+/// The wallet owned by the daemon
+struct OpenWallet {
+    /// Keystore it was opened from
+    keystore: PathBuf,
+
+    wallet: Arc<RwLock<Wallet>>,
+
+    history: Arc<Mutex<Vec<HistoryEntry>>>,
+
+    /// Payments made on the wallet's behalf, saved next to the keystore
+    schedule: Arc<Mutex<Schedule>>,
+
+    /// Stops the thread forwarding sync events, which owns the sync
+    pump: Option<(Sender<()>, JoinHandle<()>)>,
+
+    /// Stops the thread making scheduled payments
+    scheduler: Option<(Sender<()>, JoinHandle<()>)>,
+}
+
+impl OpenWallet {
+    fn close(mut self) {
+        for (stop, thread) in [self.pump.take(), self.scheduler.take()].into_iter().flatten() {
+            drop(stop);
+            let _ = thread.join();
+        }
+    }
+
+    /// Shares the wallet's state, without its threads
+    fn handle(&self) -> Self {
+        Self {
+            keystore: self.keystore.clone(),
+            wallet: Arc::clone(&self.wallet),
+            history: Arc::clone(&self.history),
+            schedule: Arc::clone(&self.schedule),
+            pump: None,
+            scheduler: None,
+        }
+    }
+
+    fn save_schedule(&self, schedule: &Schedule) -> Result<()> {
+        schedule.save(&schedule::schedule_path(&self.keystore))
+    }
+}
+
+impl WalletDaemon {
+    /// Binds the daemon's socket, authenticating clients with `token`
+    ///
+    /// # Errors
+    /// - `IoError` if the address cannot be bound
+    pub fn bind(config: DaemonConfig, token: String) -> Result<Self> {
+        let listener = TcpListener::bind(config.listen)?;
+        Ok(Self {
+            listener,
+            token: Arc::from(token),
+            shared: Arc::new(Shared {
+                config,
+                open: Mutex::new(None),
+                subscribers: Mutex::new(Vec::new()),
+            }),
+        })
+    }
+
+    /// Address the daemon listens on
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Serves clients until the listener fails, one thread per session
+    pub fn run(self) -> Result<()> {
+        for stream in self.listener.incoming() {
+            let stream = stream?;
+            let token = Arc::clone(&self.token);
+            let shared = Arc::clone(&self.shared);
+            std::thread::spawn(move || {
+                let _ = serve(stream, &token, &shared);
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Creates a random session token
+pub fn generate_token() -> String {
+    let mut token = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut token);
+    hex::encode(token)
+}
+
+/// Writes the token cookie, readable only by the current user
+pub fn write_cookie(path: &Path, token: &str) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(token.as_bytes())?;
+    Ok(())
+}
+
+/// Reads the token cookie written by the daemon
+pub fn read_cookie(path: &Path) -> Result<String> {
+    fs::read_to_string(path)
+        .map(|token| token.trim().to_string())
+        .map_err(|_| WalletError::FileNotFound(format!("Daemon cookie {}", path.display())))
+}
+
+/// Runs one client session
+fn serve(stream: TcpStream, token: &str, shared: &Arc<Shared>) -> std::io::Result<()> {
+    let (lines, queue) = mpsc::channel::<String>();
+    let mut writer = stream.try_clone()?;
+    std::thread::spawn(move || {
+        for line in queue {
+            if writer.write_all(line.as_bytes()).and_then(|()| writer.write_all(b"\n")).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut authenticated = false;
+    loop {
+        let mut line = String::new();
+        if reader.by_ref().take(MAX_REQUEST_SIZE).read_line(&mut line)? == 0 || !line.ends_with('\n') {
+            break;
+        }
+
+        let request: Value = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let _ = lines.send(json!({ "id": null, "error": format!("Malformed request: {}", e) }).to_string());
+                continue;
+            }
+        };
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let method = request.get("method").and_then(Value::as_str).unwrap_or_default();
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = if method == "auth" {
+            let provided = params.get("token").and_then(Value::as_str).unwrap_or_default();
+            authenticated = ct::ct_eq(provided.as_bytes(), token.as_bytes());
+            if authenticated { Ok(Value::Null) } else { Err("Invalid token".to_string()) }
+        } else if !authenticated {
+            Err("Not authenticated".to_string())
+        } else if method == "subscribe" {
+            shared.subscribers.lock().unwrap_or_else(|e| e.into_inner()).push(lines.clone());
+            Ok(Value::Null)
+        } else {
+            handle(shared, method, &params).map_err(|e| e.to_string())
+        };
+
+        let failed_auth = method == "auth" && !authenticated;
+        let response = match result {
+            Ok(result) => json!({ "id": id, "result": result }),
+            Err(error) => json!({ "id": id, "error": error }),
+        };
+        let _ = lines.send(response.to_string());
+        if failed_auth {
+            break;
+        }
+    }
+
+    // A subscribed session's writer exits on its next failed write
+    let _ = stream.shutdown(Shutdown::Both);
+    Ok(())
+}
+
+/// Runs an authenticated request
+fn handle(shared: &Arc<Shared>, method: &str, params: &Value) -> Result<Value> {
+    let param = |name: &str| params.get(name)
+        .ok_or_else(|| WalletError::SerializationError(format!("Missing parameter: {}", name)));
+    let mut open = shared.open.lock().unwrap_or_else(|e| e.into_inner());
+
+    match method {
+        "open_wallet" => {
+            let keystore = PathBuf::from(param("keystore")?.as_str().unwrap_or_default());
+            let password = param("password")?.as_str().unwrap_or_default();
+            let account = Wallet::unlock_keystore(&keystore, password)?;
+            let mut wallet = Wallet::for_network(shared.config.network);
+            wallet.add_account(account)?;
+            let address = wallet.get_active_account()?.address.to_string();
+            if let Some(previous) = open.take() {
+                previous.close();
+            }
+            *open = Some(open_wallet(shared, keystore, wallet)?);
+            Ok(json!({ "address": address }))
+        }
+        "close_wallet" => {
+            if let Some(previous) = open.take() {
+                previous.close();
+            }
+            Ok(Value::Null)
+        }
+        _ => {
+            let open = open.as_ref()
+                .ok_or_else(|| WalletError::AccountNotFound("No wallet is open".to_string()))?;
+            match method {
+                "balance" => {
+                    let wallet = open.wallet.read().unwrap_or_else(|e| e.into_inner());
+                    let accounts: Vec<Value> = wallet.account_balances().into_iter()
+                        .map(|account| json!({ "name": account.name, "balance": account.balance, "utxos": account.utxos }))
+                        .collect();
+                    Ok(json!({
+                        "keystore": open.keystore,
+                        "total": wallet.get_total_balance(),
+                        "accounts": accounts,
+                    }))
+                }
+                "send" => {
+                    let address = param("address")?.as_str().unwrap_or_default().to_string();
+                    let amount = param("amount")?.as_u64()
+                        .ok_or_else(|| WalletError::InvalidAmount("Amount must be a whole number".to_string()))?;
+                    let mut options = SendOptions::new();
+                    for input in params.get("inputs").and_then(Value::as_array).into_iter().flatten() {
+                        let (tx_hash, index) = coin_control::parse_outpoint(input.as_str().unwrap_or_default())?;
+                        options = options.with_input(tx_hash, index);
+                    }
+                    let tx_hash = hex::encode(send(shared, &open.wallet, &address, amount, &options)?);
+                    open.history.lock().unwrap_or_else(|e| e.into_inner())
+                        .push(HistoryEntry::Sent { tx_hash: tx_hash.clone(), address, amount, schedule: None });
+                    Ok(json!({ "tx_hash": tx_hash }))
+                }
+                "history" => Ok(serde_json::to_value(&*open.history.lock().unwrap_or_else(|e| e.into_inner()))?),
+                "schedule_payment" => {
+                    let address = param("address")?.as_str().unwrap_or_default().to_string();
+                    let recipient = crate::account::Address::from_string(&address)?;
+                    if recipient.network != shared.config.network {
+                        return Err(WalletError::InvalidAddress(format!(
+                            "Address is for {}, wallet is on {}",
+                            recipient.network, shared.config.network
+                        )));
+                    }
+                    let amount = param("amount")?.as_u64()
+                        .ok_or_else(|| WalletError::InvalidAmount("Amount must be a whole number".to_string()))?;
+                    let start = params.get("start").and_then(Value::as_u64).unwrap_or_else(|| SystemClock.now());
+                    let interval = params.get("interval").and_then(Value::as_u64);
+
+                    let mut schedule = open.schedule.lock().unwrap_or_else(|e| e.into_inner());
+                    let id = schedule.add(&address, amount, start, interval)?;
+                    if let Err(e) = open.save_schedule(&schedule) {
+                        schedule.cancel(id);
+                        return Err(e);
+                    }
+                    Ok(json!({ "id": id }))
+                }
+                "scheduled_payments" => {
+                    Ok(serde_json::to_value(open.schedule.lock().unwrap_or_else(|e| e.into_inner()).payments())?)
+                }
+                "cancel_payment" => {
+                    let id = param("id")?.as_u64().unwrap_or_default();
+                    let mut schedule = open.schedule.lock().unwrap_or_else(|e| e.into_inner());
+                    if !schedule.cancel(id) {
+                        return Err(WalletError::UnknownTransaction(format!("No scheduled payment {}", id)));
+                    }
+                    open.save_schedule(&schedule)?;
+                    Ok(Value::Null)
+                }
+                "outputs" => {
+                    let wallet = open.wallet.read().unwrap_or_else(|e| e.into_inner());
+                    let outputs: Vec<Value> = wallet.list_accounts().iter()
+                        .flat_map(|name| wallet.list_outputs(name).into_iter().map(move |output| (name.clone(), output)))
+                        .map(|(account, output)| json!({
+                            "account": account,
+                            "outpoint": coin_control::format_outpoint(&(output.utxo.tx_hash, output.utxo.index)),
+                            "amount": output.utxo.amount,
+                            "frozen": output.frozen,
+                            "label": output.label,
+                        }))
+                        .collect();
+                    Ok(Value::from(outputs))
+                }
+                "freeze" | "thaw" | "label" => {
+                    let (tx_hash, index) = coin_control::parse_outpoint(param("outpoint")?.as_str().unwrap_or_default())?;
+                    let mut wallet = open.wallet.write().unwrap_or_else(|e| e.into_inner());
+                    match method {
+                        "freeze" => wallet.freeze_output(tx_hash, index)?,
+                        "thaw" => wallet.thaw_output(tx_hash, index),
+                        _ => wallet.label_output(tx_hash, index, param("label")?.as_str().unwrap_or_default())?,
+                    }
+                    Ok(Value::Null)
+                }
+                _ => Err(WalletError::SerializationError(format!("Unknown method: {}", method))),
+            }
+        }
+    }
+}
+
+/// Takes ownership of a wallet, syncing it if a node is configured and
+/// making its scheduled payments
+fn open_wallet(shared: &Arc<Shared>, keystore: PathBuf, wallet: Wallet) -> Result<OpenWallet> {
+    let wallet = Arc::new(RwLock::new(wallet));
+    let history = Arc::new(Mutex::new(Vec::new()));
+    let schedule = Arc::new(Mutex::new(Schedule::load(&schedule::schedule_path(&keystore))?));
+
+    let pump = match &shared.config.node_endpoint {
+        Some(endpoint) => {
+            let sync = Wallet::start_auto_sync(&wallet, endpoint, shared.config.sync_interval)?;
+            let (stop, stopped) = mpsc::channel();
+            let shared = Arc::clone(shared);
+            let history = Arc::clone(&history);
+            Some((stop, std::thread::spawn(move || pump_events(sync, stopped, &shared, &history))))
+        }
+        None => None,
+    };
+
+    let mut open = OpenWallet { keystore, wallet, history, schedule, pump, scheduler: None };
+    let (stop, stopped) = mpsc::channel();
+    let (shared, open_handle) = (Arc::clone(shared), open.handle());
+    open.scheduler = Some((stop, std::thread::spawn(move || {
+        while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(shared.config.schedule_interval) {
+            pay_due(&shared, &open_handle, SystemClock.now());
+        }
+    })));
+    Ok(open)
+}
+
+/// Records sync events in the history and forwards them to subscribers
+/// until the wallet is closed
+fn pump_events(sync: AutoSync, stopped: Receiver<()>, shared: &Shared, history: &Mutex<Vec<HistoryEntry>>) {
+    loop {
+        match sync.events().recv_timeout(PUMP_INTERVAL) {
+            Ok(event) => {
+                if let WalletEvent::OutputReceived { account, tx_hash, index, amount } = &event {
+                    history.lock().unwrap_or_else(|e| e.into_inner()).push(HistoryEntry::Received {
+                        account: account.clone(),
+                        tx_hash: hex::encode(tx_hash),
+                        index: *index,
+                        amount: *amount,
+                    });
+                }
+                publish(shared, &event);
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        if !matches!(stopped.try_recv(), Err(mpsc::TryRecvError::Empty)) {
+            break;
+        }
+    }
+    sync.stop();
+}
+
+/// Makes the scheduled payments due at `now`, unless the wallet is locked
+fn pay_due(shared: &Shared, open: &OpenWallet, now: Timestamp) {
+    if open.wallet.read().unwrap_or_else(|e| e.into_inner()).is_locked() {
+        return;
+    }
+    let due = open.schedule.lock().unwrap_or_else(|e| e.into_inner()).due(now);
+    if due.is_empty() {
+        return;
+    }
+
+    for payment in due {
+        let result = send(shared, &open.wallet, &payment.address, payment.amount, &SendOptions::new());
+        let mut schedule = open.schedule.lock().unwrap_or_else(|e| e.into_inner());
+        match result {
+            Ok(tx_hash) => {
+                schedule.complete(payment.id, now);
+                open.history.lock().unwrap_or_else(|e| e.into_inner()).push(HistoryEntry::Sent {
+                    tx_hash: hex::encode(tx_hash),
+                    address: payment.address.clone(),
+                    amount: payment.amount,
+                    schedule: Some(payment.id),
+                });
+                publish_json(shared, json!({
+                    "type": "scheduled_payment_sent",
+                    "id": payment.id,
+                    "tx_hash": hex::encode(tx_hash),
+                    "address": payment.address,
+                    "amount": payment.amount,
+                }));
+            }
+            Err(e) => {
+                let error = e.to_string();
+                if schedule.fail(payment.id, &error) {
+                    publish_json(shared, json!({
+                        "type": "scheduled_payment_failed",
+                        "id": payment.id,
+                        "error": error,
+                    }));
+                }
+            }
+        }
+    }
+
+    let schedule = open.schedule.lock().unwrap_or_else(|e| e.into_inner());
+    if let Err(e) = open.save_schedule(&schedule) {
+        publish_json(shared, json!({ "type": "scheduled_payment_failed", "id": null, "error": e.to_string() }));
+    }
+}
+
+/// Sends an event to every subscribed session, dropping closed ones
+fn publish(shared: &Shared, event: &WalletEvent) {
+    publish_json(shared, event_json(event));
+}
+
+/// Sends a daemon event to every subscribed session
+fn publish_json(shared: &Shared, event: Value) {
+    let line = json!({ "event": event }).to_string();
+    shared.subscribers.lock().unwrap_or_else(|e| e.into_inner())
+        .retain(|subscriber| subscriber.send(line.clone()).is_ok());
+}
+
+fn event_json(event: &WalletEvent) -> Value {
+    match event {
+        WalletEvent::OutputReceived { account, tx_hash, index, amount } => json!({
+            "type": "output_received",
+            "account": account,
+            "tx_hash": hex::encode(tx_hash),
+            "index": index,
+            "amount": amount,
+        }),
+        WalletEvent::InconsistentOutput { account, tx_hash, index } => json!({
+            "type": "inconsistent_output",
+            "account": account,
+            "tx_hash": hex::encode(tx_hash),
+            "index": index,
+        }),
+        WalletEvent::BalanceChanged { account, balance } => json!({
+            "type": "balance_changed",
+            "account": account,
+            "balance": balance,
+        }),
+        WalletEvent::SyncFailed { error } => json!({
+            "type": "sync_failed",
+            "error": error,
+        }),
+    }
+}
+
+/// Builds and submits a payment from the open wallet
+///
+/// With a node configured, the transaction approves the node's most recent
+/// transactions and is submitted to it before it counts as pending.
+fn send(shared: &Shared, wallet: &RwLock<Wallet>, address: &str, amount: u64, options: &SendOptions) -> Result<nyx_core::Hash> {
+    let mut wallet = wallet.write().unwrap_or_else(|e| e.into_inner());
+    let Some(endpoint) = &shared.config.node_endpoint else {
+        return wallet.send_with_options(address, amount, options);
+    };
+
+    let node = RpcSource::new(endpoint)?;
+    let recent = node.get_json("/explorer/recent?limit=2")?;
+    let parents: Vec<nyx_core::Hash> = recent.as_array().into_iter().flatten()
+        .filter_map(|tx| tx.get("hash")?.as_str())
+        .filter_map(|hash| hex::decode(hash).ok()?.try_into().ok())
+        .collect();
+    let (first, second) = match parents.as_slice() {
+        [first, second, ..] => (*first, *second),
+        [only] => (*only, *only),
+        [] => return Err(WalletError::SyncError("Node has no transactions to approve".to_string())),
+    };
+
+    let mut tx = wallet.build_transaction_with_options(address, amount, 0, options)?;
+    wallet.attach_parents(&mut tx, [first, second])?;
+    node.post_json("/submit", &json!({
+        "transaction": hex::encode(nyx_core::encoding::encode_transaction(&tx)),
+    }))?;
+    wallet.submit_transaction(&tx)
+}
+
+/// Session with a running daemon
+pub struct DaemonClient {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    next_id: u64,
+}
+
+impl DaemonClient {
+    /// Connects to the daemon at `addr` and authenticates with `token`
+    ///
+    /// # Errors
+    /// - `IoError` if the daemon cannot be reached
+    /// - `InvalidPassword` if the token is rejected
+    pub fn connect(addr: SocketAddr, token: &str) -> Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        let mut client = Self { reader: BufReader::new(stream.try_clone()?), writer: stream, next_id: 0 };
+        client.call("auth", json!({ "token": token }))
+            .map_err(|_| WalletError::InvalidPassword)?;
+        Ok(client)
+    }
+
+    /// Calls a daemon method, returning its result
+    ///
+    /// Events received while waiting for the response are dropped; read
+    /// them with [`next_event`](Self::next_event) before calling again.
+    ///
+    /// # Errors
+    /// - `SyncError` with the daemon's message if the call failed
+    pub fn call(&mut self, method: &str, params: Value) -> Result<Value> {
+        self.next_id += 1;
+        let request = json!({ "id": self.next_id, "method": method, "params": params });
+        self.writer.write_all(format!("{}\n", request).as_bytes())?;
+
+        loop {
+            let response = self.read_line()?;
+            if response.get("event").is_some() || response["id"] != json!(self.next_id) {
+                continue;
+            }
+            if let Some(error) = response.get("error") {
+                return Err(WalletError::SyncError(error.as_str().unwrap_or_default().to_string()));
+            }
+            return Ok(response.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    /// Waits for the next event of a subscribed session
+    pub fn next_event(&mut self) -> Result<Value> {
+        loop {
+            let line = self.read_line()?;
+            if let Some(event) = line.get("event") {
+                return Ok(event.clone());
+            }
+        }
+    }
+
+    fn read_line(&mut self) -> Result<Value> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(WalletError::SyncError("Daemon closed the session".to_string()));
+        }
+        Ok(serde_json::from_str(&line)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::Account;
+    use crate::builder::RingMember;
+    use crate::keystore::Keystore;
+
+    fn start(config: DaemonConfig) -> (SocketAddr, Arc<Shared>) {
+        let daemon = WalletDaemon::bind(config, "secret".to_string()).unwrap();
+        let addr = daemon.local_addr().unwrap();
+        let shared = Arc::clone(&daemon.shared);
+        std::thread::spawn(move || daemon.run());
+        (addr, shared)
+    }
+
+    fn local_config() -> DaemonConfig {
+        DaemonConfig { listen: "127.0.0.1:0".parse().unwrap(), ..DaemonConfig::default() }
+    }
+
+    #[test]
+    fn test_sessions_must_authenticate() {
+        let (addr, _) = start(local_config());
+        assert!(matches!(DaemonClient::connect(addr, "wrong"), Err(WalletError::InvalidPassword)));
+
+        // Requests before `auth` are refused
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(b"{\"id\":1,\"method\":\"balance\"}\n").unwrap();
+        let mut line = String::new();
+        BufReader::new(stream).read_line(&mut line).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&line).unwrap()["error"], "Not authenticated");
+    }
+
+    #[test]
+    fn test_open_send_and_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = dir.path().join("keystore.json");
+        let account = Account::generate_with_name("main".to_string());
+        Keystore::new(&account, "pass").unwrap().save_to_file(&keystore).unwrap();
+
+        let (addr, _) = start(local_config());
+        let mut client = DaemonClient::connect(addr, "secret").unwrap();
+        assert!(client.call("balance", Value::Null).is_err());
+        assert!(client.call("open_wallet", json!({ "keystore": keystore, "password": "wrong" })).is_err());
+
+        let opened = client.call("open_wallet", json!({ "keystore": keystore, "password": "pass" })).unwrap();
+        let address = opened["address"].as_str().unwrap().to_string();
+        assert_eq!(client.call("balance", Value::Null).unwrap()["total"], 0);
+
+        // Nothing to spend yet
+        let error = client.call("send", json!({ "address": address, "amount": 10 })).unwrap_err();
+        assert!(error.to_string().contains("Insufficient balance"));
+        assert_eq!(client.call("history", Value::Null).unwrap(), json!([]));
+
+        // Coin control on outputs the wallet does not have
+        let outpoint = coin_control::format_outpoint(&([9u8; 32], 0));
+        assert!(client.call("freeze", json!({ "outpoint": outpoint })).unwrap_err().to_string().contains("Unknown output"));
+        assert!(client.call("send", json!({ "address": address, "amount": 1, "inputs": [outpoint] })).is_err());
+        assert_eq!(client.call("outputs", Value::Null).unwrap(), json!([]));
+
+        // A second session sees the same wallet until it is closed
+        let mut other = DaemonClient::connect(addr, "secret").unwrap();
+        assert_eq!(other.call("balance", Value::Null).unwrap()["accounts"][0]["name"], "main");
+        other.call("close_wallet", Value::Null).unwrap();
+        assert!(client.call("balance", Value::Null).is_err());
+    }
+
+    #[test]
+    fn test_subscribers_receive_events() {
+        let (addr, shared) = start(local_config());
+        let mut client = DaemonClient::connect(addr, "secret").unwrap();
+        client.call("subscribe", Value::Null).unwrap();
+
+        publish(&shared, &WalletEvent::BalanceChanged { account: "main".to_string(), balance: 42 });
+        assert_eq!(
+            client.next_event().unwrap(),
+            json!({ "type": "balance_changed", "account": "main", "balance": 42 })
+        );
+
+        // Closed sessions are dropped on the next event
+        drop(client);
+        std::thread::sleep(Duration::from_millis(50));
+        publish(&shared, &WalletEvent::SyncFailed { error: "down".to_string() });
+        publish(&shared, &WalletEvent::SyncFailed { error: "down".to_string() });
+        assert!(shared.subscribers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scheduled_payments_run_and_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = dir.path().join("keystore.json");
+        let account = Account::generate_with_name("main".to_string());
+        Keystore::new(&account, "pass").unwrap().save_to_file(&keystore).unwrap();
+
+        let (addr, shared) = start(local_config());
+        let mut client = DaemonClient::connect(addr, "secret").unwrap();
+        let address = client.call("open_wallet", json!({ "keystore": keystore, "password": "pass" })).unwrap()["address"]
+            .as_str().unwrap().to_string();
+        assert!(client.call("schedule_payment", json!({ "address": "bogus", "amount": 10 })).is_err());
+        let scheduled = client.call("schedule_payment", json!({
+            "address": address, "amount": 10, "interval": 3_600, "start": 100,
+        })).unwrap();
+        assert_eq!(scheduled["id"], 1);
+        client.call("subscribe", Value::Null).unwrap();
+
+        // Unfunded: the failure is announced once and the payment stays due
+        let open = shared.open.lock().unwrap().as_ref().unwrap().handle();
+        pay_due(&shared, &open, 100);
+        pay_due(&shared, &open, 150);
+        let failed = client.next_event().unwrap();
+        assert_eq!(failed["type"], "scheduled_payment_failed");
+        assert!(failed["error"].as_str().unwrap().contains("Insufficient balance"));
+
+        {
+            let mut wallet = open.wallet.write().unwrap();
+            wallet.credit_output("main", [7u8; 32], 0, 1_000).unwrap();
+            let member = RingMember { global_index: 0, stealth_address: vec![7u8; 32] };
+            wallet.confirm_output([7u8; 32], 0, member).unwrap();
+        }
+        pay_due(&shared, &open, 200);
+        assert_eq!(client.next_event().unwrap()["type"], "scheduled_payment_sent");
+        let history = client.call("history", Value::Null).unwrap();
+        assert_eq!(history[0]["schedule"], 1);
+        assert_eq!(history[0]["amount"], 10);
+
+        // Nothing more is due until the next interval, across a reopen
+        pay_due(&shared, &open, 300);
+        assert_eq!(client.call("history", Value::Null).unwrap().as_array().unwrap().len(), 1);
+        client.call("close_wallet", Value::Null).unwrap();
+        client.call("open_wallet", json!({ "keystore": keystore, "password": "pass" })).unwrap();
+        let payments = client.call("scheduled_payments", Value::Null).unwrap();
+        assert_eq!(payments[0]["next_due"], 3_700);
+        assert_eq!(payments[0].get("last_error"), None);
+
+        client.call("cancel_payment", json!({ "id": 1 })).unwrap();
+        assert!(client.call("cancel_payment", json!({ "id": 1 })).is_err());
+        assert_eq!(Schedule::load(&schedule::schedule_path(&keystore)).unwrap().payments(), &[]);
+    }
+
+    #[test]
+    fn test_cookie_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(COOKIE_FILE);
+        let token = generate_token();
+        write_cookie(&path, &token).unwrap();
+        assert_eq!(read_cookie(&path).unwrap(), token);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+}
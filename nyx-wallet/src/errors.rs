@@ -22,6 +22,9 @@ pub enum WalletError {
     /// Invalid address format
     InvalidAddress(String),
 
+    /// Amount is malformed or arithmetic on it overflowed
+    InvalidAmount(String),
+
     /// Keystore error
     KeystoreError(String),
 
@@ -45,6 +48,30 @@ pub enum WalletError {
 
     /// Account already exists
     AccountExists(String),
+
+    /// Fetching transactions from a node failed
+    SyncError(String),
+
+    /// Transaction is not pending in this wallet
+    UnknownTransaction(String),
+
+    /// Payment proof is malformed or does not hold
+    InvalidProof(String),
+
+    /// Recovery code is malformed or does not unlock the backup
+    InvalidRecoveryCode(String),
+
+    /// Recovery phrase is malformed or mistyped
+    InvalidMnemonic(String),
+
+    /// Spend keys are locked away until the wallet is unlocked
+    WalletLocked,
+
+    /// Output is not an unspent output of this wallet
+    UnknownOutput(String),
+
+    /// Ledger user identifier is empty, too long or has disallowed characters
+    InvalidUserId(String),
 }
 
 impl fmt::Display for WalletError {
@@ -55,6 +82,7 @@ impl fmt::Display for WalletError {
                 write!(f, "Insufficient balance: required {}, available {}", required, available)
             }
             WalletError::InvalidAddress(msg) => write!(f, "Invalid address: {}", msg),
+            WalletError::InvalidAmount(msg) => write!(f, "Invalid amount: {}", msg),
             WalletError::KeystoreError(msg) => write!(f, "Keystore error: {}", msg),
             WalletError::CryptoError(msg) => write!(f, "Crypto error: {}", msg),
             WalletError::TransactionBuildError(msg) => write!(f, "Transaction build error: {}", msg),
@@ -63,6 +91,14 @@ impl fmt::Display for WalletError {
             WalletError::InvalidPassword => write!(f, "Invalid password"),
             WalletError::FileNotFound(msg) => write!(f, "File not found: {}", msg),
             WalletError::AccountExists(msg) => write!(f, "Account already exists: {}", msg),
+            WalletError::SyncError(msg) => write!(f, "Sync error: {}", msg),
+            WalletError::UnknownTransaction(msg) => write!(f, "Unknown transaction: {}", msg),
+            WalletError::InvalidProof(msg) => write!(f, "Invalid proof: {}", msg),
+            WalletError::InvalidRecoveryCode(msg) => write!(f, "Invalid recovery code: {}", msg),
+            WalletError::InvalidMnemonic(msg) => write!(f, "Invalid mnemonic: {}", msg),
+            WalletError::WalletLocked => write!(f, "Wallet is locked"),
+            WalletError::UnknownOutput(msg) => write!(f, "Unknown output: {}", msg),
+            WalletError::InvalidUserId(msg) => write!(f, "Invalid user id: {}", msg),
         }
     }
 }
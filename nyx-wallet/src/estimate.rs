@@ -0,0 +1,51 @@
+// src/estimate.rs
+
+//! User-facing confirmation time estimates.
+//!
+//! Nodes serve a [`ConfirmationEstimate`] at `/estimate/confirmation`; this
+//! turns it into the short text a wallet shows next to a pending send.
+
+use nyx_core::metrics::{ConfirmationEstimate, EstimateBasis};
+
+/// Describes an estimate for display, e.g. `≈8s to confirm`
+pub fn describe_confirmation(estimate: &ConfirmationEstimate) -> String {
+    if estimate.basis == EstimateBasis::Instant {
+        return "Confirms instantly".to_string();
+    }
+
+    match estimate.seconds {
+        Some(secs) if secs < 1.0 => "<1s to confirm".to_string(),
+        Some(secs) => format!("≈{} to confirm", format_duration(secs.round() as u64)),
+        None => "Confirmation time unknown".to_string(),
+    }
+}
+
+/// Formats whole seconds as `8s`, `2m 5s` or `1h 3m`
+fn format_duration(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 if secs.is_multiple_of(60) => format!("{}m", secs / 60),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(seconds: Option<f64>, basis: EstimateBasis) -> ConfirmationEstimate {
+        ConfirmationEstimate { seconds, basis, tps: 0.0, tips: 0 }
+    }
+
+    #[test]
+    fn test_describe_confirmation() {
+        assert_eq!(describe_confirmation(&estimate(Some(7.6), EstimateBasis::Measured)), "≈8s to confirm");
+        assert_eq!(describe_confirmation(&estimate(Some(0.1), EstimateBasis::Modelled)), "<1s to confirm");
+        assert_eq!(describe_confirmation(&estimate(Some(125.0), EstimateBasis::Measured)), "≈2m 5s to confirm");
+        assert_eq!(describe_confirmation(&estimate(Some(120.0), EstimateBasis::Measured)), "≈2m to confirm");
+        assert_eq!(describe_confirmation(&estimate(Some(3780.0), EstimateBasis::Measured)), "≈1h 3m to confirm");
+        assert_eq!(describe_confirmation(&estimate(Some(0.0), EstimateBasis::Instant)), "Confirms instantly");
+        assert_eq!(describe_confirmation(&estimate(None, EstimateBasis::Unknown)), "Confirmation time unknown");
+    }
+}
@@ -0,0 +1,392 @@
+// src/htlc.rs
+
+//! Hash time-locked contracts for atomic swaps.
+//!
+//! An HTLC output is spent by whoever reveals the preimage of its hashlock
+//! and signs with its claim key or, once its timeout passes, by whoever
+//! signs with its refund key. The hashlock is SHA-256, as on Bitcoin and
+//! most other chains, so one secret can lock both sides of a swap:
+//!
+//! 1. The initiator draws a preimage and funds an HTLC with its hashlock.
+//! 2. The counterparty funds an HTLC on the other chain with the same
+//!    hashlock and a shorter timeout.
+//! 3. The initiator claims the counterparty's HTLC, revealing the preimage.
+//! 4. The counterparty reads the preimage off the claim (see
+//!    [`revealed_preimage`]) and claims the initiator's HTLC.
+//!
+//! If either side stalls, each party refunds its own HTLC after the
+//! timeout. Claim and refund keys are Ed25519 keys the parties exchange
+//! out of band. Claims and refunds pay the active account and are built
+//! against their parents, as the HTLC witnesses sign them.
+//!
+//! HTLC outputs are blinded like any other output, under a mask derived
+//! from the recipient's shared secret. The funding party keeps the mask
+//! along with the amount (see [`HtlcFunding`]), so it balances its refund
+//! without the recipient's keys.
+
+use crate::account::Address;
+use crate::amount;
+use crate::builder::{RingMember, TransactionBuilder, Utxo};
+use crate::coin_control::{format_outpoint, SendOptions};
+use crate::errors::{WalletError, Result};
+use crate::wallet::Wallet;
+use nyx_core::extra::{Extra, ExtraField};
+use nyx_core::transaction::{HtlcLock, Transaction};
+use nyx_core::Hash;
+use nyx_crypto::keys;
+use nyx_crypto::pedersen::BlindingFactor;
+use nyx_crypto::stealth;
+use rand::RngCore;
+
+/// HTLC output as its funding party keeps it for the refund path
+///
+/// The amount and the commitment's mask are only known to the funder and
+/// the recipient; refunding balances against both.
+#[derive(Clone, Debug)]
+pub struct HtlcFunding {
+    /// Transaction paying into the HTLC
+    pub tx: Transaction,
+
+    /// Index of the HTLC output
+    pub index: u32,
+
+    /// Amount locked in the HTLC
+    pub amount: u64,
+
+    /// Blinding factor of the HTLC output's commitment
+    pub mask: BlindingFactor,
+}
+
+/// Draws a random swap preimage
+pub fn generate_preimage() -> [u8; 32] {
+    let mut preimage = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut preimage);
+    preimage
+}
+
+/// Hashlock a preimage opens
+pub fn hashlock(preimage: &[u8; 32]) -> [u8; 32] {
+    nyx_crypto::hash::sha256(preimage)
+}
+
+/// Finds the preimage a transaction revealed to claim an HTLC with
+/// `hashlock`
+pub fn revealed_preimage(tx: &Transaction, hashlock: &[u8; 32]) -> Option<[u8; 32]> {
+    let extra = Extra::parse(&tx.extra).ok()?;
+    extra.fields().iter().find_map(|field| match field {
+        ExtraField::HtlcWitnesses(witnesses) => witnesses.iter()
+            .filter_map(|witness| witness.preimage)
+            .find(|preimage| &self::hashlock(preimage) == hashlock),
+        _ => None,
+    })
+}
+
+impl Wallet {
+    /// Builds a transaction paying `amount` into an HTLC output
+    ///
+    /// The returned funding keeps what a later
+    /// [refund](Self::refund_htlc) takes; attaching parents to its
+    /// transaction leaves it valid.
+    pub fn build_htlc_transaction(
+        &self,
+        to_address: &str,
+        amount: u64,
+        fee: u64,
+        htlc: HtlcLock,
+    ) -> Result<HtlcFunding> {
+        let options = SendOptions::new().with_htlc(htlc);
+        let (tx, tx_keys) = self.build_transaction_with_keys(to_address, amount, fee, &options)?;
+        let index = tx.outputs.iter()
+            .position(|output| output.htlc.is_some())
+            .ok_or_else(|| WalletError::TransactionBuildError("No HTLC output built".to_string()))?;
+
+        let recipient = Address::from_string(to_address)?;
+        let shared_secret = stealth::derive_sender_shared_secret(&recipient.view_public, &tx_keys[index])?;
+        let mask = nyx_crypto::amount::amount_mask(&shared_secret, index as u32);
+        Ok(HtlcFunding { tx, index: index as u32, amount, mask })
+    }
+
+    /// Claims HTLC output `index` of `funding` by revealing its preimage
+    ///
+    /// The output must pay an account of the wallet, which recovers its
+    /// amount.
+    ///
+    /// # Arguments
+    /// * `global_index` - Global index the output got when confirmed
+    /// * `claim_seed` - Ed25519 seed of the HTLC's claim key
+    /// * `parents` - DAG tips the claim approves
+    ///
+    /// # Errors
+    /// - `UnknownOutput` if the output is not an HTLC output paying the
+    ///   wallet
+    /// - `TransactionBuildError` if the preimage or key does not open the
+    ///   HTLC
+    #[allow(clippy::too_many_arguments)]
+    pub fn claim_htlc(
+        &self,
+        funding: &Transaction,
+        index: u32,
+        global_index: u64,
+        preimage: &[u8; 32],
+        claim_seed: &[u8; 32],
+        fee: u64,
+        parents: [Hash; 2],
+    ) -> Result<Transaction> {
+        let htlc = htlc_output(funding, index)?;
+        if !htlc.opens(preimage) {
+            return Err(WalletError::TransactionBuildError("Preimage does not open the hashlock".to_string()));
+        }
+        if keys::ed25519_public_key(claim_seed) != htlc.claim_key {
+            return Err(WalletError::TransactionBuildError("Not the HTLC's claim key".to_string()));
+        }
+
+        let tx_hash = funding.id();
+        let (account, amount) = self.scan_transaction(tx_hash, funding)
+            .into_iter()
+            .find(|output| output.index == index)
+            .and_then(|output| Some((output.account, output.amount?)))
+            .ok_or_else(|| WalletError::UnknownOutput(format_outpoint(&(tx_hash, index))))?;
+        let view_private = self.get_account(&account)?.view_private_key();
+        let mask = amount::output_mask(funding, index, view_private, amount);
+        self.spend_htlc(funding, index, global_index, (amount, mask), Some(*preimage), claim_seed, fee, parents)
+    }
+
+    /// Refunds the HTLC output of `funding` after its timeout
+    ///
+    /// The refunding party funded the HTLC, so it kept the funding built
+    /// by [`build_htlc_transaction`](Self::build_htlc_transaction).
+    ///
+    /// # Arguments
+    /// * `global_index` - Global index the output got when confirmed
+    /// * `refund_seed` - Ed25519 seed of the HTLC's refund key
+    /// * `parents` - DAG tips the refund approves
+    ///
+    /// # Errors
+    /// - `UnknownOutput` if the output is not an HTLC output
+    /// - `TransactionBuildError` if the key does not refund the HTLC or
+    ///   its timeout has not passed at the wallet's chain height
+    pub fn refund_htlc(
+        &self,
+        funding: &HtlcFunding,
+        global_index: u64,
+        refund_seed: &[u8; 32],
+        fee: u64,
+        parents: [Hash; 2],
+    ) -> Result<Transaction> {
+        let HtlcFunding { tx, index, amount, mask } = funding;
+        let htlc = htlc_output(tx, *index)?;
+        if keys::ed25519_public_key(refund_seed) != htlc.refund_key {
+            return Err(WalletError::TransactionBuildError("Not the HTLC's refund key".to_string()));
+        }
+        if !htlc.timeout.is_unlocked(&self.spend_context()) {
+            return Err(WalletError::TransactionBuildError(format!(
+                "HTLC cannot be refunded until {}",
+                htlc.timeout
+            )));
+        }
+        self.spend_htlc(tx, *index, global_index, (*amount, *mask), None, refund_seed, fee, parents)
+    }
+
+    /// Spends an HTLC output, opened by its amount and mask, to a change
+    /// subaddress of the active account
+    #[allow(clippy::too_many_arguments)]
+    fn spend_htlc(
+        &self,
+        funding: &Transaction,
+        index: u32,
+        global_index: u64,
+        (amount, mask): (u64, BlindingFactor),
+        preimage: Option<[u8; 32]>,
+        seed: &[u8; 32],
+        fee: u64,
+        parents: [Hash; 2],
+    ) -> Result<Transaction> {
+        self.ensure_unlocked()?;
+        let account = self.get_active_account()?.clone();
+        let amount = amount.checked_sub(fee)
+            .filter(|&amount| amount > 0)
+            .ok_or_else(|| WalletError::InvalidAmount(format!("Fee {} leaves nothing of the HTLC output", fee)))?;
+
+        let tx_hash = funding.id();
+        let utxo = Utxo {
+            tx_hash,
+            index,
+            amount,
+            key_image: HtlcLock::key_image(&tx_hash, index),
+            unlock_time: None,
+            mask,
+            ring_member: Some(RingMember {
+                global_index,
+                stealth_address: funding.outputs[index as usize].stealth_address.clone(),
+            }),
+        };
+        let change_key = self.next_change_key(&account)?;
+        TransactionBuilder::new()
+            .network(self.network())
+            .sender(account.clone())
+            .with_ring_members(self.pick_decoys())
+            .add_htlc_input(utxo, preimage, seed)
+            .add_output(account.address.view_public.clone(), change_key, amount)
+            .build(parents[0], parents[1])
+    }
+}
+
+/// Lock of HTLC output `index` of `funding`
+fn htlc_output(funding: &Transaction, index: u32) -> Result<HtlcLock> {
+    funding.outputs.get(index as usize)
+        .and_then(|output| output.htlc)
+        .ok_or_else(|| WalletError::UnknownOutput(format!("{} is not an HTLC output", format_outpoint(&(funding.id(), index)))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::Account;
+    use nyx_core::dag::DagProcessor;
+    use nyx_core::storage::MemoryStorage;
+    use nyx_core::transaction::{TxOutput, UnlockTime};
+    use nyx_core::verification::{verify_htlc_spends, VerifyMode};
+    use nyx_core::versioning::ValidationContext;
+    use nyx_core::NetworkId;
+    use nyx_crypto::pedersen;
+
+    /// Wallet with one funded account, and HTLC keys for both paths
+    fn setup() -> (Wallet, HtlcLock, [u8; 32], [u8; 32], [u8; 32]) {
+        let mut wallet = Wallet::new();
+        wallet.add_account(Account::generate_with_name("swap".to_string())).unwrap();
+        wallet.credit_output("swap", [9u8; 32], 0, 5_000).unwrap();
+        wallet.confirm_output([9u8; 32], 0, RingMember { global_index: 0, stealth_address: vec![9u8; 32] }).unwrap();
+
+        let preimage = generate_preimage();
+        let (claim_seed, refund_seed) = ([1u8; 32], [2u8; 32]);
+        let htlc = HtlcLock {
+            hashlock: hashlock(&preimage),
+            timeout: UnlockTime::Height(100),
+            claim_key: keys::ed25519_public_key(&claim_seed),
+            refund_key: keys::ed25519_public_key(&refund_seed),
+        };
+        (wallet, htlc, preimage, claim_seed, refund_seed)
+    }
+
+    fn spent_output(funding: &Transaction, index: u32) -> Vec<TxOutput> {
+        vec![funding.outputs[index as usize].clone()]
+    }
+
+    #[test]
+    fn test_claim_reveals_preimage() {
+        let (mut wallet, htlc, preimage, claim_seed, _) = setup();
+        let address = wallet.get_active_account().unwrap().address.to_string();
+        let HtlcFunding { tx: funding, index, .. } = wallet.build_htlc_transaction(&address, 1_000, 10, htlc).unwrap();
+        assert_eq!(funding.version, nyx_core::HTLC_VERSION);
+
+        // The wallet sees its HTLC output but does not credit it
+        let synced = wallet.sync_transaction(funding.id(), &funding).unwrap();
+        assert!(synced.iter().all(|output| output.index != index));
+
+        assert!(wallet.claim_htlc(&funding, index, 1, &[0u8; 32], &claim_seed, 0, [[3u8; 32], [4u8; 32]]).is_err());
+        let claim = wallet.claim_htlc(&funding, index, 1, &preimage, &claim_seed, 10, [[3u8; 32], [4u8; 32]]).unwrap();
+        assert_eq!(claim.inputs[0].key_image, HtlcLock::key_image(&funding.id(), index));
+        assert_eq!(revealed_preimage(&claim, &htlc.hashlock), Some(preimage));
+
+        let ctx = ValidationContext { height: 0, timestamp: claim.timestamp };
+        verify_htlc_spends(&claim, &spent_output(&funding, index), &ctx).unwrap();
+
+        // Attaching other parents would invalidate the witness
+        let mut moved = claim.clone();
+        assert!(wallet.attach_parents(&mut moved, [[5u8; 32], [6u8; 32]]).is_err());
+    }
+
+    #[test]
+    fn test_refund_waits_for_timeout() {
+        let (mut wallet, htlc, _, _, refund_seed) = setup();
+        let address = wallet.get_active_account().unwrap().address.to_string();
+        let funding = wallet.build_htlc_transaction(&address, 1_000, 10, htlc).unwrap();
+        let parents = [[3u8; 32], [4u8; 32]];
+
+        assert!(wallet.refund_htlc(&funding, 1, &refund_seed, 0, parents).is_err());
+        wallet.set_chain_height(100);
+        assert!(wallet.refund_htlc(&funding, 1, &[7u8; 32], 0, parents).is_err());
+        let refund = wallet.refund_htlc(&funding, 1, &refund_seed, 0, parents).unwrap();
+        assert_eq!(revealed_preimage(&refund, &htlc.hashlock), None);
+
+        let spent = spent_output(&funding.tx, funding.index);
+        let early = ValidationContext { height: 99, timestamp: refund.timestamp };
+        assert!(verify_htlc_spends(&refund, &spent, &early).is_err());
+        let late = ValidationContext { height: 100, timestamp: refund.timestamp };
+        verify_htlc_spends(&refund, &spent, &late).unwrap();
+
+        // Not an HTLC output
+        let plain = HtlcFunding { index: (funding.index + 1) % funding.tx.outputs.len() as u32, ..funding };
+        assert!(wallet.refund_htlc(&plain, 1, &refund_seed, 0, parents).is_err());
+    }
+
+    #[test]
+    fn test_htlc_amount_is_blinded() {
+        let (wallet, htlc, _, _, _) = setup();
+        let address = wallet.get_active_account().unwrap().address.to_string();
+        let funding = wallet.build_htlc_transaction(&address, 1_000, 10, htlc).unwrap();
+
+        // The commitment does not reveal the swap amount, but the funder's
+        // mask opens it
+        let commitment = &funding.tx.outputs[funding.index as usize].amount_commitment;
+        assert_ne!(commitment, &pedersen::commit_public(1_000).to_bytes().to_vec());
+        assert_eq!(commitment, &pedersen::commit(1_000, &funding.mask).to_bytes().to_vec());
+    }
+
+    /// Finalizes `tx_hash`, returning its output at `index` as a ring member
+    fn finalize_output(dag: &DagProcessor, tx_hash: Hash, index: u32) -> RingMember {
+        dag.finalize_transactions(&[tx_hash]).unwrap();
+        let global_index = dag.storage().first_output_index(&tx_hash).unwrap().unwrap() + u64::from(index);
+        let stealth_address = dag.storage().get_output_by_index(global_index).unwrap().stealth_address;
+        RingMember { global_index, stealth_address }
+    }
+
+    /// Funds an HTLC paying the wallet itself on a fully verifying DAG,
+    /// returning the funding, its output as a ring member, and parents for
+    /// a spend of it
+    fn fund_on_dag(dag: &DagProcessor, wallet: &mut Wallet, htlc: HtlcLock) -> (HtlcFunding, RingMember, [Hash; 2]) {
+        let address = wallet.get_active_account().unwrap().address.to_string();
+        let minted: Vec<Hash> = (0..2)
+            .map(|_| dag.mint(vec![wallet.minted_output(1_000).unwrap()]).unwrap())
+            .collect();
+        wallet.credit_minted(minted[0], 0, 1_000).unwrap();
+        wallet.confirm_output(minted[0], 0, finalize_output(dag, minted[0], 0)).unwrap();
+        wallet.set_decoys(vec![finalize_output(dag, minted[1], 0)]);
+
+        let mut funding = wallet.build_htlc_transaction(&address, 600, 0, htlc).unwrap();
+        wallet.attach_parents(&mut funding.tx, [minted[0], minted[1]]).unwrap();
+        let funding_hash = dag.add_transaction_with_mode(funding.tx.clone(), VerifyMode::Full).unwrap();
+        let member = finalize_output(dag, funding_hash, funding.index);
+        (funding, member, [funding_hash, minted[1]])
+    }
+
+    #[test]
+    fn test_claim_passes_full_verification() {
+        let (_, htlc, preimage, claim_seed, _) = setup();
+        let dag = DagProcessor::new(MemoryStorage::new()).with_network(NetworkId::Regtest);
+        let mut wallet = Wallet::for_network(NetworkId::Regtest).with_generated_account();
+        let (funding, member, parents) = fund_on_dag(&dag, &mut wallet, htlc);
+
+        // The HTLC output is a ring member by its global index, among decoys
+        let claim = wallet
+            .claim_htlc(&funding.tx, funding.index, member.global_index, &preimage, &claim_seed, 0, parents)
+            .unwrap();
+        assert!(claim.inputs[0].ring_indices.contains(&u32::try_from(member.global_index).unwrap()));
+        assert!(claim.inputs[0].ring_indices.contains(&1));
+        dag.add_transaction_with_mode(claim, VerifyMode::Full).unwrap();
+    }
+
+    #[test]
+    fn test_refund_passes_full_verification() {
+        let (_, htlc, _, _, refund_seed) = setup();
+        let htlc = HtlcLock { timeout: UnlockTime::Height(0), ..htlc };
+        let dag = DagProcessor::new(MemoryStorage::new()).with_network(NetworkId::Regtest);
+        let mut wallet = Wallet::for_network(NetworkId::Regtest).with_generated_account();
+        let (funding, member, parents) = fund_on_dag(&dag, &mut wallet, htlc);
+
+        // The refund balances against the blinded HTLC output with the
+        // funder's mask
+        let refund = wallet.refund_htlc(&funding, member.global_index, &refund_seed, 0, parents).unwrap();
+        dag.add_transaction_with_mode(refund, VerifyMode::Full).unwrap();
+    }
+}
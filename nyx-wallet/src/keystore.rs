@@ -1,25 +1,171 @@
 // src/keystore.rs
 
 //! Encrypted keystore for secure account storage.
+//!
+//! A keystore file is JSON holding a header and the encrypted account:
+//!
+//! - `magic` and `version` identify the format
+//! - `kdf` names the password stretching function and its cost
+//! - `salt` is fresh on every write, so every write encrypts under a new
+//!   key and a repeated AES-GCM nonce cannot leak anything across writes
+//! - `cipher` names the AEAD
+//!
+//! The header is bound into the AEAD as associated data: lowering the KDF
+//! cost or swapping the salt makes decryption fail like a wrong password.
+//!
+//! Version 1 keystores, without a header or associated data, still load and
+//! decrypt; [`Wallet::unlock_keystore`] rewrites them in the current format.
 
 use crate::account::Account;
 use crate::errors::{WalletError, Result};
+use crate::wallet::Wallet;
 use nyx_crypto::encryption;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Identifies a keystore file
+pub const KEYSTORE_MAGIC: &str = "nyx-keystore";
+
+/// Keystore format written by this version
+pub const KEYSTORE_VERSION: u32 = 2;
+
+/// Password stretching rounds of new keystores
+pub const DEFAULT_KDF_ITERATIONS: u32 = 100_000;
+
+/// Size of a keystore's password salt
+const SALT_SIZE: usize = 32;
+
+/// Password stretching function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "kebab-case")]
+pub enum Kdf {
+    /// Single BLAKE3 hash of the password and salt (version 1 only)
+    Blake3Legacy,
+
+    /// BLAKE3 iterated over the salt `iterations` times
+    Blake3 {
+        /// Hashing rounds
+        iterations: u32,
+    },
+}
+
+impl Kdf {
+    fn id(&self) -> u8 {
+        match self {
+            Kdf::Blake3Legacy => 0,
+            Kdf::Blake3 { .. } => 1,
+        }
+    }
+
+    /// Derives the 32-byte encryption key
+    pub(crate) fn derive_key(&self, password: &str, salt: &[u8]) -> Vec<u8> {
+        match *self {
+            Kdf::Blake3Legacy => {
+                let mut data = password.as_bytes().to_vec();
+                if salt.iter().any(|byte| *byte != 0) {
+                    data.extend_from_slice(salt);
+                }
+                nyx_crypto::hash::blake3_hash(&data).to_vec()
+            }
+            Kdf::Blake3 { iterations } => {
+                let mut data = salt.to_vec();
+                data.extend_from_slice(password.as_bytes());
+                let mut key = nyx_crypto::hash::blake3_hash(&data);
+                for _ in 0..iterations {
+                    let mut round = key.to_vec();
+                    round.extend_from_slice(salt);
+                    key = nyx_crypto::hash::blake3_hash(&round);
+                }
+                key.to_vec()
+            }
+        }
+    }
+}
+
+/// Authenticated encryption of the account data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Cipher {
+    /// AES-256-GCM with a random 96-bit nonce
+    #[serde(rename = "aes-256-gcm")]
+    Aes256Gcm,
+}
+
+impl Cipher {
+    fn id(&self) -> u8 {
+        match self {
+            Cipher::Aes256Gcm => 0,
+        }
+    }
+}
+
+/// Parameters needed to decrypt a keystore
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeystoreHeader {
+    /// Always [`KEYSTORE_MAGIC`]
+    pub magic: String,
+
+    /// Format version
+    pub version: u32,
+
+    /// Password stretching function
+    pub kdf: Kdf,
+
+    /// Salt of the password; all zero in keystores written before salts
+    /// were used
+    pub salt: Vec<u8>,
+
+    /// Encryption of the account data
+    pub cipher: Cipher,
+}
+
+impl KeystoreHeader {
+    /// Header of a new keystore with a fresh salt
+    fn generate(kdf: Kdf) -> Self {
+        let mut salt = vec![0u8; SALT_SIZE];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Self {
+            magic: KEYSTORE_MAGIC.to_string(),
+            version: KEYSTORE_VERSION,
+            kdf,
+            salt,
+            cipher: Cipher::Aes256Gcm,
+        }
+    }
+
+    /// Associated data binding the header into the ciphertext
+    ///
+    /// Fixed-width fields in order, the salt prefixed with its length.
+    fn associated_data(&self) -> Vec<u8> {
+        let mut data = self.magic.as_bytes().to_vec();
+        data.extend_from_slice(&self.version.to_le_bytes());
+        data.push(self.kdf.id());
+        if let Kdf::Blake3 { iterations } = self.kdf {
+            data.extend_from_slice(&iterations.to_le_bytes());
+        }
+        data.extend_from_slice(&(self.salt.len() as u32).to_le_bytes());
+        data.extend_from_slice(&self.salt);
+        data.push(self.cipher.id());
+        data
+    }
+}
+
+/// Version 1 keystore file
+#[derive(Deserialize)]
+struct KeystoreV1 {
+    encrypted_data: Vec<u8>,
+    salt: Vec<u8>,
+}
+
 /// Encrypted keystore
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Keystore {
-    /// Keystore version
-    version: u32,
+    /// Format and key derivation parameters
+    header: KeystoreHeader,
 
     /// Encrypted account data
-    encrypted_data: Vec<u8>,
-
-    /// Salt for password derivation (mock)
-    salt: Vec<u8>,
+    ciphertext: Vec<u8>,
 }
 
 impl Keystore {
@@ -29,23 +175,52 @@ impl Keystore {
     /// * `account` - Account to store
     /// * `password` - Password for encryption
     pub fn new(account: &Account, password: &str) -> Result<Self> {
+        Self::with_kdf(account, password, Kdf::Blake3 { iterations: DEFAULT_KDF_ITERATIONS })
+    }
+
+    /// Creates a new keystore stretching the password with `kdf`
+    ///
+    /// # Errors
+    /// - `KeystoreError` for [`Kdf::Blake3Legacy`], which only version 1
+    ///   keystores use
+    pub fn with_kdf(account: &Account, password: &str, kdf: Kdf) -> Result<Self> {
+        if kdf == Kdf::Blake3Legacy {
+            return Err(WalletError::KeystoreError("The legacy KDF cannot be used for new keystores".to_string()));
+        }
+
         // Serialize account
         let account_data = account.export_binary()?;
 
-        // Derive key from password (mock implementation)
-        let key = Self::derive_key(password);
+        // Derive key from password under a fresh salt
+        let header = KeystoreHeader::generate(kdf);
+        let key = kdf.derive_key(password, &header.salt);
 
-        // Generate salt (mock)
-        let salt = vec![0u8; 32];
+        // Encrypt data, authenticating the header
+        let ciphertext = encryption::encrypt_with_aad(&account_data, &key, &header.associated_data())?;
 
-        // Encrypt data
-        let encrypted_data = encryption::encrypt(&account_data, &key)?;
+        Ok(Self { header, ciphertext })
+    }
 
-        Ok(Self {
-            version: crate::WALLET_VERSION,
-            encrypted_data,
-            salt,
-        })
+    /// Gets the header
+    pub fn header(&self) -> &KeystoreHeader {
+        &self.header
+    }
+
+    /// Checks whether the keystore is in an older format
+    pub fn needs_upgrade(&self) -> bool {
+        self.header.version < KEYSTORE_VERSION
+    }
+
+    /// Re-encrypts the keystore under a new password and a fresh salt
+    ///
+    /// The result is always in the current format.
+    ///
+    /// # Errors
+    /// - `InvalidPassword` if `old_password` does not decrypt the keystore
+    pub fn change_password(&mut self, old_password: &str, new_password: &str) -> Result<()> {
+        let account = self.decrypt(old_password)?;
+        *self = Self::new(&account, new_password)?;
+        Ok(())
     }
 
     /// Decrypts and returns the account
@@ -54,11 +229,15 @@ impl Keystore {
     /// * `password` - Password for decryption
     pub fn decrypt(&self, password: &str) -> Result<Account> {
         // Derive key from password
-        let key = Self::derive_key(password);
+        let key = self.header.kdf.derive_key(password, &self.header.salt);
 
-        // Decrypt data
-        let account_data = encryption::decrypt(&self.encrypted_data, &key)
-            .map_err(|_| WalletError::InvalidPassword)?;
+        // Decrypt data; version 1 has no associated data
+        let account_data = if self.header.version == 1 {
+            encryption::decrypt(&self.ciphertext, &key)
+        } else {
+            encryption::decrypt_with_aad(&self.ciphertext, &key, &self.header.associated_data())
+        };
+        let account_data = account_data.map_err(|_| WalletError::InvalidPassword)?;
 
         // Deserialize account
         Account::import_binary(&account_data)
@@ -66,32 +245,89 @@ impl Keystore {
 
     /// Saves keystore to file
     ///
+    /// The keystore is written to a temporary file next to `path`, which
+    /// then replaces `path`, so a crash never leaves a truncated keystore.
+    ///
     /// # Arguments
     /// * `path` - File path to save to
+    ///
+    /// # Errors
+    /// - `KeystoreError` for a version 1 keystore; upgrade it first
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        if self.needs_upgrade() {
+            return Err(WalletError::KeystoreError("Refusing to write a version 1 keystore".to_string()));
+        }
+
+        let path = path.as_ref();
+        let file_name = path.file_name()
+            .ok_or_else(|| WalletError::KeystoreError(format!("Invalid keystore path: {}", path.display())))?;
+        let mut temp_name = file_name.to_os_string();
+        temp_name.push(".tmp");
+        let temp_path = path.with_file_name(temp_name);
+
         let json = serde_json::to_string_pretty(self)?;
-        fs::write(path, json)?;
+        let written = fs::File::create(&temp_path).and_then(|mut file| {
+            std::io::Write::write_all(&mut file, json.as_bytes())?;
+            file.sync_all()
+        });
+        if let Err(e) = written.and_then(|()| fs::rename(&temp_path, path)) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+
+        // Make the rename itself durable
+        #[cfg(unix)]
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::File::open(dir)?.sync_all()?;
+        }
         Ok(())
     }
 
     /// Loads keystore from file
     ///
+    /// Version 1 files load as well; see [`needs_upgrade`](Self::needs_upgrade).
+    ///
     /// # Arguments
     /// * `path` - File path to load from
+    ///
+    /// # Errors
+    /// - `FileNotFound` if the file cannot be read
+    /// - `KeystoreError` if the file is not a keystore or is newer than this
+    ///   version supports
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let json = fs::read_to_string(path)
             .map_err(|_| WalletError::FileNotFound("Keystore file not found".to_string()))?;
-
-        Ok(serde_json::from_str(&json)?)
+        Self::from_json(&json)
     }
 
-    /// Derives encryption key from password (mock implementation)
-    fn derive_key(password: &str) -> Vec<u8> {
-        // In production, use proper KDF like Argon2 or PBKDF2
-        // For now, simple hash-based derivation
-        let mut key = nyx_crypto::hash::blake3_hash(password.as_bytes()).to_vec();
-        key.truncate(32); // AES-256 key size
-        key
+    /// Parses a keystore of any supported version
+    fn from_json(json: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        if value.get("header").is_none() {
+            let v1: KeystoreV1 = serde_json::from_value(value)?;
+            return Ok(Self {
+                header: KeystoreHeader {
+                    magic: KEYSTORE_MAGIC.to_string(),
+                    version: 1,
+                    kdf: Kdf::Blake3Legacy,
+                    salt: v1.salt,
+                    cipher: Cipher::Aes256Gcm,
+                },
+                ciphertext: v1.encrypted_data,
+            });
+        }
+
+        let keystore: Self = serde_json::from_value(value)?;
+        if keystore.header.magic != KEYSTORE_MAGIC {
+            return Err(WalletError::KeystoreError("Not a Nyx keystore".to_string()));
+        }
+        if keystore.header.version != KEYSTORE_VERSION {
+            return Err(WalletError::KeystoreError(format!(
+                "Unsupported keystore version {}",
+                keystore.header.version
+            )));
+        }
+        Ok(keystore)
     }
 
     /// Gets the default keystore directory
@@ -110,6 +346,33 @@ impl Keystore {
     }
 }
 
+impl Wallet {
+    /// Decrypts a keystore file, upgrading it to the current format
+    ///
+    /// # Errors
+    /// - `InvalidPassword` if `password` does not decrypt the keystore; the
+    ///   file is left untouched
+    pub fn unlock_keystore<P: AsRef<Path>>(path: P, password: &str) -> Result<Account> {
+        let keystore = Keystore::load_from_file(&path)?;
+        let account = keystore.decrypt(password)?;
+        if keystore.needs_upgrade() {
+            Keystore::new(&account, password)?.save_to_file(path)?;
+        }
+        Ok(account)
+    }
+
+    /// Changes the password of a keystore file in place
+    ///
+    /// # Errors
+    /// - `InvalidPassword` if `old_password` does not decrypt the keystore;
+    ///   the file is left untouched
+    pub fn change_keystore_password<P: AsRef<Path>>(path: P, old_password: &str, new_password: &str) -> Result<()> {
+        let mut keystore = Keystore::load_from_file(&path)?;
+        keystore.change_password(old_password, new_password)?;
+        keystore.save_to_file(path)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,8 +384,10 @@ mod tests {
         let password = "secure_password";
 
         let keystore = Keystore::new(&account, password).unwrap();
-        assert_eq!(keystore.version, crate::WALLET_VERSION);
-        assert!(!keystore.encrypted_data.is_empty());
+        assert_eq!(keystore.header().version, KEYSTORE_VERSION);
+        assert_eq!(keystore.header().kdf, Kdf::Blake3 { iterations: DEFAULT_KDF_ITERATIONS });
+        assert!(!keystore.needs_upgrade());
+        assert!(!keystore.ciphertext.is_empty());
     }
 
     #[test]
@@ -171,10 +436,122 @@ mod tests {
 
     #[test]
     fn test_derive_key() {
-        let key1 = Keystore::derive_key("password");
-        let key2 = Keystore::derive_key("password");
+        let kdf = Kdf::Blake3 { iterations: 10 };
+        let key1 = kdf.derive_key("password", &[1u8; SALT_SIZE]);
+        let key2 = kdf.derive_key("password", &[1u8; SALT_SIZE]);
 
         assert_eq!(key1, key2);
         assert_eq!(key1.len(), 32);
+        assert_ne!(key1, kdf.derive_key("password", &[2u8; SALT_SIZE]));
+        assert_ne!(key1, Kdf::Blake3 { iterations: 11 }.derive_key("password", &[1u8; SALT_SIZE]));
+    }
+
+    #[test]
+    fn test_header_is_authenticated() {
+        let account = Account::generate();
+        let keystore = Keystore::with_kdf(&account, "pass", Kdf::Blake3 { iterations: 10 }).unwrap();
+        assert!(keystore.decrypt("pass").is_ok());
+
+        // Lowering the KDF cost breaks decryption even with the right key
+        let mut weakened = keystore.clone();
+        weakened.header.kdf = Kdf::Blake3 { iterations: 1 };
+        let key = keystore.header.kdf.derive_key("pass", &keystore.header.salt);
+        assert!(encryption::decrypt_with_aad(&weakened.ciphertext, &key, &weakened.header.associated_data()).is_err());
+        assert!(matches!(weakened.decrypt("pass"), Err(WalletError::InvalidPassword)));
+
+        assert!(Keystore::with_kdf(&account, "pass", Kdf::Blake3Legacy).is_err());
+    }
+
+    #[test]
+    fn test_foreign_files_are_rejected() {
+        let account = Account::generate();
+        let keystore = Keystore::with_kdf(&account, "pass", Kdf::Blake3 { iterations: 10 }).unwrap();
+
+        let mut newer = serde_json::to_value(&keystore).unwrap();
+        newer["header"]["version"] = 3.into();
+        assert!(matches!(
+            Keystore::from_json(&newer.to_string()),
+            Err(WalletError::KeystoreError(_))
+        ));
+
+        let mut foreign = serde_json::to_value(&keystore).unwrap();
+        foreign["header"]["magic"] = "other".into();
+        assert!(matches!(
+            Keystore::from_json(&foreign.to_string()),
+            Err(WalletError::KeystoreError(_))
+        ));
+    }
+
+    #[test]
+    fn test_unsalted_keystore_still_decrypts() {
+        let account = Account::generate();
+        let json = serde_json::json!({
+            "version": 1,
+            "encrypted_data": encryption::encrypt(
+                &account.export_binary().unwrap(),
+                &nyx_crypto::hash::blake3_hash(b"legacy"),
+            ).unwrap(),
+            "salt": vec![0u8; SALT_SIZE],
+        });
+
+        let keystore = Keystore::from_json(&json.to_string()).unwrap();
+        assert!(keystore.needs_upgrade());
+        assert_eq!(keystore.decrypt("legacy").unwrap().address, account.address);
+    }
+
+    #[test]
+    fn test_v1_keystore_is_upgraded_on_unlock() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("keystore.json");
+
+        let account = Account::generate();
+        let salt = vec![5u8; SALT_SIZE];
+        let key = Kdf::Blake3Legacy.derive_key("pass", &salt);
+        let json = serde_json::json!({
+            "version": 1,
+            "encrypted_data": encryption::encrypt(&account.export_binary().unwrap(), &key).unwrap(),
+            "salt": salt,
+        });
+        fs::write(&file_path, json.to_string()).unwrap();
+
+        // A v1 keystore cannot be written back as is
+        let v1 = Keystore::load_from_file(&file_path).unwrap();
+        assert!(v1.save_to_file(&file_path).is_err());
+
+        // The wrong password leaves the file alone
+        assert!(Wallet::unlock_keystore(&file_path, "wrong").is_err());
+        assert!(Keystore::load_from_file(&file_path).unwrap().needs_upgrade());
+
+        let unlocked = Wallet::unlock_keystore(&file_path, "pass").unwrap();
+        assert_eq!(unlocked.address, account.address);
+
+        let upgraded = Keystore::load_from_file(&file_path).unwrap();
+        assert_eq!(upgraded.header().version, KEYSTORE_VERSION);
+        assert_eq!(upgraded.decrypt("pass").unwrap().address, account.address);
+    }
+
+    #[test]
+    fn test_change_keystore_password() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("keystore.json");
+
+        let account = Account::generate_with_name("rotate".to_string());
+        let keystore = Keystore::new(&account, "old").unwrap();
+        keystore.save_to_file(&file_path).unwrap();
+
+        assert!(matches!(
+            Wallet::change_keystore_password(&file_path, "wrong", "new"),
+            Err(WalletError::InvalidPassword)
+        ));
+        assert!(Keystore::load_from_file(&file_path).unwrap().decrypt("old").is_ok());
+
+        Wallet::change_keystore_password(&file_path, "old", "new").unwrap();
+        let loaded = Keystore::load_from_file(&file_path).unwrap();
+        assert!(loaded.decrypt("old").is_err());
+        assert_eq!(loaded.decrypt("new").unwrap().address, account.address);
+        assert_ne!(loaded.header().salt, keystore.header().salt);
+
+        // Only the keystore remains, no temporary file
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
     }
 }
@@ -0,0 +1,421 @@
+// src/ledger.rs
+
+//! Exchange deposit ledger.
+//!
+//! A [`DepositLedger`] gives each user of a custodial service its own
+//! deposit address: subaddress `i` of one wallet account, with spend key
+//! B + H(a || "deposit" || i)G under the account's view key. Users get
+//! indices in the order they first ask for an address, and keep them.
+//!
+//! The wallet recognizes payments to watched deposit subaddresses like any
+//! other owned output and reports their index in
+//! [`ScannedOutput::deposit_index`]; [`DepositLedger::record`] turns those
+//! into deposits credited to the user the index belongs to. Deposits start
+//! out [`DepositStatus::Pending`] and move forward as the caller learns
+//! from its node that the paying transaction is confirmed, then finalized
+//! by a PoS snapshot.
+//!
+//! The ledger is exported as CSV or JSON for reconciliation. User
+//! identifiers are limited to characters that need no quoting in CSV and
+//! cannot start a spreadsheet formula.
+
+use crate::account::Address;
+use crate::errors::{WalletError, Result};
+use crate::wallet::{ScannedOutput, Wallet};
+use nyx_core::Hash;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Longest user identifier accepted
+pub const MAX_USER_ID_LENGTH: usize = 64;
+
+/// Header line of CSV exports
+pub const CSV_HEADER: &str = "user_id,subaddress,tx_hash,index,amount,status";
+
+/// How far a deposit's transaction has settled
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DepositStatus {
+    /// Seen by a scan, not yet confirmed
+    Pending,
+
+    /// Confirmed in the DAG
+    Confirmed,
+
+    /// Finalized by a PoS snapshot; cannot be reverted
+    Finalized,
+}
+
+impl DepositStatus {
+    /// Name used in exports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DepositStatus::Pending => "pending",
+            DepositStatus::Confirmed => "confirmed",
+            DepositStatus::Finalized => "finalized",
+        }
+    }
+}
+
+/// Payment to a user's deposit address
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deposit {
+    /// User the deposit is credited to
+    pub user_id: String,
+
+    /// Deposit subaddress index it paid
+    pub subaddress: u32,
+
+    /// Paying transaction
+    pub tx_hash: Hash,
+
+    /// Output index within the transaction
+    pub index: u32,
+
+    /// Amount deposited
+    pub amount: u64,
+
+    /// How far the transaction has settled
+    pub status: DepositStatus,
+}
+
+/// Deposit addresses and deposits of the users of one account
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositLedger {
+    /// Account whose subaddresses users deposit to
+    account: String,
+
+    /// Next subaddress index to hand out
+    next_index: u32,
+
+    /// Subaddress index of each user
+    users: BTreeMap<String, u32>,
+
+    /// Deposits in the order they were recorded
+    deposits: Vec<Deposit>,
+}
+
+impl Wallet {
+    /// Watches deposit subaddress `index` of an account, so scans find
+    /// payments to it
+    ///
+    /// # Returns
+    /// The subaddress's address
+    ///
+    /// # Errors
+    /// - `AccountNotFound` if the wallet has no such account
+    pub fn watch_deposit_address(&mut self, account_name: &str, index: u32) -> Result<Address> {
+        let account = self.accounts.iter().find(|account| account.name == account_name)
+            .ok_or_else(|| WalletError::AccountNotFound(account_name.to_string()))?;
+        let address = account.deposit_address(index)?;
+        self.deposits.entry(account_name.to_string()).or_default()
+            .insert(address.spend_public.clone(), index);
+        Ok(address)
+    }
+}
+
+impl DepositLedger {
+    /// Creates an empty ledger for deposits to `account`
+    pub fn new(account: &str) -> Self {
+        Self { account: account.to_string(), next_index: 0, users: BTreeMap::new(), deposits: Vec::new() }
+    }
+
+    /// Loads a saved ledger
+    ///
+    /// Call [`watch`](Self::watch) with the wallet afterwards.
+    ///
+    /// # Errors
+    /// - `FileNotFound` if the file cannot be read
+    /// - `SerializationError` if it is not a ledger
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path)
+            .map_err(|_| WalletError::FileNotFound(format!("Ledger {}", path.display())))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Saves the ledger, replacing the file atomically
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut temp_name = path.file_name()
+            .ok_or_else(|| WalletError::SerializationError(format!("Invalid ledger path: {}", path.display())))?
+            .to_os_string();
+        temp_name.push(".tmp");
+        let temp_path = path.with_file_name(temp_name);
+
+        fs::write(&temp_path, serde_json::to_string_pretty(self)?)?;
+        if let Err(e) = fs::rename(&temp_path, path) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Account the ledger's deposit addresses belong to
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// Gets the deposit address of a user, allocating the next subaddress
+    /// index to users asking for the first time
+    ///
+    /// # Errors
+    /// - `InvalidUserId` if the identifier is not accepted
+    /// - `AccountNotFound` if the wallet lacks the ledger's account
+    pub fn allocate(&mut self, wallet: &mut Wallet, user_id: &str) -> Result<Address> {
+        validate_user_id(user_id)?;
+        if let Some(&index) = self.users.get(user_id) {
+            return wallet.watch_deposit_address(&self.account, index);
+        }
+
+        let index = self.next_index;
+        let address = wallet.watch_deposit_address(&self.account, index)?;
+        self.next_index += 1;
+        self.users.insert(user_id.to_string(), index);
+        Ok(address)
+    }
+
+    /// Makes the wallet watch every allocated deposit address, as needed
+    /// after loading the ledger
+    pub fn watch(&self, wallet: &mut Wallet) -> Result<()> {
+        for &index in self.users.values() {
+            wallet.watch_deposit_address(&self.account, index)?;
+        }
+        Ok(())
+    }
+
+    /// Subaddress index allocated to a user
+    pub fn index_of(&self, user_id: &str) -> Option<u32> {
+        self.users.get(user_id).copied()
+    }
+
+    /// User a subaddress index was allocated to
+    pub fn user_of(&self, index: u32) -> Option<&str> {
+        self.users.iter().find(|(_, &i)| i == index).map(|(user, _)| user.as_str())
+    }
+
+    /// Records deposits among scanned outputs
+    ///
+    /// Takes what [`Wallet::sync_transaction`] returns. Outputs of other
+    /// accounts, outputs not paying an allocated deposit address, outputs
+    /// of unknown or inconsistent amount and outputs recorded before are
+    /// skipped.
+    ///
+    /// # Returns
+    /// Newly recorded deposits, all pending
+    pub fn record(&mut self, outputs: &[ScannedOutput]) -> Vec<Deposit> {
+        let mut recorded = Vec::new();
+        for output in outputs {
+            if output.account != self.account || output.inconsistent {
+                continue;
+            }
+            let (Some(subaddress), Some(amount)) = (output.deposit_index, output.amount) else {
+                continue;
+            };
+            let Some(user_id) = self.user_of(subaddress) else {
+                continue;
+            };
+            let known = self.deposits.iter()
+                .any(|deposit| deposit.tx_hash == output.tx_hash && deposit.index == output.index);
+            if known || amount == 0 {
+                continue;
+            }
+
+            let deposit = Deposit {
+                user_id: user_id.to_string(),
+                subaddress,
+                tx_hash: output.tx_hash,
+                index: output.index,
+                amount,
+                status: DepositStatus::Pending,
+            };
+            self.deposits.push(deposit.clone());
+            recorded.push(deposit);
+        }
+        recorded
+    }
+
+    /// Advances the deposits of a transaction to `status`
+    ///
+    /// Statuses only move forward; a report older than what the ledger
+    /// already knows is ignored.
+    ///
+    /// # Returns
+    /// Deposits whose status changed
+    pub fn set_status(&mut self, tx_hash: &Hash, status: DepositStatus) -> Vec<Deposit> {
+        self.deposits.iter_mut()
+            .filter(|deposit| &deposit.tx_hash == tx_hash && deposit.status < status)
+            .map(|deposit| {
+                deposit.status = status;
+                deposit.clone()
+            })
+            .collect()
+    }
+
+    /// Deposits in the order they were recorded
+    pub fn deposits(&self) -> &[Deposit] {
+        &self.deposits
+    }
+
+    /// Deposits of one user
+    pub fn deposits_of<'a>(&'a self, user_id: &'a str) -> impl Iterator<Item = &'a Deposit> + 'a {
+        self.deposits.iter().filter(move |deposit| deposit.user_id == user_id)
+    }
+
+    /// Total a user deposited in transactions at least `min_status` settled
+    pub fn credited(&self, user_id: &str, min_status: DepositStatus) -> u64 {
+        self.deposits_of(user_id)
+            .filter(|deposit| deposit.status >= min_status)
+            .fold(0u64, |total, deposit| total.saturating_add(deposit.amount))
+    }
+
+    /// Exports the deposits as CSV, one line each after [`CSV_HEADER`]
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("{}\n", CSV_HEADER);
+        for deposit in &self.deposits {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                deposit.user_id,
+                deposit.subaddress,
+                hex::encode(deposit.tx_hash),
+                deposit.index,
+                deposit.amount,
+                deposit.status.as_str(),
+            ));
+        }
+        csv
+    }
+
+    /// Exports the deposits as a JSON array, hashes in hex
+    pub fn to_json(&self) -> Result<String> {
+        let deposits: Vec<serde_json::Value> = self.deposits.iter()
+            .map(|deposit| json!({
+                "user_id": deposit.user_id,
+                "subaddress": deposit.subaddress,
+                "tx_hash": hex::encode(deposit.tx_hash),
+                "index": deposit.index,
+                "amount": deposit.amount,
+                "status": deposit.status,
+            }))
+            .collect();
+        Ok(serde_json::to_string_pretty(&deposits)?)
+    }
+}
+
+/// Checks a user identifier: ASCII letters, digits and `-_.:@`, starting
+/// with a letter or digit
+fn validate_user_id(user_id: &str) -> Result<()> {
+    if user_id.is_empty() || user_id.len() > MAX_USER_ID_LENGTH {
+        return Err(WalletError::InvalidUserId(format!(
+            "Must be 1 to {} characters long",
+            MAX_USER_ID_LENGTH
+        )));
+    }
+    let allowed = |c: char| c.is_ascii_alphanumeric() || "-_.:@".contains(c);
+    if !user_id.starts_with(|c: char| c.is_ascii_alphanumeric()) || !user_id.chars().all(allowed) {
+        return Err(WalletError::InvalidUserId(user_id.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::Account;
+    use crate::builder::{RingMember, Utxo};
+
+    fn exchange() -> (Wallet, DepositLedger) {
+        let mut wallet = Wallet::new();
+        wallet.add_account(Account::generate_with_name("hot".to_string())).unwrap();
+        (wallet, DepositLedger::new("hot"))
+    }
+
+    fn pay(to: &Address, amount: u64) -> (Hash, nyx_core::Transaction) {
+        let mut payer = Wallet::with_default_account();
+        let name = payer.get_active_account().unwrap().name.clone();
+        let utxo = Utxo {
+            tx_hash: [amount as u8; 32],
+            index: 0,
+            amount: 10_000,
+            key_image: [amount as u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![amount as u8; 32] }),
+        };
+        payer.add_utxo(&name, utxo).unwrap();
+        let tx = payer.build_transaction(&to.to_string(), amount, 0).unwrap();
+        (tx.id(), tx)
+    }
+
+    #[test]
+    fn test_users_keep_their_deposit_addresses() {
+        let (mut wallet, mut ledger) = exchange();
+        let alice = ledger.allocate(&mut wallet, "alice").unwrap();
+        let bob = ledger.allocate(&mut wallet, "bob@example.com").unwrap();
+        assert_ne!(alice, bob);
+        assert_eq!(ledger.allocate(&mut wallet, "alice").unwrap(), alice);
+        assert_eq!((ledger.index_of("alice"), ledger.index_of("bob@example.com")), (Some(0), Some(1)));
+        assert_eq!(ledger.user_of(1), Some("bob@example.com"));
+
+        for bad in ["", "=cmd()", "a,b", "名前", &"x".repeat(MAX_USER_ID_LENGTH + 1)] {
+            assert!(matches!(ledger.allocate(&mut wallet, bad), Err(WalletError::InvalidUserId(_))));
+        }
+        assert!(DepositLedger::new("cold").allocate(&mut wallet, "alice").is_err());
+    }
+
+    #[test]
+    fn test_deposits_are_credited_to_their_user() {
+        let (mut wallet, mut ledger) = exchange();
+        let alice = ledger.allocate(&mut wallet, "alice").unwrap();
+        ledger.allocate(&mut wallet, "bob").unwrap();
+
+        let (tx_hash, tx) = pay(&alice, 250);
+        let scanned = wallet.sync_transaction(tx_hash, &tx).unwrap();
+        assert_eq!(scanned.len(), 1);
+        assert_eq!(scanned[0].deposit_index, Some(0));
+        assert_eq!(wallet.get_balance(), 250);
+
+        let recorded = ledger.record(&scanned);
+        assert_eq!(recorded.len(), 1);
+        assert_eq!((recorded[0].user_id.as_str(), recorded[0].amount), ("alice", 250));
+        assert!(ledger.record(&scanned).is_empty());
+
+        // Statuses only move forward
+        assert_eq!(ledger.credited("alice", DepositStatus::Confirmed), 0);
+        assert_eq!(ledger.set_status(&tx_hash, DepositStatus::Finalized).len(), 1);
+        assert!(ledger.set_status(&tx_hash, DepositStatus::Confirmed).is_empty());
+        assert_eq!(ledger.credited("alice", DepositStatus::Finalized), 250);
+        assert_eq!(ledger.credited("bob", DepositStatus::Pending), 0);
+
+        // A restarted exchange watches its addresses again
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ledger.json");
+        ledger.save(&path).unwrap();
+        let loaded = DepositLedger::load(&path).unwrap();
+        assert_eq!(loaded, ledger);
+        let mut restarted = Wallet::new();
+        restarted.add_account(wallet.get_active_account().unwrap().clone()).unwrap();
+        assert!(restarted.scan_transaction(tx_hash, &tx).is_empty());
+        loaded.watch(&mut restarted).unwrap();
+        assert_eq!(restarted.scan_transaction(tx_hash, &tx).len(), 1);
+    }
+
+    #[test]
+    fn test_exports() {
+        let (mut wallet, mut ledger) = exchange();
+        let bob = ledger.allocate(&mut wallet, "bob").unwrap();
+        let (tx_hash, tx) = pay(&bob, 40);
+        ledger.record(&wallet.sync_transaction(tx_hash, &tx).unwrap());
+        ledger.set_status(&tx_hash, DepositStatus::Confirmed);
+
+        let index = ledger.deposits()[0].index;
+        assert_eq!(
+            ledger.to_csv(),
+            format!("{}\nbob,0,{},{},40,confirmed\n", CSV_HEADER, hex::encode(tx_hash), index)
+        );
+        let json: serde_json::Value = serde_json::from_str(&ledger.to_json().unwrap()).unwrap();
+        assert_eq!(json[0]["tx_hash"], hex::encode(tx_hash));
+        assert_eq!(json[0]["status"], "confirmed");
+    }
+}
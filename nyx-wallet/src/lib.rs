@@ -6,9 +6,23 @@
 //!
 //! This module provides:
 //! - **Account Management**: Key generation and storage
-//! - **Transaction Building**: Create privacy-preserving transactions
+//! - **Transaction Building**: Create privacy-preserving transactions, paying
+//!   one recipient or many at once
+//! - **Coin Control**: Frozen, labelled and hand-picked inputs
+//! - **Change**: Change paid to fresh, deterministic subaddresses
 //! - **Balance Tracking**: Monitor wallet balances
 //! - **Keystore**: Encrypted key storage
+//! - **Locking**: Spend keys encrypted in memory behind a passphrase
+//! - **Backup**: Encrypted backups restored with threshold recovery codes
+//! - **Recovery Phrases**: Deterministic accounts restored from a mnemonic
+//! - **Confirmation Estimates**: Expected wait for pending sends
+//! - **Daemon**: One wallet process serving GUIs and the CLI over local RPC
+//! - **Scheduled Payments**: One-off and recurring payments made by the daemon
+//! - **Auto-Sync**: Background scanning of a node for incoming payments
+//! - **Payment Proofs**: Showing a third party who an output paid
+//! - **Audit Packages**: View-only disclosure of incoming history
+//! - **Deposit Ledger**: Per-user deposit subaddresses for exchanges
+//! - **Atomic Swaps**: Hash time-locked outputs claimed with a secret or refunded
 //!
 //! ## Example Usage
 //!
@@ -32,16 +46,41 @@
 
 pub mod errors;
 pub mod account;
+pub mod amount;
+pub mod audit;
 pub mod keystore;
+pub mod ledger;
+pub mod daemon;
+pub mod lock;
+pub mod backup;
 pub mod builder;
+pub mod change;
+pub mod coin_control;
+pub mod mnemonic;
+pub mod estimate;
+pub mod htlc;
+pub mod proof;
+pub mod schedule;
+pub mod sync;
 pub mod wallet;
 
 // Re-export commonly used types
 pub use crate::errors::{WalletError, Result};
 pub use crate::account::Account;
-pub use crate::keystore::Keystore;
-pub use crate::builder::TransactionBuilder;
-pub use crate::wallet::Wallet;
+pub use crate::keystore::{Cipher, Kdf, Keystore, KeystoreHeader};
+pub use crate::daemon::{DaemonClient, DaemonConfig, HistoryEntry, WalletDaemon};
+pub use crate::backup::{Backup, RecoveryCode};
+pub use crate::builder::{RingMember, TransactionBuilder};
+pub use crate::coin_control::{OutputInfo, SendOptions};
+pub use crate::mnemonic::Mnemonic;
+pub use crate::wallet::{AccountBalance, ScannedOutput, Wallet, WalletStats};
+pub use crate::estimate::describe_confirmation;
+pub use crate::htlc::{generate_preimage, hashlock, revealed_preimage, HtlcFunding};
+pub use crate::ledger::{Deposit, DepositLedger, DepositStatus};
+pub use crate::audit::{audit, AuditEntry, AuditPackage, AuditReport};
+pub use crate::schedule::{Schedule, ScheduledPayment};
+pub use crate::proof::{verify_payment, PaymentProof, PaymentSecret, VerifiedPayment};
+pub use crate::sync::{AutoSync, RpcSource, TransactionSource, WalletEvent};
 
 /// Wallet version for compatibility
 pub const WALLET_VERSION: u32 = 1;
@@ -0,0 +1,294 @@
+// src/lock.rs
+
+//! Passphrase locking of spend keys in memory.
+//!
+//! Once a passphrase is set, [`Wallet::lock`] encrypts every account's spend
+//! key under a key derived from the passphrase and wipes the plaintext. A
+//! locked wallet keeps scanning for payments, which only needs view keys,
+//! but building, signing and backing up fail with `WalletLocked` until
+//! [`Wallet::unlock`].
+//!
+//! With an auto-lock timeout the wallet counts as locked once it has stayed
+//! unlocked that long; [`Wallet::enforce_auto_lock`] then wipes the keys.
+//! Long-running wallets call it periodically.
+//!
+//! Key images of outputs credited while locked depend on the spend key, so
+//! they are filled in on unlock.
+
+use crate::errors::{WalletError, Result};
+use crate::keystore::{Kdf, DEFAULT_KDF_ITERATIONS};
+use crate::wallet::Wallet;
+use nyx_core::Hash;
+use nyx_crypto::encryption;
+use rand::RngCore;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+/// Plaintext encrypted under the passphrase to recognize it again
+const VERIFIER: &[u8] = b"nyx-wallet-lock";
+
+/// Passphrase state of a wallet
+#[derive(Clone)]
+pub(crate) struct WalletLock {
+    /// Stretching of the passphrase
+    kdf: Kdf,
+
+    /// Salt of the passphrase
+    salt: Vec<u8>,
+
+    /// [`VERIFIER`] encrypted under the passphrase key
+    verifier: Vec<u8>,
+
+    /// Key derived from the passphrase; held only while unlocked
+    key: Option<Zeroizing<Vec<u8>>>,
+
+    /// Encrypted spend keys by account name; held only while locked
+    sealed: HashMap<String, Vec<u8>>,
+
+    /// When the wallet was last unlocked
+    unlocked_at: Instant,
+
+    /// How long the wallet stays unlocked
+    auto_lock: Option<Duration>,
+
+    /// Outputs credited while locked, by account, awaiting key images
+    deferred: Vec<(String, Hash, u32)>,
+}
+
+impl WalletLock {
+    /// Checks whether the auto-lock timeout has passed
+    fn expired(&self) -> bool {
+        self.auto_lock.is_some_and(|timeout| self.unlocked_at.elapsed() >= timeout)
+    }
+}
+
+impl Wallet {
+    /// Sets the passphrase that [`unlock`](Self::unlock) asks for
+    ///
+    /// The wallet stays unlocked; an existing passphrase is replaced.
+    ///
+    /// # Errors
+    /// - `WalletLocked` if the wallet is locked
+    pub fn set_passphrase(&mut self, passphrase: &str) -> Result<()> {
+        self.ensure_unlocked()?;
+
+        let kdf = Kdf::Blake3 { iterations: DEFAULT_KDF_ITERATIONS };
+        let mut salt = vec![0u8; 32];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let key = Zeroizing::new(kdf.derive_key(passphrase, &salt));
+        let verifier = encryption::encrypt(VERIFIER, &key)?;
+
+        let auto_lock = self.lock.as_ref().and_then(|lock| lock.auto_lock);
+        self.lock = Some(WalletLock {
+            kdf,
+            salt,
+            verifier,
+            key: Some(key),
+            sealed: HashMap::new(),
+            unlocked_at: Instant::now(),
+            auto_lock,
+            deferred: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// Checks whether a passphrase is set
+    pub fn has_passphrase(&self) -> bool {
+        self.lock.is_some()
+    }
+
+    /// Checks whether spending needs [`unlock`](Self::unlock) first
+    pub fn is_locked(&self) -> bool {
+        self.lock.as_ref().is_some_and(|lock| lock.key.is_none() || lock.expired())
+    }
+
+    /// Sets how long the wallet stays unlocked; `None` keeps it unlocked
+    /// until [`lock`](Self::lock)
+    ///
+    /// # Errors
+    /// - `KeystoreError` if no passphrase is set
+    pub fn set_auto_lock(&mut self, timeout: Option<Duration>) -> Result<()> {
+        self.passphrase_lock()?.auto_lock = timeout;
+        Ok(())
+    }
+
+    /// Encrypts the spend keys and wipes them from memory
+    ///
+    /// Locking a locked wallet does nothing.
+    ///
+    /// # Errors
+    /// - `KeystoreError` if no passphrase is set
+    pub fn lock(&mut self) -> Result<()> {
+        let lock = self.lock.as_mut()
+            .ok_or_else(|| WalletError::KeystoreError("Wallet has no passphrase".to_string()))?;
+        let Some(key) = lock.key.as_ref() else {
+            return Ok(());
+        };
+
+        // Encrypt every key before wiping any
+        let mut sealed = HashMap::new();
+        for account in &self.accounts {
+            sealed.insert(account.name.clone(), encryption::encrypt(account.spend_private_key(), key)?);
+        }
+        for account in &mut self.accounts {
+            drop(account.take_spend_private_key());
+        }
+        lock.sealed = sealed;
+        lock.key = None;
+        Ok(())
+    }
+
+    /// Decrypts the spend keys and restarts the auto-lock timeout
+    ///
+    /// # Errors
+    /// - `KeystoreError` if no passphrase is set
+    /// - `InvalidPassword` if `passphrase` is not the one set; the wallet
+    ///   stays as it was
+    pub fn unlock(&mut self, passphrase: &str) -> Result<()> {
+        let lock = self.passphrase_lock()?;
+        let key = Zeroizing::new(lock.kdf.derive_key(passphrase, &lock.salt));
+        encryption::decrypt(&lock.verifier, &key).map_err(|_| WalletError::InvalidPassword)?;
+
+        let mut keys = HashMap::new();
+        for (name, sealed) in &lock.sealed {
+            let spend_key = encryption::decrypt(sealed, &key).map_err(|_| WalletError::InvalidPassword)?;
+            keys.insert(name.clone(), Zeroizing::new(spend_key));
+        }
+        let deferred = std::mem::take(&mut lock.deferred);
+        lock.sealed.clear();
+        lock.key = Some(key);
+        lock.unlocked_at = Instant::now();
+
+        for account in &mut self.accounts {
+            if let Some(spend_key) = keys.get(&account.name) {
+                account.restore_spend_private_key(spend_key.to_vec());
+            }
+        }
+        for (name, tx_hash, index) in deferred {
+            let key_image = self.get_account(&name)?.output_key_image(&tx_hash, index);
+            if let Some(utxo) = self.utxos.get_mut(&name)
+                .and_then(|utxos| utxos.iter_mut().find(|u| u.tx_hash == tx_hash && u.index == index))
+            {
+                utxo.key_image = key_image;
+            }
+        }
+        Ok(())
+    }
+
+    /// Locks the wallet if the auto-lock timeout has passed
+    ///
+    /// # Returns
+    /// Whether the wallet was locked by this call
+    pub fn enforce_auto_lock(&mut self) -> Result<bool> {
+        let expired = self.lock.as_ref().is_some_and(|lock| lock.key.is_some() && lock.expired());
+        if expired {
+            self.lock()?;
+        }
+        Ok(expired)
+    }
+
+    /// Fails with `WalletLocked` if spending needs an unlock first
+    pub(crate) fn ensure_unlocked(&self) -> Result<()> {
+        if self.is_locked() {
+            return Err(WalletError::WalletLocked);
+        }
+        Ok(())
+    }
+
+    /// Records an output credited while the spend keys are wiped
+    ///
+    /// # Returns
+    /// Whether its key image has to wait for an unlock
+    pub(crate) fn defer_key_image(&mut self, account_name: &str, tx_hash: Hash, index: u32) -> bool {
+        match self.lock.as_mut() {
+            Some(lock) if lock.key.is_none() => {
+                lock.deferred.push((account_name.to_string(), tx_hash, index));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn passphrase_lock(&mut self) -> Result<&mut WalletLock> {
+        self.lock.as_mut()
+            .ok_or_else(|| WalletError::KeystoreError("Wallet has no passphrase".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::{RingMember, Utxo};
+
+    fn funded_wallet() -> (Wallet, String) {
+        let mut wallet = Wallet::with_default_account();
+        let account = wallet.get_active_account().unwrap().clone();
+        let utxo = Utxo {
+            tx_hash: [1u8; 32],
+            index: 0,
+            amount: 2000,
+            key_image: [2u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
+        };
+        wallet.add_utxo(&account.name, utxo).unwrap();
+        (wallet, account.address.to_string())
+    }
+
+    #[test]
+    fn test_locked_wallet_cannot_sign() {
+        let (mut wallet, address) = funded_wallet();
+        let spend_key = wallet.get_active_account().unwrap().spend_private_key().to_vec();
+        assert!(matches!(wallet.lock(), Err(WalletError::KeystoreError(_))));
+
+        wallet.set_passphrase("hunter2").unwrap();
+        wallet.lock().unwrap();
+        assert!(wallet.is_locked());
+        assert!(wallet.get_active_account().unwrap().spend_private_key().is_empty());
+        assert!(matches!(wallet.build_transaction(&address, 1000, 0), Err(WalletError::WalletLocked)));
+        assert!(matches!(wallet.create_backup(2, 3), Err(WalletError::WalletLocked)));
+
+        assert!(matches!(wallet.unlock("wrong"), Err(WalletError::InvalidPassword)));
+        assert!(wallet.is_locked());
+
+        wallet.unlock("hunter2").unwrap();
+        assert!(!wallet.is_locked());
+        assert_eq!(wallet.get_active_account().unwrap().spend_private_key(), spend_key.as_slice());
+        assert!(wallet.build_transaction(&address, 1000, 0).is_ok());
+    }
+
+    #[test]
+    fn test_auto_lock_timeout() {
+        let (mut wallet, address) = funded_wallet();
+        wallet.set_passphrase("pass").unwrap();
+        assert!(!wallet.enforce_auto_lock().unwrap());
+
+        wallet.set_auto_lock(Some(Duration::ZERO)).unwrap();
+        assert!(wallet.is_locked());
+        assert!(matches!(wallet.build_transaction(&address, 1000, 0), Err(WalletError::WalletLocked)));
+
+        assert!(wallet.enforce_auto_lock().unwrap());
+        assert!(wallet.get_active_account().unwrap().spend_private_key().is_empty());
+
+        wallet.set_auto_lock(None).unwrap();
+        wallet.unlock("pass").unwrap();
+        assert!(!wallet.is_locked());
+    }
+
+    #[test]
+    fn test_outputs_credited_while_locked_get_key_images_on_unlock() {
+        let mut wallet = Wallet::with_default_account();
+        wallet.set_passphrase("pass").unwrap();
+        wallet.lock().unwrap();
+
+        wallet.credit_minted([7u8; 32], 0, 500).unwrap();
+        assert_eq!(wallet.get_balance(), 500);
+
+        wallet.unlock("pass").unwrap();
+        let account = wallet.get_active_account().unwrap();
+        let expected = account.output_key_image(&[7u8; 32], 0);
+        assert_eq!(wallet.get_utxos(&account.name)[0].key_image, expected);
+    }
+}
@@ -0,0 +1,124 @@
+// src/main.rs
+
+//! Nyx wallet command line tool.
+//!
+//! Commands other than `change-password` are run by a `nyx-walletd` daemon
+//! that has a wallet open.
+
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use nyx_wallet::daemon::{self, DaemonClient, COOKIE_FILE, DEFAULT_DAEMON_PORT};
+use nyx_wallet::{Keystore, Wallet};
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+fn cli() -> Command {
+    Command::new("nyx-wallet")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Nyx wallet tool")
+        .subcommand_required(true)
+        .arg(Arg::new("daemon").long("daemon").global(true).help("Address of nyx-walletd, 127.0.0.1:18091 by default"))
+        .arg(Arg::new("cookie").long("cookie").global(true).help("Token cookie of nyx-walletd"))
+        .subcommand(
+            Command::new("change-password")
+                .about("Re-encrypts a keystore under a new password; passwords are read from stdin")
+                .arg(Arg::new("keystore").required(true).help("Keystore file")),
+        )
+        .subcommand(Command::new("balance").about("Shows the balance of the daemon's open wallet"))
+        .subcommand(
+            Command::new("send")
+                .about("Pays an address from the daemon's open wallet")
+                .arg(Arg::new("address").required(true).help("Recipient address"))
+                .arg(Arg::new("amount").required(true).help("Amount in atomic units"))
+                .arg(Arg::new("input").long("input").action(ArgAction::Append).help("Outpoint <tx hash>:<index> to spend; repeatable")),
+        )
+        .subcommand(Command::new("history").about("Lists payments of the daemon's open wallet"))
+        .subcommand(Command::new("outputs").about("Lists unspent outputs with their frozen state and labels"))
+        .subcommand(outpoint_command("freeze", "Keeps an output out of automatic input selection"))
+        .subcommand(outpoint_command("thaw", "Makes a frozen output selectable again"))
+        .subcommand(
+            outpoint_command("label", "Labels an output; an empty label removes it")
+                .arg(Arg::new("label").required(true).help("Label")),
+        )
+}
+
+fn outpoint_command(name: &'static str, about: &'static str) -> Command {
+    Command::new(name)
+        .about(about)
+        .arg(Arg::new("outpoint").required(true).help("Output as <tx hash>:<index>"))
+}
+
+fn main() -> ExitCode {
+    let result = match cli().get_matches().subcommand() {
+        Some(("change-password", args)) => {
+            let keystore = args.get_one::<String>("keystore").expect("required argument");
+            change_password(keystore)
+        }
+        Some((method @ ("balance" | "history" | "outputs"), args)) => {
+            call_daemon(args, method, Value::Null)
+        }
+        Some((method @ ("freeze" | "thaw" | "label"), args)) => {
+            let outpoint = args.get_one::<String>("outpoint").expect("required argument");
+            let label = args.try_get_one::<String>("label").ok().flatten();
+            call_daemon(args, method, serde_json::json!({ "outpoint": outpoint, "label": label }))
+        }
+        Some(("send", args)) => {
+            let address = args.get_one::<String>("address").expect("required argument");
+            let amount = args.get_one::<String>("amount").expect("required argument");
+            match amount.parse::<u64>() {
+                Ok(amount) => {
+                    let inputs: Vec<&String> = args.get_many::<String>("input").into_iter().flatten().collect();
+                    call_daemon(args, "send", serde_json::json!({ "address": address, "amount": amount, "inputs": inputs }))
+                }
+                Err(e) => Err(format!("Invalid amount: {}", e).into()),
+            }
+        }
+        _ => unreachable!("subcommand is required"),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn change_password(keystore: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut lines = io::stdin().lock().lines();
+    let mut prompt = |label: &str| -> io::Result<String> {
+        eprint!("{}: ", label);
+        io::stderr().flush()?;
+        lines.next().unwrap_or_else(|| Err(io::ErrorKind::UnexpectedEof.into()))
+    };
+
+    let old_password = prompt("Current password")?;
+    let new_password = prompt("New password")?;
+    if prompt("Repeat new password")? != new_password {
+        return Err("New passwords do not match".into());
+    }
+
+    Wallet::change_keystore_password(keystore, &old_password, &new_password)?;
+    eprintln!("Password changed");
+    Ok(())
+}
+
+/// Runs a method on the daemon and prints its result
+fn call_daemon(args: &ArgMatches, method: &str, params: Value) -> Result<(), Box<dyn std::error::Error>> {
+    let addr: SocketAddr = match args.get_one::<String>("daemon") {
+        Some(addr) => addr.parse()?,
+        None => SocketAddr::from(([127, 0, 0, 1], DEFAULT_DAEMON_PORT)),
+    };
+    let cookie = match args.get_one::<String>("cookie") {
+        Some(cookie) => PathBuf::from(cookie),
+        None => Keystore::default_directory()?.join(COOKIE_FILE),
+    };
+
+    let mut client = DaemonClient::connect(addr, &daemon::read_cookie(&cookie)?)?;
+    let result = client.call(method, params)?;
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    Ok(())
+}
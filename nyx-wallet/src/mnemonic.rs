@@ -0,0 +1,164 @@
+// src/mnemonic.rs
+
+//! Recovery phrases for deterministic accounts.
+//!
+//! A [`Mnemonic`] is 32 bytes of entropy written as 33 words: one word per
+//! byte from a fixed 256-word list, then a checksum word. The account's
+//! view and spend keys are derived from the entropy (see
+//! [`Account::from_mnemonic`](crate::Account::from_mnemonic)), so writing
+//! the phrase down once is enough to restore the account on any device.
+
+use crate::errors::{WalletError, Result};
+use nyx_crypto::hash::{blake3_hash, hash_chunks};
+use rand::RngCore;
+use std::fmt;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Bytes of entropy behind a phrase
+pub const ENTROPY_SIZE: usize = 32;
+
+/// Words in a phrase, including the checksum word
+pub const PHRASE_WORDS: usize = ENTROPY_SIZE + 1;
+
+/// Secret recovery phrase
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct Mnemonic {
+    entropy: [u8; ENTROPY_SIZE],
+}
+
+impl Mnemonic {
+    /// Generates a phrase from system randomness
+    pub fn generate() -> Self {
+        let mut entropy = [0u8; ENTROPY_SIZE];
+        rand::thread_rng().fill_bytes(&mut entropy);
+        Self { entropy }
+    }
+
+    /// Creates a phrase for the given entropy
+    pub fn from_entropy(entropy: [u8; ENTROPY_SIZE]) -> Self {
+        Self { entropy }
+    }
+
+    /// Parses a phrase, ignoring case and extra whitespace
+    ///
+    /// # Errors
+    /// - `InvalidMnemonic` if a word is unknown, the phrase has the wrong
+    ///   length, or the checksum word does not match
+    pub fn from_phrase(phrase: &str) -> Result<Self> {
+        let mut indices = Vec::with_capacity(PHRASE_WORDS);
+        for word in phrase.split_whitespace() {
+            let word = word.to_ascii_lowercase();
+            let index = WORDS.binary_search(&word.as_str())
+                .map_err(|_| WalletError::InvalidMnemonic(format!("Unknown word '{}'", word)))?;
+            indices.push(index as u8);
+        }
+        if indices.len() != PHRASE_WORDS {
+            return Err(WalletError::InvalidMnemonic(format!(
+                "Expected {} words, got {}", PHRASE_WORDS, indices.len()
+            )));
+        }
+
+        let mut entropy = [0u8; ENTROPY_SIZE];
+        entropy.copy_from_slice(&indices[..ENTROPY_SIZE]);
+        let checksum = indices[ENTROPY_SIZE];
+        indices.zeroize();
+
+        let mnemonic = Self { entropy };
+        if checksum != mnemonic.checksum() {
+            return Err(WalletError::InvalidMnemonic("Checksum word does not match".to_string()));
+        }
+        Ok(mnemonic)
+    }
+
+    /// Writes the phrase as space-separated words
+    pub fn phrase(&self) -> String {
+        let mut words: Vec<&str> = self.entropy.iter().map(|b| WORDS[*b as usize]).collect();
+        words.push(WORDS[self.checksum() as usize]);
+        words.join(" ")
+    }
+
+    /// Derives the 32-byte seed of one of the account's keys
+    pub(crate) fn derive_seed(&self, purpose: &[u8]) -> [u8; 32] {
+        hash_chunks(&[b"nyx-mnemonic", purpose, &self.entropy])
+    }
+
+    fn checksum(&self) -> u8 {
+        blake3_hash(&self.entropy)[0]
+    }
+}
+
+impl fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Mnemonic([REDACTED])")
+    }
+}
+
+/// Word list, sorted so lookups can binary search
+const WORDS: [&str; 256] = [
+    "acid", "acorn", "actor", "admit", "adult", "agent", "alarm", "album", "alert", "alpha",
+    "amber", "anchor", "angle", "ankle", "apple", "arena", "armor", "arrow", "artist", "aspen",
+    "atlas", "audio", "autumn", "avocado", "bacon", "badge", "bagel", "bamboo", "banjo",
+    "barley", "basil", "basket", "beach", "beaver", "berry", "bicycle", "bishop", "blanket",
+    "blossom", "bonus", "border", "bottle", "bracket", "breeze", "brick", "bronze", "brush",
+    "bubble", "bucket", "budget", "buffalo", "butter", "cabin", "cable", "cactus", "camel",
+    "canal", "canvas", "canyon", "captain", "carbon", "carpet", "castle", "cedar", "cello",
+    "cement", "cherry", "chess", "chimney", "cinema", "circle", "citrus", "clay", "cliff",
+    "clock", "clover", "coconut", "coffee", "comet", "copper", "coral", "cousin", "coyote",
+    "crane", "crater", "cricket", "crystal", "curtain", "cycle", "dance", "dawn", "delta",
+    "denim", "diamond", "dinner", "dolphin", "domain", "donkey", "dragon", "dream", "drum",
+    "eagle", "earth", "echo", "eclipse", "ember", "engine", "equator", "fabric", "falcon",
+    "ferry", "fiddle", "finch", "flame", "flannel", "forest", "fossil", "fox", "frost",
+    "galaxy", "garden", "garlic", "gazelle", "glacier", "globe", "goose", "granite", "grape",
+    "gravel", "hammer", "harbor", "harvest", "hazel", "helmet", "heron", "honey", "horizon",
+    "hotel", "iceberg", "igloo", "island", "jacket", "jaguar", "jasmine", "jelly", "jungle",
+    "kayak", "kettle", "kitten", "koala", "ladder", "lagoon", "lantern", "lava", "lemon",
+    "lentil", "library", "lily", "linen", "lobster", "locket", "lotus", "magnet", "mango",
+    "maple", "meadow", "melon", "mercury", "meteor", "mirror", "mitten", "mosaic", "motor",
+    "muffin", "museum", "napkin", "nectar", "nickel", "noodle", "nutmeg", "oak", "oasis",
+    "ocean", "olive", "onion", "orbit", "orchid", "otter", "owl", "paddle", "palace", "panda",
+    "paper", "parrot", "pasta", "pebble", "pelican", "pepper", "piano", "pigeon", "pillow",
+    "planet", "plaza", "pocket", "pony", "poppy", "prairie", "quartz", "quill", "rabbit",
+    "radar", "radish", "raven", "river", "robin", "rocket", "saddle", "salmon", "sandal",
+    "scarf", "shadow", "shell", "silver", "sketch", "sleet", "spider", "sponge", "spruce",
+    "squid", "starfish", "stone", "summit", "sunset", "swan", "tango", "teapot", "thistle",
+    "tiger", "timber", "tomato", "topaz", "tornado", "tulip", "tunnel", "turtle", "umbrella",
+    "valley", "velvet", "violin", "volcano", "walnut", "walrus", "willow", "window", "winter",
+    "zebra",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_list_is_sorted_and_unique() {
+        assert!(WORDS.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_phrase_roundtrip() {
+        let mnemonic = Mnemonic::generate();
+        let phrase = mnemonic.phrase();
+        assert_eq!(phrase.split(' ').count(), PHRASE_WORDS);
+        assert_eq!(Mnemonic::from_phrase(&phrase).unwrap(), mnemonic);
+
+        let shouted = format!("  {}\n", phrase.to_uppercase().replace(' ', "   "));
+        assert_eq!(Mnemonic::from_phrase(&shouted).unwrap(), mnemonic);
+    }
+
+    #[test]
+    fn test_invalid_phrases_rejected() {
+        let phrase = Mnemonic::from_entropy([0u8; ENTROPY_SIZE]).phrase();
+        let words: Vec<&str> = phrase.split(' ').collect();
+
+        let short = words[1..].join(" ");
+        assert!(matches!(Mnemonic::from_phrase(&short), Err(WalletError::InvalidMnemonic(_))));
+
+        let unknown = phrase.replacen(words[0], "nyxcoin", 1);
+        assert!(matches!(Mnemonic::from_phrase(&unknown), Err(WalletError::InvalidMnemonic(_))));
+
+        let mut swapped = words.clone();
+        swapped[0] = WORDS[1];
+        assert!(matches!(Mnemonic::from_phrase(&swapped.join(" ")), Err(WalletError::InvalidMnemonic(_))));
+    }
+}
@@ -0,0 +1,218 @@
+// src/proof.rs
+
+//! Payment proofs for dispute resolution.
+//!
+//! Outputs are unlinkable, so an outsider cannot tell who a transaction
+//! paid. A [`PaymentProof`] discloses the secret of a single output, which
+//! lets anyone holding the transaction check that the output pays a given
+//! address, and read its amount, without learning anything about the
+//! parties' other outputs.
+//!
+//! Two secrets can be disclosed:
+//! - the sender discloses the output's **transaction key** (the randomness
+//!   `r` its stealth address was derived from). The verifier recomputes
+//!   `R = rG` and the shared secret `rA`, so the proof also shows the
+//!   recipient can detect the output.
+//! - the recipient discloses the **shared secret** `aR`. This shows the
+//!   output pays the address's spend key, but not how it was derived.
+
+use crate::account::Address;
+use crate::amount;
+use crate::errors::{WalletError, Result};
+use nyx_core::transaction::Transaction;
+use nyx_core::Hash;
+use nyx_crypto::{ct, stealth};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Prefix of an encoded payment proof
+const PROOF_PREFIX: &str = "nyxpay";
+
+/// Secret disclosed by a payment proof
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PaymentSecret {
+    /// Randomness the sender derived the output from
+    TxKey(Vec<u8>),
+
+    /// Shared secret `aR`, known to the recipient
+    SharedSecret(Vec<u8>),
+}
+
+/// Proof that an output was sent to an address
+///
+/// Encoded as `nyxpay:<tx hash>:<output index>:<txkey|secret>:<secret>`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PaymentProof {
+    /// Transaction holding the output
+    pub tx_hash: Hash,
+
+    /// Index of the output
+    pub output_index: u32,
+
+    /// Disclosed secret of the output
+    pub secret: PaymentSecret,
+}
+
+/// A verified payment
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedPayment {
+    /// Address paid
+    pub address: Address,
+
+    /// Amount paid, if the transaction carries a valid amount hint for the
+    /// output
+    pub amount: Option<u64>,
+}
+
+impl PaymentProof {
+    /// Parses a proof from its string encoding
+    pub fn from_string(s: &str) -> Result<Self> {
+        let invalid = |msg: &str| WalletError::InvalidProof(msg.to_string());
+
+        let parts: Vec<&str> = s.split(':').collect();
+        if parts.len() != 5 || parts[0] != PROOF_PREFIX {
+            return Err(invalid("Invalid proof format"));
+        }
+
+        let tx_hash = hex::decode(parts[1]).ok()
+            .and_then(|bytes| Hash::try_from(bytes).ok())
+            .ok_or_else(|| invalid("Invalid transaction hash"))?;
+        let output_index = parts[2].parse()
+            .map_err(|_| invalid("Invalid output index"))?;
+        let secret = hex::decode(parts[4])
+            .map_err(|_| invalid("Invalid secret"))?;
+        let secret = match parts[3] {
+            "txkey" => PaymentSecret::TxKey(secret),
+            "secret" => PaymentSecret::SharedSecret(secret),
+            kind => return Err(invalid(&format!("Unknown secret kind '{}'", kind))),
+        };
+
+        Ok(Self { tx_hash, output_index, secret })
+    }
+}
+
+impl fmt::Display for PaymentProof {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, secret) = match &self.secret {
+            PaymentSecret::TxKey(key) => ("txkey", key),
+            PaymentSecret::SharedSecret(secret) => ("secret", secret),
+        };
+        write!(
+            f,
+            "{}:{}:{}:{}:{}",
+            PROOF_PREFIX,
+            hex::encode(self.tx_hash),
+            self.output_index,
+            kind,
+            hex::encode(secret)
+        )
+    }
+}
+
+/// Verifies that a proof's output pays `address`
+///
+/// Needs nothing but the transaction and the proof, so any party to a
+/// dispute can run it.
+///
+/// # Errors
+/// - `InvalidProof` if the proof is for another transaction, names a
+///   missing output, or does not show the output pays `address`
+pub fn verify_payment(tx: &Transaction, proof: &PaymentProof, address: &Address) -> Result<VerifiedPayment> {
+    let invalid = |msg: &str| WalletError::InvalidProof(msg.to_string());
+
+    if tx.id() != proof.tx_hash {
+        return Err(invalid("Proof is for another transaction"));
+    }
+    if tx.network != address.network {
+        return Err(invalid("Address is for another network"));
+    }
+    let output = tx.outputs.get(proof.output_index as usize)
+        .ok_or_else(|| invalid("No such output"))?;
+
+    let shared_secret = match &proof.secret {
+        PaymentSecret::TxKey(key) => {
+            if !ct::ct_eq(&stealth::derive_ephemeral_public(key), &output.ephemeral_pubkey) {
+                return Err(invalid("Transaction key does not match the output"));
+            }
+            stealth::derive_sender_shared_secret(&address.view_public, key)
+                .map_err(|e| WalletError::InvalidProof(e.to_string()))?
+        }
+        PaymentSecret::SharedSecret(secret) => secret.clone(),
+    };
+
+    let stealth_address = stealth::stealth_address_from_secret(&shared_secret, &address.spend_public)
+        .map_err(|e| WalletError::InvalidProof(e.to_string()))?;
+    if !ct::ct_eq(&stealth_address, &output.stealth_address) {
+        return Err(invalid("Output does not pay the address"));
+    }
+
+    Ok(VerifiedPayment {
+        address: address.clone(),
+        amount: amount::recover_amount_with_secret(tx, proof.output_index, &shared_secret),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::Account;
+    use crate::builder::{RingMember, TransactionBuilder, Utxo};
+
+    /// Pays `recipient`, returning the index of the paying output
+    fn payment(sender: &Account, recipient: &Account, amount: u64) -> (Transaction, Vec<Vec<u8>>, u32) {
+        let (tx, keys) = TransactionBuilder::new()
+            .sender(sender.clone())
+            .add_input(Utxo {
+                tx_hash: [1u8; 32],
+                index: 0,
+                amount,
+                key_image: [2u8; 32],
+                unlock_time: None,
+                mask: [0u8; 32],
+                ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
+            })
+            .add_output(recipient.view_public_key().to_vec(), recipient.spend_public_key().to_vec(), amount)
+            .build_with_tx_keys([0u8; 32], [1u8; 32])
+            .unwrap();
+        let index = (0..tx.outputs.len() as u32)
+            .find(|&index| crate::amount::recover_amount(&tx, index, recipient.view_private_key()).is_some())
+            .unwrap();
+        (tx, keys, index)
+    }
+
+    #[test]
+    fn test_sender_proof_verifies() {
+        let (sender, recipient) = (Account::generate(), Account::generate());
+        let (tx, keys, index) = payment(&sender, &recipient, 750);
+        let proof = PaymentProof { tx_hash: tx.id(), output_index: index, secret: PaymentSecret::TxKey(keys[index as usize].clone()) };
+
+        let verified = verify_payment(&tx, &proof, recipient.get_address()).unwrap();
+        assert_eq!(verified.amount, Some(750));
+
+        // Not an output to anyone else, nor under another key
+        assert!(verify_payment(&tx, &proof, Account::generate().get_address()).is_err());
+        let forged = PaymentProof { secret: PaymentSecret::TxKey(vec![9u8; 32]), ..proof };
+        assert!(verify_payment(&tx, &forged, recipient.get_address()).is_err());
+    }
+
+    #[test]
+    fn test_recipient_proof_verifies() {
+        let (sender, recipient) = (Account::generate(), Account::generate());
+        let (tx, _, index) = payment(&sender, &recipient, 320);
+        let secret = stealth::derive_shared_secret(recipient.view_private_key(), &tx.outputs[index as usize].ephemeral_pubkey).unwrap();
+        let proof = PaymentProof { tx_hash: tx.id(), output_index: index, secret: PaymentSecret::SharedSecret(secret) };
+
+        assert_eq!(verify_payment(&tx, &proof, recipient.get_address()).unwrap().amount, Some(320));
+        assert!(verify_payment(&tx, &PaymentProof { output_index: 1 - index, ..proof.clone() }, recipient.get_address()).is_err());
+        assert!(verify_payment(&tx, &PaymentProof { tx_hash: [0u8; 32], ..proof }, recipient.get_address()).is_err());
+    }
+
+    #[test]
+    fn test_proof_string_roundtrip() {
+        let proof = PaymentProof { tx_hash: [4u8; 32], output_index: 3, secret: PaymentSecret::TxKey(vec![5u8; 32]) };
+        assert_eq!(PaymentProof::from_string(&proof.to_string()).unwrap(), proof);
+
+        assert!(PaymentProof::from_string("nyxpay:00:0:txkey:00").is_err());
+        assert!(PaymentProof::from_string(&proof.to_string().replace("txkey", "view")).is_err());
+    }
+}
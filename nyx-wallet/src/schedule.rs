@@ -0,0 +1,244 @@
+// src/schedule.rs
+
+//! Scheduled and recurring payments.
+//!
+//! A [`Schedule`] holds payments the daemon makes on its own: one-off
+//! payments due at a set time, and recurring ones repeating at a fixed
+//! interval. It is saved next to the keystore it belongs to (see
+//! [`schedule_path`]), so payments survive daemon restarts and run again
+//! once the wallet is reopened.
+//!
+//! A recurring payment missed while the wallet was closed is made once, not
+//! once per missed interval; the next one falls due a whole interval later.
+
+use crate::errors::{WalletError, Result};
+use nyx_core::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Shortest interval between recurring payments, in seconds
+pub const MIN_INTERVAL_SECS: u64 = 60;
+
+/// Most payments one schedule holds
+pub const MAX_SCHEDULED_PAYMENTS: usize = 256;
+
+/// Payment the daemon makes on its own
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledPayment {
+    /// Identifier, unique within its schedule
+    pub id: u64,
+
+    /// Recipient address
+    pub address: String,
+
+    /// Amount paid each time
+    pub amount: u64,
+
+    /// Seconds between payments; `None` for a one-off payment
+    pub interval_secs: Option<u64>,
+
+    /// Unix time the next payment falls due
+    pub next_due: Timestamp,
+
+    /// Why the last attempt failed, cleared once a payment goes out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Payments scheduled for one wallet
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schedule {
+    next_id: u64,
+    payments: Vec<ScheduledPayment>,
+}
+
+/// File a keystore's schedule is saved to, e.g. `wallet.schedule.json`
+/// for `wallet.json`
+pub fn schedule_path(keystore: &Path) -> PathBuf {
+    keystore.with_extension("schedule.json")
+}
+
+impl Schedule {
+    /// Creates an empty schedule
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a schedule, or an empty one if none was saved yet
+    ///
+    /// # Errors
+    /// - `SerializationError` if the file is not a schedule
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Saves the schedule, replacing the file atomically
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut temp_name = path.file_name()
+            .ok_or_else(|| WalletError::SerializationError(format!("Invalid schedule path: {}", path.display())))?
+            .to_os_string();
+        temp_name.push(".tmp");
+        let temp_path = path.with_file_name(temp_name);
+
+        fs::write(&temp_path, serde_json::to_string_pretty(self)?)?;
+        if let Err(e) = fs::rename(&temp_path, path) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Schedules a payment, returning its identifier
+    ///
+    /// # Arguments
+    /// * `first_due` - Unix time of the first payment
+    /// * `interval_secs` - Seconds between payments, or `None` to pay once
+    ///
+    /// # Errors
+    /// - `InvalidAmount` for a zero amount
+    /// - `TransactionBuildError` for an interval under [`MIN_INTERVAL_SECS`]
+    ///   or a full schedule
+    pub fn add(&mut self, address: &str, amount: u64, first_due: Timestamp, interval_secs: Option<u64>) -> Result<u64> {
+        if amount == 0 {
+            return Err(WalletError::InvalidAmount("Scheduled amount must be positive".to_string()));
+        }
+        if interval_secs.is_some_and(|interval| interval < MIN_INTERVAL_SECS) {
+            return Err(WalletError::TransactionBuildError(format!(
+                "Interval must be at least {} seconds",
+                MIN_INTERVAL_SECS
+            )));
+        }
+        if self.payments.len() >= MAX_SCHEDULED_PAYMENTS {
+            return Err(WalletError::TransactionBuildError(format!(
+                "At most {} payments can be scheduled",
+                MAX_SCHEDULED_PAYMENTS
+            )));
+        }
+
+        self.next_id += 1;
+        self.payments.push(ScheduledPayment {
+            id: self.next_id,
+            address: address.to_string(),
+            amount,
+            interval_secs,
+            next_due: first_due,
+            last_error: None,
+        });
+        Ok(self.next_id)
+    }
+
+    /// Cancels a payment, returning whether it was scheduled
+    pub fn cancel(&mut self, id: u64) -> bool {
+        let before = self.payments.len();
+        self.payments.retain(|payment| payment.id != id);
+        self.payments.len() != before
+    }
+
+    /// Scheduled payments, in the order they were added
+    pub fn payments(&self) -> &[ScheduledPayment] {
+        &self.payments
+    }
+
+    /// Payments due at `now`
+    pub fn due(&self, now: Timestamp) -> Vec<ScheduledPayment> {
+        self.payments.iter().filter(|payment| payment.next_due <= now).cloned().collect()
+    }
+
+    /// Records that payment `id` went out at `now`
+    ///
+    /// A one-off payment is removed; a recurring one falls due again at the
+    /// first interval boundary after `now`.
+    pub fn complete(&mut self, id: u64, now: Timestamp) {
+        let Some(position) = self.payments.iter().position(|payment| payment.id == id) else {
+            return;
+        };
+        let payment = &mut self.payments[position];
+        match payment.interval_secs {
+            Some(interval) => {
+                let missed = now.saturating_sub(payment.next_due) / interval;
+                payment.next_due = payment.next_due.saturating_add((missed + 1).saturating_mul(interval));
+                payment.last_error = None;
+            }
+            None => {
+                self.payments.remove(position);
+            }
+        }
+    }
+
+    /// Records why payment `id` failed; it stays due and is retried
+    ///
+    /// # Returns
+    /// Whether the error differs from the previous attempt's, so callers
+    /// notify once per distinct failure rather than on every retry
+    pub fn fail(&mut self, id: u64, error: &str) -> bool {
+        let Some(payment) = self.payments.iter_mut().find(|payment| payment.id == id) else {
+            return false;
+        };
+        if payment.last_error.as_deref() == Some(error) {
+            return false;
+        }
+        payment.last_error = Some(error.to_string());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recurring_payment_skips_missed_intervals() {
+        let mut schedule = Schedule::new();
+        let monthly = schedule.add("addr", 100, 1_000, Some(600)).unwrap();
+        let once = schedule.add("addr", 5, 1_500, None).unwrap();
+
+        assert!(schedule.due(999).is_empty());
+        assert_eq!(schedule.due(1_000).len(), 1);
+
+        // Paid late, after two more intervals passed: due once, then next boundary
+        schedule.complete(monthly, 2_300);
+        assert_eq!(schedule.payments()[0].next_due, 2_800);
+
+        schedule.complete(once, 2_300);
+        assert!(!schedule.cancel(once));
+        assert_eq!(schedule.payments().len(), 1);
+    }
+
+    #[test]
+    fn test_failures_notify_once_until_paid() {
+        let mut schedule = Schedule::new();
+        let id = schedule.add("addr", 100, 0, Some(MIN_INTERVAL_SECS)).unwrap();
+        assert!(schedule.fail(id, "Insufficient balance"));
+        assert!(!schedule.fail(id, "Insufficient balance"));
+        assert_eq!(schedule.due(10).len(), 1);
+
+        schedule.complete(id, 10);
+        assert_eq!(schedule.payments()[0].last_error, None);
+        assert!(schedule.fail(id, "Insufficient balance"));
+
+        assert!(schedule.add("addr", 0, 0, None).is_err());
+        assert!(schedule.add("addr", 1, 0, Some(1)).is_err());
+    }
+
+    #[test]
+    fn test_schedule_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = schedule_path(&dir.path().join("wallet.json"));
+        assert_eq!(path, dir.path().join("wallet.schedule.json"));
+        assert_eq!(Schedule::load(&path).unwrap(), Schedule::new());
+
+        let mut schedule = Schedule::new();
+        schedule.add("addr", 100, 1_000, Some(3_600)).unwrap();
+        schedule.save(&path).unwrap();
+        let mut loaded = Schedule::load(&path).unwrap();
+        assert_eq!(loaded, schedule);
+
+        // Identifiers are not reused after a reload
+        assert_eq!(loaded.add("addr", 1, 0, None).unwrap(), 2);
+    }
+}
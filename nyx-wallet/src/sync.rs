@@ -0,0 +1,470 @@
+// src/sync.rs
+
+//! Background wallet synchronization.
+//!
+//! [`AutoSync`] polls a [`TransactionSource`] (normally a node's RPC
+//! endpoint) on a background thread, credits outputs paying any account
+//! of the wallet and reports what changed as [`WalletEvent`]s, so
+//! applications do not need their own scanner loops.
+
+use crate::errors::{Result, WalletError};
+use crate::wallet::Wallet;
+use nyx_core::transaction::Transaction;
+use nyx_core::{Hash, Timestamp};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Maximum number of transactions requested per poll
+pub const SYNC_BATCH_SIZE: usize = 500;
+
+/// Timeout for a single request to the node
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Supplier of transactions to scan
+pub trait TransactionSource: Send + 'static {
+    /// Fetches transactions created at or after `from`, oldest first
+    ///
+    /// At most `limit` transactions are returned. Transactions at `from`
+    /// may have been returned by an earlier call; the caller skips them.
+    fn fetch(&mut self, from: Timestamp, limit: usize) -> Result<Vec<Transaction>>;
+}
+
+/// Transaction source backed by a node's `/wallet/transactions` endpoint
+#[derive(Clone, Debug)]
+pub struct RpcSource {
+    /// `host:port` of the node's RPC server
+    host: String,
+}
+
+impl RpcSource {
+    /// Creates a source for a node endpoint such as `http://127.0.0.1:8080`
+    pub fn new(node_endpoint: &str) -> Result<Self> {
+        let host = node_endpoint.strip_prefix("http://").unwrap_or(node_endpoint);
+        let host = host.trim_end_matches('/');
+        if host.is_empty() || host.contains('/') || node_endpoint.starts_with("https://") {
+            return Err(WalletError::SyncError(format!(
+                "Unsupported node endpoint: {}", node_endpoint
+            )));
+        }
+
+        Ok(Self { host: host.to_string() })
+    }
+
+    /// Fetches `path` from the node and parses the JSON response
+    pub fn get_json(&self, path: &str) -> Result<serde_json::Value> {
+        self.call("GET", path, None)
+    }
+
+    /// Posts `body` to `path` on the node and parses the JSON response
+    pub fn post_json(&self, path: &str, body: &serde_json::Value) -> Result<serde_json::Value> {
+        self.call("POST", path, Some(body.to_string().as_bytes()))
+    }
+
+    fn call<T: serde::de::DeserializeOwned>(&self, method: &str, path: &str, body: Option<&[u8]>) -> Result<T> {
+        let (status, response) = self.request(method, path, body)
+            .map_err(|e| WalletError::SyncError(format!("{}: {}", self.host, e)))?;
+        if status != 200 {
+            return Err(WalletError::SyncError(format!(
+                "{} returned status {}: {}", self.host, status, String::from_utf8_lossy(&response)
+            )));
+        }
+
+        serde_json::from_slice(&response)
+            .map_err(|e| WalletError::SyncError(format!("Invalid response: {}", e)))
+    }
+
+    fn request(&self, method: &str, path: &str, body: Option<&[u8]>) -> std::io::Result<(u16, Vec<u8>)> {
+        let mut stream = TcpStream::connect(&self.host)?;
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+        let mut request = format!("{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n", method, path, self.host);
+        if let Some(body) = body {
+            request.push_str(&format!("Content-Type: application/json\r\nContent-Length: {}\r\n", body.len()));
+        }
+        request.push_str("\r\n");
+        let mut request = request.into_bytes();
+        request.extend_from_slice(body.unwrap_or_default());
+        stream.write_all(&request)?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+
+        let invalid = || std::io::Error::new(std::io::ErrorKind::InvalidData, "malformed HTTP response");
+        let split = response.windows(4).position(|w| w == b"\r\n\r\n").ok_or_else(invalid)?;
+        let status = std::str::from_utf8(&response[..split]).ok()
+            .and_then(|head| head.split_whitespace().nth(1))
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(invalid)?;
+        Ok((status, response[split + 4..].to_vec()))
+    }
+}
+
+impl TransactionSource for RpcSource {
+    fn fetch(&mut self, from: Timestamp, limit: usize) -> Result<Vec<Transaction>> {
+        self.call("GET", &format!("/wallet/transactions?from={}&limit={}", from, limit), None)
+    }
+}
+
+/// Change observed by the background sync
+#[derive(Clone, Debug, PartialEq)]
+pub enum WalletEvent {
+    /// An output paying one of the wallet's accounts was credited
+    OutputReceived {
+        /// Receiving account
+        account: String,
+        /// Transaction containing the output
+        tx_hash: Hash,
+        /// Output index within it
+        index: u32,
+        /// Credited amount
+        amount: u64,
+    },
+
+    /// An output paying one of the wallet's accounts carries an amount
+    /// hint its commitment does not match, and was not credited
+    InconsistentOutput {
+        /// Receiving account
+        account: String,
+        /// Transaction containing the output
+        tx_hash: Hash,
+        /// Output index within it
+        index: u32,
+    },
+
+    /// An account's balance changed
+    BalanceChanged {
+        /// Account name
+        account: String,
+        /// New balance
+        balance: u64,
+    },
+
+    /// Polling the source failed; sync retries on the next interval
+    SyncFailed {
+        /// Error description
+        error: String,
+    },
+}
+
+/// Handle to a running background sync
+///
+/// Dropping the handle stops the sync without waiting for it.
+pub struct AutoSync {
+    /// Stops the sync thread when signalled or dropped
+    stop: Option<Sender<()>>,
+
+    /// Sync thread
+    thread: Option<JoinHandle<()>>,
+
+    /// Events produced by the sync thread
+    events: Receiver<WalletEvent>,
+}
+
+impl AutoSync {
+    /// Starts syncing `wallet` from `source` every `interval`
+    ///
+    /// The first poll happens immediately.
+    pub fn start<S: TransactionSource>(wallet: Arc<RwLock<Wallet>>, source: S, interval: Duration) -> Self {
+        let (stop, stop_rx) = mpsc::channel();
+        let (events_tx, events) = mpsc::channel();
+
+        let thread = std::thread::spawn(move || {
+            let mut syncer = Syncer { wallet, source, cursor: Cursor::starting_at(0), events: events_tx };
+            loop {
+                syncer.poll();
+                match stop_rx.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    _ => break,
+                }
+            }
+        });
+
+        Self { stop: Some(stop), thread: Some(thread), events }
+    }
+
+    /// Events produced by the sync, in order
+    pub fn events(&self) -> &Receiver<WalletEvent> {
+        &self.events
+    }
+
+    /// Stops the sync and waits for an in-progress poll to finish
+    pub fn stop(mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Position in the oldest-first stream of a [`TransactionSource`]
+pub(crate) struct Cursor {
+    /// Timestamp to fetch from
+    from: Timestamp,
+
+    /// Transactions already returned at the `from` timestamp
+    seen: HashSet<Hash>,
+}
+
+impl Cursor {
+    pub(crate) fn starting_at(from: Timestamp) -> Self {
+        Self { from, seen: HashSet::new() }
+    }
+
+    /// Gets the timestamp to fetch from
+    pub(crate) fn from(&self) -> Timestamp {
+        self.from
+    }
+
+    /// Moves past a fetched transaction
+    ///
+    /// # Returns
+    /// `false` if the transaction was returned before and should be skipped
+    pub(crate) fn advance(&mut self, tx_hash: Hash, timestamp: Timestamp) -> bool {
+        if timestamp < self.from || !self.seen.insert(tx_hash) {
+            return false;
+        }
+        if timestamp > self.from {
+            self.from = timestamp;
+            self.seen = HashSet::from([tx_hash]);
+        }
+        true
+    }
+}
+
+/// State of the sync thread
+struct Syncer<S> {
+    wallet: Arc<RwLock<Wallet>>,
+    source: S,
+    cursor: Cursor,
+    events: Sender<WalletEvent>,
+}
+
+impl<S: TransactionSource> Syncer<S> {
+    /// Fetches and scans everything new since the last poll
+    fn poll(&mut self) {
+        loop {
+            let batch = match self.source.fetch(self.cursor.from(), SYNC_BATCH_SIZE) {
+                Ok(batch) => batch,
+                Err(e) => {
+                    let _ = self.events.send(WalletEvent::SyncFailed { error: e.to_string() });
+                    return;
+                }
+            };
+
+            let full = batch.len() >= SYNC_BATCH_SIZE;
+            let mut progressed = false;
+            for tx in batch {
+                let hash = tx.id();
+                if !self.cursor.advance(hash, tx.timestamp) {
+                    continue;
+                }
+                progressed = true;
+                self.scan(hash, &tx);
+            }
+
+            if !full || !progressed {
+                return;
+            }
+        }
+    }
+
+    fn scan(&mut self, hash: Hash, tx: &Transaction) {
+        let (credited, balances) = {
+            let mut wallet = self.wallet.write().unwrap_or_else(|e| e.into_inner());
+            let credited = match wallet.sync_transaction(hash, tx) {
+                Ok(credited) => credited,
+                Err(e) => {
+                    let _ = self.events.send(WalletEvent::SyncFailed { error: e.to_string() });
+                    return;
+                }
+            };
+            let balances: HashMap<String, u64> = wallet.account_balances()
+                .into_iter()
+                .map(|account| (account.name, account.balance))
+                .collect();
+            (credited, balances)
+        };
+
+        let mut changed = Vec::new();
+        for output in credited {
+            if output.inconsistent {
+                let _ = self.events.send(WalletEvent::InconsistentOutput {
+                    account: output.account,
+                    tx_hash: output.tx_hash,
+                    index: output.index,
+                });
+                continue;
+            }
+            if !changed.contains(&output.account) {
+                changed.push(output.account.clone());
+            }
+            let _ = self.events.send(WalletEvent::OutputReceived {
+                account: output.account,
+                tx_hash: output.tx_hash,
+                index: output.index,
+                amount: output.amount.unwrap_or_default(),
+            });
+        }
+        for account in changed {
+            let balance = balances.get(&account).copied().unwrap_or_default();
+            let _ = self.events.send(WalletEvent::BalanceChanged { account, balance });
+        }
+    }
+}
+
+impl Wallet {
+    /// Starts a background task keeping the wallet in sync with a node
+    ///
+    /// # Arguments
+    /// * `wallet` - Wallet to credit incoming outputs to
+    /// * `node_endpoint` - Node RPC endpoint, e.g. `http://127.0.0.1:8080`
+    /// * `interval` - Delay between polls
+    pub fn start_auto_sync(
+        wallet: &Arc<RwLock<Wallet>>,
+        node_endpoint: &str,
+        interval: Duration,
+    ) -> Result<AutoSync> {
+        let source = RpcSource::new(node_endpoint)?;
+        Ok(AutoSync::start(Arc::clone(wallet), source, interval))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::account::Account;
+    use crate::builder::{RingMember, TransactionBuilder, Utxo};
+
+    /// Serves a fixed list of transactions, filtered like the node does
+    struct FakeSource {
+        transactions: Arc<RwLock<Vec<Transaction>>>,
+    }
+
+    impl TransactionSource for FakeSource {
+        fn fetch(&mut self, from: Timestamp, limit: usize) -> Result<Vec<Transaction>> {
+            Ok(self.transactions.read().unwrap().iter()
+                .filter(|tx| tx.timestamp >= from)
+                .take(limit)
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn payment_to(account: &Account, amount: u64, timestamp: Timestamp) -> Transaction {
+        let mut tx = TransactionBuilder::new()
+            .sender(Account::generate())
+            .add_input(Utxo {
+                tx_hash: [1u8; 32],
+                index: 0,
+                amount,
+                key_image: [2u8; 32],
+                unlock_time: None,
+                mask: [0u8; 32],
+                ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
+            })
+            .add_output(account.address.view_public.clone(), account.address.spend_public.clone(), amount)
+            .build([0u8; 32], [1u8; 32])
+            .unwrap();
+        tx.timestamp = timestamp;
+        tx
+    }
+
+    fn next_event(sync: &AutoSync) -> WalletEvent {
+        sync.events().recv_timeout(Duration::from_secs(5)).unwrap()
+    }
+
+    #[test]
+    fn test_auto_sync_credits_new_transactions_once() {
+        let account = Account::generate();
+        let mut wallet = Wallet::new();
+        wallet.add_account(account.clone()).unwrap();
+        let name = wallet.list_accounts()[0].clone();
+        let wallet = Arc::new(RwLock::new(wallet));
+
+        let first = payment_to(&account, 700, 10);
+        let paid = |tx: &Transaction| wallet.read().unwrap().scan_transaction(tx.id(), tx)[0].index;
+        let index = paid(&first);
+        let transactions = Arc::new(RwLock::new(vec![first.clone(), payment_to(&Account::generate(), 5, 10)]));
+        let source = FakeSource { transactions: Arc::clone(&transactions) };
+        let sync = AutoSync::start(Arc::clone(&wallet), source, Duration::from_millis(10));
+
+        assert_eq!(next_event(&sync), WalletEvent::OutputReceived {
+            account: name.clone(), tx_hash: first.id(), index, amount: 700,
+        });
+        assert_eq!(next_event(&sync), WalletEvent::BalanceChanged { account: name.clone(), balance: 700 });
+
+        let second = payment_to(&account, 300, 20);
+        let index = paid(&second);
+        transactions.write().unwrap().push(second.clone());
+        assert_eq!(next_event(&sync), WalletEvent::OutputReceived {
+            account: name.clone(), tx_hash: second.id(), index, amount: 300,
+        });
+        assert_eq!(next_event(&sync), WalletEvent::BalanceChanged { account: name, balance: 1_000 });
+
+        sync.stop();
+        assert_eq!(wallet.read().unwrap().get_total_balance(), 1_000);
+    }
+
+    #[test]
+    fn test_auto_sync_reports_source_errors() {
+        struct Failing;
+        impl TransactionSource for Failing {
+            fn fetch(&mut self, _from: Timestamp, _limit: usize) -> Result<Vec<Transaction>> {
+                Err(WalletError::SyncError("node unreachable".to_string()))
+            }
+        }
+
+        let wallet = Arc::new(RwLock::new(Wallet::with_default_account()));
+        let sync = AutoSync::start(wallet, Failing, Duration::from_secs(60));
+        assert_eq!(next_event(&sync), WalletEvent::SyncFailed {
+            error: "Sync error: node unreachable".to_string(),
+        });
+        sync.stop();
+    }
+
+    #[test]
+    fn test_rpc_source_rejects_unsupported_endpoints() {
+        assert!(RpcSource::new("http://127.0.0.1:8080/").is_ok());
+        assert!(RpcSource::new("127.0.0.1:8080").is_ok());
+        assert!(RpcSource::new("https://node.example:443").is_err());
+        assert!(RpcSource::new("http://node.example/rpc").is_err());
+    }
+
+    #[test]
+    fn test_rpc_source_json_requests() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let source = RpcSource::new(&listener.local_addr().unwrap().to_string()).unwrap();
+
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in [("200 OK", r#"{"version":"0.1.0"}"#), ("400 Bad Request", "bad")] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = String::new();
+                let mut buf = [0u8; 4096];
+                while !request.contains("\r\n\r\n") || (request.starts_with("POST") && !request.ends_with('}')) {
+                    let n = stream.read(&mut buf).unwrap();
+                    request.push_str(&String::from_utf8_lossy(&buf[..n]));
+                }
+                requests.push(request);
+                write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\n\r\n{}", status, body.len(), body).unwrap();
+            }
+            requests
+        });
+
+        assert_eq!(source.get_json("/status").unwrap()["version"], "0.1.0");
+        assert!(matches!(
+            source.post_json("/submit", &serde_json::json!({ "transaction": "00" })),
+            Err(WalletError::SyncError(e)) if e.contains("status 400")
+        ));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /status HTTP/1.1"));
+        assert!(requests[1].starts_with("POST /submit HTTP/1.1"));
+        assert!(requests[1].ends_with(r#"{"transaction":"00"}"#));
+    }
+}
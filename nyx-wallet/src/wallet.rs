@@ -3,27 +3,73 @@
 //! Wallet implementation with balance tracking and transaction management.
 
 use crate::account::Account;
-use crate::builder::{TransactionBuilder, Utxo};
+use crate::amount;
+use crate::builder::{RingMember, TransactionBuilder, Utxo};
+use crate::change::{ChangeChain, OwnedKey};
+use crate::coin_control::{format_outpoint, Outpoint, SendOptions};
 use crate::errors::{WalletError, Result};
-use nyx_core::transaction::Transaction;
-use nyx_core::Hash;
+use crate::lock::WalletLock;
+use crate::proof::{PaymentProof, PaymentSecret};
+use nyx_core::clock::{Clock, SystemClock};
+use nyx_core::limits::MAX_TX_OUTPUTS;
+use nyx_core::extra::{Extra, TAG_HTLC_WITNESSES};
+use nyx_core::transaction::{Transaction, TxOutput, UnlockTime};
+use nyx_core::versioning::ValidationContext;
+use nyx_core::{Amount, Hash, NetworkId, DEFAULT_RING_SIZE};
+use nyx_crypto::pedersen::{self, BlindingFactor};
 use nyx_crypto::stealth;
-use std::collections::HashMap;
+use rand::seq::SliceRandom;
+use std::collections::{HashMap, HashSet};
 
 /// Main wallet structure
 #[derive(Clone)]
 pub struct Wallet {
     /// Accounts in this wallet
-    accounts: Vec<Account>,
+    pub(crate) accounts: Vec<Account>,
 
     /// Active account index
     active_account: usize,
 
     /// Mock UTXO set (in production, this would query the blockchain)
-    utxos: HashMap<String, Vec<Utxo>>,
+    pub(crate) utxos: HashMap<String, Vec<Utxo>>,
 
     /// Balance cache
     balance_cache: HashMap<String, u64>,
+
+    /// Network the wallet's addresses and transactions belong to
+    network: NetworkId,
+
+    /// Submitted transactions not yet seen by a scan
+    pending: HashMap<Hash, Transaction>,
+
+    /// Transaction keys of the outputs of sent transactions, kept for
+    /// payment proofs
+    tx_keys: HashMap<Hash, Vec<Vec<u8>>>,
+
+    /// Last known DAG height, against which height-locked UTXOs unlock
+    chain_height: u64,
+
+    /// Confirmed outputs rings of new transactions draw decoys from
+    decoys: Vec<RingMember>,
+
+    /// Passphrase protecting the spend keys, if set
+    pub(crate) lock: Option<WalletLock>,
+
+    /// Outputs automatic input selection skips
+    pub(crate) frozen: HashSet<Outpoint>,
+
+    /// Labels of outputs
+    pub(crate) labels: HashMap<Outpoint, String>,
+
+    /// Change subaddresses by account name
+    pub(crate) change: HashMap<String, ChangeChain>,
+
+    /// Owned outputs whose amount hint does not match their commitment
+    pub(crate) inconsistent: HashSet<Outpoint>,
+
+    /// Indices of watched deposit subaddresses by account name, then by
+    /// spend key
+    pub(crate) deposits: HashMap<String, HashMap<Vec<u8>, u32>>,
 }
 
 impl Wallet {
@@ -42,27 +88,96 @@ impl Wallet {
             active_account: 0,
             utxos: HashMap::new(),
             balance_cache: HashMap::new(),
+            network: NetworkId::Mainnet,
+            pending: HashMap::new(),
+            tx_keys: HashMap::new(),
+            chain_height: 0,
+            decoys: Vec::new(),
+            lock: None,
+            frozen: HashSet::new(),
+            labels: HashMap::new(),
+            change: HashMap::new(),
+            inconsistent: HashSet::new(),
+            deposits: HashMap::new(),
+        }
+    }
+
+    /// Creates an empty wallet for a specific network
+    pub fn for_network(network: NetworkId) -> Self {
+        Self {
+            network,
+            ..Self::new()
+        }
+    }
+
+    /// Gets the network the wallet operates on
+    pub fn network(&self) -> NetworkId {
+        self.network
+    }
+
+    /// Gets the last known DAG height
+    pub fn chain_height(&self) -> u64 {
+        self.chain_height
+    }
+
+    /// Sets the last known DAG height, which height-locked UTXOs unlock
+    /// against
+    pub fn set_chain_height(&mut self, height: u64) {
+        self.chain_height = height;
+    }
+
+    /// Sets the confirmed outputs, as fetched from a node, that rings of new
+    /// transactions draw decoys from
+    pub fn set_decoys(&mut self, decoys: Vec<RingMember>) {
+        self.decoys = decoys;
+    }
+
+    /// Draws decoys for the rings of a new transaction
+    pub(crate) fn pick_decoys(&self) -> Vec<RingMember> {
+        let count = usize::from(DEFAULT_RING_SIZE) - 1;
+        self.decoys.choose_multiple(&mut rand::thread_rng(), count).cloned().collect()
+    }
+
+    /// Chain position a transaction built now would be validated against
+    pub(crate) fn spend_context(&self) -> ValidationContext {
+        ValidationContext {
+            height: self.chain_height,
+            timestamp: SystemClock.now(),
         }
     }
 
     /// Creates a wallet with a default account
     pub fn with_default_account() -> Self {
-        let mut wallet = Self::new();
+        Self::new().with_generated_account()
+    }
+
+    /// Adds a freshly generated default account
+    pub fn with_generated_account(mut self) -> Self {
         let account = Account::generate();
-        wallet.add_account(account).unwrap();
-        wallet
+        self.add_account(account).unwrap();
+        self
     }
 
     /// Adds an account to the wallet
     ///
     /// # Arguments
     /// * `account` - Account to add
-    pub fn add_account(&mut self, account: Account) -> Result<()> {
+    ///
+    /// The account's address is switched to the wallet's network.
+    ///
+    /// # Errors
+    /// - `WalletLocked` if the wallet is locked
+    pub fn add_account(&mut self, mut account: Account) -> Result<()> {
+        self.ensure_unlocked()?;
+
         // Check if account already exists
         if self.accounts.iter().any(|a| a.name == account.name) {
             return Err(WalletError::AccountExists(account.name.clone()));
         }
 
+        account.address.network = self.network;
+
+        self.change.insert(account.name.clone(), ChangeChain::new(&account, 0)?);
         self.accounts.push(account);
         Ok(())
     }
@@ -143,10 +258,36 @@ impl Wallet {
         Ok(self.calculate_balance(name))
     }
 
+    /// Gets the balance across all accounts
+    ///
+    /// Saturates at the maximum amount instead of wrapping on overflow.
+    pub fn get_total_balance(&self) -> u64 {
+        Amount::checked_sum_atomic(self.accounts.iter().map(|a| self.calculate_balance(&a.name)))
+            .unwrap_or(Amount::MAX)
+            .as_atomic()
+    }
+
+    /// Gets the balance and UTXO count of every account, in account order
+    pub fn account_balances(&self) -> Vec<AccountBalance> {
+        self.accounts.iter()
+            .map(|account| AccountBalance {
+                name: account.name.clone(),
+                balance: self.calculate_balance(&account.name),
+                utxos: self.utxos.get(&account.name).map_or(0, Vec::len),
+            })
+            .collect()
+    }
+
     /// Calculates balance from UTXOs
+    ///
+    /// Saturates at the maximum amount instead of wrapping on overflow.
     fn calculate_balance(&self, account_name: &str) -> u64 {
         self.utxos.get(account_name)
-            .map(|utxos| utxos.iter().map(|u| u.amount).sum())
+            .map(|utxos| {
+                Amount::checked_sum_atomic(utxos.iter().map(|u| u.amount))
+                    .unwrap_or(Amount::MAX)
+                    .as_atomic()
+            })
             .unwrap_or(0)
     }
 
@@ -158,7 +299,7 @@ impl Wallet {
         self.get_account(account_name)?;
 
         self.utxos.entry(account_name.to_string())
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(utxo);
 
         // Invalidate cache
@@ -167,6 +308,104 @@ impl Wallet {
         Ok(())
     }
 
+    /// Builds an output paying `amount` to the active account, for minting
+    ///
+    /// Minted amounts are public, so the commitment has no blinding.
+    pub fn minted_output(&self, amount: u64) -> Result<TxOutput> {
+        let account = self.get_active_account()?;
+        let random = stealth::generate_random_ephemeral();
+        let (stealth_address, ephemeral_pubkey) = stealth::generate_stealth_address(
+            account.view_public_key(),
+            account.spend_public_key(),
+            &random,
+        )?;
+
+        Ok(TxOutput {
+            stealth_address,
+            amount_commitment: pedersen::commit_public(amount).to_bytes().to_vec(),
+            range_proof: Vec::new(),
+            ephemeral_pubkey,
+            unlock_time: None,
+            htlc: None,
+        })
+    }
+
+    /// Credits a minted output to the active account
+    ///
+    /// # Arguments
+    /// * `tx_hash` - Hash of the minting transaction
+    /// * `index` - Output index within it
+    /// * `amount` - Minted amount
+    pub fn credit_minted(&mut self, tx_hash: Hash, index: u32, amount: u64) -> Result<()> {
+        let name = self.get_active_account()?.name.clone();
+        self.credit_output(&name, tx_hash, index, amount)
+    }
+
+    /// Credits an output of known amount to an account
+    ///
+    /// # Arguments
+    /// * `account_name` - Account owning the output
+    /// * `tx_hash` - Hash of the paying transaction
+    /// * `index` - Output index within it
+    /// * `amount` - Output amount
+    pub fn credit_output(&mut self, account_name: &str, tx_hash: Hash, index: u32, amount: u64) -> Result<()> {
+        self.credit_locked_output(account_name, tx_hash, index, amount, None)
+    }
+
+    /// Credits an output of known amount that cannot be spent before
+    /// `unlock_time`
+    ///
+    /// The output's commitment is taken to be unblinded, as minted outputs'
+    /// are. While the wallet is locked the output's key image is filled in
+    /// on unlock.
+    pub fn credit_locked_output(
+        &mut self,
+        account_name: &str,
+        tx_hash: Hash,
+        index: u32,
+        amount: u64,
+        unlock_time: Option<UnlockTime>,
+    ) -> Result<()> {
+        self.credit_masked_output(account_name, tx_hash, index, amount, unlock_time, [0u8; 32])
+    }
+
+    /// Credits an output whose commitment is blinded by `mask`
+    fn credit_masked_output(
+        &mut self,
+        account_name: &str,
+        tx_hash: Hash,
+        index: u32,
+        amount: u64,
+        unlock_time: Option<UnlockTime>,
+        mask: BlindingFactor,
+    ) -> Result<()> {
+        self.get_account(account_name)?;
+        let key_image = if self.defer_key_image(account_name, tx_hash, index) {
+            [0u8; 32]
+        } else {
+            self.get_account(account_name)?.output_key_image(&tx_hash, index)
+        };
+
+        self.add_utxo(account_name, Utxo { tx_hash, index, amount, key_image, unlock_time, mask, ring_member: None })
+    }
+
+    /// Records the global index and stealth address a UTXO got when its
+    /// transaction was confirmed
+    ///
+    /// Rings reference outputs by global index, so UTXOs are spent only
+    /// once confirmed.
+    ///
+    /// # Errors
+    /// - `UnknownOutput` if the output is not a UTXO of the wallet
+    pub fn confirm_output(&mut self, tx_hash: Hash, index: u32, member: RingMember) -> Result<()> {
+        let utxo = self.utxos.values_mut()
+            .flat_map(|utxos| utxos.iter_mut())
+            .find(|utxo| utxo.tx_hash == tx_hash && utxo.index == index)
+            .ok_or_else(|| WalletError::UnknownOutput(format_outpoint(&(tx_hash, index))))?;
+        utxo.ring_member = Some(member);
+        Ok(())
+    }
+
     /// Gets available UTXOs for an account
     pub fn get_utxos(&self, account_name: &str) -> Vec<&Utxo> {
         self.utxos.get(account_name)
@@ -174,35 +413,130 @@ impl Wallet {
             .unwrap_or_default()
     }
 
-    /// Scans for outputs belonging to the active account
+    /// Scans for outputs belonging to any account
     ///
     /// This is a mock implementation. In production, this would:
     /// 1. Query blockchain for new transactions
-    /// 2. Check each output with stealth address detection
+    /// 2. Run [`scan_transaction`](Self::scan_transaction) on each
     /// 3. Add matching outputs as UTXOs
     pub fn scan_outputs(&mut self) -> Result<usize> {
-        let account = self.get_active_account()?.clone();
+        if self.accounts.is_empty() {
+            return Err(WalletError::AccountNotFound("No active account".to_string()));
+        }
 
-        // Mock: In production, iterate through blockchain transactions
-        // and use stealth::is_mine() to detect owned outputs
+        // Mock: one output found for every account
+        let names = self.list_accounts();
+        for name in &names {
+            self.credit_output(name, [0u8; 32], 0, 1000)?;
+        }
 
-        let mut found = 0;
+        Ok(names.len())
+    }
 
-        // Mock finding an output
-        let mock_utxo = Utxo {
-            tx_hash: [0u8; 32],
-            index: 0,
-            amount: 1000,
-            key_image: [1u8; 32],
-        };
+    /// Finds the outputs of a transaction that belong to any account
+    ///
+    /// Every output is checked against the view keys of all accounts in a
+    /// single pass, including their change subaddresses. Amounts are
+    /// recovered from the transaction's amount hints where present (see
+    /// [`crate::amount`]); nothing is credited.
+    pub fn scan_transaction(&self, tx_hash: Hash, tx: &Transaction) -> Vec<ScannedOutput> {
+        let mut found = Vec::new();
+        for (index, output) in tx.outputs.iter().enumerate() {
+            let owner = self.accounts.iter()
+                .find_map(|account| self.owned_key(account, output).map(|key| (account, key)));
+
+            if let Some((account, key)) = owner {
+                let check = amount::check_amount(tx, index as u32, account.view_private_key());
+                found.push(ScannedOutput {
+                    account: account.name.clone(),
+                    tx_hash,
+                    index: index as u32,
+                    amount: check.verified(),
+                    inconsistent: matches!(check, amount::AmountCheck::Inconsistent(_)),
+                    deposit_index: match key {
+                        OwnedKey::Deposit(index) => Some(index),
+                        _ => None,
+                    },
+                });
+            }
+        }
+        found
+    }
+
+    /// Scans a transaction and credits its outputs of known amount to the
+    /// owning accounts
+    ///
+    /// Outputs credited before are skipped, so transactions can be synced
+    /// more than once, as are zero-amount outputs. UTXOs the transaction
+    /// spends are removed, and pending transactions spending any of the
+    /// same key images, including this one, are no longer pending. Credited
+    /// UTXOs keep their commitment's mask, which spending them takes.
+    ///
+    /// Owned outputs whose amount hint does not regenerate their commitment
+    /// are not credited but flagged (see [`Self::inconsistent_outputs`]).
+    /// HTLC outputs are not credited either; they are claimed or refunded
+    /// (see [`crate::htlc`]).
+    ///
+    /// # Returns
+    /// Newly credited outputs, and newly flagged ones with `inconsistent`
+    /// set
+    pub fn sync_transaction(&mut self, tx_hash: Hash, tx: &Transaction) -> Result<Vec<ScannedOutput>> {
+        let spends = |key_image: &[u8; 32]| tx.inputs.iter().any(|i| &i.key_image == key_image);
+        self.pending.retain(|_, pending| !pending.inputs.iter().any(|p| spends(&p.key_image)));
+        for (account, utxos) in &mut self.utxos {
+            let before = utxos.len();
+            utxos.retain(|utxo| !spends(&utxo.key_image));
+            if utxos.len() != before {
+                self.balance_cache.remove(account);
+            }
+        }
+        self.forget_spent_outputs();
+        self.observe_change(tx)?;
+
+        let mut credited = Vec::new();
+        for output in self.scan_transaction(tx_hash, tx) {
+            if output.inconsistent {
+                if self.inconsistent.insert((tx_hash, output.index)) {
+                    credited.push(output);
+                }
+                continue;
+            }
+            let Some(amount) = output.amount.filter(|&amount| amount > 0) else {
+                continue;
+            };
+            let known = self.utxos.get(&output.account)
+                .is_some_and(|utxos| utxos.iter().any(|u| u.tx_hash == tx_hash && u.index == output.index));
+            if known {
+                continue;
+            }
 
-        self.add_utxo(&account.name, mock_utxo)?;
-        found += 1;
+            // HTLC outputs are spent with swap keys, not the account's
+            if tx.outputs[output.index as usize].htlc.is_some() {
+                continue;
+            }
 
-        Ok(found)
+            let unlock_time = tx.outputs[output.index as usize].unlock_time;
+            let view_private = self.get_account(&output.account)?.view_private_key();
+            let mask = amount::output_mask(tx, output.index, view_private, amount);
+            self.credit_masked_output(&output.account, tx_hash, output.index, amount, unlock_time, mask)?;
+            credited.push(output);
+        }
+        Ok(credited)
+    }
+
+    /// Gets owned outputs whose amount hint decrypts to an amount their
+    /// commitment is not to, in outpoint order
+    ///
+    /// Such outputs were built wrongly or tampered with and are never
+    /// credited.
+    pub fn inconsistent_outputs(&self) -> Vec<Outpoint> {
+        let mut outputs: Vec<Outpoint> = self.inconsistent.iter().copied().collect();
+        outputs.sort();
+        outputs
     }
 
-    /// Checks if a transaction output belongs to the active account
+    /// Checks if a transaction output belongs to the active account, at
+    /// its address or a change subaddress
     ///
     /// # Arguments
     /// * `stealth_address` - The stealth address to check
@@ -213,13 +547,7 @@ impl Wallet {
         ephemeral_pubkey: &[u8],
     ) -> Result<bool> {
         let account = self.get_active_account()?;
-
-        stealth::is_mine(
-            stealth_address,
-            account.view_private_key(),
-            &account.address.spend_public,
-            ephemeral_pubkey,
-        ).map_err(|e| WalletError::CryptoError(format!("{}", e)))
+        Ok(self.owned_key_of(account, stealth_address, ephemeral_pubkey).is_some())
     }
 
     /// Builds a transaction to send funds
@@ -237,65 +565,330 @@ impl Wallet {
         amount: u64,
         fee: u64,
     ) -> Result<Transaction> {
-        let account = self.get_active_account()?.clone();
+        self.build_transaction_with_options(to_address, amount, fee, &SendOptions::new())
+    }
 
-        // Parse recipient address
-        let recipient = crate::account::Address::from_string(to_address)?;
+    /// Builds a transaction to send funds with coin control or a time lock
+    ///
+    /// # Errors
+    /// - `UnknownOutput` if a named input is not an output of the active
+    ///   account
+    /// - `InsufficientBalance` if the inputs do not cover amount and fee
+    pub fn build_transaction_with_options(
+        &self,
+        to_address: &str,
+        amount: u64,
+        fee: u64,
+        options: &SendOptions,
+    ) -> Result<Transaction> {
+        self.build_transaction_with_keys(to_address, amount, fee, options).map(|(tx, _)| tx)
+    }
 
-        // Check balance
-        let balance = self.get_balance();
-        let total_needed = amount + fee;
+    /// Builds a transaction paying an output the recipient cannot spend
+    /// before `unlock_time`, for vesting payments and escrow
+    ///
+    /// The change output is not locked.
+    pub fn build_locked_transaction(
+        &self,
+        to_address: &str,
+        amount: u64,
+        fee: u64,
+        unlock_time: UnlockTime,
+    ) -> Result<Transaction> {
+        let options = SendOptions::new().with_unlock_time(unlock_time);
+        self.build_transaction_with_options(to_address, amount, fee, &options)
+    }
 
-        if balance < total_needed {
-            return Err(WalletError::InsufficientBalance {
-                required: total_needed,
-                available: balance,
-            });
+    /// Approves `parents` with a built transaction and signs it again with
+    /// the active account
+    ///
+    /// Transactions are built against placeholder parents; callers attach
+    /// the DAG tips they fetch from a node right before submitting. Every
+    /// input keeps its ring.
+    pub fn attach_parents(&self, tx: &mut Transaction, parents: [Hash; 2]) -> Result<()> {
+        self.ensure_unlocked()?;
+        let account = self.get_active_account()?;
+        let not_ours = || WalletError::TransactionBuildError(
+            "Transaction was not built by the active account".to_string()
+        );
+        let mut signers = Vec::with_capacity(tx.inputs.len());
+        for (input, ring_signature) in tx.inputs.iter().zip(&tx.ring_signatures) {
+            let member = self.get_utxos(&account.name).into_iter()
+                .find(|utxo| utxo.tx_hash == input.prev_tx && utxo.index == input.index)
+                .and_then(|utxo| utxo.ring_member.as_ref())
+                .filter(|member| ring_signature.contains_member(&member.stealth_address))
+                .ok_or_else(not_ours)?;
+            signers.push((input.prev_tx, input.index, member.stealth_address.clone()));
+        }
+        if Extra::parse(&tx.extra).is_ok_and(|extra| extra.get(TAG_HTLC_WITNESSES).is_some()) {
+            return Err(WalletError::TransactionBuildError(
+                "HTLC spends are built against their parents".to_string()
+            ));
+        }
+
+        tx.references = parents;
+        let message = tx.signing_message();
+        for (input, (tx_hash, index, stealth_address)) in signers.into_iter().enumerate() {
+            let ring = tx.ring_signatures[input].ring_members.clone();
+            let private_key = account.output_private_key(&tx_hash, index);
+            tx.sign(input, &message, private_key.as_ref(), &stealth_address, &ring)?;
+        }
+        Ok(())
+    }
+
+    /// Builds one transaction paying several recipients, plus change
+    ///
+    /// Paying many recipients at once, as an exchange paying out does,
+    /// takes one transaction instead of one per recipient. Every payment
+    /// gets its own output, in shuffled order, and any time lock in
+    /// `options` applies to all of them.
+    ///
+    /// # Errors
+    /// - `TransactionBuildError` if there are no payments, or too many to
+    ///   fit in one transaction with change
+    /// - `InvalidAddress` if an address is malformed or for another network
+    /// - `InsufficientBalance` if the inputs do not cover amounts and fee
+    pub fn build_transaction_many(
+        &self,
+        payments: &[(String, u64)],
+        fee: u64,
+        options: &SendOptions,
+    ) -> Result<Transaction> {
+        self.build_payments_with_keys(payments, fee, options).map(|(tx, _)| tx)
+    }
+
+    /// Builds a transaction to send funds, also returning its outputs'
+    /// transaction keys
+    pub(crate) fn build_transaction_with_keys(
+        &self,
+        to_address: &str,
+        amount: u64,
+        fee: u64,
+        options: &SendOptions,
+    ) -> Result<(Transaction, Vec<Vec<u8>>)> {
+        self.build_payments_with_keys(&[(to_address.to_string(), amount)], fee, options)
+    }
+
+    /// Builds a transaction paying every `(address, amount)`, also
+    /// returning its outputs' transaction keys
+    fn build_payments_with_keys(
+        &self,
+        payments: &[(String, u64)],
+        fee: u64,
+        options: &SendOptions,
+    ) -> Result<(Transaction, Vec<Vec<u8>>)> {
+        self.ensure_unlocked()?;
+        let account = self.get_active_account()?.clone();
+
+        if payments.is_empty() {
+            return Err(WalletError::TransactionBuildError("No recipients".to_string()));
+        }
+        if options.htlc.is_some() && options.unlock_time.is_some() {
+            return Err(WalletError::TransactionBuildError(
+                "An HTLC payment cannot also be time-locked".to_string()
+            ));
+        }
+        // Two outputs are kept for change and balancing
+        if payments.len() > MAX_TX_OUTPUTS - 2 {
+            return Err(WalletError::TransactionBuildError(format!(
+                "Too many recipients: {} (max {})",
+                payments.len(),
+                MAX_TX_OUTPUTS - 2
+            )));
         }
 
-        // Select UTXOs to spend
-        let utxos = self.select_utxos(&account.name, total_needed)?;
+        // Parse recipient addresses
+        let mut recipients = Vec::with_capacity(payments.len());
+        for (to_address, amount) in payments {
+            let recipient = crate::account::Address::from_string(to_address)?;
+            if recipient.network != self.network {
+                return Err(WalletError::InvalidAddress(format!(
+                    "Address is for {}, wallet is on {}",
+                    recipient.network, self.network
+                )));
+            }
+            recipients.push((recipient, *amount));
+        }
+
+        let total_needed = Amount::checked_sum_atomic(payments.iter().map(|(_, amount)| *amount))
+            .and_then(|total| total.checked_add(Amount::from_atomic(fee)))
+            .ok_or_else(|| WalletError::InvalidAmount("Amount plus fee overflows".to_string()))?
+            .as_atomic();
+
+        // Spend the named inputs, or select UTXOs
+        let utxos = if options.inputs.is_empty() {
+            let balance = self.get_balance();
+            if balance < total_needed {
+                return Err(WalletError::InsufficientBalance {
+                    required: total_needed,
+                    available: balance,
+                });
+            }
+            self.select_utxos(&account.name, total_needed)?
+        } else {
+            let utxos = self.chosen_inputs(&account.name, options)?;
+            let available = Amount::checked_sum_atomic(utxos.iter().map(|u| u.amount))
+                .unwrap_or(Amount::MAX)
+                .as_atomic();
+            if available < total_needed {
+                return Err(WalletError::InsufficientBalance {
+                    required: total_needed,
+                    available,
+                });
+            }
+            utxos
+        };
 
         // Calculate change
-        let total_input: u64 = utxos.iter().map(|u| u.amount).sum();
+        let total_input = Amount::checked_sum_atomic(utxos.iter().map(|u| u.amount))
+            .ok_or_else(|| WalletError::InvalidAmount("Input total overflows".to_string()))?
+            .as_atomic();
         let change = total_input - total_needed;
 
         // Build transaction
         let mut builder = TransactionBuilder::new()
-            .sender(account.clone());
+            .network(self.network)
+            .sender(account.clone())
+            .with_ring_members(self.pick_decoys());
 
         // Add inputs
         for utxo in utxos {
             builder = builder.add_input(utxo);
         }
 
-        // Add output to recipient
-        builder = builder.add_output(
-            recipient.view_public.clone(),
-            recipient.spend_public.clone(),
-            amount,
-        );
+        // Add an output to each recipient
+        for (recipient, amount) in recipients {
+            builder = match (options.unlock_time, options.htlc) {
+                (Some(unlock_time), _) => builder.add_locked_output(
+                    recipient.view_public,
+                    recipient.spend_public,
+                    amount,
+                    unlock_time,
+                ),
+                (None, Some(htlc)) => {
+                    builder.add_htlc_output(recipient.view_public, recipient.spend_public, amount, htlc)
+                }
+                (None, None) => builder.add_output(recipient.view_public, recipient.spend_public, amount),
+            };
+        }
 
-        // Add change output if any
+        // Add change output if any, to the next change subaddress
         if change > 0 {
             builder = builder.add_output(
                 account.address.view_public.clone(),
-                account.address.spend_public.clone(),
+                self.next_change_key(&account)?,
                 change,
             );
         }
 
         // Build with mock parent hashes
-        builder.build([0u8; 32], [1u8; 32])
+        builder.build_with_tx_keys([0u8; 32], [1u8; 32])
     }
 
     /// Submits a transaction to the network (mock implementation)
     ///
+    /// The transaction stays pending until a scan sees it or a conflicting
+    /// spend, and can be [rebroadcast](Self::rebroadcast) or
+    /// [cancelled](Self::cancel) meanwhile.
+    pub fn submit_transaction(&mut self, tx: &Transaction) -> Result<Hash> {
+        self.broadcast(tx)?;
+        self.observe_change(tx)?;
+
+        let tx_hash = tx.id();
+        self.pending.insert(tx_hash, tx.clone());
+        Ok(tx_hash)
+    }
+
+    /// Submits a pending transaction again
+    ///
+    /// # Errors
+    /// - `UnknownTransaction` if the transaction is not pending
+    pub fn rebroadcast(&self, tx_hash: &Hash) -> Result<Hash> {
+        let tx = self.pending_transaction(tx_hash)?;
+        self.broadcast(tx)?;
+        Ok(*tx_hash)
+    }
+
+    /// Replaces a pending transaction by spending its inputs back to a
+    /// change subaddress of the account that owns them
+    ///
+    /// Both transactions spend the same key images, so at most one of them
+    /// can be accepted; once the replacement is, the original is void.
+    ///
+    /// # Returns
+    /// Hash of the replacement transaction
+    ///
+    /// # Errors
+    /// - `UnknownTransaction` if the transaction is not pending
+    /// - `TransactionBuildError` if its inputs are no longer in the wallet
+    pub fn cancel(&mut self, tx_hash: &Hash) -> Result<Hash> {
+        self.ensure_unlocked()?;
+        let tx = self.pending_transaction(tx_hash)?;
+
+        // Spent outputs stay in the UTXO set until a scan sees the spend
+        let mut owner = None;
+        let mut inputs = Vec::new();
+        for input in &tx.inputs {
+            let found = self.utxos.iter().find_map(|(account, utxos)| {
+                utxos.iter()
+                    .find(|u| u.tx_hash == input.prev_tx && u.index == input.index)
+                    .map(|utxo| (account, utxo))
+            });
+            let Some((account, utxo)) = found else {
+                return Err(WalletError::TransactionBuildError(format!(
+                    "Input {}:{} is not in the wallet", hex::encode(input.prev_tx), input.index
+                )));
+            };
+            if owner.is_some_and(|owner| owner != account) {
+                return Err(WalletError::TransactionBuildError(
+                    "Inputs belong to several accounts".to_string()
+                ));
+            }
+            owner = Some(account);
+            inputs.push(utxo.clone());
+        }
+
+        let account = self.get_account(owner.ok_or_else(|| {
+            WalletError::TransactionBuildError("Transaction has no inputs".to_string())
+        })?)?.clone();
+        let total = Amount::checked_sum_atomic(inputs.iter().map(|u| u.amount))
+            .ok_or_else(|| WalletError::InvalidAmount("Input total overflows".to_string()))?
+            .as_atomic();
+
+        let change_key = self.next_change_key(&account)?;
+
+        let mut builder = TransactionBuilder::new()
+            .network(self.network)
+            .sender(account.clone())
+            .with_ring_members(self.pick_decoys());
+        for utxo in inputs {
+            builder = builder.add_input(utxo);
+        }
+        let (replacement, keys) = builder
+            .add_output(account.address.view_public.clone(), change_key, total)
+            .build_with_tx_keys([0u8; 32], [1u8; 32])?;
+
+        self.pending.remove(tx_hash);
+        let replacement_hash = self.submit_transaction(&replacement)?;
+        self.tx_keys.insert(replacement_hash, keys);
+        Ok(replacement_hash)
+    }
+
+    /// Gets the hashes of submitted transactions not yet seen by a scan
+    pub fn pending_transactions(&self) -> Vec<Hash> {
+        self.pending.keys().copied().collect()
+    }
+
+    fn pending_transaction(&self, tx_hash: &Hash) -> Result<&Transaction> {
+        self.pending.get(tx_hash)
+            .ok_or_else(|| WalletError::UnknownTransaction(hex::encode(tx_hash)))
+    }
+
+    /// Hands a transaction to the network (mock implementation)
+    ///
     /// In production, this would broadcast to nyx-network
-    pub fn submit_transaction(&self, _tx: &Transaction) -> Result<Hash> {
-        // Mock: In production, use nyx-network to broadcast
-        // For now, just return a mock transaction hash
-        Ok([0u8; 32])
+    fn broadcast(&self, _tx: &Transaction) -> Result<()> {
+        Ok(())
     }
 
     /// Sends funds to an address (convenience method)
@@ -306,27 +899,98 @@ impl Wallet {
     ///
     /// # Returns
     /// Transaction hash
-    pub fn send(&self, to_address: &str, amount: u64) -> Result<Hash> {
+    pub fn send(&mut self, to_address: &str, amount: u64) -> Result<Hash> {
+        self.send_with_options(to_address, amount, &SendOptions::new())
+    }
+
+    /// Sends funds to an address with coin control or a time lock
+    pub fn send_with_options(&mut self, to_address: &str, amount: u64, options: &SendOptions) -> Result<Hash> {
         let fee = 0; // Mock fee
-        let tx = self.build_transaction(to_address, amount, fee)?;
-        self.submit_transaction(&tx)
+        let (tx, keys) = self.build_transaction_with_keys(to_address, amount, fee, options)?;
+        let tx_hash = self.submit_transaction(&tx)?;
+        self.tx_keys.insert(tx_hash, keys);
+        Ok(tx_hash)
+    }
+
+    /// Pays several `(address, amount)` recipients in one transaction
+    ///
+    /// See [`Self::build_transaction_many`].
+    pub fn send_many(&mut self, payments: Vec<(String, u64)>) -> Result<Hash> {
+        self.send_many_with_options(&payments, &SendOptions::new())
+    }
+
+    /// Pays several recipients in one transaction with coin control or a
+    /// time lock
+    pub fn send_many_with_options(&mut self, payments: &[(String, u64)], options: &SendOptions) -> Result<Hash> {
+        let fee = 0; // Mock fee
+        let (tx, keys) = self.build_payments_with_keys(payments, fee, options)?;
+        let tx_hash = self.submit_transaction(&tx)?;
+        self.tx_keys.insert(tx_hash, keys);
+        Ok(tx_hash)
+    }
+
+    /// Proves that an output of a transaction this wallet sent pays its
+    /// recipient, by disclosing the output's transaction key
+    ///
+    /// The proof reveals nothing about the transaction's other outputs.
+    /// Check it with [`verify_payment`](crate::proof::verify_payment).
+    ///
+    /// # Errors
+    /// - `UnknownTransaction` if the wallet did not send the transaction
+    /// - `InvalidProof` if the transaction has no such output
+    pub fn prove_payment(&self, tx_hash: &Hash, output_index: u32) -> Result<PaymentProof> {
+        let keys = self.tx_keys.get(tx_hash)
+            .ok_or_else(|| WalletError::UnknownTransaction(hex::encode(tx_hash)))?;
+        let key = keys.get(output_index as usize)
+            .ok_or_else(|| WalletError::InvalidProof(format!("No output {}", output_index)))?;
+
+        Ok(PaymentProof {
+            tx_hash: *tx_hash,
+            output_index,
+            secret: PaymentSecret::TxKey(key.clone()),
+        })
+    }
+
+    /// Proves that an output of a transaction pays one of this wallet's
+    /// accounts, by disclosing the output's shared secret
+    ///
+    /// # Errors
+    /// - `InvalidProof` if the output does not exist or belongs to no
+    ///   account
+    pub fn prove_receipt(&self, tx: &Transaction, output_index: u32) -> Result<PaymentProof> {
+        let output = tx.outputs.get(output_index as usize)
+            .ok_or_else(|| WalletError::InvalidProof(format!("No output {}", output_index)))?;
+        let owner = self.accounts.iter()
+            .find(|account| self.owned_key(account, output).is_some())
+            .ok_or_else(|| WalletError::InvalidProof("Output does not pay this wallet".to_string()))?;
+
+        Ok(PaymentProof {
+            tx_hash: tx.id(),
+            output_index,
+            secret: PaymentSecret::SharedSecret(
+                stealth::derive_shared_secret(owner.view_private_key(), &output.ephemeral_pubkey)?
+            ),
+        })
     }
 
     /// Selects UTXOs for spending
     fn select_utxos(&self, account_name: &str, amount: u64) -> Result<Vec<Utxo>> {
         let available_utxos = self.utxos.get(account_name)
-            .ok_or_else(|| WalletError::InsufficientBalance {
+            .ok_or(WalletError::InsufficientBalance {
                 required: amount,
                 available: 0,
             })?;
 
-        // Simple selection: take UTXOs until we have enough
+        // Simple selection: take unlocked, unfrozen UTXOs until we have enough
+        let ctx = self.spend_context();
         let mut selected = Vec::new();
         let mut total = 0u64;
 
-        for utxo in available_utxos {
+        let selectable = available_utxos.iter()
+            .filter(|utxo| utxo.is_unlocked(&ctx) && !self.frozen.contains(&(utxo.tx_hash, utxo.index)));
+        for utxo in selectable {
             selected.push(utxo.clone());
-            total += utxo.amount;
+            total = total.saturating_add(utxo.amount);
 
             if total >= amount {
                 break;
@@ -358,8 +1022,9 @@ impl Wallet {
         WalletStats {
             total_accounts: self.accounts.len(),
             active_account: self.active_account,
-            total_balance: self.get_balance(),
+            total_balance: self.get_total_balance(),
             total_utxos: self.utxos.values().map(|v| v.len()).sum(),
+            accounts: self.account_balances(),
         }
     }
 }
@@ -384,11 +1049,53 @@ pub struct WalletStats {
 
     /// Total number of UTXOs
     pub total_utxos: usize,
+
+    /// Per-account breakdown, in account order
+    pub accounts: Vec<AccountBalance>,
+}
+
+/// Balance of one account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountBalance {
+    /// Account name
+    pub name: String,
+
+    /// Spendable balance
+    pub balance: u64,
+
+    /// Number of UTXOs
+    pub utxos: usize,
+}
+
+/// Transaction output found to belong to an account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScannedOutput {
+    /// Owning account
+    pub account: String,
+
+    /// Hash of the paying transaction
+    pub tx_hash: Hash,
+
+    /// Output index within it
+    pub index: u32,
+
+    /// Amount, if the transaction carries a valid hint for the output
+    pub amount: Option<u64>,
+
+    /// Whether the output's hint decrypts to an amount its commitment is
+    /// not to
+    pub inconsistent: bool,
+
+    /// Index of the watched deposit subaddress the output pays, if any
+    pub deposit_index: Option<u32>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nyx_core::dag::DagProcessor;
+    use nyx_core::storage::MemoryStorage;
+    use nyx_core::verification::VerifyMode;
 
     #[test]
     fn test_wallet_creation() {
@@ -484,6 +1191,9 @@ mod tests {
             index: 0,
             amount: 1000,
             key_image: [2u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
         };
 
         wallet.add_utxo(&account.name, utxo).unwrap();
@@ -501,6 +1211,9 @@ mod tests {
             index: 0,
             amount: 500,
             key_image: [2u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
         };
 
         let utxo2 = Utxo {
@@ -508,6 +1221,9 @@ mod tests {
             index: 0,
             amount: 300,
             key_image: [3u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 1, stealth_address: vec![2u8; 32] }),
         };
 
         wallet.add_utxo(&account.name, utxo1).unwrap();
@@ -516,14 +1232,123 @@ mod tests {
         assert_eq!(wallet.get_balance(), 800);
     }
 
+    #[test]
+    fn test_credit_minted_outputs() {
+        let mut wallet = Wallet::for_network(NetworkId::Regtest).with_generated_account();
+        let account = wallet.get_active_account().unwrap().clone();
+
+        let output = wallet.minted_output(250).unwrap();
+        assert!(wallet.is_mine(&output.stealth_address, &output.ephemeral_pubkey).unwrap());
+
+        wallet.credit_minted([9u8; 32], 0, 250).unwrap();
+        wallet.credit_minted([9u8; 32], 1, 250).unwrap();
+        assert_eq!(wallet.get_balance(), 500);
+
+        let utxos = wallet.get_utxos(&account.name);
+        assert_ne!(utxos[0].key_image, utxos[1].key_image);
+    }
+
     #[test]
     fn test_scan_outputs() {
         let mut wallet = Wallet::with_default_account();
+        wallet.add_account(Account::generate_with_name("savings".to_string())).unwrap();
 
         let found = wallet.scan_outputs().unwrap();
 
-        assert!(found > 0);
+        assert_eq!(found, 2);
         assert!(wallet.get_balance() > 0);
+        assert!(wallet.get_balance_for_account("savings").unwrap() > 0);
+    }
+
+    #[test]
+    fn test_inconsistent_output_flagged_not_credited() {
+        let mut sender = Wallet::with_default_account();
+        let name = sender.get_active_account().unwrap().name.clone();
+        let utxo = Utxo {
+            tx_hash: [1u8; 32],
+            index: 0,
+            amount: 1000,
+            key_image: [1u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
+        };
+        sender.add_utxo(&name, utxo).unwrap();
+
+        let mut wallet = Wallet::with_default_account();
+        let recipient = wallet.get_active_account().unwrap().clone();
+        let mut tx = sender.build_transaction(&recipient.address.to_string(), 400, 0).unwrap();
+        let index = tx.outputs.iter()
+            .position(|output| wallet.owned_key(&recipient, output).is_some())
+            .unwrap();
+
+        // Commit to more than the hint says
+        tx.outputs[index].amount_commitment = pedersen::commit_public(4_000).to_bytes().to_vec();
+        let flagged = wallet.sync_transaction(tx.id(), &tx).unwrap();
+        assert_eq!(flagged.len(), 1);
+        assert!(flagged[0].inconsistent);
+        assert_eq!(flagged[0].amount, None);
+        assert_eq!(wallet.get_balance(), 0);
+        assert_eq!(wallet.inconsistent_outputs(), vec![(tx.id(), index as u32)]);
+
+        // Flagged once
+        assert!(wallet.sync_transaction(tx.id(), &tx).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scan_transaction_checks_all_accounts() {
+        let mut wallet = Wallet::for_network(NetworkId::Regtest);
+        wallet.add_account(Account::generate_with_name("spending".to_string())).unwrap();
+        wallet.add_account(Account::generate_with_name("savings".to_string())).unwrap();
+        let stranger = Account::generate();
+
+        let pay = |account: &Account| {
+            let (stealth_address, ephemeral_pubkey) = stealth::generate_stealth_address(
+                account.view_public_key(),
+                account.spend_public_key(),
+                &stealth::generate_random_ephemeral(),
+            ).unwrap();
+            TxOutput { stealth_address, amount_commitment: vec![], range_proof: vec![], ephemeral_pubkey, unlock_time: None, htlc: None }
+        };
+        let savings = wallet.get_account("savings").unwrap().clone();
+        let spending = wallet.get_account("spending").unwrap().clone();
+        let tx = Transaction::new(
+            vec![],
+            vec![pay(&stranger), pay(&savings), pay(&spending)],
+            vec![],
+            vec![],
+            [0u8; 32],
+            [0u8; 32],
+        );
+
+        let found = wallet.scan_transaction([7u8; 32], &tx);
+        assert_eq!(found, vec![
+            ScannedOutput { account: "savings".to_string(), tx_hash: [7u8; 32], index: 1, amount: None, inconsistent: false, deposit_index: None },
+            ScannedOutput { account: "spending".to_string(), tx_hash: [7u8; 32], index: 2, amount: None, inconsistent: false, deposit_index: None },
+        ]);
+    }
+
+    #[test]
+    fn test_total_balance_and_breakdown() {
+        let mut wallet = Wallet::new();
+        wallet.add_account(Account::generate_with_name("acc1".to_string())).unwrap();
+        wallet.add_account(Account::generate_with_name("acc2".to_string())).unwrap();
+
+        wallet.credit_output("acc1", [1u8; 32], 0, 300).unwrap();
+        wallet.credit_output("acc2", [2u8; 32], 0, 500).unwrap();
+        wallet.credit_output("acc2", [2u8; 32], 1, 200).unwrap();
+        assert!(wallet.credit_output("acc3", [3u8; 32], 0, 1).is_err());
+
+        assert_eq!(wallet.get_balance(), 300);
+        assert_eq!(wallet.get_total_balance(), 1000);
+
+        let stats = wallet.get_stats();
+        assert_eq!(stats.total_balance, 1000);
+        assert_eq!(stats.total_utxos, 3);
+        assert_eq!(stats.accounts, vec![
+            AccountBalance { name: "acc1".to_string(), balance: 300, utxos: 1 },
+            AccountBalance { name: "acc2".to_string(), balance: 700, utxos: 2 },
+        ]);
     }
 
     #[test]
@@ -537,6 +1362,9 @@ mod tests {
             index: 0,
             amount: 2000,
             key_image: [2u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
         };
         wallet.add_utxo(&account.name, utxo).unwrap();
 
@@ -545,7 +1373,128 @@ mod tests {
         let tx = wallet.build_transaction(&to_address, 1000, 0).unwrap();
 
         assert_eq!(tx.inputs.len(), 1);
-        assert_eq!(tx.outputs.len(), 2); // Output + change
+        assert_eq!(tx.outputs.len(), 3); // Output, change and balancing
+    }
+
+    #[test]
+    fn test_network_bound_transactions() {
+        let mut wallet = Wallet::for_network(NetworkId::Testnet).with_generated_account();
+        let account = wallet.get_active_account().unwrap().clone();
+        assert_eq!(account.address.network, NetworkId::Testnet);
+
+        let utxo = Utxo {
+            tx_hash: [1u8; 32],
+            index: 0,
+            amount: 2000,
+            key_image: account.output_key_image(&[1u8; 32], 0),
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
+        };
+        wallet.add_utxo(&account.name, utxo).unwrap();
+
+        let tx = wallet.build_transaction(&account.address.to_string(), 1000, 0).unwrap();
+        assert_eq!(tx.network, NetworkId::Testnet);
+        assert!(tx.verify_signature().unwrap());
+
+        // Mainnet addresses are refused
+        let mut mainnet = account.address.clone();
+        mainnet.network = NetworkId::Mainnet;
+        let result = wallet.build_transaction(&mainnet.to_string(), 1000, 0);
+        assert!(matches!(result, Err(WalletError::InvalidAddress(_))));
+    }
+
+    #[test]
+    fn test_amount_overflow_rejected() {
+        let mut wallet = Wallet::with_default_account();
+        let account = wallet.get_active_account().unwrap().clone();
+
+        let utxo = Utxo {
+            tx_hash: [1u8; 32],
+            index: 0,
+            amount: 1000,
+            key_image: [2u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
+        };
+        wallet.add_utxo(&account.name, utxo).unwrap();
+
+        let to_address = account.address.to_string();
+        let result = wallet.build_transaction(&to_address, u64::MAX, 1);
+
+        assert!(matches!(result, Err(WalletError::InvalidAmount(_))));
+    }
+
+    #[test]
+    fn test_balance_saturates_on_overflow() {
+        let mut wallet = Wallet::with_default_account();
+        let account = wallet.get_active_account().unwrap().clone();
+
+        for i in 0..2u8 {
+            let utxo = Utxo {
+                tx_hash: [i; 32],
+                index: 0,
+                amount: u64::MAX,
+                key_image: [i + 1; 32],
+                unlock_time: None,
+                mask: [0u8; 32],
+                ring_member: Some(RingMember { global_index: 0, stealth_address: vec![i; 32] }),
+            };
+            wallet.add_utxo(&account.name, utxo).unwrap();
+        }
+
+        assert_eq!(wallet.get_balance(), u64::MAX);
+    }
+
+    #[test]
+    fn test_locked_payment_spendable_once_unlocked() {
+        let mut sender = Wallet::with_default_account();
+        let sender_name = sender.get_active_account().unwrap().name.clone();
+        sender.credit_output(&sender_name, [1u8; 32], 0, 2000).unwrap();
+        sender.confirm_output([1u8; 32], 0, RingMember { global_index: 0, stealth_address: vec![1u8; 32] }).unwrap();
+        let mut recipient = Wallet::with_default_account();
+        let to_address = recipient.get_active_account().unwrap().address.to_string();
+
+        let tx = sender.build_locked_transaction(&to_address, 1500, 0, UnlockTime::Height(10)).unwrap();
+        assert_eq!(tx.version, nyx_core::RING_INDEX_VERSION);
+        // Only the payment is locked, not the change
+        let paid = recipient.scan_transaction(tx.id(), &tx)[0].index as usize;
+        assert_eq!(tx.outputs[paid].unlock_time, Some(UnlockTime::Height(10)));
+        assert!(tx.outputs.iter().enumerate().all(|(index, output)| index == paid || output.unlock_time.is_none()));
+
+        recipient.sync_transaction(tx.id(), &tx).unwrap();
+        let member = RingMember { global_index: 1, stealth_address: tx.outputs[paid].stealth_address.clone() };
+        recipient.confirm_output(tx.id(), paid as u32, member).unwrap();
+        let name = recipient.get_active_account().unwrap().name.clone();
+        assert_eq!(recipient.get_utxos(&name)[0].unlock_time, Some(UnlockTime::Height(10)));
+        assert!(matches!(
+            recipient.build_transaction(&to_address, 1000, 0),
+            Err(WalletError::InsufficientBalance { available: 0, .. })
+        ));
+
+        recipient.set_chain_height(10);
+        assert!(recipient.build_transaction(&to_address, 1000, 0).is_ok());
+    }
+
+    #[test]
+    fn test_attach_parents_resigns() {
+        let mut wallet = Wallet::with_default_account();
+        let name = wallet.get_active_account().unwrap().name.clone();
+        wallet.credit_output(&name, [1u8; 32], 0, 2000).unwrap();
+        wallet.confirm_output([1u8; 32], 0, RingMember { global_index: 0, stealth_address: vec![1u8; 32] }).unwrap();
+        let to_address = Wallet::with_default_account().get_active_account().unwrap().address.to_string();
+
+        let mut tx = wallet.build_transaction(&to_address, 500, 0).unwrap();
+        wallet.attach_parents(&mut tx, [[7u8; 32], [8u8; 32]]).unwrap();
+        assert_eq!(tx.references, [[7u8; 32], [8u8; 32]]);
+        assert!(tx.verify_signature().unwrap());
+
+        let stranger = Wallet::with_default_account();
+        assert!(matches!(
+            stranger.attach_parents(&mut tx, [[9u8; 32], [8u8; 32]]),
+            Err(WalletError::TransactionBuildError(_))
+        ));
     }
 
     #[test]
@@ -574,13 +1523,157 @@ mod tests {
             index: 0,
             amount: 2000,
             key_image: [2u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
         };
         wallet.add_utxo(&account.name, utxo).unwrap();
 
         let to_address = account.address.to_string();
         let tx_hash = wallet.send(&to_address, 1000).unwrap();
 
-        assert_eq!(tx_hash.len(), 32);
+        assert_eq!(wallet.pending_transactions(), vec![tx_hash]);
+    }
+
+    #[test]
+    fn test_send_many_pays_every_recipient_once() {
+        let mut wallet = Wallet::with_default_account();
+        let account = wallet.get_active_account().unwrap().clone();
+        let utxo = Utxo {
+            tx_hash: [1u8; 32],
+            index: 0,
+            amount: 2000,
+            key_image: [2u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
+        };
+        wallet.add_utxo(&account.name, utxo).unwrap();
+
+        let recipients: Vec<Account> = (0..3).map(|_| Account::generate()).collect();
+        let payments: Vec<(String, u64)> = recipients.iter()
+            .zip([100, 200, 300])
+            .map(|(recipient, amount)| (recipient.address.to_string(), amount))
+            .collect();
+        let tx_hash = wallet.send_many(payments).unwrap();
+        let tx = wallet.pending_transaction(&tx_hash).unwrap().clone();
+
+        // Three payments, change and the balancing output
+        assert_eq!(tx.outputs.len(), 5);
+        for (recipient, expected) in recipients.iter().zip([100, 200, 300]) {
+            let paid: Vec<u64> = (0..tx.outputs.len() as u32)
+                .filter_map(|index| amount::recover_amount(&tx, index, recipient.view_private_key()))
+                .collect();
+            assert_eq!(paid, vec![expected]);
+        }
+        let change: Vec<u64> = (0..tx.outputs.len() as u32)
+            .filter_map(|index| amount::recover_amount(&tx, index, account.view_private_key()))
+            .collect();
+        assert_eq!(change, vec![1400]);
+        assert!(wallet.prove_payment(&tx_hash, 3).is_ok());
+    }
+
+    #[test]
+    fn test_send_many_rejects_bad_batches() {
+        let mut wallet = Wallet::with_default_account();
+        let account = wallet.get_active_account().unwrap().clone();
+        let utxo = Utxo {
+            tx_hash: [1u8; 32],
+            index: 0,
+            amount: 500,
+            key_image: [2u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
+        };
+        wallet.add_utxo(&account.name, utxo).unwrap();
+        let address = Account::generate().address.to_string();
+
+        assert!(matches!(wallet.send_many(vec![]), Err(WalletError::TransactionBuildError(_))));
+        let over = vec![(address.clone(), 300), (address.clone(), 300)];
+        assert!(matches!(wallet.send_many(over), Err(WalletError::InsufficientBalance { required: 600, .. })));
+        let overflow = vec![(address.clone(), u64::MAX), (address.clone(), 1)];
+        assert!(matches!(wallet.send_many(overflow), Err(WalletError::InvalidAmount(_))));
+        let too_many = vec![(address, 1); MAX_TX_OUTPUTS];
+        assert!(matches!(wallet.send_many(too_many), Err(WalletError::TransactionBuildError(_))));
+        assert!(wallet.pending_transactions().is_empty());
+    }
+
+    #[test]
+    fn test_rebroadcast_and_cancel_pending() {
+        let mut wallet = Wallet::with_default_account();
+        let account = wallet.get_active_account().unwrap().clone();
+        let utxo = Utxo {
+            tx_hash: [1u8; 32],
+            index: 0,
+            amount: 2000,
+            key_image: [2u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
+        };
+        wallet.add_utxo(&account.name, utxo).unwrap();
+
+        let recipient = Account::generate().address.to_string();
+        let stuck = wallet.send(&recipient, 1500).unwrap();
+        assert_eq!(wallet.rebroadcast(&stuck).unwrap(), stuck);
+
+        let replacement = wallet.cancel(&stuck).unwrap();
+        assert_eq!(wallet.pending_transactions(), vec![replacement]);
+        assert!(matches!(wallet.rebroadcast(&stuck), Err(WalletError::UnknownTransaction(_))));
+
+        // The replacement returns all 2000 to the sender, spending the same
+        // input, alongside the balancing output nobody can open
+        let tx = wallet.pending[&replacement].clone();
+        assert_eq!(tx.inputs[0].key_image, [2u8; 32]);
+        let mut amounts: Vec<Option<u64>> = (0..tx.outputs.len() as u32)
+            .map(|index| amount::recover_amount(&tx, index, account.view_private_key()))
+            .collect();
+        amounts.sort();
+        assert_eq!(amounts, vec![None, Some(2000)]);
+
+        // Seeing the replacement clears it from the pending set and swaps
+        // the spent input for the returned output
+        wallet.sync_transaction(replacement, &tx).unwrap();
+        assert!(wallet.pending_transactions().is_empty());
+        assert_eq!(wallet.get_balance(), 2000);
+        assert_eq!(wallet.get_utxos(&account.name)[0].tx_hash, replacement);
+    }
+
+    #[test]
+    fn test_prove_payment_and_receipt() {
+        let mut wallet = Wallet::with_default_account();
+        let account = wallet.get_active_account().unwrap().clone();
+        wallet.add_utxo(&account.name, Utxo {
+            tx_hash: [1u8; 32],
+            index: 0,
+            amount: 2000,
+            key_image: [2u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
+        }).unwrap();
+
+        let mut merchant = Wallet::with_default_account();
+        let address = merchant.get_active_account().unwrap().address.clone();
+        let tx_hash = wallet.send(&address.to_string(), 1200).unwrap();
+        let tx = wallet.pending[&tx_hash].clone();
+
+        // One output pays the merchant, another is change
+        let paid = merchant.scan_transaction(tx_hash, &tx)[0].index;
+        let change = wallet.scan_transaction(tx_hash, &tx)[0].index;
+        let proof = wallet.prove_payment(&tx_hash, paid).unwrap();
+        let proof = PaymentProof::from_string(&proof.to_string()).unwrap();
+        assert_eq!(crate::proof::verify_payment(&tx, &proof, &address).unwrap().amount, Some(1200));
+        assert!(crate::proof::verify_payment(&tx, &wallet.prove_payment(&tx_hash, change).unwrap(), &address).is_err());
+        assert!(matches!(wallet.prove_payment(&[9u8; 32], 0), Err(WalletError::UnknownTransaction(_))));
+
+        let receipt = merchant.prove_receipt(&tx, paid).unwrap();
+        assert_eq!(crate::proof::verify_payment(&tx, &receipt, &address).unwrap().amount, Some(1200));
+        assert!(merchant.prove_receipt(&tx, change).is_err());
+        assert!(wallet.prove_receipt(&tx, change).is_ok());
+        merchant.sync_transaction(tx_hash, &tx).unwrap();
+        assert_eq!(merchant.get_balance(), 1200);
     }
 
     #[test]
@@ -606,6 +1699,9 @@ mod tests {
             index: 0,
             amount: 1000,
             key_image: [2u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
         };
         wallet.add_utxo(&account.name, utxo).unwrap();
 
@@ -624,6 +1720,9 @@ mod tests {
             index: 0,
             amount: 1000,
             key_image: [2u8; 32],
+            unlock_time: None,
+            mask: [0u8; 32],
+            ring_member: Some(RingMember { global_index: 0, stealth_address: vec![1u8; 32] }),
         };
         wallet.add_utxo(&account.name, utxo).unwrap();
 
@@ -631,4 +1730,42 @@ mod tests {
         assert_eq!(utxos.len(), 1);
         assert_eq!(utxos[0].amount, 1000);
     }
+
+    /// Finalizes `tx_hash`, returning its output at `index` as a ring member
+    fn finalize_output(dag: &DagProcessor, tx_hash: Hash, index: u32) -> RingMember {
+        dag.finalize_transactions(&[tx_hash]).unwrap();
+        let global_index = dag.storage().first_output_index(&tx_hash).unwrap().unwrap() + u64::from(index);
+        let stealth_address = dag.storage().get_output_by_index(global_index).unwrap().stealth_address;
+        RingMember { global_index, stealth_address }
+    }
+
+    #[test]
+    fn test_built_transactions_pass_full_verification() {
+        let dag = DagProcessor::new(MemoryStorage::new()).with_network(NetworkId::Regtest);
+        let mut wallet = Wallet::for_network(NetworkId::Regtest).with_generated_account();
+        let recipient = Wallet::for_network(NetworkId::Regtest).with_generated_account();
+        let to_address = recipient.get_active_account().unwrap().address.to_string();
+        let minted: Vec<Hash> = (0..2)
+            .map(|_| dag.mint(vec![wallet.minted_output(1000).unwrap()]).unwrap())
+            .collect();
+        wallet.credit_minted(minted[0], 0, 1000).unwrap();
+        wallet.confirm_output(minted[0], 0, finalize_output(&dag, minted[0], 0)).unwrap();
+        wallet.set_decoys(vec![finalize_output(&dag, minted[1], 0)]);
+
+        // Spending a minted output, whose commitment is unblinded, in a ring
+        // with a decoy
+        let mut tx = wallet.build_transaction(&to_address, 600, 0).unwrap();
+        assert!(tx.version >= nyx_core::RING_INDEX_VERSION);
+        assert_eq!(tx.inputs[0].ring_indices, vec![0, 1]);
+        wallet.attach_parents(&mut tx, [minted[0], minted[1]]).unwrap();
+        let tx_hash = dag.add_transaction_with_mode(tx.clone(), VerifyMode::Full).unwrap();
+        let change = wallet.sync_transaction(tx_hash, &tx).unwrap()[0].index;
+        assert_eq!(wallet.get_balance(), 400);
+
+        // Spending the change, blinded by its shared secret
+        wallet.confirm_output(tx_hash, change, finalize_output(&dag, tx_hash, change)).unwrap();
+        let mut spend = wallet.build_transaction(&to_address, 400, 0).unwrap();
+        wallet.attach_parents(&mut spend, [tx_hash, minted[1]]).unwrap();
+        dag.add_transaction_with_mode(spend, VerifyMode::Full).unwrap();
+    }
 }